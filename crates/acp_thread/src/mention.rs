@@ -74,6 +74,12 @@ pub enum MentionUri {
         source: String,
         skill_file_path: PathBuf,
     },
+    /// A resource exposed by a running MCP context server.
+    McpResource {
+        server_id: String,
+        uri: Url,
+        name: String,
+    },
 }
 
 impl MentionUri {
@@ -273,6 +279,18 @@ impl MentionUri {
                         source: source.context("missing skill source")?,
                         skill_file_path: skill_file_path.context("missing skill file path")?,
                     })
+                } else if path.starts_with("/agent/mcp-resource") {
+                    validate_query_params(&url, &["server", "uri", "name"])?;
+                    let server_id =
+                        query_param(&url, "server").context("Missing server for MCP resource")?;
+                    let uri = query_param(&url, "uri").context("Missing uri for MCP resource")?;
+                    let uri = Url::parse(&uri).context("Invalid MCP resource uri")?;
+                    let name = query_param(&url, "name").unwrap_or_else(|| uri.to_string());
+                    Ok(Self::McpResource {
+                        server_id,
+                        uri,
+                        name,
+                    })
                 } else {
                     bail!("invalid zed url: {:?}", input);
                 }
@@ -328,7 +346,8 @@ impl MentionUri {
             | MentionUri::Fetch { .. }
             | MentionUri::TerminalSelection { .. }
             | MentionUri::GitDiff { .. }
-            | MentionUri::MergeConflict { .. } => None,
+            | MentionUri::MergeConflict { .. }
+            | MentionUri::McpResource { .. } => None,
         }
     }
 
@@ -366,6 +385,7 @@ impl MentionUri {
             } => selection_name(path.as_deref(), line_range),
             MentionUri::Fetch { url } => url.to_string(),
             MentionUri::Skill { name, .. } => name.clone(),
+            MentionUri::McpResource { name, .. } => name.clone(),
         }
     }
 
@@ -391,6 +411,9 @@ impl MentionUri {
             MentionUri::File { abs_path, .. } | MentionUri::Directory { abs_path, .. } => {
                 project::path_suffix(abs_path, detail)
             }
+            MentionUri::McpResource {
+                server_id, name, ..
+            } => format!("{} ({})", name, server_id),
             _ => self.name(),
         }
     }
@@ -429,6 +452,9 @@ impl MentionUri {
             MentionUri::Skill {
                 skill_file_path, ..
             } => Some(skill_file_path.to_string_lossy().into_owned().into()),
+            MentionUri::McpResource { server_id, uri, .. } => {
+                Some(format!("{} ({})", uri, server_id).into())
+            }
             _ => None,
         }
     }
@@ -451,6 +477,7 @@ impl MentionUri {
             MentionUri::GitDiff { .. } => IconName::GitBranch.path().into(),
             MentionUri::MergeConflict { .. } => IconName::GitMergeConflict.path().into(),
             MentionUri::Skill { .. } => IconName::Sparkle.path().into(),
+            MentionUri::McpResource { .. } => IconName::ToolHammer.path().into(),
         }
     }
 
@@ -582,6 +609,18 @@ impl MentionUri {
                     .append_pair("path", &skill_file_path.to_string_lossy());
                 url
             }
+            MentionUri::McpResource {
+                server_id,
+                uri,
+                name,
+            } => {
+                let mut url = Url::parse("zed:///agent/mcp-resource").unwrap();
+                url.query_pairs_mut()
+                    .append_pair("server", server_id)
+                    .append_pair("uri", uri.as_str())
+                    .append_pair("name", name);
+                url
+            }
         }
     }
 }
@@ -1386,6 +1425,22 @@ mod tests {
         assert_eq!(parsed, skill_uri);
     }
 
+    #[test]
+    fn test_parse_mcp_resource_uri_round_trip() {
+        let resource_uri = MentionUri::McpResource {
+            server_id: "postgres".to_string(),
+            uri: Url::parse("postgres://db/users/schema?table=a&b").unwrap(),
+            name: "users schema".to_string(),
+        };
+
+        let serialized = resource_uri.to_uri().to_string();
+        assert!(serialized.starts_with("zed:///agent/mcp-resource?"));
+        let parsed = MentionUri::parse(&serialized, PathStyle::local()).unwrap();
+
+        assert_eq!(parsed, resource_uri);
+        assert_eq!(parsed.name(), "users schema");
+    }
+
     #[test]
     fn test_parse_fetch_http_uri() {
         let http_uri = "http://example.com/path?query=value#fragment";
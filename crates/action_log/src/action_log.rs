@@ -62,6 +62,25 @@ pub struct ActionLog {
     last_reject_undo: Option<LastRejectUndo>,
     /// Tracks the last time files were read by the agent, to detect external modifications
     file_read_times: HashMap<PathBuf, MTime>,
+    /// Comments the agent left while reviewing changes, in the order they were added
+    review_comments: Vec<ReviewComment>,
+    next_review_comment_id: usize,
+}
+
+/// Identifies a [`ReviewComment`] within its action log.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ReviewCommentId(usize);
+
+/// A comment the agent left on a range of a buffer while reviewing changes,
+/// instead of editing it.
+#[derive(Clone, Debug)]
+pub struct ReviewComment {
+    pub id: ReviewCommentId,
+    pub buffer: Entity<Buffer>,
+    pub range: Range<Anchor>,
+    pub comment: SharedString,
+    /// Replacement text for `range`, if the agent suggested an edit.
+    pub suggestion: Option<String>,
 }
 
 impl ActionLog {
@@ -73,6 +92,8 @@ impl ActionLog {
             linked_action_log: None,
             last_reject_undo: None,
             file_read_times: HashMap::default(),
+            review_comments: Vec::new(),
+            next_review_comment_id: 0,
         }
     }
 
@@ -1031,6 +1052,48 @@ impl ActionLog {
         DiffStats::all_files(self.changed_buffers(cx), cx)
     }
 
+    /// Records a review comment on `range`. Comments from a subagent are
+    /// recorded on the linked action log, so they show up in the parent's review.
+    pub fn add_review_comment(
+        &mut self,
+        buffer: Entity<Buffer>,
+        range: Range<Anchor>,
+        comment: SharedString,
+        suggestion: Option<String>,
+        cx: &mut Context<Self>,
+    ) -> ReviewCommentId {
+        if let Some(linked_action_log) = &self.linked_action_log {
+            return linked_action_log.update(cx, |log, cx| {
+                log.add_review_comment(buffer, range, comment, suggestion, cx)
+            });
+        }
+
+        let id = ReviewCommentId(self.next_review_comment_id);
+        self.next_review_comment_id += 1;
+        self.review_comments.push(ReviewComment {
+            id,
+            buffer,
+            range,
+            comment,
+            suggestion,
+        });
+        cx.notify();
+        id
+    }
+
+    /// Returns the review comments that haven't been dismissed.
+    pub fn review_comments(&self) -> &[ReviewComment] {
+        &self.review_comments
+    }
+
+    pub fn dismiss_review_comment(&mut self, id: ReviewCommentId, cx: &mut Context<Self>) {
+        let comment_count = self.review_comments.len();
+        self.review_comments.retain(|comment| comment.id != id);
+        if self.review_comments.len() != comment_count {
+            cx.notify();
+        }
+    }
+
     /// Iterate over buffers changed since last read or edited by the model
     pub fn stale_buffers<'a>(&'a self, cx: &'a App) -> impl Iterator<Item = &'a Entity<Buffer>> {
         self.tracked_buffers
@@ -3271,6 +3334,68 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_review_comments(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({"file": "abc\ndef\nghi"}))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let parent_log = cx.new(|_| ActionLog::new(project.clone()));
+        let child_log =
+            cx.new(|_| ActionLog::new(project.clone()).with_linked_action_log(parent_log.clone()));
+        let file_path = project
+            .read_with(cx, |project, cx| project.find_project_path("dir/file", cx))
+            .unwrap();
+        let buffer = project
+            .update(cx, |project, cx| project.open_buffer(file_path, cx))
+            .await
+            .unwrap();
+
+        let (first, second) = cx.update(|cx| {
+            let snapshot = buffer.read(cx).snapshot();
+            let range =
+                snapshot.anchor_before(Point::new(1, 0))..snapshot.anchor_after(Point::new(1, 3));
+            let first = parent_log.update(cx, |log, cx| {
+                log.add_review_comment(buffer.clone(), range.clone(), "Typo".into(), None, cx)
+            });
+            let second = child_log.update(cx, |log, cx| {
+                log.add_review_comment(
+                    buffer.clone(),
+                    range,
+                    "Use uppercase".into(),
+                    Some("DEF".into()),
+                    cx,
+                )
+            });
+            (first, second)
+        });
+
+        cx.read(|cx| {
+            assert!(
+                child_log.read(cx).review_comments().is_empty(),
+                "subagent comments should be recorded on the parent"
+            );
+            let comments = parent_log.read(cx).review_comments();
+            assert_eq!(
+                comments
+                    .iter()
+                    .map(|comment| (comment.id, comment.comment.as_ref()))
+                    .collect::<Vec<_>>(),
+                vec![(first, "Typo"), (second, "Use uppercase")]
+            );
+            assert_eq!(comments[1].suggestion.as_deref(), Some("DEF"));
+        });
+
+        parent_log.update(cx, |log, cx| log.dismiss_review_comment(first, cx));
+        cx.read(|cx| {
+            let comments = parent_log.read(cx).review_comments();
+            assert_eq!(comments.len(), 1);
+            assert_eq!(comments[0].id, second);
+        });
+    }
+
     #[gpui::test]
     async fn test_file_read_time_recorded_on_buffer_read(cx: &mut TestAppContext) {
         init_test(cx);
@@ -1,4 +1,6 @@
+mod budget;
 mod db;
+mod hooks;
 mod legacy_thread;
 mod native_agent_server;
 pub mod outline;
@@ -12,6 +14,7 @@ mod thread_store;
 mod tool_permissions;
 mod tools;
 
+pub use budget::*;
 use context_server::ContextServerId;
 pub use db::*;
 use itertools::Itertools;
@@ -401,6 +404,22 @@ pub trait SiblingThreadHost {
     fn list_available_agents(&self, cx: &mut App) -> Result<AvailableAgents>;
 }
 
+/// Implemented by the UI layer to let the `run_task` tool list and run the
+/// project's tasks in the workspace's terminal panel, and the `debug_start`
+/// tool start debug scenarios in the debug panel.
+///
+/// `agent_ui::AgentPanel` installs an implementation of this trait on the
+/// `NativeAgent` alongside the [`SiblingThreadHost`].
+pub trait ProjectTaskHost {
+    fn list_tasks(&self, cx: &mut AsyncApp) -> Task<Result<Vec<ProjectTaskInfo>>>;
+
+    fn run_task(&self, label: String, cx: &mut AsyncApp) -> Task<Result<ProjectTaskOutput>>;
+
+    fn list_debug_scenarios(&self, cx: &mut AsyncApp) -> Task<Result<Vec<DebugScenarioInfo>>>;
+
+    fn start_debug_scenario(&self, label: String, cx: &mut AsyncApp) -> Task<Result<()>>;
+}
+
 pub struct NativeAgent {
     /// Session ID -> Session mapping
     sessions: HashMap<acp::SessionId, Session>,
@@ -414,6 +433,8 @@ pub struct NativeAgent {
     models: LanguageModels,
     /// Handler installed by the UI for `create_thread` / `list_agents_and_models` tools.
     sibling_thread_host: Option<Rc<dyn SiblingThreadHost>>,
+    /// Handler installed by the UI for the `run_task` and `debug_start` tools.
+    project_task_host: Option<Rc<dyn ProjectTaskHost>>,
     fs: Arc<dyn Fs>,
     _subscriptions: Vec<Subscription>,
     /// Tracks the lifecycle of global skills directory observation. We
@@ -587,6 +608,7 @@ impl NativeAgent {
                 templates,
                 models: LanguageModels::new(cx),
                 sibling_thread_host: None,
+                project_task_host: None,
                 fs,
                 _subscriptions: subscriptions,
                 skills_state: SkillsState::default(),
@@ -721,6 +743,14 @@ impl NativeAgent {
         self.sibling_thread_host.clone()
     }
 
+    pub fn set_project_task_host(&mut self, host: Rc<dyn ProjectTaskHost>) {
+        self.project_task_host = Some(host);
+    }
+
+    pub fn project_task_host(&self) -> Option<Rc<dyn ProjectTaskHost>> {
+        self.project_task_host.clone()
+    }
+
     fn new_session(
         &mut self,
         project: Entity<Project>,
@@ -3081,6 +3111,17 @@ pub struct NativeThreadEnvironment {
 }
 
 impl NativeThreadEnvironment {
+    fn project_task_host(&self, cx: &mut AsyncApp) -> Result<Rc<dyn ProjectTaskHost>> {
+        self.agent
+            .read_with(cx, |agent, _| agent.project_task_host())?
+            .ok_or_else(|| {
+                anyhow!(
+                    "No project task host is registered. This usually means the \
+                     agent panel hasn't been initialized in this workspace."
+                )
+            })
+    }
+
     pub(crate) fn create_subagent_thread(
         &self,
         label: String,
@@ -3328,6 +3369,38 @@ impl ThreadEnvironment for NativeThreadEnvironment {
             })?;
         host.list_available_agents(cx)
     }
+
+    fn list_project_tasks(&self, cx: &mut AsyncApp) -> Task<Result<Vec<ProjectTaskInfo>>> {
+        match self.project_task_host(cx) {
+            Ok(host) => host.list_tasks(cx),
+            Err(err) => Task::ready(Err(err)),
+        }
+    }
+
+    fn run_project_task(
+        &self,
+        label: String,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<ProjectTaskOutput>> {
+        match self.project_task_host(cx) {
+            Ok(host) => host.run_task(label, cx),
+            Err(err) => Task::ready(Err(err)),
+        }
+    }
+
+    fn list_debug_scenarios(&self, cx: &mut AsyncApp) -> Task<Result<Vec<DebugScenarioInfo>>> {
+        match self.project_task_host(cx) {
+            Ok(host) => host.list_debug_scenarios(cx),
+            Err(err) => Task::ready(Err(err)),
+        }
+    }
+
+    fn start_debug_scenario(&self, label: String, cx: &mut AsyncApp) -> Task<Result<()>> {
+        match self.project_task_host(cx) {
+            Ok(host) => host.start_debug_scenario(label, cx),
+            Err(err) => Task::ready(Err(err)),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -3384,8 +3457,8 @@ impl SubagentHandle for NativeSubagentHandle {
                     .map(|usage| usage.ratio());
 
                 parent_thread
-                    .update(cx, |parent_thread, _cx| {
-                        parent_thread.register_running_subagent(thread.downgrade())
+                    .update(cx, |parent_thread, cx| {
+                        parent_thread.register_running_subagent(thread.downgrade(), cx)
                     })
                     .ok();
 
@@ -3423,7 +3496,7 @@ impl SubagentHandle for NativeSubagentHandle {
                                     match response.stop_reason {
                                         acp::StopReason::Cancelled => SubagentPromptResult::Cancelled,
                                         acp::StopReason::MaxTokens => SubagentPromptResult::Error("The agent reached the maximum number of tokens.".into()),
-                                        acp::StopReason::MaxTurnRequests => SubagentPromptResult::Error("The agent reached the maximum number of allowed requests between user turns. Try prompting again.".into()),
+                                        acp::StopReason::MaxTurnRequests => SubagentPromptResult::Error("The agent reached its budget of tokens, tool calls, time, or cost.".into()),
                                         acp::StopReason::Refusal => SubagentPromptResult::Error("The agent refused to process that prompt. Try again.".into()),
                                         acp::StopReason::EndTurn | _ => SubagentPromptResult::Completed,
                                     }
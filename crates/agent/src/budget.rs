@@ -0,0 +1,203 @@
+use agent_settings::AgentBudget;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// What a thread has spent toward its budget, in addition to the tokens it
+/// already tracks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BudgetUsage {
+    #[serde(default)]
+    pub tool_calls: u32,
+    /// Time spent running turns, excluding the one in progress.
+    #[serde(default)]
+    pub active_time: Duration,
+    /// The estimated cost in US dollars of requests to models with a price.
+    #[serde(default)]
+    pub cost: f64,
+    /// Tokens used by subagents while they worked for this thread. Their tool
+    /// calls and cost are counted in the fields above.
+    #[serde(default)]
+    pub subagent_tokens: u64,
+}
+
+/// Everything a thread has spent toward the limits of its budget, other than
+/// time.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BudgetSpend {
+    pub tokens: u64,
+    pub tool_calls: u32,
+    pub cost: f64,
+}
+
+impl BudgetSpend {
+    /// What was spent since the given earlier snapshot of the same thread.
+    pub fn since(&self, earlier: &Self) -> Self {
+        Self {
+            tokens: self.tokens.saturating_sub(earlier.tokens),
+            tool_calls: self.tool_calls.saturating_sub(earlier.tool_calls),
+            cost: (self.cost - earlier.cost).max(0.0),
+        }
+    }
+}
+
+impl std::ops::Add for BudgetSpend {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            tokens: self.tokens + other.tokens,
+            tool_calls: self.tool_calls + other.tool_calls,
+            cost: self.cost + other.cost,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BudgetLimit {
+    Tokens,
+    ToolCalls,
+    Time,
+    Cost,
+}
+
+/// How much of one budget limit a thread has used.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BudgetLimitUsage {
+    pub limit: BudgetLimit,
+    pub used: f64,
+    pub max: f64,
+}
+
+impl BudgetLimitUsage {
+    pub fn ratio(&self) -> f64 {
+        if self.max > 0.0 {
+            self.used / self.max
+        } else {
+            1.0
+        }
+    }
+
+    pub fn is_exceeded(&self) -> bool {
+        self.used >= self.max
+    }
+
+    /// Describes the usage, e.g. "12 of 50 tool calls".
+    pub fn describe(&self) -> String {
+        match self.limit {
+            BudgetLimit::Tokens => format!("{} of {} tokens", self.used, self.max),
+            BudgetLimit::ToolCalls => format!("{} of {} tool calls", self.used, self.max),
+            BudgetLimit::Time => format!(
+                "{} of {} minutes",
+                (self.used / 60.0).floor(),
+                (self.max / 60.0).round()
+            ),
+            BudgetLimit::Cost => format!("${:.2} of ${:.2}", self.used, self.max),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BudgetLevel {
+    Normal,
+    Warning,
+    Exceeded,
+}
+
+/// A thread's usage of each limit in its budget.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BudgetStatus {
+    pub limits: Vec<BudgetLimitUsage>,
+    pub warning_threshold: f32,
+}
+
+impl BudgetStatus {
+    /// Returns `None` if the budget has no limits.
+    pub fn new(budget: &AgentBudget, spend: &BudgetSpend, active_time: Duration) -> Option<Self> {
+        if budget.is_unlimited() {
+            return None;
+        }
+        let limits = [
+            budget.max_tokens.map(|max| BudgetLimitUsage {
+                limit: BudgetLimit::Tokens,
+                used: spend.tokens as f64,
+                max: max as f64,
+            }),
+            budget.max_tool_calls.map(|max| BudgetLimitUsage {
+                limit: BudgetLimit::ToolCalls,
+                used: spend.tool_calls as f64,
+                max: max as f64,
+            }),
+            budget.max_duration.map(|max| BudgetLimitUsage {
+                limit: BudgetLimit::Time,
+                used: active_time.as_secs_f64(),
+                max: max.as_secs_f64(),
+            }),
+            budget.max_cost.map(|max| BudgetLimitUsage {
+                limit: BudgetLimit::Cost,
+                used: spend.cost,
+                max,
+            }),
+        ];
+        Some(Self {
+            limits: limits.into_iter().flatten().collect(),
+            warning_threshold: budget.warning_threshold,
+        })
+    }
+
+    /// The limit closest to being reached.
+    pub fn most_used(&self) -> Option<&BudgetLimitUsage> {
+        self.limits
+            .iter()
+            .max_by(|a, b| a.ratio().total_cmp(&b.ratio()))
+    }
+
+    pub fn exceeded(&self) -> Option<&BudgetLimitUsage> {
+        self.limits.iter().find(|limit| limit.is_exceeded())
+    }
+
+    pub fn level(&self) -> BudgetLevel {
+        match self.most_used() {
+            Some(limit) if limit.is_exceeded() => BudgetLevel::Exceeded,
+            Some(limit) if limit.ratio() >= self.warning_threshold as f64 => BudgetLevel::Warning,
+            _ => BudgetLevel::Normal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_status_levels() {
+        let budget = AgentBudget {
+            max_tokens: Some(1000),
+            max_tool_calls: Some(10),
+            max_duration: None,
+            max_cost: Some(2.0),
+            warning_threshold: 0.8,
+        };
+        let active_time = Duration::from_secs(600);
+        let mut spend = BudgetSpend {
+            tokens: 100,
+            tool_calls: 2,
+            cost: 0.5,
+        };
+
+        let status = BudgetStatus::new(&budget, &spend, active_time).unwrap();
+        assert_eq!(status.limits.len(), 3, "unset limits are left out");
+        assert_eq!(status.level(), BudgetLevel::Normal);
+
+        spend.cost = 1.7;
+        let status = BudgetStatus::new(&budget, &spend, active_time).unwrap();
+        assert_eq!(status.level(), BudgetLevel::Warning);
+        assert_eq!(status.most_used().unwrap().limit, BudgetLimit::Cost);
+
+        spend.tool_calls = 10;
+        let status = BudgetStatus::new(&budget, &spend, active_time).unwrap();
+        assert_eq!(status.level(), BudgetLevel::Exceeded);
+        assert_eq!(status.exceeded().unwrap().describe(), "10 of 10 tool calls");
+
+        assert!(BudgetStatus::new(&AgentBudget::default(), &spend, Duration::ZERO).is_none());
+    }
+}
@@ -63,6 +63,9 @@ pub struct DbThread {
     pub cumulative_token_usage: language_model::TokenUsage,
     #[serde(default)]
     pub request_token_usage: HashMap<acp_thread::ClientUserMessageId, language_model::TokenUsage>,
+    /// Tool calls, time, and cost counted against the thread's budget.
+    #[serde(default)]
+    pub budget_usage: crate::BudgetUsage,
     #[serde(default)]
     pub model: Option<DbLanguageModel>,
     #[serde(default)]
@@ -169,6 +172,7 @@ impl SharedThread {
             ui_scroll_position: None,
             sandboxed_terminal_temp_dir: None,
             sandbox_grants: DbSandboxGrants::default(),
+            budget_usage: Default::default(),
         }
     }
 
@@ -185,9 +189,65 @@ impl SharedThread {
     }
 }
 
+/// A thread written to a file so it can be shared and continued elsewhere.
+///
+/// Unlike [`SharedThread`], this keeps everything the thread store knows about
+/// the thread, except state that only makes sense on the machine it came from.
+/// Checkpoints are left out too: their commits only exist in the exporter's
+/// repositories.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedThread {
+    pub version: String,
+    pub exported_at: DateTime<Utc>,
+    pub thread: DbThread,
+}
+
+impl ExportedThread {
+    pub const VERSION: &'static str = "1";
+
+    pub fn new(mut thread: DbThread) -> Self {
+        thread.clear_local_state();
+        Self {
+            version: Self::VERSION.to_string(),
+            exported_at: Utc::now(),
+            thread,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &[u8]) -> Result<Self> {
+        let value = serde_json::from_slice::<serde_json::Value>(json)?;
+        match value.get("version").and_then(|version| version.as_str()) {
+            Some(Self::VERSION) => {
+                let mut exported: Self = serde_json::from_value(value)?;
+                exported.thread.clear_local_state();
+                Ok(exported)
+            }
+            Some(version) => anyhow::bail!("unsupported thread export version {version}"),
+            None => anyhow::bail!("not a thread exported from Zed"),
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        format!("# {}\n\n{}", self.thread.title, self.thread.to_markdown())
+    }
+}
+
 impl DbThread {
     pub const VERSION: &'static str = "0.3.0";
 
+    /// Drops state that refers to the machine or window the thread was saved on.
+    fn clear_local_state(&mut self) {
+        self.subagent_context = None;
+        self.draft_prompt = None;
+        self.ui_scroll_position = None;
+        self.sandboxed_terminal_temp_dir = None;
+        self.sandbox_grants = DbSandboxGrants::default();
+    }
+
     pub fn to_markdown(&self) -> String {
         crate::messages_to_markdown(&self.messages)
     }
@@ -355,6 +415,7 @@ impl DbThread {
             ui_scroll_position: None,
             sandboxed_terminal_temp_dir: None,
             sandbox_grants: DbSandboxGrants::default(),
+            budget_usage: Default::default(),
         })
     }
 }
@@ -783,6 +844,32 @@ mod tests {
         assert_eq!(restored.updated_at, original.updated_at);
     }
 
+    #[test]
+    fn test_exported_thread_roundtrip() {
+        let mut thread = make_thread(
+            "Exported",
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        );
+        thread.draft_prompt = Some(vec![acp::ContentBlock::from("unsent")]);
+        thread.sandboxed_terminal_temp_dir = Some(PathBuf::from("/tmp/sandbox"));
+        thread.sandbox_grants.unsandboxed = true;
+
+        let json = ExportedThread::new(thread).to_json().unwrap();
+        let imported = ExportedThread::from_json(json.as_bytes()).unwrap();
+
+        assert_eq!(imported.thread.title.as_ref(), "Exported");
+        assert!(imported.thread.draft_prompt.is_none());
+        assert!(imported.thread.sandboxed_terminal_temp_dir.is_none());
+        assert_eq!(imported.thread.sandbox_grants, DbSandboxGrants::default());
+        assert!(imported.to_markdown().starts_with("# Exported\n"));
+
+        let db_json = serde_json::to_vec(&imported.thread).unwrap();
+        assert!(
+            ExportedThread::from_json(&db_json).is_err(),
+            "a bare thread isn't an export"
+        );
+    }
+
     fn session_id(value: &str) -> acp::SessionId {
         acp::SessionId::new(Arc::<str>::from(value))
     }
@@ -806,6 +893,7 @@ mod tests {
             ui_scroll_position: None,
             sandboxed_terminal_temp_dir: None,
             sandbox_grants: DbSandboxGrants::default(),
+            budget_usage: Default::default(),
         }
     }
 
@@ -0,0 +1,393 @@
+//! User-defined hooks: shell commands from the `agent.hooks` setting that run
+//! before and after tool calls and when the agent stops.
+//!
+//! Each hook receives a JSON description of the event on stdin and runs in the
+//! project's first directory. A `pre_tool_call` hook vetoes the call by exiting
+//! with a non-zero status, and can rewrite the tool's input by printing
+//! `{"tool_input": {...}}`. The output of `post_tool_call` hooks is appended to
+//! the tool's result.
+
+use crate::{ToolInput, ToolInputPayload, ToolInputSender};
+use agent_client_protocol::schema::v1 as acp;
+use agent_settings::{AgentHook, AgentSettings};
+use anyhow::{Context as _, Result, anyhow};
+use futures::{AsyncWriteExt as _, FutureExt as _};
+use gpui::{App, AsyncApp, Entity, Task};
+use language_model::{
+    LanguageModelToolResult, LanguageModelToolResultContent, LanguageModelToolUseId,
+};
+use project::Project;
+use serde::Deserialize;
+use serde_json::json;
+use settings::Settings as _;
+use std::process::Stdio;
+#[cfg(test)]
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// The maximum number of characters of a hook's output passed to the model.
+const MAX_HOOK_OUTPUT_LEN: usize = 16 * 1024;
+
+/// The hooks that apply to a single tool call.
+#[derive(Clone)]
+pub(crate) struct ToolCallHooks {
+    pre: Arc<[AgentHook]>,
+    post: Arc<[AgentHook]>,
+    project: Entity<Project>,
+    thread_id: acp::SessionId,
+    tool_name: Arc<str>,
+    tool_use_id: LanguageModelToolUseId,
+}
+
+/// What became of a tool's input after it went through the pre-call hooks.
+pub(crate) enum HookedInput {
+    Ready(serde_json::Value),
+    Vetoed(String),
+    /// The input never arrived in full, e.g. because the model produced
+    /// invalid JSON or the turn was cancelled.
+    Missing,
+}
+
+/// Why a turn ended, as reported to `stop` hooks.
+pub(crate) enum TurnEnd {
+    Stopped(acp::StopReason),
+    /// The turn failed with the given error.
+    Failed(String),
+}
+
+/// The exit status and output of a hook's command.
+pub(crate) struct HookOutput {
+    /// `None` if the command was killed by a signal.
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl HookOutput {
+    fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+
+    fn status(&self) -> String {
+        match self.exit_code {
+            Some(code) => format!("exit code {code}"),
+            None => "no exit code".to_string(),
+        }
+    }
+}
+
+/// Stands in for the shell in tests. The closure receives each hook's command
+/// and event, and returns its output, or `None` to simulate a command that
+/// never exits.
+#[cfg(test)]
+pub(crate) struct FakeHookShell(pub Rc<dyn Fn(&str, &serde_json::Value) -> Option<HookOutput>>);
+
+#[cfg(test)]
+impl gpui::Global for FakeHookShell {}
+
+#[derive(Deserialize)]
+struct PreToolCallResponse {
+    tool_input: Option<serde_json::Value>,
+}
+
+impl ToolCallHooks {
+    /// Returns the hooks configured for the given tool, if there are any.
+    pub(crate) fn new(
+        tool_name: &Arc<str>,
+        tool_use_id: &LanguageModelToolUseId,
+        thread_id: acp::SessionId,
+        project: Entity<Project>,
+        cx: &App,
+    ) -> Option<Self> {
+        let hooks = &AgentSettings::get_global(cx).hooks;
+        let matching = |hooks: &[AgentHook]| {
+            hooks
+                .iter()
+                .filter(|hook| hook.matches_tool(tool_name))
+                .cloned()
+                .collect::<Arc<[_]>>()
+        };
+        let pre = matching(&hooks.pre_tool_call);
+        let post = matching(&hooks.post_tool_call);
+        if pre.is_empty() && post.is_empty() {
+            return None;
+        }
+        Some(Self {
+            pre,
+            post,
+            project,
+            thread_id,
+            tool_name: tool_name.clone(),
+            tool_use_id: tool_use_id.clone(),
+        })
+    }
+
+    /// Passes the tool's input through to the tool, running the pre-call hooks
+    /// on it once it is complete. The returned task resolves to the input the
+    /// tool actually received.
+    pub(crate) fn intercept_input(
+        &self,
+        mut input: ToolInput<serde_json::Value>,
+        cx: &mut App,
+    ) -> (ToolInput<serde_json::Value>, Task<HookedInput>) {
+        let (mut sender, forwarded) = ToolInputSender::channel();
+        let this = self.clone();
+        let task = cx.spawn(async move |cx| {
+            loop {
+                match input.next().await {
+                    Ok(ToolInputPayload::Partial(partial)) => {
+                        // Streaming tools act on partial input, so hold it back
+                        // until the pre-call hooks have seen the whole input.
+                        if this.pre.is_empty() {
+                            sender.send_partial(partial);
+                        }
+                    }
+                    Ok(ToolInputPayload::Full(full)) => {
+                        return match this.run_pre_hooks(full, cx).await {
+                            Ok(full) => {
+                                sender.send_full(full.clone());
+                                HookedInput::Ready(full)
+                            }
+                            Err(reason) => {
+                                sender.send_invalid_json(reason.clone());
+                                HookedInput::Vetoed(reason)
+                            }
+                        };
+                    }
+                    Ok(ToolInputPayload::InvalidJson { error_message }) => {
+                        sender.send_invalid_json(error_message);
+                        return HookedInput::Missing;
+                    }
+                    Err(_) => return HookedInput::Missing,
+                }
+            }
+        });
+        (forwarded, task)
+    }
+
+    /// Runs the pre-call hooks in order, each seeing the input as rewritten by
+    /// the ones before it. Returns the reason to give the model on a veto.
+    async fn run_pre_hooks(
+        &self,
+        mut tool_input: serde_json::Value,
+        cx: &mut AsyncApp,
+    ) -> Result<serde_json::Value, String> {
+        for hook in self.pre.iter() {
+            let event = json!({
+                "event": "pre_tool_call",
+                "thread_id": self.thread_id.0,
+                "tool_name": self.tool_name,
+                "tool_use_id": self.tool_use_id,
+                "tool_input": tool_input,
+            });
+            let veto = |reason: String| {
+                format!(
+                    "The `{}` call was blocked by the hook `{}`: {}",
+                    self.tool_name,
+                    hook.command,
+                    truncate_output(reason.trim()),
+                )
+            };
+
+            let output = run_hook(hook, &event, &self.project, cx)
+                .await
+                .map_err(|error| veto(error.to_string()))?;
+            if !output.success() {
+                let reason = if !output.stderr.trim().is_empty() {
+                    output.stderr
+                } else if !output.stdout.trim().is_empty() {
+                    output.stdout
+                } else {
+                    format!("it exited with {}", output.status())
+                };
+                return Err(veto(reason));
+            }
+
+            if output.stdout.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<PreToolCallResponse>(&output.stdout) {
+                Ok(PreToolCallResponse {
+                    tool_input: Some(rewritten),
+                }) => tool_input = rewritten,
+                Ok(PreToolCallResponse { tool_input: None }) => {}
+                Err(error) => log::warn!(
+                    "Ignoring output of pre_tool_call hook `{}` that isn't a JSON response: {error}",
+                    hook.command
+                ),
+            }
+        }
+        Ok(tool_input)
+    }
+
+    /// Applies the outcome of the pre-call hooks to the tool's result, and
+    /// appends the output of the post-call hooks.
+    pub(crate) async fn finish(
+        &self,
+        input: Task<HookedInput>,
+        result: &mut LanguageModelToolResult,
+        cx: &mut AsyncApp,
+    ) {
+        let tool_input = match input.await {
+            HookedInput::Ready(tool_input) => tool_input,
+            HookedInput::Vetoed(reason) => {
+                result.is_error = true;
+                result.content = vec![LanguageModelToolResultContent::Text(reason.into())];
+                return;
+            }
+            HookedInput::Missing => return,
+        };
+        if self.post.is_empty() {
+            return;
+        }
+
+        let tool_output = result
+            .content
+            .iter()
+            .filter_map(|content| match content {
+                LanguageModelToolResultContent::Text(text) => Some(text.as_ref()),
+                LanguageModelToolResultContent::Image(_) => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let event = json!({
+            "event": "post_tool_call",
+            "thread_id": self.thread_id.0,
+            "tool_name": self.tool_name,
+            "tool_use_id": self.tool_use_id,
+            "tool_input": tool_input,
+            "tool_output": tool_output,
+            "is_error": result.is_error,
+        });
+        for hook in self.post.iter() {
+            let report = match run_hook(hook, &event, &self.project, cx).await {
+                Ok(output) => {
+                    let text = format!("{}{}", output.stdout, output.stderr);
+                    if output.success() && text.trim().is_empty() {
+                        continue;
+                    }
+                    format!(
+                        "Output of the hook `{}` ({}):\n{}",
+                        hook.command,
+                        output.status(),
+                        truncate_output(text.trim_end())
+                    )
+                }
+                Err(error) => format!("The hook `{}` failed: {error}", hook.command),
+            };
+            result
+                .content
+                .push(LanguageModelToolResultContent::Text(report.into()));
+        }
+    }
+}
+
+/// Runs the configured `stop` hooks in the background. Their output is only
+/// logged, since the turn is already over.
+pub(crate) fn run_stop_hooks(
+    project: Entity<Project>,
+    thread_id: acp::SessionId,
+    turn_end: TurnEnd,
+    cx: &mut App,
+) {
+    let hooks = AgentSettings::get_global(cx).hooks.stop.clone();
+    if hooks.is_empty() {
+        return;
+    }
+    let event = match turn_end {
+        TurnEnd::Stopped(stop_reason) => json!({
+            "event": "stop",
+            "thread_id": thread_id.0,
+            "stop_reason": stop_reason,
+        }),
+        TurnEnd::Failed(error) => json!({
+            "event": "stop",
+            "thread_id": thread_id.0,
+            "stop_reason": "error",
+            "error": error,
+        }),
+    };
+    cx.spawn(async move |cx| {
+        for hook in &hooks {
+            match run_hook(hook, &event, &project, cx).await {
+                Ok(output) if !output.success() => log::warn!(
+                    "Stop hook `{}` exited with {}: {}",
+                    hook.command,
+                    output.status(),
+                    output.stderr.trim()
+                ),
+                Ok(_) => {}
+                Err(error) => log::error!("Stop hook `{}` failed: {error:#}", hook.command),
+            }
+        }
+    })
+    .detach();
+}
+
+/// Runs a hook's command in the project's shell, writing the event to its
+/// stdin, and kills it if it outlives its timeout.
+async fn run_hook(
+    hook: &AgentHook,
+    event: &serde_json::Value,
+    project: &Entity<Project>,
+    cx: &mut AsyncApp,
+) -> Result<HookOutput> {
+    #[cfg(test)]
+    if let Some(output) =
+        cx.try_read_global(|shell: &FakeHookShell, _| (shell.0)(&hook.command, event))
+    {
+        let timer = cx.background_executor().timer(hook.timeout);
+        return match output {
+            Some(output) => Ok(output),
+            None => {
+                timer.await;
+                Err(timed_out(hook))
+            }
+        };
+    }
+
+    let mut command = project
+        .update(cx, |project, cx| {
+            project.exec_in_shell(hook.command.clone(), cx)
+        })
+        .await?;
+    command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let mut child = command
+        .spawn()
+        .with_context(|| format!("failed to spawn `{}`", hook.command))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let event = serde_json::to_vec(event)?;
+        cx.background_spawn(async move {
+            // Hooks are free to exit without reading their input, so a broken
+            // pipe here isn't an error. Dropping stdin closes it.
+            stdin.write_all(&event).await.ok();
+        })
+        .detach();
+    }
+
+    let timer = cx.background_executor().timer(hook.timeout);
+    futures::select_biased! {
+        output = child.output().fuse() => {
+            let output = output?;
+            Ok(HookOutput {
+                exit_code: output.status.code(),
+                stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            })
+        }
+        _ = timer.fuse() => Err(timed_out(hook)),
+    }
+}
+
+fn timed_out(hook: &AgentHook) -> anyhow::Error {
+    anyhow!("it timed out after {} seconds", hook.timeout.as_secs())
+}
+
+fn truncate_output(output: &str) -> String {
+    util::truncate_and_trailoff(output, MAX_HOOK_OUTPUT_LEN)
+}
@@ -6209,8 +6209,8 @@ async fn test_subagent_thread_uses_configured_subagent_model(cx: &mut TestAppCon
         assert_eq!(subagent_thread.thinking_effort(), Some(&"high".to_string()));
     });
 
-    parent_thread.update(cx, |parent_thread, _cx| {
-        parent_thread.register_running_subagent(subagent_thread.downgrade());
+    parent_thread.update(cx, |parent_thread, cx| {
+        parent_thread.register_running_subagent(subagent_thread.downgrade(), cx);
     });
     parent_thread.update(cx, |parent_thread, cx| {
         parent_thread.set_model(parent_model.clone(), cx);
@@ -6537,8 +6537,8 @@ async fn test_parent_cancel_stops_subagent(cx: &mut TestAppContext) {
 
     let subagent = cx.new(|cx| Thread::new_subagent(&parent, cx));
 
-    parent.update(cx, |thread, _cx| {
-        thread.register_running_subagent(subagent.downgrade());
+    parent.update(cx, |thread, cx| {
+        thread.register_running_subagent(subagent.downgrade(), cx);
     });
 
     subagent
@@ -8719,3 +8719,495 @@ async fn test_mid_turn_model_and_settings_refresh(cx: &mut TestAppContext) {
     // Thinking should now be enabled.
     assert!(model_b_completions[0].thinking_allowed);
 }
+
+fn hook(command: &str, timeout: Duration) -> agent_settings::AgentHook {
+    agent_settings::AgentHook {
+        command: command.to_string(),
+        tools: None,
+        timeout,
+    }
+}
+
+fn hook_output(exit_code: i32, stdout: &str, stderr: &str) -> Option<crate::hooks::HookOutput> {
+    Some(crate::hooks::HookOutput {
+        exit_code: Some(exit_code),
+        stdout: stdout.to_string(),
+        stderr: stderr.to_string(),
+    })
+}
+
+/// Configures the given hooks and answers their commands with `shell` instead
+/// of a real shell. Returns the command and event of every hook that ran.
+fn set_fake_hooks(
+    hooks: agent_settings::AgentHooks,
+    shell: impl Fn(&str, &serde_json::Value) -> Option<crate::hooks::HookOutput> + 'static,
+    cx: &mut TestAppContext,
+) -> Rc<std::cell::RefCell<Vec<(String, serde_json::Value)>>> {
+    let runs = Rc::new(std::cell::RefCell::new(Vec::new()));
+    cx.update(|cx| {
+        let mut settings = agent_settings::AgentSettings::get_global(cx).clone();
+        settings.hooks = hooks;
+        agent_settings::AgentSettings::override_global(settings, cx);
+
+        let runs = runs.clone();
+        cx.set_global(crate::hooks::FakeHookShell(Rc::new(
+            move |command: &str, event: &serde_json::Value| {
+                runs.borrow_mut().push((command.to_string(), event.clone()));
+                shell(command, event)
+            },
+        )));
+    });
+    runs
+}
+
+fn send_echo_tool_use(fake_model: &FakeLanguageModel, text: &str) {
+    let input = json!({ "text": text });
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "tool_1".into(),
+            name: EchoTool::NAME.into(),
+            raw_input: input.to_string(),
+            input: language_model::LanguageModelToolUseInput::Json(input),
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+}
+
+/// Returns the tool result sent to the model in the latest request.
+fn last_tool_result(fake_model: &FakeLanguageModel) -> LanguageModelToolResult {
+    let completion = fake_model.pending_completions().pop().unwrap();
+    completion
+        .messages
+        .last()
+        .unwrap()
+        .content
+        .iter()
+        .find_map(|content| match content {
+            MessageContent::ToolResult(result) => Some(result.clone()),
+            _ => None,
+        })
+        .expect("the latest request should end with a tool result")
+}
+
+#[gpui::test]
+async fn test_pre_tool_call_hook_vetoes_tool_call(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    let runs = set_fake_hooks(
+        agent_settings::AgentHooks {
+            pre_tool_call: vec![hook("./check.sh", Duration::from_secs(60))],
+            ..Default::default()
+        },
+        |_, _| hook_output(1, "", "echo is not allowed here\n"),
+        cx,
+    );
+
+    let _events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(EchoTool);
+            thread.send(ClientUserMessageId::new(), ["Call the echo tool!"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    send_echo_tool_use(fake_model, "hello");
+    cx.run_until_parked();
+
+    let runs = runs.borrow();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].0, "./check.sh");
+    assert_eq!(runs[0].1["event"], "pre_tool_call");
+    assert_eq!(runs[0].1["tool_name"], EchoTool::NAME);
+    assert_eq!(runs[0].1["tool_input"], json!({ "text": "hello" }));
+
+    let result = last_tool_result(fake_model);
+    assert!(result.is_error);
+    assert_eq!(
+        result.content,
+        vec![
+            "The `echo` call was blocked by the hook `./check.sh`: echo is not allowed here".into()
+        ]
+    );
+}
+
+#[gpui::test]
+async fn test_pre_tool_call_hook_rewrites_input(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    let runs = set_fake_hooks(
+        agent_settings::AgentHooks {
+            pre_tool_call: vec![
+                hook("./rewrite.sh", Duration::from_secs(60)),
+                hook("./check.sh", Duration::from_secs(60)),
+            ],
+            ..Default::default()
+        },
+        |command, _| match command {
+            "./rewrite.sh" => hook_output(0, r#"{"tool_input": {"text": "rewritten"}}"#, ""),
+            _ => hook_output(0, "", ""),
+        },
+        cx,
+    );
+
+    let _events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(EchoTool);
+            thread.send(ClientUserMessageId::new(), ["Call the echo tool!"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    send_echo_tool_use(fake_model, "hello");
+    cx.run_until_parked();
+
+    // Later hooks see the input as rewritten by earlier ones.
+    let runs = runs.borrow();
+    assert_eq!(runs.len(), 2);
+    assert_eq!(runs[1].1["tool_input"], json!({ "text": "rewritten" }));
+
+    let result = last_tool_result(fake_model);
+    assert!(!result.is_error);
+    assert_eq!(result.content, vec!["rewritten".into()]);
+}
+
+#[gpui::test]
+async fn test_pre_tool_call_hook_timeout_blocks_tool_call(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    set_fake_hooks(
+        agent_settings::AgentHooks {
+            pre_tool_call: vec![hook("./slow.sh", Duration::from_secs(5))],
+            ..Default::default()
+        },
+        |_, _| None,
+        cx,
+    );
+
+    let _events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(EchoTool);
+            thread.send(ClientUserMessageId::new(), ["Call the echo tool!"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    send_echo_tool_use(fake_model, "hello");
+    cx.run_until_parked();
+    assert!(
+        fake_model.pending_completions().is_empty(),
+        "the tool call should wait for the hook"
+    );
+
+    cx.executor().advance_clock(Duration::from_secs(5));
+    cx.run_until_parked();
+    let result = last_tool_result(fake_model);
+    assert!(result.is_error);
+    assert_eq!(
+        result.content,
+        vec![
+            "The `echo` call was blocked by the hook `./slow.sh`: it timed out after 5 seconds"
+                .into()
+        ]
+    );
+}
+
+#[gpui::test]
+async fn test_post_tool_call_hook_output_is_fed_back(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    let runs = set_fake_hooks(
+        agent_settings::AgentHooks {
+            post_tool_call: vec![
+                hook("./quiet.sh", Duration::from_secs(60)),
+                hook("./lint.sh", Duration::from_secs(60)),
+            ],
+            ..Default::default()
+        },
+        |command, _| match command {
+            "./lint.sh" => hook_output(2, "1 warning\n", "lint failed\n"),
+            _ => hook_output(0, "", ""),
+        },
+        cx,
+    );
+
+    let _events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(EchoTool);
+            thread.send(ClientUserMessageId::new(), ["Call the echo tool!"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    send_echo_tool_use(fake_model, "hello");
+    cx.run_until_parked();
+
+    let runs = runs.borrow();
+    assert_eq!(runs.len(), 2);
+    assert_eq!(runs[1].1["event"], "post_tool_call");
+    assert_eq!(runs[1].1["tool_output"], "hello");
+    assert_eq!(runs[1].1["is_error"], false);
+
+    // Hooks that succeed silently add nothing.
+    let result = last_tool_result(fake_model);
+    assert!(!result.is_error);
+    assert_eq!(
+        result.content,
+        vec![
+            "hello".into(),
+            "Output of the hook `./lint.sh` (exit code 2):\n1 warning\nlint failed".into(),
+        ]
+    );
+}
+
+#[gpui::test]
+async fn test_stop_hooks_run_whenever_a_turn_ends(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    let runs = set_fake_hooks(
+        agent_settings::AgentHooks {
+            stop: vec![hook("./notify.sh", Duration::from_secs(60))],
+            ..Default::default()
+        },
+        |_, _| hook_output(0, "", ""),
+        cx,
+    );
+    let last_stop_event = || runs.borrow_mut().pop().map(|(_, event)| event);
+
+    let _events = thread
+        .update(cx, |thread, cx| {
+            thread.send(ClientUserMessageId::new(), ["Hello"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_text_chunk("Hi!");
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+    let event = last_stop_event().unwrap();
+    assert_eq!(event["event"], "stop");
+    assert_eq!(event["stop_reason"], "end_turn");
+
+    let _events = thread
+        .update(cx, |thread, cx| {
+            thread.send(ClientUserMessageId::new(), ["Hello again"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_error(LanguageModelCompletionError::PaymentRequired);
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+    let event = last_stop_event().unwrap();
+    assert_eq!(event["stop_reason"], "error");
+    assert!(
+        !event["error"].as_str().unwrap().is_empty(),
+        "the error should be passed to the hook: {event}"
+    );
+
+    let _events = thread
+        .update(cx, |thread, cx| {
+            thread.send(ClientUserMessageId::new(), ["One more time"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    thread.update(cx, |thread, cx| thread.cancel(cx)).await;
+    cx.run_until_parked();
+    let event = last_stop_event().unwrap();
+    assert_eq!(event["stop_reason"], "cancelled");
+    assert!(last_stop_event().is_none());
+}
+
+fn set_budget(
+    budget: agent_settings::AgentBudget,
+    model_prices: Vec<settings::LanguageModelPrice>,
+    cx: &mut TestAppContext,
+) {
+    cx.update(|cx| {
+        let mut settings = agent_settings::AgentSettings::get_global(cx).clone();
+        settings.budget = budget;
+        settings.model_prices = model_prices;
+        agent_settings::AgentSettings::override_global(settings, cx);
+    });
+}
+
+/// Has the model use the given number of input tokens and call the echo tool,
+/// then returns the reasons the turn stopped.
+async fn run_turn_with_echo_tool_use(
+    thread: &Entity<Thread>,
+    fake_model: &FakeLanguageModel,
+    input_tokens: u64,
+    cx: &mut TestAppContext,
+) -> Vec<acp::StopReason> {
+    let events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(EchoTool);
+            thread.send(ClientUserMessageId::new(), ["Call the echo tool!"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::UsageUpdate(
+        TokenUsage {
+            input_tokens,
+            ..Default::default()
+        },
+    ));
+    send_echo_tool_use(fake_model, "hello");
+    stop_events(events.collect().await)
+}
+
+#[gpui::test]
+async fn test_token_budget_stops_turn(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    set_budget(
+        agent_settings::AgentBudget {
+            max_tokens: Some(100),
+            ..Default::default()
+        },
+        Vec::new(),
+        cx,
+    );
+
+    let stops = run_turn_with_echo_tool_use(&thread, fake_model, 150, cx).await;
+    assert_eq!(stops, vec![acp::StopReason::MaxTurnRequests]);
+    assert!(
+        fake_model.pending_completions().is_empty(),
+        "the tool result shouldn't be sent to the model"
+    );
+}
+
+#[gpui::test]
+async fn test_tool_call_budget_stops_turn(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    set_budget(
+        agent_settings::AgentBudget {
+            max_tool_calls: Some(1),
+            ..Default::default()
+        },
+        Vec::new(),
+        cx,
+    );
+
+    let stops = run_turn_with_echo_tool_use(&thread, fake_model, 10, cx).await;
+    assert_eq!(stops, vec![acp::StopReason::MaxTurnRequests]);
+    assert!(fake_model.pending_completions().is_empty());
+}
+
+#[gpui::test]
+async fn test_cost_budget_stops_turn(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    set_budget(
+        agent_settings::AgentBudget {
+            max_cost: Some(10.0),
+            ..Default::default()
+        },
+        // A dollar per input token.
+        vec![settings::LanguageModelPrice {
+            provider: None,
+            model: None,
+            input: Some(1_000_000.0),
+            output: None,
+            cache_write: None,
+            cache_read: None,
+        }],
+        cx,
+    );
+
+    let stops = run_turn_with_echo_tool_use(&thread, fake_model, 20, cx).await;
+    assert_eq!(stops, vec![acp::StopReason::MaxTurnRequests]);
+    assert!(fake_model.pending_completions().is_empty());
+}
+
+#[gpui::test]
+async fn test_time_budget_stops_turn_mid_request(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    set_budget(
+        agent_settings::AgentBudget {
+            max_duration: Some(Duration::from_secs(60)),
+            ..Default::default()
+        },
+        Vec::new(),
+        cx,
+    );
+
+    let events = thread
+        .update(cx, |thread, cx| {
+            thread.send(ClientUserMessageId::new(), ["Take your time"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_text_chunk("Thinking it over...");
+    cx.run_until_parked();
+
+    // The model never finishes, so only the deadline can end the turn.
+    cx.executor().advance_clock(Duration::from_secs(60));
+    cx.run_until_parked();
+    assert_eq!(
+        stop_events(events.collect().await),
+        vec![acp::StopReason::MaxTurnRequests]
+    );
+}
+
+#[gpui::test]
+async fn test_subagent_spend_counts_toward_parent_budget(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    set_budget(
+        agent_settings::AgentBudget {
+            max_tokens: Some(100),
+            ..Default::default()
+        },
+        Vec::new(),
+        cx,
+    );
+    let tokens_used = |cx: &mut TestAppContext| {
+        thread.read_with(cx, |thread, cx| {
+            let status = thread.budget_status(cx).unwrap();
+            status.limits[0].used
+        })
+    };
+
+    // Spend from before the subagent started working for the parent doesn't count.
+    let subagent = cx.new(|cx| Thread::new_subagent(&thread, cx));
+    subagent.update(cx, |subagent, cx| {
+        subagent.add_mcp_sampling_token_usage(
+            TokenUsage {
+                input_tokens: 500,
+                ..Default::default()
+            },
+            cx,
+        )
+    });
+    thread.update(cx, |thread, cx| {
+        thread.register_running_subagent(subagent.downgrade(), cx)
+    });
+    assert_eq!(tokens_used(cx), 0.0);
+
+    subagent.update(cx, |subagent, cx| {
+        subagent.add_mcp_sampling_token_usage(
+            TokenUsage {
+                input_tokens: 150,
+                ..Default::default()
+            },
+            cx,
+        )
+    });
+    assert_eq!(tokens_used(cx), 150.0);
+
+    // The spend stays with the parent once the subagent is done.
+    let subagent_id = subagent.read_with(cx, |subagent, _| subagent.id().clone());
+    thread.update(cx, |thread, cx| {
+        thread.unregister_running_subagent(&subagent_id, cx)
+    });
+    assert_eq!(tokens_used(cx), 150.0);
+
+    let events = thread
+        .update(cx, |thread, cx| {
+            thread.send(ClientUserMessageId::new(), ["Hello"], cx)
+        })
+        .unwrap();
+    assert_eq!(
+        stop_events(events.collect().await),
+        vec![acp::StopReason::MaxTurnRequests]
+    );
+    assert!(fake_model.pending_completions().is_empty());
+}
@@ -1,9 +1,11 @@
 use crate::{
-    ApplyCodeActionTool, AskUserTool, CodeActionStore, ContextServerRegistry, CopyPathTool,
-    CreateDirectoryTool, CreateThreadTool, DbLanguageModel, DbThread, DeletePathTool,
-    DiagnosticsTool, EditFileTool, FetchTool, FindPathTool, FindReferencesTool, GetCodeActionsTool,
+    AddReviewCommentTool, AgentDebugSessions, ApplyCodeActionTool, AskUserTool, BudgetSpend,
+    BudgetStatus, BudgetUsage, CodeActionStore, ContextServerRegistry, CopyPathTool,
+    CreateDirectoryTool, CreateThreadTool, DbLanguageModel, DbThread, DebugBreakpointTool,
+    DebugControlTool, DebugInspectTool, DebugStartTool, DeletePathTool, DiagnosticsTool,
+    EditFileTool, FetchTool, FindPathTool, FindReferencesTool, GetCodeActionsTool,
     GoToDefinitionTool, GrepTool, ListAgentsAndModelsTool, ListDirectoryTool, MovePathTool,
-    ProjectSnapshot, ReadFileTool, RenameTool, SandboxedTerminalTool, SpawnAgentTool,
+    ProjectSnapshot, ReadFileTool, RenameTool, RunTaskTool, SandboxedTerminalTool, SpawnAgentTool,
     SystemPromptTemplate, Template, Templates, TerminalTool, ToolPermissionDecision, WebSearchTool,
     WriteFileTool, decide_permission_from_settings,
 };
@@ -11,6 +13,7 @@ use acp_thread::{ClientUserMessageId, MentionUri};
 use action_log::ActionLog;
 use agent_settings::UserAgentsMd;
 
+use crate::hooks::{self, ToolCallHooks, TurnEnd};
 use crate::sandboxing::{
     SandboxRequest, ThreadSandbox, ThreadSandboxGrants, sandbox_git_dirs,
     sandbox_worktree_writable_paths, sandboxing_available_for_project,
@@ -18,8 +21,8 @@ use crate::sandboxing::{
 };
 use agent_client_protocol::schema::v1 as acp;
 use agent_settings::{
-    AgentProfileId, AgentProfileSettings, AgentSettings, AutoCompactThreshold, COMPACTION_PROMPT,
-    SUMMARIZE_THREAD_DETAILED_PROMPT, SUMMARIZE_THREAD_PROMPT, builtin_profiles,
+    AgentBudget, AgentProfileId, AgentProfileSettings, AgentSettings, AutoCompactThreshold,
+    COMPACTION_PROMPT, SUMMARIZE_THREAD_DETAILED_PROMPT, SUMMARIZE_THREAD_PROMPT, builtin_profiles,
 };
 use anyhow::{Context as _, Result, anyhow};
 use chrono::{DateTime, Local, Utc};
@@ -35,8 +38,8 @@ use futures::{
 };
 use futures::{StreamExt, stream};
 use gpui::{
-    App, AppContext, AsyncApp, Context, Entity, EventEmitter, ReadGlobal as _, SharedString, Task,
-    WeakEntity,
+    App, AppContext, AsyncApp, Context, Entity, EntityId, EventEmitter, ReadGlobal as _,
+    SharedString, Task, WeakEntity,
 };
 use heck::ToSnakeCase as _;
 use language_model::{
@@ -328,6 +331,7 @@ impl UserMessage {
         const MERGE_CONFLICT_TAG: &str = "<merge_conflicts>";
         const OPEN_SKILLS_TAG: &str =
             "<skills>\nThe user has attached the following agent skills:\n";
+        const OPEN_MCP_RESOURCES_TAG: &str = "<mcp_resources>";
 
         let mut file_context = OPEN_FILES_TAG.to_string();
         let mut directory_context = OPEN_DIRECTORIES_TAG.to_string();
@@ -340,6 +344,7 @@ impl UserMessage {
         let mut diffs_context = OPEN_DIFFS_TAG.to_string();
         let mut merge_conflict_context = MERGE_CONFLICT_TAG.to_string();
         let mut skills_context = OPEN_SKILLS_TAG.to_string();
+        let mut mcp_resources_context = OPEN_MCP_RESOURCES_TAG.to_string();
 
         for chunk in &*self.content {
             let chunk = match chunk {
@@ -461,6 +466,14 @@ impl UserMessage {
                             let label = format!("{} ({})", name, source);
                             write!(&mut skills_context, "\nSkill: {}\n{}\n", label, content).ok();
                         }
+                        MentionUri::McpResource { server_id, uri, .. } => {
+                            write!(
+                                &mut mcp_resources_context,
+                                "\nResource {} from context server {}:\n{}\n",
+                                uri, server_id, content
+                            )
+                            .ok();
+                        }
                     }
 
                     language_model::MessageContent::Text(uri.as_link().to_string())
@@ -549,6 +562,13 @@ impl UserMessage {
                 .push(language_model::MessageContent::Text(merge_conflict_context));
         }
 
+        if mcp_resources_context.len() > OPEN_MCP_RESOURCES_TAG.len() {
+            mcp_resources_context.push_str("</mcp_resources>\n");
+            message
+                .content
+                .push(language_model::MessageContent::Text(mcp_resources_context));
+        }
+
         if message.content.len() > len_before_context {
             message.content.insert(
                 len_before_context,
@@ -798,6 +818,47 @@ pub trait ThreadEnvironment {
             "Listing available agents is not supported in this environment"
         ))
     }
+
+    /// Lists the project's tasks (from `tasks.json`, languages, and global task
+    /// files), resolved against the current task context.
+    fn list_project_tasks(&self, cx: &mut AsyncApp) -> Task<Result<Vec<ProjectTaskInfo>>> {
+        let _ = cx;
+        Task::ready(Err(anyhow::anyhow!(
+            "Running project tasks is not supported in this environment"
+        )))
+    }
+
+    /// Runs the project task with the given label in a visible terminal and
+    /// waits for it to finish.
+    fn run_project_task(
+        &self,
+        label: String,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<ProjectTaskOutput>> {
+        let _ = (label, cx);
+        Task::ready(Err(anyhow::anyhow!(
+            "Running project tasks is not supported in this environment"
+        )))
+    }
+
+    /// Lists the project's debug scenarios (from `debug.json` and recently
+    /// started sessions), resolved against the current task context.
+    fn list_debug_scenarios(&self, cx: &mut AsyncApp) -> Task<Result<Vec<DebugScenarioInfo>>> {
+        let _ = cx;
+        Task::ready(Err(anyhow::anyhow!(
+            "Debugging is not supported in this environment"
+        )))
+    }
+
+    /// Starts the debug scenario with the given label in the debug panel. The
+    /// returned task resolves once the session has been requested; the session
+    /// itself appears in the project's `DapStore`.
+    fn start_debug_scenario(&self, label: String, cx: &mut AsyncApp) -> Task<Result<()>> {
+        let _ = (label, cx);
+        Task::ready(Err(anyhow::anyhow!(
+            "Debugging is not supported in this environment"
+        )))
+    }
 }
 
 /// A request to create a new sibling thread.
@@ -839,6 +900,45 @@ pub struct SiblingThreadInfo {
     pub warning: Option<String>,
 }
 
+/// A project task, resolved against the current task context, that can be run
+/// with `run_project_task`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectTaskInfo {
+    /// The resolved label, used to pick the task to run.
+    pub label: String,
+    /// The full command line the task runs.
+    pub command: String,
+    /// Where the task is defined (e.g. a `tasks.json` file or a language).
+    pub source: String,
+}
+
+/// The outcome of a project task that ran to completion.
+#[derive(Debug, Clone)]
+pub struct ProjectTaskOutput {
+    /// The process exit code, if the task exited normally.
+    pub exit_code: Option<i32>,
+    /// Everything the task printed to its terminal.
+    pub output: String,
+}
+
+/// A debug scenario that can be started with `start_debug_scenario`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DebugScenarioInfo {
+    /// The scenario's label, used to pick the scenario to start.
+    pub label: String,
+    /// The debug adapter the scenario uses.
+    pub adapter: String,
+    /// Where the scenario is defined (e.g. a `debug.json` file).
+    pub source: String,
+    /// The command line of the task the scenario builds with, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build_command: Option<String>,
+    /// The program the scenario launches and its arguments, if the adapter
+    /// configuration names one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub program: Option<String>,
+}
+
 /// A list of agents and, for each, the models available for use.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AvailableAgents {
@@ -1205,6 +1305,8 @@ enum CompletionError {
     MaxTokens,
     #[error("refusal")]
     Refusal,
+    #[error("budget exceeded")]
+    BudgetExceeded,
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -1267,6 +1369,12 @@ pub struct Thread {
     /// `cumulative_token_usage` for the in-flight completion request. Reset at
     /// the start of each request.
     current_request_token_usage: TokenUsage,
+    /// Tool calls, time, and cost counted against the thread's budget.
+    budget_usage: BudgetUsage,
+    /// When the running turn started, so its time counts against the budget.
+    turn_started_at: Option<Instant>,
+    /// Ends the running turn once the thread reaches its time limit.
+    budget_deadline: Option<Task<()>>,
     pending_compaction_telemetry: Option<CompactionTelemetry>,
     #[allow(unused)]
     initial_project_snapshot: Shared<Task<Option<Arc<ProjectSnapshot>>>>,
@@ -1293,6 +1401,9 @@ pub struct Thread {
     ui_scroll_position: Option<gpui::ListOffset>,
     /// Weak references to running subagent threads for cancellation propagation
     running_subagents: Vec<WeakEntity<Thread>>,
+    /// What each running subagent had spent when it started working for this
+    /// thread, so that what it spends since counts against this thread's budget.
+    subagent_spend_baselines: HashMap<EntityId, BudgetSpend>,
     inherits_parent_model_settings: bool,
     sandboxed_terminal_temp_dir: Option<PathBuf>,
     /// Sandbox permissions the user approved "for the rest of the thread".
@@ -1408,6 +1519,9 @@ impl Thread {
             request_token_usage: HashMap::default(),
             cumulative_token_usage: TokenUsage::default(),
             current_request_token_usage: TokenUsage::default(),
+            budget_usage: BudgetUsage::default(),
+            turn_started_at: None,
+            budget_deadline: None,
             pending_compaction_telemetry: None,
             initial_project_snapshot: {
                 let project_snapshot = Self::project_snapshot(project.clone(), cx);
@@ -1433,6 +1547,7 @@ impl Thread {
             draft_prompt: None,
             ui_scroll_position: None,
             running_subagents: Vec::new(),
+            subagent_spend_baselines: HashMap::default(),
             inherits_parent_model_settings: true,
             sandboxed_terminal_temp_dir: None,
             sandbox_grants: Rc::new(RefCell::new(ThreadSandboxGrants::default())),
@@ -1791,6 +1906,9 @@ impl Thread {
             request_token_usage: db_thread.request_token_usage.clone(),
             cumulative_token_usage: db_thread.cumulative_token_usage,
             current_request_token_usage: TokenUsage::default(),
+            budget_usage: db_thread.budget_usage,
+            turn_started_at: None,
+            budget_deadline: None,
             pending_compaction_telemetry: None,
             initial_project_snapshot: Task::ready(db_thread.initial_project_snapshot).shared(),
             context_server_registry,
@@ -1815,6 +1933,7 @@ impl Thread {
                 offset_in_item: gpui::px(sp.offset_in_item),
             }),
             running_subagents: Vec::new(),
+            subagent_spend_baselines: HashMap::default(),
             inherits_parent_model_settings: true,
             sandboxed_terminal_temp_dir: db_thread.sandboxed_terminal_temp_dir,
             sandbox_grants: Rc::new(RefCell::new(ThreadSandboxGrants::from_db(
@@ -1902,6 +2021,10 @@ impl Thread {
             initial_project_snapshot: None,
             cumulative_token_usage: self.cumulative_token_usage,
             request_token_usage: self.request_token_usage.clone(),
+            budget_usage: BudgetUsage {
+                active_time: self.active_time(),
+                ..self.budget_usage
+            },
             model: (&self.model).into(),
             profile: Some(self.profile_id.clone()),
             subagent_context: self.subagent_context.clone(),
@@ -2163,6 +2286,19 @@ impl Thread {
             self.project.clone(),
             environment.clone(),
         ));
+        self.add_tool(RunTaskTool::new(environment.clone()));
+        let debug_sessions = AgentDebugSessions::default();
+        self.add_tool(DebugStartTool::new(
+            self.project.clone(),
+            environment.clone(),
+            debug_sessions.clone(),
+        ));
+        self.add_tool(DebugBreakpointTool::new(self.project.clone()));
+        self.add_tool(DebugControlTool::new(
+            self.project.clone(),
+            debug_sessions.clone(),
+        ));
+        self.add_tool(DebugInspectTool::new(self.project.clone(), debug_sessions));
         self.add_tool(WebSearchTool);
 
         self.add_tool(AskUserTool);
@@ -2181,6 +2317,10 @@ impl Thread {
         ));
         self.add_tool(GoToDefinitionTool::new(self.project.clone()));
         self.add_tool(RenameTool::new(self.project.clone()));
+        self.add_tool(AddReviewCommentTool::new(
+            self.project.clone(),
+            self.action_log.clone(),
+        ));
 
         if self.depth() < MAX_SUBAGENT_DEPTH {
             self.add_tool(SpawnAgentTool::new(environment.clone()));
@@ -2270,23 +2410,41 @@ impl Thread {
     }
 
     pub fn cancel(&mut self, cx: &mut Context<Self>) -> Task<()> {
-        for subagent in self.running_subagents.drain(..) {
+        self.stop_running_turn(acp::StopReason::Cancelled, cx)
+    }
+
+    /// Stops the running turn and its subagents, reporting the given reason.
+    fn stop_running_turn(
+        &mut self,
+        stop_reason: acp::StopReason,
+        cx: &mut Context<Self>,
+    ) -> Task<()> {
+        self.stop_budget_timer();
+        for subagent in std::mem::take(&mut self.running_subagents) {
             if let Some(subagent) = subagent.upgrade() {
+                self.add_subagent_spend(&subagent, cx);
                 subagent.update(cx, |thread, cx| thread.cancel(cx)).detach();
             }
         }
+        self.subagent_spend_baselines.clear();
 
         let Some(running_turn) = self.running_turn.take() else {
             self.flush_pending_message(cx);
             return Task::ready(());
         };
 
-        let turn_task = running_turn.cancel();
+        let turn_task = running_turn.stop(stop_reason);
 
         cx.spawn(async move |this, cx| {
             turn_task.await;
             this.update(cx, |this, cx| {
                 this.flush_pending_message(cx);
+                hooks::run_stop_hooks(
+                    this.project.clone(),
+                    this.id().clone(),
+                    TurnEnd::Stopped(stop_reason),
+                    cx,
+                );
             })
             .ok();
         })
@@ -2300,7 +2458,12 @@ impl Thread {
         self.end_turn_at_next_boundary
     }
 
-    fn accumulate_token_usage(&mut self, update: language_model::TokenUsage) {
+    fn accumulate_token_usage(
+        &mut self,
+        update: language_model::TokenUsage,
+        model: Option<&Arc<dyn LanguageModel>>,
+        cx: &App,
+    ) {
         let previous_accounted_usage = self.current_request_token_usage;
         let current_accounted_usage = TokenUsage {
             input_tokens: previous_accounted_usage
@@ -2317,25 +2480,40 @@ impl Thread {
                 .max(update.cache_read_input_tokens),
         };
         self.current_request_token_usage = current_accounted_usage;
-        self.cumulative_token_usage = self.cumulative_token_usage
-            + TokenUsage {
-                input_tokens: current_accounted_usage
-                    .input_tokens
-                    .saturating_sub(previous_accounted_usage.input_tokens),
-                output_tokens: current_accounted_usage
-                    .output_tokens
-                    .saturating_sub(previous_accounted_usage.output_tokens),
-                cache_creation_input_tokens: current_accounted_usage
-                    .cache_creation_input_tokens
-                    .saturating_sub(previous_accounted_usage.cache_creation_input_tokens),
-                cache_read_input_tokens: current_accounted_usage
-                    .cache_read_input_tokens
-                    .saturating_sub(previous_accounted_usage.cache_read_input_tokens),
-            };
+        let added_usage = TokenUsage {
+            input_tokens: current_accounted_usage
+                .input_tokens
+                .saturating_sub(previous_accounted_usage.input_tokens),
+            output_tokens: current_accounted_usage
+                .output_tokens
+                .saturating_sub(previous_accounted_usage.output_tokens),
+            cache_creation_input_tokens: current_accounted_usage
+                .cache_creation_input_tokens
+                .saturating_sub(previous_accounted_usage.cache_creation_input_tokens),
+            cache_read_input_tokens: current_accounted_usage
+                .cache_read_input_tokens
+                .saturating_sub(previous_accounted_usage.cache_read_input_tokens),
+        };
+        self.cumulative_token_usage = self.cumulative_token_usage + added_usage;
+        self.add_budget_cost(&added_usage, model, cx);
+    }
+
+    /// Counts the estimated cost of the given usage against the budget, if the
+    /// model has a price in the settings.
+    fn add_budget_cost(
+        &mut self,
+        usage: &TokenUsage,
+        model: Option<&Arc<dyn LanguageModel>>,
+        cx: &App,
+    ) {
+        if let Some(pricing) = model.and_then(|model| AgentSettings::pricing_for_model(model, cx)) {
+            self.budget_usage.cost += pricing.cost(usage);
+        }
     }
 
     fn update_token_usage(&mut self, update: language_model::TokenUsage, cx: &mut Context<Self>) {
-        self.accumulate_token_usage(update);
+        let model = self.model().cloned();
+        self.accumulate_token_usage(update, model.as_ref(), cx);
 
         let Some(last_user_message) = self.last_user_message() else {
             return;
@@ -2347,6 +2525,20 @@ impl Thread {
         cx.notify();
     }
 
+    /// Counts tokens spent on completions requested by a context server (MCP
+    /// sampling) toward this thread's cumulative usage. They don't occupy the
+    /// thread's context window, so the per-request usage is left untouched.
+    pub fn add_mcp_sampling_token_usage(
+        &mut self,
+        usage: language_model::TokenUsage,
+        cx: &mut Context<Self>,
+    ) {
+        self.cumulative_token_usage = self.cumulative_token_usage + usage;
+        let model = self.model().cloned();
+        self.add_budget_cost(&usage, model.as_ref(), cx);
+        cx.notify();
+    }
+
     /// Records that the last request overflowed the model's context window so
     /// the token usage indicator reports `Exceeded` instead of the stale usage
     /// from the last successful request. Providers don't report usage for
@@ -2410,6 +2602,84 @@ impl Thread {
         self.cumulative_token_usage
     }
 
+    /// The budget of this thread, from its profile or the global settings.
+    pub fn budget(&self, cx: &App) -> AgentBudget {
+        AgentSettings::get_global(cx).budget_for_profile(&self.profile_id)
+    }
+
+    /// How much of its budget the thread has used, or `None` if its budget has
+    /// no limits.
+    pub fn budget_status(&self, cx: &App) -> Option<BudgetStatus> {
+        BudgetStatus::new(&self.budget(cx), &self.budget_spend(cx), self.active_time())
+    }
+
+    /// What the thread has spent, including what its running subagents have
+    /// spent since they started working for it.
+    fn budget_spend(&self, cx: &App) -> BudgetSpend {
+        let own = BudgetSpend {
+            tokens: self.cumulative_token_usage.total_tokens() + self.budget_usage.subagent_tokens,
+            tool_calls: self.budget_usage.tool_calls,
+            cost: self.budget_usage.cost,
+        };
+        self.running_subagents
+            .iter()
+            .filter_map(|subagent| {
+                let subagent = subagent.upgrade()?;
+                let baseline = self.subagent_spend_baselines.get(&subagent.entity_id())?;
+                Some(subagent.read(cx).budget_spend(cx).since(baseline))
+            })
+            .fold(own, |total, spend| total + spend)
+    }
+
+    /// Counts what the subagent spent while working for this thread against
+    /// this thread's budget.
+    fn add_subagent_spend(&mut self, subagent: &Entity<Thread>, cx: &App) {
+        let Some(baseline) = self.subagent_spend_baselines.remove(&subagent.entity_id()) else {
+            return;
+        };
+        let spend = subagent.read(cx).budget_spend(cx).since(&baseline);
+        self.budget_usage.subagent_tokens += spend.tokens;
+        self.budget_usage.tool_calls += spend.tool_calls;
+        self.budget_usage.cost += spend.cost;
+    }
+
+    /// The time spent running turns, including the one in progress.
+    fn active_time(&self) -> Duration {
+        self.budget_usage.active_time
+            + self
+                .turn_started_at
+                .map_or(Duration::ZERO, |started_at| started_at.elapsed())
+    }
+
+    /// Stops counting the running turn's time against the budget.
+    fn stop_budget_timer(&mut self) {
+        self.budget_deadline = None;
+        if let Some(started_at) = self.turn_started_at.take() {
+            self.budget_usage.active_time += started_at.elapsed();
+        }
+    }
+
+    /// Starts counting the running turn's time against the budget, and stops
+    /// the turn once the thread has used all of its time.
+    fn start_budget_timer(&mut self, cx: &mut Context<Self>) {
+        let remaining = self
+            .budget(cx)
+            .max_duration
+            .map(|max_duration| max_duration.saturating_sub(self.active_time()));
+        self.turn_started_at = Some(Instant::now());
+        self.budget_deadline = remaining.map(|remaining| {
+            cx.spawn(async move |this, cx| {
+                cx.background_executor().timer(remaining).await;
+                this.update(cx, |this, cx| {
+                    log::info!("Stopping turn: the thread reached its time limit");
+                    this.stop_running_turn(acp::StopReason::MaxTurnRequests, cx)
+                        .detach();
+                })
+                .ok();
+            })
+        });
+    }
+
     pub fn latest_token_usage(&self) -> Option<acp_thread::TokenUsage> {
         let usage = self.latest_request_token_usage()?;
         let model = self.model()?;
@@ -2703,10 +2973,16 @@ impl Thread {
 
                 _ = this.update(cx, |this, cx| this.flush_pending_message(cx));
 
+                let run_stop_hooks = |turn_end, cx: &mut AsyncApp| {
+                    _ = this.update(cx, |this, cx| {
+                        hooks::run_stop_hooks(this.project.clone(), this.id().clone(), turn_end, cx)
+                    });
+                };
                 match turn_result {
                     Ok(()) => {
                         log::debug!("Turn execution completed");
                         event_stream.send_stop(acp::StopReason::EndTurn);
+                        run_stop_hooks(TurnEnd::Stopped(acp::StopReason::EndTurn), cx);
                     }
                     Err(error) => {
                         log::error!("Turn execution failed: {:?}", error);
@@ -2714,21 +2990,35 @@ impl Thread {
                             Ok(CompletionError::Refusal) => {
                                 event_stream.send_stop(acp::StopReason::Refusal);
                                 _ = this.update(cx, |this, _| this.messages.truncate(message_ix));
+                                run_stop_hooks(TurnEnd::Stopped(acp::StopReason::Refusal), cx);
                             }
                             Ok(CompletionError::MaxTokens) => {
                                 event_stream.send_stop(acp::StopReason::MaxTokens);
+                                run_stop_hooks(TurnEnd::Stopped(acp::StopReason::MaxTokens), cx);
+                            }
+                            Ok(CompletionError::BudgetExceeded) => {
+                                event_stream.send_stop(acp::StopReason::MaxTurnRequests);
+                                run_stop_hooks(
+                                    TurnEnd::Stopped(acp::StopReason::MaxTurnRequests),
+                                    cx,
+                                );
                             }
                             Ok(CompletionError::Other(error)) | Err(error) => {
+                                run_stop_hooks(TurnEnd::Failed(format!("{error:#}")), cx);
                                 event_stream.send_error(error);
                             }
                         }
                     }
                 }
 
-                _ = this.update(cx, |this, _| this.running_turn.take());
+                _ = this.update(cx, |this, _| {
+                    this.stop_budget_timer();
+                    this.running_turn.take()
+                });
             }
         });
         self.running_turn = Some(RunningTurn::new(event_stream, tools, cancellation_tx, task));
+        self.start_budget_timer(cx);
         Ok(events_rx)
     }
 
@@ -2743,6 +3033,15 @@ impl Thread {
         // Set when a refusal fallback occurs so subsequent iterations use the fallback model.
         let mut refusal_fallback_model: Option<Arc<dyn LanguageModel>> = None;
         loop {
+            let exceeded_limit = this.read_with(cx, |this, cx| {
+                this.budget_status(cx)
+                    .and_then(|status| status.exceeded().copied())
+            })?;
+            if let Some(limit) = exceeded_limit {
+                log::info!("Stopping turn: the thread used {}", limit.describe());
+                return Err(CompletionError::BudgetExceeded.into());
+            }
+
             match Self::perform_compaction_if_needed(
                 this,
                 event_stream,
@@ -3198,8 +3497,8 @@ impl Thread {
                     event_stream.send_context_compaction_update(compaction_id.clone(), &text);
                 }
                 LanguageModelCompletionEvent::UsageUpdate(usage) => {
-                    this.update(cx, |this, _cx| {
-                        this.accumulate_token_usage(usage);
+                    this.update(cx, |this, cx| {
+                        this.accumulate_token_usage(usage, Some(&model), cx);
                     })?;
                 }
                 LanguageModelCompletionEvent::Stop(_)
@@ -3579,7 +3878,7 @@ impl Thread {
     }
 
     fn run_tool(
-        &self,
+        &mut self,
         tool: Arc<dyn AnyAgentTool>,
         tool_input: ToolInput<serde_json::Value>,
         tool_use_id: LanguageModelToolUseId,
@@ -3612,6 +3911,40 @@ impl Thread {
             ));
         }
 
+        if let Some(max_tool_calls) = self.budget(cx).max_tool_calls
+            && self.budget_spend(cx).tool_calls >= max_tool_calls
+        {
+            let content = format!(
+                "This thread reached its budget of {max_tool_calls} tool calls, so the tool wasn't run."
+            );
+            return Task::ready((
+                owning_message_ix,
+                LanguageModelToolResult {
+                    tool_use_id,
+                    tool_name,
+                    is_error: true,
+                    content: vec![LanguageModelToolResultContent::Text(Arc::from(content))],
+                    output: None,
+                },
+            ));
+        }
+        self.budget_usage.tool_calls += 1;
+
+        let hooks = ToolCallHooks::new(
+            &tool_name,
+            &tool_use_id,
+            self.id().clone(),
+            self.project.clone(),
+            cx,
+        );
+        let (tool_input, hooked_input) = match &hooks {
+            Some(hooks) => {
+                let (tool_input, hooked_input) = hooks.intercept_input(tool_input, cx);
+                (tool_input, Some(hooked_input))
+            }
+            None => (tool_input, None),
+        };
+
         let fs = self.project.read(cx).fs().clone();
         let tool_call_id = scoped_tool_call_id(owning_message_ix, &tool_use_id);
         let tool_event_stream = ToolCallEventStream::new(
@@ -3628,7 +3961,7 @@ impl Thread {
         );
         let supports_images = self.model().is_some_and(|model| model.supports_images());
         let tool_result = tool.run(tool_input, tool_event_stream, cx);
-        cx.foreground_executor().spawn(async move {
+        cx.spawn(async move |_, cx| {
             let (is_error, output) = match tool_result.await {
                 Ok(mut output) => {
                     let contains_image = output
@@ -3672,16 +4005,17 @@ impl Thread {
                 Err(output) => (true, output),
             };
 
-            (
-                owning_message_ix,
-                LanguageModelToolResult {
-                    tool_use_id,
-                    tool_name,
-                    is_error,
-                    content: output.llm_output,
-                    output: Some(output.raw_output),
-                },
-            )
+            let mut result = LanguageModelToolResult {
+                tool_use_id,
+                tool_name,
+                is_error,
+                content: output.llm_output,
+                output: Some(output.raw_output),
+            };
+            if let Some((hooks, hooked_input)) = hooks.zip(hooked_input) {
+                hooks.finish(hooked_input, &mut result, cx).await;
+            }
+            (owning_message_ix, result)
         })
     }
 
@@ -4238,7 +4572,12 @@ impl Thread {
         self.tools.contains_key(name)
     }
 
-    pub(crate) fn register_running_subagent(&mut self, subagent: WeakEntity<Thread>) {
+    pub(crate) fn register_running_subagent(&mut self, subagent: WeakEntity<Thread>, cx: &App) {
+        if let Some(thread) = subagent.upgrade() {
+            self.subagent_spend_baselines
+                .entry(thread.entity_id())
+                .or_insert_with(|| thread.read(cx).budget_spend(cx));
+        }
         self.running_subagents.push(subagent);
     }
 
@@ -4247,10 +4586,18 @@ impl Thread {
         subagent_session_id: &acp::SessionId,
         cx: &App,
     ) {
-        self.running_subagents.retain(|s| {
-            s.upgrade()
-                .map_or(false, |s| s.read(cx).id() != subagent_session_id)
+        let mut finished = Vec::new();
+        self.running_subagents.retain(|s| match s.upgrade() {
+            Some(s) if s.read(cx).id() == subagent_session_id => {
+                finished.push(s);
+                false
+            }
+            Some(_) => true,
+            None => false,
         });
+        for subagent in finished {
+            self.add_subagent_spend(&subagent, cx);
+        }
     }
 
     #[cfg(any(test, feature = "test-support"))]
@@ -4806,10 +5153,10 @@ impl RunningTurn {
         }
     }
 
-    fn cancel(mut self) -> Task<()> {
-        log::debug!("Cancelling in progress turn");
+    fn stop(mut self, stop_reason: acp::StopReason) -> Task<()> {
+        log::debug!("Stopping in progress turn: {stop_reason:?}");
         self.cancellation_tx.send(true).ok();
-        self.event_stream.send_canceled();
+        self.event_stream.send_stop(stop_reason);
         self._task
     }
 }
@@ -5454,12 +5801,6 @@ impl ThreadEventStream {
         self.0.unbounded_send(Ok(ThreadEvent::Stop(reason))).ok();
     }
 
-    fn send_canceled(&self) {
-        self.0
-            .unbounded_send(Ok(ThreadEvent::Stop(acp::StopReason::Cancelled)))
-            .ok();
-    }
-
     fn send_error(&self, error: impl Into<anyhow::Error>) {
         self.0.unbounded_send(Err(error.into())).ok();
     }
@@ -5730,6 +6071,62 @@ impl ToolCallEventStream {
         self.run_authorization_loop(title, options, None, Some(check_settings), cx)
     }
 
+    /// Authorize a context server's `sampling/createMessage` request, which
+    /// runs a completion with the thread's model on the server's behalf.
+    ///
+    /// Gated by the `mcp_sampling:<server_id>` tool permission, so "Always for
+    /// <server_id> MCP server" sets `tools.mcp_sampling:<server_id>.default`.
+    pub fn authorize_mcp_sampling(
+        &self,
+        title: impl Into<String>,
+        server_id: &str,
+        cx: &mut App,
+    ) -> Task<Result<()>> {
+        let title = title.into();
+        let permission_id = crate::mcp_sampling_id(server_id);
+        let options = acp_thread::PermissionOptions::Dropdown(vec![
+            acp_thread::PermissionOptionChoice {
+                allow: acp::PermissionOption::new(
+                    acp::PermissionOptionId::new(format!("always_allow_mcp:{permission_id}")),
+                    format!("Always for {server_id} MCP server"),
+                    acp::PermissionOptionKind::AllowAlways,
+                ),
+                deny: acp::PermissionOption::new(
+                    acp::PermissionOptionId::new(format!("always_deny_mcp:{permission_id}")),
+                    format!("Always for {server_id} MCP server"),
+                    acp::PermissionOptionKind::RejectAlways,
+                ),
+                sub_patterns: vec![],
+            },
+            acp_thread::PermissionOptionChoice {
+                allow: acp::PermissionOption::new(
+                    acp::PermissionOptionId::new("allow"),
+                    "Only this time",
+                    acp::PermissionOptionKind::AllowOnce,
+                ),
+                deny: acp::PermissionOption::new(
+                    acp::PermissionOptionId::new("deny"),
+                    "Only this time",
+                    acp::PermissionOptionKind::RejectOnce,
+                ),
+                sub_patterns: vec![],
+            },
+        ]);
+
+        let check_settings: Box<dyn Fn(&App) -> ToolPermissionDecision> =
+            Box::new(move |cx: &App| {
+                let settings = agent_settings::AgentSettings::get_global(cx);
+                decide_permission_from_settings(&permission_id, &[String::new()], settings)
+            });
+
+        self.run_authorization_loop(title, options, None, Some(check_settings), cx)
+    }
+
+    /// The thread this tool call belongs to, if it is tied to a live thread.
+    pub fn thread(&self) -> Option<WeakEntity<Thread>> {
+        self.thread.clone()
+    }
+
     /// Gate a tool call on user permission, driven by the agent's
     /// tool-permission settings.
     ///
@@ -8065,8 +8462,8 @@ mod tests {
             let mut subagents = Vec::new();
             for _ in 0..count {
                 let subagent = cx.new(|cx| Thread::new_subagent(parent, cx));
-                parent.update(cx, |thread, _cx| {
-                    thread.register_running_subagent(subagent.downgrade());
+                parent.update(cx, |thread, cx| {
+                    thread.register_running_subagent(subagent.downgrade(), cx);
                 });
                 subagents.push(subagent);
             }
@@ -168,6 +168,7 @@ mod tests {
             ui_scroll_position: None,
             sandboxed_terminal_temp_dir: None,
             sandbox_grants: Default::default(),
+            budget_usage: Default::default(),
         }
     }
 
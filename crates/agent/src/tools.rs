@@ -1,9 +1,15 @@
+mod add_review_comment_tool;
 mod apply_code_action_tool;
 mod ask_user_tool;
 mod context_server_registry;
 mod copy_path_tool;
 mod create_directory_tool;
 mod create_thread_tool;
+mod debug_breakpoint_tool;
+mod debug_control_tool;
+mod debug_inspect_tool;
+mod debug_session;
+mod debug_start_tool;
 mod delete_path_tool;
 mod diagnostics_tool;
 mod edit_file_tool;
@@ -21,6 +27,7 @@ mod list_directory_tool;
 mod move_path_tool;
 mod read_file_tool;
 mod rename_tool;
+mod run_task_tool;
 mod skill_tool;
 mod spawn_agent_tool;
 mod symbol_locator;
@@ -70,12 +77,20 @@ where
     }
 }
 
+pub use add_review_comment_tool::*;
 pub use apply_code_action_tool::*;
 pub use ask_user_tool::*;
 pub use context_server_registry::*;
 pub use copy_path_tool::*;
 pub use create_directory_tool::*;
 pub use create_thread_tool::*;
+pub use debug_breakpoint_tool::*;
+pub use debug_control_tool::*;
+pub use debug_inspect_tool::*;
+pub use debug_session::{
+    AgentDebugSessions, DebugSessionReport, DebugSessionState, DebugStackFrame,
+};
+pub use debug_start_tool::*;
 pub use delete_path_tool::*;
 pub use diagnostics_tool::*;
 pub use edit_file_tool::*;
@@ -90,6 +105,7 @@ pub use list_directory_tool::*;
 pub use move_path_tool::*;
 pub use read_file_tool::*;
 pub use rename_tool::*;
+pub use run_task_tool::*;
 pub use skill_tool::*;
 pub use spawn_agent_tool::*;
 pub use symbol_locator::*;
@@ -195,11 +211,16 @@ macro_rules! tools {
 //    are dropped unless it is active. The agent-profile UI uses the same gate so
 //    it never offers a tool the agent can't actually use.
 tools! {
+    AddReviewCommentTool,
     ApplyCodeActionTool,
     AskUserTool,
     CopyPathTool,
     CreateDirectoryTool,
     CreateThreadTool,
+    DebugBreakpointTool,
+    DebugControlTool,
+    DebugInspectTool,
+    DebugStartTool,
     DeletePathTool,
     DiagnosticsTool,
     EditFileTool,
@@ -214,6 +235,7 @@ tools! {
     MovePathTool,
     ReadFileTool,
     RenameTool,
+    RunTaskTool,
     SkillTool,
     SpawnAgentTool,
     TerminalTool,
@@ -247,13 +269,18 @@ mod tests {
     use super::*;
 
     #[test]
-    fn fetch_and_terminal_are_forbidden_in_restricted_mode() {
+    fn fetch_and_process_spawning_tools_are_forbidden_in_restricted_mode() {
         assert!(!tool_allowed_in_restricted_mode(FetchTool::NAME));
         assert!(!tool_allowed_in_restricted_mode(TerminalTool::NAME));
+        assert!(!tool_allowed_in_restricted_mode(RunTaskTool::NAME));
+        assert!(!tool_allowed_in_restricted_mode(DebugStartTool::NAME));
 
         // Every other built-in tool, and unknown (e.g. MCP) tools, are allowed.
         for name in ALL_TOOL_NAMES {
-            let expected = *name != FetchTool::NAME && *name != TerminalTool::NAME;
+            let expected = *name != FetchTool::NAME
+                && *name != TerminalTool::NAME
+                && *name != RunTaskTool::NAME
+                && *name != DebugStartTool::NAME;
             assert_eq!(
                 tool_allowed_in_restricted_mode(name),
                 expected,
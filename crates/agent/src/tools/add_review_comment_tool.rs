@@ -0,0 +1,220 @@
+use std::sync::Arc;
+
+use crate::{AgentTool, ToolCallEventStream, ToolInput};
+use action_log::ActionLog;
+use agent_client_protocol::schema::v1 as acp;
+use gpui::{App, Entity, SharedString, Task};
+use language::Point;
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use util::markdown::MarkdownInlineCode;
+
+/// Leaves a review comment on a range of lines in a file, instead of editing it.
+///
+/// Use this tool when you are asked to review changes. Each comment is shown to the user inline, next to the lines it is about, where they can apply your suggestion, ask you a follow-up question, or dismiss it.
+///
+/// <guidelines>
+/// - Anchor each comment to the smallest range of changed lines it is about, using line numbers in the new version of the file in the diff you are reviewing.
+/// - Leave one comment per issue. Don't leave comments that only praise or describe the change.
+/// - When the fix is a small, local replacement of the commented lines, include it as `suggestion`. Otherwise, describe the fix in the comment.
+/// </guidelines>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AddReviewCommentToolInput {
+    /// The path of the file to comment on.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The first line the comment is about (1-based).
+    pub start_line: u32,
+    /// The last line the comment is about (1-based, inclusive).
+    pub end_line: u32,
+    /// The review comment.
+    pub comment: String,
+    /// The text to replace the lines from `start_line` to `end_line` with, if
+    /// you are suggesting a concrete edit. Omit the trailing newline.
+    #[serde(default)]
+    pub suggestion: Option<String>,
+}
+
+pub struct AddReviewCommentTool {
+    project: Entity<Project>,
+    action_log: Entity<ActionLog>,
+}
+
+impl AddReviewCommentTool {
+    pub fn new(project: Entity<Project>, action_log: Entity<ActionLog>) -> Self {
+        Self {
+            project,
+            action_log,
+        }
+    }
+}
+
+impl AgentTool for AddReviewCommentTool {
+    type Input = AddReviewCommentToolInput;
+    type Output = String;
+
+    const NAME: &'static str = "add_review_comment";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Other
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        if let Ok(input) = input {
+            let location = if input.start_line == input.end_line {
+                format!("{}:{}", input.path, input.start_line)
+            } else {
+                format!("{}:{}-{}", input.path, input.start_line, input.end_line)
+            };
+            format!("Comment on {}", MarkdownInlineCode(&location)).into()
+        } else {
+            "Add review comment".into()
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        _event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<String, String>> {
+        let project = self.project.clone();
+        let action_log = self.action_log.clone();
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| format!("Failed to receive tool input: {e}"))?;
+
+            let open_buffer_task = project.update(cx, |project, cx| {
+                let Some(project_path) = project.find_project_path(&input.path, cx) else {
+                    return Err(format!("Could not find path {} in project", input.path));
+                };
+                Ok(project.open_buffer(project_path, cx))
+            })?;
+            let buffer = open_buffer_task.await.map_err(|e| e.to_string())?;
+
+            let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+            let line_count = snapshot.max_point().row + 1;
+            if input.start_line == 0
+                || input.start_line > input.end_line
+                || input.end_line > line_count
+            {
+                return Err(format!(
+                    "Invalid line range {}-{}: {} has {line_count} lines",
+                    input.start_line, input.end_line, input.path
+                ));
+            }
+            let end_row = input.end_line - 1;
+            let range = snapshot.anchor_before(Point::new(input.start_line - 1, 0))
+                ..snapshot.anchor_after(Point::new(end_row, snapshot.line_len(end_row)));
+
+            action_log.update(cx, |action_log, cx| {
+                action_log.add_review_comment(
+                    buffer,
+                    range,
+                    input.comment.into(),
+                    input.suggestion,
+                    cx,
+                )
+            });
+
+            Ok(format!(
+                "Added a review comment on {} lines {}-{}",
+                input.path, input.start_line, input.end_line
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_add_review_comment(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({"src": {"main.rs": "fn main() {\n    println!(\"hi\");\n}\n"}}),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let tool = Arc::new(AddReviewCommentTool::new(project, action_log.clone()));
+
+        let run = |input: AddReviewCommentToolInput, cx: &mut TestAppContext| {
+            let (event_stream, _event_rx) = ToolCallEventStream::test();
+            cx.update(|cx| {
+                tool.clone()
+                    .run(ToolInput::resolved(input), event_stream, cx)
+            })
+        };
+
+        run(
+            AddReviewCommentToolInput {
+                path: "root/src/main.rs".into(),
+                start_line: 2,
+                end_line: 2,
+                comment: "Say hello".into(),
+                suggestion: Some("    println!(\"hello\");".into()),
+            },
+            cx,
+        )
+        .await
+        .unwrap();
+
+        let error = run(
+            AddReviewCommentToolInput {
+                path: "root/src/main.rs".into(),
+                start_line: 3,
+                end_line: 9,
+                comment: "Out of range".into(),
+                suggestion: None,
+            },
+            cx,
+        )
+        .await
+        .unwrap_err();
+        assert!(error.contains("has 4 lines"), "unexpected error: {error}");
+
+        cx.read(|cx| {
+            let comments = action_log.read(cx).review_comments();
+            assert_eq!(comments.len(), 1);
+            let comment = &comments[0];
+            let buffer = comment.buffer.read(cx);
+            assert_eq!(
+                buffer
+                    .text_for_range(comment.range.clone())
+                    .collect::<String>(),
+                "    println!(\"hi\");"
+            );
+            assert_eq!(comment.comment.as_ref(), "Say hello");
+            assert_eq!(
+                comment.suggestion.as_deref(),
+                Some("    println!(\"hello\");")
+            );
+        });
+    }
+}
@@ -1,11 +1,24 @@
 use crate::{AgentToolOutput, AnyAgentTool, ToolCallEventStream, ToolInput};
 use agent_client_protocol::schema::v1 as acp;
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use collections::{BTreeMap, HashMap};
-use context_server::{ContextServerId, client::NotificationSubscription};
-use futures::FutureExt as _;
+use context_server::{
+    ContextServerId,
+    client::NotificationSubscription,
+    protocol::InitializedContextServerProtocol,
+    types::{CreateMessageRequest, CreateMessageResult},
+};
+use futures::{
+    FutureExt as _, Stream, StreamExt as _,
+    channel::{mpsc, oneshot},
+};
 use gpui::{App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Task};
-use language_model::{LanguageModelImage, LanguageModelImageExt, LanguageModelToolResultContent};
+use language_model::{
+    LanguageModelCompletionError, LanguageModelCompletionEvent, LanguageModelImage,
+    LanguageModelImageExt, LanguageModelRequest, LanguageModelRequestMessage,
+    LanguageModelToolResultContent, MessageContent, Role, StopReason, TokenUsage,
+};
+use parking_lot::Mutex;
 use project::context_server_store::{ContextServerStatus, ContextServerStore};
 use std::sync::Arc;
 use util::{ResultExt, markdown::MarkdownEscaped};
@@ -21,6 +34,13 @@ pub fn mcp_tool_id(server_id: &str, tool_name: &str) -> String {
     format!("mcp:{}:{}", server_id, tool_name)
 }
 
+/// Generates the permission ID gating a context server's sampling requests.
+///
+/// The format is `mcp_sampling:<server_id>`, so it can be auto-allowed per server.
+pub fn mcp_sampling_id(server_id: &str) -> String {
+    format!("mcp_sampling:{}", server_id)
+}
+
 pub struct ContextServerPrompt {
     pub server_id: ContextServerId,
     pub prompt: context_server::types::Prompt,
@@ -44,6 +64,7 @@ struct RegisteredContextServer {
     prompts: BTreeMap<SharedString, ContextServerPrompt>,
     load_tools: Task<Result<()>>,
     load_prompts: Task<Result<()>>,
+    sampling: SamplingRouter,
     _tools_updated_subscription: Option<NotificationSubscription>,
 }
 
@@ -126,6 +147,15 @@ impl ContextServerRegistry {
         server_store: &Entity<ContextServerStore>,
         cx: &mut Context<Self>,
     ) -> RegisteredContextServer {
+        let sampling = SamplingRouter::default();
+        if let Some(client) = server_store
+            .read(cx)
+            .get_running_server(server_id)
+            .and_then(|server| server.client())
+        {
+            sampling.handle_requests(server_id, &client);
+        }
+
         let tools_updated_subscription = server_store
             .read(cx)
             .get_running_server(server_id)
@@ -163,6 +193,7 @@ impl ContextServerRegistry {
             prompts: BTreeMap::default(),
             load_tools: Task::ready(Ok(())),
             load_prompts: Task::ready(Ok(())),
+            sampling,
             _tools_updated_subscription: tools_updated_subscription,
         }
     }
@@ -197,6 +228,7 @@ impl ContextServerRegistry {
                             this.server_store.clone(),
                             server.id(),
                             tool,
+                            registered_server.sampling.clone(),
                         ));
                         registered_server.tools.insert(tool.name(), tool);
                     }
@@ -285,6 +317,7 @@ struct ContextServerTool {
     store: Entity<ContextServerStore>,
     server_id: ContextServerId,
     tool: context_server::types::Tool,
+    sampling: SamplingRouter,
 }
 
 impl ContextServerTool {
@@ -292,11 +325,13 @@ impl ContextServerTool {
         store: Entity<ContextServerStore>,
         server_id: ContextServerId,
         tool: context_server::types::Tool,
+        sampling: SamplingRouter,
     ) -> Self {
         Self {
             store,
             server_id,
             tool,
+            sampling,
         }
     }
 }
@@ -344,6 +379,8 @@ impl AnyAgentTool for ContextServerTool {
         let Some(server) = self.store.read(cx).get_running_server(&self.server_id) else {
             return Task::ready(Err(anyhow::anyhow!("Context server not found").into()));
         };
+        let server_id = self.server_id.clone();
+        let sampling = self.sampling.clone();
         let tool_name = self.tool.name.clone();
         let tool_id = mcp_tool_id(&self.server_id.0, &self.tool.name);
         let display_name = self.tool.name.clone();
@@ -377,6 +414,10 @@ impl AnyAgentTool for ContextServerTool {
                 arguments
             );
 
+            // Servers may only sample while one of their tools is running, so the
+            // completion is attributed to (and approved within) this tool call.
+            let (_sampling_registration, mut sampling_rx) = sampling.register();
+
             let request = protocol.request::<context_server::types::requests::CallTool>(
                 context_server::types::CallToolParams {
                     name: tool_name,
@@ -384,13 +425,31 @@ impl AnyAgentTool for ContextServerTool {
                     meta: None,
                 },
             );
-
-            let response = futures::select! {
-                response = request.fuse() => response?,
-                _ = event_stream.cancelled_by_user().fuse() => {
-                    return Err(anyhow::anyhow!("MCP tool cancelled by user").into());
+            let mut request = std::pin::pin!(request.fuse());
+            let mut cancelled = std::pin::pin!(event_stream.cancelled_by_user().fuse());
+            let mut sampling_tasks = Vec::new();
+
+            let response = loop {
+                futures::select! {
+                    response = request => break response?,
+                    sampling_request = sampling_rx.next() => {
+                        let Some((params, response_tx)) = sampling_request else {
+                            continue;
+                        };
+                        let server_id = server_id.clone();
+                        let event_stream = event_stream.clone();
+                        sampling_tasks.push(cx.spawn(async move |cx| {
+                            let result =
+                                run_sampling_request(server_id, params, event_stream, cx).await;
+                            response_tx.send(result).ok();
+                        }));
+                    }
+                    _ = cancelled => {
+                        return Err(anyhow::anyhow!("MCP tool cancelled by user").into());
+                    }
                 }
             };
+            drop(sampling_tasks);
 
             if response.is_error == Some(true) {
                 let error_message: String =
@@ -543,6 +602,259 @@ pub fn get_prompt(
     })
 }
 
+type SamplingRequest = (
+    CreateMessageRequest,
+    oneshot::Sender<Result<CreateMessageResult>>,
+);
+
+/// Routes a server's `sampling/createMessage` requests to the tool calls that
+/// are running on it.
+///
+/// A client holds a single handler per method, so one handler is registered
+/// per server when it starts, and each tool call registers itself here for as
+/// long as it runs. When several of a server's tools are running at once, a
+/// request goes to the one that started first.
+#[derive(Clone, Default)]
+struct SamplingRouter {
+    state: Arc<Mutex<SamplingRouterState>>,
+}
+
+#[derive(Default)]
+struct SamplingRouterState {
+    next_id: usize,
+    tool_calls: Vec<(usize, mpsc::UnboundedSender<SamplingRequest>)>,
+}
+
+/// Keeps a tool call registered with its server's [`SamplingRouter`] until dropped.
+struct SamplingRegistration {
+    state: Arc<Mutex<SamplingRouterState>>,
+    id: usize,
+}
+
+impl Drop for SamplingRegistration {
+    fn drop(&mut self) {
+        self.state
+            .lock()
+            .tool_calls
+            .retain(|(id, _)| *id != self.id);
+    }
+}
+
+impl SamplingRouter {
+    fn handle_requests(
+        &self,
+        server_id: &ContextServerId,
+        client: &InitializedContextServerProtocol,
+    ) {
+        let router = self.clone();
+        let server_id = server_id.clone();
+        client.on_request::<context_server::types::requests::CreateMessage>(Box::new(
+            move |params, cx| {
+                let (response_tx, response_rx) = oneshot::channel();
+                if router.route((params, response_tx)).is_err() {
+                    return Task::ready(Err(anyhow!(
+                        "Zed only serves sampling requests from {server_id} while one of its \
+                        tools is running in the agent panel"
+                    )));
+                }
+                cx.background_spawn(async move { response_rx.await? })
+            },
+        ));
+    }
+
+    fn register(
+        &self,
+    ) -> (
+        SamplingRegistration,
+        mpsc::UnboundedReceiver<SamplingRequest>,
+    ) {
+        let (tx, rx) = mpsc::unbounded();
+        let mut state = self.state.lock();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.tool_calls.push((id, tx));
+        let registration = SamplingRegistration {
+            state: self.state.clone(),
+            id,
+        };
+        (registration, rx)
+    }
+
+    /// Sends the request to the oldest running tool call, handing it back if
+    /// none is running.
+    fn route(&self, mut request: SamplingRequest) -> Result<(), SamplingRequest> {
+        let mut state = self.state.lock();
+        while let Some((_, tx)) = state.tool_calls.first() {
+            match tx.unbounded_send(request) {
+                Ok(()) => return Ok(()),
+                Err(error) => {
+                    request = error.into_inner();
+                    state.tool_calls.remove(0);
+                }
+            }
+        }
+        Err(request)
+    }
+}
+
+/// Services a context server's `sampling/createMessage` request by running a
+/// completion with the thread's model, once the user has approved it.
+async fn run_sampling_request(
+    server_id: ContextServerId,
+    params: CreateMessageRequest,
+    event_stream: ToolCallEventStream,
+    cx: &mut AsyncApp,
+) -> Result<CreateMessageResult> {
+    let thread = event_stream
+        .thread()
+        .ok_or_else(|| anyhow!("sampling requires an active thread"))?;
+    let model = thread
+        .read_with(cx, |thread, _| thread.model().cloned())?
+        .ok_or_else(|| anyhow!("no language model is configured"))?;
+
+    let title = format!(
+        "MCP server `{}` wants to use {}",
+        server_id.0,
+        model.name().0
+    );
+    cx.update(|cx| event_stream.authorize_mcp_sampling(title, &server_id.0, cx))
+        .await?;
+
+    let max_tokens = params.max_tokens;
+    let stop_sequences = params.stop_sequences.clone().unwrap_or_default();
+    let request = build_sampling_request(params)?;
+    let events = model.stream_completion(request, cx).await?;
+    let completion = collect_sampled_completion(events, max_tokens, &stop_sequences).await?;
+
+    thread
+        .update(cx, |thread, cx| {
+            thread.add_mcp_sampling_token_usage(completion.usage, cx)
+        })
+        .log_err();
+
+    Ok(CreateMessageResult {
+        role: context_server::types::Role::Assistant,
+        content: context_server::types::MessageContent::Text {
+            text: completion.text,
+            annotations: None,
+        },
+        model: model.id().0.to_string(),
+        stop_reason: completion.stop_reason.map(str::to_string),
+    })
+}
+
+/// Rough size of a token, used to enforce `maxTokens` before the provider
+/// reports how many tokens it has generated.
+const BYTES_PER_TOKEN_ESTIMATE: usize = 4;
+
+#[derive(Debug)]
+struct SampledCompletion {
+    text: String,
+    stop_reason: Option<&'static str>,
+    usage: TokenUsage,
+}
+
+/// Collects a sampling completion's text. Language model requests carry no
+/// output limit, so `maxTokens` is enforced here by ending the stream early,
+/// and stop sequences are applied here too in case the provider ignores them.
+async fn collect_sampled_completion(
+    mut events: impl Stream<Item = Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>
+    + Unpin,
+    max_tokens: u32,
+    stop_sequences: &[String],
+) -> Result<SampledCompletion> {
+    let max_tokens = max_tokens as u64;
+    let max_len = max_tokens as usize * BYTES_PER_TOKEN_ESTIMATE;
+    let mut completion = SampledCompletion {
+        text: String::new(),
+        stop_reason: None,
+        usage: TokenUsage::default(),
+    };
+    while let Some(event) = events.next().await {
+        match event? {
+            LanguageModelCompletionEvent::Text(chunk) => {
+                completion.text.push_str(&chunk);
+                let stop_ix = stop_sequences
+                    .iter()
+                    .filter(|sequence| !sequence.is_empty())
+                    .filter_map(|sequence| completion.text.find(sequence.as_str()))
+                    .min();
+                if let Some(stop_ix) = stop_ix {
+                    completion.text.truncate(stop_ix);
+                    completion.stop_reason = Some("stopSequence");
+                    break;
+                }
+                if completion.text.len() > max_len {
+                    let end = completion.text.floor_char_boundary(max_len);
+                    completion.text.truncate(end);
+                    completion.stop_reason = Some("maxTokens");
+                    break;
+                }
+            }
+            LanguageModelCompletionEvent::UsageUpdate(usage) => {
+                completion.usage = usage;
+                if usage.output_tokens >= max_tokens {
+                    completion.stop_reason = Some("maxTokens");
+                    break;
+                }
+            }
+            LanguageModelCompletionEvent::Stop(reason) => {
+                completion.stop_reason = Some(match reason {
+                    StopReason::EndTurn | StopReason::ToolUse => "endTurn",
+                    StopReason::MaxTokens => "maxTokens",
+                    StopReason::Refusal => "refusal",
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok(completion)
+}
+
+fn build_sampling_request(params: CreateMessageRequest) -> Result<LanguageModelRequest> {
+    let mut messages = Vec::with_capacity(params.messages.len() + 1);
+    if let Some(system_prompt) = params.system_prompt {
+        messages.push(LanguageModelRequestMessage {
+            role: Role::System,
+            content: vec![MessageContent::Text(system_prompt)],
+            cache: false,
+            reasoning_details: None,
+        });
+    }
+    for message in params.messages {
+        let content = match message.content {
+            context_server::types::MessageContent::Text { text, .. } => MessageContent::Text(text),
+            context_server::types::MessageContent::Image {
+                data, mime_type, ..
+            } => match LanguageModelImage::from_base64_image(&data, &mime_type)? {
+                Some(image) => MessageContent::Image(image),
+                None => {
+                    return Err(anyhow!(
+                        "sampling images of type `{mime_type}` are not supported"
+                    ));
+                }
+            },
+            _ => return Err(anyhow!("sampling only supports text and image content")),
+        };
+        messages.push(LanguageModelRequestMessage {
+            role: match message.role {
+                context_server::types::Role::User => Role::User,
+                context_server::types::Role::Assistant => Role::Assistant,
+            },
+            content: vec![content],
+            cache: false,
+            reasoning_details: None,
+        });
+    }
+
+    Ok(LanguageModelRequest {
+        messages,
+        stop: params.stop_sequences.unwrap_or_default(),
+        temperature: params.temperature.map(|temperature| temperature as f32),
+        ..Default::default()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -565,6 +877,141 @@ mod tests {
         assert_eq!(mcp_tool_id("my_server", "my_tool"), "mcp:my_server:my_tool");
     }
 
+    #[test]
+    fn test_build_sampling_request() {
+        let params: CreateMessageRequest = serde_json::from_value(serde_json::json!({
+            "messages": [
+                { "role": "user", "content": { "type": "text", "text": "Summarize this" } },
+                { "role": "assistant", "content": { "type": "text", "text": "Sure" } }
+            ],
+            "systemPrompt": "Be brief",
+            "temperature": 0.5,
+            "maxTokens": 100,
+            "stopSequences": ["END"]
+        }))
+        .unwrap();
+        let request = build_sampling_request(params).unwrap();
+        let roles = request
+            .messages
+            .iter()
+            .map(|message| message.role)
+            .collect::<Vec<_>>();
+        assert_eq!(roles, [Role::System, Role::User, Role::Assistant]);
+        assert_eq!(request.messages[1].string_contents(), "Summarize this");
+        assert_eq!(request.stop, ["END"]);
+        assert_eq!(request.temperature, Some(0.5));
+    }
+
+    fn sampling_request(text: &str) -> SamplingRequest {
+        let params = serde_json::from_value(serde_json::json!({
+            "messages": [{ "role": "user", "content": { "type": "text", "text": text } }],
+            "maxTokens": 100
+        }))
+        .unwrap();
+        (params, oneshot::channel().0)
+    }
+
+    fn sampled_text(request: SamplingRequest) -> String {
+        match &request.0.messages[0].content {
+            context_server::types::MessageContent::Text { text, .. } => text.clone(),
+            content => panic!("unexpected content: {content:?}"),
+        }
+    }
+
+    #[test]
+    fn test_sampling_router_routes_to_running_tool_calls() {
+        let router = SamplingRouter::default();
+        assert!(router.route(sampling_request("nobody")).is_err());
+
+        let (first, mut first_rx) = router.register();
+        let (_second, mut second_rx) = router.register();
+
+        // Concurrent tool calls don't replace each other; the oldest one is served.
+        router.route(sampling_request("one")).ok().unwrap();
+        assert_eq!(sampled_text(first_rx.try_recv().unwrap()), "one");
+        assert!(second_rx.try_recv().is_err());
+
+        // Once a tool call finishes, requests go to the next one still running.
+        drop(first);
+        router.route(sampling_request("two")).ok().unwrap();
+        assert_eq!(sampled_text(second_rx.try_recv().unwrap()), "two");
+
+        drop(second_rx);
+        assert!(router.route(sampling_request("three")).is_err());
+    }
+
+    fn text_events(
+        chunks: &[&str],
+    ) -> futures::stream::Iter<
+        std::vec::IntoIter<Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
+    > {
+        let events = chunks
+            .iter()
+            .map(|chunk| Ok(LanguageModelCompletionEvent::Text(chunk.to_string())))
+            .chain([Ok(LanguageModelCompletionEvent::Stop(StopReason::EndTurn))])
+            .collect::<Vec<_>>();
+        futures::stream::iter(events)
+    }
+
+    #[test]
+    fn test_sampled_completion_limits() {
+        futures::executor::block_on(async {
+            let completion =
+                collect_sampled_completion(text_events(&["Hello", " world"]), 100, &[])
+                    .await
+                    .unwrap();
+            assert_eq!(completion.text, "Hello world");
+            assert_eq!(completion.stop_reason, Some("endTurn"));
+
+            // Stop sequences are applied even when they span chunks.
+            let completion = collect_sampled_completion(
+                text_events(&["Hello EN", "D world"]),
+                100,
+                &["END".to_string()],
+            )
+            .await
+            .unwrap();
+            assert_eq!(completion.text, "Hello ");
+            assert_eq!(completion.stop_reason, Some("stopSequence"));
+
+            // Output is cut off at `maxTokens`, estimated from the text...
+            let completion = collect_sampled_completion(text_events(&["abcdefghij"]), 2, &[])
+                .await
+                .unwrap();
+            assert_eq!(completion.text, "abcdefgh");
+            assert_eq!(completion.stop_reason, Some("maxTokens"));
+
+            // ...or taken from the usage the provider reports.
+            let events = futures::stream::iter([
+                Ok::<_, LanguageModelCompletionError>(LanguageModelCompletionEvent::Text(
+                    "abc".into(),
+                )),
+                Ok(LanguageModelCompletionEvent::UsageUpdate(TokenUsage {
+                    output_tokens: 2,
+                    ..Default::default()
+                })),
+                Ok(LanguageModelCompletionEvent::Text("def".into())),
+            ]);
+            let completion = collect_sampled_completion(events, 2, &[]).await.unwrap();
+            assert_eq!(completion.text, "abc");
+            assert_eq!(completion.stop_reason, Some("maxTokens"));
+            assert_eq!(completion.usage.output_tokens, 2);
+        });
+    }
+
+    #[test]
+    fn test_build_sampling_request_rejects_audio() {
+        let params: CreateMessageRequest = serde_json::from_value(serde_json::json!({
+            "messages": [{
+                "role": "user",
+                "content": { "type": "audio", "data": "", "mimeType": "audio/wav" }
+            }],
+            "maxTokens": 100
+        }))
+        .unwrap();
+        assert!(build_sampling_request(params).is_err());
+    }
+
     // Note: Tests for MCP tool ID collision with built-in tools and permission
     // decisions are in crates/agent/src/tool_permissions.rs to avoid duplication.
 
@@ -0,0 +1,273 @@
+use agent_client_protocol::schema::v1 as acp;
+use anyhow::Result;
+use gpui::{App, Entity, SharedString, Task};
+use language::Point;
+use language_model::LanguageModelToolResultContent;
+use project::Project;
+use project::debugger::breakpoint_store::{
+    Breakpoint, BreakpointEditAction, BreakpointState, BreakpointStore, BreakpointWithPosition,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::markdown::MarkdownInlineCode;
+
+use super::debug_session::display_path;
+use crate::{AgentTool, ToolCallEventStream, ToolInput};
+
+/// Set, clear, or list the project's breakpoints.
+///
+/// Breakpoints are shared with the editor and the debug panel: the ones you set
+/// show up in the gutter, and they apply to running debug sessions right away
+/// as well as to sessions started later with `debug_start`.
+///
+/// A breakpoint can have a `condition`, an expression in the debuggee's
+/// language that must be true for it to pause, or a `log_message`, which makes
+/// it print instead of pausing. Log messages can interpolate expressions in
+/// braces, e.g. `"len = {items.len()}"`. Conditions and log messages run as
+/// code in the debuggee, and breakpoints affect the user's own sessions too, so
+/// the user may be asked to approve setting or clearing one.
+///
+/// <example>
+/// To pause at line 42 of `src/main.rs` in the `app` root directory:
+/// {
+///     "action": "set",
+///     "path": "app/src/main.rs",
+///     "line": 42
+/// }
+/// </example>
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct DebugBreakpointToolInput {
+    pub action: DebugBreakpointAction,
+    /// The file to set or clear a breakpoint in. Required for `set` and
+    /// `clear`.
+    ///
+    /// This path should never be absolute, and the first component of the path
+    /// should always be a root directory in a project.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// The 1-based line to set or clear a breakpoint on. Required for `set` and
+    /// `clear`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    /// Only pause when this expression is true. Only used by `set`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    /// Log this message instead of pausing. Only used by `set`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DebugBreakpointAction {
+    /// Set a breakpoint, replacing any existing one on the line.
+    Set,
+    /// Remove the breakpoint on the line.
+    Clear,
+    /// List all breakpoints.
+    List,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DebugBreakpointToolOutput {
+    Breakpoints { breakpoints: Vec<BreakpointInfo> },
+    Error { error: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakpointInfo {
+    pub path: String,
+    pub line: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_message: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+}
+
+impl From<DebugBreakpointToolOutput> for LanguageModelToolResultContent {
+    fn from(output: DebugBreakpointToolOutput) -> Self {
+        serde_json::to_string(&output)
+            .unwrap_or_else(|e| format!("Failed to serialize debug_breakpoint output: {e}"))
+            .into()
+    }
+}
+
+pub struct DebugBreakpointTool {
+    project: Entity<Project>,
+}
+
+impl DebugBreakpointTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for DebugBreakpointTool {
+    type Input = DebugBreakpointToolInput;
+    type Output = DebugBreakpointToolOutput;
+
+    const NAME: &'static str = "debug_breakpoint";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Edit
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        let Ok(input) = input else {
+            return "Edit breakpoints".into();
+        };
+        let location = match (&input.path, input.line) {
+            (Some(path), Some(line)) => format!("{path}:{line}"),
+            (Some(path), None) => path.clone(),
+            _ => String::new(),
+        };
+        match input.action {
+            DebugBreakpointAction::Set => {
+                format!("Set breakpoint at {}", MarkdownInlineCode(&location)).into()
+            }
+            DebugBreakpointAction::Clear => {
+                format!("Clear breakpoint at {}", MarkdownInlineCode(&location)).into()
+            }
+            DebugBreakpointAction::List => "List breakpoints".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output, Self::Output>> {
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| DebugBreakpointToolOutput::Error {
+                    error: format!("Failed to receive tool input: {e}"),
+                })?;
+            let error = |error: String| DebugBreakpointToolOutput::Error { error };
+
+            if input.action != DebugBreakpointAction::List {
+                let (Some(path), Some(line)) = (input.path.as_deref(), input.line) else {
+                    return Err(error(
+                        "`path` and `line` are required to set or clear a breakpoint".to_string(),
+                    ));
+                };
+                let row = line
+                    .checked_sub(1)
+                    .ok_or_else(|| error("`line` is 1-based and must be at least 1".to_string()))?;
+
+                let location = format!("{path}:{line}");
+                let (title, inputs) = match input.action {
+                    DebugBreakpointAction::Set => (
+                        format!("Set breakpoint at {}", MarkdownInlineCode(&location)),
+                        std::iter::once(location)
+                            .chain(input.condition.clone())
+                            .chain(input.log_message.clone())
+                            .collect(),
+                    ),
+                    _ => (
+                        format!("Clear breakpoint at {}", MarkdownInlineCode(&location)),
+                        vec![location],
+                    ),
+                };
+                let authorize = cx.update(|cx| {
+                    let context = crate::ToolPermissionContext::new(Self::NAME, inputs);
+                    event_stream.authorize(title, context, cx)
+                });
+                authorize.await.map_err(|e| error(e.to_string()))?;
+
+                let open_buffer = self.project.update(cx, |project, cx| {
+                    let project_path = project
+                        .find_project_path(path, cx)
+                        .ok_or_else(|| error(format!("Could not find path {path} in project")))?;
+                    Ok(project.open_buffer(project_path, cx))
+                })?;
+                let buffer = open_buffer.await.map_err(|e| error(e.to_string()))?;
+
+                cx.update(|cx| {
+                    let abs_path = BreakpointStore::abs_path_from_buffer(&buffer, cx)
+                        .ok_or_else(|| error(format!("{path} is not a file on disk")))?;
+                    let max_row = buffer.read(cx).max_point().row;
+                    if row > max_row {
+                        return Err(error(format!("{path} has only {} lines", max_row + 1)));
+                    }
+
+                    let breakpoint_store = self.project.read(cx).breakpoint_store();
+                    let existing = breakpoint_store
+                        .read(cx)
+                        .breakpoint_at_row(&abs_path, row, cx);
+                    if input.action == DebugBreakpointAction::Clear && existing.is_none() {
+                        return Err(error(format!("There is no breakpoint at {path}:{line}")));
+                    }
+
+                    let position = buffer.read(cx).anchor_before(Point::new(row, 0));
+                    breakpoint_store.update(cx, |store, cx| {
+                        if let Some((existing_buffer, existing)) = existing {
+                            store.toggle_breakpoint(
+                                existing_buffer,
+                                existing,
+                                BreakpointEditAction::Toggle,
+                                cx,
+                            );
+                        }
+                        if input.action == DebugBreakpointAction::Set {
+                            store.toggle_breakpoint(
+                                buffer.clone(),
+                                BreakpointWithPosition {
+                                    position,
+                                    bp: Breakpoint {
+                                        message: non_empty(input.log_message),
+                                        hit_condition: None,
+                                        condition: non_empty(input.condition),
+                                        state: BreakpointState::Enabled,
+                                    },
+                                },
+                                BreakpointEditAction::Toggle,
+                                cx,
+                            );
+                        }
+                    });
+                    Ok(())
+                })?;
+            }
+
+            let breakpoints = cx.update(|cx| {
+                let breakpoint_store = self.project.read(cx).breakpoint_store();
+                breakpoint_store
+                    .read(cx)
+                    .all_source_breakpoints(cx)
+                    .into_iter()
+                    .flat_map(|(path, breakpoints)| {
+                        let path = display_path(&path, &self.project, cx);
+                        breakpoints
+                            .into_iter()
+                            .map(move |breakpoint| BreakpointInfo {
+                                path: path.clone(),
+                                line: breakpoint.row + 1,
+                                condition: breakpoint.condition.map(|c| c.to_string()),
+                                log_message: breakpoint.message.map(|m| m.to_string()),
+                                disabled: breakpoint.state.is_disabled(),
+                            })
+                    })
+                    .collect()
+            });
+            Ok(DebugBreakpointToolOutput::Breakpoints { breakpoints })
+        })
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<Arc<str>> {
+    value
+        .filter(|value| !value.trim().is_empty())
+        .map(Arc::from)
+}
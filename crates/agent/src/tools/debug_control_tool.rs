@@ -0,0 +1,214 @@
+use agent_client_protocol::schema::v1 as acp;
+use anyhow::Result;
+use dap::debugger_settings::DebuggerSettings;
+use gpui::{App, Entity, SharedString, Task};
+use language_model::LanguageModelToolResultContent;
+use project::Project;
+use project::debugger::session::ThreadId;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::debug_session::{
+    AgentDebugSessions, DebugSessionReport, DebugSessionState, StopWatcher, resolve_session,
+    resolve_thread, session_report,
+};
+use crate::{AgentTool, ToolCallEventStream, ToolInput};
+
+const DEFAULT_WAIT_SECS: u64 = 30;
+const MAX_WAIT_SECS: u64 = 300;
+
+/// Resume, step, pause, or stop a debug session started with `debug_start`.
+///
+/// Resuming and stepping wait for the debuggee to stop again (at a breakpoint,
+/// after the step, or on an exception) or to exit, and report where it stopped.
+/// If it keeps running past `wait_seconds`, the result says so; call this tool
+/// again with `wait` to keep waiting without resuming it.
+///
+/// Commands apply to the most recent session started with `debug_start` and its
+/// paused thread unless
+/// `session_id` or `thread_id` are given. The debug panel follows along, so the
+/// user sees every step.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct DebugControlToolInput {
+    pub action: DebugControlAction,
+    /// The debug session to act on, as reported by `debug_start`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<u32>,
+    /// The thread to resume or step, as reported by `debug_inspect`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<i64>,
+    /// How long to wait for the debuggee to stop. Defaults to 30 seconds, at
+    /// most 300.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DebugControlAction {
+    /// Resume the thread until it hits a breakpoint or exits.
+    Continue,
+    /// Run to the next line, stepping over calls.
+    StepOver,
+    /// Step into the call on the current line.
+    StepIn,
+    /// Run until the current function returns.
+    StepOut,
+    /// Interrupt a running thread.
+    Pause,
+    /// Wait for a running debuggee to stop, without resuming it.
+    Wait,
+    /// End the debug session.
+    Stop,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DebugControlToolOutput {
+    Session(DebugSessionReport),
+    Error { error: String },
+}
+
+impl From<DebugControlToolOutput> for LanguageModelToolResultContent {
+    fn from(output: DebugControlToolOutput) -> Self {
+        serde_json::to_string(&output)
+            .unwrap_or_else(|e| format!("Failed to serialize debug_control output: {e}"))
+            .into()
+    }
+}
+
+pub struct DebugControlTool {
+    project: Entity<Project>,
+    sessions: AgentDebugSessions,
+}
+
+impl DebugControlTool {
+    pub fn new(project: Entity<Project>, sessions: AgentDebugSessions) -> Self {
+        Self { project, sessions }
+    }
+}
+
+impl AgentTool for DebugControlTool {
+    type Input = DebugControlToolInput;
+    type Output = DebugControlToolOutput;
+
+    const NAME: &'static str = "debug_control";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Execute
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        let Ok(input) = input else {
+            return "Control debugger".into();
+        };
+        match input.action {
+            DebugControlAction::Continue => "Continue".into(),
+            DebugControlAction::StepOver => "Step over".into(),
+            DebugControlAction::StepIn => "Step in".into(),
+            DebugControlAction::StepOut => "Step out".into(),
+            DebugControlAction::Pause => "Pause debuggee".into(),
+            DebugControlAction::Wait => "Wait for debuggee to stop".into(),
+            DebugControlAction::Stop => "Stop debugging".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output, Self::Output>> {
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| DebugControlToolOutput::Error {
+                    error: format!("Failed to receive tool input: {e}"),
+                })?;
+            let error = |error: String| DebugControlToolOutput::Error { error };
+
+            let session = cx
+                .update(|cx| resolve_session(&self.project, &self.sessions, input.session_id, cx))
+                .map_err(error)?;
+
+            if input.action == DebugControlAction::Stop {
+                session.update(cx, |session, cx| session.shutdown(cx)).await;
+                let report = session_report(
+                    &session,
+                    DebugSessionState::Terminated,
+                    None,
+                    &self.project,
+                    cx,
+                )
+                .await;
+                return Ok(DebugControlToolOutput::Session(report));
+            }
+
+            if input.action == DebugControlAction::Wait
+                && session.read_with(cx, |session, _| session.any_stopped_thread())
+            {
+                let report = session_report(
+                    &session,
+                    DebugSessionState::Stopped,
+                    input.thread_id.map(ThreadId),
+                    &self.project,
+                    cx,
+                )
+                .await;
+                return Ok(DebugControlToolOutput::Session(report));
+            }
+
+            let thread_id = match input.action {
+                DebugControlAction::Wait => None,
+                _ => Some(
+                    resolve_thread(&session, input.thread_id, cx)
+                        .await
+                        .map_err(error)?,
+                ),
+            };
+            let watcher = cx.update(|cx| StopWatcher::new(&session, cx));
+            if let Some(thread_id) = thread_id {
+                session.update(cx, |session, cx| {
+                    let granularity = DebuggerSettings::get_global(cx).stepping_granularity;
+                    match input.action {
+                        DebugControlAction::Continue => session.continue_thread(thread_id, cx),
+                        DebugControlAction::StepOver => {
+                            session.step_over(thread_id, granularity, cx)
+                        }
+                        DebugControlAction::StepIn => session.step_in(thread_id, granularity, cx),
+                        DebugControlAction::StepOut => session.step_out(thread_id, granularity, cx),
+                        DebugControlAction::Pause => session.pause_thread(thread_id, cx),
+                        DebugControlAction::Wait | DebugControlAction::Stop => {}
+                    }
+                });
+            }
+
+            let wait = Duration::from_secs(
+                input
+                    .wait_seconds
+                    .unwrap_or(DEFAULT_WAIT_SECS)
+                    .min(MAX_WAIT_SECS),
+            );
+            let (state, stopped_thread) =
+                watcher.wait(wait, &event_stream, cx).await.map_err(error)?;
+            let report = session_report(
+                &session,
+                state,
+                stopped_thread.or(thread_id),
+                &self.project,
+                cx,
+            )
+            .await;
+            Ok(DebugControlToolOutput::Session(report))
+        })
+    }
+}
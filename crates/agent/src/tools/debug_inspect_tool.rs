@@ -0,0 +1,366 @@
+use agent_client_protocol::schema::v1 as acp;
+use anyhow::Result;
+use dap::EvaluateArgumentsContext;
+use gpui::{App, AsyncApp, Entity, SharedString, Task};
+use language_model::LanguageModelToolResultContent;
+use project::Project;
+use project::debugger::session::{Session, ThreadId};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::markdown::MarkdownInlineCode;
+
+use super::debug_session::{AgentDebugSessions, DebugStackFrame, resolve_session, resolve_thread};
+use crate::{AgentTool, ToolCallEventStream, ToolInput};
+
+/// The maximum number of stack frames returned for a thread.
+const MAX_FRAMES: usize = 50;
+/// The maximum number of variables returned for one reference.
+const MAX_VARIABLES: usize = 200;
+/// The maximum number of characters of a single value returned to the model.
+const MAX_VALUE_LEN: usize = 1024;
+
+/// Read the state of a paused debuggee: its threads, stack frames, scopes, and
+/// variables, or the value of an expression.
+///
+/// Drill down step by step: `stack` lists a thread's frames, `scopes` lists a
+/// frame's scopes (locals, arguments, globals...), and `variables` expands a
+/// scope or a structured value by its `variables_reference`. `evaluate` runs an
+/// expression in the debuggee's language in the context of a frame; it shows up
+/// in the debug panel's console, and can have side effects like any code, so
+/// the user may be asked to approve it.
+///
+/// Inspection applies to the most recent session started with `debug_start`
+/// and its paused thread's top frame unless `session_id`, `thread_id`, or `frame_id` are given.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct DebugInspectToolInput {
+    pub action: DebugInspectAction,
+    /// The debug session to inspect, as reported by `debug_start`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<u32>,
+    /// The thread whose stack to read. Used by `stack`, and by `scopes` and
+    /// `evaluate` when `frame_id` is omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<i64>,
+    /// The stack frame to read scopes from or evaluate in, as listed by
+    /// `stack`. Defaults to the thread's top frame.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frame_id: Option<u64>,
+    /// The scope or value to expand, as listed by `scopes`, `variables`, or
+    /// `evaluate`. Required for `variables`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variables_reference: Option<u64>,
+    /// The expression to evaluate. Required for `evaluate`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expression: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DebugInspectAction {
+    /// List the debuggee's threads and whether each is paused.
+    Threads,
+    /// List a thread's stack frames, innermost first.
+    Stack,
+    /// List a stack frame's scopes.
+    Scopes,
+    /// List the variables in a scope, or the children of a structured value.
+    Variables,
+    /// Evaluate an expression in a stack frame.
+    Evaluate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DebugInspectToolOutput {
+    Threads {
+        threads: Vec<DebugThreadInfo>,
+    },
+    Stack {
+        thread_id: i64,
+        frames: Vec<DebugStackFrame>,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        truncated: bool,
+    },
+    Scopes {
+        frame_id: u64,
+        scopes: Vec<DebugScopeInfo>,
+    },
+    Variables {
+        variables: Vec<DebugVariableInfo>,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        truncated: bool,
+    },
+    Evaluated {
+        #[serde(flatten)]
+        value: DebugVariableInfo,
+    },
+    Error {
+        error: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugThreadInfo {
+    pub id: i64,
+    pub name: String,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugScopeInfo {
+    pub name: String,
+    pub variables_reference: u64,
+    /// Whether the adapter considers the scope expensive to expand.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub expensive: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugVariableInfo {
+    pub name: String,
+    pub value: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub type_: Option<String>,
+    /// Non-zero when the value has children that `variables` can expand.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub variables_reference: u64,
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+impl From<DebugInspectToolOutput> for LanguageModelToolResultContent {
+    fn from(output: DebugInspectToolOutput) -> Self {
+        serde_json::to_string(&output)
+            .unwrap_or_else(|e| format!("Failed to serialize debug_inspect output: {e}"))
+            .into()
+    }
+}
+
+pub struct DebugInspectTool {
+    project: Entity<Project>,
+    sessions: AgentDebugSessions,
+}
+
+impl DebugInspectTool {
+    pub fn new(project: Entity<Project>, sessions: AgentDebugSessions) -> Self {
+        Self { project, sessions }
+    }
+}
+
+impl AgentTool for DebugInspectTool {
+    type Input = DebugInspectToolInput;
+    type Output = DebugInspectToolOutput;
+
+    const NAME: &'static str = "debug_inspect";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Read
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        let Ok(input) = input else {
+            return "Inspect debuggee".into();
+        };
+        match input.action {
+            DebugInspectAction::Threads => "List threads".into(),
+            DebugInspectAction::Stack => "Read stack trace".into(),
+            DebugInspectAction::Scopes => "Read scopes".into(),
+            DebugInspectAction::Variables => "Read variables".into(),
+            DebugInspectAction::Evaluate => match &input.expression {
+                Some(expression) => format!("Evaluate {}", MarkdownInlineCode(expression)).into(),
+                None => "Evaluate expression".into(),
+            },
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output, Self::Output>> {
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| DebugInspectToolOutput::Error {
+                    error: format!("Failed to receive tool input: {e}"),
+                })?;
+            let error = |error: String| DebugInspectToolOutput::Error { error };
+
+            let session = cx
+                .update(|cx| resolve_session(&self.project, &self.sessions, input.session_id, cx))
+                .map_err(error)?;
+
+            match input.action {
+                DebugInspectAction::Threads => {
+                    let threads = session
+                        .read_with(cx, |session, _| session.fetch_threads())
+                        .await
+                        .map_err(|e| error(format!("Failed to list threads: {e}")))?;
+                    let threads = session.read_with(cx, |session, _| {
+                        threads
+                            .into_iter()
+                            .map(|thread| DebugThreadInfo {
+                                id: thread.id,
+                                status: session
+                                    .thread_status(ThreadId(thread.id))
+                                    .label()
+                                    .to_string(),
+                                name: thread.name,
+                            })
+                            .collect()
+                    });
+                    Ok(DebugInspectToolOutput::Threads { threads })
+                }
+                DebugInspectAction::Stack => {
+                    let thread_id = resolve_thread(&session, input.thread_id, cx)
+                        .await
+                        .map_err(error)?;
+                    let frames = stack_trace(&session, thread_id, cx).await.map_err(error)?;
+                    let truncated = frames.len() > MAX_FRAMES;
+                    let frames = cx.update(|cx| {
+                        frames
+                            .iter()
+                            .take(MAX_FRAMES)
+                            .map(|frame| DebugStackFrame::new(frame, &self.project, cx))
+                            .collect()
+                    });
+                    Ok(DebugInspectToolOutput::Stack {
+                        thread_id: thread_id.0,
+                        frames,
+                        truncated,
+                    })
+                }
+                DebugInspectAction::Scopes => {
+                    let frame_id = resolve_frame(&session, input.thread_id, input.frame_id, cx)
+                        .await
+                        .map_err(error)?;
+                    let scopes = session
+                        .read_with(cx, |session, _| session.fetch_scopes(frame_id))
+                        .await
+                        .map_err(|e| error(format!("Failed to read scopes: {e}")))?;
+                    Ok(DebugInspectToolOutput::Scopes {
+                        frame_id,
+                        scopes: scopes
+                            .into_iter()
+                            .map(|scope| DebugScopeInfo {
+                                name: scope.name,
+                                variables_reference: scope.variables_reference,
+                                expensive: scope.expensive,
+                            })
+                            .collect(),
+                    })
+                }
+                DebugInspectAction::Variables => {
+                    let variables_reference = input.variables_reference.ok_or_else(|| {
+                        error("`variables_reference` is required to read variables".to_string())
+                    })?;
+                    let variables = session
+                        .read_with(cx, |session, _| {
+                            session.fetch_variables(variables_reference)
+                        })
+                        .await
+                        .map_err(|e| error(format!("Failed to read variables: {e}")))?;
+                    let truncated = variables.len() > MAX_VARIABLES;
+                    Ok(DebugInspectToolOutput::Variables {
+                        variables: variables
+                            .into_iter()
+                            .take(MAX_VARIABLES)
+                            .map(|variable| DebugVariableInfo {
+                                name: variable.name,
+                                value: truncate_value(variable.value),
+                                type_: variable.type_,
+                                variables_reference: variable.variables_reference,
+                            })
+                            .collect(),
+                        truncated,
+                    })
+                }
+                DebugInspectAction::Evaluate => {
+                    let expression = input
+                        .expression
+                        .clone()
+                        .ok_or_else(|| error("`expression` is required to evaluate".to_string()))?;
+                    // Evaluating runs code in the debuggee, so it is subject to
+                    // the same approval as the other tools that run code.
+                    let authorize = cx.update(|cx| {
+                        let context =
+                            crate::ToolPermissionContext::new(Self::NAME, vec![expression.clone()]);
+                        event_stream.authorize(
+                            format!("Evaluate {}", MarkdownInlineCode(&expression)),
+                            context,
+                            cx,
+                        )
+                    });
+                    authorize.await.map_err(|e| error(e.to_string()))?;
+                    let frame_id = resolve_frame(&session, input.thread_id, input.frame_id, cx)
+                        .await
+                        .map_err(error)?;
+                    let response = session
+                        .update(cx, |session, cx| {
+                            session.evaluate_with_response(
+                                expression.clone(),
+                                Some(EvaluateArgumentsContext::Repl),
+                                Some(frame_id),
+                                None,
+                                cx,
+                            )
+                        })
+                        .await
+                        .map_err(|e| error(format!("Failed to evaluate {expression:?}: {e}")))?;
+                    Ok(DebugInspectToolOutput::Evaluated {
+                        value: DebugVariableInfo {
+                            name: expression,
+                            value: truncate_value(response.result),
+                            type_: response.type_,
+                            variables_reference: response.variables_reference,
+                        },
+                    })
+                }
+            }
+        })
+    }
+}
+
+async fn stack_trace(
+    session: &Entity<Session>,
+    thread_id: ThreadId,
+    cx: &mut AsyncApp,
+) -> Result<Vec<dap::StackFrame>, String> {
+    session
+        .read_with(cx, |session, _| session.fetch_stack_trace(thread_id))
+        .await
+        .map_err(|e| format!("Failed to read the stack of thread {}: {e}", thread_id.0))
+}
+
+/// Returns the given frame or, if none is given, the top frame of the given or
+/// paused thread.
+async fn resolve_frame(
+    session: &Entity<Session>,
+    thread_id: Option<i64>,
+    frame_id: Option<u64>,
+    cx: &mut AsyncApp,
+) -> Result<u64, String> {
+    if let Some(frame_id) = frame_id {
+        return Ok(frame_id);
+    }
+    let thread_id = resolve_thread(session, thread_id, cx).await?;
+    stack_trace(session, thread_id, cx)
+        .await?
+        .first()
+        .map(|frame| frame.id)
+        .ok_or_else(|| format!("Thread {} has no stack frames", thread_id.0))
+}
+
+fn truncate_value(value: String) -> String {
+    util::truncate_and_trailoff(&value, MAX_VALUE_LEN)
+}
@@ -0,0 +1,868 @@
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
+
+use collections::HashSet;
+use dap::client::SessionId;
+use futures::channel::mpsc;
+use futures::{FutureExt as _, StreamExt as _};
+use gpui::{App, AsyncApp, Entity, Subscription};
+use project::Project;
+use project::debugger::session::{
+    Session, SessionEvent, SessionStateEvent, ThreadId, ThreadStatus,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::ToolCallEventStream;
+
+/// The debug sessions a thread started with `debug_start`. The other debug
+/// tools only act on these (and the child sessions their adapters spawn), so
+/// the agent never drives a session the user started themselves.
+#[derive(Clone, Default)]
+pub struct AgentDebugSessions(Rc<RefCell<HashSet<SessionId>>>);
+
+impl AgentDebugSessions {
+    pub(crate) fn insert(&self, session_id: SessionId) {
+        self.0.borrow_mut().insert(session_id);
+    }
+
+    fn started(&self, session: &Session, cx: &App) -> bool {
+        let started = self.0.borrow();
+        let mut session = session;
+        loop {
+            if started.contains(&session.session_id()) {
+                return true;
+            }
+            match session.parent_session() {
+                Some(parent) => session = parent.read(cx),
+                None => return false,
+            }
+        }
+    }
+}
+
+/// Picks the debug session a tool call acts on: the one with the given ID or,
+/// if none is given, the most recently started live session, preferring one
+/// that is paused. Only sessions this thread started are considered.
+pub(crate) fn resolve_session(
+    project: &Entity<Project>,
+    sessions: &AgentDebugSessions,
+    session_id: Option<u32>,
+    cx: &App,
+) -> Result<Entity<Session>, String> {
+    let dap_store = project.read(cx).dap_store();
+    let dap_store = dap_store.read(cx);
+    if let Some(session_id) = session_id {
+        let session = dap_store
+            .session_by_id(SessionId(session_id))
+            .ok_or_else(|| format!("No debug session with ID {session_id}"))?;
+        if !sessions.started(session.read(cx), cx) {
+            return Err(format!(
+                "Debug session {session_id} wasn't started by `debug_start` in this thread"
+            ));
+        }
+        return Ok(session);
+    }
+
+    dap_store
+        .sessions()
+        .filter(|session| {
+            let session = session.read(cx);
+            !session.is_terminated() && sessions.started(session, cx)
+        })
+        .max_by_key(|session| {
+            let session = session.read(cx);
+            (session.any_stopped_thread(), session.session_id())
+        })
+        .cloned()
+        .ok_or_else(|| {
+            "No debug session started by this thread is running. Start one with the \
+             `debug_start` tool."
+                .to_string()
+        })
+}
+
+/// Picks the thread a tool call acts on: the one with the given ID or, if none
+/// is given, the first paused thread, falling back to the first thread.
+pub(crate) async fn resolve_thread(
+    session: &Entity<Session>,
+    thread_id: Option<i64>,
+    cx: &mut AsyncApp,
+) -> Result<ThreadId, String> {
+    if let Some(thread_id) = thread_id {
+        return Ok(ThreadId(thread_id));
+    }
+
+    let threads = session
+        .read_with(cx, |session, _| session.fetch_threads())
+        .await
+        .map_err(|e| format!("Failed to list threads: {e}"))?;
+    let stopped = session.read_with(cx, |session, _| {
+        threads
+            .iter()
+            .map(|thread| ThreadId(thread.id))
+            .find(|thread_id| session.thread_status(*thread_id) == ThreadStatus::Stopped)
+    });
+    stopped
+        .or_else(|| threads.first().map(|thread| ThreadId(thread.id)))
+        .ok_or_else(|| "The debuggee has no threads".to_string())
+}
+
+/// How a debug session settled after the agent resumed or started it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DebugSessionState {
+    Stopped,
+    Running,
+    Terminated,
+}
+
+/// Listens for a session to pause or end. Create it *before* issuing the
+/// request that resumes the debuggee so a quick stop isn't missed.
+pub(crate) struct StopWatcher {
+    session: Entity<Session>,
+    events: mpsc::UnboundedReceiver<(DebugSessionState, Option<ThreadId>)>,
+    _subscriptions: [Subscription; 2],
+}
+
+impl StopWatcher {
+    pub(crate) fn new(session: &Entity<Session>, cx: &mut App) -> Self {
+        let (tx, events) = mpsc::unbounded();
+        let stopped_tx = tx.clone();
+        let subscriptions = [
+            cx.subscribe(session, move |_, event: &SessionEvent, _| {
+                if let SessionEvent::Stopped(thread_id) = event {
+                    stopped_tx
+                        .unbounded_send((DebugSessionState::Stopped, *thread_id))
+                        .ok();
+                }
+            }),
+            cx.subscribe(session, move |_, event: &SessionStateEvent, _| {
+                if let SessionStateEvent::Shutdown = event {
+                    tx.unbounded_send((DebugSessionState::Terminated, None))
+                        .ok();
+                }
+            }),
+        ];
+        Self {
+            session: session.clone(),
+            events,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    /// Waits up to `timeout` for the session to pause or end, reporting it as
+    /// still running if neither happens in time. When the session pauses, also
+    /// returns the thread that stopped, if the adapter said.
+    pub(crate) async fn wait(
+        mut self,
+        timeout: Duration,
+        event_stream: &ToolCallEventStream,
+        cx: &mut AsyncApp,
+    ) -> Result<(DebugSessionState, Option<ThreadId>), String> {
+        if self
+            .session
+            .read_with(cx, |session, _| session.is_terminated())
+        {
+            return Ok((DebugSessionState::Terminated, None));
+        }
+        let timer = cx.background_executor().timer(timeout);
+        futures::select! {
+            event = self.events.next() => {
+                Ok(event.unwrap_or((DebugSessionState::Terminated, None)))
+            }
+            _ = timer.fuse() => Ok((DebugSessionState::Running, None)),
+            _ = event_stream.cancelled_by_user().fuse() => {
+                Err("The user stopped waiting for the debuggee. It may still be running."
+                    .to_string())
+            }
+        }
+    }
+}
+
+/// A stack frame as reported to the model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugStackFrame {
+    pub id: u64,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub line: u64,
+}
+
+impl DebugStackFrame {
+    pub(crate) fn new(frame: &dap::StackFrame, project: &Entity<Project>, cx: &App) -> Self {
+        let path = frame
+            .source
+            .as_ref()
+            .and_then(|source| source.path.as_deref())
+            .map(|path| display_path(Path::new(path), project, cx));
+        Self {
+            id: frame.id,
+            name: frame.name.clone(),
+            path,
+            line: frame.line,
+        }
+    }
+}
+
+/// Shows paths inside the project the way the other tools accept them,
+/// prefixed with their worktree's root name, and leaves the rest absolute.
+pub(crate) fn display_path(abs_path: &Path, project: &Entity<Project>, cx: &App) -> String {
+    match project.read(cx).find_worktree(abs_path, cx) {
+        Some((worktree, path)) => format!(
+            "{}/{}",
+            worktree.read(cx).root_name_str(),
+            path.as_unix_str()
+        ),
+        None => abs_path.display().to_string(),
+    }
+}
+
+/// Fetches the innermost stack frame of a thread, to show where it stopped.
+pub(crate) async fn top_frame(
+    session: &Entity<Session>,
+    thread_id: ThreadId,
+    project: &Entity<Project>,
+    cx: &mut AsyncApp,
+) -> Option<DebugStackFrame> {
+    let frames = session
+        .read_with(cx, |session, _| session.fetch_stack_trace(thread_id))
+        .await
+        .ok()?;
+    let frame = frames.first()?;
+    Some(cx.update(|cx| DebugStackFrame::new(frame, project, cx)))
+}
+
+/// Describes a session for the model after it settled: which thread stopped
+/// and where, if it is paused.
+pub(crate) async fn session_report(
+    session: &Entity<Session>,
+    state: DebugSessionState,
+    thread_id: Option<ThreadId>,
+    project: &Entity<Project>,
+    cx: &mut AsyncApp,
+) -> DebugSessionReport {
+    let session_id = session.read_with(cx, |session, _| session.session_id().0);
+    let mut report = DebugSessionReport {
+        session_id,
+        state,
+        thread_id: None,
+        stopped_at: None,
+    };
+    if state == DebugSessionState::Stopped {
+        let thread_id = match thread_id {
+            Some(thread_id) => Some(thread_id),
+            None => resolve_thread(session, None, cx).await.ok(),
+        };
+        if let Some(thread_id) = thread_id {
+            report.thread_id = Some(thread_id.0);
+            report.stopped_at = top_frame(session, thread_id, project, cx).await;
+        }
+    }
+    report
+}
+
+/// The state of a debug session as reported to the model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugSessionReport {
+    pub session_id: u32,
+    pub state: DebugSessionState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stopped_at: Option<DebugStackFrame>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        AgentTool, BreakpointInfo, DebugBreakpointAction, DebugBreakpointTool,
+        DebugBreakpointToolInput, DebugBreakpointToolOutput, DebugControlAction, DebugControlTool,
+        DebugControlToolInput, DebugControlToolOutput, DebugInspectAction, DebugInspectTool,
+        DebugInspectToolInput, DebugInspectToolOutput, DebugScenarioInfo, DebugStartTool,
+        DebugStartToolInput, DebugStartToolOutput, SubagentHandle, TerminalHandle,
+        ThreadEnvironment, ToolCallEventStreamReceiver, ToolInput,
+    };
+    use agent_client_protocol::schema::v1 as acp;
+    use agent_settings::{AgentSettings, CompiledRegex, ToolRules};
+    use anyhow::Result;
+    use dap::FakeAdapter;
+    use dap::adapters::DebugTaskDefinition;
+    use fs::FakeFs;
+    use gpui::{Task, TestAppContext};
+    use project::debugger::session::SessionQuirks;
+    use serde_json::json;
+    use settings::{Settings as _, SettingsStore, ToolPermissionMode};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use task::SharedTaskContext;
+    use util::path;
+
+    /// Environment that starts scenarios with the fake debug adapter, straight
+    /// in the project's `DapStore`, instead of through the debug panel.
+    struct FakeDebugEnvironment {
+        project: Entity<Project>,
+    }
+
+    impl ThreadEnvironment for FakeDebugEnvironment {
+        fn create_terminal(
+            &self,
+            _command: String,
+            _extra_env: Vec<acp::EnvVariable>,
+            _cwd: Option<PathBuf>,
+            _output_byte_limit: Option<u64>,
+            _sandbox_wrap: Option<acp_thread::SandboxWrap>,
+            _cx: &mut AsyncApp,
+        ) -> Task<Result<Rc<dyn TerminalHandle>>> {
+            unimplemented!()
+        }
+
+        fn create_subagent(&self, _label: String, _cx: &mut App) -> Result<Rc<dyn SubagentHandle>> {
+            unimplemented!()
+        }
+
+        fn list_debug_scenarios(&self, _cx: &mut AsyncApp) -> Task<Result<Vec<DebugScenarioInfo>>> {
+            Task::ready(Ok(vec![DebugScenarioInfo {
+                label: "Debug App".to_string(),
+                adapter: FakeAdapter::ADAPTER_NAME.to_string(),
+                source: ".zed/debug.json".to_string(),
+                build_command: Some("cargo build".to_string()),
+                program: Some("target/debug/app".to_string()),
+            }]))
+        }
+
+        fn start_debug_scenario(&self, label: String, cx: &mut AsyncApp) -> Task<Result<()>> {
+            cx.update(|cx| start_session(&self.project, &label, cx));
+            Task::ready(Ok(()))
+        }
+    }
+
+    /// Starts a session with the fake adapter, the way the debug panel does.
+    fn start_session(project: &Entity<Project>, label: &str, cx: &mut App) -> Entity<Session> {
+        let worktree = project.read(cx).worktrees(cx).next().unwrap();
+        project.read(cx).dap_store().update(cx, |dap_store, cx| {
+            let session = dap_store.new_session(
+                Some(label.to_string().into()),
+                FakeAdapter::ADAPTER_NAME.into(),
+                SharedTaskContext::default(),
+                None,
+                SessionQuirks::default(),
+                cx,
+            );
+            dap_store
+                .boot_session(
+                    session.clone(),
+                    DebugTaskDefinition {
+                        label: label.to_string().into(),
+                        adapter: FakeAdapter::ADAPTER_NAME.into(),
+                        config: json!({ "request": "launch" }),
+                        tcp_connection: None,
+                    },
+                    worktree,
+                    cx,
+                )
+                .detach_and_log_err(cx);
+            session
+        })
+    }
+
+    /// Counts the requests the fake adapter received that the tools send.
+    #[derive(Clone, Default)]
+    struct Requests {
+        evaluate: Arc<AtomicUsize>,
+        next: Arc<AtomicUsize>,
+        continue_: Arc<AtomicUsize>,
+    }
+
+    struct Harness {
+        project: Entity<Project>,
+        requests: Requests,
+        start: Arc<DebugStartTool>,
+        breakpoint: Arc<DebugBreakpointTool>,
+        control: Arc<DebugControlTool>,
+        inspect: Arc<DebugInspectTool>,
+        _intercept: gpui::Subscription,
+    }
+
+    async fn init_test(cx: &mut TestAppContext, default: ToolPermissionMode) -> Harness {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            let mut settings = AgentSettings::get_global(cx).clone();
+            settings.tool_permissions.default = default;
+            AgentSettings::override_global(settings, cx);
+            cx.update_default_global(|registry: &mut dap::DapRegistry, _| {
+                registry.add_adapter(Arc::new(FakeAdapter::new()));
+            });
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({ "src": { "main.rs": "fn main() {\n    let x = 1;\n    println!(\"{x}\");\n}\n" } }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+
+        let requests = Requests::default();
+        let intercept = project::debugger::test::intercept_debug_sessions(cx, {
+            let requests = requests.clone();
+            move |client| {
+                client.on_request::<dap::requests::Threads, _>(|_, _| {
+                    Ok(dap::ThreadsResponse {
+                        threads: vec![dap::Thread {
+                            id: 1,
+                            name: "main".into(),
+                        }],
+                    })
+                });
+                client.on_request::<dap::requests::StackTrace, _>(|_, _| {
+                    Ok(dap::StackTraceResponse {
+                        stack_frames: vec![stack_frame()],
+                        total_frames: None,
+                    })
+                });
+                client.on_request::<dap::requests::SetBreakpoints, _>(|_, _| {
+                    Ok(dap::SetBreakpointsResponse {
+                        breakpoints: Vec::new(),
+                    })
+                });
+                let evaluate = requests.evaluate.clone();
+                client.on_request::<dap::requests::Evaluate, _>(move |_, args| {
+                    evaluate.fetch_add(1, Ordering::SeqCst);
+                    Ok(dap::EvaluateResponse {
+                        result: format!("{} = 1", args.expression),
+                        type_: Some("i32".into()),
+                        presentation_hint: None,
+                        variables_reference: 0,
+                        named_variables: None,
+                        indexed_variables: None,
+                        memory_reference: None,
+                        value_location_reference: None,
+                    })
+                });
+                let next = requests.next.clone();
+                client.on_request::<dap::requests::Next, _>(move |_, _| {
+                    next.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                });
+                let continue_ = requests.continue_.clone();
+                client.on_request::<dap::requests::Continue, _>(move |_, _| {
+                    continue_.fetch_add(1, Ordering::SeqCst);
+                    Ok(dap::ContinueResponse {
+                        all_threads_continued: Some(true),
+                    })
+                });
+            }
+        });
+
+        let sessions = AgentDebugSessions::default();
+        let environment = Rc::new(FakeDebugEnvironment {
+            project: project.clone(),
+        });
+        Harness {
+            start: Arc::new(DebugStartTool::new(
+                project.clone(),
+                environment,
+                sessions.clone(),
+            )),
+            breakpoint: Arc::new(DebugBreakpointTool::new(project.clone())),
+            control: Arc::new(DebugControlTool::new(project.clone(), sessions.clone())),
+            inspect: Arc::new(DebugInspectTool::new(project.clone(), sessions)),
+            project,
+            requests,
+            _intercept: intercept,
+        }
+    }
+
+    fn stack_frame() -> dap::StackFrame {
+        dap::StackFrame {
+            id: 1,
+            name: "main".into(),
+            source: Some(dap::Source {
+                name: Some("main.rs".into()),
+                path: Some(path!("/project/src/main.rs").into()),
+                source_reference: None,
+                presentation_hint: None,
+                origin: None,
+                sources: None,
+                adapter_data: None,
+                checksums: None,
+            }),
+            line: 2,
+            column: 1,
+            end_line: None,
+            end_column: None,
+            can_restart: None,
+            instruction_pointer_reference: None,
+            module_id: None,
+            presentation_hint: None,
+        }
+    }
+
+    fn run<T: AgentTool>(
+        tool: &Arc<T>,
+        input: T::Input,
+        cx: &mut TestAppContext,
+    ) -> (
+        Task<Result<T::Output, T::Output>>,
+        ToolCallEventStreamReceiver,
+    ) {
+        let (event_stream, event_rx) = crate::ToolCallEventStream::test();
+        let task = cx.update(|cx| {
+            tool.clone()
+                .run(ToolInput::resolved(input), event_stream, cx)
+        });
+        (task, event_rx)
+    }
+
+    fn respond(auth: crate::ToolCallAuthorization, allow: bool) {
+        let outcome = if allow {
+            acp_thread::SelectedPermissionOutcome::new(
+                acp::PermissionOptionId::new("allow"),
+                acp::PermissionOptionKind::AllowOnce,
+            )
+        } else {
+            acp_thread::SelectedPermissionOutcome::new(
+                acp::PermissionOptionId::new("deny"),
+                acp::PermissionOptionKind::RejectOnce,
+            )
+        };
+        auth.response.send(outcome).unwrap();
+    }
+
+    /// Makes the fake adapter report that the debuggee's thread paused.
+    async fn fake_stop(session: &Entity<Session>, cx: &mut TestAppContext) {
+        let client = session.read_with(cx, |session, _| session.adapter_client().unwrap());
+        client
+            .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+                reason: dap::StoppedEventReason::Breakpoint,
+                description: None,
+                thread_id: Some(1),
+                preserve_focus_hint: None,
+                text: None,
+                all_threads_stopped: None,
+                hit_breakpoint_ids: None,
+            }))
+            .await;
+        cx.run_until_parked();
+    }
+
+    fn latest_session(project: &Entity<Project>, cx: &mut TestAppContext) -> Entity<Session> {
+        project.read_with(cx, |project, cx| {
+            project
+                .dap_store()
+                .read(cx)
+                .sessions()
+                .max_by_key(|session| session.read(cx).session_id())
+                .cloned()
+                .unwrap()
+        })
+    }
+
+    /// Starts "Debug App" with `debug_start` and waits for it to pause.
+    async fn start_and_stop(harness: &Harness, cx: &mut TestAppContext) -> DebugSessionReport {
+        let (task, _event_rx) = run(
+            &harness.start,
+            DebugStartToolInput {
+                scenario: Some("Debug App".to_string()),
+            },
+            cx,
+        );
+        cx.run_until_parked();
+        fake_stop(&latest_session(&harness.project, cx), cx).await;
+        match task.await {
+            Ok(DebugStartToolOutput::Started { session, .. }) => session,
+            result => panic!("expected the session to start, got {result:?}"),
+        }
+    }
+
+    #[gpui::test]
+    async fn test_debug_start_asks_with_build_command_and_program(cx: &mut TestAppContext) {
+        let harness = init_test(cx, ToolPermissionMode::Confirm).await;
+        let input = DebugStartToolInput {
+            scenario: Some("Debug App".to_string()),
+        };
+
+        let (task, mut event_rx) = run(&harness.start, input.clone(), cx);
+        let auth = event_rx.expect_authorization().await;
+        assert_eq!(
+            auth.context.as_ref().unwrap().input_values,
+            vec!["Debug App", "cargo build", "target/debug/app"]
+        );
+        respond(auth, false);
+        assert!(task.await.is_err());
+        cx.run_until_parked();
+        assert_eq!(
+            harness.project.read_with(cx, |project, cx| project
+                .dap_store()
+                .read(cx)
+                .sessions()
+                .count()),
+            0
+        );
+
+        let (task, mut event_rx) = run(&harness.start, input, cx);
+        respond(event_rx.expect_authorization().await, true);
+        cx.run_until_parked();
+        fake_stop(&latest_session(&harness.project, cx), cx).await;
+        let Ok(DebugStartToolOutput::Started { session, .. }) = task.await else {
+            panic!("expected the session to start");
+        };
+        assert_eq!(session.state, DebugSessionState::Stopped);
+        assert_eq!(session.thread_id, Some(1));
+        let stopped_at = session.stopped_at.unwrap();
+        assert_eq!(stopped_at.path.as_deref(), Some("project/src/main.rs"));
+        assert_eq!(stopped_at.line, 2);
+    }
+
+    #[gpui::test]
+    async fn test_debug_start_is_held_to_terminal_rules(cx: &mut TestAppContext) {
+        let harness = init_test(cx, ToolPermissionMode::Allow).await;
+        cx.update(|cx| {
+            let mut settings = AgentSettings::get_global(cx).clone();
+            settings.tool_permissions.tools.insert(
+                crate::TerminalTool::NAME.into(),
+                ToolRules {
+                    always_deny: vec![CompiledRegex::new(r"^cargo\s+build\b", false).unwrap()],
+                    ..Default::default()
+                },
+            );
+            AgentSettings::override_global(settings, cx);
+        });
+
+        let (task, _event_rx) = run(
+            &harness.start,
+            DebugStartToolInput {
+                scenario: Some("Debug App".to_string()),
+            },
+            cx,
+        );
+        assert!(matches!(
+            task.await,
+            Err(DebugStartToolOutput::Error { .. })
+        ));
+        assert_eq!(
+            harness.project.read_with(cx, |project, cx| project
+                .dap_store()
+                .read(cx)
+                .sessions()
+                .count()),
+            0
+        );
+    }
+
+    #[gpui::test]
+    async fn test_debug_tools_ignore_sessions_the_thread_did_not_start(cx: &mut TestAppContext) {
+        let harness = init_test(cx, ToolPermissionMode::Allow).await;
+        let user_session = cx.update(|cx| start_session(&harness.project, "User Session", cx));
+        cx.run_until_parked();
+        fake_stop(&user_session, cx).await;
+        let user_session_id = user_session.read_with(cx, |session, _| session.session_id().0);
+
+        let (task, _event_rx) = run(
+            &harness.control,
+            DebugControlToolInput {
+                action: DebugControlAction::Continue,
+                session_id: Some(user_session_id),
+                thread_id: None,
+                wait_seconds: None,
+            },
+            cx,
+        );
+        let Err(DebugControlToolOutput::Error { error }) = task.await else {
+            panic!("expected the user's session to be off limits");
+        };
+        assert!(error.contains("wasn't started"), "{error}");
+
+        let (task, _event_rx) = run(
+            &harness.inspect,
+            DebugInspectToolInput {
+                action: DebugInspectAction::Evaluate,
+                session_id: None,
+                thread_id: None,
+                frame_id: None,
+                variables_reference: None,
+                expression: Some("x".to_string()),
+            },
+            cx,
+        );
+        assert!(matches!(
+            task.await,
+            Err(DebugInspectToolOutput::Error { .. })
+        ));
+        assert_eq!(harness.requests.continue_.load(Ordering::SeqCst), 0);
+        assert_eq!(harness.requests.evaluate.load(Ordering::SeqCst), 0);
+
+        // Once the thread starts its own session, that's the one it acts on.
+        let report = start_and_stop(&harness, cx).await;
+        assert_ne!(report.session_id, user_session_id);
+        let (task, _event_rx) = run(
+            &harness.control,
+            DebugControlToolInput {
+                action: DebugControlAction::Wait,
+                session_id: None,
+                thread_id: None,
+                wait_seconds: None,
+            },
+            cx,
+        );
+        let Ok(DebugControlToolOutput::Session(waited)) = task.await else {
+            panic!("expected the thread's session to be used");
+        };
+        assert_eq!(waited.session_id, report.session_id);
+    }
+
+    #[gpui::test]
+    async fn test_debug_control_steps_and_continues(cx: &mut TestAppContext) {
+        let harness = init_test(cx, ToolPermissionMode::Allow).await;
+        let report = start_and_stop(&harness, cx).await;
+        let session = latest_session(&harness.project, cx);
+
+        for (action, requests) in [
+            (DebugControlAction::StepOver, &harness.requests.next),
+            (DebugControlAction::Continue, &harness.requests.continue_),
+        ] {
+            let (task, _event_rx) = run(
+                &harness.control,
+                DebugControlToolInput {
+                    action,
+                    session_id: Some(report.session_id),
+                    thread_id: None,
+                    wait_seconds: None,
+                },
+                cx,
+            );
+            cx.run_until_parked();
+            assert_eq!(requests.load(Ordering::SeqCst), 1, "{action:?}");
+            fake_stop(&session, cx).await;
+            let Ok(DebugControlToolOutput::Session(stopped)) = task.await else {
+                panic!("expected {action:?} to report the session");
+            };
+            assert_eq!(stopped.state, DebugSessionState::Stopped);
+            assert_eq!(stopped.thread_id, Some(1));
+        }
+    }
+
+    #[gpui::test]
+    async fn test_debug_inspect_asks_before_evaluating(cx: &mut TestAppContext) {
+        let harness = init_test(cx, ToolPermissionMode::Confirm).await;
+        cx.update(|cx| {
+            let mut settings = AgentSettings::get_global(cx).clone();
+            settings.tool_permissions.tools.insert(
+                DebugStartTool::NAME.into(),
+                ToolRules {
+                    default: Some(ToolPermissionMode::Allow),
+                    ..Default::default()
+                },
+            );
+            AgentSettings::override_global(settings, cx);
+        });
+        start_and_stop(&harness, cx).await;
+        let input = DebugInspectToolInput {
+            action: DebugInspectAction::Evaluate,
+            session_id: None,
+            thread_id: None,
+            frame_id: None,
+            variables_reference: None,
+            expression: Some("x".to_string()),
+        };
+
+        let (task, mut event_rx) = run(&harness.inspect, input.clone(), cx);
+        let auth = event_rx.expect_authorization().await;
+        assert_eq!(auth.context.as_ref().unwrap().input_values, vec!["x"]);
+        respond(auth, false);
+        assert!(task.await.is_err());
+        assert_eq!(harness.requests.evaluate.load(Ordering::SeqCst), 0);
+
+        let (task, mut event_rx) = run(&harness.inspect, input, cx);
+        respond(event_rx.expect_authorization().await, true);
+        let Ok(DebugInspectToolOutput::Evaluated { value }) = task.await else {
+            panic!("expected the expression to be evaluated");
+        };
+        assert_eq!(value.value, "x = 1");
+        assert_eq!(harness.requests.evaluate.load(Ordering::SeqCst), 1);
+
+        // Reading the stack doesn't run code, so it doesn't ask.
+        let (task, _event_rx) = run(
+            &harness.inspect,
+            DebugInspectToolInput {
+                action: DebugInspectAction::Stack,
+                session_id: None,
+                thread_id: None,
+                frame_id: None,
+                variables_reference: None,
+                expression: None,
+            },
+            cx,
+        );
+        let Ok(DebugInspectToolOutput::Stack { frames, .. }) = task.await else {
+            panic!("expected the stack");
+        };
+        assert_eq!(frames.len(), 1);
+    }
+
+    #[gpui::test]
+    async fn test_debug_breakpoint_sets_and_clears(cx: &mut TestAppContext) {
+        let harness = init_test(cx, ToolPermissionMode::Confirm).await;
+        let breakpoint = |action, condition: Option<&str>| DebugBreakpointToolInput {
+            action,
+            path: Some("project/src/main.rs".to_string()),
+            line: Some(2),
+            condition: condition.map(ToString::to_string),
+            log_message: None,
+        };
+
+        let (task, mut event_rx) = run(
+            &harness.breakpoint,
+            breakpoint(DebugBreakpointAction::Set, Some("x > 0")),
+            cx,
+        );
+        let auth = event_rx.expect_authorization().await;
+        assert_eq!(
+            auth.context.as_ref().unwrap().input_values,
+            vec!["project/src/main.rs:2", "x > 0"]
+        );
+        respond(auth, false);
+        assert!(task.await.is_err());
+
+        let (task, mut event_rx) = run(
+            &harness.breakpoint,
+            breakpoint(DebugBreakpointAction::Set, Some("x > 0")),
+            cx,
+        );
+        respond(event_rx.expect_authorization().await, true);
+        let Ok(DebugBreakpointToolOutput::Breakpoints { breakpoints }) = task.await else {
+            panic!("expected the breakpoint to be set");
+        };
+        assert_eq!(
+            breakpoints
+                .iter()
+                .map(
+                    |BreakpointInfo {
+                         path,
+                         line,
+                         condition,
+                         ..
+                     }| { (path.as_str(), *line, condition.as_deref()) }
+                )
+                .collect::<Vec<_>>(),
+            vec![("project/src/main.rs", 2, Some("x > 0"))]
+        );
+
+        let (task, mut event_rx) = run(
+            &harness.breakpoint,
+            breakpoint(DebugBreakpointAction::Clear, None),
+            cx,
+        );
+        respond(event_rx.expect_authorization().await, true);
+        let Ok(DebugBreakpointToolOutput::Breakpoints { breakpoints }) = task.await else {
+            panic!("expected the breakpoint to be cleared");
+        };
+        assert!(breakpoints.is_empty());
+    }
+}
@@ -0,0 +1,261 @@
+use agent_client_protocol::schema::v1 as acp;
+use anyhow::Result;
+use collections::HashSet;
+use gpui::{App, Entity, SharedString, Task};
+use language_model::LanguageModelToolResultContent;
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+use util::markdown::MarkdownInlineCode;
+
+use super::debug_session::{AgentDebugSessions, DebugSessionReport, StopWatcher, session_report};
+use crate::{AgentTool, DebugScenarioInfo, ThreadEnvironment, ToolCallEventStream, ToolInput};
+
+/// How long to wait for a newly started session to hit a breakpoint. This
+/// includes the scenario's build step, if any.
+const START_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Start debugging the project with one of its debug scenarios.
+///
+/// Debug scenarios are the launch and attach configurations the user has set
+/// up in `.zed/debug.json` (or `.vscode/launch.json`), plus ones they started
+/// recently. The session runs in the debug panel, where the user can follow
+/// along.
+///
+/// Call this tool without `scenario` to list the available scenarios, then
+/// call it again with the label of the scenario to start. Set breakpoints with
+/// `debug_breakpoint` first: this tool waits for the debuggee to stop (or exit)
+/// and reports where it stopped. Then use `debug_inspect` to read its state and
+/// `debug_control` to step or resume it.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct DebugStartToolInput {
+    /// The label of the debug scenario to start, exactly as listed. Omit to
+    /// list the available scenarios instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scenario: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DebugStartToolOutput {
+    Scenarios {
+        scenarios: Vec<DebugScenarioInfo>,
+    },
+    Started {
+        scenario: String,
+        #[serde(flatten)]
+        session: DebugSessionReport,
+    },
+    Error {
+        error: String,
+    },
+}
+
+impl From<DebugStartToolOutput> for LanguageModelToolResultContent {
+    fn from(output: DebugStartToolOutput) -> Self {
+        serde_json::to_string(&output)
+            .unwrap_or_else(|e| format!("Failed to serialize debug_start output: {e}"))
+            .into()
+    }
+}
+
+pub struct DebugStartTool {
+    project: Entity<Project>,
+    environment: Rc<dyn ThreadEnvironment>,
+    sessions: AgentDebugSessions,
+}
+
+impl DebugStartTool {
+    pub fn new(
+        project: Entity<Project>,
+        environment: Rc<dyn ThreadEnvironment>,
+        sessions: AgentDebugSessions,
+    ) -> Self {
+        Self {
+            project,
+            environment,
+            sessions,
+        }
+    }
+}
+
+impl AgentTool for DebugStartTool {
+    type Input = DebugStartToolInput;
+    type Output = DebugStartToolOutput;
+
+    const NAME: &'static str = "debug_start";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Execute
+    }
+
+    fn allow_in_restricted_mode() -> bool {
+        false
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        let scenario = match input {
+            Ok(input) => input.scenario,
+            Err(value) => value
+                .get("scenario")
+                .and_then(|scenario| scenario.as_str())
+                .map(ToString::to_string),
+        };
+        match scenario {
+            Some(scenario) => format!("Debug {}", MarkdownInlineCode(&scenario)).into(),
+            None => "List debug scenarios".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output, Self::Output>> {
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| DebugStartToolOutput::Error {
+                    error: format!("Failed to receive tool input: {e}"),
+                })?;
+
+            let scenarios = self
+                .environment
+                .list_debug_scenarios(cx)
+                .await
+                .map_err(|e| DebugStartToolOutput::Error {
+                    error: e.to_string(),
+                })?;
+
+            let Some(label) = input.scenario else {
+                return Ok(DebugStartToolOutput::Scenarios { scenarios });
+            };
+            let scenario =
+                find_scenario(&scenarios, &label).ok_or_else(|| DebugStartToolOutput::Error {
+                    error: format!(
+                        "No debug scenario labeled {label:?}. Call `debug_start` without \
+                         `scenario` to list the available scenarios."
+                    ),
+                })?;
+
+            // Starting a scenario runs its build command and launches its
+            // program, so both are checked along with the label.
+            let authorize = cx.update(|cx| {
+                let inputs = std::iter::once(scenario.label.clone())
+                    .chain(scenario.build_command.clone())
+                    .chain(scenario.program.clone())
+                    .collect();
+                let context = crate::ToolPermissionContext::new(Self::NAME, inputs);
+                event_stream.authorize(
+                    format!("Debug {}", MarkdownInlineCode(&scenario.label)),
+                    context,
+                    cx,
+                )
+            });
+            authorize.await.map_err(|e| DebugStartToolOutput::Error {
+                error: e.to_string(),
+            })?;
+
+            let existing_sessions = self.project.read_with(cx, |project, cx| {
+                project
+                    .dap_store()
+                    .read(cx)
+                    .sessions()
+                    .map(|session| session.read(cx).session_id())
+                    .collect::<HashSet<_>>()
+            });
+            self.environment
+                .start_debug_scenario(scenario.label.clone(), cx)
+                .await
+                .map_err(|e| DebugStartToolOutput::Error {
+                    error: e.to_string(),
+                })?;
+
+            // The debug panel registers the session as soon as it is asked to
+            // start one; booting the adapter and the debuggee happens after.
+            let (session, watcher) = cx
+                .update(|cx| {
+                    let session = self
+                        .project
+                        .read(cx)
+                        .dap_store()
+                        .read(cx)
+                        .sessions()
+                        .filter(|session| {
+                            !existing_sessions.contains(&session.read(cx).session_id())
+                        })
+                        .max_by_key(|session| session.read(cx).session_id())
+                        .cloned()?;
+                    self.sessions.insert(session.read(cx).session_id());
+                    let watcher = StopWatcher::new(&session, cx);
+                    Some((session, watcher))
+                })
+                .ok_or_else(|| DebugStartToolOutput::Error {
+                    error: format!(
+                        "The debugger didn't start a session for {:?}. Check that the {} debug \
+                         adapter is available.",
+                        scenario.label, scenario.adapter
+                    ),
+                })?;
+
+            let (state, thread_id) = watcher
+                .wait(START_TIMEOUT, &event_stream, cx)
+                .await
+                .map_err(|error| DebugStartToolOutput::Error { error })?;
+            let session = session_report(&session, state, thread_id, &self.project, cx).await;
+            Ok(DebugStartToolOutput::Started {
+                scenario: scenario.label.clone(),
+                session,
+            })
+        })
+    }
+}
+
+/// Finds the scenario with the given label, falling back to a case-insensitive
+/// match since models often normalize capitalization.
+fn find_scenario<'a>(
+    scenarios: &'a [DebugScenarioInfo],
+    label: &str,
+) -> Option<&'a DebugScenarioInfo> {
+    scenarios
+        .iter()
+        .find(|scenario| scenario.label == label)
+        .or_else(|| {
+            scenarios
+                .iter()
+                .find(|scenario| scenario.label.eq_ignore_ascii_case(label))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scenario(label: &str) -> DebugScenarioInfo {
+        DebugScenarioInfo {
+            label: label.to_string(),
+            adapter: "CodeLLDB".to_string(),
+            source: ".zed/debug.json".to_string(),
+            build_command: None,
+            program: None,
+        }
+    }
+
+    #[test]
+    fn test_find_scenario_prefers_exact_label() {
+        let scenarios = vec![scenario("Debug App"), scenario("debug app")];
+        assert_eq!(find_scenario(&scenarios, "debug app"), Some(&scenarios[1]));
+        assert_eq!(find_scenario(&scenarios, "DEBUG APP"), Some(&scenarios[0]));
+        assert_eq!(find_scenario(&scenarios, "Debug Tests"), None);
+    }
+}
@@ -0,0 +1,399 @@
+use agent_client_protocol::schema::v1 as acp;
+use anyhow::Result;
+use futures::FutureExt as _;
+use gpui::{App, SharedString, Task};
+use language_model::LanguageModelToolResultContent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+use std::sync::Arc;
+use util::markdown::MarkdownInlineCode;
+
+use crate::{AgentTool, ProjectTaskInfo, ThreadEnvironment, ToolCallEventStream, ToolInput};
+
+/// The maximum number of characters of task output returned to the model. Output
+/// beyond this is dropped from the front, since failures are usually reported
+/// at the end.
+const TASK_OUTPUT_LIMIT: usize = 16 * 1024;
+
+/// Run one of the project's tasks and report how it went.
+///
+/// Tasks are the commands the user has set up to build, test, lint, or run the
+/// project: the ones in `.zed/tasks.json`, the global tasks file, and those
+/// provided by the languages in use. Prefer them over guessing a command for the
+/// terminal tool, since they encode how this project is actually built and tested.
+///
+/// Call this tool without `task` to list the available tasks, then call it
+/// again with the label of the task to run. The task runs in a terminal the user
+/// can see, and the result contains its exit code and output (trimmed to the
+/// last part if long).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RunTaskToolInput {
+    /// The label of the task to run, exactly as listed. Omit to list the
+    /// available tasks instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RunTaskToolOutput {
+    Tasks {
+        tasks: Vec<ProjectTaskInfo>,
+    },
+    Completed {
+        task: String,
+        command: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        exit_code: Option<i32>,
+        output: String,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        output_truncated: bool,
+    },
+    Error {
+        error: String,
+    },
+}
+
+impl From<RunTaskToolOutput> for LanguageModelToolResultContent {
+    fn from(output: RunTaskToolOutput) -> Self {
+        serde_json::to_string(&output)
+            .unwrap_or_else(|e| format!("Failed to serialize run_task output: {e}"))
+            .into()
+    }
+}
+
+pub struct RunTaskTool {
+    environment: Rc<dyn ThreadEnvironment>,
+}
+
+impl RunTaskTool {
+    pub fn new(environment: Rc<dyn ThreadEnvironment>) -> Self {
+        Self { environment }
+    }
+}
+
+impl AgentTool for RunTaskTool {
+    type Input = RunTaskToolInput;
+    type Output = RunTaskToolOutput;
+
+    const NAME: &'static str = "run_task";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Execute
+    }
+
+    fn allow_in_restricted_mode() -> bool {
+        false
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        let task = match input {
+            Ok(input) => input.task,
+            Err(value) => value
+                .get("task")
+                .and_then(|task| task.as_str())
+                .map(ToString::to_string),
+        };
+        match task {
+            Some(task) => format!("Run task {}", MarkdownInlineCode(&task)).into(),
+            None => "List tasks".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output, Self::Output>> {
+        cx.spawn(async move |cx| {
+            let input = input.recv().await.map_err(|e| RunTaskToolOutput::Error {
+                error: format!("Failed to receive tool input: {e}"),
+            })?;
+
+            let tasks = self.environment.list_project_tasks(cx).await.map_err(|e| {
+                RunTaskToolOutput::Error {
+                    error: e.to_string(),
+                }
+            })?;
+
+            let Some(label) = input.task else {
+                return Ok(RunTaskToolOutput::Tasks { tasks });
+            };
+            let task = find_task(&tasks, &label).ok_or_else(|| RunTaskToolOutput::Error {
+                error: format!(
+                    "No task labeled {label:?}. Call `run_task` without `task` to list the \
+                     available tasks."
+                ),
+            })?;
+
+            let authorize = cx.update(|cx| {
+                let context =
+                    crate::ToolPermissionContext::new(Self::NAME, vec![task.command.clone()]);
+                event_stream.authorize(
+                    format!("Run task {}", MarkdownInlineCode(&task.label)),
+                    context,
+                    cx,
+                )
+            });
+            authorize.await.map_err(|e| RunTaskToolOutput::Error {
+                error: e.to_string(),
+            })?;
+
+            let run = self.environment.run_project_task(task.label.clone(), cx);
+            let result = futures::select! {
+                result = run.fuse() => result,
+                _ = event_stream.cancelled_by_user().fuse() => {
+                    return Err(RunTaskToolOutput::Error {
+                        error: "The user stopped waiting for this task. It may still be \
+                                running in the terminal panel."
+                            .to_string(),
+                    });
+                }
+            };
+            let result = result.map_err(|e| RunTaskToolOutput::Error {
+                error: e.to_string(),
+            })?;
+
+            let output = result.output.trim();
+            let output_truncated = output.chars().count() > TASK_OUTPUT_LIMIT;
+            let output = if output_truncated {
+                util::truncate_and_remove_front(output, TASK_OUTPUT_LIMIT)
+            } else {
+                output.to_string()
+            };
+            Ok(RunTaskToolOutput::Completed {
+                task: task.label.clone(),
+                command: task.command.clone(),
+                exit_code: result.exit_code,
+                output,
+                output_truncated,
+            })
+        })
+    }
+}
+
+/// Finds the task with the given label, falling back to a case-insensitive
+/// match since models often normalize capitalization.
+fn find_task<'a>(tasks: &'a [ProjectTaskInfo], label: &str) -> Option<&'a ProjectTaskInfo> {
+    tasks.iter().find(|task| task.label == label).or_else(|| {
+        tasks
+            .iter()
+            .find(|task| task.label.eq_ignore_ascii_case(label))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ProjectTaskOutput, SubagentHandle, TerminalHandle};
+    use agent_settings::{AgentSettings, CompiledRegex, ToolRules};
+    use futures::channel::oneshot;
+    use gpui::{AsyncApp, TestAppContext};
+    use settings::{Settings as _, SettingsStore, ToolPermissionMode};
+    use std::cell::RefCell;
+    use std::path::PathBuf;
+
+    fn task(label: &str) -> ProjectTaskInfo {
+        ProjectTaskInfo {
+            label: label.to_string(),
+            command: format!("run {label}"),
+            source: "tasks.json".to_string(),
+        }
+    }
+
+    /// Environment whose tasks only finish once the test sends their output.
+    struct FakeTaskEnvironment {
+        tasks: Vec<ProjectTaskInfo>,
+        runs: Rc<RefCell<Vec<String>>>,
+        output: RefCell<Option<oneshot::Receiver<ProjectTaskOutput>>>,
+    }
+
+    impl FakeTaskEnvironment {
+        fn new(tasks: Vec<ProjectTaskInfo>) -> (Self, oneshot::Sender<ProjectTaskOutput>) {
+            let (output_tx, output_rx) = oneshot::channel();
+            let environment = Self {
+                tasks,
+                runs: Rc::default(),
+                output: RefCell::new(Some(output_rx)),
+            };
+            (environment, output_tx)
+        }
+    }
+
+    impl ThreadEnvironment for FakeTaskEnvironment {
+        fn create_terminal(
+            &self,
+            _command: String,
+            _extra_env: Vec<acp::EnvVariable>,
+            _cwd: Option<PathBuf>,
+            _output_byte_limit: Option<u64>,
+            _sandbox_wrap: Option<acp_thread::SandboxWrap>,
+            _cx: &mut AsyncApp,
+        ) -> Task<Result<Rc<dyn TerminalHandle>>> {
+            unimplemented!()
+        }
+
+        fn create_subagent(&self, _label: String, _cx: &mut App) -> Result<Rc<dyn SubagentHandle>> {
+            unimplemented!()
+        }
+
+        fn list_project_tasks(&self, _cx: &mut AsyncApp) -> Task<Result<Vec<ProjectTaskInfo>>> {
+            Task::ready(Ok(self.tasks.clone()))
+        }
+
+        fn run_project_task(
+            &self,
+            label: String,
+            cx: &mut AsyncApp,
+        ) -> Task<Result<ProjectTaskOutput>> {
+            self.runs.borrow_mut().push(label);
+            let output = self.output.borrow_mut().take().expect("task already run");
+            cx.background_spawn(async move { Ok(output.await?) })
+        }
+    }
+
+    fn init_test(cx: &mut TestAppContext, default: ToolPermissionMode) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+        cx.update(|cx| {
+            let mut settings = AgentSettings::get_global(cx).clone();
+            settings.tool_permissions.default = default;
+            AgentSettings::override_global(settings, cx);
+        });
+    }
+
+    fn run_task(
+        tool: &Arc<RunTaskTool>,
+        label: &str,
+        event_stream: ToolCallEventStream,
+        cx: &mut TestAppContext,
+    ) -> Task<Result<RunTaskToolOutput, RunTaskToolOutput>> {
+        let input = ToolInput::resolved(RunTaskToolInput {
+            task: Some(label.to_string()),
+        });
+        cx.update(|cx| tool.clone().run(input, event_stream, cx))
+    }
+
+    #[gpui::test]
+    async fn test_run_task_denied_by_terminal_rules(cx: &mut TestAppContext) {
+        init_test(cx, ToolPermissionMode::Allow);
+        cx.update(|cx| {
+            let mut settings = AgentSettings::get_global(cx).clone();
+            settings.tool_permissions.tools.insert(
+                crate::TerminalTool::NAME.into(),
+                ToolRules {
+                    always_deny: vec![CompiledRegex::new(r"\bgit\s+push\b", false).unwrap()],
+                    ..Default::default()
+                },
+            );
+            AgentSettings::override_global(settings, cx);
+        });
+
+        let release = ProjectTaskInfo {
+            label: "release".to_string(),
+            command: "make release && git push".to_string(),
+            source: "tasks.json".to_string(),
+        };
+        let (environment, _output_tx) = FakeTaskEnvironment::new(vec![release]);
+        let runs = environment.runs.clone();
+        let tool = Arc::new(RunTaskTool::new(Rc::new(environment)));
+
+        let (event_stream, _event_rx) = ToolCallEventStream::test();
+        let result = run_task(&tool, "release", event_stream, cx).await;
+        assert!(
+            matches!(result, Err(RunTaskToolOutput::Error { .. })),
+            "expected a denial, got {result:?}"
+        );
+        assert!(runs.borrow().is_empty());
+    }
+
+    #[gpui::test]
+    async fn test_run_task_asks_for_confirmation(cx: &mut TestAppContext) {
+        init_test(cx, ToolPermissionMode::Confirm);
+        let (environment, output_tx) = FakeTaskEnvironment::new(vec![task("test")]);
+        let runs = environment.runs.clone();
+        let tool = Arc::new(RunTaskTool::new(Rc::new(environment)));
+
+        let (event_stream, mut event_rx) = ToolCallEventStream::test();
+        let result = run_task(&tool, "test", event_stream, cx);
+
+        let auth = event_rx.expect_authorization().await;
+        let title = auth.tool_call.fields.title.as_deref().unwrap_or("");
+        assert!(title.contains("test"), "unexpected title: {title}");
+        assert!(runs.borrow().is_empty());
+        auth.response
+            .send(acp_thread::SelectedPermissionOutcome::new(
+                acp::PermissionOptionId::new("allow"),
+                acp::PermissionOptionKind::AllowOnce,
+            ))
+            .unwrap();
+
+        cx.run_until_parked();
+        assert_eq!(*runs.borrow(), vec!["test".to_string()]);
+        output_tx
+            .send(ProjectTaskOutput {
+                exit_code: Some(1),
+                output: "1 test failed\n".to_string(),
+            })
+            .unwrap();
+
+        match result.await {
+            Ok(RunTaskToolOutput::Completed {
+                task,
+                command,
+                exit_code,
+                output,
+                output_truncated,
+            }) => {
+                assert_eq!(task, "test");
+                assert_eq!(command, "run test");
+                assert_eq!(exit_code, Some(1));
+                assert_eq!(output, "1 test failed");
+                assert!(!output_truncated);
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[gpui::test]
+    async fn test_run_task_cancelled(cx: &mut TestAppContext) {
+        init_test(cx, ToolPermissionMode::Allow);
+        let (environment, _output_tx) = FakeTaskEnvironment::new(vec![task("serve")]);
+        let runs = environment.runs.clone();
+        let tool = Arc::new(RunTaskTool::new(Rc::new(environment)));
+
+        let (event_stream, _event_rx, mut cancellation_tx) =
+            ToolCallEventStream::test_with_cancellation();
+        let result = run_task(&tool, "serve", event_stream, cx);
+        cx.run_until_parked();
+        assert_eq!(*runs.borrow(), vec!["serve".to_string()]);
+
+        ToolCallEventStream::signal_cancellation_with_sender(&mut cancellation_tx);
+        match result.await {
+            Err(RunTaskToolOutput::Error { error }) => {
+                assert!(
+                    error.contains("stopped waiting"),
+                    "unexpected error: {error}"
+                );
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_find_task_prefers_exact_label() {
+        let tasks = vec![task("Test"), task("test")];
+        assert_eq!(find_task(&tasks, "test"), Some(&tasks[1]));
+        assert_eq!(find_task(&tasks, "TEST"), Some(&tasks[0]));
+        assert_eq!(find_task(&tasks, "build"), None);
+    }
+}
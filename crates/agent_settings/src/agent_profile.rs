@@ -6,8 +6,8 @@ use convert_case::{Case, Casing as _};
 use fs::Fs;
 use gpui::{App, SharedString};
 use settings::{
-    AgentProfileContent, ContextServerPresetContent, LanguageModelSelection, Settings as _,
-    SettingsContent, SettingsStore, update_settings_file,
+    AgentBudgetContent, AgentProfileContent, ContextServerPresetContent, LanguageModelSelection,
+    Settings as _, SettingsContent, SettingsStore, update_settings_file,
 };
 use util::ResultExt as _;
 
@@ -19,9 +19,11 @@ pub mod builtin_profiles {
     pub const WRITE: &str = "write";
     pub const ASK: &str = "ask";
     pub const MINIMAL: &str = "minimal";
+    /// The read-only profile that threads reviewing a diff start with.
+    pub const REVIEW: &str = "review";
 
     pub fn is_builtin(profile_id: &AgentProfileId) -> bool {
-        profile_id.as_str() == WRITE || profile_id.as_str() == ASK || profile_id.as_str() == MINIMAL
+        [WRITE, ASK, MINIMAL, REVIEW].contains(&profile_id.as_str())
     }
 }
 
@@ -70,6 +72,7 @@ impl AgentProfile {
         let default_model = base_profile
             .as_ref()
             .and_then(|profile| profile.default_model.clone());
+        let budget = base_profile.and_then(|profile| profile.budget);
 
         let profile_settings = AgentProfileSettings {
             name: name.into(),
@@ -77,6 +80,7 @@ impl AgentProfile {
             enable_all_context_servers,
             context_servers,
             default_model,
+            budget,
         };
 
         update_settings_file(fs, cx, {
@@ -109,6 +113,8 @@ pub struct AgentProfileSettings {
     pub context_servers: IndexMap<Arc<str>, ContextServerPreset>,
     /// Default language model to apply when this profile becomes active.
     pub default_model: Option<LanguageModelSelection>,
+    /// Limits that replace the global `budget` for threads using this profile.
+    pub budget: Option<AgentBudgetContent>,
 }
 
 impl AgentProfileSettings {
@@ -183,6 +189,7 @@ impl AgentProfileSettings {
                     })
                     .collect(),
                 default_model: self.default_model.clone(),
+                budget: self.budget.clone(),
             },
         );
 
@@ -198,6 +205,7 @@ impl From<AgentProfileContent> for AgentProfileSettings {
             enable_all_context_servers,
             context_servers,
             default_model,
+            budget,
         } = content;
 
         Self {
@@ -209,6 +217,7 @@ impl From<AgentProfileContent> for AgentProfileSettings {
                 .map(|(server_id, preset)| (server_id, preset.into()))
                 .collect(),
             default_model,
+            budget,
         }
     }
 }
@@ -240,6 +249,7 @@ mod tests {
             enable_all_context_servers,
             context_servers,
             default_model: None,
+            budget: None,
         }
     }
 
@@ -306,4 +316,40 @@ mod tests {
         assert!(!AgentProfileSettings::is_unmodified_default(&write, cx));
         assert!(AgentProfileSettings::is_unmodified_default(&minimal, cx));
     }
+
+    #[gpui::test]
+    fn review_profile_is_read_only(cx: &mut gpui::App) {
+        let store = SettingsStore::test(cx);
+        cx.set_global(store);
+        project::DisableAiSettings::register(cx);
+        AgentSettings::register(cx);
+
+        let review = AgentProfileId(builtin_profiles::REVIEW.into());
+        let profile = AgentSettings::get_global(cx)
+            .profiles
+            .get(&review)
+            .expect("the review profile is shipped by default")
+            .clone();
+
+        let mut enabled_tools = profile
+            .tools
+            .iter()
+            .filter(|(_, enabled)| **enabled)
+            .map(|(name, _)| name.as_ref())
+            .collect::<Vec<_>>();
+        enabled_tools.sort_unstable();
+        assert_eq!(
+            enabled_tools,
+            [
+                "add_review_comment",
+                "diagnostics",
+                "find_path",
+                "grep",
+                "list_directory",
+                "read_file",
+            ]
+        );
+        assert!(!profile.enable_all_context_servers);
+        assert!(AgentProfileSettings::is_unmodified_default(&review, cx));
+    }
 }
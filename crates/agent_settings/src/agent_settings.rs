@@ -16,10 +16,11 @@ use project::DisableAiSettings;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{
-    DockPosition, DockSide, IntoGpui, LanguageModelParameters, LanguageModelSelection,
-    NotifyWhenAgentWaiting, PlaySoundWhenAgentDone, RegisterSetting, Settings, SettingsContent,
-    SettingsStore, SidebarDockPosition, SidebarSide, ThinkingBlockDisplay, ToolPermissionMode,
-    update_settings_file, update_settings_file_with_completion,
+    DockPosition, DockSide, IntoGpui, LanguageModelParameters, LanguageModelPrice,
+    LanguageModelSelection, NotifyWhenAgentWaiting, PlaySoundWhenAgentDone, RegisterSetting,
+    Settings, SettingsContent, SettingsStore, SidebarDockPosition, SidebarSide,
+    ThinkingBlockDisplay, ToolPermissionMode, update_settings_file,
+    update_settings_file_with_completion,
 };
 use util::ResultExt as _;
 
@@ -172,6 +173,78 @@ pub struct AutoCompactSettings {
     pub threshold: AutoCompactThreshold,
 }
 
+/// Limits on what a single agent thread may spend. `None` means no limit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AgentBudget {
+    pub max_tokens: Option<u64>,
+    pub max_tool_calls: Option<u32>,
+    pub max_duration: Option<std::time::Duration>,
+    /// The maximum estimated cost in US dollars.
+    pub max_cost: Option<f64>,
+    /// The fraction of any limit at which to warn, in the range `(0.0, 1.0]`.
+    pub warning_threshold: f32,
+}
+
+impl Default for AgentBudget {
+    fn default() -> Self {
+        Self {
+            max_tokens: None,
+            max_tool_calls: None,
+            max_duration: None,
+            max_cost: None,
+            warning_threshold: Self::DEFAULT_WARNING_THRESHOLD,
+        }
+    }
+}
+
+impl AgentBudget {
+    pub const DEFAULT_WARNING_THRESHOLD: f32 = 0.8;
+
+    pub fn is_unlimited(&self) -> bool {
+        self.max_tokens.is_none()
+            && self.max_tool_calls.is_none()
+            && self.max_duration.is_none()
+            && self.max_cost.is_none()
+    }
+
+    /// Returns this budget with the limits set in `content` replacing its own.
+    pub fn overridden_by(self, content: &settings::AgentBudgetContent) -> Self {
+        Self {
+            max_tokens: content.max_tokens.or(self.max_tokens),
+            max_tool_calls: content.max_tool_calls.or(self.max_tool_calls),
+            max_duration: content
+                .max_minutes
+                .map(|minutes| std::time::Duration::from_secs(minutes * 60))
+                .or(self.max_duration),
+            max_cost: content.max_cost.or(self.max_cost),
+            warning_threshold: content
+                .warn_at_percent
+                .filter(|percent| (1..=100).contains(percent))
+                .map_or(self.warning_threshold, |percent| percent as f32 / 100.0),
+        }
+    }
+}
+
+/// What a language model costs, in US dollars per million tokens.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LanguageModelPricing {
+    pub input: f64,
+    pub output: f64,
+    pub cache_write: f64,
+    pub cache_read: f64,
+}
+
+impl LanguageModelPricing {
+    pub fn cost(&self, usage: &language_model::TokenUsage) -> f64 {
+        const PER: f64 = 1_000_000.0;
+        (usage.input_tokens as f64 * self.input
+            + usage.output_tokens as f64 * self.output
+            + usage.cache_creation_input_tokens as f64 * self.cache_write
+            + usage.cache_read_input_tokens as f64 * self.cache_read)
+            / PER
+    }
+}
+
 fn parse_auto_compact_threshold(raw: &str) -> anyhow::Result<AutoCompactThreshold> {
     let trimmed = raw.trim();
     if let Some(percent) = trimmed.strip_suffix('%') {
@@ -230,6 +303,8 @@ pub struct AgentSettings {
     pub single_file_review: bool,
     pub model_parameters: Vec<LanguageModelParameters>,
     pub auto_compact: AutoCompactSettings,
+    pub budget: AgentBudget,
+    pub model_prices: Vec<LanguageModelPrice>,
     pub enable_feedback: bool,
     pub expand_edit_card: bool,
     pub expand_terminal_card: bool,
@@ -242,6 +317,7 @@ pub struct AgentSettings {
     pub show_merge_conflict_indicator: bool,
     pub tool_permissions: ToolPermissions,
     pub sandbox_permissions: SandboxPermissions,
+    pub hooks: AgentHooks,
 }
 
 impl AgentSettings {
@@ -274,6 +350,45 @@ impl AgentSettings {
         }
     }
 
+    /// The pricing of the given model, taken from the last matching entry in
+    /// `model_prices`.
+    pub fn pricing_for_model(
+        model: &Arc<dyn LanguageModel>,
+        cx: &App,
+    ) -> Option<LanguageModelPricing> {
+        let settings = Self::get_global(cx);
+        let price = settings.model_prices.iter().rev().find(|price| {
+            price
+                .provider
+                .as_ref()
+                .is_none_or(|provider| provider.0 == model.provider_id().0)
+                && price
+                    .model
+                    .as_ref()
+                    .is_none_or(|price_model| *price_model == model.id().0)
+        })?;
+        let input = price.input.unwrap_or(0.0);
+        Some(LanguageModelPricing {
+            input,
+            output: price.output.unwrap_or(0.0),
+            cache_write: price.cache_write.unwrap_or(input),
+            cache_read: price.cache_read.unwrap_or(input),
+        })
+    }
+
+    /// The budget for threads using the given profile: the profile's limits,
+    /// falling back to the global `budget`.
+    pub fn budget_for_profile(&self, profile_id: &AgentProfileId) -> AgentBudget {
+        match self
+            .profiles
+            .get(profile_id)
+            .and_then(|profile| profile.budget.as_ref())
+        {
+            Some(profile_budget) => self.budget.overridden_by(profile_budget),
+            None => self.budget,
+        }
+    }
+
     pub fn set_message_editor_max_lines(&self) -> usize {
         self.message_editor_min_lines * 2
     }
@@ -543,6 +658,38 @@ impl CompiledRegex {
     }
 }
 
+/// Shell commands the agent runs at points in its lifecycle.
+#[derive(Clone, Debug, Default)]
+pub struct AgentHooks {
+    pub pre_tool_call: Vec<AgentHook>,
+    pub post_tool_call: Vec<AgentHook>,
+    pub stop: Vec<AgentHook>,
+}
+
+impl AgentHooks {
+    pub fn is_empty(&self) -> bool {
+        self.pre_tool_call.is_empty() && self.post_tool_call.is_empty() && self.stop.is_empty()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AgentHook {
+    pub command: String,
+    /// Restricts the hook to matching tool names. `None` matches every tool.
+    pub tools: Option<CompiledRegex>,
+    pub timeout: std::time::Duration,
+}
+
+impl AgentHook {
+    pub const DEFAULT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+    pub fn matches_tool(&self, tool_name: &str) -> bool {
+        self.tools
+            .as_ref()
+            .is_none_or(|tools| tools.is_match(tool_name))
+    }
+}
+
 pub const HARDCODED_SECURITY_DENIAL_MESSAGE: &str = "Blocked by built-in security rule. This operation is considered too \
      harmful to be allowed, and cannot be overridden by settings.";
 
@@ -804,6 +951,13 @@ impl Settings for AgentSettings {
                     threshold,
                 }
             },
+            budget: agent
+                .budget
+                .as_ref()
+                .map_or_else(AgentBudget::default, |budget| {
+                    AgentBudget::default().overridden_by(budget)
+                }),
+            model_prices: agent.model_prices,
             enable_feedback: agent.enable_feedback.unwrap(),
             expand_edit_card: agent.expand_edit_card.unwrap(),
             expand_terminal_card: agent.expand_terminal_card.unwrap(),
@@ -818,6 +972,7 @@ impl Settings for AgentSettings {
             show_merge_conflict_indicator: agent.show_merge_conflict_indicator.unwrap(),
             tool_permissions: compile_tool_permissions(agent.tool_permissions),
             sandbox_permissions: compile_sandbox_permissions(agent.sandbox_permissions),
+            hooks: compile_agent_hooks(agent.hooks),
         }
     }
 }
@@ -892,6 +1047,48 @@ fn insert_granted_subtree(
     subtrees.push(granted);
 }
 
+fn compile_agent_hooks(content: Option<settings::AgentHooksContent>) -> AgentHooks {
+    let Some(content) = content else {
+        return AgentHooks::default();
+    };
+
+    let compile = |hooks: Option<Vec<settings::AgentHookContent>>, kind: &str| {
+        hooks
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|hook| !hook.command.trim().is_empty())
+            .map(|hook| {
+                // A hook with an invalid tool pattern runs for every tool rather
+                // than silently never running, since hooks may enforce policy.
+                let tools = hook.tools.and_then(|pattern| {
+                    CompiledRegex::try_new(&pattern, true)
+                        .inspect_err(|error| {
+                            log::error!(
+                                "Invalid tools pattern in {kind} hook '{}': '{pattern}' - {error}",
+                                hook.command,
+                            );
+                        })
+                        .ok()
+                });
+                AgentHook {
+                    command: hook.command,
+                    tools,
+                    timeout: hook
+                        .timeout
+                        .map(std::time::Duration::from_secs)
+                        .unwrap_or(AgentHook::DEFAULT_TIMEOUT),
+                }
+            })
+            .collect()
+    };
+
+    AgentHooks {
+        pre_tool_call: compile(content.pre_tool_call, "pre_tool_call"),
+        post_tool_call: compile(content.post_tool_call, "post_tool_call"),
+        stop: compile(content.stop, "stop"),
+    }
+}
+
 fn compile_tool_permissions(content: Option<settings::ToolPermissionsContent>) -> ToolPermissions {
     let Some(content) = content else {
         return ToolPermissions::default();
@@ -1156,6 +1353,70 @@ mod tests {
         assert_eq!(rules.default, Some(ToolPermissionMode::Deny));
     }
 
+    #[test]
+    fn test_agent_hooks_compilation() {
+        let json = json!({
+            "pre_tool_call": [
+                { "command": "./check-edit.sh", "tools": "^(edit_file|delete_path)$" },
+                { "command": "  " }
+            ],
+            "post_tool_call": [
+                { "command": "cargo fmt", "tools": "edit_file(", "timeout": 5 }
+            ],
+            "stop": [
+                { "command": "notify-send done" }
+            ]
+        });
+        let content: settings::AgentHooksContent = serde_json::from_value(json).unwrap();
+        let hooks = compile_agent_hooks(Some(content));
+
+        assert_eq!(hooks.pre_tool_call.len(), 1, "blank commands are dropped");
+        let pre = &hooks.pre_tool_call[0];
+        assert!(pre.matches_tool("edit_file"));
+        assert!(!pre.matches_tool("read_file"));
+        assert_eq!(pre.timeout, AgentHook::DEFAULT_TIMEOUT);
+
+        let post = &hooks.post_tool_call[0];
+        assert!(post.tools.is_none(), "invalid patterns match every tool");
+        assert!(post.matches_tool("terminal"));
+        assert_eq!(post.timeout, std::time::Duration::from_secs(5));
+
+        assert_eq!(hooks.stop.len(), 1);
+        assert!(compile_agent_hooks(None).is_empty());
+    }
+
+    #[test]
+    fn test_budget_overrides() {
+        let global: settings::AgentBudgetContent = serde_json::from_value(json!({
+            "max_tool_calls": 100,
+            "max_minutes": 30,
+            "warn_at_percent": 0
+        }))
+        .unwrap();
+        let thirty_minutes = std::time::Duration::from_secs(30 * 60);
+        let budget = AgentBudget::default().overridden_by(&global);
+        assert_eq!(budget.max_tool_calls, Some(100));
+        assert_eq!(budget.max_duration, Some(thirty_minutes));
+        assert_eq!(
+            budget.warning_threshold,
+            AgentBudget::DEFAULT_WARNING_THRESHOLD,
+            "out-of-range thresholds are ignored"
+        );
+
+        let profile: settings::AgentBudgetContent = serde_json::from_value(json!({
+            "max_tool_calls": 10,
+            "max_cost": 2.5,
+            "warn_at_percent": 50
+        }))
+        .unwrap();
+        let budget = budget.overridden_by(&profile);
+        assert_eq!(budget.max_tool_calls, Some(10));
+        assert_eq!(budget.max_duration, Some(thirty_minutes));
+        assert_eq!(budget.max_cost, Some(2.5));
+        assert_eq!(budget.warning_threshold, 0.5);
+        assert!(AgentBudget::default().is_unlimited());
+    }
+
     #[test]
     fn test_tool_permissions_empty() {
         let permissions = compile_tool_permissions(None);
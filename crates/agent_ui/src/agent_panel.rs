@@ -1,5 +1,4 @@
 use std::{
-    cell::Cell,
     fmt,
     path::PathBuf,
     rc::Rc,
@@ -26,7 +25,7 @@ use zed_actions::{
     agent::{
         AddSelectionToThread, ConflictContent, LogoutAgent, OpenSettings, ReauthenticateAgent,
         ResetAgentZoom, ResetOnboarding, ResolveConflictedFilesWithAgent,
-        ResolveConflictsWithAgent, ReviewBranchDiff, SelectAgent,
+        ResolveConflictsWithAgent, ReviewBranchDiff, ReviewChanges, SelectAgent,
     },
     assistant::{
         FocusAgent, ManageSkills, OpenGlobalAgentsMdRules, OpenProjectAgentsMdRules, Toggle,
@@ -38,6 +37,7 @@ use crate::ExpandMessageEditor;
 use crate::ManageProfiles;
 use crate::agent_connection_store::AgentConnectionStore;
 use crate::completion_provider::{AgentContextSelection, AgentContextSource};
+use crate::project_task_host::AgentPanelTaskHost;
 use crate::terminal_thread_metadata_store::{
     TerminalThreadMetadata, TerminalThreadMetadataStore, compose_terminal_thread_title,
     terminal_title_without_prefix,
@@ -48,16 +48,18 @@ use crate::{
     NewNativeAgentThreadFromSummary,
 };
 use crate::{
-    AgentDiffPane, ConversationView, CopyThreadToClipboard, Follow, LoadThreadFromClipboard,
+    AgentDiffPane, ConversationView, CopyThreadToClipboard, ExportThreadAsJson,
+    ExportThreadAsMarkdown, Follow, ImportThreadFromFile, LoadThreadFromClipboard,
     NewTerminalThread, NewThread, OpenActiveThreadAsMarkdown, OpenAgentDiff, ResetFastModeWarnings,
-    ResetTrialEndUpsell, ResetTrialUpsell, ShowAllSidebarThreadMetadata, ShowThreadMetadata,
-    ToggleNewThreadMenu, ToggleOptionsMenu,
+    ResetTrialEndUpsell, ResetTrialUpsell, RunBestOfN, ShowAllSidebarThreadMetadata,
+    ShowThreadMetadata, ToggleNewThreadMenu, ToggleOptionsMenu, best_of_n,
     conversation_view::{
         AcpThreadViewEvent, RootThreadUpdated, ThreadView, reset_fast_mode_warnings,
     },
+    thread_export::{self, ThreadExportFormat},
     ui::{AgentNotification, AgentNotificationEvent, EndTrialUpsell},
 };
-use agent_settings::AgentSettings;
+use agent_settings::{AgentProfileId, AgentSettings, builtin_profiles};
 use ai_onboarding::AgentPanelOnboarding;
 use anyhow::{Context as _, Result, anyhow};
 #[cfg(feature = "audio")]
@@ -380,6 +382,9 @@ pub fn init(cx: &mut App) {
                         workspace.focus_panel::<AgentPanel>(window, cx);
                     }
                 })
+                .register_action(|workspace, action: &RunBestOfN, window, cx| {
+                    best_of_n::run_best_of_n(workspace, action, window, cx);
+                })
                 .register_action(|workspace, _: &NewTerminalThread, window, cx| {
                     if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
                         panel.update(cx, |panel, cx| {
@@ -519,6 +524,25 @@ pub fn init(cx: &mut App) {
                         });
                     }
                 })
+                .register_action(|workspace, _: &ExportThreadAsMarkdown, window, cx| {
+                    thread_export::export_active_thread(
+                        workspace,
+                        ThreadExportFormat::Markdown,
+                        window,
+                        cx,
+                    );
+                })
+                .register_action(|workspace, _: &ExportThreadAsJson, window, cx| {
+                    thread_export::export_active_thread(
+                        workspace,
+                        ThreadExportFormat::Json,
+                        window,
+                        cx,
+                    );
+                })
+                .register_action(|workspace, _: &ImportThreadFromFile, window, cx| {
+                    thread_export::import_thread(workspace, window, cx);
+                })
                 .register_action(|workspace, _: &ShowThreadMetadata, window, cx| {
                     if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
                         panel.update(cx, |panel, cx| {
@@ -548,11 +572,9 @@ pub fn init(cx: &mut App) {
                     let diff_uri = mention_uri.to_uri().to_string();
 
                     let content_blocks = vec![
-                        acp::ContentBlock::Text(acp::TextContent::new(
-                            "Please review this branch diff carefully. Point out any issues, \
-                             potential bugs, or improvement opportunities you find.\n\n"
-                                .to_string(),
-                        )),
+                        acp::ContentBlock::Text(acp::TextContent::new(build_review_prompt(
+                            "this branch diff",
+                        ))),
                         acp::ContentBlock::Resource(acp::EmbeddedResource::new(
                             acp::EmbeddedResourceResource::TextResourceContents(
                                 acp::TextResourceContents::new(
@@ -566,20 +588,28 @@ pub fn init(cx: &mut App) {
                     workspace.focus_panel::<AgentPanel>(window, cx);
 
                     panel.update(cx, |panel, cx| {
-                        panel.external_thread(
-                            None,
-                            None,
-                            None,
-                            None,
-                            Some(AgentInitialContent::ContentBlock {
-                                blocks: content_blocks,
-                                auto_submit: true,
-                            }),
-                            true,
-                            AgentThreadSource::GitPanel,
-                            window,
-                            cx,
-                        );
+                        panel.review_thread(content_blocks, window, cx);
+                    });
+                })
+                .register_action(|workspace, action: &ReviewChanges, window, cx| {
+                    let Some(panel) = workspace.panel::<AgentPanel>(cx) else {
+                        return;
+                    };
+
+                    let content_blocks = vec![
+                        acp::ContentBlock::Text(acp::TextContent::new(build_review_prompt(
+                            &action.subject,
+                        ))),
+                        acp::ContentBlock::Text(acp::TextContent::new(format!(
+                            "```diff\n{}\n```",
+                            action.diff_text.trim_end()
+                        ))),
+                    ];
+
+                    workspace.focus_panel::<AgentPanel>(window, cx);
+
+                    panel.update(cx, |panel, cx| {
+                        panel.review_thread(content_blocks, window, cx);
                     });
                 })
                 .register_action(
@@ -829,6 +859,19 @@ fn conflict_resource_block(conflict: &ConflictContent) -> acp::ContentBlock {
     ))
 }
 
+fn build_review_prompt(subject: &str) -> String {
+    indoc::formatdoc!(
+        "Please review {subject} carefully. Point out any issues, potential bugs, \
+        or improvement opportunities you find.
+
+        Leave each finding as a comment with the `add_review_comment` tool, on the \
+        lines it is about, then summarize your review. Don't edit any files. \
+        Comments refer to lines in the new version of each file in the diff.
+
+        "
+    )
+}
+
 fn build_conflict_resolution_prompt(conflicts: &[ConflictContent]) -> Vec<acp::ContentBlock> {
     if conflicts.is_empty() {
         return Vec::new();
@@ -3355,9 +3398,14 @@ impl AgentPanel {
         self.editor_text_if_in_memory(id, cx).flatten()
     }
 
-    pub fn editor_text_if_in_memory(&self, id: ThreadId, cx: &App) -> Option<Option<String>> {
-        let cv = self
-            .retained_threads
+    /// Returns the view of a thread the panel holds in memory, whether it is
+    /// retained, the draft, or the active thread.
+    pub(crate) fn conversation_view_for_thread(
+        &self,
+        id: ThreadId,
+        cx: &App,
+    ) -> Option<&Entity<ConversationView>> {
+        self.retained_threads
             .get(&id)
             .or_else(|| {
                 self.draft_thread
@@ -3371,7 +3419,11 @@ impl AgentPanel {
                     Some(conversation_view)
                 }
                 _ => None,
-            })?;
+            })
+    }
+
+    pub fn editor_text_if_in_memory(&self, id: ThreadId, cx: &App) -> Option<Option<String>> {
+        let cv = self.conversation_view_for_thread(id, cx)?;
         let tv = cv.read(cx).root_thread_view()?;
         let text = tv.read(cx).message_editor.read(cx).text(cx);
         if text.trim().is_empty() {
@@ -3386,22 +3438,7 @@ impl AgentPanel {
         id: ThreadId,
         cx: &App,
     ) -> Option<Vec<acp::ContentBlock>> {
-        let cv = self
-            .retained_threads
-            .get(&id)
-            .or_else(|| {
-                self.draft_thread
-                    .as_ref()
-                    .filter(|draft| draft.read(cx).thread_id == id)
-            })
-            .or_else(|| match &self.base_view {
-                BaseView::AgentThread { conversation_view }
-                    if conversation_view.read(cx).thread_id == id =>
-                {
-                    Some(conversation_view)
-                }
-                _ => None,
-            })?;
+        let cv = self.conversation_view_for_thread(id, cx)?;
         let thread_view = cv.read(cx).root_thread_view()?;
         let thread_view = thread_view.read(cx);
         Some(
@@ -4588,41 +4625,107 @@ impl AgentPanel {
         self.ensure_sibling_host_installed(&conversation_view, window, cx);
 
         if let Some(model) = model_override {
-            // The native thread is constructed asynchronously after the
-            // connection establishes. Wait for the first `RootThreadUpdated`
-            // event that yields a native thread, then apply the override once.
-            let applied = Cell::new(false);
-            cx.subscribe(
+            Self::on_native_thread(
                 &conversation_view,
-                move |_this, view, _event: &RootThreadUpdated, cx| {
-                    if applied.get() {
-                        return;
-                    }
-                    let Some(native_thread) = view.read(cx).as_native_thread(cx) else {
-                        return;
-                    };
-                    apply_native_model_override(&native_thread, &model, cx);
-                    applied.set(true);
-                },
-            )
-            .detach();
+                move |native_thread, cx| apply_native_model_override(native_thread, &model, cx),
+                cx,
+            );
         }
 
         AgentThread { conversation_view }
     }
 
-    fn ensure_sibling_host_installed(
-        &self,
+    /// Runs `f` once on the conversation's native thread. The native thread is
+    /// constructed asynchronously after the connection establishes, so this
+    /// waits for the first `RootThreadUpdated` event that yields one.
+    fn on_native_thread(
         conversation_view: &Entity<ConversationView>,
+        f: impl FnOnce(&Entity<agent::Thread>, &mut App) + 'static,
+        cx: &mut Context<Self>,
+    ) {
+        let mut f = Some(f);
+        cx.subscribe(
+            conversation_view,
+            move |_this, view, _event: &RootThreadUpdated, cx| {
+                if f.is_none() {
+                    return;
+                }
+                let Some(native_thread) = view.read(cx).as_native_thread(cx) else {
+                    return;
+                };
+                if let Some(f) = f.take() {
+                    f(&native_thread, cx);
+                }
+            },
+        )
+        .detach();
+    }
+
+    /// Opens a Zed Agent thread that reviews a diff with the read-only
+    /// `review` profile, so it can read the project and comment on the diff
+    /// but not edit it.
+    fn review_thread(
+        &mut self,
+        content_blocks: Vec<acp::ContentBlock>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if !cx.has_flag::<CreateThreadToolFeatureFlag>() {
+        if !self.has_open_project(cx) {
             return;
         }
+
+        // Only the Zed Agent can comment on diffs. Like an agent override in
+        // `create_thread_with_options`, this doesn't change the agent the
+        // user selected.
+        let selected_agent = self.selected_agent.clone();
+        let thread = self.create_agent_thread_with_server(
+            Agent::NativeAgent,
+            None,
+            None,
+            None,
+            None,
+            Some(AgentInitialContent::ContentBlock {
+                blocks: content_blocks,
+                auto_submit: true,
+            }),
+            None,
+            AgentThreadSource::GitPanel,
+            window,
+            cx,
+        );
+        self.set_selected_agent_and_persist(selected_agent, cx);
+        Self::on_native_thread(
+            &thread.conversation_view,
+            |native_thread, cx| {
+                native_thread.update(cx, |native_thread, cx| {
+                    native_thread.set_profile(AgentProfileId(builtin_profiles::REVIEW.into()), cx);
+                });
+            },
+            cx,
+        );
+        self.set_base_view(thread.into(), true, window, cx);
+    }
+
+    fn ensure_sibling_host_installed(
+        &self,
+        conversation_view: &Entity<ConversationView>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         let Some(native_connection) = conversation_view.read(cx).as_native_connection(cx) else {
             return;
         };
+        let task_host = Rc::new(AgentPanelTaskHost::new(
+            self.workspace.clone(),
+            window.window_handle(),
+        )) as Rc<dyn agent::ProjectTaskHost>;
+        native_connection.0.update(cx, |native_agent, _cx| {
+            native_agent.set_project_task_host(task_host);
+        });
+
+        if !cx.has_flag::<CreateThreadToolFeatureFlag>() {
+            return;
+        }
         let host = Rc::new(AgentPanelSiblingHost::new(
             cx.weak_entity(),
             window.window_handle(),
@@ -5651,6 +5754,18 @@ impl AgentPanel {
                                     });
                                 }
 
+                                if conversation_view.read(cx).as_native_thread(cx).is_some() {
+                                    menu = menu
+                                        .action(
+                                            "Export Thread as Markdown…",
+                                            Box::new(ExportThreadAsMarkdown),
+                                        )
+                                        .action(
+                                            "Export Thread as JSON…",
+                                            Box::new(ExportThreadAsJson),
+                                        );
+                                }
+
                                 menu = menu.separator();
                             }
                         }
@@ -10984,6 +11099,7 @@ mod tests {
             ui_scroll_position: None,
             sandboxed_terminal_temp_dir: None,
             sandbox_grants: Default::default(),
+            budget_usage: Default::default(),
         };
 
         let thread_store = cx.update(|cx| ThreadStore::global(cx));
@@ -11249,6 +11365,13 @@ mod tests {
             },
         );
         assert_eq!(resolved, Some("refs/remotes/origin/main".to_string()));
+
+        let resolved = git_ui_core::worktree_service::resolve_worktree_branch_target(
+            &NewWorktreeBranchTarget::Commit {
+                sha: "abc123".to_string(),
+            },
+        );
+        assert_eq!(resolved, Some("abc123".to_string()));
     }
 
     #[gpui::test]
@@ -0,0 +1,594 @@
+use crate::AgentPanel;
+use acp_thread::AcpThread;
+use action_log::{ActionLog, ReviewComment, ReviewCommentId};
+use collections::{HashMap, HashSet};
+use editor::{
+    Editor, EditorEvent, MultiBufferSnapshot,
+    display_map::{BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId},
+};
+use gpui::{AnyElement, App, Entity, Global, Subscription, WeakEntity, Window, prelude::*};
+use language::{Point, ToPoint as _};
+use std::sync::Arc;
+use ui::prelude::*;
+use workspace::Workspace;
+
+/// Shows the comments the agent left with the `add_review_comment` tool as
+/// blocks below the lines they are about, in every editor of the workspace
+/// that displays those lines, including the project, branch, staged and
+/// commit diffs.
+#[derive(Default)]
+pub struct AgentReview {
+    workspaces: HashMap<WeakEntity<Workspace>, WorkspaceReview>,
+}
+
+struct WorkspaceReview {
+    action_log: WeakEntity<ActionLog>,
+    editors: HashMap<WeakEntity<Editor>, ReviewingEditor>,
+    _action_log_subscription: Subscription,
+    _workspace_subscription: Option<Subscription>,
+}
+
+struct ReviewingEditor {
+    blocks: HashMap<ReviewCommentId, CustomBlockId>,
+    _subscription: Subscription,
+}
+
+struct AgentReviewGlobal(Entity<AgentReview>);
+
+impl Global for AgentReviewGlobal {}
+
+impl AgentReview {
+    fn global(cx: &mut App) -> Entity<Self> {
+        cx.try_global::<AgentReviewGlobal>()
+            .map(|global| global.0.clone())
+            .unwrap_or_else(|| {
+                let entity = cx.new(|_cx| Self::default());
+                let global = AgentReviewGlobal(entity.clone());
+                cx.set_global(global);
+                entity
+            })
+    }
+
+    /// Shows the review comments of `thread` in the editors of `workspace`,
+    /// replacing the ones of the previously active thread.
+    pub fn set_active_thread(
+        workspace: &WeakEntity<Workspace>,
+        thread: &Entity<AcpThread>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let action_log = thread.read(cx).action_log().clone();
+        Self::global(cx).update(cx, |this, cx| {
+            this.set_active_action_log(workspace, action_log, window, cx);
+        });
+    }
+
+    fn set_active_action_log(
+        &mut self,
+        workspace: &WeakEntity<Workspace>,
+        action_log: Entity<ActionLog>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(review) = self.workspaces.get(workspace) {
+            if review.action_log == action_log.downgrade() {
+                return;
+            }
+            self.clear_workspace(workspace, cx);
+        }
+
+        let action_log_subscription = cx.observe_in(&action_log, window, {
+            let workspace = workspace.clone();
+            move |this, _action_log, window, cx| {
+                this.update_workspace(&workspace, window, cx);
+            }
+        });
+        let workspace_subscription = workspace
+            .upgrade()
+            .map(|workspace| cx.subscribe_in(&workspace, window, Self::handle_workspace_event));
+
+        self.workspaces.insert(
+            workspace.clone(),
+            WorkspaceReview {
+                action_log: action_log.downgrade(),
+                editors: HashMap::default(),
+                _action_log_subscription: action_log_subscription,
+                _workspace_subscription: workspace_subscription,
+            },
+        );
+
+        self.update_workspace(workspace, window, cx);
+    }
+
+    fn clear_workspace(&mut self, workspace: &WeakEntity<Workspace>, cx: &mut Context<Self>) {
+        let Some(review) = self.workspaces.remove(workspace) else {
+            return;
+        };
+        for (editor, reviewing_editor) in review.editors {
+            if let Some(editor) = editor.upgrade() {
+                let block_ids = reviewing_editor
+                    .blocks
+                    .into_values()
+                    .collect::<HashSet<_>>();
+                editor.update(cx, |editor, cx| editor.remove_blocks(block_ids, None, cx));
+            }
+        }
+    }
+
+    fn handle_workspace_event(
+        &mut self,
+        workspace: &Entity<Workspace>,
+        event: &workspace::Event,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let workspace::Event::ItemAdded { item } = event
+            && let Some(editor) = item.act_as::<Editor>(cx)
+        {
+            self.update_editor(&workspace.downgrade(), editor, window, cx);
+        }
+    }
+
+    fn update_workspace(
+        &mut self,
+        workspace: &WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace_entity) = workspace.upgrade() else {
+            self.workspaces.remove(workspace);
+            return;
+        };
+        let Some(review) = self.workspaces.get_mut(workspace) else {
+            return;
+        };
+        review
+            .editors
+            .retain(|editor, _| editor.upgrade().is_some());
+
+        let editors = workspace_entity
+            .read(cx)
+            .items(cx)
+            .filter_map(|item| item.act_as::<Editor>(cx))
+            .collect::<Vec<_>>();
+        for editor in editors {
+            self.update_editor(workspace, editor, window, cx);
+        }
+    }
+
+    fn update_editor(
+        &mut self,
+        workspace: &WeakEntity<Workspace>,
+        editor: Entity<Editor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(review) = self.workspaces.get_mut(workspace) else {
+            return;
+        };
+        let Some(action_log) = review.action_log.upgrade() else {
+            return;
+        };
+        let reviewing_editor =
+            review
+                .editors
+                .entry(editor.downgrade())
+                .or_insert_with(|| ReviewingEditor {
+                    blocks: HashMap::default(),
+                    _subscription: cx.subscribe_in(&editor, window, {
+                        let workspace = workspace.clone();
+                        move |this, editor, event, window, cx| {
+                            if let EditorEvent::BufferRangesUpdated { .. } = event {
+                                this.update_editor(&workspace, editor.clone(), window, cx);
+                            }
+                        }
+                    }),
+                });
+
+        let comments = action_log.read(cx).review_comments();
+        let mut removed_block_ids = HashSet::default();
+        reviewing_editor.blocks.retain(|id, block_id| {
+            let keep = comments.iter().any(|comment| comment.id == *id);
+            if !keep {
+                removed_block_ids.insert(*block_id);
+            }
+            keep
+        });
+        let new_comments = comments
+            .iter()
+            .filter(|comment| !reviewing_editor.blocks.contains_key(&comment.id))
+            .cloned()
+            .collect::<Vec<_>>();
+        if removed_block_ids.is_empty() && new_comments.is_empty() {
+            return;
+        }
+
+        let action_log = action_log.downgrade();
+        let new_blocks = editor.update(cx, |editor, cx| {
+            editor.remove_blocks(removed_block_ids, None, cx);
+
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let (ids, blocks): (Vec<_>, Vec<_>) = new_comments
+                .into_iter()
+                .filter_map(|comment| {
+                    let anchor = snapshot
+                        .anchor_in_excerpt(comment.range.end)
+                        .or_else(|| anchor_in_same_file(&snapshot, &comment, cx))?;
+                    let height = comment_block_height(&comment);
+                    let id = comment.id;
+                    let action_log = action_log.clone();
+                    let workspace = workspace.clone();
+                    let block = BlockProperties {
+                        placement: BlockPlacement::Below(anchor),
+                        height: Some(height),
+                        style: BlockStyle::Sticky,
+                        render: Arc::new(move |cx| {
+                            render_review_comment(&comment, &action_log, &workspace, cx)
+                        }),
+                        priority: 0,
+                    };
+                    Some((id, block))
+                })
+                .unzip();
+            ids.into_iter()
+                .zip(editor.insert_blocks(blocks, None, cx))
+                .collect::<Vec<_>>()
+        });
+        reviewing_editor.blocks.extend(new_blocks);
+    }
+}
+
+/// Finds where to show `comment` in an editor that displays another buffer
+/// for the file it is about, such as the index text in the staged diff or the
+/// committed text in a commit view. The agent gives line numbers in the
+/// version of the file that the reviewed diff shows, so the comment goes on
+/// the same row of that buffer.
+fn anchor_in_same_file(
+    snapshot: &MultiBufferSnapshot,
+    comment: &ReviewComment,
+    cx: &App,
+) -> Option<editor::Anchor> {
+    let buffer = comment.buffer.read(cx);
+    let file = buffer.file()?;
+    let row = comment.range.end.to_point(&buffer.snapshot()).row;
+    snapshot
+        .buffers_with_paths()
+        .filter(|(displayed_buffer, _)| {
+            displayed_buffer.remote_id() != buffer.remote_id()
+                && displayed_buffer.file().is_some_and(|displayed_file| {
+                    displayed_file.worktree_id(cx) == file.worktree_id(cx)
+                        && displayed_file.path() == file.path()
+                })
+        })
+        .find_map(|(displayed_buffer, _)| {
+            let row = row.min(displayed_buffer.max_point().row);
+            let point = Point::new(row, displayed_buffer.line_len(row));
+            snapshot.anchor_in_excerpt(displayed_buffer.anchor_after(point))
+        })
+}
+
+fn comment_block_height(comment: &ReviewComment) -> u32 {
+    let comment_lines = comment.comment.lines().count().max(1);
+    let suggestion_lines = comment
+        .suggestion
+        .as_ref()
+        .map_or(0, |suggestion| suggestion.lines().count().max(1));
+    // One line for the buttons and one for the padding around the block.
+    (comment_lines + suggestion_lines + 2) as u32
+}
+
+fn render_review_comment(
+    comment: &ReviewComment,
+    action_log: &WeakEntity<ActionLog>,
+    workspace: &WeakEntity<Workspace>,
+    cx: &mut BlockContext,
+) -> AnyElement {
+    let id = comment.id;
+    let colors = cx.theme().colors();
+
+    v_flex()
+        .id(cx.block_id)
+        .ml(cx.margins.gutter.width)
+        .mr_4()
+        .my_1()
+        .p_2()
+        .gap_1()
+        .rounded_md()
+        .border_1()
+        .border_color(colors.border_variant)
+        .bg(colors.editor_background)
+        .child(
+            h_flex()
+                .items_start()
+                .gap_1p5()
+                .child(
+                    Icon::new(IconName::ZedAgent)
+                        .size(IconSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(Label::new(comment.comment.clone()).size(LabelSize::Small)),
+        )
+        .when_some(comment.suggestion.clone(), |this, suggestion| {
+            this.child(
+                div()
+                    .px_2()
+                    .rounded_sm()
+                    .bg(colors.editor_subheader_background)
+                    .font_buffer(cx)
+                    .text_sm()
+                    .child(suggestion),
+            )
+        })
+        .child(
+            h_flex()
+                .gap_1()
+                .when_some(comment.suggestion.clone(), |this, suggestion| {
+                    this.child(
+                        Button::new("apply-suggestion", "Apply Suggestion")
+                            .label_size(LabelSize::Small)
+                            .on_click({
+                                let buffer = comment.buffer.clone();
+                                let range = comment.range.clone();
+                                let action_log = action_log.clone();
+                                move |_, _window, cx| {
+                                    buffer.update(cx, |buffer, cx| {
+                                        buffer.edit(
+                                            [(range.clone(), suggestion.as_str())],
+                                            None,
+                                            cx,
+                                        )
+                                    });
+                                    action_log
+                                        .update(cx, |action_log, cx| {
+                                            action_log.dismiss_review_comment(id, cx)
+                                        })
+                                        .ok();
+                                }
+                            }),
+                    )
+                })
+                .child(
+                    Button::new("follow-up", "Follow Up")
+                        .label_size(LabelSize::Small)
+                        .on_click({
+                            let comment = comment.clone();
+                            let workspace = workspace.clone();
+                            move |_, window, cx| {
+                                let text = follow_up_text(&comment, cx);
+                                workspace
+                                    .update(cx, |workspace, cx| {
+                                        let Some(panel) = workspace.panel::<AgentPanel>(cx) else {
+                                            return;
+                                        };
+                                        workspace.focus_panel::<AgentPanel>(window, cx);
+                                        panel.update(cx, |panel, cx| {
+                                            if let Some(conversation_view) =
+                                                panel.active_conversation_view().cloned()
+                                            {
+                                                conversation_view.update(cx, |view, cx| {
+                                                    view.insert_text(&text, window, cx)
+                                                });
+                                            }
+                                        });
+                                    })
+                                    .ok();
+                            }
+                        }),
+                )
+                .child(
+                    Button::new("dismiss", "Dismiss")
+                        .label_size(LabelSize::Small)
+                        .on_click({
+                            let action_log = action_log.clone();
+                            move |_, _window, cx| {
+                                action_log
+                                    .update(cx, |action_log, cx| {
+                                        action_log.dismiss_review_comment(id, cx)
+                                    })
+                                    .ok();
+                            }
+                        }),
+                ),
+        )
+        .into_any_element()
+}
+
+/// Quotes `comment` with its location, for the user to continue the
+/// conversation about it in the thread that left it.
+fn follow_up_text(comment: &ReviewComment, cx: &App) -> String {
+    let buffer = comment.buffer.read(cx);
+    let snapshot = buffer.snapshot();
+    let line = comment.range.start.to_point(&snapshot).row + 1;
+    let location = match buffer.file() {
+        Some(file) => format!("{}:{line}", file.full_path(cx).display()),
+        None => format!("line {line}"),
+    };
+
+    let mut text = format!("About your comment on `{location}`:\n");
+    for line in comment.comment.lines() {
+        text.push_str("> ");
+        text.push_str(line);
+        text.push('\n');
+    }
+    text.push('\n');
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::{
+        ToPoint as _,
+        display_map::{Block, BlockId},
+    };
+    use fs::FakeFs;
+    use git_ui::{commit_view::CommitView, staged_diff::StagedDiff};
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::Project;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+    use workspace::MultiWorkspace;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme_settings::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+            git_ui::init(cx);
+        });
+    }
+
+    /// Returns the lines that the review comment blocks of `editor` are shown
+    /// below, along with whether they are lines of a historical version of
+    /// the file rather than the working copy.
+    fn lines_with_comments(
+        workspace: &Entity<Workspace>,
+        editor: &Entity<Editor>,
+        cx: &mut VisualTestContext,
+    ) -> Vec<(String, bool)> {
+        let block_ids = cx.update(|_, cx| {
+            AgentReview::global(cx)
+                .read(cx)
+                .workspaces
+                .get(&workspace.downgrade())
+                .and_then(|review| review.editors.get(&editor.downgrade()))
+                .map(|editor| editor.blocks.values().copied().collect::<Vec<_>>())
+                .unwrap_or_default()
+        });
+        editor.update(cx, |editor, cx| {
+            let snapshot = editor.display_snapshot(cx);
+            block_ids
+                .into_iter()
+                .filter_map(|block_id| {
+                    let Some(Block::Custom(block)) =
+                        snapshot.block_for_id(BlockId::Custom(block_id))
+                    else {
+                        return None;
+                    };
+                    let point = block.start().to_point(snapshot.buffer_snapshot());
+                    let (buffer, point) =
+                        snapshot.buffer_snapshot().point_to_buffer_point(point)?;
+                    let line = buffer
+                        .text_for_range(
+                            Point::new(point.row, 0)
+                                ..Point::new(point.row, buffer.line_len(point.row)),
+                        )
+                        .collect::<String>();
+                    let is_historic = buffer.file().is_some_and(|file| {
+                        matches!(file.disk_state(), language::DiskState::Historic { .. })
+                    });
+                    Some((line, is_historic))
+                })
+                .collect()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_comments_show_in_staged_and_commit_views(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "src": {
+                    "main.rs": "// print goodbye\nfn main() {\n    println!(\"goodbye world\");\n}\n",
+                }
+            }),
+        )
+        .await;
+        fs.set_head_for_repo(
+            Path::new(path!("/project/.git")),
+            &[(
+                "src/main.rs",
+                "fn main() {\n    println!(\"hello world\");\n}\n".into(),
+            )],
+            "0123456789abcdef0123456789abcdef01234567",
+        );
+        fs.set_index_for_repo(
+            Path::new(path!("/project/.git")),
+            &[(
+                "src/main.rs",
+                "fn main() {\n    println!(\"goodbye world\");\n}\n".into(),
+            )],
+        );
+
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = multi_workspace.read_with(cx, |mw, _| mw.workspace().clone());
+        cx.run_until_parked();
+
+        // The agent reviews the staged changes and comments on line 2 of the
+        // staged version of the file, which is line 3 of the working copy.
+        let buffer = project
+            .update(cx, |project, cx| {
+                let project_path = project.find_project_path("project/src/main.rs", cx)?;
+                Some(project.open_buffer(project_path, cx))
+            })
+            .unwrap()
+            .await
+            .unwrap();
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        action_log.update(cx, |action_log, cx| {
+            let snapshot = buffer.read(cx).snapshot();
+            let range = snapshot.anchor_before(Point::new(1, 0))
+                ..snapshot.anchor_after(Point::new(1, snapshot.line_len(1)));
+            action_log.add_review_comment(buffer.clone(), range, "Say hello".into(), None, cx);
+        });
+        cx.update(|window, cx| {
+            AgentReview::global(cx).update(cx, |review, cx| {
+                review.set_active_action_log(&workspace.downgrade(), action_log, window, cx);
+            });
+        });
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            StagedDiff::deploy_at(workspace, None, window, cx);
+        });
+        cx.run_until_parked();
+        let staged_editor = workspace.update(cx, |workspace, cx| {
+            assert!(workspace.active_item_as::<StagedDiff>(cx).is_some());
+            workspace
+                .active_item(cx)
+                .unwrap()
+                .act_as::<Editor>(cx)
+                .unwrap()
+        });
+        assert_eq!(
+            lines_with_comments(&workspace, &staged_editor, cx),
+            [("    println!(\"goodbye world\");".to_string(), true)]
+        );
+
+        let repository = project.read_with(cx, |project, cx| {
+            project.active_repository(cx).unwrap().downgrade()
+        });
+        cx.update(|window, cx| {
+            CommitView::open(
+                "HEAD".into(),
+                repository,
+                workspace.downgrade(),
+                None,
+                None,
+                window,
+                cx,
+            );
+        });
+        cx.run_until_parked();
+        let commit_editor = workspace.update(cx, |workspace, cx| {
+            assert!(workspace.active_item_as::<CommitView>(cx).is_some());
+            workspace
+                .active_item(cx)
+                .unwrap()
+                .act_as::<Editor>(cx)
+                .unwrap()
+        });
+        assert_eq!(
+            lines_with_comments(&workspace, &commit_editor, cx),
+            [("    println!(\"hello world\");".to_string(), true)]
+        );
+    }
+}
@@ -4,6 +4,8 @@ mod agent_diff;
 mod agent_model_selector;
 mod agent_panel;
 mod agent_registry_ui;
+mod agent_review;
+mod best_of_n;
 mod buffer_codegen;
 mod completion_provider;
 mod config_options;
@@ -24,11 +26,13 @@ mod mode_selector;
 mod model_selector;
 mod model_selector_popover;
 mod profile_selector;
+mod project_task_host;
 mod terminal_codegen;
 mod terminal_inline_assistant;
 pub mod terminal_thread_metadata_store;
 #[cfg(any(test, feature = "test-support"))]
 pub mod test_support;
+mod thread_export;
 mod thread_import;
 pub mod thread_metadata_store;
 pub mod thread_worktree_archive;
@@ -253,6 +257,12 @@ actions!(
         CopyThreadToClipboard,
         /// Loads a thread from the clipboard JSON for debugging.
         LoadThreadFromClipboard,
+        /// Exports the selected or active Zed Agent thread to a Markdown file.
+        ExportThreadAsMarkdown,
+        /// Exports the selected or active Zed Agent thread to a JSON file that can be imported elsewhere.
+        ExportThreadAsJson,
+        /// Imports a thread from an exported JSON file.
+        ImportThreadFromFile,
         /// Reruns the rules-to-skills migration.
         RerunRulesToSkillsMigration,
         /// Keeps the current suggestion or change.
@@ -382,6 +392,22 @@ pub struct ToggleCommandPattern {
 #[serde(deny_unknown_fields)]
 pub struct NewThread;
 
+/// Sends the prompt in the agent panel to several new threads at once, each
+/// working in its own git worktree, to compare their results and keep the best.
+#[derive(Default, Clone, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = agent)]
+#[serde(deny_unknown_fields)]
+pub struct RunBestOfN {
+    /// How many threads to start. Ignored when `models` is given.
+    /// Defaults to 3.
+    #[serde(default)]
+    pub attempts: Option<usize>,
+    /// The model for each thread, as `provider/model-id`. Starts one thread
+    /// per model. Defaults to the panel's current model for every thread.
+    #[serde(default)]
+    pub models: Vec<String>,
+}
+
 /// Creates a new external agent conversation thread.
 #[derive(Clone, PartialEq, Deserialize, JsonSchema, Action)]
 #[action(namespace = agent)]
@@ -1001,6 +1027,8 @@ mod tests {
                 enabled: false,
                 threshold: agent_settings::AutoCompactThreshold::DEFAULT,
             },
+            budget: Default::default(),
+            model_prices: vec![],
             enable_feedback: false,
             expand_edit_card: true,
             expand_terminal_card: true,
@@ -1010,6 +1038,7 @@ mod tests {
             message_editor_min_lines: 1,
             tool_permissions: Default::default(),
             sandbox_permissions: Default::default(),
+            hooks: Default::default(),
             show_turn_stats: false,
             show_merge_conflict_indicator: true,
             sidebar_side: Default::default(),
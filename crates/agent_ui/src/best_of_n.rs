@@ -0,0 +1,1329 @@
+//! Best-of-N runs: one prompt sent to several agent threads at once, each
+//! working in its own linked git worktree and possibly using a different model.
+//!
+//! A [`BestOfNView`] tracks the attempts and shows their changes side by side
+//! in [`MultiDiffView`]s. Picking a finished attempt copies its changes into
+//! the main worktree, all or nothing, then archives the other attempts'
+//! threads and removes their worktrees, the same way archiving a thread from
+//! the sidebar does.
+
+use std::mem;
+use std::path::PathBuf;
+
+use acp_thread::ThreadStatus;
+use agent_client_protocol::schema::v1 as acp;
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use fs::{CopyOptions, Fs, RemoveOptions, RenameOptions};
+use git::repository::RepoPath;
+use git::status::{DiffTreeType, TreeDiffStatus};
+use git_ui::multi_diff_view::MultiDiffView;
+use gpui::{
+    App, AsyncWindowContext, Context, Entity, EventEmitter, FocusHandle, Focusable, SharedString,
+    Subscription, Task, WeakEntity, Window,
+};
+use project::Project;
+use project::git_store::Repository;
+use ui::{Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    MultiWorkspace, RemovalIntent, Toast, Workspace,
+    item::{Item, ItemEvent},
+    notifications::NotificationId,
+};
+
+use crate::agent_panel::CreateThreadOptions;
+use crate::thread_metadata_store::{ThreadId, ThreadMetadataStore};
+use crate::thread_worktree_archive;
+use crate::{AgentInitialContent, AgentPanel, AgentThreadSource, ConversationView, RunBestOfN};
+
+const DEFAULT_ATTEMPTS: usize = 3;
+const MAX_ATTEMPTS: usize = 8;
+
+/// Starts a best-of-N run with the prompt in the agent panel's message editor.
+pub(crate) fn run_best_of_n(
+    workspace: &mut Workspace,
+    action: &RunBestOfN,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let message_editor = workspace
+        .panel::<AgentPanel>(cx)
+        .and_then(|panel| panel.read(cx).active_thread_view(cx))
+        .map(|thread_view| thread_view.read(cx).message_editor.clone())
+        .filter(|message_editor| !message_editor.read(cx).is_empty(cx));
+    let Some(message_editor) = message_editor else {
+        show_toast(
+            workspace,
+            "Write a prompt in the agent panel to run it best-of-N.",
+            cx,
+        );
+        return;
+    };
+
+    let project = workspace.project().clone();
+    if !project.read(cx).is_local() {
+        show_toast(
+            workspace,
+            "Best-of-N runs are only available in local projects.",
+            cx,
+        );
+        return;
+    }
+    let bases = project
+        .read(cx)
+        .repositories(cx)
+        .values()
+        .filter_map(|repository| {
+            let snapshot = repository.read(cx).snapshot();
+            Some(RepositoryBase {
+                sha: snapshot.head_commit.as_ref()?.sha.clone(),
+                common_dir: snapshot.common_dir_abs_path.to_path_buf(),
+                repository: repository.clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+    if bases.is_empty() {
+        show_toast(
+            workspace,
+            "Best-of-N runs need a git repository with at least one commit.",
+            cx,
+        );
+        return;
+    }
+
+    let models = if action.models.is_empty() {
+        let attempts = action
+            .attempts
+            .unwrap_or(DEFAULT_ATTEMPTS)
+            .clamp(2, MAX_ATTEMPTS);
+        vec![None; attempts]
+    } else {
+        action
+            .models
+            .iter()
+            .take(MAX_ATTEMPTS)
+            .cloned()
+            .map(Some)
+            .collect()
+    };
+    let (blocks, prompt) = message_editor.update(cx, |message_editor, cx| {
+        let blocks = message_editor.draft_content_blocks_snapshot(cx);
+        let prompt = message_editor.text(cx);
+        message_editor.clear(window, cx);
+        (blocks, prompt)
+    });
+
+    let view = BestOfNView::new(workspace, &prompt, blocks, models, bases, window, cx);
+    workspace.add_item_to_center(Box::new(view), window, cx);
+}
+
+fn show_toast(workspace: &mut Workspace, message: &'static str, cx: &mut Context<Workspace>) {
+    struct BestOfNToast;
+    workspace.show_toast(
+        Toast::new(NotificationId::unique::<BestOfNToast>(), message),
+        cx,
+    );
+}
+
+/// The commit a repository in the main worktree was at when the run started,
+/// which every attempt's worktree is based on.
+struct RepositoryBase {
+    repository: Entity<Repository>,
+    common_dir: PathBuf,
+    sha: SharedString,
+}
+
+struct Attempt {
+    model: Option<String>,
+    state: AttemptState,
+}
+
+enum AttemptState {
+    Creating,
+    Running {
+        workspace: WeakEntity<Workspace>,
+        thread_id: ThreadId,
+        conversation: Entity<ConversationView>,
+        thread_subscription: Option<Subscription>,
+        _conversation_subscription: Subscription,
+    },
+    Failed(SharedString),
+    Picked,
+    Discarded,
+}
+
+/// A file an attempt changed, relative to the commit the run started from.
+///
+/// Changes are listed without rename detection, so a renamed file is a
+/// deletion of its old path and an addition of its new one.
+struct FileChange {
+    repo_path: RepoPath,
+    main_path: PathBuf,
+    attempt_path: PathBuf,
+    deleted: bool,
+}
+
+impl FileChange {
+    /// Where the main worktree's file is kept while the change is applied, so
+    /// that it can be put back as it was, mode and symlink included.
+    fn backup_path(&self) -> PathBuf {
+        let mut file_name = std::ffi::OsString::from(".");
+        file_name.push(self.main_path.file_name().unwrap_or_default());
+        file_name.push(".best-of-n");
+        self.main_path.with_file_name(file_name)
+    }
+
+    /// Moves the main worktree's file out of the way. Returns whether there
+    /// was one to move.
+    async fn back_up(&self, fs: &dyn Fs) -> Result<bool> {
+        let exists = fs.metadata(&self.main_path).await?.is_some()
+            || fs.read_link(&self.main_path).await.is_ok();
+        if exists {
+            fs.rename(
+                &self.main_path,
+                &self.backup_path(),
+                RenameOptions::default(),
+            )
+            .await?;
+        }
+        Ok(exists)
+    }
+
+    /// Writes the attempt's version of the file, which must have been backed
+    /// up first. Symlinks are recreated rather than followed, and copying
+    /// keeps the file's mode.
+    async fn apply(&self, fs: &dyn Fs) -> Result<()> {
+        if self.deleted {
+            return Ok(());
+        }
+        if let Some(parent) = self.main_path.parent() {
+            fs.create_dir(parent).await?;
+        }
+        match fs.read_link(&self.attempt_path).await {
+            Ok(target) => fs.create_symlink(&self.main_path, target).await,
+            Err(_) => {
+                fs.copy_file(&self.attempt_path, &self.main_path, CopyOptions::default())
+                    .await
+            }
+        }
+    }
+
+    /// Puts back what was at the main worktree path before [`Self::apply`].
+    async fn restore(&self, backed_up: bool, fs: &dyn Fs) -> Result<()> {
+        fs.remove_file(
+            &self.main_path,
+            RemoveOptions {
+                ignore_if_not_exists: true,
+                ..Default::default()
+            },
+        )
+        .await?;
+        if backed_up {
+            fs.rename(
+                &self.backup_path(),
+                &self.main_path,
+                RenameOptions::default(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Copies an attempt's changes into the main worktree, all or nothing.
+///
+/// Nothing is written if any of the files also changed in the main worktree
+/// since the run started. Each file is moved aside before it's replaced, and
+/// if one can't be updated, every file updated before it is moved back.
+async fn apply_changes(
+    changes: &[FileChange],
+    main_changes: &HashSet<PathBuf>,
+    fs: &dyn Fs,
+) -> Result<()> {
+    let conflicts = changes
+        .iter()
+        .filter(|change| main_changes.contains(&change.main_path))
+        .map(|change| change.repo_path.as_unix_str())
+        .collect::<Vec<_>>();
+    anyhow::ensure!(
+        conflicts.is_empty(),
+        "these files also changed in the main worktree since the run started: {}",
+        conflicts.join(", ")
+    );
+
+    let mut backed_up = Vec::with_capacity(changes.len());
+    for change in changes {
+        let result = async {
+            backed_up.push(change.back_up(fs).await?);
+            change.apply(fs).await
+        }
+        .await
+        .with_context(|| format!("failed to update {:?}", change.main_path));
+        if result.is_err() {
+            for (change, backed_up) in changes.iter().zip(&backed_up).rev() {
+                change.restore(*backed_up, fs).await.log_err();
+            }
+            return result;
+        }
+    }
+
+    for (change, backed_up) in changes.iter().zip(backed_up) {
+        if backed_up {
+            fs.remove_file(&change.backup_path(), RemoveOptions::default())
+                .await
+                .log_err();
+        }
+    }
+    Ok(())
+}
+
+/// Archives the threads of discarded attempts, closes their workspaces and
+/// removes their worktrees, the same way archiving a thread from the sidebar
+/// does.
+fn discard_attempts(
+    workspaces: Vec<Entity<Workspace>>,
+    thread_ids: Vec<ThreadId>,
+    window: &mut Window,
+    cx: &mut App,
+) -> Task<Result<()>> {
+    let open_workspaces = thread_worktree_archive::all_open_workspaces(cx);
+    let mut roots = Vec::new();
+    for workspace in &workspaces {
+        let root_paths = workspace
+            .read(cx)
+            .project()
+            .read(cx)
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).abs_path())
+            .collect::<Vec<_>>();
+        roots.extend(root_paths.iter().filter_map(|path| {
+            thread_worktree_archive::build_root_plan(path, None, &open_workspaces, cx)
+        }));
+    }
+
+    let remove_workspaces = window
+        .root::<MultiWorkspace>()
+        .flatten()
+        .map(|multi_workspace| {
+            multi_workspace.update(cx, |multi_workspace, cx| {
+                multi_workspace.remove(workspaces, RemovalIntent::KeepProject, window, cx)
+            })
+        });
+    ThreadMetadataStore::global(cx).update(cx, |store, cx| {
+        for thread_id in thread_ids {
+            store.archive(thread_id, None, cx);
+        }
+    });
+
+    cx.spawn(async move |cx| {
+        if let Some(remove_workspaces) = remove_workspaces
+            && !remove_workspaces.await?
+        {
+            return Ok(());
+        }
+        // Persist each worktree's state before removing it, so the
+        // archived threads can be restored along with their changes.
+        for root in roots {
+            let archived_worktree_id =
+                thread_worktree_archive::persist_worktree_state(&root, cx).await?;
+            if let Err(error) = thread_worktree_archive::remove_root(root.clone(), cx).await {
+                thread_worktree_archive::rollback_persist(archived_worktree_id, &root, cx).await;
+                return Err(error);
+            }
+        }
+        Ok(())
+    })
+}
+
+pub struct BestOfNView {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    title: SharedString,
+    blocks: Vec<acp::ContentBlock>,
+    bases: Vec<RepositoryBase>,
+    attempts: Vec<Attempt>,
+    diff_views: Vec<WeakEntity<MultiDiffView>>,
+    creating_attempts: bool,
+    busy: bool,
+    message: Option<(SharedString, Color)>,
+    focus_handle: FocusHandle,
+    _create_attempts: Task<()>,
+    _pending: Task<()>,
+}
+
+impl BestOfNView {
+    fn new(
+        workspace: &Workspace,
+        prompt: &str,
+        blocks: Vec<acp::ContentBlock>,
+        models: Vec<Option<String>>,
+        bases: Vec<RepositoryBase>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        let title = prompt
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(|line| util::truncate_and_trailoff(line, 60))
+            .unwrap_or_default()
+            .into();
+        let attempts = models
+            .into_iter()
+            .map(|model| Attempt {
+                model,
+                state: AttemptState::Creating,
+            })
+            .collect::<Vec<_>>();
+
+        cx.new(|cx| {
+            let attempt_count = attempts.len();
+            let create_attempts = cx.spawn_in(window, async move |this, cx| {
+                // Creating worktrees of the same repository concurrently makes
+                // git fail on its lock files, so create them one at a time.
+                for ix in 0..attempt_count {
+                    let result = Self::create_attempt(&this, ix, cx).await;
+                    let updated = this.update(cx, |this, cx| {
+                        if let Err(error) = result {
+                            log::error!("Failed to start best-of-N attempt: {error:#}");
+                            this.attempts[ix].state =
+                                AttemptState::Failed(format!("{error:#}").into());
+                        }
+                        cx.notify();
+                    });
+                    if updated.is_err() {
+                        return;
+                    }
+                }
+                this.update(cx, |this, cx| {
+                    this.creating_attempts = false;
+                    cx.notify();
+                })
+                .ok();
+            });
+
+            Self {
+                workspace: workspace.weak_handle(),
+                project: workspace.project().clone(),
+                title,
+                blocks,
+                bases,
+                attempts,
+                diff_views: Vec::new(),
+                creating_attempts: true,
+                busy: false,
+                message: None,
+                focus_handle: cx.focus_handle(),
+                _create_attempts: create_attempts,
+                _pending: Task::ready(()),
+            }
+        })
+    }
+
+    /// Opens a workspace on new worktrees of the project's repositories and
+    /// starts a thread in its agent panel with the run's prompt.
+    async fn create_attempt(
+        this: &WeakEntity<Self>,
+        ix: usize,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<()> {
+        let (workspace, options, branch_target) = this.read_with(cx, |this, cx| {
+            let options = CreateThreadOptions {
+                title: Some(format!("{} ({}/{})", this.title, ix + 1, this.attempts.len()).into()),
+                initial_content: Some(AgentInitialContent::ContentBlock {
+                    blocks: this.blocks.clone(),
+                    auto_submit: true,
+                }),
+                agent: None,
+                model: this.attempts[ix].model.clone(),
+                work_dirs: None,
+            };
+            (this.workspace.clone(), options, this.branch_target(cx))
+        })?;
+
+        let action = zed_actions::CreateWorktree {
+            worktree_name: None,
+            branch_target: branch_target?,
+        };
+        let creation = workspace.update_in(cx, |workspace, window, cx| {
+            git_ui_core::worktree_service::create_worktree_workspace(
+                workspace, &action, window, None, cx,
+            )
+        })?;
+        let created = creation.await.context("failed to create worktree")?;
+        let panel = created
+            .workspace
+            .read_with(cx, |workspace, cx| workspace.panel::<AgentPanel>(cx))
+            .context("the new workspace has no agent panel")?;
+        let (thread_id, conversation) = panel.update_in(cx, |panel, window, cx| {
+            let thread_id = panel.create_thread_with_options(
+                options,
+                AgentThreadSource::AgentPanel,
+                window,
+                cx,
+            );
+            let conversation = panel.conversation_view_for_thread(thread_id, cx).cloned();
+            (thread_id, conversation)
+        })?;
+        let conversation = conversation.context("the new thread was not retained")?;
+
+        this.update(cx, |this, cx| {
+            let conversation_subscription = cx.observe(&conversation, move |this, _, cx| {
+                this.observe_thread(ix, cx);
+                cx.notify();
+            });
+            this.attempts[ix].state = AttemptState::Running {
+                workspace: created.workspace.downgrade(),
+                thread_id,
+                conversation,
+                thread_subscription: None,
+                _conversation_subscription: conversation_subscription,
+            };
+            this.observe_thread(ix, cx);
+        })
+    }
+
+    /// Where to create an attempt's worktrees so that they start from the
+    /// commit the run started from. A single target applies to every
+    /// repository, so with several repositories the attempt is based on their
+    /// current HEADs, which must not have moved since the run started.
+    fn branch_target(&self, cx: &App) -> Result<zed_actions::NewWorktreeBranchTarget> {
+        if let [base] = self.bases.as_slice() {
+            return Ok(zed_actions::NewWorktreeBranchTarget::Commit {
+                sha: base.sha.to_string(),
+            });
+        }
+        for base in &self.bases {
+            let snapshot = base.repository.read(cx).snapshot();
+            let head = snapshot.head_commit.as_ref().map(|commit| &commit.sha);
+            anyhow::ensure!(
+                head == Some(&base.sha),
+                "{} moved to another commit since the run started",
+                snapshot.work_directory_abs_path.display()
+            );
+        }
+        Ok(zed_actions::NewWorktreeBranchTarget::CurrentBranch)
+    }
+
+    /// Re-renders when an attempt's thread starts or stops generating. The
+    /// thread only exists once its agent has connected.
+    fn observe_thread(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let AttemptState::Running {
+            conversation,
+            thread_subscription,
+            ..
+        } = &mut self.attempts[ix].state
+        else {
+            return;
+        };
+        if thread_subscription.is_some() {
+            return;
+        }
+        if let Some(thread) = conversation.read(cx).root_thread(cx) {
+            *thread_subscription = Some(cx.observe(&thread, |_, _, cx| cx.notify()));
+        }
+    }
+
+    fn attempt_label(&self, ix: usize) -> SharedString {
+        format!("Attempt {}", ix + 1).into()
+    }
+
+    /// Whether an attempt's thread has stopped generating, so its changes
+    /// won't move under a pick.
+    fn attempt_is_idle(&self, ix: usize, cx: &App) -> bool {
+        let AttemptState::Running { conversation, .. } = &self.attempts[ix].state else {
+            return false;
+        };
+        conversation
+            .read(cx)
+            .root_thread(cx)
+            .is_some_and(|thread| thread.read(cx).status() == ThreadStatus::Idle)
+    }
+
+    fn attempt_workspace(&self, ix: usize) -> Option<Entity<Workspace>> {
+        match &self.attempts[ix].state {
+            AttemptState::Running { workspace, .. } => workspace.upgrade(),
+            _ => None,
+        }
+    }
+
+    /// Collects the files an attempt changed in each of its worktrees, pairing
+    /// them with the same files in the main worktree.
+    fn attempt_changes(
+        &self,
+        attempt_workspace: &Entity<Workspace>,
+        cx: &mut App,
+    ) -> Task<Result<Vec<FileChange>>> {
+        let repositories = attempt_workspace
+            .read(cx)
+            .project()
+            .read(cx)
+            .repositories(cx)
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut diffs = Vec::new();
+        for repository in repositories {
+            let snapshot = repository.read(cx).snapshot();
+            let Some(base) = self
+                .bases
+                .iter()
+                .find(|base| base.common_dir.as_path() == &*snapshot.common_dir_abs_path)
+            else {
+                continue;
+            };
+            let main = base.repository.read(cx).snapshot();
+            if main.work_directory_abs_path == snapshot.work_directory_abs_path {
+                continue;
+            }
+            // Untracked files don't show up in a diff against a commit.
+            let untracked = snapshot
+                .status()
+                .filter(|entry| entry.status.is_untracked())
+                .map(|entry| entry.repo_path)
+                .collect::<Vec<_>>();
+            let diff = repository.update(cx, |repository, cx| {
+                repository.diff_tree(
+                    DiffTreeType::MergeBaseWithWorktree {
+                        base: base.sha.clone(),
+                    },
+                    cx,
+                )
+            });
+            diffs.push((snapshot, main, untracked, diff));
+        }
+
+        cx.background_spawn(async move {
+            let mut changes = Vec::new();
+            for (snapshot, main, untracked, diff) in diffs {
+                let diff = diff.await??;
+                let change = |repo_path: RepoPath, deleted: bool| FileChange {
+                    main_path: main.repo_path_to_abs_path(&repo_path),
+                    attempt_path: snapshot.repo_path_to_abs_path(&repo_path),
+                    repo_path,
+                    deleted,
+                };
+                changes.extend(diff.entries.into_iter().map(|(repo_path, status)| {
+                    change(repo_path, matches!(status, TreeDiffStatus::Deleted { .. }))
+                }));
+                changes.extend(
+                    untracked
+                        .into_iter()
+                        .map(|repo_path| change(repo_path, false)),
+                );
+            }
+            changes.sort_by(|a, b| a.main_path.cmp(&b.main_path));
+            Ok(changes)
+        })
+    }
+
+    /// Returns the files in the main worktree that changed since the run
+    /// started, either in new commits or as uncommitted changes. An attempt
+    /// that also changed one of these can't be merged.
+    fn main_changes_since_base(&self, cx: &mut App) -> Task<Result<HashSet<PathBuf>>> {
+        let mut changed = HashSet::default();
+        let mut diffs = Vec::new();
+        for base in &self.bases {
+            let snapshot = base.repository.read(cx).snapshot();
+            changed.extend(
+                snapshot
+                    .status()
+                    .map(|entry| snapshot.repo_path_to_abs_path(&entry.repo_path)),
+            );
+            let diff = base.repository.update(cx, |repository, cx| {
+                repository.diff_tree(
+                    DiffTreeType::Since {
+                        base: base.sha.clone(),
+                        head: "HEAD".into(),
+                    },
+                    cx,
+                )
+            });
+            diffs.push((snapshot, diff));
+        }
+
+        cx.background_spawn(async move {
+            for (snapshot, diff) in diffs {
+                let diff = diff.await??;
+                changed.extend(
+                    diff.entries
+                        .into_keys()
+                        .map(|repo_path| snapshot.repo_path_to_abs_path(&repo_path)),
+                );
+            }
+            Ok(changed)
+        })
+    }
+
+    fn compare(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let columns = (0..self.attempts.len())
+            .filter_map(|ix| {
+                let attempt_workspace = self.attempt_workspace(ix)?;
+                Some((
+                    self.attempt_label(ix),
+                    self.attempt_changes(&attempt_workspace, cx),
+                ))
+            })
+            .collect::<Vec<_>>();
+        if columns.is_empty() {
+            return;
+        }
+
+        self.close_diff_views(window, cx);
+        self.busy = true;
+        self.message = None;
+        cx.notify();
+        self._pending = cx.spawn_in(window, async move |this, cx| {
+            let result = async {
+                let mut diff_columns = Vec::with_capacity(columns.len());
+                for (label, changes) in columns {
+                    let diff_pairs = changes
+                        .await?
+                        .into_iter()
+                        .map(|change| {
+                            [
+                                change.main_path.to_string_lossy().into_owned(),
+                                change.attempt_path.to_string_lossy().into_owned(),
+                            ]
+                        })
+                        .collect();
+                    diff_columns.push((label, diff_pairs));
+                }
+                workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        MultiDiffView::open_side_by_side(diff_columns, workspace, window, cx)
+                    })?
+                    .await
+            }
+            .await;
+            this.update(cx, |this, cx| {
+                this.busy = false;
+                match result {
+                    Ok(diff_views) => {
+                        this.diff_views = diff_views.iter().map(|view| view.downgrade()).collect();
+                    }
+                    Err(error) => {
+                        this.message = Some((
+                            format!("Failed to compare attempts: {error:#}").into(),
+                            Color::Error,
+                        ));
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn close_diff_views(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let diff_views = mem::take(&mut self.diff_views);
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            for pane in workspace.panes().to_vec() {
+                for diff_view in &diff_views {
+                    let Some(diff_view) = diff_view.upgrade() else {
+                        continue;
+                    };
+                    pane.update(cx, |pane, cx| {
+                        if pane.index_for_item(&diff_view).is_some() {
+                            let item_id = diff_view.entity_id();
+                            pane.close_item_by_id(item_id, workspace::SaveIntent::Skip, window, cx)
+                                .detach_and_log_err(cx);
+                        }
+                    });
+                }
+            }
+        });
+    }
+
+    fn open_attempt(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(attempt_workspace) = self.attempt_workspace(ix) else {
+            return;
+        };
+        if let Some(multi_workspace) = window.root::<MultiWorkspace>().flatten() {
+            multi_workspace.update(cx, |multi_workspace, cx| {
+                multi_workspace.activate(attempt_workspace, None, window, cx);
+            });
+        }
+    }
+
+    /// Copies the attempt's changes into the main worktree and discards the
+    /// other attempts.
+    fn pick(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.attempt_is_idle(ix, cx) {
+            return;
+        }
+        let Some(attempt_workspace) = self.attempt_workspace(ix) else {
+            return;
+        };
+        let changes = self.attempt_changes(&attempt_workspace, cx);
+        let main_changes = self.main_changes_since_base(cx);
+        let fs = self.project.read(cx).fs().clone();
+
+        self.busy = true;
+        self.message = None;
+        cx.notify();
+        self._pending = cx.spawn_in(window, async move |this, cx| {
+            let result = async {
+                let changes = changes.await?;
+                let main_changes = main_changes.await?;
+                apply_changes(&changes, &main_changes, fs.as_ref()).await?;
+                anyhow::Ok(changes.len())
+            }
+            .await;
+
+            this.update_in(cx, |this, window, cx| {
+                this.busy = false;
+                match result {
+                    Ok(applied) => {
+                        this.message = Some((
+                            format!(
+                                "Merged {applied} {} from {}.",
+                                if applied == 1 { "file" } else { "files" },
+                                this.attempt_label(ix)
+                            )
+                            .into(),
+                            Color::Success,
+                        ));
+                        this.discard_others(ix, window, cx);
+                    }
+                    Err(error) => {
+                        this.message =
+                            Some((format!("Failed to merge: {error:#}").into(), Color::Error));
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    /// Closes the comparison, then archives the threads of every attempt but
+    /// the winner and removes their workspaces and worktrees. The winner's
+    /// thread and worktree are kept.
+    fn discard_others(&mut self, winner: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.close_diff_views(window, cx);
+
+        let mut workspaces = Vec::new();
+        let mut thread_ids = Vec::new();
+        for (ix, attempt) in self.attempts.iter_mut().enumerate() {
+            if ix == winner {
+                attempt.state = AttemptState::Picked;
+                continue;
+            }
+            let AttemptState::Running {
+                workspace,
+                thread_id,
+                ..
+            } = mem::replace(&mut attempt.state, AttemptState::Discarded)
+            else {
+                continue;
+            };
+            thread_ids.push(thread_id);
+            workspaces.extend(workspace.upgrade());
+        }
+        discard_attempts(workspaces, thread_ids, window, cx).detach_and_log_err(cx);
+    }
+
+    fn render_attempt(&self, ix: usize, cx: &mut Context<Self>) -> impl IntoElement {
+        let attempt = &self.attempts[ix];
+        let (status, status_color): (SharedString, Color) = match &attempt.state {
+            AttemptState::Creating => ("Creating worktree…".into(), Color::Muted),
+            AttemptState::Running { conversation, .. } => {
+                match conversation.read(cx).root_thread(cx) {
+                    Some(thread) if thread.read(cx).status() == ThreadStatus::Idle => {
+                        ("Finished".into(), Color::Success)
+                    }
+                    Some(_) => ("Running".into(), Color::Accent),
+                    None => ("Starting…".into(), Color::Muted),
+                }
+            }
+            AttemptState::Failed(error) => (format!("Failed: {error}").into(), Color::Error),
+            AttemptState::Picked => ("Picked".into(), Color::Success),
+            AttemptState::Discarded => ("Discarded".into(), Color::Muted),
+        };
+        let is_running = matches!(attempt.state, AttemptState::Running { .. });
+        let can_pick = self.attempt_is_idle(ix, cx) && !self.creating_attempts && !self.busy;
+
+        h_flex()
+            .id(("attempt", ix))
+            .w_full()
+            .p_2()
+            .gap_2()
+            .justify_between()
+            .border_1()
+            .border_color(cx.theme().colors().border_variant)
+            .rounded_md()
+            .child(
+                v_flex()
+                    .min_w_0()
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(self.attempt_label(ix)))
+                            .when_some(attempt.model.clone(), |this, model| {
+                                this.child(Label::new(model).color(Color::Muted))
+                            }),
+                    )
+                    .child(
+                        Label::new(status)
+                            .size(LabelSize::Small)
+                            .color(status_color)
+                            .truncate(),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Button::new(("open-attempt", ix), "Open")
+                            .disabled(!is_running)
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.open_attempt(ix, window, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new(("pick-attempt", ix), "Pick")
+                            .style(ButtonStyle::Filled)
+                            .disabled(!can_pick)
+                            .tooltip(Tooltip::text(
+                                "Merge this attempt's changes into the main worktree and \
+                                 discard the others",
+                            ))
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.pick(ix, window, cx);
+                            })),
+                    ),
+            )
+    }
+}
+
+impl EventEmitter<ItemEvent> for BestOfNView {}
+
+impl Focusable for BestOfNView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for BestOfNView {
+    type Event = ItemEvent;
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        format!("Best of {}", self.attempts.len()).into()
+    }
+
+    fn tab_tooltip_text(&self, _cx: &App) -> Option<SharedString> {
+        Some(self.title.clone())
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn to_item_events(event: &Self::Event, f: &mut dyn FnMut(workspace::item::ItemEvent)) {
+        f(*event)
+    }
+}
+
+impl Render for BestOfNView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let can_compare = !self.busy
+            && !self.creating_attempts
+            && self
+                .attempts
+                .iter()
+                .any(|attempt| matches!(attempt.state, AttemptState::Running { .. }));
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_4()
+            .gap_4()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .justify_between()
+                    .child(
+                        v_flex()
+                            .min_w_0()
+                            .child(
+                                Headline::new(format!("Best of {}", self.attempts.len()))
+                                    .size(HeadlineSize::Large),
+                            )
+                            .child(Label::new(self.title.clone()).color(Color::Muted)),
+                    )
+                    .child(
+                        Button::new("compare-attempts", "Compare Side by Side")
+                            .style(ButtonStyle::Filled)
+                            .disabled(!can_compare)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.compare(window, cx);
+                            })),
+                    ),
+            )
+            .when_some(self.message.clone(), |this, (message, color)| {
+                this.child(Label::new(message).color(color))
+            })
+            .child(
+                v_flex()
+                    .id("attempts")
+                    .gap_2()
+                    .overflow_y_scroll()
+                    .children((0..self.attempts.len()).map(|ix| self.render_attempt(ix, cx))),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            cx.set_global(db::AppDatabase::test_new());
+            theme_settings::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+            ThreadMetadataStore::init_global(cx);
+        });
+    }
+
+    fn change(path: &str, deleted: bool) -> FileChange {
+        FileChange {
+            repo_path: RepoPath::new(path).unwrap(),
+            main_path: Path::new("/project").join(path),
+            attempt_path: Path::new("/attempt").join(path),
+            deleted,
+        }
+    }
+
+    async fn fake_fs(cx: &mut TestAppContext) -> Arc<FakeFs> {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/project",
+            json!({
+                "a.txt": "old a",
+                "c.txt": "old c",
+            }),
+        )
+        .await;
+        fs.insert_tree(
+            "/attempt",
+            json!({
+                "a.txt": "new a",
+                "b.txt": "new b",
+            }),
+        )
+        .await;
+        fs
+    }
+
+    async fn contents(fs: &FakeFs, path: &str) -> Option<String> {
+        fs.load(Path::new(path)).await.ok()
+    }
+
+    #[gpui::test]
+    async fn test_apply_changes(cx: &mut TestAppContext) {
+        let fs = fake_fs(cx).await;
+        let changes = [
+            change("a.txt", false),
+            change("b.txt", false),
+            change("c.txt", true),
+        ];
+
+        apply_changes(&changes, &HashSet::default(), fs.as_ref())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            contents(&fs, "/project/a.txt").await.as_deref(),
+            Some("new a")
+        );
+        assert_eq!(
+            contents(&fs, "/project/b.txt").await.as_deref(),
+            Some("new b")
+        );
+        assert_eq!(contents(&fs, "/project/c.txt").await, None);
+        assert!(
+            fs.files()
+                .iter()
+                .all(|path| !path.to_string_lossy().ends_with(".best-of-n")),
+            "backups should be removed once the merge succeeds"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_apply_changes_recreates_symlinks_and_renames(cx: &mut TestAppContext) {
+        let fs = fake_fs(cx).await;
+        fs.insert_symlink("/attempt/link", PathBuf::from("b.txt"))
+            .await;
+        fs.insert_file("/attempt/e.txt", b"old c".to_vec()).await;
+        // The attempt renamed `c.txt` to `e.txt`, which shows up as a deletion
+        // and an addition.
+        let changes = [
+            change("b.txt", false),
+            change("c.txt", true),
+            change("e.txt", false),
+            change("link", false),
+        ];
+
+        apply_changes(&changes, &HashSet::default(), fs.as_ref())
+            .await
+            .unwrap();
+
+        assert_eq!(contents(&fs, "/project/c.txt").await, None);
+        assert_eq!(
+            contents(&fs, "/project/e.txt").await.as_deref(),
+            Some("old c")
+        );
+        assert_eq!(
+            fs.read_link(Path::new("/project/link")).await.unwrap(),
+            PathBuf::from("b.txt")
+        );
+    }
+
+    #[gpui::test]
+    async fn test_apply_changes_refuses_files_changed_in_main(cx: &mut TestAppContext) {
+        let fs = fake_fs(cx).await;
+        let changes = [change("a.txt", false), change("b.txt", false)];
+        let main_changes = HashSet::from_iter([PathBuf::from("/project/a.txt")]);
+
+        let error = apply_changes(&changes, &main_changes, fs.as_ref())
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "these files also changed in the main worktree since the run started: a.txt"
+        );
+        assert_eq!(
+            contents(&fs, "/project/a.txt").await.as_deref(),
+            Some("old a")
+        );
+        assert_eq!(contents(&fs, "/project/b.txt").await, None);
+    }
+
+    #[gpui::test]
+    async fn test_apply_changes_rolls_back_on_failure(cx: &mut TestAppContext) {
+        let fs = fake_fs(cx).await;
+        // The attempt has no `d.txt` to copy, so updating it fails after the
+        // other files were already written.
+        let changes = [
+            change("a.txt", false),
+            change("b.txt", false),
+            change("c.txt", true),
+            change("d.txt", false),
+        ];
+
+        apply_changes(&changes, &HashSet::default(), fs.as_ref())
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            contents(&fs, "/project/a.txt").await.as_deref(),
+            Some("old a")
+        );
+        assert_eq!(contents(&fs, "/project/b.txt").await, None);
+        assert_eq!(
+            contents(&fs, "/project/c.txt").await.as_deref(),
+            Some("old c")
+        );
+        assert_eq!(contents(&fs, "/project/d.txt").await, None);
+    }
+
+    #[cfg(unix)]
+    #[gpui::test]
+    async fn test_apply_changes_keeps_modes_and_symlinks_on_disk(cx: &mut TestAppContext) {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        cx.executor().allow_parking();
+        let fs = fs::RealFs::new(None, cx.executor());
+        let dir = tempfile::tempdir().unwrap();
+        let main = dir.path().join("project");
+        let attempt = dir.path().join("attempt");
+        std::fs::create_dir_all(&main).unwrap();
+        std::fs::create_dir_all(&attempt).unwrap();
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        let set_mode = |path: &Path, mode| {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).unwrap()
+        };
+
+        std::fs::write(main.join("a.txt"), "old a").unwrap();
+        std::fs::write(main.join("run.sh"), "old run").unwrap();
+        set_mode(&main.join("run.sh"), 0o644);
+        std::fs::write(main.join("tool.sh"), "old tool").unwrap();
+        set_mode(&main.join("tool.sh"), 0o755);
+        std::os::unix::fs::symlink("a.txt", main.join("link")).unwrap();
+
+        std::fs::write(attempt.join("run.sh"), "new run").unwrap();
+        set_mode(&attempt.join("run.sh"), 0o755);
+        std::fs::write(attempt.join("tool.sh"), "new tool").unwrap();
+        set_mode(&attempt.join("tool.sh"), 0o644);
+        std::fs::write(attempt.join("link"), "not a link").unwrap();
+
+        let change = |path: &str| FileChange {
+            repo_path: RepoPath::new(path).unwrap(),
+            main_path: main.join(path),
+            attempt_path: attempt.join(path),
+            deleted: false,
+        };
+
+        // The attempt has no `missing.txt`, so the merge fails after `tool.sh`
+        // and `link` were replaced, and both are put back as they were.
+        apply_changes(
+            &[change("link"), change("tool.sh"), change("missing.txt")],
+            &HashSet::default(),
+            &fs,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            std::fs::read_link(main.join("link")).unwrap(),
+            PathBuf::from("a.txt")
+        );
+        assert_eq!(
+            std::fs::read_to_string(main.join("tool.sh")).unwrap(),
+            "old tool"
+        );
+        assert_eq!(mode(&main.join("tool.sh")), 0o755);
+
+        apply_changes(
+            &[change("link"), change("run.sh")],
+            &HashSet::default(),
+            &fs,
+        )
+        .await
+        .unwrap();
+        assert!(
+            !std::fs::symlink_metadata(main.join("link"))
+                .unwrap()
+                .is_symlink()
+        );
+        assert_eq!(
+            std::fs::read_to_string(main.join("link")).unwrap(),
+            "not a link"
+        );
+        assert_eq!(
+            std::fs::read_to_string(main.join("a.txt")).unwrap(),
+            "old a",
+            "replacing a symlink shouldn't write through it"
+        );
+        assert_eq!(
+            std::fs::read_to_string(main.join("run.sh")).unwrap(),
+            "new run"
+        );
+        assert_eq!(mode(&main.join("run.sh")), 0o755);
+        let mut leftovers = std::fs::read_dir(&main)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        leftovers.sort();
+        assert_eq!(leftovers, ["a.txt", "link", "run.sh", "tool.sh"]);
+    }
+
+    #[gpui::test]
+    async fn test_discarding_attempts_removes_their_worktrees(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/project",
+            json!({
+                ".git": {
+                    "worktrees": {
+                        "attempt": {
+                            "commondir": "../../",
+                            "HEAD": "ref: refs/heads/attempt",
+                        },
+                    },
+                },
+                "src": {},
+            }),
+        )
+        .await;
+        fs.insert_tree(
+            "/worktrees/project/attempt/project",
+            json!({
+                ".git": "gitdir: /project/.git/worktrees/attempt",
+                "src": {},
+            }),
+        )
+        .await;
+        fs.add_linked_worktree_for_repo(
+            Path::new("/project/.git"),
+            false,
+            git::repository::Worktree {
+                path: PathBuf::from("/worktrees/project/attempt/project"),
+                ref_name: Some("refs/heads/attempt".into()),
+                sha: "aaa".into(),
+                is_main: false,
+                is_bare: false,
+            },
+        )
+        .await;
+        crate::test_support::record_zed_created_worktree(
+            fs.as_ref(),
+            Path::new("/worktrees/project/attempt/project"),
+            None,
+            cx,
+        )
+        .await;
+        cx.update(|cx| <dyn Fs>::set_global(fs.clone(), cx));
+
+        let main_project = Project::test(fs.clone(), [Path::new("/project")], cx).await;
+        let attempt_project = Project::test(
+            fs.clone(),
+            [Path::new("/worktrees/project/attempt/project")],
+            cx,
+        )
+        .await;
+        main_project
+            .update(cx, |project, cx| project.git_scans_complete(cx))
+            .await;
+        attempt_project
+            .update(cx, |project, cx| project.git_scans_complete(cx))
+            .await;
+
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(main_project, window, cx));
+        let attempt_workspace = multi_workspace.update_in(cx, |multi_workspace, window, cx| {
+            multi_workspace.test_add_workspace(attempt_project, window, cx)
+        });
+        cx.run_until_parked();
+
+        cx.update(|window, cx| discard_attempts(vec![attempt_workspace], Vec::new(), window, cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        assert_eq!(
+            multi_workspace.read_with(cx, |multi_workspace, _| multi_workspace
+                .workspaces()
+                .count()),
+            1,
+            "the attempt's workspace should be closed"
+        );
+        assert!(
+            !fs.is_dir(Path::new("/worktrees/project/attempt/project"))
+                .await,
+            "the attempt's worktree should be removed from disk"
+        );
+        assert!(fs.is_dir(Path::new("/project")).await);
+    }
+}
@@ -19,6 +19,7 @@ use language::{Buffer, CodeLabel, CodeLabelBuilder, HighlightId};
 use lsp::CompletionContext;
 use multi_buffer::ToOffset as _;
 use ordered_float::OrderedFloat;
+use project::context_server_store::ContextServerResource;
 use project::lsp_store::{CompletionDocumentation, SymbolLocation};
 use project::{
     Completion, CompletionDisplayOptions, CompletionGroup, CompletionIntent, CompletionResponse,
@@ -163,6 +164,7 @@ pub(crate) enum PromptContextType {
     Skill,
     Diagnostics,
     BranchDiff,
+    McpResource,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -247,6 +249,7 @@ impl TryFrom<&str> for PromptContextType {
             "skill" => Ok(Self::Skill),
             "diagnostics" => Ok(Self::Diagnostics),
             "diff" => Ok(Self::BranchDiff),
+            "resource" => Ok(Self::McpResource),
             _ => Err(format!("Invalid context picker mode: {}", value)),
         }
     }
@@ -262,6 +265,7 @@ impl PromptContextType {
             Self::Skill => "skill",
             Self::Diagnostics => "diagnostics",
             Self::BranchDiff => "branch diff",
+            Self::McpResource => "resource",
         }
     }
 
@@ -274,6 +278,7 @@ impl PromptContextType {
             Self::Skill => "Skills",
             Self::Diagnostics => "Diagnostics",
             Self::BranchDiff => "Branch Diff",
+            Self::McpResource => "MCP Resources",
         }
     }
 
@@ -286,6 +291,7 @@ impl PromptContextType {
             Self::Skill => IconName::Sparkle,
             Self::Diagnostics => IconName::Warning,
             Self::BranchDiff => IconName::GitBranch,
+            Self::McpResource => IconName::ToolHammer,
         }
     }
 }
@@ -299,6 +305,7 @@ pub(crate) enum Match {
     Skill(AvailableSkill),
     Entry(EntryMatch),
     BranchDiff(BranchDiffMatch),
+    McpResource(ContextServerResource),
 }
 
 #[derive(Debug, Clone)]
@@ -317,6 +324,7 @@ impl Match {
             Match::Skill(_) => 1.,
             Match::Fetch(_) => 1.,
             Match::BranchDiff(_) => 1.,
+            Match::McpResource(_) => 1.,
         }
     }
 }
@@ -817,6 +825,68 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
         })
     }
 
+    fn completion_for_mcp_resource(
+        resource: ContextServerResource,
+        source_range: Range<Anchor>,
+        source: Arc<T>,
+        editor: WeakEntity<Editor>,
+        mention_set: WeakEntity<MentionSet>,
+        workspace: Entity<Workspace>,
+        cx: &mut App,
+    ) -> Completion {
+        let ContextServerResource {
+            server_id,
+            resource,
+        } = resource;
+        let name: Arc<str> = resource.title.unwrap_or(resource.name).into();
+        let uri = MentionUri::McpResource {
+            server_id: server_id.0.to_string(),
+            uri: resource.uri.clone(),
+            name: name.to_string(),
+        };
+        let new_text = format!("{} ", uri.as_link());
+        let new_text_len = new_text.len();
+        let icon_path = uri.icon_path(cx);
+        let server_highlight_id = cx
+            .theme()
+            .syntax()
+            .highlight_id("variable")
+            .map(HighlightId::new);
+        let label = build_slash_item_label(
+            &name,
+            Some(&SharedString::from(server_id.0.to_string())),
+            server_highlight_id,
+        );
+        let documentation = resource
+            .description
+            .unwrap_or_else(|| resource.uri.to_string());
+        Completion {
+            replace_range: source_range.clone(),
+            new_text,
+            label,
+            documentation: Some(CompletionDocumentation::MultiLinePlainText(
+                documentation.into(),
+            )),
+            source: project::CompletionSource::Custom,
+            icon_path: Some(icon_path),
+            icon_color: None,
+            match_start: None,
+            snippet_deduplication_key: None,
+            insert_text_mode: None,
+            confirm: Some(confirm_completion_callback(
+                name.to_string().into(),
+                source_range.start,
+                new_text_len - 1,
+                uri,
+                source,
+                editor,
+                mention_set,
+                workspace,
+            )),
+            group: None,
+        }
+    }
+
     pub(crate) fn completion_for_action(
         action: PromptContextAction,
         source_range: Range<Anchor>,
@@ -1159,6 +1229,19 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
 
             Some(PromptContextType::BranchDiff) => Task::ready(Vec::new()),
 
+            Some(PromptContextType::McpResource) => {
+                let resources = mcp_resources(&workspace, cx);
+                let search_resources_task =
+                    search_mcp_resources(query, cancellation_flag, resources, cx);
+                cx.background_spawn(async move {
+                    search_resources_task
+                        .await
+                        .into_iter()
+                        .map(Match::McpResource)
+                        .collect()
+                })
+            }
+
             None if query.is_empty() => {
                 let recent_task = self.recent_context_picker_entries(&workspace, cx);
                 let entries = self
@@ -1198,7 +1281,17 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
                 let executor = cx.background_executor().clone();
 
                 let search_files_task =
-                    search_files(query.clone(), cancellation_flag, &workspace, cx);
+                    search_files(query.clone(), cancellation_flag.clone(), &workspace, cx);
+
+                let search_resources_task = if self
+                    .source
+                    .supports_context(PromptContextType::McpResource, cx)
+                {
+                    let resources = mcp_resources(&workspace, cx);
+                    search_mcp_resources(query.clone(), cancellation_flag, resources, cx)
+                } else {
+                    Task::ready(Vec::new())
+                };
 
                 let entries = self.available_context_picker_entries(&workspace, cx);
                 let entry_candidates = entries
@@ -1222,6 +1315,12 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
                         .into_iter()
                         .map(Match::File)
                         .collect::<Vec<_>>();
+                    matches.extend(
+                        search_resources_task
+                            .await
+                            .into_iter()
+                            .map(Match::McpResource),
+                    );
 
                     let entry_matches = fuzzy::match_strings(
                         &entry_candidates,
@@ -1391,6 +1490,14 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
             entries.push(PromptContextEntry::Mode(PromptContextType::Fetch));
         }
 
+        if self
+            .source
+            .supports_context(PromptContextType::McpResource, cx)
+            && !mcp_resources(workspace, cx).is_empty()
+        {
+            entries.push(PromptContextEntry::Mode(PromptContextType::McpResource));
+        }
+
         if self
             .source
             .supports_context(PromptContextType::Diagnostics, cx)
@@ -1870,6 +1977,17 @@ impl<T: PromptCompletionProviderDelegate> CompletionProvider for PromptCompletio
                                             cx,
                                         ))
                                     }
+                                    Match::McpResource(resource) => {
+                                        Some(Self::completion_for_mcp_resource(
+                                            resource,
+                                            source_range.clone(),
+                                            source.clone(),
+                                            editor.clone(),
+                                            mention_set.clone(),
+                                            workspace.clone(),
+                                            cx,
+                                        ))
+                                    }
                                 };
                                 if let Some(completion) = &mut completion {
                                     completion.group = group;
@@ -2528,6 +2646,59 @@ pub(crate) fn search_skills(
     })
 }
 
+fn mcp_resources(workspace: &Entity<Workspace>, cx: &App) -> Vec<ContextServerResource> {
+    workspace
+        .read(cx)
+        .project()
+        .read(cx)
+        .context_server_store()
+        .read(cx)
+        .resources()
+}
+
+fn search_mcp_resources(
+    query: String,
+    cancellation_flag: Arc<AtomicBool>,
+    resources: Vec<ContextServerResource>,
+    cx: &mut App,
+) -> Task<Vec<ContextServerResource>> {
+    if resources.is_empty() {
+        return Task::ready(Vec::new());
+    }
+    if query.is_empty() {
+        return Task::ready(resources);
+    }
+    let executor = cx.background_executor().clone();
+    cx.background_spawn(async move {
+        let candidates = resources
+            .iter()
+            .enumerate()
+            .map(|(id, resource)| {
+                let name = resource
+                    .resource
+                    .title
+                    .as_deref()
+                    .unwrap_or(&resource.resource.name);
+                StringMatchCandidate::new(id, name)
+            })
+            .collect::<Vec<_>>();
+        let matches = fuzzy::match_strings(
+            &candidates,
+            &query,
+            false,
+            true,
+            100,
+            &cancellation_flag,
+            executor,
+        )
+        .await;
+        matches
+            .into_iter()
+            .map(|mat| resources[mat.candidate_id].clone())
+            .collect()
+    })
+}
+
 pub struct SymbolMatch {
     pub symbol: Symbol,
 }
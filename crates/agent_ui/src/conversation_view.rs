@@ -83,6 +83,7 @@ use crate::agent_connection_store::{
     AgentConnectedState, AgentConnectionEntryEvent, AgentConnectionStore,
 };
 use crate::agent_diff::AgentDiff;
+use crate::agent_review::AgentReview;
 use crate::completion_provider::{AgentContextSelection, AvailableSkill};
 use crate::entry_view_state::{EntryViewEvent, ViewEvent};
 use crate::message_editor::{InputAttempt, MessageEditor, MessageEditorEvent};
@@ -1284,6 +1285,7 @@ impl ConversationView {
         }
 
         AgentDiff::set_active_thread(&self.workspace, thread.clone(), window, cx);
+        AgentReview::set_active_thread(&self.workspace, &thread, window, cx);
 
         let connection = thread.read(cx).connection().clone();
         let session_id = thread.read(cx).session_id().clone();
@@ -3194,6 +3196,16 @@ impl ConversationView {
         }
     }
 
+    pub(crate) fn insert_text(&self, text: &str, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(active_thread) = self.active_thread() {
+            active_thread.update(cx, |thread, cx| {
+                thread.active_editor(cx).update(cx, |editor, cx| {
+                    editor.insert_text(text, window, cx);
+                })
+            });
+        }
+    }
+
     fn current_model_name(&self, cx: &App) -> SharedString {
         // For native agent (Zed Agent), use the specific model name (e.g., "Claude 3.5 Sonnet")
         // For ACP agents, use the agent name (e.g., "Claude Agent", "Gemini CLI")
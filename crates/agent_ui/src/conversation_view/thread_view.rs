@@ -1,5 +1,5 @@
 use crate::{
-    DEFAULT_THREAD_TITLE, SelectPermissionGranularity,
+    DEFAULT_THREAD_TITLE, ExportThreadAsJson, ExportThreadAsMarkdown, SelectPermissionGranularity,
     agent_configuration::configure_context_server_modal::default_markdown_style,
     conversation_view::thread_search_bar::{ThreadSearchBar, ThreadSearchBarEvent},
     open_abs_path_at_point,
@@ -13,7 +13,7 @@ use acp_thread::{
     SandboxFallbackAuthorizationDetails, SandboxNotAppliedReason, decode_path_escapes,
 };
 use agent::{
-    SandboxStatusKey, SandboxStatusRefresh, SkillLoadingIssue, SkillLoadingIssueKind,
+    BudgetLevel, SandboxStatusKey, SandboxStatusRefresh, SkillLoadingIssue, SkillLoadingIssueKind,
     SkillLoadingIssuesUpdated, ThreadSandbox, VerifiedSandboxStatus,
 };
 use agent_settings::UserAgentsMd;
@@ -587,6 +587,7 @@ pub struct ThreadView {
     pub(super) thread_error: Option<ThreadError>,
     pub thread_error_markdown: Option<Entity<Markdown>>,
     pub token_limit_callout_dismissed: bool,
+    pub budget_callout_dismissed: bool,
     pub last_token_limit_telemetry: Option<acp_thread::TokenUsageRatio>,
     thread_feedback: ThreadFeedbackState,
     pub list_state: ListState,
@@ -1007,6 +1008,7 @@ impl ThreadView {
             thread_error: None,
             thread_error_markdown: None,
             token_limit_callout_dismissed: false,
+            budget_callout_dismissed: false,
             last_token_limit_telemetry: None,
             thread_feedback: Default::default(),
             expanded_tool_call_raw_inputs: HashSet::default(),
@@ -2976,6 +2978,7 @@ impl ThreadView {
         self.thread_error = None;
         self.thread_error_markdown = None;
         self.token_limit_callout_dismissed = true;
+        self.budget_callout_dismissed = true;
         cx.notify();
     }
 
@@ -4443,6 +4446,7 @@ impl ThreadView {
                                     .min_w_0()
                                     .flex_wrap()
                                     .gap_1()
+                                    .children(self.render_budget_usage(cx))
                                     .children(self.render_token_usage(cx))
                                     .children(self.profile_selector.clone())
                                     .map(|this| match self.config_options_view.clone() {
@@ -4867,6 +4871,40 @@ impl ThreadView {
         }
     }
 
+    fn render_budget_usage(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let status = self.as_native_thread(cx)?.read(cx).budget_status(cx)?;
+        let most_used = *status.most_used()?;
+        let color = match status.level() {
+            BudgetLevel::Normal => Color::Muted,
+            BudgetLevel::Warning => Color::Warning,
+            BudgetLevel::Exceeded => Color::Error,
+        };
+        let percentage = format!("{}%", (most_used.ratio().min(1.0) * 100.0).round() as u32);
+        let details = status
+            .limits
+            .iter()
+            .map(|limit| limit.describe())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Some(
+            h_flex()
+                .id("budget_usage")
+                .flex_shrink_0()
+                .gap_0p5()
+                .mr_1()
+                .child(
+                    Icon::new(IconName::CountdownTimer)
+                        .size(IconSize::XSmall)
+                        .color(color),
+                )
+                .child(Label::new(percentage).size(LabelSize::Small).color(color))
+                .tooltip(move |_window, cx| {
+                    Tooltip::with_meta("Thread Budget", None, details.clone(), cx)
+                }),
+        )
+    }
+
     fn fast_mode_available(&self, cx: &Context<Self>) -> bool {
         self.as_native_thread(cx)
             .and_then(|thread| thread.read(cx).model())
@@ -7644,6 +7682,8 @@ impl ThreadView {
                             }
                         });
 
+                    let is_native = this.as_native_thread(cx).is_some();
+
                     menu.when_some(focus, |menu, focus| menu.context(focus))
                         .when_some(context_menu_link, |menu, url| {
                             menu.entry("Copy Link", None, move |_, cx| {
@@ -7660,6 +7700,13 @@ impl ThreadView {
                         .separator()
                         .item(scroll_item)
                         .item(open_thread_as_markdown)
+                        .when(is_native, |menu| {
+                            menu.action(
+                                "Export Thread as Markdown…",
+                                Box::new(ExportThreadAsMarkdown),
+                            )
+                            .action("Export Thread as JSON…", Box::new(ExportThreadAsJson))
+                        })
                 })
             })
             .into_any_element()
@@ -11891,6 +11938,60 @@ impl ThreadView {
         )
     }
 
+    fn render_budget_callout(&self, cx: &mut Context<Self>) -> Option<Callout> {
+        if self.budget_callout_dismissed {
+            return None;
+        }
+        let status = self.as_native_thread(cx)?.read(cx).budget_status(cx)?;
+
+        let (severity, icon, title, limit) = match status.level() {
+            BudgetLevel::Normal => return None,
+            BudgetLevel::Warning => (
+                Severity::Warning,
+                IconName::Warning,
+                "Thread approaching its budget",
+                status.most_used()?,
+            ),
+            BudgetLevel::Exceeded => (
+                Severity::Error,
+                IconName::XCircle,
+                "Thread reached its budget",
+                status.exceeded()?,
+            ),
+        };
+
+        let description = format!(
+            "It has used {}. To continue, raise the limits in the `budget` settings or start a new thread and @-mention this one",
+            limit.describe()
+        );
+
+        Some(
+            Callout::new()
+                .border_position(self.callout_border_position())
+                .severity(severity)
+                .icon(icon)
+                .title(title)
+                .description(description)
+                .actions_slot(
+                    h_flex().gap_0p5().child(
+                        Button::new("start-new-thread-from-budget", "Start New Thread")
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                let session_id = this.thread.read(cx).session_id().clone();
+                                window.dispatch_action(
+                                    crate::NewNativeAgentThreadFromSummary {
+                                        from_session_id: session_id,
+                                    }
+                                    .boxed_clone(),
+                                    cx,
+                                );
+                            })),
+                    ),
+                )
+                .dismiss_action(self.dismiss_error_button(cx)),
+        )
+    }
+
     /// Returns the model to offer as a downgrade target when the current model
     /// requires data retention consent (e.g. Opus 4.8 for Fable).
     fn data_retention_fallback_model(&self, cx: &App) -> Option<Arc<dyn LanguageModel>> {
@@ -12485,6 +12586,7 @@ impl Render for ThreadView {
                 |this, version| this.child(self.render_new_version_callout(&version, cx)),
             )
             .children(self.render_token_limit_callout(cx))
+            .children(self.render_budget_callout(cx))
             .children(self.render_request_elicitations(cx))
             .child(self.render_message_editor(window, cx))
     }
@@ -12609,6 +12711,7 @@ pub(crate) fn open_link(
             MentionUri::TerminalSelection { .. } => {}
             MentionUri::GitDiff { .. } => {}
             MentionUri::MergeConflict { .. } => {}
+            MentionUri::McpResource { .. } => {}
             MentionUri::Rule { name, .. } => {
                 crate::ui::open_migrated_rule(workspace, &name, window, cx);
             }
@@ -367,7 +367,7 @@ impl<T: 'static> PromptEditor<T> {
             editor
         });
 
-        self.mention_set.update(cx, |mention_set, _cx| {
+        self.mention_set.update(cx, |mention_set, cx| {
             debug_assert_eq!(
                 creases.len(),
                 mention_set.creases().len(),
@@ -375,11 +375,12 @@ impl<T: 'static> PromptEditor<T> {
             );
 
             let mentions = mention_set
-                .clear()
+                .clear(cx)
+                .into_iter()
                 .zip(creases)
                 .map(|((_, value), id)| (id, value))
                 .collect::<HashMap<_, _>>();
-            mention_set.set_mentions(mentions);
+            mention_set.set_mentions(mentions, cx);
         });
 
         self.assign_completion_provider(cx);
@@ -435,8 +436,9 @@ impl<T: 'static> PromptEditor<T> {
             EditorEvent::Edited { .. } => {
                 let snapshot = editor.update(cx, |editor, cx| editor.snapshot(window, cx));
 
-                self.mention_set
-                    .update(cx, |mention_set, _cx| mention_set.remove_invalid(&snapshot));
+                self.mention_set.update(cx, |mention_set, cx| {
+                    mention_set.remove_invalid(&snapshot, cx)
+                });
 
                 if let Some(workspace) = Workspace::for_window(window, cx) {
                     workspace.update(cx, |workspace, cx| {
@@ -5,6 +5,7 @@ use agent_client_protocol::schema::v1 as acp;
 use agent_servers::{AgentServer, AgentServerDelegate};
 use anyhow::{Context as _, Result, anyhow};
 use collections::{HashMap, HashSet};
+use context_server::{ContextServerId, types::ResourceContentsType};
 use editor::{
     Anchor, Editor, EditorSnapshot, FoldPlaceholder, ToOffset,
     display_map::{Crease, CreaseId, CreaseMetadata, FoldId},
@@ -13,7 +14,7 @@ use editor::{
 use futures::{AsyncReadExt as _, FutureExt as _, future::Shared};
 use gpui::{
     AppContext, ClipboardEntry, Context, Empty, Entity, EntityId, Image, ImageFormat, Img,
-    SharedString, Task, WeakEntity,
+    SharedString, Subscription, Task, WeakEntity,
 };
 use http_client::{AsyncBody, HttpClientWithUrl};
 use itertools::Either;
@@ -21,7 +22,10 @@ use language::Buffer;
 use language_model::{LanguageModelImage, LanguageModelImageExt};
 use multi_buffer::MultiBufferRow;
 use postage::stream::Stream as _;
-use project::{Project, ProjectItem, ProjectPath, Worktree};
+use project::{
+    Project, ProjectItem, ProjectPath, Worktree,
+    context_server_store::{ContextServerResourceUpdatedEvent, ContextServerStore},
+};
 use rope::Point;
 use std::{
     cell::RefCell,
@@ -62,6 +66,10 @@ pub struct MentionSet {
     thread_store: Option<Entity<ThreadStore>>,
     mentions: HashMap<CreaseId, (MentionUri, MentionTask)>,
     crease_entities: HashMap<CreaseId, Entity<LoadingContext>>,
+    /// MCP resources this set is subscribed to, one subscription per resource
+    /// no matter how many mentions refer to it.
+    mcp_resource_subscriptions: HashSet<(ContextServerId, url::Url)>,
+    context_server_subscriptions: Vec<Subscription>,
 }
 
 impl MentionSet {
@@ -71,6 +79,8 @@ impl MentionSet {
             thread_store,
             mentions: HashMap::default(),
             crease_entities: HashMap::default(),
+            mcp_resource_subscriptions: HashSet::default(),
+            context_server_subscriptions: Vec::new(),
         }
     }
 
@@ -101,13 +111,14 @@ impl MentionSet {
         })
     }
 
-    pub fn remove_invalid(&mut self, snapshot: &EditorSnapshot) {
+    pub fn remove_invalid(&mut self, snapshot: &EditorSnapshot, cx: &mut App) {
         for (crease_id, crease) in snapshot.crease_snapshot.creases() {
             if !crease.range().start.is_valid(snapshot.buffer_snapshot()) {
                 self.mentions.remove(&crease_id);
                 self.crease_entities.remove(&crease_id);
             }
         }
+        self.sync_mcp_resource_subscriptions(cx);
     }
 
     pub fn insert_mention(
@@ -123,6 +134,7 @@ impl MentionSet {
             self.crease_entities.insert(crease_id, entity);
         }
         self.recompute_disambiguation(cx);
+        self.sync_mcp_resource_subscriptions(cx);
     }
 
     /// Creates the appropriate confirmation task for a mention based on its URI type.
@@ -156,6 +168,9 @@ impl MentionSet {
             MentionUri::GitDiff { base_ref } => {
                 self.confirm_mention_for_git_diff(base_ref.into(), cx)
             }
+            MentionUri::McpResource { server_id, uri, .. } => {
+                self.confirm_mention_for_mcp_resource(server_id, uri, cx)
+            }
             MentionUri::Selection {
                 abs_path: Some(abs_path),
                 line_range,
@@ -177,6 +192,7 @@ impl MentionSet {
         self.mentions.remove(crease_id);
         self.crease_entities.remove(crease_id);
         self.recompute_disambiguation(cx);
+        self.sync_mcp_resource_subscriptions(cx);
     }
 
     pub fn creases(&self) -> HashSet<CreaseId> {
@@ -205,15 +221,54 @@ impl MentionSet {
         Some((uri.clone(), mention))
     }
 
-    pub fn set_mentions(&mut self, mentions: HashMap<CreaseId, (MentionUri, MentionTask)>) {
+    pub fn set_mentions(
+        &mut self,
+        mentions: HashMap<CreaseId, (MentionUri, MentionTask)>,
+        cx: &mut App,
+    ) {
         self.crease_entities
             .retain(|id, _| mentions.contains_key(id));
         self.mentions = mentions;
+        self.sync_mcp_resource_subscriptions(cx);
     }
 
-    pub fn clear(&mut self) -> impl Iterator<Item = (CreaseId, (MentionUri, MentionTask))> {
+    pub fn clear(&mut self, cx: &mut App) -> Vec<(CreaseId, (MentionUri, MentionTask))> {
         self.crease_entities.clear();
-        self.mentions.drain()
+        let mentions = self.mentions.drain().collect();
+        self.sync_mcp_resource_subscriptions(cx);
+        mentions
+    }
+
+    /// Subscribes to every MCP resource that is mentioned and unsubscribes from
+    /// the ones that no longer are, so servers stop sending updates for
+    /// resources that were removed from the message.
+    fn sync_mcp_resource_subscriptions(&mut self, cx: &mut App) {
+        let mentioned = self
+            .mentions
+            .values()
+            .filter_map(|(uri, _)| match uri {
+                MentionUri::McpResource { server_id, uri, .. } => {
+                    Some((ContextServerId(server_id.as_str().into()), uri.clone()))
+                }
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+        if mentioned == self.mcp_resource_subscriptions {
+            return;
+        }
+        let Some(project) = self.project.upgrade() else {
+            return;
+        };
+        let store = project.read(cx).context_server_store();
+        store.update(cx, |store, cx| {
+            for (server_id, uri) in self.mcp_resource_subscriptions.difference(&mentioned) {
+                store.unsubscribe_from_resource(server_id, uri.clone(), cx);
+            }
+            for (server_id, uri) in mentioned.difference(&self.mcp_resource_subscriptions) {
+                store.subscribe_to_resource(server_id, uri.clone(), cx);
+            }
+        });
+        self.mcp_resource_subscriptions = mentioned;
     }
 
     fn recompute_disambiguation(&self, cx: &mut App) {
@@ -344,6 +399,9 @@ impl MentionSet {
             MentionUri::GitDiff { base_ref } => {
                 self.confirm_mention_for_git_diff(base_ref.into(), cx)
             }
+            MentionUri::McpResource { server_id, uri, .. } => {
+                self.confirm_mention_for_mcp_resource(server_id, uri, cx)
+            }
             MentionUri::MergeConflict { .. } => {
                 debug_panic!("unexpected merge conflict URI");
                 Task::ready(Err(anyhow!("unexpected merge conflict URI")))
@@ -361,6 +419,7 @@ impl MentionSet {
             self.crease_entities.insert(crease_id, entity);
         }
         self.recompute_disambiguation(cx);
+        self.sync_mcp_resource_subscriptions(cx);
 
         // Notify the user if we failed to load the mentioned context
         let workspace = workspace.downgrade();
@@ -375,6 +434,7 @@ impl MentionSet {
                     });
                     this.mentions.remove(&crease_id);
                     this.crease_entities.remove(&crease_id);
+                    this.sync_mcp_resource_subscriptions(cx);
                 })
                 .ok();
             }
@@ -707,6 +767,88 @@ impl MentionSet {
             }
         })
     }
+
+    pub fn confirm_mention_for_mcp_resource(
+        &mut self,
+        server_id: String,
+        uri: url::Url,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Mention>> {
+        let Some(project) = self.project.upgrade() else {
+            return Task::ready(Err(anyhow!("project not found")));
+        };
+        let store = project.read(cx).context_server_store();
+        if self.context_server_subscriptions.is_empty() {
+            self.context_server_subscriptions = vec![
+                cx.subscribe(&store, Self::handle_context_server_resource_updated),
+                cx.on_release(|this, cx| {
+                    this.mentions.clear();
+                    this.sync_mcp_resource_subscriptions(cx);
+                }),
+            ];
+        }
+
+        // Subscribing happens in `sync_mcp_resource_subscriptions` once the
+        // mention is inserted, so re-reading an updated resource doesn't
+        // subscribe to it again.
+        let server_id = ContextServerId(server_id.into());
+        let read_task = store.update(cx, |store, cx| store.read_resource(&server_id, uri, cx));
+        cx.spawn(async move |_, _| {
+            let response = read_task.await?;
+            let mut content = String::new();
+            for contents in response.contents {
+                match contents {
+                    ResourceContentsType::Text(text) => content.push_str(&text.text),
+                    ResourceContentsType::Blob(blob) => {
+                        writeln!(
+                            content,
+                            "[binary resource {} ({})]",
+                            blob.uri,
+                            blob.mime_type.as_deref().unwrap_or("unknown type")
+                        )
+                        .ok();
+                    }
+                }
+            }
+            Ok(Mention::Text {
+                content,
+                tracked_buffers: Vec::new(),
+            })
+        })
+    }
+
+    /// Re-reads every mention of a resource whose server reported that it changed,
+    /// so the message picks up the latest contents when it is sent.
+    fn handle_context_server_resource_updated(
+        &mut self,
+        _: Entity<ContextServerStore>,
+        event: &ContextServerResourceUpdatedEvent,
+        cx: &mut Context<Self>,
+    ) {
+        let stale_mentions = self
+            .mentions
+            .iter()
+            .filter_map(|(crease_id, (mention_uri, _))| match mention_uri {
+                MentionUri::McpResource { server_id, uri, .. }
+                    if server_id.as_str() == event.server_id.0.as_ref()
+                        && uri.as_str() == event.uri =>
+                {
+                    Some((*crease_id, server_id.clone(), uri.clone()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        for (crease_id, server_id, uri) in stale_mentions {
+            let task = self.confirm_mention_for_mcp_resource(server_id, uri, cx);
+            let task = cx
+                .spawn(async move |_, _| task.await.map_err(|e| e.to_string()))
+                .shared();
+            if let Some((_, mention_task)) = self.mentions.get_mut(&crease_id) {
+                *mention_task = task;
+            }
+        }
+    }
 }
 
 /// Computes disambiguated labels for a set of mentions, so that mentions sharing
@@ -751,6 +893,7 @@ fn disambiguated_labels_for_uris(uris: &[&MentionUri]) -> Vec<SharedString> {
 mod tests {
     use super::*;
 
+    use context_server::{ContextServer, test::create_fake_transport_with_capabilities, types};
     use fs::FakeFs;
     use gpui::TestAppContext;
     use project::Project;
@@ -760,6 +903,7 @@ mod tests {
     use serde_json::json;
     use settings::SettingsStore;
     use std::path::Path;
+    use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
     use theme;
     use util::path;
 
@@ -773,6 +917,151 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_mcp_resource_mention_subscriptions(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/project", json!({"file": ""})).await;
+        let project = Project::test(fs, [Path::new(path!("/project"))], cx).await;
+
+        let received = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let reads = Arc::new(AtomicUsize::new(0));
+        let transport = create_fake_transport_with_capabilities(
+            "notes",
+            || types::ServerCapabilities {
+                resources: Some(types::ResourcesCapabilities {
+                    subscribe: Some(true),
+                    list_changed: None,
+                }),
+                ..Default::default()
+            },
+            cx.executor(),
+        )
+        .on_request::<types::requests::ResourcesList, _>(|_| async {
+            types::ResourcesListResponse {
+                resources: Vec::new(),
+                next_cursor: None,
+                meta: None,
+            }
+        })
+        .on_request::<types::requests::ResourcesRead, _>({
+            let reads = reads.clone();
+            move |params| {
+                let version = reads.fetch_add(1, SeqCst) + 1;
+                async move {
+                    types::ResourcesReadResponse {
+                        contents: vec![ResourceContentsType::Text(types::TextResourceContents {
+                            uri: params.uri,
+                            mime_type: None,
+                            text: format!("version {version}"),
+                        })],
+                        meta: None,
+                    }
+                }
+            }
+        })
+        .on_request::<types::requests::ResourcesSubscribe, _>({
+            let received = received.clone();
+            move |params| {
+                received.lock().push(format!("subscribe {}", params.uri));
+                async {}
+            }
+        })
+        .on_request::<types::requests::ResourcesUnsubscribe, _>({
+            let received = received.clone();
+            move |params| {
+                received.lock().push(format!("unsubscribe {}", params.uri));
+                async {}
+            }
+        });
+
+        let server_id = ContextServerId("notes".into());
+        let store = project.read_with(cx, |project, _| project.context_server_store());
+        store.update(cx, |store, cx| {
+            store.test_start_server(
+                Arc::new(ContextServer::new(server_id.clone(), Arc::new(transport))),
+                cx,
+            )
+        });
+        cx.run_until_parked();
+
+        let uri = url::Url::parse("file:///notes.md").unwrap();
+        let mention_uri = MentionUri::McpResource {
+            server_id: "notes".into(),
+            uri: uri.clone(),
+            name: "notes.md".into(),
+        };
+        let crease_id = CreaseId::default();
+        let mention_set = cx.new(|_cx| MentionSet::new(project.downgrade(), None));
+        let insert_mention = |cx: &mut TestAppContext| {
+            mention_set.update(cx, |mention_set, cx| {
+                let http_client = project.read(cx).client().http_client();
+                let task = mention_set.confirm_mention_for_uri(
+                    mention_uri.clone(),
+                    false,
+                    http_client,
+                    cx,
+                );
+                let task = cx
+                    .spawn(async move |_, _| task.await.map_err(|e| e.to_string()))
+                    .shared();
+                mention_set.insert_mention(crease_id, mention_uri.clone(), task, None, cx);
+            });
+            cx.run_until_parked();
+        };
+        let mention_text = |cx: &mut TestAppContext| {
+            mention_set.read_with(cx, |mention_set, _| {
+                match mention_set.resolved_mention_for_crease(&crease_id) {
+                    Some((_, Some(Mention::Text { content, .. }))) => content,
+                    mention => panic!("unexpected mention: {mention:?}"),
+                }
+            })
+        };
+
+        insert_mention(cx);
+        assert_eq!(mention_text(cx), "version 1");
+        assert_eq!(
+            received.lock().drain(..).collect::<Vec<_>>(),
+            vec!["subscribe file:///notes.md".to_string()]
+        );
+
+        // An update re-reads the resource without subscribing to it again.
+        store.update(cx, |_, cx| {
+            cx.emit(ContextServerResourceUpdatedEvent {
+                server_id: server_id.clone(),
+                uri: uri.to_string(),
+            })
+        });
+        cx.run_until_parked();
+        assert_eq!(mention_text(cx), "version 2");
+        assert_eq!(reads.load(SeqCst), 2);
+        assert!(received.lock().is_empty());
+
+        // Removing the mention unsubscribes.
+        mention_set.update(cx, |mention_set, cx| {
+            mention_set.remove_mention(&crease_id, cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            received.lock().drain(..).collect::<Vec<_>>(),
+            vec!["unsubscribe file:///notes.md".to_string()]
+        );
+
+        // So does dropping the message the mention was part of.
+        insert_mention(cx);
+        assert_eq!(
+            received.lock().drain(..).collect::<Vec<_>>(),
+            vec!["subscribe file:///notes.md".to_string()]
+        );
+        drop(mention_set);
+        cx.run_until_parked();
+        assert_eq!(
+            received.lock().drain(..).collect::<Vec<_>>(),
+            vec!["unsubscribe file:///notes.md".to_string()]
+        );
+    }
+
     #[gpui::test]
     async fn test_thread_mentions_disabled(cx: &mut TestAppContext) {
         init_test(cx);
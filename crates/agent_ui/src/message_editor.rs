@@ -103,6 +103,7 @@ impl SessionCapabilities {
                 PromptContextType::Fetch,
                 PromptContextType::Skill,
                 PromptContextType::BranchDiff,
+                PromptContextType::McpResource,
             ]);
         }
         supported
@@ -561,8 +562,9 @@ impl MessageEditor {
                     cx.emit(MessageEditorEvent::Edited);
                     editor.update(cx, |editor, cx| {
                         let snapshot = editor.snapshot(window, cx);
-                        this.mention_set
-                            .update(cx, |mention_set, _cx| mention_set.remove_invalid(&snapshot));
+                        this.mention_set.update(cx, |mention_set, cx| {
+                            mention_set.remove_invalid(&snapshot, cx)
+                        });
 
                         let new_hints = this
                             .command_hint(snapshot.buffer())
@@ -930,9 +932,10 @@ impl MessageEditor {
         self.editor.update(cx, |editor, cx| {
             editor.clear(window, cx);
             editor.remove_creases(
-                self.mention_set.update(cx, |mention_set, _cx| {
+                self.mention_set.update(cx, |mention_set, cx| {
                     mention_set
-                        .clear()
+                        .clear(cx)
+                        .into_iter()
                         .map(|(crease_id, _)| crease_id)
                         .collect::<Vec<_>>()
                 }),
@@ -391,6 +391,7 @@ impl ProfilePickerDelegate {
             builtin_profiles::WRITE => Some("Get help to write anything."),
             builtin_profiles::ASK => Some("Chat about your codebase."),
             builtin_profiles::MINIMAL => Some("Chat about anything with no tools."),
+            builtin_profiles::REVIEW => Some("Review a diff and comment on it."),
             _ => None,
         }
     }
@@ -0,0 +1,347 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use gpui::{AnyWindowHandle, AsyncApp, Entity, Task, WeakEntity};
+use project::{Inventory, TaskSourceKind, WorktreeId};
+use task::{BuildTaskDefinition, DebugScenario, ResolvedTask, SharedTaskContext};
+use terminal_view::terminal_panel::TerminalPanel;
+use workspace::Workspace;
+
+/// Bridges the agent's `run_task` and `debug_start` tools to the workspace:
+/// tasks and debug scenarios are resolved against the same contexts as their
+/// pickers, tasks run in the terminal panel and scenarios in the debug panel.
+/// Installed on a `NativeAgent` by the agent panel alongside
+/// `AgentPanelSiblingHost`.
+pub(crate) struct AgentPanelTaskHost {
+    workspace: WeakEntity<Workspace>,
+    window: AnyWindowHandle,
+}
+
+impl AgentPanelTaskHost {
+    pub(crate) fn new(workspace: WeakEntity<Workspace>, window: AnyWindowHandle) -> Self {
+        Self { workspace, window }
+    }
+
+    /// Resolves the project's tasks the way the task picker does: recently run
+    /// tasks first, then the rest, deduplicated by label.
+    fn resolve_tasks(
+        &self,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<Vec<(TaskSourceKind, ResolvedTask)>>> {
+        let workspace = self.workspace.clone();
+        let window = self.window;
+        cx.spawn(async move |cx| {
+            let workspace = workspace.upgrade().context("workspace was closed")?;
+            let (task_contexts, inventory) = window.update(cx, |_, window, cx| {
+                workspace.update(cx, |workspace, cx| {
+                    let inventory = workspace
+                        .project()
+                        .read(cx)
+                        .task_store()
+                        .read(cx)
+                        .task_inventory()
+                        .cloned();
+                    (tasks_ui::task_contexts(workspace, window, cx), inventory)
+                })
+            })?;
+            let inventory = inventory.context("tasks are not available in this project")?;
+            let task_contexts = Arc::new(task_contexts.await);
+            let (used, current) = inventory
+                .update(cx, |inventory, cx| {
+                    inventory.used_and_current_resolved_tasks(task_contexts, cx)
+                })
+                .await;
+
+            let mut seen_labels = HashSet::default();
+            Ok(used
+                .into_iter()
+                .chain(current)
+                .filter(|(_, task)| seen_labels.insert(task.resolved_label.clone()))
+                .collect())
+        })
+    }
+
+    /// Resolves the project's debug scenarios the way the debug scenario picker
+    /// does: recently started scenarios first, with the context they were
+    /// started in, then the rest, deduplicated by label.
+    fn resolve_debug_scenarios(
+        &self,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<Vec<(String, DebugScenario, DebugScenarioStartContext)>>> {
+        let workspace = self.workspace.clone();
+        let window = self.window;
+        cx.spawn(async move |cx| {
+            let workspace = workspace.upgrade().context("workspace was closed")?;
+            let (task_contexts, inventory) = window.update(cx, |_, window, cx| {
+                workspace.update(cx, |workspace, cx| {
+                    let inventory = workspace
+                        .project()
+                        .read(cx)
+                        .task_store()
+                        .read(cx)
+                        .task_inventory()
+                        .cloned();
+                    (tasks_ui::task_contexts(workspace, window, cx), inventory)
+                })
+            })?;
+            let inventory = inventory.context("debugging is not available in this project")?;
+            let task_contexts = task_contexts.await;
+            let (recent, scenarios) = inventory
+                .update(cx, |inventory, cx| {
+                    inventory.list_debug_scenarios(
+                        &task_contexts,
+                        Vec::new(),
+                        Vec::new(),
+                        false,
+                        cx,
+                    )
+                })
+                .await;
+
+            let current_context = DebugScenarioStartContext {
+                task_context: SharedTaskContext::from(
+                    task_contexts.active_context().cloned().unwrap_or_default(),
+                ),
+                worktree_id: task_contexts.worktree(),
+            };
+            let mut seen_labels = HashSet::default();
+            Ok(recent
+                .into_iter()
+                .map(|(scenario, context)| {
+                    (
+                        "recently started".to_string(),
+                        scenario,
+                        DebugScenarioStartContext {
+                            task_context: context.task_context,
+                            worktree_id: context.worktree_id,
+                        },
+                    )
+                })
+                .chain(scenarios.into_iter().map(|(kind, scenario)| {
+                    (
+                        source_description(&kind, "debug.json"),
+                        scenario,
+                        current_context.clone(),
+                    )
+                }))
+                .filter(|(_, scenario, _)| seen_labels.insert(scenario.label.clone()))
+                .collect())
+        })
+    }
+}
+
+#[derive(Clone)]
+struct DebugScenarioStartContext {
+    task_context: SharedTaskContext,
+    worktree_id: Option<WorktreeId>,
+}
+
+impl agent::ProjectTaskHost for AgentPanelTaskHost {
+    fn list_tasks(&self, cx: &mut AsyncApp) -> Task<Result<Vec<agent::ProjectTaskInfo>>> {
+        let tasks = self.resolve_tasks(cx);
+        cx.spawn(async move |_| {
+            Ok(tasks
+                .await?
+                .iter()
+                .map(|(kind, task)| agent::ProjectTaskInfo {
+                    label: task.resolved_label.clone(),
+                    command: task.resolved.command_label.clone(),
+                    source: source_description(kind, "tasks.json"),
+                })
+                .collect())
+        })
+    }
+
+    fn run_task(&self, label: String, cx: &mut AsyncApp) -> Task<Result<agent::ProjectTaskOutput>> {
+        let tasks = self.resolve_tasks(cx);
+        let workspace = self.workspace.clone();
+        let window = self.window;
+        cx.spawn(async move |cx| {
+            let (kind, task) = tasks
+                .await?
+                .into_iter()
+                .find(|(_, task)| task.resolved_label == label)
+                .with_context(|| format!("no task labeled {label:?}"))?;
+
+            let workspace = workspace.upgrade().context("workspace was closed")?;
+            let spawn = window.update(cx, |_, window, cx| {
+                workspace.update(cx, |workspace, cx| {
+                    // Record the run so the task shows up as recently used in
+                    // the task picker, as if the user had started it.
+                    if let Some(inventory) = workspace
+                        .project()
+                        .read(cx)
+                        .task_store()
+                        .read(cx)
+                        .task_inventory()
+                        .cloned()
+                    {
+                        inventory.update(cx, |inventory, _| {
+                            inventory.task_scheduled(kind, task.clone())
+                        });
+                    }
+                    let terminal_panel = workspace
+                        .panel::<TerminalPanel>(cx)
+                        .context("the terminal panel is not available")?;
+                    anyhow::Ok(terminal_panel.update(cx, |terminal_panel, cx| {
+                        terminal_panel.spawn_task(&task.resolved, window, cx)
+                    }))
+                })
+            })??;
+
+            let terminal = spawn.await?;
+            let exit_status = terminal
+                .update(cx, |terminal, cx| terminal.wait_for_completed_task(cx))?
+                .await;
+            let output = terminal.read_with(cx, |terminal, _| terminal.get_content())?;
+            Ok(agent::ProjectTaskOutput {
+                exit_code: exit_status.and_then(|status| status.code()),
+                output,
+            })
+        })
+    }
+
+    fn list_debug_scenarios(
+        &self,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<Vec<agent::DebugScenarioInfo>>> {
+        let scenarios = self.resolve_debug_scenarios(cx);
+        let workspace = self.workspace.clone();
+        cx.spawn(async move |cx| {
+            let scenarios = scenarios.await?;
+            let inventory = workspace
+                .upgrade()
+                .context("workspace was closed")?
+                .read_with(cx, |workspace, cx| {
+                    workspace
+                        .project()
+                        .read(cx)
+                        .task_store()
+                        .read(cx)
+                        .task_inventory()
+                        .cloned()
+                })
+                .context("debugging is not available in this project")?;
+
+            let mut infos = Vec::with_capacity(scenarios.len());
+            for (source, scenario, context) in scenarios {
+                let build_command = match &scenario.build {
+                    Some(build) => build_command_line(build, &context, &inventory, cx).await,
+                    None => None,
+                };
+                infos.push(agent::DebugScenarioInfo {
+                    label: scenario.label.to_string(),
+                    adapter: scenario.adapter.to_string(),
+                    source,
+                    build_command,
+                    program: program_command_line(&scenario.config),
+                });
+            }
+            Ok(infos)
+        })
+    }
+
+    fn start_debug_scenario(&self, label: String, cx: &mut AsyncApp) -> Task<Result<()>> {
+        let scenarios = self.resolve_debug_scenarios(cx);
+        let workspace = self.workspace.clone();
+        let window = self.window;
+        cx.spawn(async move |cx| {
+            let (_, scenario, context) = scenarios
+                .await?
+                .into_iter()
+                .find(|(_, scenario, _)| scenario.label.as_ref() == label)
+                .with_context(|| format!("no debug scenario labeled {label:?}"))?;
+
+            let workspace = workspace.upgrade().context("workspace was closed")?;
+            window.update(cx, |_, window, cx| {
+                let provider = workspace
+                    .read(cx)
+                    .debugger_provider()
+                    .context("the debugger is not available")?;
+                // Start the session through the debug panel, exactly as the
+                // scenario picker does, so the user can follow along there.
+                provider.start_session(
+                    scenario,
+                    context.task_context,
+                    None,
+                    context.worktree_id,
+                    window,
+                    cx,
+                );
+                anyhow::Ok(())
+            })?
+        })
+    }
+}
+
+/// Resolves the command line a debug scenario's build step runs, the way the
+/// debugger does before it starts the session.
+async fn build_command_line(
+    build: &BuildTaskDefinition,
+    context: &DebugScenarioStartContext,
+    inventory: &Entity<Inventory>,
+    cx: &mut AsyncApp,
+) -> Option<String> {
+    let task_template = match build {
+        BuildTaskDefinition::Template { task_template, .. } => task_template.clone(),
+        BuildTaskDefinition::ByName(label) => {
+            inventory
+                .read_with(cx, |inventory, cx| {
+                    inventory.task_template_by_label(None, context.worktree_id, label, cx)
+                })
+                .await?
+        }
+    };
+    match task_template.resolve_task("debug-build-task", &context.task_context) {
+        Some(task) => Some(task.resolved.command_label),
+        // Inline build tasks may have no label, which resolving requires.
+        None => Some(
+            std::iter::once(task_template.command.as_str())
+                .chain(task_template.args.iter().map(String::as_str))
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+    }
+}
+
+/// The program a debug scenario launches and its arguments, from the fields
+/// adapters commonly use to name it.
+fn program_command_line(config: &serde_json::Value) -> Option<String> {
+    let program = ["program", "command", "module"]
+        .iter()
+        .find_map(|key| config.get(key)?.as_str())?;
+    let args = config
+        .get("args")
+        .and_then(|args| args.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|arg| arg.as_str());
+    Some(
+        std::iter::once(program)
+            .chain(args)
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+/// Describes where a task or debug scenario is defined. Worktree sources
+/// name the directory holding the definitions file (e.g. `.zed`).
+fn source_description(kind: &TaskSourceKind, file_name: &str) -> String {
+    match kind {
+        TaskSourceKind::UserInput => "previously run command".to_string(),
+        TaskSourceKind::Worktree {
+            directory_in_worktree,
+            ..
+        } if directory_in_worktree.is_empty() => file_name.to_string(),
+        TaskSourceKind::Worktree {
+            directory_in_worktree,
+            ..
+        } => format!("{}/{file_name}", directory_in_worktree.as_unix_str()),
+        TaskSourceKind::AbsPath { abs_path, .. } => abs_path.display().to_string(),
+        TaskSourceKind::Language { name } => format!("{name} language tasks"),
+        TaskSourceKind::Lsp { language_name, .. } => {
+            format!("{language_name} language server")
+        }
+    }
+}
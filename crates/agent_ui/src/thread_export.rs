@@ -0,0 +1,196 @@
+//! Exporting agent threads to files, and importing exported threads into the
+//! local thread store so they can be continued against this checkout.
+
+use agent::{ExportedThread, ThreadStore};
+use agent_client_protocol::schema::v1 as acp;
+use anyhow::{Context as _, Result};
+use gpui::{Context, PathPromptOptions, SharedString, Task, Window};
+use project::DirectoryLister;
+use util::path_list::PathList;
+use workspace::{
+    Toast, Workspace,
+    notifications::{DetachAndPromptErr as _, NotificationId},
+};
+
+use crate::{AgentPanel, DEFAULT_THREAD_TITLE};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ThreadExportFormat {
+    Markdown,
+    Json,
+}
+
+impl ThreadExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Json => "json",
+        }
+    }
+
+    /// Renders a thread the same way whether it's open or only in the thread
+    /// store.
+    fn render(self, exported: ExportedThread) -> Result<String> {
+        match self {
+            Self::Markdown => Ok(exported.to_markdown()),
+            Self::Json => exported.to_json(),
+        }
+    }
+}
+
+/// Exports the thread that is active in the agent panel.
+pub(crate) fn export_active_thread(
+    workspace: &mut Workspace,
+    format: ThreadExportFormat,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(panel) = workspace.panel::<AgentPanel>(cx) else {
+        return;
+    };
+    let Some(acp_thread) = panel.read(cx).active_agent_thread(cx) else {
+        show_toast(workspace, "No active thread to export", cx);
+        return;
+    };
+    let Some(native_thread) = panel.read(cx).active_native_agent_thread(cx) else {
+        show_toast(workspace, "Only Zed Agent threads can be exported", cx);
+        return;
+    };
+
+    let title = acp_thread
+        .read(cx)
+        .title()
+        .unwrap_or_else(|| DEFAULT_THREAD_TITLE.into());
+    let db_thread = native_thread.read(cx).to_db(cx);
+    let contents =
+        cx.background_spawn(async move { format.render(ExportedThread::new(db_thread.await)) });
+
+    save_export(workspace, title, format, contents, window, cx);
+}
+
+/// Exports a Zed Agent thread from the thread store, without opening it.
+pub(crate) fn export_stored_thread(
+    workspace: &mut Workspace,
+    session_id: acp::SessionId,
+    title: SharedString,
+    format: ThreadExportFormat,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let load_thread =
+        ThreadStore::global(cx).update(cx, |store, cx| store.load_thread(session_id, cx));
+    let contents = cx.background_spawn(async move {
+        let thread = load_thread.await?.context("thread not found")?;
+        format.render(ExportedThread::new(thread))
+    });
+
+    save_export(workspace, title, format, contents, window, cx);
+}
+
+/// Asks for a thread exported as JSON and opens it as a new thread in the
+/// agent panel, scoped to the current project.
+pub(crate) fn import_thread(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    if workspace.root_paths(cx).is_empty() {
+        show_toast(workspace, "Open a project to import a thread", cx);
+        return;
+    }
+
+    let fs = workspace.app_state().fs.clone();
+    let lister = DirectoryLister::Local(workspace.project().clone(), fs.clone());
+    let paths = workspace.prompt_for_open_path(
+        PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Import".into()),
+        },
+        lister,
+        window,
+        cx,
+    );
+
+    cx.spawn_in(window, async move |workspace, cx| {
+        let Some(path) = paths.await.ok().flatten().into_iter().flatten().next() else {
+            return Ok(());
+        };
+        let json = fs.load_bytes(&path).await?;
+        let exported = ExportedThread::from_json(&json)
+            .with_context(|| format!("reading {}", path.display()))?;
+        let thread = exported.thread;
+        let title = thread.title.clone();
+        let session_id = acp::SessionId::new(uuid::Uuid::new_v4().to_string());
+
+        let save_thread = workspace.update(cx, |workspace, cx| {
+            let folder_paths = PathList::new(&workspace.root_paths(cx));
+            ThreadStore::global(cx).update(cx, |store, cx| {
+                store.save_thread(session_id.clone(), thread, folder_paths, cx)
+            })
+        })?;
+        save_thread.await?;
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            if let Some(panel) = workspace.focus_panel::<AgentPanel>(window, cx) {
+                panel.update(cx, |panel, cx| {
+                    panel.open_thread(session_id, None, Some(title), window, cx);
+                });
+            }
+        })
+    })
+    .detach_and_prompt_err("Failed to import thread", window, cx, |_, _, _| None);
+}
+
+fn save_export(
+    workspace: &mut Workspace,
+    title: SharedString,
+    format: ThreadExportFormat,
+    contents: Task<Result<String>>,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let fs = workspace.app_state().fs.clone();
+    let lister = DirectoryLister::Local(workspace.project().clone(), fs.clone());
+    let suggested_name = format!("{}.{}", file_stem(&title), format.extension());
+    let path = workspace.prompt_for_new_path(lister, Some(suggested_name), window, cx);
+
+    cx.spawn_in(window, async move |workspace, cx| {
+        let Some(path) = path.await.ok().flatten().into_iter().flatten().next() else {
+            return Ok(());
+        };
+        fs.atomic_write(path, contents.await?).await?;
+        workspace.update(cx, |workspace, cx| {
+            show_toast(workspace, "Thread exported", cx);
+        })
+    })
+    .detach_and_prompt_err("Failed to export thread", window, cx, |_, _, _| None);
+}
+
+fn file_stem(title: &str) -> String {
+    let stem = title
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    let stem = stem.trim_matches('-');
+    if stem.is_empty() {
+        "thread".to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
+fn show_toast(workspace: &mut Workspace, message: &'static str, cx: &mut Context<Workspace>) {
+    struct ThreadExportToast;
+    workspace.show_toast(
+        Toast::new(NotificationId::unique::<ThreadExportToast>(), message).autohide(),
+        cx,
+    );
+}
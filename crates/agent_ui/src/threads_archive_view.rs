@@ -4,10 +4,14 @@ use std::sync::Arc;
 
 use crate::agent_connection_store::AgentConnectionStore;
 
+use crate::thread_export::{self, ThreadExportFormat};
 use crate::thread_metadata_store::{
     ThreadId, ThreadMetadata, ThreadMetadataStore, worktree_info_from_thread_paths,
 };
-use crate::{Agent, ArchiveSelectedThread, DEFAULT_THREAD_TITLE, RemoveSelectedThread};
+use crate::{
+    Agent, ArchiveSelectedThread, DEFAULT_THREAD_TITLE, ExportThreadAsJson, ExportThreadAsMarkdown,
+    RemoveSelectedThread,
+};
 
 use agent::ThreadStore;
 use agent_client_protocol::schema::v1 as acp;
@@ -32,8 +36,8 @@ use project::{AgentId, AgentServerStore};
 use settings::Settings as _;
 use theme::ActiveTheme;
 use ui::{
-    AgentThreadStatus, Divider, KeyBinding, ListItem, ListItemSpacing, ListSubHeader, ScrollAxes,
-    Scrollbars, Tab, ThreadItem, Tooltip, WithScrollbar, prelude::*,
+    AgentThreadStatus, ContextMenu, Divider, KeyBinding, ListItem, ListItemSpacing, ListSubHeader,
+    ScrollAxes, Scrollbars, Tab, ThreadItem, Tooltip, WithScrollbar, prelude::*, right_click_menu,
     utils::platform_title_bar_height,
 };
 use util::ResultExt;
@@ -687,7 +691,7 @@ impl ThreadsArchiveView {
                         }
                     }));
 
-                if is_restoring {
+                let element = if is_restoring {
                     base.status(AgentThreadStatus::Running)
                         .action_slot(
                             IconButton::new("cancel-restore", IconName::Close)
@@ -779,11 +783,101 @@ impl ThreadsArchiveView {
                         })
                     })
                     .into_any_element()
+                };
+
+                if thread.session_id.is_none()
+                    || thread.agent_id.as_ref() != agent::ZED_AGENT_ID.as_ref()
+                {
+                    return element;
                 }
+
+                let this = cx.weak_entity();
+                let thread = thread.clone();
+                right_click_menu(SharedString::from(format!("archive-entry-menu-{ix}")))
+                    .trigger(move |_, _, _| element)
+                    .menu(move |window, cx| {
+                        let this = this.clone();
+                        let thread = thread.clone();
+                        ContextMenu::build(window, cx, move |menu, _, _| {
+                            [
+                                ("Export as Markdown…", ThreadExportFormat::Markdown),
+                                ("Export as JSON…", ThreadExportFormat::Json),
+                            ]
+                            .into_iter()
+                            .fold(menu, |menu, (label, format)| {
+                                let this = this.clone();
+                                let thread = thread.clone();
+                                menu.entry(label, None, move |window, cx| {
+                                    this.update(cx, |this, cx| {
+                                        this.export_thread(&thread, format, window, cx);
+                                    })
+                                    .ok();
+                                })
+                            })
+                        })
+                    })
+                    .into_any_element()
             }
         }
     }
 
+    fn export_selected_thread_as_markdown(
+        &mut self,
+        _: &ExportThreadAsMarkdown,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.export_selected_thread(ThreadExportFormat::Markdown, window, cx);
+    }
+
+    fn export_selected_thread_as_json(
+        &mut self,
+        _: &ExportThreadAsJson,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.export_selected_thread(ThreadExportFormat::Json, window, cx);
+    }
+
+    fn export_selected_thread(
+        &mut self,
+        format: ThreadExportFormat,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(ix) = self.selection else { return };
+        let Some(ArchiveListItem::Entry { thread, .. }) = self.items.get(ix) else {
+            return;
+        };
+        let thread = thread.clone();
+        self.export_thread(&thread, format, window, cx);
+    }
+
+    /// Only Zed Agent threads are kept in the thread store, so those are the
+    /// only ones that can be exported without opening them.
+    fn export_thread(
+        &self,
+        thread: &ThreadMetadata,
+        format: ThreadExportFormat,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let Some(session_id) = thread.session_id.clone() else {
+            return;
+        };
+        if thread.agent_id.as_ref() != agent::ZED_AGENT_ID.as_ref() {
+            return;
+        }
+        let title = thread.display_title();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                thread_export::export_stored_thread(
+                    workspace, session_id, title, format, window, cx,
+                );
+            })
+            .ok();
+    }
+
     fn remove_selected_thread(
         &mut self,
         _: &RemoveSelectedThread,
@@ -990,6 +1084,18 @@ impl ThreadsArchiveView {
                                 cx.emit(ThreadsArchiveViewEvent::Import);
                             })),
                     )
+                    .child(
+                        IconButton::new("thread-import-file", IconName::File)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Import Thread from File"))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.workspace
+                                    .update(cx, |workspace, cx| {
+                                        thread_export::import_thread(workspace, window, cx);
+                                    })
+                                    .ok();
+                            })),
+                    )
                     .child(
                         IconButton::new("filter-archived-only", IconName::Archive)
                             .icon_size(IconSize::Small)
@@ -1099,6 +1205,8 @@ impl Render for ThreadsArchiveView {
             .on_action(cx.listener(Self::confirm))
             .on_action(cx.listener(Self::remove_selected_thread))
             .on_action(cx.listener(Self::archive_selected_thread))
+            .on_action(cx.listener(Self::export_selected_thread_as_markdown))
+            .on_action(cx.listener(Self::export_selected_thread_as_json))
             .size_full()
             .child(self.render_header(window, cx))
             .when(!has_query, |this| this.child(self.render_toolbar(cx)))
@@ -0,0 +1,92 @@
+//! `zed --agent`: runs the Zed Agent on a prompt in a Zed started without a
+//! window, and streams its progress to stdout.
+
+use std::{
+    io::{self, Read as _},
+    process::{Command, Stdio},
+    thread,
+};
+
+use anyhow::{Context as _, Result};
+use cli::{CliRequest, CliResponse, IpcHandshake, ipc::IpcOneShotServer};
+
+use crate::InstalledApp;
+
+pub(crate) struct AgentArgs {
+    pub prompt: String,
+    pub profile: Option<String>,
+    pub model: Option<String>,
+    pub yes: bool,
+}
+
+pub(crate) fn run(
+    args: AgentArgs,
+    app: &impl InstalledApp,
+    user_data_dir: Option<&str>,
+) -> Result<()> {
+    let prompt = if args.prompt == "-" {
+        let mut prompt = String::new();
+        io::stdin()
+            .read_to_string(&mut prompt)
+            .context("reading the prompt from stdin")?;
+        prompt
+    } else {
+        args.prompt
+    };
+    anyhow::ensure!(!prompt.trim().is_empty(), "the prompt is empty");
+
+    let (server, server_name) =
+        IpcOneShotServer::<IpcHandshake>::new().context("Handshake before Zed spawn")?;
+    let url = format!("zed-cli://{server_name}");
+
+    let request = CliRequest::RunAgent {
+        prompt,
+        cwd: std::env::current_dir().context("reading the current directory")?,
+        profile: args.profile,
+        model: args.model,
+        allow_confirmations: args.yes,
+        env: crate::cli_env(),
+    };
+
+    // Zed runs the agent in its own process, so that it doesn't take over a
+    // running Zed or open a window, and quits once the run is done. Its logs
+    // go to the log file rather than mixing with the agent's output.
+    let mut command = Command::new(app.path());
+    command.arg(url).arg("--headless");
+    if let Some(dir) = user_data_dir {
+        command.arg("--user-data-dir").arg(dir);
+    }
+    let mut zed = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Spawning {command:?}"))?;
+
+    // If Zed fails before it connects, nothing would ever answer the
+    // handshake below.
+    thread::Builder::new()
+        .name("CliZedProcess".to_string())
+        .spawn(move || {
+            if let Ok(status) = zed.wait()
+                && !status.success()
+            {
+                eprintln!("error: Zed exited before the agent finished ({status})");
+                std::process::exit(1);
+            }
+        })
+        .unwrap();
+
+    let (_, handshake) = server.accept().context("Handshake after Zed spawn")?;
+    handshake.requests.send(request)?;
+    while let Ok(response) = handshake.responses.recv() {
+        match response {
+            CliResponse::Ping | CliResponse::PromptOpenBehavior => {}
+            CliResponse::Stdout { message } => println!("{message}"),
+            CliResponse::Stderr { message } => eprintln!("{message}"),
+            CliResponse::Exit { status } => std::process::exit(status),
+        }
+    }
+
+    anyhow::bail!("Zed exited before the agent finished")
+}
@@ -72,6 +72,19 @@ pub enum CliRequest {
     SetOpenBehavior {
         behavior: CliBehaviorSetting,
     },
+    /// Runs the Zed Agent on a prompt in `cwd`, streaming its progress back as
+    /// [`CliResponse::Stdout`] lines until it responds with [`CliResponse::Exit`].
+    /// Corresponds to `zed --agent`.
+    RunAgent {
+        prompt: String,
+        cwd: PathBuf,
+        profile: Option<String>,
+        model: Option<String>,
+        /// Allow tool calls that would need the user's confirmation, instead of
+        /// denying them.
+        allow_confirmations: bool,
+        env: Option<HashMap<String, String>>,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -7,6 +7,7 @@
     allow(dead_code)
 )]
 
+mod agent;
 mod completions;
 
 use crate::completions::Shell;
@@ -61,7 +62,9 @@ Examples:
     `zed path-to-your-project`
           Open your project in Zed
     `zed -n path-to-file `
-          Open file/folder in a new window",
+          Open file/folder in a new window
+    `zed --agent \"fix the failing tests\"`
+          Runs the agent on a prompt in the current directory",
     after_help = "To read from stdin, append '-', e.g. 'ps axf | zed -'"
 )]
 struct Args {
@@ -149,6 +152,23 @@ struct Args {
     #[arg(long)]
     uninstall: bool,
 
+    /// Run the Zed Agent on a prompt in the current directory, without opening a window,
+    /// and print its progress. Use '-' to read the prompt from stdin.
+    ///
+    /// Tool calls that would need a confirmation in the editor are denied unless `--yes` is given.
+    /// Exits with 0 when the agent finishes its turn, and non-zero otherwise.
+    #[arg(long, value_name = "PROMPT", conflicts_with_all = ["paths_with_position", "diff"])]
+    agent: Option<String>,
+    /// The agent profile to use with `--agent`, e.g. `write` or `ask`. Defaults to the `default_profile` setting.
+    #[arg(long, requires = "agent")]
+    profile: Option<String>,
+    /// The model to use with `--agent`, as `provider/model`. Defaults to the `default_model` setting.
+    #[arg(long, requires = "agent")]
+    model: Option<String>,
+    /// Allow tool calls that would need a confirmation with `--agent`, instead of denying them.
+    #[arg(short, long, requires = "agent")]
+    yes: bool,
+
     /// Used for SSH/Git password authentication, to remove the need for netcat as a dependency,
     /// by having Zed act like netcat communicating over a Unix socket.
     #[arg(long, hide = true)]
@@ -356,6 +376,40 @@ mod tests {
         assert_path_eq!(result, expected)
     }
 
+    #[test]
+    fn test_agent_flag() {
+        // A path named `agent` is still opened.
+        let args = Args::try_parse_from(["zed", "agent"]).unwrap();
+        assert_eq!(args.agent, None);
+        assert_eq!(args.paths_with_position, ["agent"]);
+
+        let args = Args::try_parse_from([
+            "zed",
+            "--agent",
+            "fix the failing tests",
+            "--profile",
+            "ask",
+            "--model",
+            "anthropic/claude-sonnet-4-5",
+            "-y",
+        ])
+        .unwrap();
+        assert_eq!(args.agent.as_deref(), Some("fix the failing tests"));
+        assert_eq!(args.profile.as_deref(), Some("ask"));
+        assert_eq!(args.model.as_deref(), Some("anthropic/claude-sonnet-4-5"));
+        assert!(args.yes);
+        assert!(args.paths_with_position.is_empty());
+
+        let args = Args::try_parse_from(["zed", "--agent", "-"]).unwrap();
+        assert_eq!(args.agent.as_deref(), Some("-"));
+
+        // The agent runs in the current directory, so it can't be given paths,
+        // and its options can't be used without it.
+        assert!(Args::try_parse_from(["zed", "--agent", "fix it", "src/main.rs"]).is_err());
+        assert!(Args::try_parse_from(["zed", "--yes", "src/main.rs"]).is_err());
+        assert!(Args::try_parse_from(["zed", "--model", "anthropic/claude-sonnet-4-5"]).is_err());
+    }
+
     #[test]
     fn test_parse_existing_path() {
         let temp_tree = TempTree::new(json!({
@@ -581,6 +635,16 @@ fn run() -> Result<()> {
         std::process::exit(status.code().unwrap_or(1));
     }
 
+    if let Some(prompt) = args.agent {
+        let args = agent::AgentArgs {
+            prompt,
+            profile: args.profile,
+            model: args.model,
+            yes: args.yes,
+        };
+        return agent::run(args, &app, user_data_dir.as_deref());
+    }
+
     let (server, server_name) =
         IpcOneShotServer::<IpcHandshake>::new().context("Handshake before Zed spawn")?;
     let url = format!("zed-cli://{server_name}");
@@ -599,37 +663,7 @@ fn run() -> Result<()> {
         cli::OpenBehavior::Default
     };
 
-    let env = {
-        #[cfg(any(target_os = "linux", target_os = "freebsd"))]
-        {
-            use collections::HashMap;
-
-            // On Linux, the desktop entry uses `cli` to spawn `zed`.
-            // We need to handle env vars correctly since std::env::vars() may not contain
-            // project-specific vars (e.g. those set by direnv).
-            // By setting env to None here, the LSP will use worktree env vars instead,
-            // which is what we want.
-            if !std::io::stdout().is_terminal() {
-                None
-            } else {
-                Some(std::env::vars().collect::<HashMap<_, _>>())
-            }
-        }
-
-        #[cfg(target_os = "windows")]
-        {
-            // On Windows, by default, a child process inherits a copy of the environment block of the parent process.
-            // So we don't need to pass env vars explicitly.
-            None
-        }
-
-        #[cfg(not(any(target_os = "linux", target_os = "freebsd", target_os = "windows")))]
-        {
-            use collections::HashMap;
-
-            Some(std::env::vars().collect::<HashMap<_, _>>())
-        }
-    };
+    let env = cli_env();
 
     let exit_status = Arc::new(Mutex::new(None));
     let mut paths = vec![];
@@ -798,6 +832,39 @@ fn run() -> Result<()> {
     Ok(())
 }
 
+/// The environment to forward to Zed, for language servers and terminals.
+fn cli_env() -> Option<collections::HashMap<String, String>> {
+    #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+    {
+        use collections::HashMap;
+
+        // On Linux, the desktop entry uses `cli` to spawn `zed`.
+        // We need to handle env vars correctly since std::env::vars() may not contain
+        // project-specific vars (e.g. those set by direnv).
+        // By setting env to None here, the LSP will use worktree env vars instead,
+        // which is what we want.
+        if !std::io::stdout().is_terminal() {
+            None
+        } else {
+            Some(std::env::vars().collect::<HashMap<_, _>>())
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // On Windows, by default, a child process inherits a copy of the environment block of the parent process.
+        // So we don't need to pass env vars explicitly.
+        None
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "freebsd", target_os = "windows")))]
+    {
+        use collections::HashMap;
+
+        Some(std::env::vars().collect::<HashMap<_, _>>())
+    }
+}
+
 fn anonymous_fd(path: &str) -> Option<fs::File> {
     #[cfg(target_os = "linux")]
     {
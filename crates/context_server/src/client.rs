@@ -53,6 +53,7 @@ pub(crate) struct Client {
     outbound_tx: async_channel::Sender<String>,
     name: Arc<str>,
     subscription_set: Arc<Mutex<NotificationSubscriptionSet>>,
+    request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
     response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
     #[allow(clippy::type_complexity)]
     #[allow(dead_code)]
@@ -110,7 +111,6 @@ struct AnyResponse<'a> {
 }
 
 #[derive(Serialize, Deserialize)]
-#[allow(dead_code)]
 pub(crate) struct Response<T> {
     pub jsonrpc: &'static str,
     pub id: RequestId,
@@ -123,7 +123,6 @@ pub(crate) struct Response<T> {
 pub(crate) enum CspResult<T> {
     #[serde(rename = "result")]
     Ok(Option<T>),
-    #[allow(dead_code)]
     Error(Option<Error>),
 }
 
@@ -212,12 +211,14 @@ impl Client {
             let response_handlers = response_handlers.clone();
             let request_handlers = request_handlers.clone();
             let transport = transport.clone();
+            let outbound_tx = outbound_tx.clone();
             async move |cx| {
                 Self::handle_input(
                     transport,
                     subscription_set,
                     request_handlers,
                     response_handlers,
+                    outbound_tx,
                     cx,
                 )
                 .log_err()
@@ -250,6 +251,7 @@ impl Client {
         Ok(Self {
             server_id,
             subscription_set,
+            request_handlers,
             response_handlers,
             name: server_name,
             next_id: Default::default(),
@@ -269,11 +271,14 @@ impl Client {
     /// parses them as JSON-RPC responses or notifications, and dispatches them
     /// to the appropriate handlers. It processes both responses (which are matched
     /// to pending requests) and notifications (which trigger registered handlers).
+    /// Requests from the server with no registered handler are answered with a
+    /// "method not found" error.
     async fn handle_input(
         transport: Arc<dyn Transport>,
         subscription_set: Arc<Mutex<NotificationSubscriptionSet>>,
         request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
         response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
+        outbound_tx: async_channel::Sender<String>,
         cx: &mut AsyncApp,
     ) -> anyhow::Result<()> {
         let mut receiver = transport.receive();
@@ -288,6 +293,15 @@ impl Client {
                         request.params.unwrap_or(RawValue::NULL),
                         cx.clone(),
                     );
+                } else {
+                    send_response::<()>(
+                        &outbound_tx,
+                        request.id,
+                        CspResult::Error(Some(Error {
+                            message: format!("method not found: {}", request.method),
+                            code: METHOD_NOT_FOUND,
+                        })),
+                    );
                 }
             } else if let Ok(response) = serde_json::from_str::<AnyResponse>(&message) {
                 if let Some(handlers) = response_handlers.lock().as_mut()
@@ -505,6 +519,52 @@ impl Client {
         self.transport.set_protocol_version(version);
     }
 
+    /// Registers a handler for requests the server sends to the client, such as
+    /// `sampling/createMessage`, replacing any handler previously registered for
+    /// `method`. The outcome of the returned task is sent back as the response.
+    pub fn on_request<P, R>(
+        &self,
+        method: &'static str,
+        mut f: Box<dyn 'static + Send + FnMut(P, AsyncApp) -> Task<Result<R>>>,
+    ) where
+        P: DeserializeOwned,
+        R: Serialize + 'static,
+    {
+        let outbound_tx = self.outbound_tx.clone();
+        self.request_handlers.lock().insert(
+            method,
+            Box::new(move |id, params, cx| {
+                let params = match serde_json::from_str::<P>(params.get()) {
+                    Ok(params) => params,
+                    Err(error) => {
+                        send_response::<R>(
+                            &outbound_tx,
+                            id,
+                            CspResult::Error(Some(Error {
+                                message: error.to_string(),
+                                code: INVALID_PARAMS,
+                            })),
+                        );
+                        return;
+                    }
+                };
+                let task = f(params, cx.clone());
+                let outbound_tx = outbound_tx.clone();
+                cx.spawn(async move |_| {
+                    let value = match task.await {
+                        Ok(result) => CspResult::Ok(Some(result)),
+                        Err(error) => CspResult::Error(Some(Error {
+                            message: format!("{error:#}"),
+                            code: INTERNAL_ERROR,
+                        })),
+                    };
+                    send_response(&outbound_tx, id, value);
+                })
+                .detach();
+            }),
+        );
+    }
+
     #[must_use]
     pub fn on_notification(
         &self,
@@ -520,6 +580,20 @@ impl Client {
     }
 }
 
+fn send_response<T: Serialize>(
+    outbound_tx: &async_channel::Sender<String>,
+    id: RequestId,
+    value: CspResult<T>,
+) {
+    let response = serde_json::to_string(&Response {
+        jsonrpc: JSON_RPC_VERSION,
+        id,
+        value,
+    })
+    .unwrap();
+    outbound_tx.try_send(response).log_err();
+}
+
 #[derive(Debug)]
 pub struct RequestCanceled;
 
@@ -9,7 +9,7 @@ use std::time::Duration;
 
 use anyhow::Result;
 use futures::{channel::oneshot, future::BoxFuture};
-use gpui::AsyncApp;
+use gpui::{AsyncApp, Task};
 use serde_json::Value;
 
 use crate::client::{Client, NotificationSubscription};
@@ -42,7 +42,10 @@ impl ModelContextProtocol {
             protocol_version: types::ProtocolVersion(types::LATEST_PROTOCOL_VERSION.to_string()),
             capabilities: types::ClientCapabilities {
                 experimental: None,
-                sampling: None,
+                // The agent only serves sampling while one of the server's tools
+                // is running, and rejects requests made at any other time with
+                // an error explaining that restriction.
+                sampling: Some(serde_json::json!({})),
                 roots: None,
             },
             meta: None,
@@ -144,4 +147,13 @@ impl InitializedContextServerProtocol {
     ) -> NotificationSubscription {
         self.inner.on_notification(method, f)
     }
+
+    /// Handles requests of type `T` sent by the server, replacing any handler
+    /// previously registered for the same method.
+    pub fn on_request<T: Request>(
+        &self,
+        f: Box<dyn 'static + Send + FnMut(T::Params, AsyncApp) -> Task<Result<T::Response>>>,
+    ) {
+        self.inner.on_request(T::METHOD, f)
+    }
 }
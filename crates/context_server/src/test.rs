@@ -12,17 +12,30 @@ use crate::{
 pub fn create_fake_transport(
     name: impl Into<String>,
     executor: BackgroundExecutor,
+) -> FakeTransport {
+    create_fake_transport_with_capabilities(name, ServerCapabilities::default, executor)
+}
+
+/// Like [`create_fake_transport`], but the server advertises the capabilities
+/// returned by `capabilities` during initialization.
+pub fn create_fake_transport_with_capabilities(
+    name: impl Into<String>,
+    capabilities: impl 'static + Send + Sync + Fn() -> ServerCapabilities,
+    executor: BackgroundExecutor,
 ) -> FakeTransport {
     let name = name.into();
     FakeTransport::new(executor).on_request::<crate::types::requests::Initialize, _>(
         move |_params| {
-            let name = name.clone();
-            async move { create_initialize_response(name.clone()) }
+            let response = create_initialize_response(name.clone(), capabilities());
+            async move { response }
         },
     )
 }
 
-fn create_initialize_response(server_name: String) -> InitializeResponse {
+fn create_initialize_response(
+    server_name: String,
+    capabilities: ServerCapabilities,
+) -> InitializeResponse {
     InitializeResponse {
         protocol_version: ProtocolVersion(crate::types::LATEST_PROTOCOL_VERSION.to_string()),
         server_info: Implementation {
@@ -31,7 +44,7 @@ fn create_initialize_response(server_name: String) -> InitializeResponse {
             version: "1.0.0".to_string(),
             description: None,
         },
-        capabilities: ServerCapabilities::default(),
+        capabilities,
         meta: None,
     }
 }
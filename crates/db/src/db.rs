@@ -66,6 +66,16 @@ impl AppDatabase {
         Self(connection)
     }
 
+    /// Opens the database of a Zed started without windows to serve a CLI
+    /// request. It runs next to the user's Zed, so it keeps its sessions and
+    /// state apart from theirs.
+    pub fn headless() -> Self {
+        let db_dir = database_dir();
+        let scope = HeadlessDbScope::new(*RELEASE_CHANNEL);
+        let connection = gpui::block_on(open_db::<AppMigrator>(db_dir, scope));
+        Self(connection)
+    }
+
     /// Creates a new in-memory database with a unique name and runs all
     /// inventory-registered migrations in dependency order.
     #[cfg(any(test, feature = "test-support"))]
@@ -159,6 +169,21 @@ impl DbScope for GlobalDbScope {
     }
 }
 
+/// The database scope of a headless Zed, per release channel.
+struct HeadlessDbScope(String);
+
+impl HeadlessDbScope {
+    fn new(channel: ReleaseChannel) -> Self {
+        Self(format!("{}-headless", channel.dev_name()))
+    }
+}
+
+impl DbScope for HeadlessDbScope {
+    fn scope_name(&self) -> &str {
+        &self.0
+    }
+}
+
 /// Returns the path to the `AppDatabase` SQLite file for the given scope
 /// under `db_dir`.
 pub fn db_path(db_dir: &Path, scope: impl DbScope) -> PathBuf {
@@ -299,7 +324,22 @@ mod tests {
     use sqlez::domain::Domain;
     use sqlez_macros::sql;
 
-    use crate::open_db;
+    use crate::{HeadlessDbScope, db_path, open_db};
+    use release_channel::ReleaseChannel;
+    use std::path::Path;
+
+    #[test]
+    fn test_headless_db_is_separate_from_the_channels_db() {
+        let db_dir = Path::new("/db");
+        assert_ne!(
+            db_path(db_dir, HeadlessDbScope::new(ReleaseChannel::Stable)),
+            db_path(db_dir, ReleaseChannel::Stable)
+        );
+        assert_ne!(
+            db_path(db_dir, HeadlessDbScope::new(ReleaseChannel::Stable)),
+            db_path(db_dir, HeadlessDbScope::new(ReleaseChannel::Preview))
+        );
+    }
 
     // Test bad migration panics
     #[gpui::test]
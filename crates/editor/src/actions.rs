@@ -378,6 +378,83 @@ pub struct InsertSnippet {
     pub snippet: Option<String>,
 }
 
+/// Starts recording the actions and text typed in editors into a keyboard
+/// macro, or stops the recording in progress.
+///
+/// Undo and redo aren't recorded, since a replay is undone as a whole.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct ToggleMacroRecording {
+    /// The name to record the macro under. Unnamed macros are given a name
+    /// like `macro-1`.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Replays a keyboard macro as a single undoable edit.
+#[derive(PartialEq, Clone, Default, Debug, Serialize, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct ReplayMacro {
+    /// The macro to replay, either one recorded in this session or one saved
+    /// in the `keyboard_macros` setting. Defaults to the last recorded macro.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// How many times to replay the macro.
+    ///
+    /// Default: 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub times: Option<usize>,
+    /// Whether to replay the macro at each cursor on its own, rather than once
+    /// with all of the cursors.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub per_cursor: bool,
+}
+
+/// Saves a keyboard macro recorded in this session to the `keyboard_macros`
+/// setting, so that it can be bound to keys with `editor::ReplayMacro`.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct SaveMacro {
+    /// The macro to save. Defaults to the last recorded macro.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Wraps the selections, or the current line if nothing is selected, with an
+/// Emmet abbreviation, such as `ul>li*`.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct WrapWithEmmetAbbreviation {
+    /// The abbreviation to wrap with. When omitted, it's asked for.
+    #[serde(default)]
+    pub abbreviation: Option<String>,
+}
+
+/// Adds the selections, or the symbol around the cursor when nothing is
+/// selected, to a working set.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct AddToWorkingSet {
+    /// The working set to add to. Defaults to the last working set used.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Opens a working set, a multibuffer of excerpts that is kept across sessions.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct OpenWorkingSet {
+    /// The working set to open. Defaults to the last working set used.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
 actions!(
     debugger,
     [
@@ -515,6 +592,9 @@ actions!(
         /// Toggles all diff hunks in the editor. Collapses all hunks if any are
         /// currently expanded, otherwise expands all hunks.
         ToggleAllDiffHunks,
+        /// Expands the Emmet abbreviation before the cursor, such as
+        /// `ul>li*3`, or wraps the selections with an abbreviation.
+        ExpandEmmetAbbreviation,
         /// Expands macros recursively at cursor position.
         ExpandMacroRecursively,
         /// Finds the next match in the search.
@@ -640,6 +720,12 @@ actions!(
         MoveLineDown,
         /// Moves the current line up.
         MoveLineUp,
+        /// Moves the file under the cursor, with all of its excerpts, down in a
+        /// working set.
+        MoveDownInWorkingSet,
+        /// Moves the file under the cursor, with all of its excerpts, up in a
+        /// working set.
+        MoveUpInWorkingSet,
         /// Moves cursor right.
         MoveRight,
         /// Moves cursor to the beginning of the document.
@@ -740,6 +826,8 @@ actions!(
         Redo,
         /// Redoes the last selection change.
         RedoSelection,
+        /// Removes the excerpt under the cursor from a working set.
+        RemoveFromWorkingSet,
         /// Renames the symbol at cursor.
         Rename,
         /// Restarts the language server for the current file.
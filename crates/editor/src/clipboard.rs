@@ -13,6 +13,10 @@ pub struct ClipboardSelection {
     pub file_path: Option<PathBuf>,
     #[serde(default)]
     pub line_range: Option<RangeInclusive<u32>>,
+    /// Whether this was one row of a rectangular column selection, whose
+    /// block shape should be kept when pasting.
+    #[serde(default)]
+    pub is_block: bool,
 }
 
 impl ClipboardSelection {
@@ -52,6 +56,7 @@ impl ClipboardSelection {
             first_line_indent,
             file_path,
             line_range,
+            is_block: false,
         }
     }
 }
@@ -74,6 +79,7 @@ impl Editor {
         let clipboard_text = Cow::Borrowed(text.as_str());
 
         self.transact(window, cx, |this, window, cx| {
+            this.materialize_virtual_space(window, cx);
             let had_active_edit_prediction = this.has_active_edit_prediction();
             let display_map = this.display_snapshot(cx);
             let old_selections = this.selections.all::<MultiBufferOffset>(&display_map);
@@ -83,6 +89,28 @@ impl Editor {
                 .head();
 
             if let Some(mut clipboard_selections) = clipboard_selections {
+                // A rectangular selection pasted at a single cursor keeps its shape,
+                // with each of its rows going to the same column on consecutive lines.
+                if let [selection] = old_selections.as_slice()
+                    && selection.is_empty()
+                    && clipboard_selections.len() > 1
+                    && clipboard_selections.iter().all(|s| s.is_block)
+                {
+                    let mut start_offset = 0;
+                    let rows = clipboard_selections
+                        .iter()
+                        .map(|clipboard_selection| {
+                            let end_offset = start_offset + clipboard_selection.len;
+                            let row = &clipboard_text[start_offset..end_offset];
+                            start_offset = end_offset + 1;
+                            row
+                        })
+                        .collect::<Vec<_>>();
+                    let position = selection.start.to_point(display_map.buffer_snapshot());
+                    this.paste_column_block(&rows, position, window, cx);
+                    return;
+                }
+
                 let all_selections_were_entire_line =
                     clipboard_selections.iter().all(|s| s.is_entire_line);
                 let first_selection_indent_column =
@@ -372,8 +400,11 @@ impl Editor {
             let mut is_first = true;
             let mut prev_selection_was_entire_line = false;
             for selection in &mut selections {
-                let is_entire_line =
-                    (selection.is_empty() && cut_no_selection_line) || self.selections.line_mode();
+                let virtual_width = self.virtual_space_for_selection(selection.id).width() as usize;
+                let is_block = self.is_column_selection_row(selection.id)
+                    && (!selection.is_empty() || virtual_width > 0);
+                let is_entire_line = (selection.is_empty() && cut_no_selection_line && !is_block)
+                    || self.selections.line_mode();
                 if is_entire_line {
                     selection.start = Point::new(selection.start.row, 0);
                     if !selection.is_empty() && selection.end.column == 0 {
@@ -394,15 +425,20 @@ impl Editor {
                     text.push_str(chunk);
                     len += chunk.len();
                 }
-
-                clipboard_selections.push(ClipboardSelection::for_buffer(
-                    len,
-                    is_entire_line,
-                    selection.range(),
-                    &buffer,
-                    self.project.as_ref(),
-                    cx,
-                ));
+                text.extend(iter::repeat_n(' ', virtual_width));
+                len += virtual_width;
+
+                clipboard_selections.push(ClipboardSelection {
+                    is_block,
+                    ..ClipboardSelection::for_buffer(
+                        len,
+                        is_entire_line,
+                        selection.range(),
+                        &buffer,
+                        self.project.as_ref(),
+                        cx,
+                    )
+                });
             }
         }
 
@@ -590,7 +626,10 @@ impl Editor {
         for selection in &selections {
             let mut start = selection.start;
             let mut end = selection.end;
-            let is_entire_line = selection.is_empty() || self.selections.line_mode();
+            let virtual_width = self.virtual_space_for_selection(selection.id).width() as usize;
+            let is_block = self.is_column_selection_row(selection.id)
+                && (!selection.is_empty() || virtual_width > 0);
+            let is_entire_line = (selection.is_empty() && !is_block) || self.selections.line_mode();
             let mut add_trailing_newline = false;
             if is_entire_line {
                 start = Point::new(start.row, 0);
@@ -663,14 +702,22 @@ impl Editor {
             }
             prev_selection_was_entire_line = is_entire_line && !is_multiline_trim;
 
-            clipboard_selections.push(ClipboardSelection::for_buffer(
-                selection_len,
-                is_entire_line,
-                start..end,
-                &buffer,
-                self.project.as_ref(),
-                cx,
-            ));
+            // Columns past the end of the line are copied as spaces, so that every
+            // row of a rectangular selection has the same width.
+            text.extend(iter::repeat_n(' ', virtual_width));
+            selection_len += virtual_width;
+
+            clipboard_selections.push(ClipboardSelection {
+                is_block,
+                ..ClipboardSelection::for_buffer(
+                    selection_len,
+                    is_entire_line,
+                    start..end,
+                    &buffer,
+                    self.project.as_ref(),
+                    cx,
+                )
+            });
         }
 
         cx.write_to_clipboard(ClipboardItem::new_string_with_json_metadata(
@@ -705,6 +752,7 @@ fn kill_ring_metadata_for_text(
                 first_line_indent: first_selection.first_line_indent,
                 file_path: None,
                 line_range: None,
+                is_block: false,
             }])
         }
     }
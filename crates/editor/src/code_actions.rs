@@ -327,6 +327,24 @@ impl Editor {
         }
     }
 
+    pub fn add_code_action_provider(
+        &mut self,
+        provider: Rc<dyn CodeActionProvider>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .code_action_providers
+            .iter()
+            .any(|existing_provider| existing_provider.id() == provider.id())
+        {
+            return;
+        }
+
+        self.code_action_providers.push(provider);
+        self.refresh_code_actions_for_selection(window, cx);
+    }
+
     pub fn context_menu(&self) -> &RefCell<Option<CodeContextMenu>> {
         &self.context_menu
     }
@@ -376,7 +394,7 @@ impl Editor {
             .into_any_element()
     }
 
-    pub(super) fn refresh_code_actions_for_selection(
+    pub fn refresh_code_actions_for_selection(
         &mut self,
         window: &mut Window,
         cx: &mut Context<Self>,
@@ -627,6 +627,11 @@ impl Editor {
             }))
         };
 
+        let emmet_completion = provider
+            .as_ref()
+            .filter(|provider| provider.show_snippets())
+            .and_then(|_| emmet::completion_at(&buffer_snapshot, buffer_position, cx));
+
         let snippet_sort_order = EditorSettings::get_global(cx).snippet_sort_order;
 
         let id = post_inc(&mut self.next_completion_id);
@@ -693,6 +698,13 @@ impl Editor {
                     .flat_map(|response| response.completions),
             );
 
+            // The expansion depends on the whole abbreviation, so it has to be
+            // recomputed as it's typed rather than filtered.
+            if let Some(emmet_completion) = emmet_completion {
+                completions.push(emmet_completion);
+                is_incomplete = true;
+            }
+
             let menu = if completions.is_empty() {
                 None
             } else {
@@ -186,6 +186,7 @@ pub enum HighlightKey {
     Rename,
     SearchWithinRange,
     SelectedTextHighlight,
+    SpellCheck,
     SyntaxTreeView(usize),
     VimExchange,
 }
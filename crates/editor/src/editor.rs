@@ -23,6 +23,7 @@ mod document_links;
 mod document_symbols;
 mod editor_settings;
 mod element;
+mod emmet;
 mod fold;
 mod folding_ranges;
 mod git;
@@ -33,6 +34,7 @@ mod indent_guides;
 mod inlays;
 pub mod items;
 mod jsx_tag_auto_close;
+mod keyboard_macros;
 mod linked_editing_ranges;
 mod lsp_ext;
 mod mouse_context_menu;
@@ -45,6 +47,7 @@ mod selections_collection;
 pub mod semantic_tokens;
 mod split;
 pub mod split_editor_view;
+mod working_set;
 
 mod bookmarks;
 #[cfg(test)]
@@ -70,6 +73,7 @@ mod markdown_actions;
 mod navigation;
 mod rewrap;
 mod selection;
+mod virtual_space;
 
 pub(crate) use actions::*;
 pub use clipboard::ClipboardSelection;
@@ -129,6 +133,7 @@ pub use multi_buffer::{
 pub use split::{DiffStyleControls, SplittableEditor, ToggleSplitDiff};
 pub use split_editor_view::SplitEditorView;
 pub use text::Bias;
+pub use working_set::WorkingSetView;
 
 use ::git::{Blame, status::FileStatus};
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder, BuildError};
@@ -261,6 +266,8 @@ use ui::{
 };
 use ui_input::ErasedEditor;
 use util::{RangeExt, ResultExt, TryFutureExt, maybe, post_inc};
+pub(crate) use virtual_space::VirtualSpace;
+use virtual_space::{ColumnSelection, VirtualMotion};
 use workspace::{
     CollaboratorId, Item as WorkspaceItem, ItemId, ItemNavHistory, NavigationEntry, OpenInTerminal,
     OpenTerminal, Pane, RestoreOnStartupBehavior, SERIALIZATION_THROTTLE_TIME, SplitDirection,
@@ -354,6 +361,7 @@ pub fn init(cx: &mut App) {
     workspace::register_project_item::<Editor>(cx);
     workspace::FollowableViewRegistry::register::<Editor>(cx);
     workspace::register_serializable_item::<Editor>(cx);
+    workspace::register_serializable_item::<WorkingSetView>(cx);
 
     cx.observe_new(
         |workspace: &mut Workspace, _: Option<&mut Window>, _cx: &mut Context<Workspace>| {
@@ -364,6 +372,7 @@ pub fn init(cx: &mut App) {
             workspace.register_action(Editor::cancel_language_server_work);
             workspace.register_action(Editor::toggle_focus);
             workspace.register_action(Editor::view_bookmarks);
+            workspace.register_action(WorkingSetView::open);
         },
     )
     .detach();
@@ -396,6 +405,7 @@ pub fn init(cx: &mut App) {
             .update(cx, |editor, cx| editor.erased(cx))
     });
     _ = multi_buffer::EXCERPT_CONTEXT_LINES.set(multibuffer_context_lines);
+    keyboard_macros::init(cx);
 }
 
 pub struct SearchWithinRange;
@@ -938,6 +948,7 @@ pub struct Editor {
     /// typing enters text into each of them, even the ones that aren't focused.
     pub(crate) show_cursor_when_unfocused: bool,
     columnar_selection_state: Option<ColumnarSelectionState>,
+    column_selection: ColumnSelection,
     add_selections_state: Option<AddSelectionsState>,
     select_next_state: Option<SelectNextState>,
     select_prev_state: Option<SelectNextState>,
@@ -2263,6 +2274,7 @@ impl Editor {
             selections,
             scroll_manager: ScrollManager::new(cx),
             columnar_selection_state: None,
+            column_selection: ColumnSelection::default(),
             add_selections_state: None,
             select_next_state: None,
             select_prev_state: None,
@@ -5004,13 +5016,15 @@ impl Editor {
         }
         self.transact(window, cx, |this, window, cx| {
             this.select_autoclose_pair(window, cx);
+            // Cursors in virtual space move left over the empty columns instead.
+            let virtual_spaces = this.take_virtual_deletions(true);
 
             let linked_edits = this.linked_edits_for_selections(Arc::from(""), cx);
 
             let display_map = this.display_map.update(cx, |map, cx| map.snapshot(cx));
             let mut selections = this.selections.all::<MultiBufferPoint>(&display_map);
             for selection in &mut selections {
-                if selection.is_empty() {
+                if selection.is_empty() && !virtual_spaces.contains_key(&selection.id) {
                     let old_head = selection.head();
                     let mut new_head =
                         movement::left(&display_map, old_head.to_display_point(&display_map))
@@ -5044,6 +5058,7 @@ impl Editor {
 
             this.change_selections(Default::default(), window, cx, |s| s.select(selections));
             this.insert("", window, cx);
+            this.set_virtual_space(virtual_spaces, cx);
             linked_edits.apply_with_left_expansion(cx);
             this.refresh_edit_prediction(
                 true,
@@ -5061,9 +5076,13 @@ impl Editor {
             return;
         }
         self.transact(window, cx, |this, window, cx| {
+            // Selections covering only virtual space collapse to their start,
+            // and cursors in virtual space pad their line to join the next one.
+            let virtual_spaces = this.take_virtual_deletions(false);
+            this.materialize_virtual_space(window, cx);
             this.change_selections(Default::default(), window, cx, |s| {
                 s.move_with(&mut |map, selection| {
-                    if selection.is_empty() {
+                    if selection.is_empty() && !virtual_spaces.contains_key(&selection.id) {
                         let cursor = movement::right(map, selection.head());
                         selection.end = cursor;
                         selection.reversed = true;
@@ -5073,6 +5092,7 @@ impl Editor {
             });
             let linked_edits = this.linked_edits_for_selections(Arc::from(""), cx);
             this.insert("", window, cx);
+            this.set_virtual_space(virtual_spaces, cx);
             linked_edits.apply(cx);
             this.refresh_edit_prediction(
                 true,
@@ -7701,7 +7721,7 @@ impl Editor {
     }
 
     pub fn undo(&mut self, _: &Undo, window: &mut Window, cx: &mut Context<Self>) {
-        if self.read_only(cx) {
+        if self.read_only(cx) || keyboard_macros::is_replaying_macro(cx) {
             return;
         }
 
@@ -7727,7 +7747,7 @@ impl Editor {
     }
 
     pub fn redo(&mut self, _: &Redo, window: &mut Window, cx: &mut Context<Self>) {
-        if self.read_only(cx) {
+        if self.read_only(cx) || keyboard_macros::is_replaying_macro(cx) {
             return;
         }
 
@@ -1,14 +1,15 @@
 use core::num;
 
+use collections::HashMap;
 use gpui::App;
 use language::CursorShape;
 use project::project_settings::DiagnosticSeverity;
 pub use settings::{
     CodeLens, CompletionDetailAlignment, CompletionMenuItemKind, CurrentLineHighlight, DelayMs,
     DiffViewStyle, DisplayIn, DocumentColorsRenderMode, DoubleClickInMultibuffer, GitGutterWidth,
-    GoToDefinitionFallback, GoToDefinitionScrollStrategy, MinimapThumb, MinimapThumbBorder,
-    MultiCursorModifier, OpenResultsIn, ScrollBeyondLastLine, ScrollbarDiagnostics,
-    SeedQuerySetting, ShowMinimap, SnippetSortOrder,
+    GoToDefinitionFallback, GoToDefinitionScrollStrategy, KeyboardMacroStep, MinimapThumb,
+    MinimapThumbBorder, MultiCursorModifier, OpenResultsIn, ScrollBeyondLastLine,
+    ScrollbarDiagnostics, SeedQuerySetting, ShowMinimap, SnippetSortOrder,
 };
 use settings::{RegisterSetting, RelativeLineNumbers, Settings};
 use ui::scrollbars::ShowScrollbar;
@@ -22,6 +23,7 @@ pub struct EditorSettings {
     pub current_line_highlight: CurrentLineHighlight,
     pub selection_highlight: bool,
     pub rounded_selection: bool,
+    pub virtual_space: bool,
     pub lsp_highlight_debounce: DelayMs,
     pub hover_popover_enabled: bool,
     pub hover_popover_delay: DelayMs,
@@ -70,6 +72,7 @@ pub struct EditorSettings {
     pub diff_view_style: DiffViewStyle,
     pub minimum_split_diff_width: f32,
     pub file_diff: FileDiffSettings,
+    pub keyboard_macros: HashMap<String, Vec<KeyboardMacroStep>>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -221,6 +224,7 @@ impl Settings for EditorSettings {
             current_line_highlight: editor.current_line_highlight.unwrap(),
             selection_highlight: editor.selection_highlight.unwrap(),
             rounded_selection: editor.rounded_selection.unwrap(),
+            virtual_space: editor.virtual_space.unwrap(),
             lsp_highlight_debounce: editor.lsp_highlight_debounce.unwrap(),
             hover_popover_enabled: editor.hover_popover_enabled.unwrap(),
             hover_popover_delay: editor.hover_popover_delay.unwrap(),
@@ -328,6 +332,7 @@ impl Settings for EditorSettings {
             file_diff: FileDiffSettings {
                 show_full_file: file_diff.show_full_file.unwrap(),
             },
+            keyboard_macros: editor.keyboard_macros.unwrap(),
         }
     }
 }
@@ -2309,6 +2309,34 @@ fn test_move_cursor_multibyte(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_move_cursor_through_right_to_left_text(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // The Hebrew word is displayed reversed, as "ab גבא cd", so moving right
+    // steps backwards through it.
+    cx.set_state("abˇ אבג cd\nxy");
+    cx.update_editor(|editor, window, cx| editor.move_right(&MoveRight, window, cx));
+    cx.assert_editor_state("ab ˇאבג cd\nxy");
+    cx.update_editor(|editor, window, cx| editor.move_right(&MoveRight, window, cx));
+    cx.assert_editor_state("ab אבˇג cd\nxy");
+    cx.update_editor(|editor, window, cx| editor.move_right(&MoveRight, window, cx));
+    cx.assert_editor_state("ab אˇבג cd\nxy");
+    cx.update_editor(|editor, window, cx| editor.move_right(&MoveRight, window, cx));
+    cx.assert_editor_state("ab אבגˇ cd\nxy");
+    cx.update_editor(|editor, window, cx| editor.move_left(&MoveLeft, window, cx));
+    cx.assert_editor_state("ab אˇבג cd\nxy");
+
+    // Moving past the visual end of the line wraps to the next one.
+    cx.set_state("ab אבג cˇd\nxy");
+    cx.update_editor(|editor, window, cx| {
+        editor.move_right(&MoveRight, window, cx);
+        editor.move_right(&MoveRight, window, cx);
+    });
+    cx.assert_editor_state("ab אבג cd\nˇxy");
+}
+
 #[gpui::test]
 fn test_move_cursor_different_line_lengths(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -43316,6 +43344,292 @@ async fn test_columnar_selection_past_end_of_line(cx: &mut TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_columnar_selection_in_virtual_space(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    update_test_editor_settings(cx, &|settings| {
+        settings.virtual_space = Some(true);
+    });
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let select_columns = |cx: &mut EditorTestContext| {
+        cx.update_editor(|editor, window, cx| {
+            editor.select(
+                SelectPhase::BeginColumnar {
+                    position: DisplayPoint::new(DisplayRow(0), 4),
+                    goal_column: 4,
+                    reset: true,
+                    mode: ColumnarMode::FromMouse,
+                },
+                window,
+                cx,
+            );
+            editor.select(
+                SelectPhase::Update {
+                    position: DisplayPoint::new(DisplayRow(2), 6),
+                    goal_column: 6,
+                    scroll_delta: gpui::Point::default(),
+                },
+                window,
+                cx,
+            );
+            editor.select(SelectPhase::End, window, cx);
+        });
+    };
+
+    // The short row keeps its part of the rectangle in virtual space, and
+    // typing there pads the row out to the rectangle's columns.
+    cx.set_state(indoc! {"
+        ˇaaaaaaaaaa
+        bb
+        cccccccccc
+    "});
+    select_columns(&mut cx);
+    cx.assert_editor_state(indoc! {"
+        aaaa«aaˇ»aaaa
+        bbˇ
+        cccc«ccˇ»cccc
+    "});
+    cx.update_editor(|editor, _, cx| {
+        let spaces = editor
+            .selections
+            .all::<Point>(&editor.display_snapshot(cx))
+            .iter()
+            .map(|selection| editor.virtual_space_for_selection(selection.id))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            spaces,
+            vec![
+                VirtualSpace::default(),
+                VirtualSpace { start: 2, end: 4 },
+                VirtualSpace::default(),
+            ]
+        );
+    });
+    cx.update_editor(|editor, window, cx| editor.handle_input("X", window, cx));
+    cx.assert_editor_state(indoc! {"
+        aaaaXˇaaaa
+        bb  Xˇ
+        ccccXˇcccc
+    "});
+
+    // The padding is undone together with the text typed into it.
+    cx.update_editor(|editor, window, cx| editor.undo(&Undo, window, cx));
+    cx.assert_editor_state(indoc! {"
+        aaaa«aaˇ»aaaa
+        bbˇ
+        cccc«ccˇ»cccc
+    "});
+
+    // Copying keeps the rectangle's width on the short row, and pasting it at a
+    // single cursor keeps its shape, padding short rows and appending new ones.
+    cx.set_state(indoc! {"
+        ˇaaaaaaaaaa
+        bb
+        cccccccccc"});
+    select_columns(&mut cx);
+    cx.update_editor(|editor, window, cx| editor.copy(&Copy, window, cx));
+    assert_eq!(
+        cx.read_from_clipboard().and_then(|item| item.text()),
+        Some("aa\n  \ncc".to_string())
+    );
+
+    cx.set_state("xˇy\nz");
+    cx.update_editor(|editor, window, cx| editor.paste(&Paste, window, cx));
+    cx.assert_editor_state("xaay\nz  \n ccˇ");
+}
+
+fn virtual_spaces(cx: &mut EditorTestContext) -> Vec<VirtualSpace> {
+    cx.update_editor(|editor, _, cx| {
+        editor
+            .selections
+            .all::<Point>(&editor.display_snapshot(cx))
+            .iter()
+            .map(|selection| editor.virtual_space_for_selection(selection.id))
+            .collect()
+    })
+}
+
+#[gpui::test]
+async fn test_moving_in_virtual_space(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    update_test_editor_settings(cx, &|settings| {
+        settings.virtual_space = Some(true);
+    });
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // Moving right from the end of a line moves past it.
+    cx.set_state(indoc! {"
+        aaˇ
+        bbbbbb
+        c"});
+    cx.update_editor(|editor, window, cx| {
+        editor.move_right(&MoveRight, window, cx);
+        editor.move_right(&MoveRight, window, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        aaˇ
+        bbbbbb
+        c"});
+    assert_eq!(virtual_spaces(&mut cx), vec![VirtualSpace::at(2)]);
+
+    // Moving down keeps the cursor's column, past the end of shorter lines.
+    cx.update_editor(|editor, window, cx| editor.move_down(&MoveDown, window, cx));
+    cx.assert_editor_state(indoc! {"
+        aa
+        bbbbˇbb
+        c"});
+    assert_eq!(virtual_spaces(&mut cx), vec![VirtualSpace::default()]);
+    cx.update_editor(|editor, window, cx| editor.move_down(&MoveDown, window, cx));
+    cx.assert_editor_state(indoc! {"
+        aa
+        bbbbbb
+        cˇ"});
+    assert_eq!(virtual_spaces(&mut cx), vec![VirtualSpace::at(3)]);
+    cx.update_editor(|editor, window, cx| editor.move_up(&MoveUp, window, cx));
+    cx.assert_editor_state(indoc! {"
+        aa
+        bbbbˇbb
+        c"});
+    cx.update_editor(|editor, window, cx| editor.move_up(&MoveUp, window, cx));
+    cx.assert_editor_state(indoc! {"
+        aaˇ
+        bbbbbb
+        c"});
+    assert_eq!(virtual_spaces(&mut cx), vec![VirtualSpace::at(2)]);
+
+    // Moving left removes virtual columns before moving into the line.
+    cx.update_editor(|editor, window, cx| {
+        editor.move_left(&MoveLeft, window, cx);
+        editor.move_left(&MoveLeft, window, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        aaˇ
+        bbbbbb
+        c"});
+    assert_eq!(virtual_spaces(&mut cx), vec![VirtualSpace::default()]);
+    cx.update_editor(|editor, window, cx| editor.move_left(&MoveLeft, window, cx));
+    cx.assert_editor_state(indoc! {"
+        aˇa
+        bbbbbb
+        c"});
+
+    // Typing in virtual space pads the line up to the cursor.
+    cx.update_editor(|editor, window, cx| {
+        editor.move_down(&MoveDown, window, cx);
+        editor.move_down(&MoveDown, window, cx);
+        editor.move_right(&MoveRight, window, cx);
+        editor.move_right(&MoveRight, window, cx);
+        editor.handle_input("X", window, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        aa
+        bbbbbb
+        c  Xˇ"});
+}
+
+#[gpui::test]
+async fn test_deleting_in_virtual_space(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    update_test_editor_settings(cx, &|settings| {
+        settings.virtual_space = Some(true);
+    });
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // Backspace moves a cursor in virtual space back without editing its
+    // line, while other cursors still delete.
+    cx.set_state(indoc! {"
+        aaˇ
+        bbˇbb"});
+    cx.update_editor(|editor, window, cx| {
+        editor.move_right(&MoveRight, window, cx);
+        editor.move_right(&MoveRight, window, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        aaˇ
+        bbbbˇ"});
+    assert_eq!(
+        virtual_spaces(&mut cx),
+        vec![VirtualSpace::at(2), VirtualSpace::default()]
+    );
+    cx.update_editor(|editor, window, cx| editor.backspace(&Backspace, window, cx));
+    cx.assert_editor_state(indoc! {"
+        aaˇ
+        bbbˇ"});
+    assert_eq!(
+        virtual_spaces(&mut cx),
+        vec![VirtualSpace::at(1), VirtualSpace::default()]
+    );
+    cx.update_editor(|editor, window, cx| editor.backspace(&Backspace, window, cx));
+    cx.assert_editor_state(indoc! {"
+        aaˇ
+        bbˇ"});
+    assert_eq!(
+        virtual_spaces(&mut cx),
+        vec![VirtualSpace::default(), VirtualSpace::default()]
+    );
+    cx.update_editor(|editor, window, cx| editor.backspace(&Backspace, window, cx));
+    cx.assert_editor_state(indoc! {"
+        aˇ
+        bˇ"});
+
+    // The part of a column selection in virtual space collapses to its start.
+    cx.set_state(indoc! {"
+        ˇaaaaaaaaaa
+        bb
+        cccccccccc"});
+    cx.update_editor(|editor, window, cx| {
+        editor.select(
+            SelectPhase::BeginColumnar {
+                position: DisplayPoint::new(DisplayRow(0), 4),
+                goal_column: 4,
+                reset: true,
+                mode: ColumnarMode::FromMouse,
+            },
+            window,
+            cx,
+        );
+        editor.select(
+            SelectPhase::Update {
+                position: DisplayPoint::new(DisplayRow(2), 6),
+                goal_column: 6,
+                scroll_delta: gpui::Point::default(),
+            },
+            window,
+            cx,
+        );
+        editor.select(SelectPhase::End, window, cx);
+        editor.backspace(&Backspace, window, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        aaaaˇaaaa
+        bbˇ
+        ccccˇcccc"});
+    assert_eq!(
+        virtual_spaces(&mut cx),
+        vec![
+            VirtualSpace::default(),
+            VirtualSpace::at(2),
+            VirtualSpace::default(),
+        ]
+    );
+
+    // Delete pads the line up to the cursor and joins the next line onto it.
+    cx.set_state(indoc! {"
+        aaˇ
+        bb"});
+    cx.update_editor(|editor, window, cx| {
+        editor.move_right(&MoveRight, window, cx);
+        editor.move_right(&MoveRight, window, cx);
+        editor.delete(&Delete, window, cx);
+    });
+    cx.assert_editor_state("aa  ˇbb");
+    assert_eq!(virtual_spaces(&mut cx), vec![VirtualSpace::default()]);
+}
+
 #[gpui::test]
 async fn test_columnar_selection_with_soft_wrap(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -43979,3 +44293,104 @@ async fn assert_range_format_merge(
     );
     assert!(!cx.read(|cx| editor.is_dirty(cx)));
 }
+
+#[gpui::test]
+async fn test_keyboard_macros(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    cx.update(|cx| {
+        let default_key_bindings = settings::KeymapFile::load_asset_allow_partial_failure(
+            "keymaps/default-linux.json",
+            cx,
+        )
+        .unwrap();
+        cx.bind_keys(default_key_bindings);
+        cx.bind_keys([
+            gpui::KeyBinding::new(
+                "ctrl-alt-2",
+                ReplayMacro {
+                    times: Some(2),
+                    ..Default::default()
+                },
+                Some("Editor"),
+            ),
+            gpui::KeyBinding::new(
+                "ctrl-alt-3",
+                ReplayMacro {
+                    per_cursor: true,
+                    ..Default::default()
+                },
+                Some("Editor"),
+            ),
+        ]);
+    });
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("ˇaa\nbb\ncc\ndd\nee");
+    cx.simulate_keystrokes("ctrl-k shift-q end x down ctrl-k shift-q");
+    cx.assert_editor_state("aax\nbbˇ\ncc\ndd\nee");
+
+    cx.simulate_keystrokes("ctrl-k shift-p");
+    cx.assert_editor_state("aax\nbbx\nccˇ\ndd\nee");
+
+    // Every replay is undone as a whole.
+    cx.simulate_keystrokes("ctrl-alt-2");
+    cx.assert_editor_state("aax\nbbx\nccx\nddx\neeˇ");
+    cx.simulate_keystrokes("ctrl-z");
+    cx.assert_editor_state("aax\nbbx\nccˇ\ndd\nee");
+
+    // Undo isn't recorded.
+    cx.set_state("aˇa\nbb\ncˇc");
+    cx.simulate_keystrokes("ctrl-k shift-q ctrl-home y ctrl-z ctrl-k shift-q");
+    cx.assert_editor_state("ˇaa\nbb\ncc");
+
+    cx.set_state("aˇa\nbb\ncˇc");
+    cx.simulate_keystrokes("ctrl-k shift-p");
+    cx.assert_editor_state("yˇaa\nbb\ncc");
+
+    // Replaying at each cursor on its own moves both cursors to the start.
+    cx.set_state("aˇa\nbb\ncˇc");
+    cx.simulate_keystrokes("ctrl-alt-3");
+    assert_eq!(cx.buffer_text(), "yyaa\nbb\ncc");
+}
+
+#[gpui::test]
+async fn test_expand_emmet_abbreviation(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let html_language = Arc::new(Language::new(
+        LanguageConfig {
+            name: "HTML".into(),
+            ..Default::default()
+        },
+        Some(tree_sitter_html::LANGUAGE.into()),
+    ));
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(html_language), cx));
+
+    cx.set_state("ul>li*2ˇ");
+    cx.update_editor(|editor, window, cx| {
+        editor.expand_emmet_abbreviation(&ExpandEmmetAbbreviation, window, cx)
+    });
+    cx.assert_editor_state("<ul>\n    <li>ˇ</li>\n    <li></li>\n</ul>");
+    cx.update_editor(|editor, window, cx| assert!(editor.move_to_next_snippet_tabstop(window, cx)));
+    cx.assert_editor_state("<ul>\n    <li></li>\n    <li>ˇ</li>\n</ul>");
+
+    // Nothing is expanded inside a tag.
+    cx.set_state("<a hrefˇ");
+    cx.update_editor(|editor, window, cx| {
+        editor.expand_emmet_abbreviation(&ExpandEmmetAbbreviation, window, cx)
+    });
+    cx.assert_editor_state("<a hrefˇ");
+
+    cx.set_state("«Home\nAboutˇ»");
+    cx.update_editor(|editor, window, cx| {
+        editor.wrap_with_emmet_abbreviation(
+            &WrapWithEmmetAbbreviation {
+                abbreviation: Some("ul>li*".into()),
+            },
+            window,
+            cx,
+        )
+    });
+    cx.assert_editor_state("<ul>\n    <li>Home</li>\n    <li>About</li>\n</ul>ˇ");
+}
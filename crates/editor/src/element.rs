@@ -14,7 +14,7 @@ use crate::{
     EditorStyle, FILE_HEADER_HEIGHT, FocusedBlock, GutterDimensions, HalfPageDown, HalfPageUp,
     HandleInput, HoveredCursor, InlayHintRefreshReason, LineDown, LineHighlight, LineUp,
     MAX_LINE_LEN, MINIMAP_FONT_SIZE, PageDown, PageUp, Point, RowExt, RowRangeExt, Selection,
-    SelectionDragState, SizingBehavior, SoftWrap, ToPoint,
+    SelectionDragState, SizingBehavior, SoftWrap, ToPoint, VirtualSpace,
     code_context_menus::{CodeActionsMenu, MENU_ASIDE_MAX_WIDTH, MENU_ASIDE_MIN_WIDTH, MENU_GAP},
     column_pixels,
     display_map::{
@@ -147,6 +147,8 @@ struct SelectionLayout {
     range: Range<DisplayPoint>,
     active_rows: Range<DisplayRow>,
     user_name: Option<SharedString>,
+    virtual_space: VirtualSpace,
+    virtual_head_columns: u32,
 }
 
 struct InlineBlameLayout {
@@ -227,6 +229,8 @@ impl SelectionLayout {
             range,
             active_rows,
             user_name,
+            virtual_space: VirtualSpace::default(),
+            virtual_head_columns: 0,
         }
     }
 }
@@ -567,6 +571,12 @@ impl EditorElement {
         register_action(editor, window, Editor::edit_bookmark);
         register_action(editor, window, Editor::go_to_next_bookmark);
         register_action(editor, window, Editor::go_to_previous_bookmark);
+        register_action(editor, window, Editor::toggle_macro_recording);
+        register_action(editor, window, Editor::replay_macro);
+        register_action(editor, window, Editor::save_macro);
+        register_action(editor, window, Editor::expand_emmet_abbreviation);
+        register_action(editor, window, Editor::wrap_with_emmet_abbreviation);
+        register_action(editor, window, Editor::add_to_working_set);
         register_action(editor, window, Editor::toggle_breakpoint);
         register_action(editor, window, Editor::edit_log_breakpoint);
         register_action(editor, window, Editor::enable_breakpoint);
@@ -798,8 +808,10 @@ impl EditorElement {
                 for selection in local_selections.iter().cloned() {
                     let is_empty = selection.start == selection.end;
                     let is_newest = selection == newest;
+                    let virtual_space = editor.virtual_space_for_selection(selection.id);
+                    let virtual_head_columns = virtual_space.head(selection.reversed);
 
-                    let layout = SelectionLayout::new(
+                    let mut layout = SelectionLayout::new(
                         selection,
                         editor.selections.line_mode(),
                         editor.cursor_offset_on_selection,
@@ -809,6 +821,8 @@ impl EditorElement {
                         editor.leader_id.is_none(),
                         None,
                     );
+                    layout.virtual_space = virtual_space;
+                    layout.virtual_head_columns = virtual_head_columns;
                     if is_newest {
                         newest_selection_head = Some(layout.head);
                     }
@@ -1043,13 +1057,23 @@ impl EditorElement {
                         [cursor_position.row().minus(visible_display_row_range.start) as usize];
                     let cursor_column = cursor_position.column() as usize;
 
+                    // A cursor in virtual space sits on an empty cell past the line's end.
+                    let in_virtual_space = selection.virtual_head_columns > 0;
                     let cursor_character_x = cursor_row_layout.x_for_index(cursor_column)
                         + cursor_row_layout
-                            .alignment_offset(self.style.text.text_align, text_hitbox.size.width);
-                    let cursor_next_x = cursor_row_layout.x_for_index(cursor_column + 1)
-                        + cursor_row_layout
-                            .alignment_offset(self.style.text.text_align, text_hitbox.size.width);
-                    let mut cell_width = cursor_next_x - cursor_character_x;
+                            .alignment_offset(self.style.text.text_align, text_hitbox.size.width)
+                        + em_advance * selection.virtual_head_columns as f32;
+                    let cursor_next_x = if in_virtual_space {
+                        cursor_character_x + em_advance
+                    } else {
+                        cursor_row_layout.x_for_index(cursor_column + 1)
+                            + cursor_row_layout.alignment_offset(
+                                self.style.text.text_align,
+                                text_hitbox.size.width,
+                            )
+                    };
+                    // Right-to-left text places the next character to the left.
+                    let mut cell_width = (cursor_next_x - cursor_character_x).abs();
                     if cell_width == Pixels::ZERO {
                         cell_width = em_advance;
                     }
@@ -1061,7 +1085,9 @@ impl EditorElement {
                         .iter()
                         .any(|range| range.start <= cursor_position && cursor_position < range.end);
 
-                    if selection.cursor_shape == CursorShape::Block && !is_cursor_in_redacted_range
+                    if selection.cursor_shape == CursorShape::Block
+                        && !is_cursor_in_redacted_range
+                        && !in_virtual_space
                     {
                         if let Some(text) = snapshot.grapheme_at(cursor_position).or_else(|| {
                             if snapshot.is_empty() {
@@ -5630,6 +5656,16 @@ impl EditorElement {
                         layout,
                         window,
                     );
+                    if selection.virtual_space.width() > 0 {
+                        self.paint_virtual_space_highlight(
+                            selection.head.row(),
+                            selection.virtual_space,
+                            player_color.selection,
+                            corner_radius,
+                            layout,
+                            window,
+                        );
+                    }
 
                     if selection.is_local && !selection.range.is_empty() {
                         invisible_display_ranges.push(selection.range.clone());
@@ -6230,62 +6266,128 @@ impl EditorElement {
                     ..cmp::min(range.end.row().next_row(), end_row)
             };
 
-            let highlighted_range = HighlightedRange {
-                color,
-                line_height: layout.position_map.line_height,
-                corner_radius,
-                start_y: layout.content_origin.y
+            let line_height = layout.position_map.line_height;
+            let row_y = |row: DisplayRow| {
+                layout.content_origin.y
                     + Pixels::from(
-                        (row_range.start.as_f64() - layout.position_map.scroll_position.y)
-                            * ScrollOffset::from(layout.position_map.line_height),
-                    ),
-                lines: row_range
-                    .iter_rows()
-                    .map(|row| {
-                        let line_layout =
-                            &layout.position_map.line_layouts[row.minus(start_row) as usize];
-                        let alignment_offset =
-                            line_layout.alignment_offset(layout.text_align, layout.content_width);
-                        HighlightedRangeLine {
-                            start_x: if row == range.start.row() {
-                                layout.content_origin.x
-                                    + Pixels::from(
-                                        ScrollPixelOffset::from(
-                                            line_layout.x_for_index(range.start.column() as usize)
-                                                + alignment_offset,
-                                        ) - layout.position_map.scroll_pixel_position.x,
-                                    )
-                            } else {
-                                layout.content_origin.x + alignment_offset
-                                    - Pixels::from(layout.position_map.scroll_pixel_position.x)
-                            },
-                            end_x: if row == range.end.row() {
-                                layout.content_origin.x
-                                    + Pixels::from(
-                                        ScrollPixelOffset::from(
-                                            line_layout.x_for_index(range.end.column() as usize)
-                                                + alignment_offset,
-                                        ) - layout.position_map.scroll_pixel_position.x,
-                                    )
-                            } else {
-                                Pixels::from(
-                                    ScrollPixelOffset::from(
-                                        layout.content_origin.x
-                                            + line_layout.width
-                                            + alignment_offset
-                                            + line_end_overshoot,
-                                    ) - layout.position_map.scroll_pixel_position.x,
-                                )
-                            },
+                        (row.as_f64() - layout.position_map.scroll_position.y)
+                            * ScrollOffset::from(line_height),
+                    )
+            };
+            let text_x = |x: Pixels| {
+                layout.content_origin.x
+                    + Pixels::from(
+                        ScrollPixelOffset::from(x) - layout.position_map.scroll_pixel_position.x,
+                    )
+            };
+            let highlighted_range =
+                |start_y: Pixels, lines: Vec<HighlightedRangeLine>| HighlightedRange {
+                    color,
+                    line_height,
+                    corner_radius,
+                    start_y,
+                    lines,
+                };
+
+            // Rows highlighting a single span are joined into one shape. Rows that
+            // mix text directions can highlight several spans, painted on their own.
+            let mut highlighted_ranges = Vec::new();
+            let mut joined_range: Option<HighlightedRange> = None;
+            for row in row_range.iter_rows() {
+                let line_layout = &layout.position_map.line_layouts[row.minus(start_row) as usize];
+                let alignment_offset =
+                    line_layout.alignment_offset(layout.text_align, layout.content_width);
+                let start_column = if row == range.start.row() {
+                    range.start.column() as usize
+                } else {
+                    0
+                };
+                let end_column = if row == range.end.row() {
+                    range.end.column() as usize
+                } else {
+                    line_layout.len
+                };
+
+                let mut x_ranges = line_layout.x_ranges_for_range(start_column..end_column);
+                if row != range.end.row() {
+                    let is_single_span = x_ranges.len() == 1;
+                    let line_end_x = line_layout.width + line_end_overshoot;
+                    match x_ranges.last_mut() {
+                        Some(last) if is_single_span || last.end == line_layout.width => {
+                            last.end = line_end_x
                         }
+                        _ => x_ranges.push(line_layout.width..line_end_x),
+                    }
+                }
+
+                let mut lines = x_ranges
+                    .into_iter()
+                    .map(|x_range| HighlightedRangeLine {
+                        start_x: text_x(x_range.start + alignment_offset),
+                        end_x: text_x(x_range.end + alignment_offset),
                     })
-                    .collect(),
-            };
+                    .collect::<Vec<_>>();
+                if lines.len() == 1 {
+                    joined_range
+                        .get_or_insert_with(|| highlighted_range(row_y(row), Vec::new()))
+                        .lines
+                        .append(&mut lines);
+                } else {
+                    highlighted_ranges.extend(joined_range.take());
+                    highlighted_ranges.extend(
+                        lines
+                            .into_iter()
+                            .map(|line| highlighted_range(row_y(row), vec![line])),
+                    );
+                }
+            }
+            highlighted_ranges.extend(joined_range);
 
-            highlighted_range.paint(fill, layout.position_map.text_hitbox.bounds, window);
+            for highlighted_range in highlighted_ranges {
+                highlighted_range.paint(fill, layout.position_map.text_hitbox.bounds, window);
+            }
         }
     }
 
+    /// Highlights the columns of a selection that sit past the end of its line.
+    fn paint_virtual_space_highlight(
+        &self,
+        row: DisplayRow,
+        virtual_space: VirtualSpace,
+        color: Hsla,
+        corner_radius: Pixels,
+        layout: &EditorLayout,
+        window: &mut Window,
+    ) {
+        if !layout.visible_display_row_range.contains(&row) {
+            return;
+        }
+
+        let position_map = &layout.position_map;
+        let line_layout =
+            &position_map.line_layouts[row.minus(layout.visible_display_row_range.start) as usize];
+        let line_end_x = layout.content_origin.x
+            + line_layout.width
+            + line_layout.alignment_offset(layout.text_align, layout.content_width)
+            - Pixels::from(position_map.scroll_pixel_position.x);
+        let start_y = layout.content_origin.y
+            + Pixels::from(
+                (row.as_f64() - position_map.scroll_position.y)
+                    * ScrollOffset::from(position_map.line_height),
+            );
+        HighlightedRange {
+            color,
+            line_height: position_map.line_height,
+            corner_radius,
+            start_y,
+            lines: vec![HighlightedRangeLine {
+                start_x: line_end_x + position_map.em_advance * virtual_space.start as f32,
+                end_x: line_end_x + position_map.em_advance * virtual_space.end as f32,
+            }],
+        }
+        .paint(true, position_map.text_hitbox.bounds, window);
+    }
+
     fn paint_inline_diagnostics(
         &mut self,
         layout: &mut EditorLayout,
@@ -7717,6 +7819,47 @@ impl LineWithInvisibles {
         fragment_start_x
     }
 
+    /// The x ranges covered by the given range of columns, from left to right.
+    ///
+    /// Lines that mix text directions can cover several disjoint ranges.
+    pub fn x_ranges_for_range(&self, range: Range<usize>) -> SmallVec<[Range<Pixels>; 1]> {
+        let mut x_ranges: SmallVec<[Range<Pixels>; 1]> = SmallVec::new();
+        let mut fragment_start_x = Pixels::ZERO;
+        let mut fragment_start_index = 0;
+
+        for fragment in &self.fragments {
+            let (fragment_len, fragment_width) = match fragment {
+                LineFragment::Text(shaped_line) => (shaped_line.len, shaped_line.width),
+                LineFragment::Element { len, size, .. } => (*len, size.width),
+            };
+            let fragment_end_index = fragment_start_index + fragment_len;
+            if range.start < fragment_end_index && fragment_start_index < range.end {
+                let fragment_x_ranges = match fragment {
+                    LineFragment::Text(shaped_line) => shaped_line.x_ranges_for_range(
+                        range.start.max(fragment_start_index) - fragment_start_index
+                            ..range.end.min(fragment_end_index) - fragment_start_index,
+                    ),
+                    LineFragment::Element { .. } => smallvec![Pixels::ZERO..fragment_width],
+                };
+                for x_range in fragment_x_ranges {
+                    let x_range = fragment_start_x + x_range.start..fragment_start_x + x_range.end;
+                    match x_ranges.last_mut() {
+                        Some(last) if last.end == x_range.start => last.end = x_range.end,
+                        _ => x_ranges.push(x_range),
+                    }
+                }
+            }
+            fragment_start_x += fragment_width;
+            fragment_start_index = fragment_end_index;
+        }
+
+        if x_ranges.is_empty() {
+            let x = self.x_for_index(range.start);
+            x_ranges.push(x..x);
+        }
+        x_ranges
+    }
+
     pub fn index_for_x(&self, x: Pixels) -> Option<usize> {
         let mut fragment_start_x = Pixels::ZERO;
         let mut fragment_start_index = 0;
@@ -0,0 +1,1583 @@
+use std::{fmt::Write as _, ops::Range, slice};
+
+use gpui::{
+    App, AppContext as _, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement as _, IntoElement, ParentElement as _, Render, Styled as _, WeakEntity,
+    Window, div,
+};
+use language::{AutoindentMode, BufferSnapshot, CodeLabel, Point};
+use lsp::{CompletionItemKind, InsertTextFormat, LanguageServerId};
+use multi_buffer::{MultiBufferOffset, MultiBufferRow};
+use project::{Completion, CompletionSource, lsp_store::CompletionDocumentation};
+use snippet::Snippet;
+use text::{Bias, ToOffset as _};
+use ui::{ActiveTheme as _, Color, Label, LabelCommon as _, LabelSize, StyledExt as _, v_flex};
+use util::ResultExt as _;
+use workspace::ModalView;
+
+use crate::{Editor, ExpandEmmetAbbreviation, WrapWithEmmetAbbreviation};
+
+/// Languages whose markup is expanded as HTML.
+const HTML_LANGUAGES: &[&str] = &["HTML", "Vue.js", "Svelte", "Astro"];
+/// Languages whose markup is expanded as JSX, inside the `element` syntax scope.
+const JSX_LANGUAGES: &[&str] = &["JavaScript", "TSX"];
+const CSS_LANGUAGES: &[&str] = &["CSS", "SCSS"];
+
+/// How far back to look for the start of an abbreviation, which keeps
+/// minified files responsive.
+const MAX_ABBREVIATION_LEN: usize = 1024;
+/// Stops abbreviations like `li*100000` from producing huge expansions.
+const MAX_REPEAT: usize = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Syntax {
+    Html,
+    Jsx,
+    Css,
+}
+
+struct Expansion {
+    range: Range<usize>,
+    abbreviation: String,
+    snippet: String,
+}
+
+impl Editor {
+    pub fn expand_emmet_abbreviation(
+        &mut self,
+        _: &ExpandEmmetAbbreviation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.read_only(cx) {
+            return;
+        }
+        let display_snapshot = self.display_snapshot(cx);
+        let selections = self.selections.all::<MultiBufferOffset>(&display_snapshot);
+        if selections.iter().any(|selection| !selection.is_empty()) {
+            self.wrap_with_emmet_abbreviation(&WrapWithEmmetAbbreviation::default(), window, cx);
+            return;
+        }
+
+        let snapshot = display_snapshot.buffer_snapshot();
+        let newest = self
+            .selections
+            .newest::<MultiBufferOffset>(&display_snapshot);
+        let Some((buffer, buffer_offset)) = snapshot.point_to_buffer_offset(newest.head()) else {
+            return;
+        };
+        let Some(expansion) = expansion_at(buffer, buffer_offset.0, false, cx) else {
+            return;
+        };
+        let Some(snippet) = Snippet::parse(&expansion.snippet).log_err() else {
+            return;
+        };
+
+        // Other cursors expand too when they follow the same abbreviation.
+        let abbreviation_len = expansion.range.len();
+        let ranges = selections
+            .iter()
+            .filter_map(|selection| {
+                let end = selection.head();
+                let start = MultiBufferOffset(end.0.checked_sub(abbreviation_len)?);
+                (snapshot.clip_offset(start, Bias::Left) == start
+                    && snapshot.text_for_range(start..end).collect::<String>()
+                        == expansion.abbreviation)
+                    .then_some(start..end)
+            })
+            .collect::<Vec<_>>();
+        self.insert_snippet(&ranges, snippet, window, cx).log_err();
+    }
+
+    pub fn wrap_with_emmet_abbreviation(
+        &mut self,
+        action: &WrapWithEmmetAbbreviation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.read_only(cx) {
+            return;
+        }
+        if let Some(abbreviation) = &action.abbreviation {
+            self.wrap_selections_with_abbreviation(abbreviation, window, cx);
+            return;
+        }
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let editor = cx.entity().downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                AbbreviationPrompt::new(editor, window, cx)
+            });
+        });
+    }
+
+    fn wrap_selections_with_abbreviation(
+        &mut self,
+        abbreviation: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let display_snapshot = self.display_snapshot(cx);
+        let snapshot = display_snapshot.buffer_snapshot();
+        let mut wraps = Vec::new();
+        for selection in self.selections.all::<Point>(&display_snapshot) {
+            // Without a selection, the current line is wrapped, leaving its
+            // indentation in place.
+            let range = if selection.is_empty() {
+                let row = MultiBufferRow(selection.head().row);
+                Point::new(row.0, snapshot.indent_size_for_line(row).len)
+                    ..Point::new(row.0, snapshot.line_len(row))
+            } else {
+                selection.range()
+            };
+            let range = snapshot.point_to_offset(range.start)..snapshot.point_to_offset(range.end);
+            let Some((buffer, buffer_offset)) = snapshot.point_to_buffer_offset(range.start) else {
+                continue;
+            };
+            let Some(syntax) = syntax_at(buffer, buffer_offset.0, false, cx)
+                .filter(|syntax| *syntax != Syntax::Css)
+            else {
+                continue;
+            };
+            let indent = buffer
+                .language_indent_size_at(buffer_offset.0, cx)
+                .chars()
+                .collect::<String>();
+            let content = snapshot.text_for_range(range.clone()).collect::<String>();
+            let Some(snippet) = expand(abbreviation, syntax, &indent, Some(&content), false)
+                .and_then(|source| Snippet::parse(&source).log_err())
+            else {
+                continue;
+            };
+            wraps.push((range, snippet));
+        }
+
+        if let [(range, snippet)] = wraps.as_slice() {
+            self.insert_snippet(slice::from_ref(range), snippet.clone(), window, cx)
+                .log_err();
+        } else if !wraps.is_empty() {
+            // Each selection wraps different text, so there's no snippet
+            // that can be shared between them and no tabstops to move through.
+            self.transact(window, cx, |editor, _, cx| {
+                editor.buffer.update(cx, |buffer, cx| {
+                    let edits = wraps
+                        .into_iter()
+                        .map(|(range, snippet)| (range, snippet.text))
+                        .collect::<Vec<_>>();
+                    buffer.edit(
+                        edits,
+                        Some(AutoindentMode::Block {
+                            original_indent_columns: Vec::new(),
+                        }),
+                        cx,
+                    );
+                });
+            });
+        }
+    }
+}
+
+/// Returns the Emmet expansion of the abbreviation before `position` as a
+/// snippet completion, if the position is in HTML, JSX or CSS.
+pub(crate) fn completion_at(
+    buffer: &BufferSnapshot,
+    position: text::Anchor,
+    cx: &App,
+) -> Option<Completion> {
+    let offset = position.to_offset(buffer);
+    let expansion = expansion_at(buffer, offset, true, cx)?;
+    let preview = Snippet::parse(&expansion.snippet).ok()?.text;
+    let start = buffer.anchor_before(expansion.range.start);
+    let lsp_range = lsp::Range {
+        start: language::point_to_lsp(buffer.offset_to_point_utf16(expansion.range.start)),
+        end: language::point_to_lsp(buffer.offset_to_point_utf16(expansion.range.end)),
+    };
+    let label = expansion.abbreviation;
+
+    Some(Completion {
+        replace_range: start..position,
+        new_text: expansion.snippet.clone(),
+        source: CompletionSource::Lsp {
+            insert_range: None,
+            server_id: LanguageServerId(usize::MAX),
+            resolved: true,
+            lsp_completion: Box::new(lsp::CompletionItem {
+                label: label.clone(),
+                kind: Some(CompletionItemKind::SNIPPET),
+                insert_text_format: Some(InsertTextFormat::SNIPPET),
+                text_edit: Some(lsp::CompletionTextEdit::InsertAndReplace(
+                    lsp::InsertReplaceEdit {
+                        new_text: expansion.snippet,
+                        insert: lsp_range,
+                        replace: lsp_range,
+                    },
+                )),
+                sort_text: Some(char::MAX.to_string()),
+                ..lsp::CompletionItem::default()
+            }),
+            lsp_defaults: None,
+        },
+        label: CodeLabel {
+            filter_range: 0..label.len(),
+            text: label,
+            runs: Vec::new(),
+        },
+        icon_path: None,
+        icon_color: None,
+        documentation: Some(CompletionDocumentation::SingleLineAndMultiLinePlainText {
+            single_line: "Emmet Abbreviation".into(),
+            plain_text: Some(preview.into()),
+        }),
+        insert_text_mode: None,
+        confirm: None,
+        // The whole abbreviation is matched against the label, rather than
+        // the word before the cursor.
+        match_start: Some(start),
+        snippet_deduplication_key: None,
+        group: None,
+    })
+}
+
+/// Finds the abbreviation that ends at `offset` and expands it. Completions
+/// only expand abbreviations that look intended, so that prose and code
+/// don't keep offering expansions.
+fn expansion_at(
+    buffer: &BufferSnapshot,
+    offset: usize,
+    for_completion: bool,
+    cx: &App,
+) -> Option<Expansion> {
+    let syntax = syntax_at(buffer, offset, for_completion, cx)?;
+    let row = buffer.offset_to_point(offset).row;
+    let line_start = buffer.point_to_offset(Point::new(row, 0));
+    let line_start = buffer.clip_offset(
+        line_start.max(offset.saturating_sub(MAX_ABBREVIATION_LEN)),
+        Bias::Right,
+    );
+    let line = buffer
+        .text_for_range(line_start..offset)
+        .collect::<String>();
+    let start = line_start + extract_abbreviation(&line, syntax)?;
+    if syntax == Syntax::Css && !is_css_property_position(buffer, start) {
+        return None;
+    }
+
+    let abbreviation = line[start - line_start..].to_string();
+    let indent = buffer
+        .language_indent_size_at(offset, cx)
+        .chars()
+        .collect::<String>();
+    let snippet = expand(&abbreviation, syntax, &indent, None, for_completion)?;
+    Some(Expansion {
+        range: start..offset,
+        abbreviation,
+        snippet,
+    })
+}
+
+/// Determines the kind of abbreviation that can be expanded at `offset` from
+/// the language and syntax scope just before it.
+fn syntax_at(buffer: &BufferSnapshot, offset: usize, strict: bool, cx: &App) -> Option<Syntax> {
+    if !buffer.settings_at(offset, cx).emmet {
+        return None;
+    }
+    let scope_offset = buffer.clip_offset(offset.saturating_sub(1), Bias::Left);
+    let scope = buffer.language_scope_at(scope_offset)?;
+    let override_name = scope.override_name();
+    if matches!(override_name, Some("comment" | "string")) {
+        return None;
+    }
+
+    let language_name = scope.language_name();
+    if HTML_LANGUAGES.contains(&language_name.as_ref()) {
+        (!is_inside_tag(buffer, offset)).then_some(Syntax::Html)
+    } else if JSX_LANGUAGES.contains(&language_name.as_ref()) {
+        // Outside of JSX, `a.b` is a property access and `a>b` a comparison,
+        // so completions are only offered within elements.
+        (!strict || override_name == Some("element")).then_some(Syntax::Jsx)
+    } else if CSS_LANGUAGES.contains(&language_name.as_ref()) {
+        Some(Syntax::Css)
+    } else {
+        None
+    }
+}
+
+fn is_inside_tag(buffer: &BufferSnapshot, offset: usize) -> bool {
+    buffer
+        .reversed_chars_at(offset)
+        .take(MAX_ABBREVIATION_LEN)
+        .find(|c| matches!(c, '<' | '>'))
+        == Some('<')
+}
+
+/// Properties can only start a declaration, not follow a selector or a colon.
+fn is_css_property_position(buffer: &BufferSnapshot, offset: usize) -> bool {
+    buffer
+        .reversed_chars_at(offset)
+        .take(MAX_ABBREVIATION_LEN)
+        .find(|c| !c.is_whitespace())
+        .is_some_and(|c| matches!(c, '{' | ';'))
+}
+
+/// Returns the byte offset in `line` where the abbreviation that ends the
+/// line starts.
+fn extract_abbreviation(line: &str, syntax: Syntax) -> Option<usize> {
+    let start = match syntax {
+        Syntax::Css => line
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| c.is_alphanumeric() || "-#.:!%".contains(*c))
+            .last()
+            .map_or(line.len(), |(ix, _)| ix),
+        Syntax::Html | Syntax::Jsx => extract_markup_abbreviation(line)?,
+    };
+    (start < line.len()).then_some(start)
+}
+
+fn extract_markup_abbreviation(line: &str) -> Option<usize> {
+    let mut start = line.len();
+    let mut brace_depth = 0;
+    let mut bracket_depth = 0;
+    let mut paren_depth = 0;
+    let mut stopped_at_tag = false;
+    for (ix, c) in line.char_indices().rev() {
+        // Text and attribute values can contain any character.
+        if brace_depth > 0 {
+            match c {
+                '}' => brace_depth += 1,
+                '{' => brace_depth -= 1,
+                _ => {}
+            }
+        } else if bracket_depth > 0 {
+            match c {
+                ']' => bracket_depth += 1,
+                '[' => bracket_depth -= 1,
+                _ => {}
+            }
+        } else {
+            match c {
+                '}' => brace_depth += 1,
+                ']' => bracket_depth += 1,
+                ')' => paren_depth += 1,
+                '(' if paren_depth > 0 => paren_depth -= 1,
+                '<' => {
+                    stopped_at_tag = true;
+                    break;
+                }
+                c if c.is_alphanumeric() || "-_:.#*$@!+^>".contains(c) => {}
+                _ => break,
+            }
+        }
+        start = ix;
+    }
+    if brace_depth > 0 || bracket_depth > 0 || paren_depth > 0 {
+        return None;
+    }
+
+    // In `<p>ul>li`, the abbreviation starts after the tag.
+    if stopped_at_tag {
+        start += line[start..].find('>')? + 1;
+    }
+    let abbreviation = line[start..].trim_start_matches(['>', '+', '^']);
+    Some(line.len() - abbreviation.len())
+}
+
+/// Expands an abbreviation into snippet text, wrapping `content` if given.
+fn expand(
+    abbreviation: &str,
+    syntax: Syntax,
+    indent: &str,
+    content: Option<&str>,
+    strict: bool,
+) -> Option<String> {
+    match syntax {
+        Syntax::Css => content
+            .is_none()
+            .then(|| expand_css(abbreviation))
+            .flatten(),
+        Syntax::Html if abbreviation == "!" => Some(html_document(indent)),
+        Syntax::Html | Syntax::Jsx => {
+            let elements = Parser::new(abbreviation).parse()?;
+            if strict && !is_likely_abbreviation(&elements, syntax) {
+                return None;
+            }
+            let content = content.map(dedent);
+            let lines = content
+                .as_deref()
+                .map(|content| content.lines().collect::<Vec<_>>())
+                .unwrap_or_default();
+            let mut renderer = Renderer {
+                syntax,
+                indent,
+                lines: lines.clone(),
+                next_tabstop: 1,
+            };
+            // Content repeated line by line with `*` isn't also wrapped as a whole.
+            let content = (content.is_some() && !elements.iter().any(Element::repeats_per_line))
+                .then_some(lines.as_slice());
+            let repetition = Repetition {
+                index: 1,
+                count: 1,
+                line: None,
+            };
+            Some(
+                renderer
+                    .render_list(&elements, None, repetition, content)
+                    .join("\n"),
+            )
+        }
+    }
+}
+
+fn is_likely_abbreviation(elements: &[Element], syntax: Syntax) -> bool {
+    fn names_are_known(elements: &[Element], syntax: Syntax) -> bool {
+        elements.iter().all(|element| {
+            let name_is_known = element.name.as_deref().is_none_or(|name| {
+                let tag = name.split(':').next().unwrap_or(name);
+                HTML_TAGS.contains(&tag)
+                    || tag.contains('-')
+                    || syntax == Syntax::Jsx && tag.starts_with(char::is_uppercase)
+            });
+            name_is_known && names_are_known(&element.children, syntax)
+        })
+    }
+
+    elements.iter().any(|element| !element.is_text()) && names_are_known(elements, syntax)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Repeat {
+    Times(usize),
+    /// `*` without a count, which repeats once for each wrapped line.
+    PerLine,
+}
+
+#[derive(Debug)]
+struct Element {
+    name: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    attributes: Vec<(String, Option<String>)>,
+    text: Option<String>,
+    repeat: Repeat,
+    children: Vec<Element>,
+    is_group: bool,
+}
+
+impl Default for Element {
+    fn default() -> Self {
+        Self {
+            name: None,
+            id: None,
+            classes: Vec::new(),
+            attributes: Vec::new(),
+            text: None,
+            repeat: Repeat::Times(1),
+            children: Vec::new(),
+            is_group: false,
+        }
+    }
+}
+
+impl Element {
+    fn is_text(&self) -> bool {
+        !self.is_group
+            && self.name.is_none()
+            && self.id.is_none()
+            && self.classes.is_empty()
+            && self.attributes.is_empty()
+    }
+
+    fn repeats_per_line(&self) -> bool {
+        self.repeat == Repeat::PerLine || self.children.iter().any(Self::repeats_per_line)
+    }
+
+    fn uses_line_placeholder(&self) -> bool {
+        let has_placeholder = |text: &str| text.contains("$#");
+        self.name.as_deref().is_some_and(has_placeholder)
+            || self.id.as_deref().is_some_and(has_placeholder)
+            || self.classes.iter().any(|class| has_placeholder(class))
+            || self.text.as_deref().is_some_and(has_placeholder)
+            || self
+                .attributes
+                .iter()
+                .any(|(_, value)| value.as_deref().is_some_and(has_placeholder))
+            || self.children.iter().any(Self::uses_line_placeholder)
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    ix: usize,
+}
+
+impl Parser {
+    fn new(abbreviation: &str) -> Self {
+        Self {
+            chars: abbreviation.chars().collect(),
+            ix: 0,
+        }
+    }
+
+    fn parse(mut self) -> Option<Vec<Element>> {
+        let elements = self.parse_chain()?;
+        (self.ix == self.chars.len()).then_some(elements)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.ix).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.ix += 1;
+        Some(c)
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let start = self.ix;
+        while self.peek().is_some_and(&predicate) {
+            self.ix += 1;
+        }
+        self.chars[start..self.ix].iter().collect()
+    }
+
+    /// Parses elements joined by `>`, `+` and `^`, keeping the elements that
+    /// were descended into with `>` on a stack until they're climbed out of.
+    fn parse_chain(&mut self) -> Option<Vec<Element>> {
+        fn close(roots: &mut Vec<Element>, parents: &mut Vec<Element>) -> Option<()> {
+            let parent = parents.pop()?;
+            match parents.last_mut() {
+                Some(grandparent) => grandparent.children.push(parent),
+                None => roots.push(parent),
+            }
+            Some(())
+        }
+
+        let mut roots = Vec::new();
+        let mut parents: Vec<Element> = Vec::new();
+        loop {
+            let element = self.parse_item()?;
+            match parents.last_mut() {
+                Some(parent) => parent.children.push(element),
+                None => roots.push(element),
+            }
+            match self.peek() {
+                Some('>') => {
+                    self.ix += 1;
+                    let siblings = match parents.last_mut() {
+                        Some(parent) => &mut parent.children,
+                        None => &mut roots,
+                    };
+                    let element = siblings.pop()?;
+                    parents.push(element);
+                }
+                Some('+') => self.ix += 1,
+                Some('^') => {
+                    while self.peek() == Some('^') {
+                        self.ix += 1;
+                        close(&mut roots, &mut parents);
+                    }
+                }
+                _ => break,
+            }
+        }
+        while close(&mut roots, &mut parents).is_some() {}
+        Some(roots)
+    }
+
+    fn parse_item(&mut self) -> Option<Element> {
+        let mut element = if self.peek() == Some('(') {
+            self.ix += 1;
+            let children = self.parse_chain()?;
+            if self.next()? != ')' {
+                return None;
+            }
+            Element {
+                children,
+                is_group: true,
+                ..Element::default()
+            }
+        } else {
+            self.parse_element()?
+        };
+        if self.peek() == Some('*') {
+            self.ix += 1;
+            let count = self.take_while(|c| c.is_ascii_digit());
+            element.repeat = if count.is_empty() {
+                Repeat::PerLine
+            } else {
+                Repeat::Times(count.parse::<usize>().ok()?.min(MAX_REPEAT))
+            };
+        }
+        Some(element)
+    }
+
+    fn parse_element(&mut self) -> Option<Element> {
+        let is_name_char = |c: char| c.is_alphanumeric() || "-_:$@!".contains(c);
+        let mut element = Element::default();
+        let name = self.take_while(is_name_char);
+        if !name.is_empty() {
+            element.name = Some(name);
+        }
+        let mut is_empty = element.name.is_none();
+        loop {
+            match self.peek() {
+                Some('#') => {
+                    self.ix += 1;
+                    element.id = Some(self.take_while(is_name_char));
+                }
+                Some('.') => {
+                    self.ix += 1;
+                    element.classes.push(self.take_while(is_name_char));
+                }
+                Some('[') => {
+                    self.ix += 1;
+                    self.parse_attributes(&mut element.attributes)?;
+                }
+                Some('{') => {
+                    self.ix += 1;
+                    element.text = Some(self.parse_text()?);
+                }
+                _ => break,
+            }
+            is_empty = false;
+        }
+        (!is_empty).then_some(element)
+    }
+
+    fn parse_attributes(&mut self, attributes: &mut Vec<(String, Option<String>)>) -> Option<()> {
+        loop {
+            self.take_while(char::is_whitespace);
+            if self.peek()? == ']' {
+                self.ix += 1;
+                return Some(());
+            }
+            let name = self.take_while(|c| !c.is_whitespace() && c != '=' && c != ']');
+            if name.is_empty() {
+                return None;
+            }
+            let value = if self.peek() == Some('=') {
+                self.ix += 1;
+                Some(match self.peek()? {
+                    quote @ ('"' | '\'') => {
+                        self.ix += 1;
+                        let value = self.take_while(|c| c != quote);
+                        self.next()?;
+                        value
+                    }
+                    '{' => {
+                        self.ix += 1;
+                        format!("{{{}}}", self.parse_text()?)
+                    }
+                    _ => self.take_while(|c| !c.is_whitespace() && c != ']'),
+                })
+            } else {
+                None
+            };
+            attributes.push((name, value));
+        }
+    }
+
+    /// Parses text up to the `}` that closes it, after its opening `{`.
+    fn parse_text(&mut self) -> Option<String> {
+        let mut depth = 0;
+        let mut text = String::new();
+        loop {
+            let c = self.next()?;
+            match c {
+                '{' => depth += 1,
+                '}' if depth == 0 => return Some(text),
+                '}' => depth -= 1,
+                _ => {}
+            }
+            text.push(c);
+        }
+    }
+}
+
+/// Which copy of a repeated element is being rendered, for numbering with `$`.
+#[derive(Clone, Copy)]
+struct Repetition<'a> {
+    index: usize,
+    count: usize,
+    /// The wrapped line for elements repeated with `*` alone, which replaces `$#`.
+    line: Option<&'a str>,
+}
+
+struct Renderer<'a> {
+    syntax: Syntax,
+    indent: &'a str,
+    lines: Vec<&'a str>,
+    next_tabstop: usize,
+}
+
+impl<'a> Renderer<'a> {
+    /// Renders elements to snippet text, one entry per top-level element.
+    /// Wrapped `content` goes into the innermost last element.
+    fn render_list(
+        &mut self,
+        elements: &[Element],
+        parent: Option<&str>,
+        repetition: Repetition<'a>,
+        content: Option<&[&str]>,
+    ) -> Vec<String> {
+        let mut rendered = Vec::new();
+        for (ix, element) in elements.iter().enumerate() {
+            let content = content.filter(|_| ix + 1 == elements.len());
+            match element.repeat {
+                Repeat::Times(count) => {
+                    for index in 1..=count {
+                        let repetition = Repetition {
+                            index,
+                            count,
+                            ..repetition
+                        };
+                        let content = content.filter(|_| index == count);
+                        rendered.extend(self.render_element(element, parent, repetition, content));
+                    }
+                }
+                Repeat::PerLine if self.lines.is_empty() => {
+                    rendered.extend(self.render_element(element, parent, repetition, content));
+                }
+                Repeat::PerLine => {
+                    let lines = self.lines.clone();
+                    let count = lines.len();
+                    for (ix, line) in lines.into_iter().enumerate() {
+                        let repetition = Repetition {
+                            index: ix + 1,
+                            count,
+                            line: Some(line),
+                        };
+                        let content =
+                            (!element.uses_line_placeholder()).then_some(slice::from_ref(&line));
+                        rendered.extend(self.render_element(element, parent, repetition, content));
+                    }
+                }
+            }
+        }
+        rendered
+    }
+
+    fn render_element(
+        &mut self,
+        element: &Element,
+        parent: Option<&str>,
+        repetition: Repetition<'a>,
+        content: Option<&[&str]>,
+    ) -> Vec<String> {
+        if element.is_group {
+            return self.render_list(&element.children, parent, repetition, content);
+        }
+        if element.is_text() {
+            let mut text = element
+                .text
+                .as_deref()
+                .map(|text| escape(&number(text, repetition)))
+                .unwrap_or_default();
+            for line in content.into_iter().flatten() {
+                text.push_str(&escape(line));
+            }
+            return vec![text];
+        }
+
+        let name = match &element.name {
+            Some(name) => number(name, repetition),
+            None => implicit_tag(parent).to_string(),
+        };
+        let (tag, kind) = match name.split_once(':') {
+            Some((tag @ ("input" | "button"), kind)) => (tag, Some(kind)),
+            _ => (name.as_str(), None),
+        };
+
+        let mut attributes = Vec::new();
+        if let Some(id) = &element.id {
+            attributes.push(("id", Some(number(id, repetition))));
+        }
+        if !element.classes.is_empty() {
+            let classes = element
+                .classes
+                .iter()
+                .map(|class| number(class, repetition))
+                .collect::<Vec<_>>();
+            attributes.push(("class", Some(classes.join(" "))));
+        }
+        for (name, value) in &element.attributes {
+            let value = value.as_deref().map(|value| number(value, repetition));
+            attributes.push((name.as_str(), value));
+        }
+        if let Some(kind) = kind {
+            attributes.retain(|(name, _)| *name != "type");
+            attributes.insert(0, ("type", Some(kind.to_string())));
+        }
+        for (ix, &(name, value)) in default_attributes(tag).iter().enumerate() {
+            if !attributes.iter().any(|(existing, _)| *existing == name) {
+                attributes.insert(ix, (name, value.map(str::to_string)));
+            }
+        }
+
+        let mut open_tag = format!("<{tag}");
+        for (name, value) in attributes {
+            let name = match (self.syntax, name) {
+                (Syntax::Jsx, "class") => "className",
+                (Syntax::Jsx, "for") => "htmlFor",
+                _ => name,
+            };
+            match value.filter(|value| !value.is_empty()) {
+                Some(value)
+                    if self.syntax == Syntax::Jsx
+                        && value.starts_with('{')
+                        && value.ends_with('}') =>
+                {
+                    write!(open_tag, " {name}={}", escape(&value)).ok();
+                }
+                Some(value) => {
+                    write!(open_tag, " {name}=\"{}\"", escape(&value)).ok();
+                }
+                None => {
+                    let tabstop = self.tabstop();
+                    write!(open_tag, " {name}=\"{tabstop}\"").ok();
+                }
+            }
+        }
+        if VOID_ELEMENTS.contains(&tag) {
+            open_tag.push_str(if self.syntax == Syntax::Jsx {
+                " />"
+            } else {
+                ">"
+            });
+            return vec![open_tag];
+        }
+
+        let mut parts = Vec::new();
+        if let Some(text) = &element.text {
+            parts.push(escape(&number(text, repetition)));
+        }
+        if element.children.is_empty() {
+            parts.extend(content.into_iter().flatten().map(|line| escape(line)));
+        } else {
+            parts.extend(self.render_list(&element.children, Some(tag), repetition, content));
+        }
+
+        let children_are_inline = element
+            .children
+            .iter()
+            .all(|child| is_inline(child, Some(tag)));
+        let body = match parts.as_slice() {
+            [] => self.tabstop(),
+            [part] if children_are_inline && !part.contains('\n') => part.clone(),
+            _ => {
+                let mut body = String::from("\n");
+                for line in parts.join("\n").lines() {
+                    if !line.is_empty() {
+                        body.push_str(self.indent);
+                        body.push_str(line);
+                    }
+                    body.push('\n');
+                }
+                body
+            }
+        };
+        vec![format!("{open_tag}>{body}</{tag}>")]
+    }
+
+    fn tabstop(&mut self) -> String {
+        let tabstop = format!("${}", self.next_tabstop);
+        self.next_tabstop += 1;
+        tabstop
+    }
+}
+
+fn is_inline(element: &Element, parent: Option<&str>) -> bool {
+    if element.is_group {
+        element
+            .children
+            .iter()
+            .all(|child| is_inline(child, parent))
+    } else if element.is_text() {
+        true
+    } else {
+        let name = element
+            .name
+            .as_deref()
+            .unwrap_or_else(|| implicit_tag(parent));
+        INLINE_ELEMENTS.contains(&name.split(':').next().unwrap_or(name))
+    }
+}
+
+fn implicit_tag(parent: Option<&str>) -> &'static str {
+    match parent {
+        Some("ul" | "ol") => "li",
+        Some("table" | "thead" | "tbody" | "tfoot") => "tr",
+        Some("tr") => "td",
+        Some("select" | "optgroup") => "option",
+        Some(parent) if INLINE_ELEMENTS.contains(&parent) => "span",
+        _ => "div",
+    }
+}
+
+fn default_attributes(tag: &str) -> &'static [(&'static str, Option<&'static str>)] {
+    match tag {
+        "a" => &[("href", None)],
+        "img" => &[("src", None), ("alt", None)],
+        "input" => &[("type", Some("text"))],
+        "link" => &[("rel", Some("stylesheet")), ("href", None)],
+        "form" => &[("action", None)],
+        "label" => &[("for", None)],
+        "iframe" => &[("src", None)],
+        _ => &[],
+    }
+}
+
+/// Replaces `$` with the number of the current repetition, padded to the
+/// number of `$`s. `$@-` counts down and `$@3` counts from 3. `$#` is
+/// replaced by the wrapped line.
+fn number(text: &str, repetition: Repetition) -> String {
+    let mut numbered = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            numbered.push(c);
+            continue;
+        }
+        if chars.next_if_eq(&'#').is_some() {
+            numbered.push_str(repetition.line.unwrap_or_default());
+            continue;
+        }
+        let mut width = 1;
+        while chars.next_if_eq(&'$').is_some() {
+            width += 1;
+        }
+        let mut reverse = false;
+        let mut first = 1;
+        if chars.next_if_eq(&'@').is_some() {
+            reverse = chars.next_if_eq(&'-').is_some();
+            let mut digits = String::new();
+            while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                digits.push(digit);
+            }
+            first = digits.parse().unwrap_or(1);
+        }
+        let value = if reverse {
+            first + repetition.count - repetition.index
+        } else {
+            first + repetition.index - 1
+        };
+        write!(numbered, "{value:0width$}").ok();
+    }
+    numbered
+}
+
+/// Escapes the characters that have a meaning in snippets.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '$' | '}' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Removes the indentation shared by the lines after the first, since the
+/// first line of a selection usually starts after its indentation.
+fn dedent(text: &str) -> String {
+    let text = text.trim_end();
+    let common_indent = text
+        .lines()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    text.lines()
+        .enumerate()
+        .map(|(ix, line)| {
+            if ix == 0 {
+                line.trim_start()
+            } else {
+                line.get(common_indent..).unwrap_or(line.trim_start())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn html_document(indent: &str) -> String {
+    [
+        "<!DOCTYPE html>",
+        "<html lang=\"en\">",
+        "<head>",
+        &format!("{indent}<meta charset=\"UTF-8\">"),
+        &format!(
+            "{indent}<meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">"
+        ),
+        &format!("{indent}<title>${{1:Document}}</title>"),
+        "</head>",
+        "<body>",
+        &format!("{indent}$0"),
+        "</body>",
+        "</html>",
+    ]
+    .join("\n")
+}
+
+/// Expands a CSS abbreviation, such as `m10-auto`, `w100p`, `dn` or `c#f`.
+/// A trailing `!` adds `!important`.
+fn expand_css(abbreviation: &str) -> Option<String> {
+    let (abbreviation, important) = match abbreviation.strip_suffix('!') {
+        Some(abbreviation) => (abbreviation, " !important"),
+        None => (abbreviation, ""),
+    };
+
+    let keyword = abbreviation.replace(':', "");
+    if let Some((_, declaration)) = CSS_KEYWORDS
+        .iter()
+        .find(|(keyword_abbreviation, _)| *keyword_abbreviation == keyword)
+    {
+        let (property, value) = declaration.split_once(": ")?;
+        return Some(format!("{property}: {value}{important};"));
+    }
+
+    let property_len = abbreviation
+        .find(|c: char| !c.is_ascii_lowercase())
+        .unwrap_or(abbreviation.len());
+    let (property, value) = abbreviation.split_at(property_len);
+    let (_, property) = CSS_PROPERTIES
+        .iter()
+        .find(|(property_abbreviation, _)| *property_abbreviation == property)?;
+    let value = value.strip_prefix(':').unwrap_or(value);
+
+    let value = if value.is_empty() {
+        "$1".to_string()
+    } else if let Some(color) = value.strip_prefix('#') {
+        if color.is_empty() || !color.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        match color.len() {
+            1 => format!("#{}", color.repeat(3)),
+            2 => format!("#{}", color.repeat(3)),
+            _ => format!("#{color}"),
+        }
+    } else if value.starts_with(char::is_alphabetic) {
+        value.to_string()
+    } else {
+        css_numbers(value, UNITLESS_PROPERTIES.contains(property))?
+    };
+    Some(format!("{property}: {value}{important};"))
+}
+
+/// Expands values like `10-20`, `-5`, `1.5e` or `100p`, which are separated
+/// by `-` and default to pixels.
+fn css_numbers(mut values: &str, unitless: bool) -> Option<String> {
+    let mut expanded = Vec::new();
+    while !values.is_empty() {
+        if !expanded.is_empty() {
+            values = values.strip_prefix('-')?;
+        }
+        let sign = if let Some(rest) = values.strip_prefix('-') {
+            values = rest;
+            "-"
+        } else {
+            ""
+        };
+        let number_len = values
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(values.len());
+        if number_len == 0 {
+            return None;
+        }
+        let (number, rest) = values.split_at(number_len);
+        let unit_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic() && c != '%')
+            .unwrap_or(rest.len());
+        let (unit, rest) = rest.split_at(unit_len);
+        values = rest;
+        let unit = match unit {
+            "" if unitless || number == "0" => "",
+            "" => "px",
+            "p" => "%",
+            "e" => "em",
+            "r" => "rem",
+            "x" => "ex",
+            unit => unit,
+        };
+        expanded.push(format!("{sign}{number}{unit}"));
+    }
+    Some(expanded.join(" "))
+}
+
+const HTML_TAGS: &[&str] = &[
+    "a",
+    "abbr",
+    "address",
+    "area",
+    "article",
+    "aside",
+    "audio",
+    "b",
+    "blockquote",
+    "body",
+    "br",
+    "button",
+    "canvas",
+    "caption",
+    "code",
+    "col",
+    "colgroup",
+    "dd",
+    "del",
+    "details",
+    "dfn",
+    "dialog",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "embed",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hr",
+    "html",
+    "i",
+    "iframe",
+    "img",
+    "input",
+    "ins",
+    "kbd",
+    "label",
+    "legend",
+    "li",
+    "link",
+    "main",
+    "mark",
+    "menu",
+    "meta",
+    "nav",
+    "noscript",
+    "object",
+    "ol",
+    "optgroup",
+    "option",
+    "output",
+    "p",
+    "picture",
+    "pre",
+    "progress",
+    "q",
+    "s",
+    "samp",
+    "script",
+    "section",
+    "select",
+    "slot",
+    "small",
+    "source",
+    "span",
+    "strong",
+    "style",
+    "sub",
+    "summary",
+    "sup",
+    "svg",
+    "table",
+    "tbody",
+    "td",
+    "template",
+    "textarea",
+    "tfoot",
+    "th",
+    "thead",
+    "time",
+    "title",
+    "tr",
+    "u",
+    "ul",
+    "var",
+    "video",
+    "wbr",
+];
+
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+const INLINE_ELEMENTS: &[&str] = &[
+    "a", "abbr", "b", "bdi", "bdo", "br", "button", "cite", "code", "data", "dfn", "em", "i",
+    "img", "input", "kbd", "label", "mark", "q", "s", "samp", "select", "small", "span", "strong",
+    "sub", "sup", "textarea", "time", "u", "var",
+];
+
+const CSS_PROPERTIES: &[(&str, &str)] = &[
+    ("m", "margin"),
+    ("mt", "margin-top"),
+    ("mr", "margin-right"),
+    ("mb", "margin-bottom"),
+    ("ml", "margin-left"),
+    ("p", "padding"),
+    ("pt", "padding-top"),
+    ("pr", "padding-right"),
+    ("pb", "padding-bottom"),
+    ("pl", "padding-left"),
+    ("w", "width"),
+    ("h", "height"),
+    ("maw", "max-width"),
+    ("mah", "max-height"),
+    ("miw", "min-width"),
+    ("mih", "min-height"),
+    ("t", "top"),
+    ("r", "right"),
+    ("b", "bottom"),
+    ("l", "left"),
+    ("z", "z-index"),
+    ("fz", "font-size"),
+    ("fw", "font-weight"),
+    ("ff", "font-family"),
+    ("fs", "font-style"),
+    ("lh", "line-height"),
+    ("lts", "letter-spacing"),
+    ("c", "color"),
+    ("bg", "background"),
+    ("bgc", "background-color"),
+    ("bgi", "background-image"),
+    ("bd", "border"),
+    ("bdt", "border-top"),
+    ("bdr", "border-right"),
+    ("bdb", "border-bottom"),
+    ("bdl", "border-left"),
+    ("bdc", "border-color"),
+    ("bdrs", "border-radius"),
+    ("bxsh", "box-shadow"),
+    ("bxz", "box-sizing"),
+    ("d", "display"),
+    ("pos", "position"),
+    ("op", "opacity"),
+    ("ov", "overflow"),
+    ("cur", "cursor"),
+    ("fl", "float"),
+    ("cl", "clear"),
+    ("v", "visibility"),
+    ("ta", "text-align"),
+    ("td", "text-decoration"),
+    ("tt", "text-transform"),
+    ("va", "vertical-align"),
+    ("ws", "white-space"),
+    ("jc", "justify-content"),
+    ("ai", "align-items"),
+    ("ac", "align-content"),
+    ("as", "align-self"),
+    ("fx", "flex"),
+    ("fxd", "flex-direction"),
+    ("fxw", "flex-wrap"),
+    ("fxg", "flex-grow"),
+    ("fxs", "flex-shrink"),
+    ("ord", "order"),
+    ("g", "gap"),
+    ("gtc", "grid-template-columns"),
+    ("gtr", "grid-template-rows"),
+    ("ol", "outline"),
+    ("trs", "transition"),
+    ("trf", "transform"),
+    ("lis", "list-style"),
+];
+
+const UNITLESS_PROPERTIES: &[&str] = &[
+    "z-index",
+    "opacity",
+    "font-weight",
+    "line-height",
+    "flex",
+    "flex-grow",
+    "flex-shrink",
+    "order",
+];
+
+const CSS_KEYWORDS: &[(&str, &str)] = &[
+    ("dn", "display: none"),
+    ("db", "display: block"),
+    ("di", "display: inline"),
+    ("dib", "display: inline-block"),
+    ("df", "display: flex"),
+    ("dif", "display: inline-flex"),
+    ("dg", "display: grid"),
+    ("dig", "display: inline-grid"),
+    ("dt", "display: table"),
+    ("posa", "position: absolute"),
+    ("posr", "position: relative"),
+    ("posf", "position: fixed"),
+    ("poss", "position: static"),
+    ("posst", "position: sticky"),
+    ("tac", "text-align: center"),
+    ("tal", "text-align: left"),
+    ("tar", "text-align: right"),
+    ("taj", "text-align: justify"),
+    ("tdn", "text-decoration: none"),
+    ("tdu", "text-decoration: underline"),
+    ("ttu", "text-transform: uppercase"),
+    ("ttl", "text-transform: lowercase"),
+    ("ttc", "text-transform: capitalize"),
+    ("fwb", "font-weight: bold"),
+    ("fwn", "font-weight: normal"),
+    ("fsi", "font-style: italic"),
+    ("fsn", "font-style: normal"),
+    ("ovh", "overflow: hidden"),
+    ("ova", "overflow: auto"),
+    ("ovs", "overflow: scroll"),
+    ("ovv", "overflow: visible"),
+    ("curp", "cursor: pointer"),
+    ("curd", "cursor: default"),
+    ("fll", "float: left"),
+    ("flr", "float: right"),
+    ("fln", "float: none"),
+    ("clb", "clear: both"),
+    ("vh", "visibility: hidden"),
+    ("vv", "visibility: visible"),
+    ("jcc", "justify-content: center"),
+    ("jcsb", "justify-content: space-between"),
+    ("jcsa", "justify-content: space-around"),
+    ("jcfs", "justify-content: flex-start"),
+    ("jcfe", "justify-content: flex-end"),
+    ("aic", "align-items: center"),
+    ("aifs", "align-items: flex-start"),
+    ("aife", "align-items: flex-end"),
+    ("ais", "align-items: stretch"),
+    ("fxdc", "flex-direction: column"),
+    ("fxdr", "flex-direction: row"),
+    ("fxww", "flex-wrap: wrap"),
+    ("wsnw", "white-space: nowrap"),
+    ("bxzbb", "box-sizing: border-box"),
+    ("lisn", "list-style: none"),
+    ("bdn", "border: none"),
+];
+
+/// Asks for the abbreviation to wrap the selections with.
+struct AbbreviationPrompt {
+    prompt_editor: Entity<Editor>,
+    editor: WeakEntity<Editor>,
+}
+
+impl EventEmitter<DismissEvent> for AbbreviationPrompt {}
+impl ModalView for AbbreviationPrompt {}
+
+impl Focusable for AbbreviationPrompt {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.prompt_editor.focus_handle(cx)
+    }
+}
+
+impl AbbreviationPrompt {
+    fn new(editor: WeakEntity<Editor>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let prompt_editor = cx.new(|cx| {
+            let mut prompt_editor = Editor::single_line(window, cx);
+            prompt_editor.set_placeholder_text("ul>li*", window, cx);
+            prompt_editor
+        });
+        Self {
+            prompt_editor,
+            editor,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let abbreviation = self.prompt_editor.read(cx).text(cx);
+        let abbreviation = abbreviation.trim();
+        if !abbreviation.is_empty() {
+            self.editor
+                .update(cx, |editor, cx| {
+                    editor.wrap_selections_with_abbreviation(abbreviation, window, cx)
+                })
+                .ok();
+        }
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for AbbreviationPrompt {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("EmmetAbbreviationPrompt")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_3(cx)
+            .w_96()
+            .overflow_hidden()
+            .child(
+                div()
+                    .p_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(self.prompt_editor.clone()),
+            )
+            .child(
+                div().p_2().child(
+                    Label::new("Wrap with Emmet abbreviation")
+                        .color(Color::Muted)
+                        .size(LabelSize::Small),
+                ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_html(abbreviation: &str) -> Option<String> {
+        expand(abbreviation, Syntax::Html, "  ", None, false)
+    }
+
+    #[test]
+    fn test_expand_markup() {
+        assert_eq!(
+            expand_html("ul>li*3>a{Item $}").unwrap(),
+            [
+                "<ul>",
+                "  <li><a href=\"$1\">Item 1</a></li>",
+                "  <li><a href=\"$2\">Item 2</a></li>",
+                "  <li><a href=\"$3\">Item 3</a></li>",
+                "</ul>",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            expand_html("div#main.card.wide>p+p^footer").unwrap(),
+            [
+                "<div id=\"main\" class=\"card wide\">",
+                "  <p>$1</p>",
+                "  <p>$2</p>",
+                "</div>",
+                "<footer>$3</footer>",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            expand_html("(h1+p)*2").unwrap(),
+            "<h1>$1</h1>\n<p>$2</p>\n<h1>$3</h1>\n<p>$4</p>"
+        );
+        assert_eq!(
+            expand_html("ul>.item$$@-*2").unwrap(),
+            "<ul>\n  <li class=\"item02\">$1</li>\n  <li class=\"item01\">$2</li>\n</ul>"
+        );
+        assert_eq!(
+            expand_html("input:checkbox[name=agree checked]+img").unwrap(),
+            [
+                "<input type=\"checkbox\" name=\"agree\" checked=\"$1\">",
+                "<img src=\"$2\" alt=\"$3\">",
+            ]
+            .join("\n")
+        );
+        assert_eq!(expand_html("pre{C:\\}").unwrap(), "<pre>C:\\\\</pre>");
+        assert_eq!(expand_html("ul>"), None);
+        assert_eq!(expand_html("(div"), None);
+        assert_eq!(
+            expand("label.name[for=email]", Syntax::Jsx, "  ", None, false).unwrap(),
+            "<label className=\"name\" htmlFor=\"email\">$1</label>"
+        );
+        assert_eq!(
+            expand("button[onClick={save}]+br", Syntax::Jsx, "  ", None, false).unwrap(),
+            "<button onClick={save\\}>$1</button>\n<br />"
+        );
+    }
+
+    #[test]
+    fn test_wrap_with_abbreviation() {
+        let content = "  Home\n  About";
+        assert_eq!(
+            expand("nav>ul>li*>a", Syntax::Html, "  ", Some(content), false).unwrap(),
+            [
+                "<nav>",
+                "  <ul>",
+                "    <li><a href=\"$1\">Home</a></li>",
+                "    <li><a href=\"$2\">About</a></li>",
+                "  </ul>",
+                "</nav>",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            expand("div.box", Syntax::Html, "  ", Some(content), false).unwrap(),
+            "<div class=\"box\">\n  Home\n  About\n</div>"
+        );
+        assert_eq!(
+            expand("ul>li.$#*", Syntax::Html, "  ", Some("a\nb"), false).unwrap(),
+            "<ul>\n  <li class=\"a\">$1</li>\n  <li class=\"b\">$2</li>\n</ul>"
+        );
+    }
+
+    #[test]
+    fn test_expand_css() {
+        assert_eq!(expand_css("m10-20").as_deref(), Some("margin: 10px 20px;"));
+        assert_eq!(
+            expand_css("m-5--10").as_deref(),
+            Some("margin: -5px -10px;")
+        );
+        assert_eq!(expand_css("w100p").as_deref(), Some("width: 100%;"));
+        assert_eq!(expand_css("lh1.5").as_deref(), Some("line-height: 1.5;"));
+        assert_eq!(
+            expand_css("fz1.2r!").as_deref(),
+            Some("font-size: 1.2rem !important;")
+        );
+        assert_eq!(expand_css("c#f").as_deref(), Some("color: #fff;"));
+        assert_eq!(expand_css("d:n").as_deref(), Some("display: none;"));
+        assert_eq!(
+            expand_css("jcsb").as_deref(),
+            Some("justify-content: space-between;")
+        );
+        assert_eq!(expand_css("bgc").as_deref(), Some("background-color: $1;"));
+        assert_eq!(expand_css("color").as_deref(), None);
+    }
+
+    #[test]
+    fn test_extract_abbreviation() {
+        let extract = |line: &str| {
+            extract_abbreviation(line, Syntax::Html).map(|start| line[start..].to_string())
+        };
+        assert_eq!(extract("  ul>li*3").as_deref(), Some("ul>li*3"));
+        assert_eq!(extract("<p>a{Read more}").as_deref(), Some("a{Read more}"));
+        assert_eq!(
+            extract("<div class=\"x\">span.y").as_deref(),
+            Some("span.y")
+        );
+        assert_eq!(
+            extract("return (div.card>(h2+p)").as_deref(),
+            Some("div.card>(h2+p)")
+        );
+        assert_eq!(extract("text ").as_deref(), None);
+        assert_eq!(
+            extract_abbreviation("  color: red; m10", Syntax::Css),
+            Some("  color: red; ".len())
+        );
+    }
+
+    #[test]
+    fn test_likely_abbreviations() {
+        let is_likely =
+            |abbreviation: &str| expand(abbreviation, Syntax::Html, "  ", None, true).is_some();
+        assert!(is_likely("div"));
+        assert!(is_likely("ul>li*3"));
+        assert!(is_likely(".card"));
+        assert!(is_likely("my-element"));
+        assert!(!is_likely("e.g"));
+        assert!(!is_likely("hello"));
+        assert!(!is_likely("{text}"));
+    }
+}
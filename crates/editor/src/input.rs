@@ -77,6 +77,15 @@ impl Editor {
         }
 
         self.unfold_buffers_with_selections(cx);
+        if self.has_virtual_space() {
+            // Pad the selections' lines in the same transaction as the input, so
+            // that a single undo reverts both.
+            self.transact(window, cx, |this, window, cx| {
+                this.materialize_virtual_space(window, cx);
+                this.handle_input(&text, window, cx);
+            });
+            return;
+        }
 
         let selections = self.selections.all_adjusted(&self.display_snapshot(cx));
         let mut bracket_inserted = false;
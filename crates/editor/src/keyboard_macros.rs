@@ -0,0 +1,435 @@
+use std::{borrow::Cow, ops::Range, rc::Rc, sync::Arc, time::Instant};
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use fs::Fs;
+use gpui::{Action, App, Context, Entity, Global, KeystrokeEvent, Window};
+use settings::{ActionName, KeyboardMacroStep, Settings as _, update_settings_file};
+use util::maybe;
+use workspace::{Toast, notifications::NotificationId};
+
+use crate::{
+    Editor, EditorEvent, EditorSettings, Redo, ReplayMacro, SaveMacro, SelectionEffects,
+    ShowCharacterPalette, ToggleMacroRecording, Undo,
+};
+
+/// How deeply macros can replay other macros, which stops a macro that
+/// replays itself.
+const MAX_REPLAY_DEPTH: usize = 16;
+
+enum MacroStep {
+    Action(Box<dyn Action>),
+    Insertion {
+        text: Arc<str>,
+        utf16_range_to_replace: Option<Range<isize>>,
+    },
+}
+
+struct Recording {
+    name: String,
+    steps: Vec<MacroStep>,
+}
+
+#[derive(Default)]
+struct KeyboardMacros {
+    recording: Option<Recording>,
+    recorded: HashMap<String, Rc<[MacroStep]>>,
+    last_recorded: Option<String>,
+    replay_depth: usize,
+}
+
+impl Global for KeyboardMacros {}
+
+impl KeyboardMacros {
+    fn observe(&mut self, step: MacroStep) {
+        if self.replay_depth > 0 {
+            return;
+        }
+        if let Some(recording) = self.recording.as_mut() {
+            recording.steps.push(step);
+        }
+    }
+}
+
+pub(crate) fn init(cx: &mut App) {
+    cx.set_global(KeyboardMacros::default());
+
+    cx.observe_keystrokes(|event, _, cx| observe_keystroke(event, cx))
+        .detach();
+
+    cx.observe_new(|editor: &mut Editor, _, cx: &mut Context<Editor>| {
+        if !editor.mode.is_full() {
+            return;
+        }
+        let subscription = cx.subscribe_self(|_, event: &EditorEvent, cx| {
+            if let EditorEvent::InputHandled {
+                text,
+                utf16_range_to_replace,
+            } = event
+            {
+                cx.global_mut::<KeyboardMacros>()
+                    .observe(MacroStep::Insertion {
+                        text: text.clone(),
+                        utf16_range_to_replace: utf16_range_to_replace.clone(),
+                    });
+            }
+        });
+        editor._subscriptions.push(subscription);
+    })
+    .detach();
+}
+
+/// Whether a keyboard macro is being replayed, during which the buffer has an
+/// open transaction and can't be undone.
+pub(crate) fn is_replaying_macro(cx: &App) -> bool {
+    cx.try_global::<KeyboardMacros>()
+        .is_some_and(|macros| macros.replay_depth > 0)
+}
+
+fn observe_keystroke(event: &KeystrokeEvent, cx: &mut App) {
+    let Some(action) = event.action.as_deref() else {
+        return;
+    };
+    let in_editor = event.context_stack.last().is_some_and(|context| {
+        context.contains("Editor") && context.get("mode").is_some_and(|mode| mode == "full")
+    });
+    if !in_editor || !is_replayable(action) {
+        return;
+    }
+
+    let macros = cx.global_mut::<KeyboardMacros>();
+    let step: Box<dyn Action> = match action.as_any().downcast_ref::<ReplayMacro>() {
+        // Refer to the replayed macro by name, so that the recording doesn't
+        // replay itself once it becomes the last recorded macro.
+        Some(replay) if replay.name.is_none() => Box::new(ReplayMacro {
+            name: macros.last_recorded.clone(),
+            ..replay.clone()
+        }),
+        _ => action.boxed_clone(),
+    };
+    macros.observe(MacroStep::Action(step));
+}
+
+fn is_replayable(action: &dyn Action) -> bool {
+    let action = action.as_any();
+    // Undo and redo can't run inside of the transaction that groups a replay,
+    // and the character palette would be left open.
+    !(action.is::<ToggleMacroRecording>()
+        || action.is::<SaveMacro>()
+        || action.is::<Undo>()
+        || action.is::<Redo>()
+        || action.is::<ShowCharacterPalette>())
+}
+
+fn unused_macro_name(cx: &App) -> String {
+    let recorded = &cx.global::<KeyboardMacros>().recorded;
+    let saved = &EditorSettings::get_global(cx).keyboard_macros;
+    (1..)
+        .map(|ix| format!("macro-{ix}"))
+        .find(|name| !recorded.contains_key(name) && !saved.contains_key(name))
+        .unwrap()
+}
+
+fn macro_steps(name: Option<&str>, cx: &App) -> Result<Rc<[MacroStep]>> {
+    let macros = cx.global::<KeyboardMacros>();
+    let name = match name {
+        Some(name) => name,
+        None => macros
+            .last_recorded
+            .as_deref()
+            .context("No keyboard macro has been recorded")?,
+    };
+    if let Some(steps) = macros.recorded.get(name) {
+        return Ok(steps.clone());
+    }
+
+    let saved = EditorSettings::get_global(cx)
+        .keyboard_macros
+        .get(name)
+        .with_context(|| format!("No keyboard macro is named \"{name}\""))?;
+    saved
+        .iter()
+        .map(|step| step_from_settings(step, cx))
+        .collect::<Result<Rc<[_]>>>()
+        .with_context(|| format!("Invalid keyboard macro \"{name}\""))
+}
+
+fn step_from_settings(step: &KeyboardMacroStep, cx: &App) -> Result<MacroStep> {
+    Ok(match step {
+        KeyboardMacroStep::Text {
+            text,
+            replace_utf16_range,
+        } => MacroStep::Insertion {
+            text: text.as_str().into(),
+            utf16_range_to_replace: replace_utf16_range.clone(),
+        },
+        KeyboardMacroStep::Action(name) => MacroStep::Action(cx.build_action(name.as_ref(), None)?),
+        KeyboardMacroStep::ActionWithArguments(name, arguments) => {
+            MacroStep::Action(cx.build_action(name.as_ref(), Some(arguments.clone()))?)
+        }
+    })
+}
+
+fn step_to_settings(step: &MacroStep, cx: &App) -> Result<KeyboardMacroStep> {
+    let action = match step {
+        MacroStep::Insertion {
+            text,
+            utf16_range_to_replace,
+        } => {
+            return Ok(KeyboardMacroStep::Text {
+                text: text.to_string(),
+                replace_utf16_range: utf16_range_to_replace.clone(),
+            });
+        }
+        MacroStep::Action(action) => action.as_ref(),
+    };
+
+    let name = action.name();
+    if let Some(replay) = action.as_any().downcast_ref::<ReplayMacro>() {
+        return Ok(KeyboardMacroStep::ActionWithArguments(
+            ActionName::new(name),
+            serde_json::to_value(replay)?,
+        ));
+    }
+    if cx
+        .build_action(name, None)
+        .is_ok_and(|default| default.partial_eq(action))
+    {
+        return Ok(KeyboardMacroStep::Action(ActionName::new(name)));
+    }
+
+    // Actions can't be serialized, but the arguments of one that was invoked
+    // with a key binding can be found in the keymap.
+    let keymap = cx.key_bindings();
+    let arguments = keymap
+        .borrow()
+        .bindings_for_action(action)
+        .find_map(|binding| binding.action_input())
+        .with_context(|| format!("The arguments of \"{name}\" can't be saved"))?;
+    Ok(KeyboardMacroStep::ActionWithArguments(
+        ActionName::new(name),
+        serde_json::from_str(&arguments)?,
+    ))
+}
+
+fn replay(
+    editor: &Entity<Editor>,
+    steps: &[MacroStep],
+    times: usize,
+    per_cursor: bool,
+    window: &mut Window,
+    cx: &mut App,
+) -> Result<()> {
+    let macros = cx.global_mut::<KeyboardMacros>();
+    anyhow::ensure!(
+        macros.replay_depth < MAX_REPLAY_DEPTH,
+        "Keyboard macros replay each other too deeply"
+    );
+    macros.replay_depth += 1;
+    let is_outermost = macros.replay_depth == 1;
+
+    editor.update(cx, |editor, cx| {
+        if is_outermost {
+            editor
+                .buffer
+                .update(cx, |buffer, cx| buffer.finalize_last_transaction(cx));
+        }
+        editor.start_transaction_at(Instant::now(), window, cx);
+    });
+
+    let result = if per_cursor {
+        let selections = editor.read(cx).selections.disjoint_anchors_arc();
+        let mut new_selections = Vec::with_capacity(selections.len());
+        let mut result = Ok(());
+        for selection in selections.iter() {
+            editor.update(cx, |editor, cx| {
+                editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                    s.select_anchors(vec![selection.clone()])
+                });
+            });
+            result = run_steps(editor, steps, times, window, cx);
+            if result.is_err() {
+                break;
+            }
+            new_selections.extend(
+                editor
+                    .read(cx)
+                    .selections
+                    .disjoint_anchors()
+                    .iter()
+                    .cloned(),
+            );
+        }
+        if !new_selections.is_empty() {
+            editor.update(cx, |editor, cx| {
+                editor.change_selections(Default::default(), window, cx, |s| {
+                    s.select_anchors(new_selections)
+                });
+            });
+        }
+        result
+    } else {
+        run_steps(editor, steps, times, window, cx)
+    };
+
+    editor.update(cx, |editor, cx| {
+        editor.end_transaction_at(Instant::now(), cx);
+        if is_outermost {
+            editor
+                .buffer
+                .update(cx, |buffer, cx| buffer.finalize_last_transaction(cx));
+        }
+    });
+    cx.global_mut::<KeyboardMacros>().replay_depth -= 1;
+    result
+}
+
+fn run_steps(
+    editor: &Entity<Editor>,
+    steps: &[MacroStep],
+    times: usize,
+    window: &mut Window,
+    cx: &mut App,
+) -> Result<()> {
+    let focus_handle = editor.read(cx).focus_handle.clone();
+    for _ in 0..times {
+        for step in steps {
+            match step {
+                MacroStep::Action(action) => {
+                    if let Some(nested) = action.as_any().downcast_ref::<ReplayMacro>() {
+                        let nested_steps = macro_steps(nested.name.as_deref(), cx)?;
+                        replay(
+                            editor,
+                            &nested_steps,
+                            nested.times.unwrap_or(1),
+                            nested.per_cursor,
+                            window,
+                            cx,
+                        )?;
+                    } else if is_replayable(action.as_ref()) {
+                        focus_handle.dispatch_action(action.as_ref(), window, cx);
+                    }
+                }
+                MacroStep::Insertion {
+                    text,
+                    utf16_range_to_replace,
+                } => editor.update(cx, |editor, cx| {
+                    editor.replay_insert_event(text, utf16_range_to_replace.clone(), window, cx)
+                }),
+            }
+        }
+    }
+    Ok(())
+}
+
+impl Editor {
+    pub fn toggle_macro_recording(
+        &mut self,
+        action: &ToggleMacroRecording,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let message = if let Some(recording) = cx.global_mut::<KeyboardMacros>().recording.take() {
+            if recording.steps.is_empty() {
+                "Nothing was recorded".to_string()
+            } else {
+                let macros = cx.global_mut::<KeyboardMacros>();
+                let message = format!("Recorded macro \"{}\"", recording.name);
+                macros
+                    .recorded
+                    .insert(recording.name.clone(), recording.steps.into());
+                macros.last_recorded = Some(recording.name);
+                message
+            }
+        } else {
+            let name = action.name.clone().unwrap_or_else(|| unused_macro_name(cx));
+            let message = format!("Recording macro \"{name}\"");
+            cx.global_mut::<KeyboardMacros>().recording = Some(Recording {
+                name,
+                steps: Vec::new(),
+            });
+            message
+        };
+        self.show_keyboard_macro_toast(message, cx);
+    }
+
+    pub fn replay_macro(
+        &mut self,
+        action: &ReplayMacro,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let steps = match macro_steps(action.name.as_deref(), cx) {
+            Ok(steps) => steps,
+            Err(error) => {
+                self.show_keyboard_macro_toast(format!("{error:#}"), cx);
+                return;
+            }
+        };
+
+        // Actions are dispatched to the editor while replaying, so it can't
+        // already be updating.
+        let editor = cx.entity();
+        let times = action.times.unwrap_or(1);
+        let per_cursor = action.per_cursor;
+        window.defer(cx, move |window, cx| {
+            if let Err(error) = replay(&editor, &steps, times, per_cursor, window, cx) {
+                editor.update(cx, |editor, cx| {
+                    editor.show_keyboard_macro_toast(format!("{error:#}"), cx)
+                });
+            }
+        });
+    }
+
+    pub fn save_macro(&mut self, action: &SaveMacro, _: &mut Window, cx: &mut Context<Self>) {
+        let result = maybe!({
+            let macros = cx.global::<KeyboardMacros>();
+            let name = action
+                .name
+                .clone()
+                .or_else(|| macros.last_recorded.clone())
+                .context("No keyboard macro has been recorded")?;
+            let steps = macros
+                .recorded
+                .get(&name)
+                .with_context(|| format!("No keyboard macro named \"{name}\" was recorded"))?
+                .iter()
+                .map(|step| step_to_settings(step, cx))
+                .collect::<Result<Vec<_>>>()?;
+            anyhow::Ok((name, steps))
+        });
+
+        let message = match result {
+            Ok((name, steps)) => {
+                let message = format!("Saved macro \"{name}\" to settings");
+                update_settings_file(<dyn Fs>::global(cx), cx, move |settings, _| {
+                    settings
+                        .editor
+                        .keyboard_macros
+                        .get_or_insert_default()
+                        .insert(name, steps);
+                });
+                message
+            }
+            Err(error) => format!("Failed to save keyboard macro: {error:#}"),
+        };
+        self.show_keyboard_macro_toast(message, cx);
+    }
+
+    fn show_keyboard_macro_toast(
+        &self,
+        message: impl Into<Cow<'static, str>>,
+        cx: &mut Context<Self>,
+    ) {
+        struct KeyboardMacroToast;
+
+        if let Some(workspace) = self.workspace() {
+            workspace.update(cx, |workspace, cx| {
+                workspace.show_toast(
+                    Toast::new(NotificationId::unique::<KeyboardMacroToast>(), message).autohide(),
+                    cx,
+                )
+            });
+        }
+    }
+}
@@ -34,6 +34,18 @@ pub struct TextLayoutDetails {
     pub vertical_scroll_margin: ScrollOffset,
 }
 
+impl TextLayoutDetails {
+    /// The width of an `m` in the editor's font, used as the width of columns
+    /// past the end of a line.
+    pub(crate) fn em_layout_width(&self) -> Pixels {
+        let font_id = self
+            .text_system
+            .resolve_font(&self.editor_style.text.font());
+        let font_size = self.editor_style.text.font_size.to_pixels(self.rem_size);
+        self.text_system.em_layout_width(font_id, font_size)
+    }
+}
+
 /// Returns a column to the left of the current point, wrapping
 /// to the previous line if that point is at the start of line.
 pub fn left(map: &DisplaySnapshot, mut point: DisplayPoint) -> DisplayPoint {
@@ -80,6 +92,74 @@ pub fn saturating_right(map: &DisplaySnapshot, mut point: DisplayPoint) -> Displ
     map.clip_point(point, Bias::Right)
 }
 
+/// Returns the position one character to the visual left of the current point.
+///
+/// In lines that mix text directions, this moves through right-to-left text in the
+/// order it's displayed rather than the order it's stored, wrapping to the previous
+/// line from the line's left edge. Other lines behave like [`left`].
+pub fn visual_left(
+    map: &DisplaySnapshot,
+    point: DisplayPoint,
+    text_layout_details: &TextLayoutDetails,
+) -> DisplayPoint {
+    visual_neighbor(map, point, Direction::Prev, text_layout_details)
+        .unwrap_or_else(|| left(map, point))
+}
+
+/// Returns the position one character to the visual right of the current point.
+///
+/// In lines that mix text directions, this moves through right-to-left text in the
+/// order it's displayed rather than the order it's stored, wrapping to the next
+/// line from the line's right edge. Other lines behave like [`right`].
+pub fn visual_right(
+    map: &DisplaySnapshot,
+    point: DisplayPoint,
+    text_layout_details: &TextLayoutDetails,
+) -> DisplayPoint {
+    visual_neighbor(map, point, Direction::Next, text_layout_details)
+        .unwrap_or_else(|| right(map, point))
+}
+
+/// Returns the closest caret to the left or right of `point` as it's displayed, or
+/// `None` when the line is laid out entirely left to right.
+fn visual_neighbor(
+    map: &DisplaySnapshot,
+    point: DisplayPoint,
+    direction: Direction,
+    text_layout_details: &TextLayoutDetails,
+) -> Option<DisplayPoint> {
+    let row = point.row();
+    // Check the text first, as most lines don't need to be laid out.
+    if gpui::bidi_runs(&map.line(row)).is_empty() {
+        return None;
+    }
+    let layout = map.layout_row(row, text_layout_details);
+
+    let current_x = layout.x_for_index(point.column() as usize);
+    let carets = layout.caret_positions().into_iter();
+    let neighbor = match direction {
+        Direction::Prev => carets
+            .filter(|(_, x)| *x < current_x)
+            .max_by_key(|(_, x)| *x),
+        Direction::Next => carets
+            .filter(|(_, x)| *x > current_x)
+            .min_by_key(|(_, x)| *x),
+    };
+    let (point, bias) = match (neighbor, direction) {
+        (Some((index, _)), Direction::Prev) => (DisplayPoint::new(row, index as u32), Bias::Left),
+        (Some((index, _)), Direction::Next) => (DisplayPoint::new(row, index as u32), Bias::Right),
+        (None, Direction::Prev) if row.0 > 0 => {
+            let row = DisplayRow(row.0 - 1);
+            (DisplayPoint::new(row, map.line_len(row)), Bias::Left)
+        }
+        (None, Direction::Next) if row < map.max_point().row() => {
+            (DisplayPoint::new(DisplayRow(row.0 + 1), 0), Bias::Right)
+        }
+        (None, _) => (point, Bias::Left),
+    };
+    Some(map.clip_point(point, bias))
+}
+
 /// Returns a display point for the preceding displayed line (which might be a soft-wrapped line).
 pub fn up(
     map: &DisplaySnapshot,
@@ -2,10 +2,14 @@ use super::*;
 
 impl Editor {
     pub fn move_left(&mut self, _: &MoveLeft, window: &mut Window, cx: &mut Context<Self>) {
+        if self.move_in_virtual_space(VirtualMotion::Left, window, cx) {
+            return;
+        }
+        let text_layout_details = &self.text_layout_details(window, cx);
         self.change_selections(Default::default(), window, cx, |s| {
             s.move_with(&mut |map, selection| {
                 let cursor = if selection.is_empty() {
-                    movement::left(map, selection.start)
+                    movement::visual_left(map, selection.start, text_layout_details)
                 } else {
                     selection.start
                 };
@@ -15,16 +19,26 @@ impl Editor {
     }
 
     pub fn select_left(&mut self, _: &SelectLeft, window: &mut Window, cx: &mut Context<Self>) {
+        let text_layout_details = &self.text_layout_details(window, cx);
         self.change_selections(Default::default(), window, cx, |s| {
-            s.move_heads_with(&mut |map, head, _| (movement::left(map, head), SelectionGoal::None));
+            s.move_heads_with(&mut |map, head, _| {
+                (
+                    movement::visual_left(map, head, text_layout_details),
+                    SelectionGoal::None,
+                )
+            });
         })
     }
 
     pub fn move_right(&mut self, _: &MoveRight, window: &mut Window, cx: &mut Context<Self>) {
+        if self.move_in_virtual_space(VirtualMotion::Right, window, cx) {
+            return;
+        }
+        let text_layout_details = &self.text_layout_details(window, cx);
         self.change_selections(Default::default(), window, cx, |s| {
             s.move_with(&mut |map, selection| {
                 let cursor = if selection.is_empty() {
-                    movement::right(map, selection.end)
+                    movement::visual_right(map, selection.end, text_layout_details)
                 } else {
                     selection.end
                 };
@@ -34,9 +48,13 @@ impl Editor {
     }
 
     pub fn select_right(&mut self, _: &SelectRight, window: &mut Window, cx: &mut Context<Self>) {
+        let text_layout_details = &self.text_layout_details(window, cx);
         self.change_selections(Default::default(), window, cx, |s| {
             s.move_heads_with(&mut |map, head, _| {
-                (movement::right(map, head), SelectionGoal::None)
+                (
+                    movement::visual_right(map, head, text_layout_details),
+                    SelectionGoal::None,
+                )
             });
         });
     }
@@ -55,21 +73,23 @@ impl Editor {
         let selection_count = self.selections.count();
         let first_selection = self.selections.first_anchor();
 
-        self.change_selections(Default::default(), window, cx, |s| {
-            s.move_with(&mut |map, selection| {
-                if !selection.is_empty() {
-                    selection.goal = SelectionGoal::None;
-                }
-                let (cursor, goal) = movement::up(
-                    map,
-                    selection.start,
-                    selection.goal,
-                    false,
-                    text_layout_details,
-                );
-                selection.collapse_to(cursor, goal);
+        if !self.move_in_virtual_space(VirtualMotion::Up, window, cx) {
+            self.change_selections(Default::default(), window, cx, |s| {
+                s.move_with(&mut |map, selection| {
+                    if !selection.is_empty() {
+                        selection.goal = SelectionGoal::None;
+                    }
+                    let (cursor, goal) = movement::up(
+                        map,
+                        selection.start,
+                        selection.goal,
+                        false,
+                        text_layout_details,
+                    );
+                    selection.collapse_to(cursor, goal);
+                });
             });
-        });
+        }
 
         if selection_count == 1 && first_selection.range() == self.selections.first_anchor().range()
         {
@@ -272,21 +292,23 @@ impl Editor {
         let selection_count = self.selections.count();
         let first_selection = self.selections.first_anchor();
 
-        self.change_selections(Default::default(), window, cx, |s| {
-            s.move_with(&mut |map, selection| {
-                if !selection.is_empty() {
-                    selection.goal = SelectionGoal::None;
-                }
-                let (cursor, goal) = movement::down(
-                    map,
-                    selection.end,
-                    selection.goal,
-                    false,
-                    text_layout_details,
-                );
-                selection.collapse_to(cursor, goal);
+        if !self.move_in_virtual_space(VirtualMotion::Down, window, cx) {
+            self.change_selections(Default::default(), window, cx, |s| {
+                s.move_with(&mut |map, selection| {
+                    if !selection.is_empty() {
+                        selection.goal = SelectionGoal::None;
+                    }
+                    let (cursor, goal) = movement::down(
+                        map,
+                        selection.end,
+                        selection.goal,
+                        false,
+                        text_layout_details,
+                    );
+                    selection.collapse_to(cursor, goal);
+                });
             });
-        });
+        }
 
         if selection_count == 1 && first_selection.range() == self.selections.first_anchor().range()
         {
@@ -555,7 +577,10 @@ impl Editor {
                     SelectionGoal::None,
                 )
             });
-        })
+        });
+        // Cursors already at the end of their line stay put, so they leave
+        // virtual space here rather than by moving.
+        self.set_virtual_space(HashMap::default(), cx);
     }
 
     pub fn select_to_end_of_line(
@@ -223,6 +223,35 @@ impl Domain for EditorDb {
                 PRIMARY KEY(workspace_id, path, start)
             );
         ),
+        // Working sets are user-curated multibuffers, stored by name so that
+        // adding to a set doesn't require its tab to be open. Like file folds,
+        // each excerpt is anchored by path and fingerprinted content.
+        sql! (
+            CREATE TABLE working_set_excerpts (
+                workspace_id INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                path BLOB NOT NULL,
+                start INTEGER NOT NULL,
+                end INTEGER NOT NULL,
+                start_fingerprint TEXT NOT NULL,
+                end_fingerprint TEXT NOT NULL,
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                    ON DELETE CASCADE
+                    ON UPDATE CASCADE,
+                PRIMARY KEY(workspace_id, name, position)
+            ) STRICT;
+
+            CREATE TABLE working_sets (
+                item_id INTEGER NOT NULL,
+                workspace_id INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                PRIMARY KEY(item_id, workspace_id),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                    ON DELETE CASCADE
+                    ON UPDATE CASCADE
+            ) STRICT;
+        ),
     ];
 }
 
@@ -319,6 +348,36 @@ impl EditorDb {
         }
     }
 
+    query! {
+        pub fn get_working_set(
+            workspace_id: WorkspaceId,
+            name: &str
+        ) -> Result<Vec<(PathBuf, usize, usize, String, String)>> {
+            SELECT path, start, end, start_fingerprint, end_fingerprint
+            FROM working_set_excerpts
+            WHERE workspace_id = ?1 AND name = ?2
+            ORDER BY position
+        }
+    }
+
+    query! {
+        pub fn get_working_set_name(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<String>> {
+            SELECT name FROM working_sets
+            WHERE item_id = ? AND workspace_id = ?
+        }
+    }
+
+    query! {
+        pub async fn save_working_set_name(item_id: ItemId, workspace_id: WorkspaceId, name: String) -> Result<()> {
+            INSERT INTO working_sets
+                (item_id, workspace_id, name)
+            VALUES
+                (?1, ?2, ?3)
+            ON CONFLICT DO UPDATE SET
+                name = ?3
+        }
+    }
+
     pub async fn save_editor_selections(
         &self,
         editor_id: ItemId,
@@ -396,6 +455,41 @@ VALUES {placeholders};
         .await
     }
 
+    pub async fn save_working_set(
+        &self,
+        workspace_id: WorkspaceId,
+        name: String,
+        excerpts: Vec<(Arc<Path>, usize, usize, String, String)>,
+    ) -> Result<()> {
+        log::debug!("Saving working set {name:?} in workspace {workspace_id:?}");
+        self.write(move |conn| {
+            conn.exec_bound(sql!(
+                DELETE FROM working_set_excerpts WHERE workspace_id = ?1 AND name = ?2;
+            ))?((workspace_id, name.as_str()))?;
+
+            for (position, (path, start, end, start_fp, end_fp)) in
+                excerpts.into_iter().enumerate()
+            {
+                conn.exec_bound(sql!(
+                    INSERT INTO working_set_excerpts
+                        (workspace_id, name, position, path, start, end, start_fingerprint, end_fingerprint)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8);
+                ))?((
+                    workspace_id,
+                    name.as_str(),
+                    position,
+                    path.as_ref(),
+                    start,
+                    end,
+                    start_fp,
+                    end_fp,
+                ))?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
     pub async fn delete_file_folds(
         &self,
         workspace_id: WorkspaceId,
@@ -614,4 +708,88 @@ mod tests {
         assert_eq!(retrieved_a[0].0, 10); // file_a's fold
         assert_eq!(retrieved_b[0].0, 30); // file_b's fold
     }
+
+    #[gpui::test]
+    async fn test_save_and_get_working_set(cx: &mut gpui::TestAppContext) {
+        let db = cx.update(|cx| workspace::WorkspaceDb::global(cx));
+        let workspace_id = db.next_id().await.unwrap();
+        let editor_db = cx.update(|cx| EditorDb::global(cx));
+
+        let path_a: Arc<Path> = Arc::from(Path::new("/tmp/working_set_a.rs"));
+        let path_b: Arc<Path> = Arc::from(Path::new("/tmp/working_set_b.rs"));
+        let excerpts = vec![
+            (
+                path_b.clone(),
+                10,
+                20,
+                "fn b".to_string(),
+                "end b".to_string(),
+            ),
+            (
+                path_a.clone(),
+                30,
+                40,
+                "fn a".to_string(),
+                "end a".to_string(),
+            ),
+        ];
+        editor_db
+            .save_working_set(workspace_id, "Review".to_string(), excerpts)
+            .await
+            .unwrap();
+
+        // Excerpts come back in the order they were saved in.
+        let retrieved = editor_db.get_working_set(workspace_id, "Review").unwrap();
+        assert_eq!(
+            retrieved,
+            vec![
+                (
+                    PathBuf::from("/tmp/working_set_b.rs"),
+                    10,
+                    20,
+                    "fn b".to_string(),
+                    "end b".to_string()
+                ),
+                (
+                    PathBuf::from("/tmp/working_set_a.rs"),
+                    30,
+                    40,
+                    "fn a".to_string(),
+                    "end a".to_string()
+                ),
+            ]
+        );
+        assert!(
+            editor_db
+                .get_working_set(workspace_id, "Other")
+                .unwrap()
+                .is_empty()
+        );
+
+        // Saving a set replaces its excerpts.
+        editor_db
+            .save_working_set(
+                workspace_id,
+                "Review".to_string(),
+                vec![(path_a, 1, 2, "a".to_string(), "a".to_string())],
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            editor_db
+                .get_working_set(workspace_id, "Review")
+                .unwrap()
+                .len(),
+            1
+        );
+
+        editor_db
+            .save_working_set_name(1234, workspace_id, "Review".to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            editor_db.get_working_set_name(1234, workspace_id).unwrap(),
+            Some("Review".to_string())
+        );
+    }
 }
@@ -1599,6 +1599,7 @@ impl Editor {
         self.select_syntax_node_history.try_clear();
         self.invalidate_autoclose_regions(&selection_anchors, buffer);
         self.snippet_stack.invalidate(&selection_anchors, buffer);
+        self.retain_column_selection_rows(&selection_anchors, buffer);
         self.take_rename(false, window, cx);
 
         let newest_selection = self.selections.newest_anchor();
@@ -1798,17 +1799,7 @@ impl Editor {
         // `PositionMap::point_for_position`). `x_for_display_point` clamps at
         // the line's width, so convert that overshoot back to pixels with the
         // same unit to keep the rectangle tracking the mouse past short lines.
-        let font_id = text_layout_details
-            .text_system
-            .resolve_font(&text_layout_details.editor_style.text.font());
-        let font_size = text_layout_details
-            .editor_style
-            .text
-            .font_size
-            .to_pixels(text_layout_details.rem_size);
-        let em_layout_width = text_layout_details
-            .text_system
-            .em_layout_width(font_id, font_size);
+        let em_layout_width = text_layout_details.em_layout_width();
         let x_for_unclipped_point = |point: DisplayPoint| {
             let line_len = display_map.line_len(point.row());
             if point.column() > line_len {
@@ -1828,6 +1819,17 @@ impl Editor {
         let end_x = tail_x.max(head_x);
         let reversed = head_x < tail_x;
 
+        // With virtual space, rows shorter than the rectangle keep the columns
+        // past their end rather than collapsing to it.
+        let virtual_space = EditorSettings::get_global(cx).virtual_space;
+        let columns_past_line_end = |x: Pixels, line_width: Pixels| {
+            if virtual_space && x > line_width {
+                ((x - line_width) / em_layout_width).round() as u32
+            } else {
+                0
+            }
+        };
+
         let mut last_buffer_row = None;
         let selection_ranges = (start_row.0..=end_row.0)
             .map(DisplayRow)
@@ -1845,6 +1847,7 @@ impl Editor {
                 let layout = display_map.layout_row(row, &text_layout_details);
                 if matches!(columnar_state, ColumnarSelectionState::FromSelection { .. })
                     && start_x > layout.width
+                    && !virtual_space
                 {
                     return None;
                 }
@@ -1858,25 +1861,36 @@ impl Editor {
                 let end = display_map
                     .clip_point(DisplayPoint::new(row, end_column), Bias::Right)
                     .to_point(display_map);
-                if reversed {
-                    Some(end..start)
+
+                // Virtual space only exists past the end of a buffer line, not
+                // past a soft wrap.
+                let space = if end.column
+                    == display_map
+                        .buffer_snapshot()
+                        .line_len(MultiBufferRow(end.row))
+                {
+                    VirtualSpace {
+                        start: columns_past_line_end(start_x, layout.width),
+                        end: columns_past_line_end(end_x, layout.width),
+                    }
                 } else {
-                    Some(start..end)
-                }
+                    VirtualSpace::default()
+                };
+                Some((start..end, space))
             })
             .collect::<Vec<_>>();
         if selection_ranges.is_empty() {
             return;
         }
 
-        let ranges = match columnar_state {
+        let rows = match columnar_state {
             ColumnarSelectionState::FromMouse { .. } => {
-                let mut non_empty_ranges = selection_ranges
+                let mut non_empty_rows = selection_ranges
                     .iter()
-                    .filter(|selection_range| selection_range.start != selection_range.end)
+                    .filter(|(range, space)| range.start != range.end || space.width() > 0)
                     .peekable();
-                if non_empty_ranges.peek().is_some() {
-                    non_empty_ranges.cloned().collect()
+                if non_empty_rows.peek().is_some() {
+                    non_empty_rows.cloned().collect()
                 } else {
                     selection_ranges
                 }
@@ -1885,8 +1899,15 @@ impl Editor {
         };
 
         self.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
-            s.select_ranges(ranges);
+            s.select_ranges(rows.iter().map(|(range, _)| {
+                if reversed {
+                    range.end..range.start
+                } else {
+                    range.clone()
+                }
+            }));
         });
+        self.set_column_selection_rows(rows, cx);
         cx.notify();
     }
 
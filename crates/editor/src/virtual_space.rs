@@ -0,0 +1,402 @@
+use super::*;
+
+/// How many columns a selection's endpoints sit past the end of its line.
+///
+/// Selections themselves are clipped to real characters, so a column selection
+/// crossing a short line collapses to that line's end. When virtual space is
+/// enabled, the columns beyond the clipped point are kept here instead, and the
+/// line is padded with spaces once text is typed or pasted there. Cursors also
+/// get here with the arrow keys, by moving right from the end of a line or up
+/// and down from a longer one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct VirtualSpace {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl VirtualSpace {
+    /// A cursor `columns` past the end of its line.
+    pub fn at(columns: u32) -> Self {
+        Self {
+            start: columns,
+            end: columns,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == 0 && self.end == 0
+    }
+
+    pub fn head(&self, reversed: bool) -> u32 {
+        if reversed { self.start } else { self.end }
+    }
+
+    /// The number of virtual columns covered by the selection.
+    pub fn width(&self) -> u32 {
+        self.end - self.start
+    }
+}
+
+/// A direction the arrow keys move a cursor in virtual space.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum VirtualMotion {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// The rows of the most recent column selection, or the cursors that were
+/// moved into virtual space, keyed by selection id.
+///
+/// A row is forgotten as soon as its selection moves, and all of them are
+/// forgotten once the buffer is edited.
+#[derive(Default)]
+pub(crate) struct ColumnSelection {
+    edit_count: usize,
+    rows: HashMap<usize, ColumnSelectionRow>,
+}
+
+struct ColumnSelectionRow {
+    range: Range<MultiBufferOffset>,
+    space: VirtualSpace,
+}
+
+impl Editor {
+    /// Records the rows of a column selection that was just selected, matching
+    /// them to the current selections by their point ranges.
+    pub(crate) fn set_column_selection_rows(
+        &mut self,
+        rows: Vec<(Range<Point>, VirtualSpace)>,
+        cx: &mut Context<Self>,
+    ) {
+        self.column_selection.rows.clear();
+        if !EditorSettings::get_global(cx).virtual_space {
+            return;
+        }
+
+        let display_map = self.display_snapshot(cx);
+        let buffer = display_map.buffer_snapshot();
+        self.column_selection.edit_count = buffer.edit_count();
+        for selection in self.selections.all::<Point>(&display_map) {
+            if let Some((_, space)) = rows
+                .iter()
+                .find(|(range, _)| range.start == selection.start && range.end == selection.end)
+            {
+                self.column_selection.rows.insert(
+                    selection.id,
+                    ColumnSelectionRow {
+                        range: selection.start.to_offset(buffer)..selection.end.to_offset(buffer),
+                        space: *space,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Forgets the rows of the column selection whose selection has moved.
+    pub(crate) fn retain_column_selection_rows(
+        &mut self,
+        selections: &[Selection<Anchor>],
+        buffer: &MultiBufferSnapshot,
+    ) {
+        let column_selection = &mut self.column_selection;
+        if column_selection.rows.is_empty() {
+            return;
+        }
+        if column_selection.edit_count != buffer.edit_count() {
+            column_selection.rows.clear();
+            return;
+        }
+        column_selection.rows.retain(|id, row| {
+            selections.iter().any(|selection| {
+                selection.id == *id
+                    && selection.start.to_offset(buffer) == row.range.start
+                    && selection.end.to_offset(buffer) == row.range.end
+            })
+        });
+    }
+
+    /// Replaces the recorded rows with the selections in `spaces` that sit in
+    /// virtual space, after they were moved or edited with the keyboard.
+    pub(crate) fn set_virtual_space(
+        &mut self,
+        spaces: HashMap<usize, VirtualSpace>,
+        cx: &mut Context<Self>,
+    ) {
+        self.column_selection.rows.clear();
+        if spaces.values().all(VirtualSpace::is_empty) {
+            return;
+        }
+
+        let display_map = self.display_snapshot(cx);
+        let buffer = display_map.buffer_snapshot();
+        self.column_selection.edit_count = buffer.edit_count();
+        for selection in self.selections.all::<MultiBufferOffset>(&display_map) {
+            if let Some(space) = spaces.get(&selection.id)
+                && !space.is_empty()
+            {
+                self.column_selection.rows.insert(
+                    selection.id,
+                    ColumnSelectionRow {
+                        range: selection.start..selection.end,
+                        space: *space,
+                    },
+                );
+            }
+        }
+        cx.notify();
+    }
+
+    /// Moves the selections one step in `direction`, letting cursors move past
+    /// the end of their line. Returns `false` without moving them when virtual
+    /// space is disabled.
+    ///
+    /// Moving right from the end of a line adds a virtual column, and moving
+    /// left removes one. Moving up or down keeps the cursor's column, past the
+    /// end of shorter lines. Selections collapse to the side they're moved to.
+    pub(crate) fn move_in_virtual_space(
+        &mut self,
+        direction: VirtualMotion,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if !EditorSettings::get_global(cx).virtual_space {
+            return false;
+        }
+
+        let text_layout_details = self.text_layout_details(window, cx);
+        let em_layout_width = text_layout_details.em_layout_width();
+        let old_spaces = self
+            .column_selection
+            .rows
+            .iter()
+            .map(|(id, row)| (*id, row.space))
+            .collect::<HashMap<_, _>>();
+        let mut new_spaces = HashMap::default();
+        self.change_selections(Default::default(), window, cx, |s| {
+            s.move_with(&mut |map, selection| {
+                let space = old_spaces.get(&selection.id).copied().unwrap_or_default();
+                let is_line_end = |point: DisplayPoint| {
+                    let point = point.to_point(map);
+                    point.column == map.buffer_snapshot().line_len(MultiBufferRow(point.row))
+                };
+                let collapse = !selection.is_empty() || space.width() > 0;
+                match direction {
+                    VirtualMotion::Left if collapse => {
+                        selection.collapse_to(selection.start, SelectionGoal::None);
+                        new_spaces.insert(selection.id, VirtualSpace::at(space.start));
+                    }
+                    VirtualMotion::Left if space.end > 0 => {
+                        selection.goal = SelectionGoal::None;
+                        new_spaces.insert(selection.id, VirtualSpace::at(space.end - 1));
+                    }
+                    VirtualMotion::Left => {
+                        let cursor =
+                            movement::visual_left(map, selection.start, &text_layout_details);
+                        selection.collapse_to(cursor, SelectionGoal::None);
+                    }
+                    VirtualMotion::Right if collapse => {
+                        selection.collapse_to(selection.end, SelectionGoal::None);
+                        new_spaces.insert(selection.id, VirtualSpace::at(space.end));
+                    }
+                    VirtualMotion::Right if is_line_end(selection.end) => {
+                        selection.goal = SelectionGoal::None;
+                        new_spaces.insert(selection.id, VirtualSpace::at(space.end + 1));
+                    }
+                    VirtualMotion::Right => {
+                        let cursor =
+                            movement::visual_right(map, selection.end, &text_layout_details);
+                        selection.collapse_to(cursor, SelectionGoal::None);
+                    }
+                    VirtualMotion::Up | VirtualMotion::Down => {
+                        let up = direction == VirtualMotion::Up;
+                        let (head, columns) = if up {
+                            (selection.start, space.start)
+                        } else {
+                            (selection.end, space.end)
+                        };
+                        let mut goal = selection.goal;
+                        if !selection.is_empty() {
+                            goal = SelectionGoal::None;
+                        }
+                        if columns > 0 && !matches!(goal, SelectionGoal::HorizontalPosition(_)) {
+                            let x = map.x_for_display_point(head, &text_layout_details)
+                                + em_layout_width * columns as f32;
+                            goal = SelectionGoal::HorizontalPosition(x.into());
+                        }
+                        let (cursor, goal) = if up {
+                            movement::up(map, head, goal, false, &text_layout_details)
+                        } else {
+                            movement::down(map, head, goal, false, &text_layout_details)
+                        };
+                        selection.collapse_to(cursor, goal);
+
+                        if let SelectionGoal::HorizontalPosition(goal_x) = goal
+                            && is_line_end(cursor)
+                        {
+                            let line_end_x = map.x_for_display_point(cursor, &text_layout_details);
+                            let goal_x = Pixels::from(goal_x);
+                            if goal_x > line_end_x {
+                                let columns = ((goal_x - line_end_x) / em_layout_width).round();
+                                new_spaces.insert(selection.id, VirtualSpace::at(columns as u32));
+                            }
+                        }
+                    }
+                }
+            });
+        });
+        self.set_virtual_space(new_spaces, cx);
+        true
+    }
+
+    /// Takes the selections that cover no text but sit in virtual space out of
+    /// the recorded rows, and returns the virtual space each keeps after being
+    /// deleted from: selections collapse to their start, and when `backward` is
+    /// set, cursors move one column left. Deleting leaves their lines alone.
+    pub(crate) fn take_virtual_deletions(
+        &mut self,
+        backward: bool,
+    ) -> HashMap<usize, VirtualSpace> {
+        let mut spaces = HashMap::default();
+        self.column_selection.rows.retain(|id, row| {
+            if row.range.start != row.range.end || row.space.is_empty() {
+                return true;
+            }
+            let columns = if row.space.width() > 0 {
+                row.space.start
+            } else if backward {
+                row.space.end - 1
+            } else {
+                return true;
+            };
+            spaces.insert(*id, VirtualSpace::at(columns));
+            false
+        });
+        spaces
+    }
+
+    pub(crate) fn virtual_space_for_selection(&self, selection_id: usize) -> VirtualSpace {
+        self.column_selection
+            .rows
+            .get(&selection_id)
+            .map(|row| row.space)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn is_column_selection_row(&self, selection_id: usize) -> bool {
+        self.column_selection.rows.contains_key(&selection_id)
+    }
+
+    pub(crate) fn has_virtual_space(&self) -> bool {
+        self.column_selection
+            .rows
+            .values()
+            .any(|row| !row.space.is_empty())
+    }
+
+    /// Pads the lines of selections that extend into virtual space with spaces,
+    /// and selects the padding, so that the next edit lands at the right column.
+    ///
+    /// This doesn't start a transaction of its own: callers run it in the
+    /// transaction of that edit, so that a single undo reverts both.
+    pub(crate) fn materialize_virtual_space(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.has_virtual_space() {
+            return;
+        }
+
+        let display_map = self.display_snapshot(cx);
+        let buffer = display_map.buffer_snapshot();
+        let mut selections = self.selections.all::<Point>(&display_map);
+        let mut edits = Vec::new();
+        for selection in &mut selections {
+            let space = self.virtual_space_for_selection(selection.id);
+            if space.is_empty() {
+                continue;
+            }
+            let row = selection.end.row;
+            let line_len = buffer.line_len(MultiBufferRow(row));
+            edits.push((
+                Point::new(row, line_len)..Point::new(row, line_len),
+                " ".repeat(space.end as usize),
+            ));
+            if space.start > 0 {
+                selection.start = Point::new(row, line_len + space.start);
+            }
+            selection.end = Point::new(row, line_len + space.end);
+            selection.goal = SelectionGoal::None;
+        }
+
+        // The padding now holds the virtual columns.
+        self.column_selection.rows.clear();
+        self.buffer
+            .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+        self.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select(selections);
+        });
+    }
+
+    /// Pastes the rows of a rectangular selection at the same column on
+    /// consecutive lines starting at `position`, padding short lines with spaces
+    /// and appending lines past the end of the buffer.
+    pub(crate) fn paste_column_block(
+        &mut self,
+        rows: &[&str],
+        position: Point,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let display_map = self.display_snapshot(cx);
+        let buffer = display_map.buffer_snapshot();
+        let column = position.to_display_point(&display_map).column();
+        let max_point = buffer.max_point();
+
+        let mut edits = Vec::with_capacity(rows.len());
+        for (ix, text) in rows.iter().enumerate() {
+            let row = position.row + ix as u32;
+            if row > max_point.row {
+                let padding = " ".repeat(column as usize);
+                edits.push((max_point..max_point, format!("\n{padding}{text}")));
+                continue;
+            }
+
+            let line_start = Point::new(row, 0).to_display_point(&display_map);
+            let line_end = Point::new(row, buffer.line_len(MultiBufferRow(row)))
+                .to_display_point(&display_map);
+            if line_end.row() == line_start.row() && line_end.column() < column {
+                let padding = " ".repeat((column - line_end.column()) as usize);
+                let end = line_end.to_point(&display_map);
+                edits.push((end..end, format!("{padding}{text}")));
+            } else {
+                let point = display_map
+                    .clip_point(DisplayPoint::new(line_start.row(), column), Bias::Left)
+                    .to_point(&display_map);
+                edits.push((point..point, text.to_string()));
+            }
+        }
+
+        // Appended lines all insert at the end of the buffer, so join them into one edit.
+        let mut joined_edits: Vec<(Range<Point>, String)> = Vec::with_capacity(edits.len());
+        for (range, text) in edits {
+            match joined_edits.last_mut() {
+                Some((last_range, last_text)) if *last_range == range => last_text.push_str(&text),
+                _ => joined_edits.push((range, text)),
+            }
+        }
+
+        let cursor = joined_edits
+            .last()
+            .map(|(range, _)| buffer.anchor_after(range.end));
+        self.buffer
+            .update(cx, |buffer, cx| buffer.edit(joined_edits, None, cx));
+        if let Some(cursor) = cursor {
+            self.change_selections(Default::default(), window, cx, |s| {
+                s.select_anchor_ranges([cursor..cursor]);
+            });
+        }
+    }
+}
@@ -0,0 +1,945 @@
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    path::Path,
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use gpui::{
+    AnyEntity, App, Entity, EventEmitter, FocusHandle, Focusable, Global, Subscription, Task,
+    WeakEntity,
+};
+use language::{Buffer, BufferSnapshot};
+use multi_buffer::{
+    MultiBuffer, MultiBufferOffset, MultiBufferRow, PathKey, ToOffset as _, ToPoint as _,
+};
+use project::{Project, ProjectPath};
+use rope::Point;
+use text::{Bias, BufferId, OffsetRangeExt as _};
+use ui::prelude::*;
+use util::ResultExt as _;
+use workspace::{
+    ItemId, ItemNavHistory, SerializableItem, Toast, Workspace, WorkspaceId,
+    item::{Item, ItemEvent, ItemHandle as _, SaveOptions},
+    notifications::NotificationId,
+    searchable::SearchableItemHandle,
+};
+
+use crate::{
+    AddToWorkingSet, Editor, EditorEvent, MoveDownInWorkingSet, MoveUpInWorkingSet, OpenWorkingSet,
+    RemoveFromWorkingSet, SelectionEffects, multibuffer_context_lines, persistence::EditorDb,
+    scroll::Autoscroll,
+};
+
+const DEFAULT_WORKING_SET_NAME: &str = "Working Set";
+
+/// How many bytes from each end of an excerpt are stored to find it again if
+/// its file changes while the working set is closed.
+const FINGERPRINT_LEN: usize = 32;
+
+/// An excerpt as it is stored in the database: the file's absolute path, the
+/// excerpt's offsets, and the fingerprints at its start and end.
+type SerializedExcerpt = (Arc<Path>, usize, usize, String, String);
+
+/// The working set that `editor::AddToWorkingSet` and `editor::OpenWorkingSet`
+/// use when they aren't given a name.
+struct LastWorkingSet(SharedString);
+
+impl Global for LastWorkingSet {}
+
+fn working_set_name(name: Option<&str>, cx: &mut App) -> SharedString {
+    let name = match name.map(str::trim).filter(|name| !name.is_empty()) {
+        Some(name) => SharedString::from(name.to_string()),
+        None => cx
+            .try_global::<LastWorkingSet>()
+            .map(|last| last.0.clone())
+            .unwrap_or(SharedString::new_static(DEFAULT_WORKING_SET_NAME)),
+    };
+    cx.set_global(LastWorkingSet(name.clone()));
+    name
+}
+
+#[derive(Clone)]
+struct WorkingSetExcerpt {
+    buffer: Entity<Buffer>,
+    range: Range<text::Anchor>,
+}
+
+impl WorkingSetExcerpt {
+    fn serialize(&self, cx: &App) -> Option<SerializedExcerpt> {
+        let buffer = self.buffer.read(cx);
+        let abs_path = project::File::from_dyn(buffer.file())?.abs_path(cx);
+        let snapshot = buffer.snapshot();
+        let range = self.range.to_offset(&snapshot);
+        let (start_fingerprint, end_fingerprint) = fingerprints(&snapshot, range.clone());
+        Some((
+            abs_path.into(),
+            range.start,
+            range.end,
+            start_fingerprint,
+            end_fingerprint,
+        ))
+    }
+}
+
+/// Returns where a new excerpt goes in a working set: after the last excerpt
+/// from the same file, so that each file's excerpts stay together.
+fn insertion_index<T>(excerpts: &[T], same_file: impl Fn(&T) -> bool) -> usize {
+    excerpts
+        .iter()
+        .rposition(same_file)
+        .map_or(excerpts.len(), |ix| ix + 1)
+}
+
+/// Returns the content at the start and end of a range, used to find it again
+/// if the offsets it was saved with no longer match.
+fn fingerprints(snapshot: &BufferSnapshot, range: Range<usize>) -> (String, String) {
+    let start_end =
+        snapshot.clip_offset(range.start + range.len().min(FINGERPRINT_LEN), Bias::Left);
+    let end_start = snapshot.clip_offset(
+        range.end.saturating_sub(FINGERPRINT_LEN).max(range.start),
+        Bias::Right,
+    );
+    (
+        snapshot.text_for_range(range.start..start_end).collect(),
+        snapshot.text_for_range(end_start..range.end).collect(),
+    )
+}
+
+/// Finds a saved excerpt in its file, which may have been edited since the
+/// excerpt was saved. Excerpts whose start or end can't be found are dropped.
+fn resolve_range(
+    snapshot: &BufferSnapshot,
+    range: Range<usize>,
+    start_fingerprint: &str,
+    end_fingerprint: &str,
+) -> Option<Range<usize>> {
+    let end_fingerprint_start = range.end.checked_sub(end_fingerprint.len())?;
+    if range.end <= snapshot.len()
+        && end_fingerprint_start >= range.start
+        && snapshot.contains_str_at(range.start, start_fingerprint)
+        && snapshot.contains_str_at(end_fingerprint_start, end_fingerprint)
+    {
+        return Some(range);
+    }
+
+    let start = find_nearest(snapshot, start_fingerprint, 0, range.start)?;
+    if start_fingerprint.len() == range.len() {
+        return Some(start..start + range.len());
+    }
+    let end_fingerprint_start = find_nearest(
+        snapshot,
+        end_fingerprint,
+        start
+            + start_fingerprint
+                .len()
+                .saturating_sub(end_fingerprint.len()),
+        start + (end_fingerprint_start - range.start),
+    )?;
+    Some(start..end_fingerprint_start + end_fingerprint.len())
+}
+
+/// Finds the occurrence of `needle` at or after `min_offset` that is closest
+/// to `expected_offset`.
+fn find_nearest(
+    snapshot: &BufferSnapshot,
+    needle: &str,
+    min_offset: usize,
+    expected_offset: usize,
+) -> Option<usize> {
+    let mut offset = snapshot.clip_offset(min_offset, Bias::Left);
+    let mut nearest: Option<usize> = None;
+    for character in snapshot.chars_at(offset) {
+        if offset + needle.len() > snapshot.len() {
+            break;
+        }
+        if snapshot.contains_str_at(offset, needle)
+            && nearest.is_none_or(|nearest| {
+                offset.abs_diff(expected_offset) < nearest.abs_diff(expected_offset)
+            })
+        {
+            nearest = Some(offset);
+        }
+        if nearest.is_some() && offset >= expected_offset {
+            break;
+        }
+        offset += character.len_utf8();
+    }
+    nearest
+}
+
+/// A user-curated multibuffer of excerpts, stored by name in the workspace
+/// database so it comes back after a restart.
+///
+/// A multibuffer keeps each file's excerpts together in the order they appear
+/// in the file, so reordering a working set moves whole files.
+pub struct WorkingSetView {
+    name: SharedString,
+    workspace_id: Option<WorkspaceId>,
+    focus_handle: FocusHandle,
+    editor: Entity<Editor>,
+    multibuffer: Entity<MultiBuffer>,
+    excerpts: Vec<WorkingSetExcerpt>,
+    save_task: Task<()>,
+    _subscription: Subscription,
+}
+
+impl EventEmitter<EditorEvent> for WorkingSetView {}
+
+impl WorkingSetView {
+    fn new(
+        name: SharedString,
+        excerpts: Vec<WorkingSetExcerpt>,
+        project: Entity<Project>,
+        workspace_id: Option<WorkspaceId>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let focus_handle = cx.focus_handle();
+        cx.on_focus_in(&focus_handle, window, |this, window, cx| {
+            if this.focus_handle.is_focused(window) && !this.excerpts.is_empty() {
+                this.editor.focus_handle(cx).focus(window, cx)
+            }
+        })
+        .detach();
+
+        let multibuffer = cx
+            .new(|cx| MultiBuffer::new(project.read(cx).capability()).with_title(name.to_string()));
+        let editor = cx.new(|cx| {
+            Editor::for_multibuffer(multibuffer.clone(), Some(project.clone()), window, cx)
+        });
+        let subscription = cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+            cx.emit(event.clone());
+        });
+
+        let mut this = Self {
+            name,
+            workspace_id,
+            focus_handle,
+            editor,
+            multibuffer,
+            excerpts,
+            save_task: Task::ready(()),
+            _subscription: subscription,
+        };
+        this.update_excerpts(cx);
+        this
+    }
+
+    fn load(
+        name: SharedString,
+        project: Entity<Project>,
+        workspace_id: WorkspaceId,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let saved_excerpts = EditorDb::global(cx).get_working_set(workspace_id, &name);
+        window.spawn(cx, async move |cx| {
+            let saved_excerpts =
+                saved_excerpts.with_context(|| format!("loading working set {name:?}"))?;
+            let mut buffers = HashMap::<Arc<Path>, Option<Entity<Buffer>>>::default();
+            let mut excerpts = Vec::new();
+            for (abs_path, start, end, start_fingerprint, end_fingerprint) in saved_excerpts {
+                let abs_path: Arc<Path> = abs_path.into();
+                let buffer = match buffers.get(&abs_path) {
+                    Some(buffer) => buffer.clone(),
+                    None => {
+                        let open_buffer = project.update(cx, |project, cx| {
+                            match project.find_worktree(&abs_path, cx) {
+                                Some((worktree, path)) => project.open_buffer(
+                                    ProjectPath {
+                                        worktree_id: worktree.read(cx).id(),
+                                        path,
+                                    },
+                                    cx,
+                                ),
+                                None => project.open_local_buffer(&abs_path, cx),
+                            }
+                        });
+                        let buffer = open_buffer
+                            .await
+                            .with_context(|| format!("opening {abs_path:?} for a working set"))
+                            .log_err();
+                        buffers.insert(abs_path, buffer.clone());
+                        buffer
+                    }
+                };
+                let Some(buffer) = buffer else {
+                    continue;
+                };
+
+                let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+                let Some(range) =
+                    resolve_range(&snapshot, start..end, &start_fingerprint, &end_fingerprint)
+                else {
+                    continue;
+                };
+                excerpts.push(WorkingSetExcerpt {
+                    range: snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end),
+                    buffer,
+                });
+            }
+
+            cx.update(|window, cx| {
+                cx.new(|cx| Self::new(name, excerpts, project, Some(workspace_id), window, cx))
+            })
+        })
+    }
+
+    pub fn open(
+        workspace: &mut Workspace,
+        action: &OpenWorkingSet,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let name = working_set_name(action.name.as_deref(), cx);
+        if let Some(working_set) = Self::find(workspace, &name, cx) {
+            workspace.activate_item(&working_set, true, true, window, cx);
+            return;
+        }
+
+        let project = workspace.project().clone();
+        let working_set = match workspace.database_id() {
+            Some(workspace_id) => Self::load(name, project, workspace_id, window, cx),
+            None => Task::ready(Ok(
+                cx.new(|cx| Self::new(name, Vec::new(), project, None, window, cx))
+            )),
+        };
+        cx.spawn_in(window, async move |workspace, cx| {
+            let working_set = working_set.await?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                workspace.add_item_to_active_pane(Box::new(working_set), None, true, window, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn find(workspace: &Workspace, name: &str, cx: &App) -> Option<Entity<Self>> {
+        workspace
+            .items_of_type::<Self>(cx)
+            .find(|working_set| working_set.read(cx).name.as_ref() == name)
+    }
+
+    fn add_excerpts(&mut self, new_excerpts: Vec<WorkingSetExcerpt>, cx: &mut Context<Self>) {
+        for new_excerpt in new_excerpts {
+            let snapshot = new_excerpt.buffer.read(cx).snapshot();
+            let is_duplicate = self.excerpts.iter().any(|excerpt| {
+                excerpt.buffer == new_excerpt.buffer
+                    && excerpt.range.to_offset(&snapshot) == new_excerpt.range.to_offset(&snapshot)
+            });
+            if !is_duplicate {
+                let ix = insertion_index(&self.excerpts, |excerpt| {
+                    excerpt.buffer == new_excerpt.buffer
+                });
+                self.excerpts.insert(ix, new_excerpt);
+            }
+        }
+        self.excerpts_changed(cx);
+    }
+
+    fn excerpts_changed(&mut self, cx: &mut Context<Self>) {
+        self.update_excerpts(cx);
+        self.save(cx);
+        cx.notify();
+    }
+
+    /// Shows the working set's files in order, each under a path key whose
+    /// sort prefix is the file's position in the set.
+    fn update_excerpts(&mut self, cx: &mut Context<Self>) {
+        let mut files = Vec::<(Entity<Buffer>, Vec<Range<Point>>)>::new();
+        for excerpt in &self.excerpts {
+            let buffer = excerpt.buffer.read(cx);
+            let range = excerpt.range.to_point(buffer);
+            match files
+                .iter_mut()
+                .find(|(buffer, _)| *buffer == excerpt.buffer)
+            {
+                Some((_, ranges)) => ranges.push(range),
+                None => files.push((excerpt.buffer.clone(), vec![range])),
+            }
+        }
+
+        let context_line_count = multibuffer_context_lines(cx);
+        self.multibuffer.update(cx, |multibuffer, cx| {
+            let removed_buffer_ids = multibuffer
+                .snapshot(cx)
+                .all_buffer_ids()
+                .filter(|buffer_id| {
+                    !files
+                        .iter()
+                        .any(|(buffer, _)| buffer.read(cx).remote_id() == *buffer_id)
+                })
+                .collect::<Vec<_>>();
+            for buffer_id in removed_buffer_ids {
+                multibuffer.remove_excerpts_for_buffer(buffer_id, cx);
+            }
+
+            for (position, (buffer, mut ranges)) in files.into_iter().enumerate() {
+                let Some(path) = buffer.read(cx).file().map(|file| file.path().clone()) else {
+                    continue;
+                };
+                ranges.sort_by_key(|range| (range.start, std::cmp::Reverse(range.end)));
+                multibuffer.set_excerpts_for_path(
+                    PathKey::with_sort_prefix(position as u64, path),
+                    buffer,
+                    ranges,
+                    context_line_count,
+                    cx,
+                );
+            }
+        });
+    }
+
+    fn save(&mut self, cx: &mut Context<Self>) {
+        let Some(workspace_id) = self.workspace_id else {
+            return;
+        };
+        let excerpts = self
+            .excerpts
+            .iter()
+            .filter_map(|excerpt| excerpt.serialize(cx))
+            .collect::<Vec<_>>();
+        let name = self.name.to_string();
+        let db = EditorDb::global(cx);
+        self.save_task = cx.background_spawn(async move {
+            db.save_working_set(workspace_id, name, excerpts)
+                .await
+                .with_context(|| format!("persisting working set for workspace {workspace_id:?}"))
+                .log_err();
+        });
+    }
+
+    /// Returns the buffer and context range of the excerpt under the newest
+    /// cursor.
+    fn excerpt_at_cursor(&self, cx: &App) -> Option<(BufferSnapshot, Range<text::Anchor>)> {
+        let head = self.editor.read(cx).selections.newest_anchor().head();
+        let snapshot = self.multibuffer.read(cx).snapshot(cx);
+        let (buffer_snapshot, excerpt_range) = snapshot.excerpt_containing(head..head)?;
+        Some((buffer_snapshot.clone(), excerpt_range.context))
+    }
+
+    fn remove_from_working_set(
+        &mut self,
+        _: &RemoveFromWorkingSet,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((buffer_snapshot, context)) = self.excerpt_at_cursor(cx) else {
+            return;
+        };
+        let buffer_id = buffer_snapshot.remote_id();
+        let excerpt_count = self.excerpts.len();
+        self.excerpts.retain(|excerpt| {
+            excerpt.range.start.buffer_id != buffer_id
+                || excerpt
+                    .range
+                    .end
+                    .cmp(&context.start, &buffer_snapshot)
+                    .is_lt()
+                || excerpt
+                    .range
+                    .start
+                    .cmp(&context.end, &buffer_snapshot)
+                    .is_gt()
+        });
+        if self.excerpts.len() != excerpt_count {
+            self.excerpts_changed(cx);
+        }
+    }
+
+    fn move_down_in_working_set(
+        &mut self,
+        _: &MoveDownInWorkingSet,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_file(1, window, cx);
+    }
+
+    fn move_up_in_working_set(
+        &mut self,
+        _: &MoveUpInWorkingSet,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_file(-1, window, cx);
+    }
+
+    /// Moves the file under the cursor past its neighbor, keeping the cursor
+    /// where it was in the file.
+    ///
+    /// Excerpts are only ever reordered a file at a time: the multibuffer keeps
+    /// all of a file's excerpts under one path key, in the order they appear
+    /// in the file, so a single excerpt can't be moved past another of its file.
+    fn move_file(&mut self, delta: isize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((buffer_snapshot, _)) = self.excerpt_at_cursor(cx) else {
+            return;
+        };
+        let buffer_id = buffer_snapshot.remote_id();
+        let cursor = self
+            .editor
+            .read(cx)
+            .selections
+            .newest_anchor()
+            .head()
+            .text_anchor_in(&buffer_snapshot);
+
+        let mut files = Vec::<(BufferId, Vec<WorkingSetExcerpt>)>::new();
+        for excerpt in self.excerpts.drain(..) {
+            let excerpt_buffer_id = excerpt.range.start.buffer_id;
+            match files.iter_mut().find(|(id, _)| *id == excerpt_buffer_id) {
+                Some((_, excerpts)) => excerpts.push(excerpt),
+                None => files.push((excerpt_buffer_id, vec![excerpt])),
+            }
+        }
+        let position = files.iter().position(|(id, _)| *id == buffer_id);
+        let new_position = position.and_then(|position| position.checked_add_signed(delta));
+        let moved = if let Some((position, new_position)) = position.zip(new_position)
+            && new_position < files.len()
+        {
+            files.swap(position, new_position);
+            true
+        } else {
+            false
+        };
+        self.excerpts = files
+            .into_iter()
+            .flat_map(|(_, excerpts)| excerpts)
+            .collect();
+        if !moved {
+            return;
+        }
+
+        self.excerpts_changed(cx);
+        let snapshot = self.multibuffer.read(cx).snapshot(cx);
+        if let Some(cursor) = snapshot.anchor_in_buffer(cursor) {
+            self.editor.update(cx, |editor, cx| {
+                editor.change_selections(
+                    SelectionEffects::scroll(Autoscroll::center()),
+                    window,
+                    cx,
+                    |selections| selections.select_anchor_ranges([cursor..cursor]),
+                );
+            });
+        }
+    }
+}
+
+impl Editor {
+    pub fn add_to_working_set(
+        &mut self,
+        action: &AddToWorkingSet,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let name = working_set_name(action.name.as_deref(), cx);
+        let excerpts = self.working_set_excerpts_for_selections(cx);
+        if excerpts.is_empty() {
+            return;
+        }
+
+        let open_working_set = WorkingSetView::find(workspace.read(cx), &name, cx);
+        let workspace_id = workspace.read(cx).database_id();
+        if let Some(working_set) = open_working_set {
+            working_set.update(cx, |working_set, cx| working_set.add_excerpts(excerpts, cx));
+        } else if let Some(workspace_id) = workspace_id {
+            let new_excerpts = excerpts
+                .iter()
+                .filter_map(|excerpt| excerpt.serialize(cx))
+                .collect::<Vec<_>>();
+            let db = EditorDb::global(cx);
+            let saved_excerpts = db.get_working_set(workspace_id, &name);
+            let name = name.to_string();
+            cx.background_spawn(async move {
+                let mut saved_excerpts = saved_excerpts?
+                    .into_iter()
+                    .map(|(abs_path, start, end, start_fp, end_fp)| {
+                        (abs_path.into(), start, end, start_fp, end_fp)
+                    })
+                    .collect::<Vec<SerializedExcerpt>>();
+                for new_excerpt in new_excerpts {
+                    let is_duplicate = saved_excerpts.iter().any(|excerpt| {
+                        excerpt.0 == new_excerpt.0
+                            && excerpt.1 == new_excerpt.1
+                            && excerpt.2 == new_excerpt.2
+                    });
+                    if !is_duplicate {
+                        let ix =
+                            insertion_index(&saved_excerpts, |excerpt| excerpt.0 == new_excerpt.0);
+                        saved_excerpts.insert(ix, new_excerpt);
+                    }
+                }
+                db.save_working_set(workspace_id, name, saved_excerpts)
+                    .await
+            })
+            .detach_and_log_err(cx);
+        } else {
+            let project = workspace.read(cx).project().clone();
+            let working_set =
+                cx.new(|cx| WorkingSetView::new(name.clone(), excerpts, project, None, window, cx));
+            workspace.update(cx, |workspace, cx| {
+                workspace.add_item_to_active_pane(Box::new(working_set), None, false, window, cx);
+            });
+        }
+
+        struct WorkingSetToast;
+        workspace.update(cx, |workspace, cx| {
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<WorkingSetToast>(),
+                    format!("Added to {name}"),
+                )
+                .autohide(),
+                cx,
+            )
+        });
+    }
+
+    /// Returns the selected ranges, using the innermost symbol around the
+    /// cursor, or the cursor's line, for empty selections.
+    fn working_set_excerpts_for_selections(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Vec<WorkingSetExcerpt> {
+        let display_snapshot = self.display_snapshot(cx);
+        let snapshot = display_snapshot.buffer_snapshot();
+        let mut excerpts = Vec::new();
+        for selection in self.selections.all::<MultiBufferOffset>(&display_snapshot) {
+            let range = if selection.is_empty() {
+                snapshot
+                    .symbols_containing(selection.head(), None)
+                    .and_then(|(_, symbols)| symbols.last().map(|symbol| symbol.range.clone()))
+                    .map(|range| range.start.to_offset(snapshot)..range.end.to_offset(snapshot))
+                    .unwrap_or_else(|| {
+                        let row = selection.head().to_point(snapshot).row;
+                        let line_len = snapshot.line_len(MultiBufferRow(row));
+                        Point::new(row, 0).to_offset(snapshot)
+                            ..Point::new(row, line_len).to_offset(snapshot)
+                    })
+            } else {
+                selection.range()
+            };
+
+            for (buffer_snapshot, buffer_range, _) in snapshot.range_to_buffer_ranges(range) {
+                let Some(buffer) = self.buffer.read(cx).buffer(buffer_snapshot.remote_id()) else {
+                    continue;
+                };
+                if buffer_range.is_empty() || buffer.read(cx).file().is_none() {
+                    continue;
+                }
+                excerpts.push(WorkingSetExcerpt {
+                    range: buffer_snapshot.anchor_before(buffer_range.start)
+                        ..buffer_snapshot.anchor_after(buffer_range.end),
+                    buffer,
+                });
+            }
+        }
+        excerpts
+    }
+}
+
+impl Focusable for WorkingSetView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for WorkingSetView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let child = if self.excerpts.is_empty() {
+            v_flex()
+                .size_full()
+                .justify_center()
+                .items_center()
+                .text_center()
+                .bg(cx.theme().colors().editor_background)
+                .child(Label::new(format!("{} is empty", self.name)).color(Color::Muted))
+                .child(
+                    Label::new("Add selections and symbols to it with editor: add to working set")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .into_any_element()
+        } else {
+            div()
+                .size_full()
+                .child(self.editor.clone())
+                .into_any_element()
+        };
+
+        div()
+            .key_context("WorkingSet")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .on_action(cx.listener(Self::remove_from_working_set))
+            .on_action(cx.listener(Self::move_down_in_working_set))
+            .on_action(cx.listener(Self::move_up_in_working_set))
+            .child(child)
+    }
+}
+
+impl Item for WorkingSetView {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: &mut dyn FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn navigate(
+        &mut self,
+        data: Arc<dyn Any + Send>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::ListTree))
+    }
+
+    fn tab_content_text(&self, _detail: usize, _: &App) -> SharedString {
+        self.name.clone()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(format!("Working Set: {}", self.name).into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Working Set Opened")
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.editor.for_each_project_item(cx, f)
+    }
+
+    fn buffer_kind(&self, _: &App) -> workspace::item::ItemBufferKind {
+        workspace::item::ItemBufferKind::Multibuffer
+    }
+
+    fn active_project_path(&self, cx: &App) -> Option<ProjectPath> {
+        self.editor.read(cx).active_project_path(cx)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.multibuffer.read(cx).is_dirty(cx)
+    }
+
+    fn has_deleted_file(&self, cx: &App) -> bool {
+        self.multibuffer.read(cx).has_deleted_file(cx)
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.multibuffer.read(cx).has_conflict(cx)
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        options: SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.editor.save(options, project, window, cx)
+    }
+
+    fn reload(
+        &mut self,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.editor.reload(project, window, cx)
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyEntity> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.clone().into())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.clone().into())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>, _: &App) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.workspace_id = workspace.database_id();
+        self.editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+}
+
+impl SerializableItem for WorkingSetView {
+    fn serialized_item_kind() -> &'static str {
+        "WorkingSet"
+    }
+
+    fn cleanup(
+        workspace_id: WorkspaceId,
+        alive_items: Vec<ItemId>,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<()>> {
+        workspace::delete_unloaded_items(
+            alive_items,
+            workspace_id,
+            "working_sets",
+            &EditorDb::global(cx),
+            cx,
+        )
+    }
+
+    fn deserialize(
+        project: Entity<Project>,
+        _workspace: WeakEntity<Workspace>,
+        workspace_id: WorkspaceId,
+        item_id: ItemId,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let name = match EditorDb::global(cx).get_working_set_name(item_id, workspace_id) {
+            Ok(Some(name)) => name,
+            Ok(None) => {
+                return Task::ready(Err(anyhow::anyhow!(
+                    "No working set in database for item_id: {item_id} and workspace_id {workspace_id:?}"
+                )));
+            }
+            Err(error) => return Task::ready(Err(error)),
+        };
+        Self::load(name.into(), project, workspace_id, window, cx)
+    }
+
+    fn serialize(
+        &mut self,
+        workspace: &mut Workspace,
+        item_id: ItemId,
+        _closing: bool,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Task<Result<()>>> {
+        let workspace_id = workspace.database_id()?;
+        self.workspace_id = Some(workspace_id);
+        self.save(cx);
+
+        let name = self.name.to_string();
+        let db = EditorDb::global(cx);
+        Some(cx.background_spawn(async move {
+            db.save_working_set_name(item_id, workspace_id, name).await
+        }))
+    }
+
+    fn should_serialize(&self, event: &Self::Event) -> bool {
+        matches!(event, EditorEvent::Saved | EditorEvent::BufferEdited)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    fn test_resolve_range_after_file_changes(cx: &mut TestAppContext) {
+        let text = indoc::indoc! {"
+            fn one() {}
+
+            fn two() {
+                let value = 2;
+                let doubled = value * 2;
+                doubled * value
+            }
+        "};
+        let buffer = cx.new(|cx| Buffer::local(text, cx));
+        let range = text.find("fn two").unwrap()..text.len() - 1;
+        let (start_fingerprint, end_fingerprint) = buffer.read_with(cx, |buffer, _| {
+            fingerprints(&buffer.snapshot(), range.clone())
+        });
+
+        let resolve = |cx: &mut TestAppContext| {
+            buffer.read_with(cx, |buffer, _| {
+                let snapshot = buffer.snapshot();
+                resolve_range(
+                    &snapshot,
+                    range.clone(),
+                    &start_fingerprint,
+                    &end_fingerprint,
+                )
+                .map(|range| snapshot.text_for_range(range).collect::<String>())
+            })
+        };
+        assert_eq!(resolve(cx), Some(text[range.clone()].to_string()));
+
+        // Text inserted before the excerpt shifts it.
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "// one\n")], None, cx));
+        assert_eq!(resolve(cx), Some(text[range.clone()].to_string()));
+
+        // Edits inside the excerpt are kept.
+        buffer.update(cx, |buffer, cx| {
+            let offset = buffer.text().find("    let doubled").unwrap();
+            buffer.edit([(offset..offset, "    // Square it.\n")], None, cx)
+        });
+        assert_eq!(
+            resolve(cx).as_deref(),
+            Some(indoc::indoc! {"
+                fn two() {
+                    let value = 2;
+                    // Square it.
+                    let doubled = value * 2;
+                    doubled * value
+                }"})
+        );
+
+        // The excerpt is dropped once its start is gone.
+        buffer.update(cx, |buffer, cx| {
+            let text = buffer.text().replace("fn two", "fn three");
+            buffer.set_text(text, cx)
+        });
+        assert_eq!(resolve(cx), None);
+    }
+}
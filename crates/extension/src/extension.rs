@@ -179,6 +179,40 @@ pub trait Extension: Send + Sync + 'static {
         locator_name: String,
         config: SpawnInTerminal,
     ) -> Result<DebugRequest>;
+
+    async fn git_hosting_provider_parse_remote_url(
+        &self,
+        provider_name: Arc<str>,
+        url: String,
+    ) -> Result<Option<ParsedGitRemote>>;
+
+    async fn git_hosting_provider_build_commit_permalink(
+        &self,
+        provider_name: Arc<str>,
+        remote: ParsedGitRemote,
+        sha: String,
+    ) -> Result<String>;
+
+    async fn git_hosting_provider_build_permalink(
+        &self,
+        provider_name: Arc<str>,
+        remote: ParsedGitRemote,
+        params: BuildPermalinkParams,
+    ) -> Result<String>;
+
+    async fn git_hosting_provider_build_create_pull_request_url(
+        &self,
+        provider_name: Arc<str>,
+        remote: ParsedGitRemote,
+        source_branch: String,
+    ) -> Result<Option<String>>;
+
+    async fn git_hosting_provider_extract_pull_request(
+        &self,
+        provider_name: Arc<str>,
+        remote: ParsedGitRemote,
+        message: String,
+    ) -> Result<Option<PullRequest>>;
 }
 
 pub fn parse_wasm_extension_version(extension_id: &str, wasm_bytes: &[u8]) -> Result<Version> {
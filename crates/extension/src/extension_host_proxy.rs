@@ -31,6 +31,7 @@ pub struct ExtensionHostProxy {
     snippet_proxy: RwLock<Option<Arc<dyn ExtensionSnippetProxy>>>,
     context_server_proxy: RwLock<Option<Arc<dyn ExtensionContextServerProxy>>>,
     debug_adapter_provider_proxy: RwLock<Option<Arc<dyn ExtensionDebugAdapterProviderProxy>>>,
+    git_hosting_provider_proxy: RwLock<Option<Arc<dyn ExtensionGitHostingProviderProxy>>>,
     language_model_provider_proxy: RwLock<Option<Arc<dyn ExtensionLanguageModelProviderProxy>>>,
 }
 
@@ -56,6 +57,7 @@ impl ExtensionHostProxy {
             snippet_proxy: RwLock::default(),
             context_server_proxy: RwLock::default(),
             debug_adapter_provider_proxy: RwLock::default(),
+            git_hosting_provider_proxy: RwLock::default(),
             language_model_provider_proxy: RwLock::default(),
         }
     }
@@ -90,6 +92,15 @@ impl ExtensionHostProxy {
             .replace(Arc::new(proxy));
     }
 
+    pub fn register_git_hosting_provider_proxy(
+        &self,
+        proxy: impl ExtensionGitHostingProviderProxy,
+    ) {
+        self.git_hosting_provider_proxy
+            .write()
+            .replace(Arc::new(proxy));
+    }
+
     pub fn register_language_model_provider_proxy(
         &self,
         proxy: impl ExtensionLanguageModelProviderProxy,
@@ -433,6 +444,40 @@ impl ExtensionDebugAdapterProviderProxy for ExtensionHostProxy {
     }
 }
 
+pub trait ExtensionGitHostingProviderProxy: Send + Sync + 'static {
+    fn register_git_hosting_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_name: Arc<str>,
+        base_url: &str,
+    );
+
+    fn unregister_git_hosting_provider(&self, provider_name: Arc<str>);
+}
+
+impl ExtensionGitHostingProviderProxy for ExtensionHostProxy {
+    fn register_git_hosting_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_name: Arc<str>,
+        base_url: &str,
+    ) {
+        let Some(proxy) = self.git_hosting_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_git_hosting_provider(extension, provider_name, base_url)
+    }
+
+    fn unregister_git_hosting_provider(&self, provider_name: Arc<str>) {
+        let Some(proxy) = self.git_hosting_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.unregister_git_hosting_provider(provider_name)
+    }
+}
+
 pub trait ExtensionLanguageModelProviderProxy: Send + Sync + 'static {
     fn register_language_model_provider(
         &self,
@@ -119,6 +119,8 @@ pub struct ExtensionManifest {
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub debug_locators: BTreeMap<Arc<str>, DebugLocatorManifestEntry>,
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub git_hosting_providers: BTreeMap<Arc<str>, GitHostingProviderManifestEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub language_model_providers: BTreeMap<Arc<str>, LanguageModelProviderManifestEntry>,
 }
 
@@ -189,7 +191,8 @@ impl ExtensionManifest {
     pub fn remote_load(&self) -> Option<RemoteLoad<'_>> {
         (!self.language_servers.is_empty()
             || !self.debug_adapters.is_empty()
-            || !self.debug_locators.is_empty())
+            || !self.debug_locators.is_empty()
+            || !self.git_hosting_providers.is_empty())
         .then_some(RemoteLoad { manifest: self })
     }
 }
@@ -376,6 +379,12 @@ pub struct DebugAdapterManifestEntry {
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct DebugLocatorManifestEntry {}
 
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct GitHostingProviderManifestEntry {
+    /// The base URL of the Git hosting provider, used to match remote URLs.
+    pub base_url: String,
+}
+
 /// Manifest entry for a language model provider.
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct LanguageModelProviderManifestEntry {
@@ -455,6 +464,7 @@ fn manifest_from_old_manifest(
         capabilities: Vec::new(),
         debug_adapters: Default::default(),
         debug_locators: Default::default(),
+        git_hosting_providers: Default::default(),
         language_model_providers: Default::default(),
     }
 }
@@ -489,6 +499,7 @@ mod tests {
             capabilities: vec![],
             debug_adapters: Default::default(),
             debug_locators: Default::default(),
+            git_hosting_providers: Default::default(),
             language_model_providers: BTreeMap::default(),
         }
     }
@@ -0,0 +1,30 @@
+use std::ops::Range;
+
+/// A Git remote URL parsed by a Git hosting provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedGitRemote {
+    /// The owner of the repository, which may contain slashes for nested groups.
+    pub owner: String,
+    /// The name of the repository.
+    pub repo: String,
+}
+
+/// The parameters used to build a permalink to a file.
+#[derive(Debug, Clone)]
+pub struct BuildPermalinkParams {
+    /// The SHA of the commit to link to.
+    pub sha: String,
+    /// The URL-escaped path of the file, relative to the repository root.
+    pub path: String,
+    /// The zero-based range of selected lines, if any.
+    pub selection: Option<Range<u32>>,
+}
+
+/// A pull request referenced by a commit message.
+#[derive(Debug, Clone)]
+pub struct PullRequest {
+    /// The number of the pull request.
+    pub number: u32,
+    /// The URL of the pull request.
+    pub url: String,
+}
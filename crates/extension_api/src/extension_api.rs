@@ -25,6 +25,7 @@ pub use wit::{
         LaunchRequest, StartDebuggingRequestArguments, StartDebuggingRequestArgumentsRequest,
         TaskTemplate, TcpArguments, TcpArgumentsTemplate, resolve_tcp_template,
     },
+    zed::extension::git_hosting_provider::{BuildPermalinkParams, ParsedGitRemote, PullRequest},
     zed::extension::github::{
         GithubRelease, GithubReleaseAsset, GithubReleaseOptions, github_release_by_tag_name,
         latest_github_release,
@@ -281,6 +282,57 @@ pub trait Extension: Send + Sync {
     ) -> Result<DebugRequest, String> {
         Err("`run_dap_locator` not implemented".to_string())
     }
+
+    /// Parses a Git remote URL for the Git hosting provider with the given name.
+    ///
+    /// Returns `None` if the URL does not belong to this provider.
+    fn git_hosting_provider_parse_remote_url(
+        &mut self,
+        _provider_name: String,
+        _url: String,
+    ) -> Option<ParsedGitRemote> {
+        None
+    }
+
+    /// Returns the URL of the given commit on the Git hosting provider.
+    fn git_hosting_provider_build_commit_permalink(
+        &mut self,
+        _provider_name: String,
+        _remote: ParsedGitRemote,
+        _sha: String,
+    ) -> Result<String, String> {
+        Err("`git_hosting_provider_build_commit_permalink` not implemented".to_string())
+    }
+
+    /// Returns a permalink to a file, and optionally a range of lines, on the Git hosting provider.
+    fn git_hosting_provider_build_permalink(
+        &mut self,
+        _provider_name: String,
+        _remote: ParsedGitRemote,
+        _params: BuildPermalinkParams,
+    ) -> Result<String, String> {
+        Err("`git_hosting_provider_build_permalink` not implemented".to_string())
+    }
+
+    /// Returns the URL for creating a pull request from the given branch.
+    fn git_hosting_provider_build_create_pull_request_url(
+        &mut self,
+        _provider_name: String,
+        _remote: ParsedGitRemote,
+        _source_branch: String,
+    ) -> Option<String> {
+        None
+    }
+
+    /// Returns the pull request referenced by the given commit message, if any.
+    fn git_hosting_provider_extract_pull_request(
+        &mut self,
+        _provider_name: String,
+        _remote: ParsedGitRemote,
+        _message: String,
+    ) -> Option<PullRequest> {
+        None
+    }
 }
 
 /// Registers the provided type as a Zed extension.
@@ -559,6 +611,49 @@ impl wit::Guest for Component {
     ) -> Result<DebugRequest, String> {
         extension().run_dap_locator(locator_name, build_task)
     }
+
+    fn git_hosting_provider_parse_remote_url(
+        provider_name: String,
+        url: String,
+    ) -> Option<ParsedGitRemote> {
+        extension().git_hosting_provider_parse_remote_url(provider_name, url)
+    }
+
+    fn git_hosting_provider_build_commit_permalink(
+        provider_name: String,
+        remote: ParsedGitRemote,
+        sha: String,
+    ) -> Result<String, String> {
+        extension().git_hosting_provider_build_commit_permalink(provider_name, remote, sha)
+    }
+
+    fn git_hosting_provider_build_permalink(
+        provider_name: String,
+        remote: ParsedGitRemote,
+        params: BuildPermalinkParams,
+    ) -> Result<String, String> {
+        extension().git_hosting_provider_build_permalink(provider_name, remote, params)
+    }
+
+    fn git_hosting_provider_build_create_pull_request_url(
+        provider_name: String,
+        remote: ParsedGitRemote,
+        source_branch: String,
+    ) -> Option<String> {
+        extension().git_hosting_provider_build_create_pull_request_url(
+            provider_name,
+            remote,
+            source_branch,
+        )
+    }
+
+    fn git_hosting_provider_extract_pull_request(
+        provider_name: String,
+        remote: ParsedGitRemote,
+        message: String,
+    ) -> Option<PullRequest> {
+        extension().git_hosting_provider_extract_pull_request(provider_name, remote, message)
+    }
 }
 
 /// The ID of a language server.
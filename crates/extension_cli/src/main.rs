@@ -712,6 +712,7 @@ mod tests {
             capabilities: Vec::new(),
             debug_adapters: BTreeMap::default(),
             debug_locators: BTreeMap::default(),
+            git_hosting_providers: BTreeMap::default(),
             language_model_providers: BTreeMap::default(),
         }
     }
@@ -150,6 +150,7 @@ fn manifest() -> ExtensionManifest {
         )],
         debug_adapters: Default::default(),
         debug_locators: Default::default(),
+        git_hosting_providers: Default::default(),
         language_model_providers: BTreeMap::default(),
     }
 }
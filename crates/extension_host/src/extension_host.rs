@@ -16,8 +16,9 @@ pub use extension::ExtensionManifest;
 use extension::extension_builder::{CompileExtensionOptions, ExtensionBuilder};
 use extension::{
     ExtensionContextServerProxy, ExtensionDebugAdapterProviderProxy, ExtensionEvents,
-    ExtensionGrammarProxy, ExtensionHostProxy, ExtensionLanguageProxy,
-    ExtensionLanguageServerProxy, ExtensionSnippetProxy, ExtensionThemeProxy,
+    ExtensionGitHostingProviderProxy, ExtensionGrammarProxy, ExtensionHostProxy,
+    ExtensionLanguageProxy, ExtensionLanguageServerProxy, ExtensionSnippetProxy,
+    ExtensionThemeProxy,
 };
 use fs::{Fs, RemoveOptions, RenameOptions};
 use futures::future::join_all;
@@ -1325,6 +1326,10 @@ impl ExtensionStore {
             for locator in extension.manifest.debug_locators.keys() {
                 self.proxy.unregister_debug_locator(locator.clone());
             }
+            for provider_name in extension.manifest.git_hosting_providers.keys() {
+                self.proxy
+                    .unregister_git_hosting_provider(provider_name.clone());
+            }
         }
 
         self.wasm_extensions
@@ -1561,6 +1566,14 @@ impl ExtensionStore {
                         this.proxy
                             .register_debug_locator(extension.clone(), debug_adapter.clone());
                     }
+
+                    for (provider_name, provider) in &manifest.git_hosting_providers {
+                        this.proxy.register_git_hosting_provider(
+                            extension.clone(),
+                            provider_name.clone(),
+                            &provider.base_url,
+                        );
+                    }
                 }
 
                 this.wasm_extensions.extend(wasm_extensions);
@@ -378,6 +378,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         capabilities: Vec::new(),
                         debug_adapters: Default::default(),
                         debug_locators: Default::default(),
+                        git_hosting_providers: Default::default(),
                         language_model_providers: BTreeMap::default(),
                     }),
                     dev: false,
@@ -409,6 +410,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         capabilities: Vec::new(),
                         debug_adapters: Default::default(),
                         debug_locators: Default::default(),
+                        git_hosting_providers: Default::default(),
                         language_model_providers: BTreeMap::default(),
                     }),
                     dev: false,
@@ -595,6 +597,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                 capabilities: Vec::new(),
                 debug_adapters: Default::default(),
                 debug_locators: Default::default(),
+                git_hosting_providers: Default::default(),
                 language_model_providers: BTreeMap::default(),
             }),
             dev: false,
@@ -4,8 +4,8 @@ use anyhow::{Context as _, Result};
 use client::{TypedEnvelope, proto};
 use collections::{HashMap, HashSet};
 use extension::{
-    Extension, ExtensionDebugAdapterProviderProxy, ExtensionHostProxy, ExtensionLanguageProxy,
-    ExtensionLanguageServerProxy, ExtensionManifest,
+    Extension, ExtensionDebugAdapterProviderProxy, ExtensionGitHostingProviderProxy,
+    ExtensionHostProxy, ExtensionLanguageProxy, ExtensionLanguageServerProxy, ExtensionManifest,
 };
 use fs::{Fs, RemoveOptions, RenameOptions};
 use futures::future::{FutureExt as _, join_all};
@@ -218,6 +218,17 @@ impl HeadlessExtensionStore {
             log::info!("Loaded debug locator: {}", debug_locator);
         }
 
+        for (provider_name, provider) in &manifest.git_hosting_providers {
+            this.update(cx, |this, _cx| {
+                this.proxy.register_git_hosting_provider(
+                    wasm_extension.clone(),
+                    provider_name.clone(),
+                    &provider.base_url,
+                );
+            })?;
+            log::info!("Loaded git hosting provider: {}", provider_name);
+        }
+
         Ok(())
     }
 
@@ -6,10 +6,10 @@ use anyhow::{Context as _, Result, anyhow, bail};
 use async_trait::async_trait;
 use dap::{DebugRequest, StartDebuggingRequestArgumentsRequest};
 use extension::{
-    CodeLabel, Command, Completion, ContextServerConfiguration, DebugAdapterBinary,
-    DebugTaskDefinition, ExtensionCapability, ExtensionHostProxy, KeyValueStoreDelegate,
-    ProjectDelegate, SlashCommand, SlashCommandArgumentCompletion, SlashCommandOutput, Symbol,
-    WorktreeDelegate,
+    BuildPermalinkParams, CodeLabel, Command, Completion, ContextServerConfiguration,
+    DebugAdapterBinary, DebugTaskDefinition, ExtensionCapability, ExtensionHostProxy,
+    KeyValueStoreDelegate, ParsedGitRemote, ProjectDelegate, PullRequest, SlashCommand,
+    SlashCommandArgumentCompletion, SlashCommandOutput, Symbol, WorktreeDelegate,
 };
 use fs::Fs;
 use futures::future::LocalBoxFuture;
@@ -527,6 +527,112 @@ impl extension::Extension for WasmExtension {
         })
         .await?
     }
+
+    async fn git_hosting_provider_parse_remote_url(
+        &self,
+        provider_name: Arc<str>,
+        url: String,
+    ) -> Result<Option<ParsedGitRemote>> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_git_hosting_provider_parse_remote_url(store, &provider_name, &url)
+                    .await
+            }
+            .boxed()
+        })
+        .await?
+    }
+
+    async fn git_hosting_provider_build_commit_permalink(
+        &self,
+        provider_name: Arc<str>,
+        remote: ParsedGitRemote,
+        sha: String,
+    ) -> Result<String> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_git_hosting_provider_build_commit_permalink(
+                        store,
+                        &provider_name,
+                        remote,
+                        &sha,
+                    )
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))
+            }
+            .boxed()
+        })
+        .await?
+    }
+
+    async fn git_hosting_provider_build_permalink(
+        &self,
+        provider_name: Arc<str>,
+        remote: ParsedGitRemote,
+        params: BuildPermalinkParams,
+    ) -> Result<String> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_git_hosting_provider_build_permalink(
+                        store,
+                        &provider_name,
+                        remote,
+                        params,
+                    )
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))
+            }
+            .boxed()
+        })
+        .await?
+    }
+
+    async fn git_hosting_provider_build_create_pull_request_url(
+        &self,
+        provider_name: Arc<str>,
+        remote: ParsedGitRemote,
+        source_branch: String,
+    ) -> Result<Option<String>> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_git_hosting_provider_build_create_pull_request_url(
+                        store,
+                        &provider_name,
+                        remote,
+                        &source_branch,
+                    )
+                    .await
+            }
+            .boxed()
+        })
+        .await?
+    }
+
+    async fn git_hosting_provider_extract_pull_request(
+        &self,
+        provider_name: Arc<str>,
+        remote: ParsedGitRemote,
+        message: String,
+    ) -> Result<Option<PullRequest>> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_git_hosting_provider_extract_pull_request(
+                        store,
+                        &provider_name,
+                        remote,
+                        &message,
+                    )
+                    .await
+            }
+            .boxed()
+        })
+        .await?
+    }
 }
 
 pub struct WasmState {
@@ -1268,6 +1268,180 @@ impl Extension {
             }
         }
     }
+
+    pub async fn call_git_hosting_provider_parse_remote_url(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_name: &str,
+        url: &str,
+    ) -> Result<Option<extension::ParsedGitRemote>> {
+        match self {
+            Extension::V0_8_0(ext) => {
+                let remote = ext
+                    .call_git_hosting_provider_parse_remote_url(store, provider_name, url)
+                    .await?;
+
+                Ok(remote.map(Into::into))
+            }
+            Extension::V0_6_0(_)
+            | Extension::V0_5_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_1(_) => {
+                anyhow::bail!(
+                    "`git_hosting_provider_parse_remote_url` not available prior to v0.8.0"
+                );
+            }
+        }
+    }
+
+    pub async fn call_git_hosting_provider_build_commit_permalink(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_name: &str,
+        remote: extension::ParsedGitRemote,
+        sha: &str,
+    ) -> Result<Result<String, String>> {
+        match self {
+            Extension::V0_8_0(ext) => {
+                let permalink = ext
+                    .call_git_hosting_provider_build_commit_permalink(
+                        store,
+                        provider_name,
+                        &remote.into(),
+                        sha,
+                    )
+                    .await?;
+
+                Ok(permalink)
+            }
+            Extension::V0_6_0(_)
+            | Extension::V0_5_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_1(_) => {
+                anyhow::bail!(
+                    "`git_hosting_provider_build_commit_permalink` not available prior to v0.8.0"
+                );
+            }
+        }
+    }
+
+    pub async fn call_git_hosting_provider_build_permalink(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_name: &str,
+        remote: extension::ParsedGitRemote,
+        params: extension::BuildPermalinkParams,
+    ) -> Result<Result<String, String>> {
+        match self {
+            Extension::V0_8_0(ext) => {
+                let permalink = ext
+                    .call_git_hosting_provider_build_permalink(
+                        store,
+                        provider_name,
+                        &remote.into(),
+                        &params.into(),
+                    )
+                    .await?;
+
+                Ok(permalink)
+            }
+            Extension::V0_6_0(_)
+            | Extension::V0_5_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_1(_) => {
+                anyhow::bail!(
+                    "`git_hosting_provider_build_permalink` not available prior to v0.8.0"
+                );
+            }
+        }
+    }
+
+    pub async fn call_git_hosting_provider_build_create_pull_request_url(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_name: &str,
+        remote: extension::ParsedGitRemote,
+        source_branch: &str,
+    ) -> Result<Option<String>> {
+        match self {
+            Extension::V0_8_0(ext) => {
+                let url = ext
+                    .call_git_hosting_provider_build_create_pull_request_url(
+                        store,
+                        provider_name,
+                        &remote.into(),
+                        source_branch,
+                    )
+                    .await?;
+
+                Ok(url)
+            }
+            Extension::V0_6_0(_)
+            | Extension::V0_5_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_1(_) => {
+                anyhow::bail!(
+                    "`git_hosting_provider_build_create_pull_request_url` not available prior to v0.8.0"
+                );
+            }
+        }
+    }
+
+    pub async fn call_git_hosting_provider_extract_pull_request(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_name: &str,
+        remote: extension::ParsedGitRemote,
+        message: &str,
+    ) -> Result<Option<extension::PullRequest>> {
+        match self {
+            Extension::V0_8_0(ext) => {
+                let pull_request = ext
+                    .call_git_hosting_provider_extract_pull_request(
+                        store,
+                        provider_name,
+                        &remote.into(),
+                        message,
+                    )
+                    .await?;
+
+                Ok(pull_request.map(Into::into))
+            }
+            Extension::V0_6_0(_)
+            | Extension::V0_5_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_1(_) => {
+                anyhow::bail!(
+                    "`git_hosting_provider_extract_pull_request` not available prior to v0.8.0"
+                );
+            }
+        }
+    }
 }
 
 trait ToWasmtimeResult<T> {
@@ -352,6 +352,46 @@ impl TryFrom<SpawnInTerminal> for ResolvedTask {
     }
 }
 
+impl From<extension::ParsedGitRemote> for git_hosting_provider::ParsedGitRemote {
+    fn from(value: extension::ParsedGitRemote) -> Self {
+        Self {
+            owner: value.owner,
+            repo: value.repo,
+        }
+    }
+}
+
+impl From<git_hosting_provider::ParsedGitRemote> for extension::ParsedGitRemote {
+    fn from(value: git_hosting_provider::ParsedGitRemote) -> Self {
+        Self {
+            owner: value.owner,
+            repo: value.repo,
+        }
+    }
+}
+
+impl From<extension::BuildPermalinkParams> for git_hosting_provider::BuildPermalinkParams {
+    fn from(value: extension::BuildPermalinkParams) -> Self {
+        Self {
+            sha: value.sha,
+            path: value.path,
+            selection: value.selection.map(|selection| Range {
+                start: selection.start,
+                end: selection.end,
+            }),
+        }
+    }
+}
+
+impl From<git_hosting_provider::PullRequest> for extension::PullRequest {
+    fn from(value: git_hosting_provider::PullRequest) -> Self {
+        Self {
+            number: value.number,
+            url: value.url,
+        }
+    }
+}
+
 impl From<CodeLabel> for extension::CodeLabel {
     fn from(value: CodeLabel) -> Self {
         Self {
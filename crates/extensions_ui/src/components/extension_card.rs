@@ -505,6 +505,7 @@ impl Component for ExtensionCard {
                 capabilities: Vec::new(),
                 debug_adapters: Default::default(),
                 debug_locators: Default::default(),
+                git_hosting_providers: Default::default(),
                 language_model_providers: Default::default(),
             })
         }
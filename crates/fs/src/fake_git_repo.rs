@@ -62,6 +62,8 @@ pub struct FakeGitRepositoryState {
     pub unmerged_paths: HashMap<RepoPath, UnmergedStatus>,
     pub head_contents: HashMap<RepoPath, Vec<u8>>,
     pub index_contents: HashMap<RepoPath, Vec<u8>>,
+    /// The base, ours and theirs index stages of unmerged paths.
+    pub conflict_stages: HashMap<RepoPath, [Option<Vec<u8>>; 3]>,
     // everything in commit contents is in oids
     pub merge_base_contents: HashMap<RepoPath, Oid>,
     pub oids: HashMap<Oid, Vec<u8>>,
@@ -89,6 +91,7 @@ impl FakeGitRepositoryState {
             event_emitter,
             head_contents: Default::default(),
             index_contents: Default::default(),
+            conflict_stages: Default::default(),
             unmerged_paths: Default::default(),
             blames: Default::default(),
             blames_at_revision: Default::default(),
@@ -179,10 +182,62 @@ impl GitRepository for FakeGitRepository {
 
     fn load_commit(
         &self,
-        _commit: String,
+        commit: String,
         _cx: AsyncApp,
     ) -> BoxFuture<'_, Result<git::repository::CommitDiff>> {
-        async { Ok(git::repository::CommitDiff { files: Vec::new() }) }.boxed()
+        self.with_state_async(false, move |state| {
+            let sha = state.refs.get(&commit).cloned().unwrap_or(commit);
+            // Each snapshot in the history holds the contents of the commit
+            // that HEAD pointed to before the next commit was made.
+            let (contents, parent_contents) =
+                if state.refs.get("HEAD").is_some_and(|head| *head == sha) {
+                    (
+                        &state.head_contents,
+                        state
+                            .commit_history
+                            .last()
+                            .map(|snapshot| &snapshot.head_contents),
+                    )
+                } else {
+                    let ix = state
+                        .commit_history
+                        .iter()
+                        .position(|snapshot| snapshot.sha == sha)
+                        .with_context(|| format!("unknown commit {sha}"))?;
+                    (
+                        &state.commit_history[ix].head_contents,
+                        ix.checked_sub(1)
+                            .map(|ix| &state.commit_history[ix].head_contents),
+                    )
+                };
+
+            let mut paths = contents
+                .keys()
+                .chain(
+                    parent_contents
+                        .into_iter()
+                        .flat_map(|contents| contents.keys()),
+                )
+                .cloned()
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect::<Vec<_>>();
+            paths.sort();
+            let files = paths
+                .into_iter()
+                .filter_map(|path| {
+                    let old_content = parent_contents.and_then(|contents| contents.get(&path));
+                    let new_content = contents.get(&path);
+                    (old_content != new_content).then(|| git::repository::CommitFile {
+                        path,
+                        old_content: old_content.cloned(),
+                        new_content: new_content.cloned(),
+                        is_binary: false,
+                    })
+                })
+                .collect();
+            Ok(git::repository::CommitDiff { files })
+        })
     }
 
     fn set_index_text(
@@ -270,6 +325,13 @@ impl GitRepository for FakeGitRepository {
                 .into_iter()
                 .map(|rev| {
                     let (prefix, path) = rev.split_once(':')?;
+                    if prefix.is_empty()
+                        && let Some((stage, path)) = path.split_once(':')
+                        && let Ok(stage @ 1..=3) = stage.parse::<usize>()
+                    {
+                        let repo_path = RepoPath::new(path).ok()?;
+                        return state.conflict_stages.get(&repo_path)?[stage - 1].clone();
+                    }
                     let repo_path = RepoPath::new(path).ok()?;
                     match prefix {
                         "" => state.index_contents.get(&repo_path).cloned(),
@@ -3044,9 +3044,20 @@ impl Fs for FakeFs {
         let content = source_entry.file_content(&source)?.clone();
         let mut kind = Some(PathEventKind::Created);
         state.write_path(&target, |e| match e {
-            btree_map::Entry::Occupied(e) => {
+            btree_map::Entry::Occupied(mut e) => {
                 if options.overwrite {
                     kind = Some(PathEventKind::Changed);
+                    if let FakeFsEntry::File {
+                        mtime: existing_mtime,
+                        len,
+                        content: existing_content,
+                        ..
+                    } = e.get_mut()
+                    {
+                        *existing_mtime = mtime;
+                        *len = content.len() as u64;
+                        *existing_content = content;
+                    }
                     Ok(Some(e.get().clone()))
                 } else if !options.ignore_if_exists {
                     anyhow::bail!("{target:?} already exists");
@@ -229,6 +229,16 @@ impl GitHostingProviderRegistry {
     ) {
         self.state.write().default_providers.push(provider);
     }
+
+    /// Removes the [`GitHostingProvider`]s with the given name from the registry.
+    ///
+    /// Providers configured in the settings are left untouched.
+    pub fn unregister_hosting_provider(&self, name: &str) {
+        self.state
+            .write()
+            .default_providers
+            .retain(|provider| provider.name() != name);
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -755,6 +755,26 @@ pub trait GitRepository: Send + Sync {
         async move { future.await.ok()?.pop()? }.boxed()
     }
 
+    /// Returns the contents of the base, ours and theirs stages (1–3) of a conflicted index entry.
+    ///
+    /// A stage is `None` when the path does not exist on that side of the merge.
+    fn load_conflict_stages(&self, path: RepoPath) -> BoxFuture<'_, Result<[Option<Vec<u8>>; 3]>> {
+        let future = self.load_revisions(
+            (1..=3)
+                .map(|stage| format!(":{stage}:{}", path.as_unix_str()))
+                .collect(),
+        );
+        async move {
+            let mut stages = future.await?.into_iter();
+            Ok([
+                stages.next().flatten(),
+                stages.next().flatten(),
+                stages.next().flatten(),
+            ])
+        }
+        .boxed()
+    }
+
     /// Returns the contents of an entry in the repository's HEAD, or None if HEAD does not exist or has no entry for the given path.
     ///
     /// Also returns `None` for symlinks.
@@ -0,0 +1,744 @@
+use std::hash::Hash;
+use std::ops::Range;
+use std::pin::pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+use extension::Extension;
+use futures::future::{self, Either};
+use git::{
+    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, ParsedGitRemote,
+    PullRequest, RemoteUrl,
+};
+use gpui::BackgroundExecutor;
+use parking_lot::Mutex;
+use url::Url;
+use util::ResultExt as _;
+
+/// How long to wait for the extension before giving up.
+const EXTENSION_CALL_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// How many results of each kind of call are kept before the cache is cleared.
+const MAX_CACHED_RESULTS: usize = 1024;
+
+/// A [`GitHostingProvider`] implemented by an extension.
+///
+/// [`GitHostingProvider`] methods are synchronous, and are often called on the main thread, e.g.
+/// when rendering blame or a commit. Calls into the extension made there never block: they start
+/// in the background and return nothing until their result is cached, which is then used by the
+/// next call with the same arguments.
+pub(crate) struct ExtensionGitHostingProvider {
+    extension: Arc<dyn Extension>,
+    name: Arc<str>,
+    base_url: Url,
+    executor: BackgroundExecutor,
+    parsed_remotes: Arc<Mutex<CallCache<String, Option<extension::ParsedGitRemote>>>>,
+    commit_permalinks: Arc<Mutex<CallCache<(RemoteKey, String), String>>>,
+    permalinks: Arc<Mutex<CallCache<(RemoteKey, String, String, Option<Range<u32>>), String>>>,
+    create_pull_request_urls: Arc<Mutex<CallCache<(RemoteKey, String), Option<String>>>>,
+    pull_requests: Arc<Mutex<CallCache<(RemoteKey, String), Option<extension::PullRequest>>>>,
+}
+
+/// The owner and name of a repository.
+type RemoteKey = (String, String);
+
+/// The results of calls into the extension, keyed by their arguments.
+struct CallCache<K, V> {
+    results: HashMap<K, V>,
+    /// Calls started in the background that haven't finished yet.
+    pending: HashSet<K>,
+}
+
+impl<K, V> Default for CallCache<K, V> {
+    fn default() -> Self {
+        Self {
+            results: HashMap::default(),
+            pending: HashSet::default(),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V> CallCache<K, V> {
+    fn insert(&mut self, key: K, value: V) {
+        if self.results.len() >= MAX_CACHED_RESULTS {
+            self.results.clear();
+        }
+        self.results.insert(key, value);
+    }
+}
+
+impl ExtensionGitHostingProvider {
+    pub(crate) fn new(
+        extension: Arc<dyn Extension>,
+        name: Arc<str>,
+        base_url: &str,
+        executor: BackgroundExecutor,
+    ) -> Result<Self> {
+        let base_url = Url::parse(base_url)
+            .with_context(|| format!("invalid base URL for git hosting provider {name}"))?;
+
+        Ok(Self {
+            extension,
+            name,
+            base_url,
+            executor,
+            parsed_remotes: Arc::default(),
+            commit_permalinks: Arc::default(),
+            permalinks: Arc::default(),
+            create_pull_request_urls: Arc::default(),
+            pull_requests: Arc::default(),
+        })
+    }
+
+    /// Returns the cached result of a call into the extension, or makes the call.
+    ///
+    /// On the main thread, the call is made in the background and `None` is returned until it
+    /// finishes. Elsewhere, the call is waited on. Failed calls are not cached, so that they are
+    /// retried.
+    fn call<K, V>(
+        &self,
+        method: &'static str,
+        cache: &Arc<Mutex<CallCache<K, V>>>,
+        key: K,
+        call: impl Future<Output = Result<V>> + Send + 'static,
+    ) -> Option<V>
+    where
+        K: Clone + Eq + Hash + Send + 'static,
+        V: Clone + Send + 'static,
+    {
+        if let Some(result) = cache.lock().results.get(&key) {
+            return Some(result.clone());
+        }
+
+        let call = with_timeout(
+            self.name.clone(),
+            method,
+            call,
+            self.executor.timer(EXTENSION_CALL_TIMEOUT),
+        );
+        if self.executor.is_main_thread() {
+            if cache.lock().pending.insert(key.clone()) {
+                let cache = cache.clone();
+                self.executor
+                    .spawn(async move {
+                        let result = call.await;
+                        let mut cache = cache.lock();
+                        cache.pending.remove(&key);
+                        if let Some(result) = result {
+                            cache.insert(key, result);
+                        }
+                    })
+                    .detach();
+            }
+            return None;
+        }
+
+        let result = futures::executor::block_on(call)?;
+        cache.lock().insert(key, result.clone());
+        Some(result)
+    }
+
+    fn url(&self, url: String) -> Option<Url> {
+        Url::parse(&url)
+            .with_context(|| format!("invalid URL from git hosting provider {}", self.name))
+            .log_err()
+    }
+}
+
+async fn with_timeout<R>(
+    name: Arc<str>,
+    method: &'static str,
+    call: impl Future<Output = Result<R>>,
+    timeout: impl Future<Output = ()>,
+) -> Option<R> {
+    match future::select(pin!(call), pin!(timeout)).await {
+        Either::Left((result, _)) => result
+            .with_context(|| format!("calling `{method}` of git hosting provider {name}"))
+            .log_err(),
+        Either::Right(_) => {
+            log::error!("timed out calling `{method}` of git hosting provider {name}");
+            None
+        }
+    }
+}
+
+fn remote_key(remote: &ParsedGitRemote) -> RemoteKey {
+    (remote.owner.to_string(), remote.repo.to_string())
+}
+
+fn to_extension_remote(remote: &ParsedGitRemote) -> extension::ParsedGitRemote {
+    extension::ParsedGitRemote {
+        owner: remote.owner.to_string(),
+        repo: remote.repo.to_string(),
+    }
+}
+
+impl GitHostingProvider for ExtensionGitHostingProvider {
+    fn name(&self) -> String {
+        self.name.to_string()
+    }
+
+    fn base_url(&self) -> Url {
+        self.base_url.clone()
+    }
+
+    fn supports_avatars(&self) -> bool {
+        false
+    }
+
+    fn format_line_number(&self, line: u32) -> String {
+        format!("L{line}")
+    }
+
+    fn format_line_numbers(&self, start_line: u32, end_line: u32) -> String {
+        format!("L{start_line}-L{end_line}")
+    }
+
+    fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
+        // Avoid calling into the extension for remotes that are hosted elsewhere.
+        let remote_url = RemoteUrl::from_str(url).ok()?;
+        if remote_url.host_str()? != self.base_url.host_str()? {
+            return None;
+        }
+
+        let extension = self.extension.clone();
+        let name = self.name.clone();
+        let url = url.to_string();
+        let remote = self
+            .call(
+                "parse_remote_url",
+                &self.parsed_remotes,
+                url.clone(),
+                async move {
+                    extension
+                        .git_hosting_provider_parse_remote_url(name, url)
+                        .await
+                },
+            )
+            .flatten()?;
+
+        Some(ParsedGitRemote {
+            owner: remote.owner.into(),
+            repo: remote.repo.into(),
+        })
+    }
+
+    fn build_commit_permalink(
+        &self,
+        remote: &ParsedGitRemote,
+        params: BuildCommitPermalinkParams,
+    ) -> Url {
+        let extension = self.extension.clone();
+        let name = self.name.clone();
+        let extension_remote = to_extension_remote(remote);
+        let sha = params.sha.to_string();
+        self.call(
+            "build_commit_permalink",
+            &self.commit_permalinks,
+            (remote_key(remote), sha.clone()),
+            async move {
+                extension
+                    .git_hosting_provider_build_commit_permalink(name, extension_remote, sha)
+                    .await
+            },
+        )
+        .and_then(|url| self.url(url))
+        .unwrap_or_else(|| self.base_url())
+    }
+
+    fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
+        let extension = self.extension.clone();
+        let name = self.name.clone();
+        let extension_remote = to_extension_remote(&remote);
+        let params = extension::BuildPermalinkParams {
+            sha: params.sha.to_string(),
+            path: params.path,
+            selection: params.selection,
+        };
+        let key = (
+            remote_key(&remote),
+            params.sha.clone(),
+            params.path.clone(),
+            params.selection.clone(),
+        );
+        self.call("build_permalink", &self.permalinks, key, async move {
+            extension
+                .git_hosting_provider_build_permalink(name, extension_remote, params)
+                .await
+        })
+        .and_then(|url| self.url(url))
+        .unwrap_or_else(|| self.base_url())
+    }
+
+    fn build_create_pull_request_url(
+        &self,
+        remote: &ParsedGitRemote,
+        source_branch: &str,
+    ) -> Option<Url> {
+        let extension = self.extension.clone();
+        let name = self.name.clone();
+        let extension_remote = to_extension_remote(remote);
+        let source_branch = source_branch.to_string();
+        let url = self
+            .call(
+                "build_create_pull_request_url",
+                &self.create_pull_request_urls,
+                (remote_key(remote), source_branch.clone()),
+                async move {
+                    extension
+                        .git_hosting_provider_build_create_pull_request_url(
+                            name,
+                            extension_remote,
+                            source_branch,
+                        )
+                        .await
+                },
+            )
+            .flatten()?;
+
+        self.url(url)
+    }
+
+    fn extract_pull_request(&self, remote: &ParsedGitRemote, message: &str) -> Option<PullRequest> {
+        let extension = self.extension.clone();
+        let name = self.name.clone();
+        let extension_remote = to_extension_remote(remote);
+        let message = message.to_string();
+        let pull_request = self
+            .call(
+                "extract_pull_request",
+                &self.pull_requests,
+                (remote_key(remote), message.clone()),
+                async move {
+                    extension
+                        .git_hosting_provider_extract_pull_request(name, extension_remote, message)
+                        .await
+                },
+            )
+            .flatten()?;
+
+        Some(PullRequest {
+            number: pull_request.number,
+            url: self.url(pull_request.url)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_trait::async_trait;
+    use extension::{
+        BuildTaskTemplate, CodeLabel, Command, Completion, ContextServerConfiguration,
+        DebugAdapterBinary, DebugRequest, DebugScenario, DebugTaskDefinition, ExtensionManifest,
+        KeyValueStoreDelegate, ProjectDelegate, SlashCommand, SlashCommandArgumentCompletion,
+        SlashCommandOutput, StartDebuggingRequestArgumentsRequest, Symbol, WorktreeDelegate,
+    };
+    use gpui::TestAppContext;
+    use language::LanguageName;
+    use lsp::LanguageServerName;
+    use task::{SpawnInTerminal, ZedDebugConfig};
+
+    use super::*;
+
+    const REMOTE_URL: &str = "https://git.example.com/zed-industries/zed.git";
+
+    /// An extension whose git hosting provider answers every call, or never does.
+    struct FakeExtension {
+        responds: bool,
+        calls: AtomicUsize,
+    }
+
+    impl FakeExtension {
+        async fn respond<R>(&self, result: R) -> Result<R> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if !self.responds {
+                future::pending::<()>().await;
+            }
+            Ok(result)
+        }
+    }
+
+    #[async_trait]
+    impl Extension for FakeExtension {
+        fn manifest(&self) -> Arc<ExtensionManifest> {
+            unimplemented!()
+        }
+
+        fn work_dir(&self) -> Arc<Path> {
+            unimplemented!()
+        }
+
+        async fn language_server_command(
+            &self,
+            _language_server_id: LanguageServerName,
+            _language_name: LanguageName,
+            _worktree: Arc<dyn WorktreeDelegate>,
+        ) -> Result<Command> {
+            unimplemented!()
+        }
+
+        async fn language_server_initialization_options(
+            &self,
+            _language_server_id: LanguageServerName,
+            _language_name: LanguageName,
+            _worktree: Arc<dyn WorktreeDelegate>,
+        ) -> Result<Option<String>> {
+            unimplemented!()
+        }
+
+        async fn language_server_workspace_configuration(
+            &self,
+            _language_server_id: LanguageServerName,
+            _worktree: Arc<dyn WorktreeDelegate>,
+        ) -> Result<Option<String>> {
+            unimplemented!()
+        }
+
+        async fn language_server_initialization_options_schema(
+            &self,
+            _language_server_id: LanguageServerName,
+            _worktree: Arc<dyn WorktreeDelegate>,
+        ) -> Result<Option<String>> {
+            unimplemented!()
+        }
+
+        async fn language_server_workspace_configuration_schema(
+            &self,
+            _language_server_id: LanguageServerName,
+            _worktree: Arc<dyn WorktreeDelegate>,
+        ) -> Result<Option<String>> {
+            unimplemented!()
+        }
+
+        async fn language_server_additional_initialization_options(
+            &self,
+            _language_server_id: LanguageServerName,
+            _target_language_server_id: LanguageServerName,
+            _worktree: Arc<dyn WorktreeDelegate>,
+        ) -> Result<Option<String>> {
+            unimplemented!()
+        }
+
+        async fn language_server_additional_workspace_configuration(
+            &self,
+            _language_server_id: LanguageServerName,
+            _target_language_server_id: LanguageServerName,
+            _worktree: Arc<dyn WorktreeDelegate>,
+        ) -> Result<Option<String>> {
+            unimplemented!()
+        }
+
+        async fn labels_for_completions(
+            &self,
+            _language_server_id: LanguageServerName,
+            _completions: Vec<Completion>,
+        ) -> Result<Vec<Option<CodeLabel>>> {
+            unimplemented!()
+        }
+
+        async fn labels_for_symbols(
+            &self,
+            _language_server_id: LanguageServerName,
+            _symbols: Vec<Symbol>,
+        ) -> Result<Vec<Option<CodeLabel>>> {
+            unimplemented!()
+        }
+
+        async fn complete_slash_command_argument(
+            &self,
+            _command: SlashCommand,
+            _arguments: Vec<String>,
+        ) -> Result<Vec<SlashCommandArgumentCompletion>> {
+            unimplemented!()
+        }
+
+        async fn run_slash_command(
+            &self,
+            _command: SlashCommand,
+            _arguments: Vec<String>,
+            _worktree: Option<Arc<dyn WorktreeDelegate>>,
+        ) -> Result<SlashCommandOutput> {
+            unimplemented!()
+        }
+
+        async fn context_server_command(
+            &self,
+            _context_server_id: Arc<str>,
+            _project: Arc<dyn ProjectDelegate>,
+        ) -> Result<Command> {
+            unimplemented!()
+        }
+
+        async fn context_server_configuration(
+            &self,
+            _context_server_id: Arc<str>,
+            _project: Arc<dyn ProjectDelegate>,
+        ) -> Result<Option<ContextServerConfiguration>> {
+            unimplemented!()
+        }
+
+        async fn suggest_docs_packages(&self, _provider: Arc<str>) -> Result<Vec<String>> {
+            unimplemented!()
+        }
+
+        async fn index_docs(
+            &self,
+            _provider: Arc<str>,
+            _package_name: Arc<str>,
+            _kv_store: Arc<dyn KeyValueStoreDelegate>,
+        ) -> Result<()> {
+            unimplemented!()
+        }
+
+        async fn get_dap_binary(
+            &self,
+            _dap_name: Arc<str>,
+            _config: DebugTaskDefinition,
+            _user_installed_path: Option<PathBuf>,
+            _worktree: Arc<dyn WorktreeDelegate>,
+        ) -> Result<DebugAdapterBinary> {
+            unimplemented!()
+        }
+
+        async fn dap_request_kind(
+            &self,
+            _dap_name: Arc<str>,
+            _config: serde_json::Value,
+        ) -> Result<StartDebuggingRequestArgumentsRequest> {
+            unimplemented!()
+        }
+
+        async fn dap_config_to_scenario(&self, _config: ZedDebugConfig) -> Result<DebugScenario> {
+            unimplemented!()
+        }
+
+        async fn dap_locator_create_scenario(
+            &self,
+            _locator_name: String,
+            _build_config_template: BuildTaskTemplate,
+            _resolved_label: String,
+            _debug_adapter_name: String,
+        ) -> Result<Option<DebugScenario>> {
+            unimplemented!()
+        }
+
+        async fn run_dap_locator(
+            &self,
+            _locator_name: String,
+            _config: SpawnInTerminal,
+        ) -> Result<DebugRequest> {
+            unimplemented!()
+        }
+
+        async fn git_hosting_provider_parse_remote_url(
+            &self,
+            _provider_name: Arc<str>,
+            url: String,
+        ) -> Result<Option<extension::ParsedGitRemote>> {
+            let remote = url
+                .strip_prefix("https://git.example.com/")
+                .and_then(|path| path.strip_suffix(".git"))
+                .and_then(|path| path.split_once('/'))
+                .map(|(owner, repo)| extension::ParsedGitRemote {
+                    owner: owner.to_string(),
+                    repo: repo.to_string(),
+                });
+            self.respond(remote).await
+        }
+
+        async fn git_hosting_provider_build_commit_permalink(
+            &self,
+            _provider_name: Arc<str>,
+            remote: extension::ParsedGitRemote,
+            sha: String,
+        ) -> Result<String> {
+            self.respond(format!(
+                "https://git.example.com/{}/{}/commit/{sha}",
+                remote.owner, remote.repo
+            ))
+            .await
+        }
+
+        async fn git_hosting_provider_build_permalink(
+            &self,
+            _provider_name: Arc<str>,
+            remote: extension::ParsedGitRemote,
+            params: extension::BuildPermalinkParams,
+        ) -> Result<String> {
+            self.respond(format!(
+                "https://git.example.com/{}/{}/blob/{}/{}",
+                remote.owner, remote.repo, params.sha, params.path
+            ))
+            .await
+        }
+
+        async fn git_hosting_provider_build_create_pull_request_url(
+            &self,
+            _provider_name: Arc<str>,
+            remote: extension::ParsedGitRemote,
+            source_branch: String,
+        ) -> Result<Option<String>> {
+            self.respond(Some(format!(
+                "https://git.example.com/{}/{}/compare/{source_branch}",
+                remote.owner, remote.repo
+            )))
+            .await
+        }
+
+        async fn git_hosting_provider_extract_pull_request(
+            &self,
+            _provider_name: Arc<str>,
+            remote: extension::ParsedGitRemote,
+            message: String,
+        ) -> Result<Option<extension::PullRequest>> {
+            let number = message
+                .rsplit_once("(#")
+                .and_then(|(_, rest)| rest.strip_suffix(')'))
+                .and_then(|number| number.parse().ok());
+            self.respond(number.map(|number| extension::PullRequest {
+                number,
+                url: format!(
+                    "https://git.example.com/{}/{}/pull/{number}",
+                    remote.owner, remote.repo
+                ),
+            }))
+            .await
+        }
+    }
+
+    fn provider(
+        responds: bool,
+        cx: &TestAppContext,
+    ) -> (ExtensionGitHostingProvider, Arc<FakeExtension>) {
+        let extension = Arc::new(FakeExtension {
+            responds,
+            calls: AtomicUsize::new(0),
+        });
+        let provider = ExtensionGitHostingProvider::new(
+            extension.clone(),
+            "Example".into(),
+            "https://git.example.com",
+            cx.executor(),
+        )
+        .unwrap();
+        (provider, extension)
+    }
+
+    fn remote() -> ParsedGitRemote {
+        ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        }
+    }
+
+    #[gpui::test]
+    async fn test_main_thread_calls_are_made_in_the_background(cx: &mut TestAppContext) {
+        let (provider, extension) = provider(true, cx);
+
+        assert_eq!(provider.parse_remote_url(REMOTE_URL), None);
+        assert_eq!(
+            provider.build_commit_permalink(&remote(), BuildCommitPermalinkParams { sha: "abc" }),
+            provider.base_url()
+        );
+        assert!(
+            provider
+                .extract_pull_request(&remote(), "Fix the thing (#42)")
+                .is_none()
+        );
+        cx.run_until_parked();
+
+        assert_eq!(provider.parse_remote_url(REMOTE_URL), Some(remote()));
+        assert_eq!(
+            provider
+                .build_commit_permalink(&remote(), BuildCommitPermalinkParams { sha: "abc" })
+                .as_str(),
+            "https://git.example.com/zed-industries/zed/commit/abc"
+        );
+        let pull_request = provider
+            .extract_pull_request(&remote(), "Fix the thing (#42)")
+            .unwrap();
+        assert_eq!(pull_request.number, 42);
+        assert_eq!(
+            pull_request.url.as_str(),
+            "https://git.example.com/zed-industries/zed/pull/42"
+        );
+        assert_eq!(extension.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[gpui::test]
+    async fn test_background_calls_wait_for_the_extension(cx: &mut TestAppContext) {
+        let (provider, extension) = provider(true, cx);
+        let provider = Arc::new(provider);
+
+        let (permalink, pull_request_url) = cx
+            .executor()
+            .spawn({
+                let provider = provider.clone();
+                async move {
+                    let permalink = provider.build_permalink(
+                        remote(),
+                        BuildPermalinkParams {
+                            sha: "abc",
+                            path: "src/main.rs".into(),
+                            selection: None,
+                        },
+                    );
+                    let pull_request_url =
+                        provider.build_create_pull_request_url(&remote(), "feature");
+                    (permalink, pull_request_url)
+                }
+            })
+            .await;
+
+        assert_eq!(
+            permalink.as_str(),
+            "https://git.example.com/zed-industries/zed/blob/abc/src/main.rs"
+        );
+        assert_eq!(
+            pull_request_url.unwrap().as_str(),
+            "https://git.example.com/zed-industries/zed/compare/feature"
+        );
+        // Both results were cached, so the main thread gets them right away.
+        assert!(
+            provider
+                .build_create_pull_request_url(&remote(), "feature")
+                .is_some()
+        );
+        assert_eq!(extension.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[gpui::test]
+    async fn test_calls_that_time_out_are_retried(cx: &mut TestAppContext) {
+        let (provider, extension) = provider(false, cx);
+
+        assert_eq!(provider.parse_remote_url(REMOTE_URL), None);
+        // A call in progress isn't started again.
+        assert_eq!(provider.parse_remote_url(REMOTE_URL), None);
+        cx.run_until_parked();
+        assert_eq!(extension.calls.load(Ordering::SeqCst), 1);
+
+        cx.executor().advance_clock(EXTENSION_CALL_TIMEOUT);
+        cx.run_until_parked();
+        assert_eq!(provider.parse_remote_url(REMOTE_URL), None);
+        cx.run_until_parked();
+        assert_eq!(extension.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[gpui::test]
+    async fn test_remotes_hosted_elsewhere_are_not_sent_to_the_extension(cx: &mut TestAppContext) {
+        let (provider, extension) = provider(true, cx);
+
+        assert_eq!(
+            provider.parse_remote_url("https://github.com/zed-industries/zed.git"),
+            None
+        );
+        cx.run_until_parked();
+        assert_eq!(extension.calls.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,56 @@
+mod extension_git_hosting_provider;
+
+use std::sync::Arc;
+
+use extension::{ExtensionGitHostingProviderProxy, ExtensionHostProxy};
+use git::GitHostingProviderRegistry;
+use gpui::{App, BackgroundExecutor};
+use util::ResultExt;
+
+use crate::extension_git_hosting_provider::ExtensionGitHostingProvider;
+
+pub fn init(extension_host_proxy: Arc<ExtensionHostProxy>, cx: &mut App) {
+    let git_hosting_provider_registry_proxy = GitHostingProviderRegistryProxy::new(cx);
+    extension_host_proxy.register_git_hosting_provider_proxy(git_hosting_provider_registry_proxy);
+}
+
+#[derive(Clone)]
+struct GitHostingProviderRegistryProxy {
+    git_hosting_provider_registry: Arc<GitHostingProviderRegistry>,
+    executor: BackgroundExecutor,
+}
+
+impl GitHostingProviderRegistryProxy {
+    fn new(cx: &mut App) -> Self {
+        Self {
+            git_hosting_provider_registry: GitHostingProviderRegistry::default_global(cx),
+            executor: cx.background_executor().clone(),
+        }
+    }
+}
+
+impl ExtensionGitHostingProviderProxy for GitHostingProviderRegistryProxy {
+    fn register_git_hosting_provider(
+        &self,
+        extension: Arc<dyn extension::Extension>,
+        provider_name: Arc<str>,
+        base_url: &str,
+    ) {
+        if let Some(provider) = ExtensionGitHostingProvider::new(
+            extension,
+            provider_name,
+            base_url,
+            self.executor.clone(),
+        )
+        .log_err()
+        {
+            self.git_hosting_provider_registry
+                .register_hosting_provider(Arc::new(provider));
+        }
+    }
+
+    fn unregister_git_hosting_provider(&self, provider_name: Arc<str>) {
+        self.git_hosting_provider_registry
+            .unregister_hosting_provider(&provider_name);
+    }
+}
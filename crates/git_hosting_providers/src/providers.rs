@@ -8,6 +8,7 @@ mod github;
 mod gitlab;
 mod sourcehut;
 mod tangled;
+mod url_template;
 
 pub use azure::*;
 pub use bitbucket::*;
@@ -19,3 +20,4 @@ pub use github::*;
 pub use gitlab::*;
 pub use sourcehut::*;
 pub use tangled::*;
+pub use url_template::*;
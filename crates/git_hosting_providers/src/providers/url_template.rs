@@ -0,0 +1,325 @@
+use std::str::FromStr;
+
+use anyhow::{Context as _, Result};
+use regex::Regex;
+use settings::GitHostingProviderUrlTemplates;
+use url::Url;
+use urlencoding::encode;
+use util::ResultExt as _;
+
+use git::{
+    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, ParsedGitRemote,
+    PullRequest, RemoteUrl,
+};
+
+const DEFAULT_LINE_TEMPLATE: &str = "L{line}";
+const DEFAULT_LINE_RANGE_TEMPLATE: &str = "L{start_line}-L{end_line}";
+const DEFAULT_PULL_REQUEST_PATTERN: &str = r"\(#(\d+)\)$";
+
+/// A Git hosting provider whose URLs are described by templates in the settings.
+pub struct UrlTemplate {
+    name: String,
+    base_url: Url,
+    templates: GitHostingProviderUrlTemplates,
+    pull_request_regex: Regex,
+}
+
+impl UrlTemplate {
+    pub fn new(
+        name: &str,
+        base_url: Url,
+        templates: GitHostingProviderUrlTemplates,
+    ) -> Result<Self> {
+        let pull_request_pattern = templates
+            .pull_request_pattern
+            .as_deref()
+            .unwrap_or(DEFAULT_PULL_REQUEST_PATTERN);
+        let pull_request_regex = Regex::new(pull_request_pattern)
+            .with_context(|| format!("invalid pull request pattern for {name}"))?;
+
+        Ok(Self {
+            name: name.to_string(),
+            base_url,
+            templates,
+            pull_request_regex,
+        })
+    }
+
+    fn expand(
+        &self,
+        template: &str,
+        remote: &ParsedGitRemote,
+        variables: &[(&str, &str)],
+    ) -> String {
+        let project = if remote.owner.is_empty() {
+            remote.repo.to_string()
+        } else {
+            format!("{}/{}", remote.owner, remote.repo)
+        };
+        let base_url = self.base_url.as_str().trim_end_matches('/');
+
+        [
+            ("base_url", base_url),
+            ("owner", remote.owner.as_ref()),
+            ("repo", remote.repo.as_ref()),
+            ("project", project.as_str()),
+        ]
+        .iter()
+        .chain(variables)
+        .fold(template.to_string(), |url, (name, value)| {
+            url.replace(&format!("{{{name}}}"), value)
+        })
+    }
+
+    fn expand_url(
+        &self,
+        template: &str,
+        remote: &ParsedGitRemote,
+        variables: &[(&str, &str)],
+    ) -> Option<Url> {
+        let url = self.expand(template, remote, variables);
+        Url::parse(&url)
+            .with_context(|| format!("invalid URL {url:?} for {}", self.name))
+            .log_err()
+    }
+}
+
+impl GitHostingProvider for UrlTemplate {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn base_url(&self) -> Url {
+        self.base_url.clone()
+    }
+
+    fn supports_avatars(&self) -> bool {
+        false
+    }
+
+    fn format_line_number(&self, line: u32) -> String {
+        let template = self
+            .templates
+            .line
+            .as_deref()
+            .unwrap_or(DEFAULT_LINE_TEMPLATE);
+        template.replace("{line}", &line.to_string())
+    }
+
+    fn format_line_numbers(&self, start_line: u32, end_line: u32) -> String {
+        let template = self
+            .templates
+            .line_range
+            .as_deref()
+            .unwrap_or(DEFAULT_LINE_RANGE_TEMPLATE);
+        template
+            .replace("{start_line}", &start_line.to_string())
+            .replace("{end_line}", &end_line.to_string())
+    }
+
+    fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
+        let url = RemoteUrl::from_str(url).ok()?;
+
+        let host = url.host_str()?;
+        if host != self.base_url.host_str()? {
+            return None;
+        }
+
+        let mut path = url.path().trim_matches('/');
+        // HTTP remotes of instances that are served from a subpath include that subpath.
+        let base_path = self.base_url.path().trim_matches('/');
+        if !base_path.is_empty() && url.scheme().starts_with("http") {
+            path = path
+                .strip_prefix(base_path)
+                .map_or(path, |path| path.trim_start_matches('/'));
+        }
+
+        let (owner, repo) = path.rsplit_once('/').unwrap_or(("", path));
+        let repo = repo.trim_end_matches(".git");
+        if repo.is_empty() {
+            return None;
+        }
+
+        Some(ParsedGitRemote {
+            owner: owner.into(),
+            repo: repo.into(),
+        })
+    }
+
+    fn build_commit_permalink(
+        &self,
+        remote: &ParsedGitRemote,
+        params: BuildCommitPermalinkParams,
+    ) -> Url {
+        let BuildCommitPermalinkParams { sha } = params;
+
+        self.expand_url(&self.templates.commit, remote, &[("sha", sha)])
+            .unwrap_or_else(|| self.base_url())
+    }
+
+    fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
+        let BuildPermalinkParams {
+            sha,
+            path,
+            selection,
+        } = params;
+
+        let Some(mut permalink) = self.expand_url(
+            &self.templates.file,
+            &remote,
+            &[("sha", sha), ("path", &path)],
+        ) else {
+            return self.base_url();
+        };
+        permalink.set_fragment(
+            selection
+                .map(|selection| self.line_fragment(&selection))
+                .as_deref(),
+        );
+        permalink
+    }
+
+    fn build_create_pull_request_url(
+        &self,
+        remote: &ParsedGitRemote,
+        source_branch: &str,
+    ) -> Option<Url> {
+        let template = self.templates.create_pull_request.as_deref()?;
+        let encoded_source = encode(source_branch);
+
+        self.expand_url(template, remote, &[("branch", &encoded_source)])
+    }
+
+    fn extract_pull_request(&self, remote: &ParsedGitRemote, message: &str) -> Option<PullRequest> {
+        let template = self.templates.pull_request.as_deref()?;
+        let number = message.lines().find_map(|line| {
+            let capture = self.pull_request_regex.captures(line)?;
+            capture.get(1)?.as_str().parse::<u32>().ok()
+        })?;
+
+        let url = self.expand_url(template, remote, &[("number", &number.to_string())])?;
+
+        Some(PullRequest { number, url })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use git::repository::repo_path;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn gerrit() -> UrlTemplate {
+        UrlTemplate::new(
+            "Corp Gerrit",
+            Url::parse("https://gerrit.example.corp").unwrap(),
+            GitHostingProviderUrlTemplates {
+                commit: "{base_url}/plugins/gitiles/{project}/+/{sha}".into(),
+                file: "{base_url}/plugins/gitiles/{project}/+/{sha}/{path}".into(),
+                line: Some("{line}".into()),
+                line_range: Some("{start_line}".into()),
+                pull_request: Some("{base_url}/c/{project}/+/{number}".into()),
+                pull_request_pattern: Some(r"^Reviewed-on: .*/\+/(\d+)$".into()),
+                create_pull_request: None,
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_remote_url_given_nested_project() {
+        let parsed_remote = gerrit()
+            .parse_remote_url("ssh://review@gerrit.example.corp:29418/platform/tools/zed.git")
+            .unwrap();
+
+        assert_eq!(
+            parsed_remote,
+            ParsedGitRemote {
+                owner: "platform/tools".into(),
+                repo: "zed".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_remote_url_given_subpath_instance() {
+        let provider = UrlTemplate::new(
+            "Corp Git",
+            Url::parse("https://code.example.corp/git/").unwrap(),
+            gerrit().templates,
+        )
+        .unwrap();
+
+        let parsed_remote = provider
+            .parse_remote_url("https://code.example.corp/git/zed")
+            .unwrap();
+
+        assert_eq!(
+            parsed_remote,
+            ParsedGitRemote {
+                owner: "".into(),
+                repo: "zed".into(),
+            }
+        );
+        assert_eq!(
+            provider.parse_remote_url("https://github.com/zed-industries/zed"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_build_permalinks() {
+        let provider = gerrit();
+        let remote = ParsedGitRemote {
+            owner: "platform".into(),
+            repo: "zed".into(),
+        };
+
+        let commit_permalink = provider.build_commit_permalink(
+            &remote,
+            BuildCommitPermalinkParams {
+                sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
+            },
+        );
+        assert_eq!(
+            commit_permalink.to_string(),
+            "https://gerrit.example.corp/plugins/gitiles/platform/zed/+/e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7"
+        );
+
+        let permalink = provider.build_permalink(
+            remote,
+            BuildPermalinkParams::new(
+                "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
+                &repo_path("crates/editor/src/git/permalink.rs"),
+                Some(6..9),
+            ),
+        );
+        assert_eq!(
+            permalink.to_string(),
+            "https://gerrit.example.corp/plugins/gitiles/platform/zed/+/e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7/crates/editor/src/git/permalink.rs#7"
+        );
+    }
+
+    #[test]
+    fn test_extract_pull_request() {
+        let provider = gerrit();
+        let remote = ParsedGitRemote {
+            owner: "platform".into(),
+            repo: "zed".into(),
+        };
+
+        let message = "Fix the build\n\nChange-Id: I1234\nReviewed-on: https://gerrit.example.corp/c/platform/zed/+/4321\n";
+        let pull_request = provider.extract_pull_request(&remote, message).unwrap();
+        assert_eq!(pull_request.number, 4321);
+        assert_eq!(
+            pull_request.url.as_str(),
+            "https://gerrit.example.corp/c/platform/zed/+/4321"
+        );
+
+        assert_eq!(
+            provider.extract_pull_request(&remote, "Fix the build (#123)"),
+            None
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use anyhow::Context as _;
 use git::GitHostingProviderRegistry;
 use gpui::App;
 use settings::{
@@ -8,7 +9,7 @@ use settings::{
 use url::Url;
 use util::ResultExt as _;
 
-use crate::{Bitbucket, Forgejo, Gitea, Github, Gitlab, SourceHut, Tangled};
+use crate::{Bitbucket, Forgejo, Gitea, Github, Gitlab, SourceHut, Tangled, UrlTemplate};
 
 pub(crate) fn init(cx: &mut App) {
     init_git_hosting_provider_settings(cx);
@@ -40,6 +41,16 @@ fn update_git_hosting_providers_from_settings(cx: &mut App) {
         .filter_map(|provider| {
             let url = Url::parse(&provider.base_url).log_err()?;
 
+            if provider.provider == GitHostingProviderKind::UrlTemplate {
+                let templates = provider
+                    .url_templates
+                    .with_context(|| format!("{} has no `url_templates`", provider.name))
+                    .log_err()?;
+                return Some(
+                    Arc::new(UrlTemplate::new(&provider.name, url, templates).log_err()?) as _,
+                );
+            }
+
             Some(match provider.provider {
                 GitHostingProviderKind::Bitbucket => {
                     Arc::new(Bitbucket::new(&provider.name, url)) as _
@@ -52,6 +63,7 @@ fn update_git_hosting_providers_from_settings(cx: &mut App) {
                     Arc::new(SourceHut::new(&provider.name, url)) as _
                 }
                 GitHostingProviderKind::Tangled => Arc::new(Tangled::new(&provider.name, url)) as _,
+                GitHostingProviderKind::UrlTemplate => return None,
             })
         });
 
@@ -1,3 +1,4 @@
+use agent_settings::AgentSettings;
 use anyhow::{Context as _, Result};
 use buffer_diff::BufferDiff;
 use collections::HashMap;
@@ -13,8 +14,8 @@ use git::{
     parse_git_remote_url,
 };
 use gpui::{
-    AnyElement, App, AppContext as _, AsyncWindowContext, ClipboardItem, Context, Entity,
-    EventEmitter, FocusHandle, Focusable, InteractiveElement, IntoElement, ParentElement,
+    Action as _, AnyElement, App, AppContext as _, AsyncWindowContext, ClipboardItem, Context,
+    Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement, IntoElement, ParentElement,
     PromptLevel, Render, ScrollHandle, StatefulInteractiveElement as _, Styled, Task, WeakEntity,
     Window, actions,
 };
@@ -47,6 +48,7 @@ use workspace::{
 
 use crate::commit_tooltip::CommitAvatar;
 use crate::git_panel::GitPanel;
+use crate::project_diff::ReviewDiff;
 
 actions!(
     git,
@@ -567,6 +569,44 @@ impl CommitView {
         self.open_file_at_head(&file, window, cx);
     }
 
+    fn review_diff(&mut self, _: &ReviewDiff, window: &mut Window, cx: &mut Context<Self>) {
+        if self.stash.is_some() {
+            return;
+        }
+
+        let sha = self.commit.sha.to_string();
+        let summary = self.commit.message.lines().next().unwrap_or_default();
+        let subject = format!("commit `{}` (\"{summary}\")", &sha[..sha.len().min(7)]);
+        let diff_receiver = self
+            .repository
+            .update(cx, |repo, _| repo.load_commit_diff(sha));
+
+        let workspace = self.workspace.clone();
+        window
+            .spawn(cx, {
+                let workspace = workspace.clone();
+                async move |cx| {
+                    let commit_diff = diff_receiver.await??;
+
+                    if let Some(workspace) = workspace.upgrade() {
+                        workspace.update_in(cx, |_workspace, window, cx| {
+                            window.dispatch_action(
+                                zed_actions::agent::ReviewChanges {
+                                    diff_text: commit_diff_text(&commit_diff).into(),
+                                    subject: subject.into(),
+                                }
+                                .boxed_clone(),
+                                cx,
+                            );
+                        })?;
+                    }
+
+                    anyhow::Ok(())
+                }
+            })
+            .detach_and_notify_err(workspace, window, cx);
+    }
+
     fn render_header(&self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let commit = &self.commit;
         let author_name = commit.author_name.clone();
@@ -1253,6 +1293,7 @@ impl Render for CommitView {
         v_flex()
             .key_context(if is_stash { "StashDiff" } else { "CommitDiff" })
             .on_action(cx.listener(Self::open_file_at_head_action))
+            .on_action(cx.listener(Self::review_diff))
             .size_full()
             .bg(cx.theme().colors().editor_background)
             .child(self.render_header(window, cx))
@@ -1303,6 +1344,8 @@ impl Render for CommitViewToolbar {
         });
 
         let sha_for_graph = commit_sha.to_string();
+        let show_review_button = !is_stash && AgentSettings::get_global(cx).enabled(cx);
+        let commit_view_for_review = commit_view.downgrade();
 
         h_flex()
             .gap_1()
@@ -1358,7 +1401,54 @@ impl Render for CommitViewToolbar {
                         .on_click(move |_, _, cx| cx.open_url(&url))
                 }))
             })
+            .when(show_review_button, |this| {
+                this.child(
+                    IconButton::new("review-commit", IconName::ZedAssistant)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Review Commit with Agent"))
+                        .on_click(move |_, window, cx| {
+                            commit_view_for_review
+                                .update(cx, |commit_view, cx| {
+                                    commit_view.review_diff(&ReviewDiff, window, cx)
+                                })
+                                .ok();
+                        }),
+                )
+            })
+    }
+}
+
+/// Renders the files of a commit as a unified diff, for the agent to review.
+fn commit_diff_text(commit_diff: &CommitDiff) -> String {
+    let mut diff_text = String::new();
+    for file in &commit_diff.files {
+        if file.is_binary {
+            continue;
+        }
+        let path = file.path.as_unix_str();
+        let old_path = match file.old_content {
+            Some(_) => format!("a/{path}"),
+            None => "/dev/null".to_string(),
+        };
+        let new_path = match file.new_content {
+            Some(_) => format!("b/{path}"),
+            None => "/dev/null".to_string(),
+        };
+        let old_text = file
+            .old_content
+            .as_deref()
+            .map(String::from_utf8_lossy)
+            .unwrap_or_default();
+        let new_text = file
+            .new_content
+            .as_deref()
+            .map(String::from_utf8_lossy)
+            .unwrap_or_default();
+
+        diff_text.push_str(&format!("--- {old_path}\n+++ {new_path}\n"));
+        diff_text.push_str(&language::unified_diff(&old_text, &new_text));
     }
+    diff_text
 }
 
 impl ToolbarItemView for CommitViewToolbar {
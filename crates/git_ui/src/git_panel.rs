@@ -8,6 +8,8 @@ use crate::commit_modal::CommitModal;
 use crate::commit_tooltip::{CommitAvatar, CommitTooltip};
 use crate::commit_view::CommitView;
 use crate::git_panel_settings::GitPanelScrollbarAccessor;
+use crate::merge_view::OpenMergeEditor;
+use crate::notebook_diff_view::{self, NotebookDiffView, OpenTextDiff};
 use crate::project_diff::{DeployBranchDiff, Diff, ProjectDiff};
 use crate::remote_output::{self, RemoteAction, SuccessMessage};
 use crate::solo_diff_view::SoloDiffView;
@@ -2170,8 +2172,19 @@ impl GitPanel {
                 .clone();
             let repository = self.active_repository.clone()?;
 
-            SoloDiffView::open_or_focus(entry, repository, self.workspace.clone(), window, cx)
+            if notebook_diff_view::is_notebook(&entry.repo_path) {
+                NotebookDiffView::open_or_focus(
+                    entry,
+                    repository,
+                    self.workspace.clone(),
+                    window,
+                    cx,
+                )
                 .detach_and_notify_err(self.workspace.clone(), window, cx);
+            } else {
+                SoloDiffView::open_or_focus(entry, repository, self.workspace.clone(), window, cx)
+                    .detach_and_notify_err(self.workspace.clone(), window, cx);
+            }
 
             Some(())
         });
@@ -2198,6 +2211,42 @@ impl GitPanel {
         });
     }
 
+    fn open_text_diff(&mut self, _: &OpenTextDiff, window: &mut Window, cx: &mut Context<Self>) {
+        maybe!({
+            let entry = self
+                .entries
+                .get(self.selected_entry?)?
+                .status_entry()?
+                .clone();
+            let repository = self.active_repository.clone()?;
+            crate::open_text_diff(entry, repository, self.workspace.clone(), window, cx);
+            Some(())
+        });
+    }
+
+    fn open_merge_editor(
+        &mut self,
+        _: &OpenMergeEditor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        maybe!({
+            let entry = self.entries.get(self.selected_entry?)?.status_entry()?;
+            if !entry.status.is_conflicted() {
+                return None;
+            }
+            let repository = self.active_repository.clone()?;
+            crate::open_merge_editor(
+                entry.repo_path.clone(),
+                repository,
+                self.workspace.clone(),
+                window,
+                cx,
+            );
+            Some(())
+        });
+    }
+
     fn copy_path(&mut self, _: &CopyPath, _: &mut Window, cx: &mut Context<Self>) {
         if let Some((repo_path, repo)) = self
             .get_selected_entry()
@@ -7663,6 +7712,15 @@ impl GitPanel {
                 .context(self.focus_handle.clone())
                 .action(stage_title, ToggleStaged.boxed_clone())
                 .action(restore_title, git::RestoreFile::default().boxed_clone())
+                .when(entry.status.is_conflicted(), |context_menu| {
+                    context_menu.action("Open Merge Editor", OpenMergeEditor.boxed_clone())
+                })
+                .when(
+                    notebook_diff_view::is_notebook(&entry.repo_path),
+                    |context_menu| {
+                        context_menu.action("Open Text Diff", OpenTextDiff.boxed_clone())
+                    },
+                )
                 .separator()
                 .action("Unstaged Changes", ViewUnstagedChanges.boxed_clone())
                 .action("Staged Changes", ViewStagedChanges.boxed_clone())
@@ -8539,6 +8597,8 @@ impl Render for GitPanel {
             .on_action(cx.listener(Self::open_diff))
             .on_action(cx.listener(Self::open_solo_diff))
             .on_action(cx.listener(Self::view_file))
+            .on_action(cx.listener(Self::open_merge_editor))
+            .on_action(cx.listener(Self::open_text_diff))
             .on_action(cx.listener(Self::copy_path))
             .on_action(cx.listener(Self::copy_relative_path))
             .on_action(cx.listener(Self::view_unstaged_changes))
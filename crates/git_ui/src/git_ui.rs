@@ -8,7 +8,9 @@ mod blame_ui;
 pub mod clone;
 
 use git::{
-    repository::{Branch, CommitDetails, Upstream, UpstreamTracking, UpstreamTrackingStatus},
+    repository::{
+        Branch, CommitDetails, RepoPath, Upstream, UpstreamTracking, UpstreamTrackingStatus,
+    },
     status::{FileStatus, StatusCode, UnmergedStatus, UnmergedStatusCode},
 };
 use gpui::{
@@ -29,6 +31,8 @@ use zed_actions;
 use crate::{
     commit_view::CommitView,
     git_panel::{GitPanel, GitStatusEntry, RemoteOperationKind},
+    merge_view::{MergeView, OpenMergeEditor},
+    notebook_diff_view::{NotebookDiffView, OpenTextDiff},
     solo_diff_view::SoloDiffView,
     text_diff_view::TextDiffView,
 };
@@ -46,7 +50,10 @@ pub mod git_panel;
 mod git_panel_settings;
 pub mod git_picker;
 mod git_runtime_diagnostics;
+pub mod merge_view;
 pub mod multi_diff_view;
+mod notebook_diff;
+pub mod notebook_diff_view;
 pub mod picker_prompt;
 pub mod project_diff;
 pub(crate) mod remote_output;
@@ -336,8 +343,22 @@ pub fn init(cx: &mut App) {
                 file_diff_entry(workspace, cx),
                 |div, (entry, repository)| {
                     let workspace = workspace.weak_handle();
-                    div.on_action(move |_: &git::OpenFileDiff, window, cx| {
-                        open_file_diff(
+                    div.on_action({
+                        let entry = entry.clone();
+                        let repository = repository.clone();
+                        let workspace = workspace.clone();
+                        move |_: &git::OpenFileDiff, window, cx| {
+                            open_file_diff(
+                                entry.clone(),
+                                repository.clone(),
+                                workspace.clone(),
+                                window,
+                                cx,
+                            );
+                        }
+                    })
+                    .on_action(move |_: &OpenTextDiff, window, cx| {
+                        open_text_diff(
                             entry.clone(),
                             repository.clone(),
                             workspace.clone(),
@@ -348,6 +369,23 @@ pub fn init(cx: &mut App) {
                 },
             )
         });
+        workspace.register_action_renderer(|div, workspace, _window, cx| {
+            div.when_some(
+                file_diff_entry(workspace, cx).filter(|(entry, _)| entry.status.is_conflicted()),
+                |div, (entry, repository)| {
+                    let workspace = workspace.weak_handle();
+                    div.on_action(move |_: &OpenMergeEditor, window, cx| {
+                        open_merge_editor(
+                            entry.repo_path.clone(),
+                            repository.clone(),
+                            workspace.clone(),
+                            window,
+                            cx,
+                        );
+                    })
+                },
+            )
+        });
         workspace.register_action(|workspace, _: &git::RenameBranch, window, cx| {
             rename_current_branch(workspace, window, cx);
         });
@@ -372,6 +410,25 @@ fn open_file_diff(
     workspace: WeakEntity<Workspace>,
     window: &mut Window,
     cx: &mut App,
+) {
+    window.defer(cx, move |window, cx| {
+        if notebook_diff_view::is_notebook(&entry.repo_path) {
+            NotebookDiffView::open_or_focus(entry, repository, workspace.clone(), window, cx)
+                .detach_and_notify_err(workspace, window, cx);
+        } else {
+            SoloDiffView::open_or_focus(entry, repository, workspace.clone(), window, cx)
+                .detach_and_notify_err(workspace, window, cx);
+        }
+    });
+}
+
+/// Opens the plain text diff of a file, even when it has a richer diff view.
+pub(crate) fn open_text_diff(
+    entry: GitStatusEntry,
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
 ) {
     window.defer(cx, move |window, cx| {
         SoloDiffView::open_or_focus(entry, repository, workspace.clone(), window, cx)
@@ -379,6 +436,19 @@ fn open_file_diff(
     });
 }
 
+pub(crate) fn open_merge_editor(
+    repo_path: RepoPath,
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    window.defer(cx, move |window, cx| {
+        MergeView::open_or_focus(repo_path, repository, workspace.clone(), window, cx)
+            .detach_and_notify_err(workspace, window, cx);
+    });
+}
+
 fn file_diff_entry(
     workspace: &Workspace,
     cx: &App,
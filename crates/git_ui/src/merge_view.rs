@@ -0,0 +1,907 @@
+//! A three-way merge editor for a single conflicted file.
+//!
+//! The base, ours and theirs versions of the file are loaded from index stages
+//! 1–3 and shown side by side above the working copy, which keeps its conflict
+//! markers and per-conflict resolution buttons. Marking the file resolved
+//! saves and stages it, after confirming if a line still looks like a
+//! conflict marker.
+
+use crate::conflict_view::resolve_conflict;
+use anyhow::{Context as _, Result};
+use buffer_diff::{BufferDiff, DiffBaseKind};
+use editor::{
+    Direction, Editor, EditorEvent, EditorMode, RestoreOnlyDiffHunkDelegate, SelectionEffects,
+    scroll::Autoscroll,
+};
+use git::repository::RepoPath;
+use gpui::{
+    AnyEntity, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, FocusHandle,
+    Focusable, IntoElement, PromptLevel, Render, Subscription, Task, WeakEntity, Window, actions,
+    relative,
+};
+use language::{Anchor, Buffer, Capability, Language, OffsetRangeExt as _, Point, ToOffset as _};
+use multi_buffer::MultiBuffer;
+use project::{
+    ConflictRegion, ConflictSet, ConflictSetUpdate, Project, ProjectPath,
+    git_store::{ConflictStages, Repository, RepositoryId},
+};
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    sync::Arc,
+};
+use ui::{Divider, Tooltip, prelude::*};
+use util::paths::PathStyle;
+use workspace::{
+    Item, ItemHandle, ItemNavHistory, Workspace,
+    item::{ItemEvent, SaveOptions},
+    notifications::NotifyTaskExt,
+    searchable::SearchableItemHandle,
+};
+
+actions!(
+    git,
+    [
+        /// Opens the selected conflicted file in a three-way merge editor.
+        OpenMergeEditor,
+    ]
+);
+
+actions!(
+    merge_editor,
+    [
+        /// Moves the cursor to the next merge conflict.
+        NextConflict,
+        /// Moves the cursor to the previous merge conflict.
+        PreviousConflict,
+        /// Resolves the conflict at the cursor with our version.
+        AcceptOurs,
+        /// Resolves the conflict at the cursor with their version.
+        AcceptTheirs,
+        /// Resolves the conflict at the cursor with both versions, ours first.
+        AcceptBoth,
+        /// Saves and stages the file to mark its conflicts resolved.
+        MarkResolved,
+    ]
+);
+
+pub struct MergeView {
+    project: Entity<Project>,
+    repository: Entity<Repository>,
+    repository_id: RepositoryId,
+    repo_path: RepoPath,
+    buffer: Entity<Buffer>,
+    conflict_set: Entity<ConflictSet>,
+    base_editor: Entity<Editor>,
+    ours_editor: Entity<Editor>,
+    theirs_editor: Entity<Editor>,
+    result_editor: Entity<Editor>,
+    ours_branch_name: Option<SharedString>,
+    theirs_branch_name: Option<SharedString>,
+    workspace: WeakEntity<Workspace>,
+    marking_resolved: bool,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl MergeView {
+    pub fn open_or_focus(
+        repo_path: RepoPath,
+        repository: Entity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let Some(workspace_entity) = workspace.upgrade() else {
+            return Task::ready(Err(anyhow::anyhow!("workspace was dropped")));
+        };
+
+        let existing = workspace_entity
+            .read(cx)
+            .items_of_type::<MergeView>(cx)
+            .find(|item| item.read(cx).matches(&repository, &repo_path, cx));
+        if let Some(existing) = existing {
+            workspace_entity.update(cx, |workspace, cx| {
+                workspace.activate_item(&existing, true, true, window, cx);
+            });
+            existing.focus_handle(cx).focus(window, cx);
+            return Task::ready(Ok(existing));
+        }
+
+        let Some(project_path) = repository
+            .read(cx)
+            .repo_path_to_project_path(&repo_path, cx)
+        else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "could not resolve repository path {:?}",
+                repo_path
+            )));
+        };
+
+        let project = workspace_entity.read(cx).project().clone();
+        let stages = repository.update(cx, |repository, _| {
+            repository.load_conflict_stages(repo_path.clone())
+        });
+        window.spawn(cx, async move |cx| {
+            let buffer = project
+                .update(cx, |project, cx| project.open_buffer(project_path, cx))
+                .await?;
+            let stages = stages.await?.with_context(|| {
+                format!(
+                    "failed to load the merge stages of {:?}",
+                    repo_path.as_ref()
+                )
+            })?;
+            let conflict_set = project
+                .update(cx, |project, cx| {
+                    project.git_store().update(cx, |git_store, cx| {
+                        git_store.open_conflict_set(buffer.clone(), cx)
+                    })
+                })
+                .await;
+
+            workspace_entity.update_in(cx, |workspace, window, cx| {
+                let workspace_handle = cx.weak_entity();
+                let view = cx.new(|cx| {
+                    Self::new(
+                        project,
+                        repository,
+                        repo_path,
+                        buffer,
+                        stages,
+                        conflict_set,
+                        workspace_handle,
+                        window,
+                        cx,
+                    )
+                });
+
+                workspace.add_item_to_active_pane(Box::new(view.clone()), None, true, window, cx);
+                view
+            })
+        })
+    }
+
+    fn new(
+        project: Entity<Project>,
+        repository: Entity<Repository>,
+        repo_path: RepoPath,
+        buffer: Entity<Buffer>,
+        stages: ConflictStages,
+        conflict_set: Entity<ConflictSet>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let repository_id = repository.read(cx).id;
+        let language = buffer.read(cx).language().cloned();
+        let base_buffer = read_only_buffer(stages.base.unwrap_or_default(), language.clone(), cx);
+        let ours_buffer = read_only_buffer(stages.ours.unwrap_or_default(), language.clone(), cx);
+        let theirs_buffer = read_only_buffer(stages.theirs.unwrap_or_default(), language, cx);
+
+        let base_editor = Self::build_stage_editor(base_buffer.clone(), None, &project, window, cx);
+        let ours_editor =
+            Self::build_stage_editor(ours_buffer, Some(base_buffer.clone()), &project, window, cx);
+        let theirs_editor =
+            Self::build_stage_editor(theirs_buffer, Some(base_buffer), &project, window, cx);
+
+        let result_editor = cx.new(|cx| {
+            let mut editor = Editor::for_buffer(buffer.clone(), Some(project.clone()), window, cx);
+            editor.set_should_serialize(false, cx);
+            editor
+        });
+
+        let (ours_branch_name, theirs_branch_name) = conflict_set
+            .read(cx)
+            .snapshot
+            .conflicts
+            .first()
+            .map(|conflict| {
+                (
+                    Some(conflict.ours_branch_name.clone()),
+                    Some(conflict.theirs_branch_name.clone()),
+                )
+            })
+            .unwrap_or_default();
+
+        let subscriptions = vec![
+            cx.subscribe(&result_editor, |_, _, event: &EditorEvent, cx| {
+                cx.emit(event.clone());
+                cx.notify();
+            }),
+            cx.subscribe_in(
+                &conflict_set,
+                window,
+                |this, _, _: &ConflictSetUpdate, window, cx| {
+                    this.conflicts_updated(window, cx);
+                },
+            ),
+        ];
+
+        Self {
+            project,
+            repository,
+            repository_id,
+            repo_path,
+            buffer,
+            conflict_set,
+            base_editor,
+            ours_editor,
+            theirs_editor,
+            result_editor,
+            ours_branch_name,
+            theirs_branch_name,
+            workspace,
+            marking_resolved: false,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    fn build_stage_editor(
+        buffer: Entity<Buffer>,
+        base_buffer: Option<Entity<Buffer>>,
+        project: &Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<Editor> {
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::singleton(buffer.clone(), cx);
+            if let Some(base_buffer) = base_buffer {
+                let diff = cx.new(|cx| {
+                    BufferDiff::new_with_base_text_buffer(
+                        &buffer.read(cx).snapshot().text,
+                        base_buffer.clone(),
+                        DiffBaseKind::Custom,
+                        cx,
+                    )
+                });
+                cx.spawn({
+                    let diff = diff.clone();
+                    async move |_, cx| update_stage_diff(&diff, &buffer, &base_buffer, cx).await
+                })
+                .detach();
+                multibuffer.add_diff(diff, cx);
+            }
+            multibuffer
+        });
+
+        cx.new(|cx| {
+            let mut editor = Editor::new(
+                EditorMode::full(),
+                multibuffer,
+                Some(project.clone()),
+                window,
+                cx,
+            );
+            editor.set_read_only(true);
+            editor.set_show_bookmarks(false, cx);
+            editor.set_show_breakpoints(false, cx);
+            editor.set_show_code_actions(false, cx);
+            editor.set_diff_hunk_delegate(Some(Arc::new(RestoreOnlyDiffHunkDelegate)), cx);
+            editor
+        })
+    }
+
+    fn matches(&self, repository: &Entity<Repository>, repo_path: &RepoPath, cx: &App) -> bool {
+        self.repository_id == repository.read(cx).id && &self.repo_path == repo_path
+    }
+
+    fn conflict_offset_ranges(&self, cx: &App) -> Vec<Range<usize>> {
+        let buffer = self.buffer.read(cx).snapshot();
+        self.conflict_set
+            .read(cx)
+            .snapshot
+            .conflicts
+            .iter()
+            .map(|conflict| conflict.range.to_offset(&buffer))
+            .collect()
+    }
+
+    fn cursor_offset(&self, cx: &App) -> Option<usize> {
+        let editor = self.result_editor.read(cx);
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let (position, _) =
+            snapshot.anchor_to_buffer_anchor(editor.selections.newest_anchor().head())?;
+        Some(position.to_offset(&self.buffer.read(cx).snapshot()))
+    }
+
+    fn current_conflict_index(&self, cx: &App) -> Option<usize> {
+        conflict_at(&self.conflict_offset_ranges(cx), self.cursor_offset(cx)?)
+    }
+
+    fn current_conflict(&self, cx: &App) -> Option<ConflictRegion> {
+        let ix = self.current_conflict_index(cx)?;
+        self.conflict_set
+            .read(cx)
+            .snapshot
+            .conflicts
+            .get(ix)
+            .cloned()
+    }
+
+    fn go_to_conflict(
+        &mut self,
+        direction: Direction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(cursor) = self.cursor_offset(cx) else {
+            return;
+        };
+        let Some(ix) = adjacent_conflict(&self.conflict_offset_ranges(cx), cursor, direction)
+        else {
+            return;
+        };
+        let start = self.conflict_set.read(cx).snapshot.conflicts[ix]
+            .range
+            .start;
+        self.select_position(start, window, cx);
+    }
+
+    fn select_position(&mut self, position: Anchor, window: &mut Window, cx: &mut Context<Self>) {
+        self.result_editor.update(cx, |editor, cx| {
+            let Some(position) = editor
+                .buffer()
+                .read(cx)
+                .snapshot(cx)
+                .anchor_in_buffer(position)
+            else {
+                return;
+            };
+            editor.change_selections(
+                SelectionEffects::scroll(Autoscroll::center()),
+                window,
+                cx,
+                |selections| selections.select_ranges([position..position]),
+            );
+        });
+        self.result_editor.focus_handle(cx).focus(window, cx);
+    }
+
+    fn accept(&mut self, ours: bool, theirs: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(conflict) = self.current_conflict(cx) else {
+            return;
+        };
+        let ranges: Vec<Range<Anchor>> = [
+            ours.then(|| conflict.ours.clone()),
+            theirs.then(|| conflict.theirs.clone()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        resolve_conflict(self.result_editor.downgrade(), conflict, ranges, window, cx).detach();
+    }
+
+    fn conflicts_updated(&mut self, _: &mut Window, cx: &mut Context<Self>) {
+        cx.notify();
+    }
+
+    /// Saves and stages the file. Deleting only part of a conflict leaves the
+    /// rest unparsed, so the file is scanned for lines that look like markers
+    /// and the user is asked to confirm if any remain.
+    fn mark_resolved(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.marking_resolved {
+            return;
+        }
+        let buffer = self.buffer.read(cx).snapshot();
+        let confirmation = first_conflict_marker(&buffer.text()).map(|row| {
+            let position = buffer.anchor_before(Point::new(row, 0));
+            self.select_position(position, window, cx);
+            window.prompt(
+                PromptLevel::Warning,
+                &format!("Line {} still looks like a conflict marker", row + 1),
+                Some("Marking the file resolved will stage it with the marker."),
+                &["Mark as Resolved", "Cancel"],
+                cx,
+            )
+        });
+        self.marking_resolved = true;
+        cx.notify();
+
+        let project = self.project.clone();
+        let buffer = self.buffer.clone();
+        let repository = self.repository.clone();
+        let repo_path = self.repo_path.clone();
+        let task = cx.spawn(async move |this, cx| {
+            let confirmed = match confirmation {
+                Some(confirmation) => confirmation.await.ok() == Some(0),
+                None => true,
+            };
+            let result = if confirmed {
+                save_and_stage(project, buffer, repository, repo_path, cx).await
+            } else {
+                Ok(())
+            };
+            this.update(cx, |this, cx| {
+                this.marking_resolved = false;
+                cx.notify();
+            })
+            .ok();
+            result
+        });
+        task.detach_and_notify_err(self.workspace.clone(), window, cx);
+    }
+
+    fn render_pane(
+        title: &'static str,
+        branch_name: Option<SharedString>,
+        editor: &Entity<Editor>,
+        cx: &App,
+    ) -> impl IntoElement {
+        v_flex()
+            .flex_1()
+            .min_w_0()
+            .h_full()
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(title).size(LabelSize::Small))
+                    .when_some(branch_name, |this, branch_name| {
+                        this.child(
+                            Label::new(branch_name)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+            .child(div().flex_1().min_h_0().child(editor.clone()))
+    }
+
+    fn render_result_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let conflict_set = self.conflict_set.read(cx);
+        let has_conflict = conflict_set.has_conflict;
+        let conflict_count = conflict_set.snapshot.conflicts.len();
+        let current = self.current_conflict_index(cx);
+        let focus_handle = self.result_editor.focus_handle(cx);
+
+        let status = match (conflict_count, current) {
+            (0, _) if has_conflict => "No conflicts remaining".to_string(),
+            (0, _) => "Resolved".to_string(),
+            (count, Some(ix)) => format!("Conflict {} of {count}", ix + 1),
+            (1, None) => "1 conflict".to_string(),
+            (count, None) => format!("{count} conflicts"),
+        };
+
+        h_flex()
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .justify_between()
+            .border_y_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Label::new("Result").size(LabelSize::Small))
+                    .child(
+                        Label::new(status)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        IconButton::new("previous-conflict", IconName::ArrowUp)
+                            .icon_size(IconSize::Small)
+                            .disabled(conflict_count == 0)
+                            .tooltip(Tooltip::for_action_title_in(
+                                "Previous Conflict",
+                                &PreviousConflict,
+                                &focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.go_to_conflict(Direction::Prev, window, cx)
+                            })),
+                    )
+                    .child(
+                        IconButton::new("next-conflict", IconName::ArrowDown)
+                            .icon_size(IconSize::Small)
+                            .disabled(conflict_count == 0)
+                            .tooltip(Tooltip::for_action_title_in(
+                                "Next Conflict",
+                                &NextConflict,
+                                &focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.go_to_conflict(Direction::Next, window, cx)
+                            })),
+                    )
+                    .child(Divider::vertical())
+                    .child(
+                        Button::new("accept-ours", "Accept Ours")
+                            .label_size(LabelSize::Small)
+                            .disabled(current.is_none())
+                            .tooltip(Tooltip::for_action_title_in(
+                                "Accept Ours",
+                                &AcceptOurs,
+                                &focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.accept(true, false, window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("accept-theirs", "Accept Theirs")
+                            .label_size(LabelSize::Small)
+                            .disabled(current.is_none())
+                            .tooltip(Tooltip::for_action_title_in(
+                                "Accept Theirs",
+                                &AcceptTheirs,
+                                &focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.accept(false, true, window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("accept-both", "Accept Both")
+                            .label_size(LabelSize::Small)
+                            .disabled(current.is_none())
+                            .tooltip(Tooltip::for_action_title_in(
+                                "Accept Both",
+                                &AcceptBoth,
+                                &focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.accept(true, true, window, cx)
+                            })),
+                    )
+                    .when(has_conflict && conflict_count == 0, |this| {
+                        this.child(Divider::vertical()).child(
+                            Button::new("mark-resolved", "Mark as Resolved")
+                                .label_size(LabelSize::Small)
+                                .disabled(self.marking_resolved)
+                                .tooltip(Tooltip::for_action_title_in(
+                                    "Mark as Resolved",
+                                    &MarkResolved,
+                                    &focus_handle,
+                                ))
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.mark_resolved(window, cx)
+                                })),
+                        )
+                    }),
+            )
+    }
+}
+
+/// Returns the row of the first line that looks like a conflict marker, such
+/// as one left over after deleting only part of a conflict.
+fn first_conflict_marker(text: &str) -> Option<u32> {
+    text.lines()
+        .position(|line| {
+            line.starts_with("<<<<<<< ")
+                || line.starts_with("=======")
+                || line.starts_with(">>>>>>> ")
+        })
+        .map(|row| row as u32)
+}
+
+/// Returns the index of the conflict containing `cursor`.
+fn conflict_at(ranges: &[Range<usize>], cursor: usize) -> Option<usize> {
+    ranges
+        .iter()
+        .position(|range| range.start <= cursor && cursor <= range.end)
+}
+
+/// Returns the index of the conflict to move to from `cursor`, wrapping around
+/// at either end of the file.
+fn adjacent_conflict(
+    ranges: &[Range<usize>],
+    cursor: usize,
+    direction: Direction,
+) -> Option<usize> {
+    if ranges.is_empty() {
+        return None;
+    }
+    match direction {
+        Direction::Next => Some(
+            ranges
+                .iter()
+                .position(|range| range.start > cursor)
+                .unwrap_or(0),
+        ),
+        Direction::Prev => Some(
+            ranges
+                .iter()
+                .rposition(|range| range.end < cursor)
+                .unwrap_or(ranges.len() - 1),
+        ),
+    }
+}
+
+fn read_only_buffer(text: String, language: Option<Arc<Language>>, cx: &mut App) -> Entity<Buffer> {
+    cx.new(|cx| {
+        let mut buffer = Buffer::local(text, cx);
+        buffer.set_language(language, cx);
+        buffer.set_capability(Capability::ReadOnly, cx);
+        buffer
+    })
+}
+
+async fn update_stage_diff(
+    diff: &Entity<BufferDiff>,
+    buffer: &Entity<Buffer>,
+    base_buffer: &Entity<Buffer>,
+    cx: &mut AsyncApp,
+) {
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+    let base_snapshot = base_buffer.read_with(cx, |buffer, _| buffer.snapshot());
+    let base_text = Arc::<str>::from(base_snapshot.text());
+
+    let update = diff
+        .update(cx, |diff, cx| {
+            diff.update_diff(snapshot.text.clone(), &base_snapshot, Some(base_text), cx)
+        })
+        .await;
+
+    diff.update(cx, |diff, cx| diff.set_snapshot(update, cx));
+}
+
+async fn save_and_stage(
+    project: Entity<Project>,
+    buffer: Entity<Buffer>,
+    repository: Entity<Repository>,
+    repo_path: RepoPath,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    if buffer.read_with(cx, |buffer, _| buffer.is_dirty()) {
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer, cx))
+            .await
+            .context("failed to save the merged file")?;
+    }
+    repository
+        .update(cx, |repository, cx| {
+            repository.stage_entries(vec![repo_path], cx)
+        })
+        .await
+        .context("failed to mark the file as resolved")
+}
+
+impl EventEmitter<EditorEvent> for MergeView {}
+
+impl Focusable for MergeView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.result_editor.focus_handle(cx)
+    }
+}
+
+impl Item for MergeView {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitMergeConflict).color(Color::Muted))
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        self.repo_path
+            .file_name()
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| {
+                self.repo_path
+                    .as_ref()
+                    .display(PathStyle::local())
+                    .into_owned()
+            })
+            .into()
+    }
+
+    fn tab_tooltip_text(&self, _cx: &App) -> Option<SharedString> {
+        Some(
+            format!(
+                "Merge {}",
+                self.repo_path.as_ref().display(PathStyle::local())
+            )
+            .into(),
+        )
+    }
+
+    fn to_item_events(event: &EditorEvent, f: &mut dyn FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Merge Editor Opened")
+    }
+
+    fn active_project_path(&self, cx: &App) -> Option<ProjectPath> {
+        self.repository
+            .read(cx)
+            .repo_path_to_project_path(&self.repo_path, cx)
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.result_editor.deactivated(window, cx);
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyEntity> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.clone().into())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.result_editor.clone().into())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>, _: &App) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.result_editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.result_editor.for_each_project_item(cx, f)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Arc<dyn Any + Send>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.result_editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.result_editor.is_dirty(cx)
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.result_editor.has_conflict(cx)
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.result_editor.can_save(cx)
+    }
+
+    fn save(
+        &mut self,
+        options: SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.result_editor.save(options, project, window, cx)
+    }
+
+    fn reload(
+        &mut self,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.result_editor.reload(project, window, cx)
+    }
+}
+
+impl Render for MergeView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("MergeEditor")
+            .on_action(cx.listener(|this, _: &NextConflict, window, cx| {
+                this.go_to_conflict(Direction::Next, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &PreviousConflict, window, cx| {
+                this.go_to_conflict(Direction::Prev, window, cx)
+            }))
+            .on_action(
+                cx.listener(|this, _: &AcceptOurs, window, cx| {
+                    this.accept(true, false, window, cx)
+                }),
+            )
+            .on_action(cx.listener(|this, _: &AcceptTheirs, window, cx| {
+                this.accept(false, true, window, cx)
+            }))
+            .on_action(
+                cx.listener(|this, _: &AcceptBoth, window, cx| this.accept(true, true, window, cx)),
+            )
+            .on_action(cx.listener(|this, _: &MarkResolved, window, cx| {
+                if this.conflict_set.read(cx).snapshot.conflicts.is_empty() {
+                    this.mark_resolved(window, cx)
+                }
+            }))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .w_full()
+                    .h(relative(0.4))
+                    .child(Self::render_pane("Base", None, &self.base_editor, cx))
+                    .child(Divider::vertical())
+                    .child(Self::render_pane(
+                        "Ours",
+                        self.ours_branch_name.clone(),
+                        &self.ours_editor,
+                        cx,
+                    ))
+                    .child(Divider::vertical())
+                    .child(Self::render_pane(
+                        "Theirs",
+                        self.theirs_branch_name.clone(),
+                        &self.theirs_editor,
+                        cx,
+                    )),
+            )
+            .child(self.render_result_header(cx))
+            .child(div().flex_1().min_h_0().child(self.result_editor.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conflict_navigation() {
+        let ranges = [10..20, 30..40, 50..60];
+
+        assert_eq!(conflict_at(&ranges, 0), None);
+        assert_eq!(conflict_at(&ranges, 10), Some(0));
+        assert_eq!(conflict_at(&ranges, 35), Some(1));
+        assert_eq!(conflict_at(&ranges, 45), None);
+
+        assert_eq!(adjacent_conflict(&ranges, 0, Direction::Next), Some(0));
+        assert_eq!(adjacent_conflict(&ranges, 10, Direction::Next), Some(1));
+        assert_eq!(adjacent_conflict(&ranges, 35, Direction::Next), Some(2));
+        assert_eq!(adjacent_conflict(&ranges, 50, Direction::Next), Some(0));
+
+        assert_eq!(adjacent_conflict(&ranges, 70, Direction::Prev), Some(2));
+        assert_eq!(adjacent_conflict(&ranges, 50, Direction::Prev), Some(1));
+        assert_eq!(adjacent_conflict(&ranges, 35, Direction::Prev), Some(0));
+        assert_eq!(adjacent_conflict(&ranges, 10, Direction::Prev), Some(2));
+
+        assert_eq!(adjacent_conflict(&[], 10, Direction::Next), None);
+    }
+
+    #[gpui::test]
+    fn test_conflict_markers_left_after_deleting_the_end_marker(cx: &mut gpui::TestAppContext) {
+        let text = "one\n<<<<<<< HEAD\nours\n=======\ntheirs\ntwo\n";
+        let buffer = cx.new(|cx| Buffer::local(text, cx));
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.text_snapshot());
+
+        // Without its end marker, the conflict is no longer parsed as one.
+        assert!(ConflictSet::parse(&snapshot).conflicts.is_empty());
+        assert_eq!(first_conflict_marker(&snapshot.text()), Some(1));
+
+        assert_eq!(first_conflict_marker("a <<<<<<< b\n==\n>>>>>>>\n"), None);
+        assert_eq!(first_conflict_marker("theirs\n>>>>>>> branch\n"), Some(1));
+    }
+}
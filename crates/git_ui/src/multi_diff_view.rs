@@ -21,7 +21,7 @@ use ui::{Color, Icon, IconName};
 use util::paths::PathStyle;
 use util::rel_path::RelPath;
 use workspace::{
-    Item, ItemHandle as _, ItemNavHistory, ToolbarItemLocation, Workspace,
+    Item, ItemHandle as _, ItemNavHistory, SplitDirection, ToolbarItemLocation, Workspace,
     item::{ItemEvent, SaveOptions},
     searchable::SearchableItemHandle,
 };
@@ -29,6 +29,7 @@ use workspace::{
 pub struct MultiDiffView {
     editor: Entity<Editor>,
     file_count: usize,
+    label: Option<SharedString>,
 }
 
 struct Entry {
@@ -159,20 +160,15 @@ impl MultiDiffView {
             let (entries, common_root) = load_entries(diff_pairs, &project, cx).await?;
 
             workspace.update_in(cx, |workspace, window, cx| {
-                let multibuffer = cx.new(|cx| {
-                    let mut multibuffer = MultiBuffer::new(Capability::ReadWrite);
-                    multibuffer.set_all_diff_hunks_expanded(cx);
-                    multibuffer
-                });
-
-                let file_count = entries.len();
-                for entry in entries {
-                    register_entry(&multibuffer, entry, &common_root, context_lines, cx);
-                }
-
-                let diff_view = cx.new(|cx| {
-                    Self::new(multibuffer.clone(), project.clone(), file_count, window, cx)
-                });
+                let diff_view = Self::build(
+                    entries,
+                    &common_root,
+                    None,
+                    &project,
+                    context_lines,
+                    window,
+                    cx,
+                );
 
                 let pane = workspace.active_pane();
                 pane.update(cx, |pane, cx| {
@@ -189,10 +185,93 @@ impl MultiDiffView {
         })
     }
 
+    /// Opens one diff view per labeled set of diff pairs, side by side from
+    /// left to right. Used to compare alternative versions of the same change.
+    pub fn open_side_by_side(
+        columns: Vec<(SharedString, Vec<[String; 2]>)>,
+        workspace: &Workspace,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Vec<Entity<Self>>>> {
+        let project = workspace.project().clone();
+        let workspace = workspace.weak_handle();
+        let context_lines = multibuffer_context_lines(cx);
+
+        window.spawn(cx, async move |cx| {
+            let mut loaded = Vec::with_capacity(columns.len());
+            for (label, diff_pairs) in columns {
+                let (entries, common_root) = load_entries(diff_pairs, &project, cx).await?;
+                loaded.push((label, entries, common_root));
+            }
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let mut diff_views = Vec::with_capacity(loaded.len());
+                for (ix, (label, entries, common_root)) in loaded.into_iter().enumerate() {
+                    let diff_view = Self::build(
+                        entries,
+                        &common_root,
+                        Some(label),
+                        &project,
+                        context_lines,
+                        window,
+                        cx,
+                    );
+                    // Each split becomes the active pane, so the next one
+                    // opens to its right.
+                    if ix == 0 {
+                        workspace.active_pane().update(cx, |pane, cx| {
+                            pane.add_item(
+                                Box::new(diff_view.clone()),
+                                true,
+                                true,
+                                None,
+                                window,
+                                cx,
+                            );
+                        });
+                    } else {
+                        workspace.split_item(
+                            SplitDirection::Right,
+                            Box::new(diff_view.clone()),
+                            window,
+                            cx,
+                        );
+                    }
+                    diff_views.push(diff_view);
+                }
+                diff_views
+            })
+        })
+    }
+
+    fn build(
+        entries: Vec<Entry>,
+        common_root: &Option<PathBuf>,
+        label: Option<SharedString>,
+        project: &Entity<Project>,
+        context_lines: u32,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::new(Capability::ReadWrite);
+            multibuffer.set_all_diff_hunks_expanded(cx);
+            multibuffer
+        });
+
+        let file_count = entries.len();
+        for entry in entries {
+            register_entry(&multibuffer, entry, common_root, context_lines, cx);
+        }
+
+        cx.new(|cx| Self::new(multibuffer, project.clone(), file_count, label, window, cx))
+    }
+
     fn new(
         multibuffer: Entity<MultiBuffer>,
         project: Entity<Project>,
         file_count: usize,
+        label: Option<SharedString>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
@@ -205,7 +284,11 @@ impl MultiDiffView {
             editor
         });
 
-        Self { editor, file_count }
+        Self {
+            editor,
+            file_count,
+            label,
+        }
     }
 
     fn title(&self) -> SharedString {
@@ -214,7 +297,10 @@ impl MultiDiffView {
         } else {
             format!("{} files", self.file_count)
         };
-        format!("Diff ({suffix})").into()
+        match &self.label {
+            Some(label) => format!("{label} ({suffix})").into(),
+            None => format!("Diff ({suffix})").into(),
+        }
     }
 }
 
@@ -0,0 +1,466 @@
+//! Cell-level comparison of two versions of a notebook.
+//!
+//! Diffing the JSON of an `.ipynb` file buries source edits under output blobs
+//! and execution counts. Here both versions are parsed, their cells are paired
+//! up, and each pair reports separately whether its source, outputs or
+//! metadata changed, so callers can show source edits and summarize the rest.
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use jupyter_protocol::media::{Media, MediaType};
+use nbformat::v4::{Cell, Notebook, Output};
+use uuid::Uuid;
+
+/// Parses notebook JSON into the v4 model, upgrading older formats.
+///
+/// Cells without an id get a random one, so ids from files written before
+/// nbformat 4.5 can't be used to pair cells across versions.
+pub fn parse_notebook(text: &str) -> Result<Notebook> {
+    if text.trim().is_empty() {
+        return Ok(Notebook {
+            nbformat: 4,
+            nbformat_minor: 5,
+            cells: Vec::new(),
+            metadata: serde_json::from_str("{}")?,
+        });
+    }
+
+    let mut json: serde_json::Value =
+        serde_json::from_str(text).context("Notebook is not valid JSON")?;
+    if let Some(cells) = json.get_mut("cells").and_then(|cells| cells.as_array_mut()) {
+        for cell in cells {
+            if cell.get("id").is_none() {
+                cell["id"] = serde_json::Value::String(Uuid::new_v4().to_string());
+            }
+        }
+    }
+
+    match nbformat::parse_notebook(&json.to_string()) {
+        Ok(nbformat::Notebook::V4(notebook)) => Ok(notebook),
+        Ok(nbformat::Notebook::Legacy(legacy_notebook)) => {
+            nbformat::upgrade_legacy_notebook(legacy_notebook)
+        }
+        Ok(nbformat::Notebook::V3(v3_notebook)) => nbformat::upgrade_v3_notebook(v3_notebook),
+        Err(error) => anyhow::bail!("Failed to parse notebook: {error:?}"),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellKind {
+    Code,
+    Markdown,
+    Raw,
+}
+
+impl CellKind {
+    fn of(cell: &Cell) -> Self {
+        match cell {
+            Cell::Code { .. } => Self::Code,
+            Cell::Markdown { .. } => Self::Markdown,
+            Cell::Raw { .. } => Self::Raw,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellChange {
+    Added,
+    Removed,
+    Modified,
+    Unchanged,
+}
+
+/// A cell from the old notebook, the new one, or both when they were paired.
+#[derive(Debug, Clone)]
+pub struct CellDiff {
+    /// The cell's index and contents in the old notebook.
+    pub old: Option<(usize, Cell)>,
+    /// The cell's index and contents in the new notebook.
+    pub new: Option<(usize, Cell)>,
+}
+
+impl CellDiff {
+    pub fn kind(&self) -> CellKind {
+        self.new
+            .as_ref()
+            .or(self.old.as_ref())
+            .map(|(_, cell)| CellKind::of(cell))
+            .unwrap_or(CellKind::Code)
+    }
+
+    pub fn old_source(&self) -> String {
+        self.old
+            .as_ref()
+            .map(|(_, cell)| cell.source().concat())
+            .unwrap_or_default()
+    }
+
+    pub fn new_source(&self) -> String {
+        self.new
+            .as_ref()
+            .map(|(_, cell)| cell.source().concat())
+            .unwrap_or_default()
+    }
+
+    pub fn old_outputs(&self) -> &[Output] {
+        self.old.as_ref().map_or(&[], |(_, cell)| outputs(cell))
+    }
+
+    pub fn new_outputs(&self) -> &[Output] {
+        self.new.as_ref().map_or(&[], |(_, cell)| outputs(cell))
+    }
+
+    pub fn old_execution_count(&self) -> Option<i32> {
+        self.old
+            .as_ref()
+            .and_then(|(_, cell)| execution_count(cell))
+    }
+
+    pub fn new_execution_count(&self) -> Option<i32> {
+        self.new
+            .as_ref()
+            .and_then(|(_, cell)| execution_count(cell))
+    }
+
+    pub fn source_changed(&self) -> bool {
+        self.old.is_none() || self.new.is_none() || self.old_source() != self.new_source()
+    }
+
+    pub fn outputs_changed(&self) -> bool {
+        to_json(self.old_outputs()) != to_json(self.new_outputs())
+    }
+
+    /// Whether the execution count or the cell metadata changed.
+    pub fn metadata_changed(&self) -> bool {
+        let (Some((_, old)), Some((_, new))) = (&self.old, &self.new) else {
+            return false;
+        };
+        execution_count(old) != execution_count(new)
+            || to_json(old.metadata()) != to_json(new.metadata())
+    }
+
+    /// How the cell changed. With `ignore_outputs_and_metadata`, a cell whose
+    /// source is the same on both sides counts as unchanged.
+    pub fn change(&self, ignore_outputs_and_metadata: bool) -> CellChange {
+        match (&self.old, &self.new) {
+            (None, _) => CellChange::Added,
+            (_, None) => CellChange::Removed,
+            _ if self.source_changed() => CellChange::Modified,
+            _ if !ignore_outputs_and_metadata
+                && (self.outputs_changed() || self.metadata_changed()) =>
+            {
+                CellChange::Modified
+            }
+            _ => CellChange::Unchanged,
+        }
+    }
+}
+
+/// Pairs the cells of two notebooks and returns them in display order: the new
+/// notebook's order, with removed cells placed where they used to be.
+///
+/// Cells are paired by id first, then by identical source. Whatever is left
+/// is paired by position between those anchors, as long as the cell kinds
+/// match, so an edited cell without a stable id still shows as modified
+/// rather than as a removal and an addition.
+pub fn diff_notebooks(old: &Notebook, new: &Notebook) -> Vec<CellDiff> {
+    let mut old_for_new: Vec<Option<usize>> = vec![None; new.cells.len()];
+    let mut old_used = vec![false; old.cells.len()];
+
+    let old_by_id: HashMap<&str, usize> = old
+        .cells
+        .iter()
+        .enumerate()
+        .map(|(ix, cell)| (cell.id().as_str(), ix))
+        .collect();
+    for (new_ix, cell) in new.cells.iter().enumerate() {
+        if let Some(&old_ix) = old_by_id.get(cell.id().as_str())
+            && !old_used[old_ix]
+            && CellKind::of(&old.cells[old_ix]) == CellKind::of(cell)
+        {
+            old_for_new[new_ix] = Some(old_ix);
+            old_used[old_ix] = true;
+        }
+    }
+
+    for (new_ix, cell) in new.cells.iter().enumerate() {
+        if old_for_new[new_ix].is_some() {
+            continue;
+        }
+        let old_ix = old.cells.iter().enumerate().position(|(old_ix, old_cell)| {
+            !old_used[old_ix]
+                && CellKind::of(old_cell) == CellKind::of(cell)
+                && old_cell.source() == cell.source()
+        });
+        if let Some(old_ix) = old_ix {
+            old_for_new[new_ix] = Some(old_ix);
+            old_used[old_ix] = true;
+        }
+    }
+
+    let mut previous_anchor = None;
+    for (new_ix, cell) in new.cells.iter().enumerate() {
+        if let Some(old_ix) = old_for_new[new_ix] {
+            previous_anchor = Some(old_ix);
+            continue;
+        }
+        let start = previous_anchor.map_or(0, |old_ix| old_ix + 1);
+        let end = old_for_new[new_ix + 1..]
+            .iter()
+            .flatten()
+            .next()
+            .copied()
+            .unwrap_or(old.cells.len())
+            .max(start);
+        let kind = CellKind::of(cell);
+        let old_ix = (start..end)
+            .find(|&old_ix| !old_used[old_ix] && CellKind::of(&old.cells[old_ix]) == kind);
+        if let Some(old_ix) = old_ix {
+            old_for_new[new_ix] = Some(old_ix);
+            old_used[old_ix] = true;
+            previous_anchor = Some(old_ix);
+        }
+    }
+
+    let mut diffs = Vec::new();
+    let mut next_old_ix = 0;
+    let mut push_removed_before = |diffs: &mut Vec<CellDiff>, end: usize| {
+        while next_old_ix < end {
+            if !old_used[next_old_ix] {
+                diffs.push(CellDiff {
+                    old: Some((next_old_ix, old.cells[next_old_ix].clone())),
+                    new: None,
+                });
+            }
+            next_old_ix += 1;
+        }
+    };
+
+    for (new_ix, cell) in new.cells.iter().enumerate() {
+        let mut old_cell = None;
+        if let Some(old_ix) = old_for_new[new_ix] {
+            push_removed_before(&mut diffs, old_ix);
+            old_cell = Some((old_ix, old.cells[old_ix].clone()));
+        }
+        diffs.push(CellDiff {
+            old: old_cell,
+            new: Some((new_ix, cell.clone())),
+        });
+    }
+    push_removed_before(&mut diffs, old.cells.len());
+
+    diffs
+}
+
+/// Whether the notebook-level metadata (kernelspec, language info, ...) changed.
+pub fn notebook_metadata_changed(old: &Notebook, new: &Notebook) -> bool {
+    to_json(&old.metadata) != to_json(&new.metadata)
+}
+
+/// A one-line description of an output, used to summarize output changes
+/// without rendering them.
+pub fn output_summary(output: &Output) -> String {
+    match output {
+        Output::Stream { name, text } => format!("{name}: {}", first_line(&text.0)),
+        Output::DisplayData(display_data) => media_summary(&display_data.data),
+        Output::ExecuteResult(execute_result) => media_summary(&execute_result.data),
+        Output::Error(error) => format!("{}: {}", error.ename, first_line(&error.evalue)),
+    }
+}
+
+fn media_summary(media: &Media) -> String {
+    let plain_text = media.content.iter().find_map(|media| match media {
+        MediaType::Plain(text) => Some(first_line(text)),
+        _ => None,
+    });
+    if let Some(plain_text) = plain_text {
+        return plain_text;
+    }
+
+    media
+        .content
+        .iter()
+        .find_map(|media| match media {
+            MediaType::Png(_) | MediaType::Jpeg(_) | MediaType::Gif(_) | MediaType::Svg(_) => {
+                Some("image".to_string())
+            }
+            MediaType::Html(_) => Some("HTML".to_string()),
+            MediaType::Markdown(text) => Some(first_line(text)),
+            _ => None,
+        })
+        .unwrap_or_else(|| "rich output".to_string())
+}
+
+fn first_line(text: &str) -> String {
+    let text = strip_ansi(text);
+    let mut lines = text.trim().lines();
+    let first = lines.next().unwrap_or_default().to_string();
+    if lines.next().is_some() {
+        format!("{first} …")
+    } else {
+        first
+    }
+}
+
+/// Removes ANSI escape sequences, which kernels use to color tracebacks.
+fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+            continue;
+        }
+        if chars.next_if_eq(&'[').is_some() {
+            // CSI sequences end with a byte in the range `@`..=`~`.
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    stripped
+}
+
+fn outputs(cell: &Cell) -> &[Output] {
+    match cell {
+        Cell::Code { outputs, .. } => outputs,
+        Cell::Markdown { .. } | Cell::Raw { .. } => &[],
+    }
+}
+
+fn execution_count(cell: &Cell) -> Option<i32> {
+    match cell {
+        Cell::Code {
+            execution_count, ..
+        } => *execution_count,
+        Cell::Markdown { .. } | Cell::Raw { .. } => None,
+    }
+}
+
+fn to_json(value: &impl serde::Serialize) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn code(id: &str, source: &str, execution_count: i32) -> serde_json::Value {
+        json!({
+            "cell_type": "code",
+            "id": id,
+            "metadata": {},
+            "execution_count": execution_count,
+            "source": source,
+            "outputs": [{ "output_type": "stream", "name": "stdout", "text": "out\n" }],
+        })
+    }
+
+    fn markdown(id: &str, source: &str) -> serde_json::Value {
+        json!({ "cell_type": "markdown", "id": id, "metadata": {}, "source": source })
+    }
+
+    fn notebook(cells: Vec<serde_json::Value>) -> Notebook {
+        parse_notebook(
+            &json!({ "metadata": {}, "nbformat": 4, "nbformat_minor": 5, "cells": cells })
+                .to_string(),
+        )
+        .unwrap()
+    }
+
+    fn changes(diffs: &[CellDiff], ignore_outputs: bool) -> Vec<(CellChange, String)> {
+        diffs
+            .iter()
+            .map(|diff| {
+                let source = if diff.new.is_some() {
+                    diff.new_source()
+                } else {
+                    diff.old_source()
+                };
+                (diff.change(ignore_outputs), source)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pairs_cells_by_id() {
+        let old = notebook(vec![
+            markdown("a", "# Title"),
+            code("b", "x = 1", 1),
+            code("c", "print(x)", 2),
+        ]);
+        let new = notebook(vec![
+            markdown("a", "# Title"),
+            code("c", "print(x + 1)", 2),
+            code("d", "y = 2", 3),
+        ]);
+
+        assert_eq!(
+            changes(&diff_notebooks(&old, &new), false),
+            vec![
+                (CellChange::Unchanged, "# Title".into()),
+                (CellChange::Removed, "x = 1".into()),
+                (CellChange::Modified, "print(x + 1)".into()),
+                (CellChange::Added, "y = 2".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pairs_cells_without_ids_by_position() {
+        let without_ids = |cells: Vec<serde_json::Value>| {
+            notebook(
+                cells
+                    .into_iter()
+                    .map(|mut cell| {
+                        cell.as_object_mut().unwrap().remove("id");
+                        cell
+                    })
+                    .collect(),
+            )
+        };
+        let old = without_ids(vec![
+            code("", "import os", 1),
+            code("", "a = 1", 2),
+            markdown("", "notes"),
+        ]);
+        let new = without_ids(vec![
+            code("", "import os", 1),
+            code("", "a = 2", 2),
+            markdown("", "notes"),
+        ]);
+
+        assert_eq!(
+            changes(&diff_notebooks(&old, &new), false),
+            vec![
+                (CellChange::Unchanged, "import os".into()),
+                (CellChange::Modified, "a = 2".into()),
+                (CellChange::Unchanged, "notes".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_output_and_metadata_changes_can_be_ignored() {
+        let old = notebook(vec![code("a", "run()", 1)]);
+        let mut rerun = code("a", "run()", 7);
+        rerun["outputs"] = json!([]);
+        let new = notebook(vec![rerun]);
+
+        let diffs = diff_notebooks(&old, &new);
+        assert!(!diffs[0].source_changed());
+        assert!(diffs[0].outputs_changed());
+        assert!(diffs[0].metadata_changed());
+        assert_eq!(diffs[0].change(false), CellChange::Modified);
+        assert_eq!(diffs[0].change(true), CellChange::Unchanged);
+    }
+
+    #[test]
+    fn test_parse_empty_notebook() {
+        assert!(parse_notebook("").unwrap().cells.is_empty());
+        assert!(parse_notebook("{").is_err());
+    }
+}
@@ -0,0 +1,778 @@
+//! A cell-by-cell view of the uncommitted changes to a Jupyter notebook.
+//!
+//! Both versions of the `.ipynb` file are parsed, their cells are paired up,
+//! and only source changes get an editor. Output, execution count and metadata
+//! changes are summarized in a collapsed line per cell, or hidden entirely.
+
+use crate::git_panel::GitStatusEntry;
+use crate::notebook_diff::{
+    CellChange, CellDiff, CellKind, diff_notebooks, notebook_metadata_changed, output_summary,
+    parse_notebook,
+};
+use anyhow::Result;
+use buffer_diff::{BufferDiff, BufferDiffEvent, DiffBaseKind};
+use collections::HashSet;
+use editor::{Editor, EditorMode, MinimapVisibility, RestoreOnlyDiffHunkDelegate, SizingBehavior};
+use git::{StageFile, ToggleStaged, UnstageFile, repository::RepoPath, status::StageStatus};
+use gpui::{
+    AnyElement, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, FocusHandle,
+    Focusable, IntoElement, Render, ScrollHandle, Subscription, Task, WeakEntity, Window, actions,
+};
+use language::{Buffer, Capability, Language, Point, language_settings::SoftWrap};
+use multi_buffer::MultiBuffer;
+use nbformat::v4::{Notebook, Output};
+use project::{
+    Project, ProjectPath,
+    git_store::{Repository, RepositoryId},
+    project_settings::DiagnosticSeverity,
+};
+use std::sync::Arc;
+use ui::{Checkbox, Disclosure, Divider, Indicator, Tooltip, prelude::*};
+use util::paths::PathStyle;
+use workspace::{Item, Workspace, item::ItemEvent, notifications::NotifyTaskExt};
+
+actions!(
+    git,
+    [
+        /// Opens the current file in a plain text diff, bypassing the cell-by-cell
+        /// diff used for notebooks.
+        OpenTextDiff,
+    ]
+);
+
+pub const NOTEBOOK_EXTENSION: &str = "ipynb";
+
+pub struct NotebookDiffView {
+    project: Entity<Project>,
+    repository: Entity<Repository>,
+    repository_id: RepositoryId,
+    repo_path: RepoPath,
+    buffer: Entity<Buffer>,
+    diff: Entity<BufferDiff>,
+    workspace: WeakEntity<Workspace>,
+    focus_handle: FocusHandle,
+    scroll_handle: ScrollHandle,
+    code_language: Option<Arc<Language>>,
+    markdown_language: Option<Arc<Language>>,
+    cells: Vec<CellEntry>,
+    notebook_metadata_changed: bool,
+    parse_error: Option<SharedString>,
+    ignore_outputs_and_metadata: bool,
+    expanded_details: HashSet<String>,
+    _refresh_task: Task<Result<()>>,
+    _diff_subscription: Subscription,
+}
+
+struct CellEntry {
+    diff: CellDiff,
+    editor: Option<Entity<Editor>>,
+}
+
+impl CellEntry {
+    /// Identifies the cell across refreshes, so expanded details stay expanded.
+    fn key(&self) -> String {
+        self.diff
+            .new
+            .as_ref()
+            .or(self.diff.old.as_ref())
+            .map(|(_, cell)| cell.id().as_str().to_string())
+            .unwrap_or_default()
+    }
+}
+
+pub fn is_notebook(repo_path: &RepoPath) -> bool {
+    repo_path.extension() == Some(NOTEBOOK_EXTENSION)
+}
+
+impl NotebookDiffView {
+    pub fn open_or_focus(
+        entry: GitStatusEntry,
+        repository: Entity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let Some(workspace_entity) = workspace.upgrade() else {
+            return Task::ready(Err(anyhow::anyhow!("workspace was dropped")));
+        };
+
+        let existing = workspace_entity
+            .read(cx)
+            .items_of_type::<NotebookDiffView>(cx)
+            .find(|item| item.read(cx).matches(&repository, &entry.repo_path, cx));
+        if let Some(existing) = existing {
+            workspace_entity.update(cx, |workspace, cx| {
+                workspace.activate_item(&existing, true, true, window, cx);
+            });
+            existing.focus_handle(cx).focus(window, cx);
+            return Task::ready(Ok(existing));
+        }
+
+        let Some(project_path) = repository
+            .read(cx)
+            .repo_path_to_project_path(&entry.repo_path, cx)
+        else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "could not resolve repository path {:?}",
+                entry.repo_path
+            )));
+        };
+
+        let project = workspace_entity.read(cx).project().clone();
+        let languages = project.read(cx).languages().clone();
+        let repo_path = entry.repo_path;
+        window.spawn(cx, async move |cx| {
+            let buffer = project
+                .update(cx, |project, cx| {
+                    project.open_buffer(project_path.clone(), cx)
+                })
+                .await?;
+            let diff = project
+                .update(cx, |project, cx| {
+                    project.open_uncommitted_diff(buffer.clone(), cx)
+                })
+                .await?;
+
+            let text = buffer.read_with(cx, |buffer, _| buffer.text());
+            let code_language = match parse_notebook(&text)
+                .ok()
+                .as_ref()
+                .and_then(notebook_language_name)
+            {
+                Some(name) => languages.language_for_name(&name).await.ok(),
+                None => None,
+            };
+            let markdown_language = languages.language_for_name("Markdown").await.ok();
+
+            workspace_entity.update_in(cx, |workspace, window, cx| {
+                let view = cx.new(|cx| {
+                    Self::new(
+                        project,
+                        repository,
+                        repo_path,
+                        buffer,
+                        diff,
+                        code_language,
+                        markdown_language,
+                        workspace.weak_handle(),
+                        window,
+                        cx,
+                    )
+                });
+
+                workspace.add_item_to_active_pane(Box::new(view.clone()), None, true, window, cx);
+                view
+            })
+        })
+    }
+
+    fn new(
+        project: Entity<Project>,
+        repository: Entity<Repository>,
+        repo_path: RepoPath,
+        buffer: Entity<Buffer>,
+        diff: Entity<BufferDiff>,
+        code_language: Option<Arc<Language>>,
+        markdown_language: Option<Arc<Language>>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let diff_subscription =
+            cx.subscribe_in(&diff, window, |this, _, event, window, cx| match event {
+                BufferDiffEvent::BaseTextChanged | BufferDiffEvent::DiffChanged(_) => {
+                    this.refresh(window, cx);
+                }
+            });
+
+        let mut this = Self {
+            project,
+            repository_id: repository.read(cx).id,
+            repository,
+            repo_path,
+            buffer,
+            diff,
+            workspace,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: ScrollHandle::new(),
+            code_language,
+            markdown_language,
+            cells: Vec::new(),
+            notebook_metadata_changed: false,
+            parse_error: None,
+            ignore_outputs_and_metadata: false,
+            expanded_details: HashSet::default(),
+            _refresh_task: Task::ready(Ok(())),
+            _diff_subscription: diff_subscription,
+        };
+        this.refresh(window, cx);
+        this
+    }
+
+    fn matches(&self, repository: &Entity<Repository>, repo_path: &RepoPath, cx: &App) -> bool {
+        self.repository_id == repository.read(cx).id && &self.repo_path == repo_path
+    }
+
+    fn refresh(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let old_text = self.diff.read(cx).base_text_string(cx).unwrap_or_default();
+        let new_text = self.buffer.read(cx).text();
+
+        self._refresh_task = cx.spawn_in(window, async move |this, cx| {
+            let result = cx
+                .background_spawn(async move {
+                    let old = parse_notebook(&old_text)?;
+                    let new = parse_notebook(&new_text)?;
+                    anyhow::Ok((
+                        diff_notebooks(&old, &new),
+                        notebook_metadata_changed(&old, &new),
+                    ))
+                })
+                .await;
+
+            this.update_in(cx, |this, window, cx| {
+                match result {
+                    Ok((cells, metadata_changed)) => {
+                        this.parse_error = None;
+                        this.notebook_metadata_changed = metadata_changed;
+                        let cells = cells
+                            .into_iter()
+                            .map(|diff| {
+                                let editor = diff
+                                    .source_changed()
+                                    .then(|| this.build_cell_editor(&diff, window, cx));
+                                CellEntry { diff, editor }
+                            })
+                            .collect();
+                        this.cells = cells;
+                    }
+                    Err(error) => {
+                        this.parse_error = Some(format!("{error:#}").into());
+                        this.cells.clear();
+                    }
+                }
+                cx.notify();
+            })
+        });
+    }
+
+    fn build_cell_editor(
+        &self,
+        cell_diff: &CellDiff,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<Editor> {
+        let language = match cell_diff.kind() {
+            CellKind::Code => self.code_language.clone(),
+            CellKind::Markdown => self.markdown_language.clone(),
+            CellKind::Raw => None,
+        };
+        let new_buffer = read_only_buffer(cell_diff.new_source(), language.clone(), cx);
+        let old_buffer = read_only_buffer(cell_diff.old_source(), language, cx);
+        let buffer_diff = cx.new(|cx| {
+            BufferDiff::new_with_base_text_buffer(
+                &new_buffer.read(cx).snapshot().text,
+                old_buffer.clone(),
+                DiffBaseKind::Custom,
+                cx,
+            )
+        });
+
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::without_headers(Capability::ReadOnly);
+            let max_point = new_buffer.read(cx).max_point();
+            multibuffer.set_excerpts_for_buffer(
+                new_buffer.clone(),
+                [Point::zero()..max_point],
+                0,
+                cx,
+            );
+            multibuffer.add_diff(buffer_diff.clone(), cx);
+            multibuffer
+        });
+
+        cx.spawn(async move |_, cx| {
+            update_cell_diff(&buffer_diff, &new_buffer, &old_buffer, cx).await;
+        })
+        .detach();
+
+        let project = self.project.clone();
+        cx.new(|cx| {
+            let mut editor = Editor::new(
+                EditorMode::Full {
+                    scale_ui_elements_with_buffer_font_size: false,
+                    show_active_line_background: false,
+                    sizing_behavior: SizingBehavior::SizeByContent,
+                },
+                multibuffer,
+                Some(project),
+                window,
+                cx,
+            );
+            editor.disable_diagnostics(cx);
+            editor.set_max_diagnostics_severity(DiagnosticSeverity::Off, cx);
+            editor.disable_expand_excerpt_buttons(cx);
+            editor.set_show_vertical_scrollbar(false, cx);
+            editor.set_minimap_visibility(MinimapVisibility::Disabled, window, cx);
+            editor.set_soft_wrap_mode(SoftWrap::None, cx);
+            editor.set_forbid_vertical_scroll(true);
+            editor.set_show_indent_guides(false, cx);
+            editor.set_read_only(true);
+            editor.set_show_bookmarks(false, cx);
+            editor.set_show_breakpoints(false, cx);
+            editor.set_show_code_actions(false, cx);
+            editor.set_expand_all_diff_hunks(cx);
+            editor.set_diff_hunk_delegate(Some(Arc::new(RestoreOnlyDiffHunkDelegate)), cx);
+            editor
+        })
+    }
+
+    fn toggle_ignore_outputs_and_metadata(&mut self, cx: &mut Context<Self>) {
+        self.ignore_outputs_and_metadata = !self.ignore_outputs_and_metadata;
+        cx.notify();
+    }
+
+    fn toggle_details(&mut self, key: String, cx: &mut Context<Self>) {
+        if !self.expanded_details.remove(&key) {
+            self.expanded_details.insert(key);
+        }
+        cx.notify();
+    }
+
+    fn stage_status(&self, cx: &App) -> StageStatus {
+        self.repository
+            .read(cx)
+            .status_for_path(&self.repo_path)
+            .map(|entry| entry.status.staging())
+            .unwrap_or(StageStatus::Unstaged)
+    }
+
+    fn change_file_stage(&self, stage: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let repository = self.repository.clone();
+        let repo_path = self.repo_path.clone();
+        let task = cx.spawn(async move |_, cx| {
+            repository
+                .update(cx, |repository, cx| {
+                    if stage {
+                        repository.stage_entries(vec![repo_path], cx)
+                    } else {
+                        repository.unstage_entries(vec![repo_path], cx)
+                    }
+                })
+                .await
+        });
+        task.detach_and_notify_err(self.workspace.clone(), window, cx);
+    }
+
+    fn toggle_staged(&mut self, _: &ToggleStaged, window: &mut Window, cx: &mut Context<Self>) {
+        let stage = self.stage_status(cx).has_unstaged();
+        self.change_file_stage(stage, window, cx);
+    }
+
+    fn open_text_diff(&mut self, _: &OpenTextDiff, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(status_entry) = self.repository.read(cx).status_for_path(&self.repo_path) else {
+            return;
+        };
+        let entry = GitStatusEntry {
+            repo_path: self.repo_path.clone(),
+            status: status_entry.status,
+            staging: status_entry.status.staging(),
+            diff_stat: status_entry.diff_stat,
+        };
+        crate::open_text_diff(
+            entry,
+            self.repository.clone(),
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let changed_cells = self
+            .cells
+            .iter()
+            .filter(|cell| {
+                cell.diff.change(self.ignore_outputs_and_metadata) != CellChange::Unchanged
+            })
+            .count();
+        let stage_status = self.stage_status(cx);
+
+        h_flex()
+            .w_full()
+            .px_3()
+            .py_2()
+            .gap_2()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new(match changed_cells {
+                    0 => "No cell changes".to_string(),
+                    1 => "1 cell changed".to_string(),
+                    count => format!("{count} cells changed"),
+                })
+                .color(Color::Muted),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Checkbox::new(
+                            "ignore-outputs-and-metadata",
+                            self.ignore_outputs_and_metadata.into(),
+                        )
+                        .label("Ignore Outputs and Metadata")
+                        .on_click(cx.listener(
+                            |this, _: &ToggleState, _, cx| {
+                                this.toggle_ignore_outputs_and_metadata(cx)
+                            },
+                        )),
+                    )
+                    .child(Divider::vertical())
+                    .child(
+                        Button::new("text-diff", "Text Diff")
+                            .tooltip(Tooltip::for_action_title_in(
+                                "Show the Raw Notebook Diff",
+                                &OpenTextDiff,
+                                &self.focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.open_text_diff(&OpenTextDiff, window, cx)
+                            })),
+                    )
+                    .child(Divider::vertical())
+                    .child(
+                        Button::new("stage-file", "Stage File")
+                            .disabled(!stage_status.has_unstaged())
+                            .tooltip(Tooltip::for_action_title_in(
+                                "Stage File",
+                                &StageFile,
+                                &self.focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.change_file_stage(true, window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("unstage-file", "Unstage File")
+                            .disabled(!stage_status.has_staged())
+                            .tooltip(Tooltip::for_action_title_in(
+                                "Unstage File",
+                                &UnstageFile,
+                                &self.focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.change_file_stage(false, window, cx)
+                            })),
+                    ),
+            )
+    }
+
+    fn render_cell(&self, ix: usize, cell: &CellEntry, cx: &mut Context<Self>) -> AnyElement {
+        let change = cell.diff.change(self.ignore_outputs_and_metadata);
+        let (status, color) = match change {
+            CellChange::Added => ("added", Color::VersionControlAdded),
+            CellChange::Removed => ("removed", Color::VersionControlDeleted),
+            CellChange::Modified => ("modified", Color::VersionControlModified),
+            CellChange::Unchanged => ("unchanged", Color::Muted),
+        };
+        let kind = match cell.diff.kind() {
+            CellKind::Code => "Code",
+            CellKind::Markdown => "Markdown",
+            CellKind::Raw => "Raw",
+        };
+        let number = cell
+            .diff
+            .new
+            .as_ref()
+            .or(cell.diff.old.as_ref())
+            .map_or(0, |(ix, _)| ix + 1);
+
+        let details = self.detail_summaries(&cell.diff);
+        let key = cell.key();
+        let expanded = self.expanded_details.contains(&key);
+
+        v_flex()
+            .id(("notebook-diff-cell", ix))
+            .w_full()
+            .border_1()
+            .rounded_sm()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .child(Indicator::dot().color(color))
+                    .child(Label::new(format!("{kind} Cell {number}")).size(LabelSize::Small))
+                    .child(
+                        Label::new(status)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .when(!details.is_empty(), |this| {
+                        this.child(
+                            Disclosure::new(("notebook-diff-details", ix), expanded).on_click(
+                                cx.listener(move |this, _, _, cx| {
+                                    this.toggle_details(key.clone(), cx)
+                                }),
+                            ),
+                        )
+                        .child(
+                            Label::new(details.join(" · "))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        )
+                    }),
+            )
+            .when(expanded && !details.is_empty(), |this| {
+                this.child(self.render_output_details(&cell.diff, cx))
+            })
+            .when_some(cell.editor.clone(), |this, editor| {
+                this.child(
+                    div()
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(editor),
+                )
+            })
+            .into_any_element()
+    }
+
+    /// Short descriptions of everything but source changes, which are hidden
+    /// when outputs and metadata are ignored.
+    fn detail_summaries(&self, cell_diff: &CellDiff) -> Vec<String> {
+        if self.ignore_outputs_and_metadata || cell_diff.old.is_none() || cell_diff.new.is_none() {
+            return Vec::new();
+        }
+
+        let mut details = Vec::new();
+        if cell_diff.outputs_changed() {
+            details.push(format!(
+                "outputs changed ({} → {})",
+                cell_diff.old_outputs().len(),
+                cell_diff.new_outputs().len()
+            ));
+        }
+        let old_count = cell_diff.old_execution_count();
+        let new_count = cell_diff.new_execution_count();
+        if old_count != new_count {
+            let count = |count: Option<i32>| count.map_or("none".to_string(), |c| c.to_string());
+            details.push(format!(
+                "execution count {} → {}",
+                count(old_count),
+                count(new_count)
+            ));
+        } else if cell_diff.metadata_changed() {
+            details.push("metadata changed".to_string());
+        }
+        details
+    }
+
+    fn render_output_details(&self, cell_diff: &CellDiff, cx: &App) -> impl IntoElement {
+        let output_lines = |outputs: &[Output], sign: &str, color: Color| {
+            outputs
+                .iter()
+                .map(|output| {
+                    Label::new(format!("{sign} {}", output_summary(output)))
+                        .size(LabelSize::Small)
+                        .color(color)
+                        .buffer_font(cx)
+                        .into_any_element()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        v_flex()
+            .px_2()
+            .pb_1()
+            .children(output_lines(
+                cell_diff.old_outputs(),
+                "-",
+                Color::VersionControlDeleted,
+            ))
+            .children(output_lines(
+                cell_diff.new_outputs(),
+                "+",
+                Color::VersionControlAdded,
+            ))
+    }
+}
+
+/// The notebook's kernel language, as recorded in its metadata.
+fn notebook_language_name(notebook: &Notebook) -> Option<String> {
+    notebook
+        .metadata
+        .language_info
+        .as_ref()
+        .map(|info| info.name.clone())
+        .or_else(|| {
+            notebook
+                .metadata
+                .kernelspec
+                .as_ref()
+                .and_then(|spec| spec.language.clone())
+        })
+}
+
+fn read_only_buffer(text: String, language: Option<Arc<Language>>, cx: &mut App) -> Entity<Buffer> {
+    cx.new(|cx| {
+        let mut buffer = Buffer::local(text, cx);
+        buffer.set_language(language, cx);
+        buffer.set_capability(Capability::ReadOnly, cx);
+        buffer
+    })
+}
+
+async fn update_cell_diff(
+    diff: &Entity<BufferDiff>,
+    new_buffer: &Entity<Buffer>,
+    old_buffer: &Entity<Buffer>,
+    cx: &mut AsyncApp,
+) {
+    let new_snapshot = new_buffer.read_with(cx, |buffer, _| buffer.snapshot());
+    let old_snapshot = old_buffer.read_with(cx, |buffer, _| buffer.snapshot());
+    let base_text = Arc::<str>::from(old_snapshot.text());
+
+    let update = diff
+        .update(cx, |diff, cx| {
+            diff.update_diff(
+                new_snapshot.text.clone(),
+                &old_snapshot,
+                Some(base_text),
+                cx,
+            )
+        })
+        .await;
+
+    diff.update(cx, |diff, cx| diff.set_snapshot(update, cx));
+}
+
+impl EventEmitter<ItemEvent> for NotebookDiffView {}
+
+impl Focusable for NotebookDiffView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for NotebookDiffView {
+    type Event = ItemEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Diff).color(Color::Muted))
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        self.repo_path
+            .file_name()
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| {
+                self.repo_path
+                    .as_ref()
+                    .display(PathStyle::local())
+                    .into_owned()
+            })
+            .into()
+    }
+
+    fn tab_tooltip_text(&self, _cx: &App) -> Option<SharedString> {
+        Some(
+            self.repo_path
+                .as_ref()
+                .display(PathStyle::local())
+                .into_owned()
+                .into(),
+        )
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Notebook Diff View Opened")
+    }
+
+    fn active_project_path(&self, cx: &App) -> Option<ProjectPath> {
+        self.repository
+            .read(cx)
+            .repo_path_to_project_path(&self.repo_path, cx)
+    }
+
+    fn to_item_events(event: &Self::Event, f: &mut dyn FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+impl Render for NotebookDiffView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut cells = Vec::new();
+        let mut unchanged_run = 0;
+        let flush_unchanged = |cells: &mut Vec<AnyElement>, unchanged_run: &mut usize| {
+            if *unchanged_run > 0 {
+                let label = if *unchanged_run == 1 {
+                    "1 unchanged cell".to_string()
+                } else {
+                    format!("{unchanged_run} unchanged cells")
+                };
+                cells.push(
+                    Label::new(label)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .into_any_element(),
+                );
+                *unchanged_run = 0;
+            }
+        };
+
+        for (ix, cell) in self.cells.iter().enumerate() {
+            if cell.diff.change(self.ignore_outputs_and_metadata) == CellChange::Unchanged {
+                unchanged_run += 1;
+                continue;
+            }
+            flush_unchanged(&mut cells, &mut unchanged_run);
+            cells.push(self.render_cell(ix, cell, cx));
+        }
+        flush_unchanged(&mut cells, &mut unchanged_run);
+
+        v_flex()
+            .key_context("NotebookDiffView")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::toggle_staged))
+            .on_action(cx.listener(Self::open_text_diff))
+            .on_action(cx.listener(|this, _: &StageFile, window, cx| {
+                this.change_file_stage(true, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &UnstageFile, window, cx| {
+                this.change_file_stage(false, window, cx)
+            }))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_header(cx))
+            .child(
+                v_flex()
+                    .id("notebook-diff-cells")
+                    .flex_1()
+                    .min_h_0()
+                    .p_3()
+                    .gap_2()
+                    .overflow_y_scroll()
+                    .track_scroll(&self.scroll_handle)
+                    .when_some(self.parse_error.clone(), |this, error| {
+                        this.child(
+                            Label::new(format!("Could not parse notebook: {error}"))
+                                .color(Color::Error),
+                        )
+                    })
+                    .when(
+                        self.notebook_metadata_changed && !self.ignore_outputs_and_metadata,
+                        |this| {
+                            this.child(
+                                Label::new("Notebook metadata changed")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        },
+                    )
+                    .children(cells),
+            )
+    }
+}
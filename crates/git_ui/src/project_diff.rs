@@ -2,6 +2,7 @@ use crate::{
     branch_diff::BranchDiff,
     diff_multibuffer::DiffMultibuffer,
     git_panel::{GitPanel, GitPanelAddon, GitStatusEntry},
+    notebook_diff_view::NOTEBOOK_EXTENSION,
     staged_diff::StagedDiff,
     unstaged_diff::UnstagedDiff,
 };
@@ -11,7 +12,7 @@ use editor::{
     Editor, EditorEvent, SplittableEditor, UncommittedDiffHunkDelegate,
     actions::{GoToHunk, GoToPreviousHunk, SendReviewToAgent},
 };
-use git::{Commit, StageAll, StageAndNext, ToggleStaged, UnstageAll, UnstageAndNext};
+use git::{Commit, OpenFileDiff, StageAll, StageAndNext, ToggleStaged, UnstageAll, UnstageAndNext};
 use gpui::{
     Action, App, AppContext as _, Entity, EventEmitter, FocusHandle, Focusable, Render,
     Subscription, Task, WeakEntity, actions,
@@ -52,7 +53,7 @@ actions!(
         ToggleDiffBase,
         /// Adds files to the git staging area.
         Add,
-        /// Opens a new agent thread with the branch diff for review.
+        /// Opens a new agent thread that reviews the diff and comments on it.
         ReviewDiff,
         LeaderAndFollower,
         /// Compare with a specific branch
@@ -813,6 +814,10 @@ impl Render for ProjectDiffToolbar {
 
         let (additions, deletions) = project_diff.read(cx).calculate_changed_lines(cx);
         let is_multibuffer_empty = project_diff.read(cx).multibuffer(cx).read(cx).is_empty();
+        let is_notebook = project_diff
+            .read(cx)
+            .active_project_path(cx)
+            .is_some_and(|path| path.path.extension() == Some(NOTEBOOK_EXTENSION));
 
         let stage_all_button_width = rems(5.);
 
@@ -830,6 +835,20 @@ impl Render for ProjectDiffToolbar {
                 ))
                 .child(Divider::vertical().ml_1())
             })
+            .when(is_notebook, |this| {
+                this.child(
+                    Button::new("notebook-diff", "Cell Diff")
+                        .tooltip(Tooltip::for_action_title_in(
+                            "Show Notebook Changes Cell by Cell",
+                            &OpenFileDiff,
+                            &focus_handle,
+                        ))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.dispatch_action(&OpenFileDiff, window, cx)
+                        })),
+                )
+                .child(Divider::vertical())
+            })
             // n.b. the only reason these arrows are here is because we don't
             // support "undo" for staging so we need a way to go back.
             .child(
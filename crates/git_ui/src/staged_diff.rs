@@ -1,14 +1,16 @@
 use crate::{
     diff_multibuffer::DiffMultibuffer,
     git_panel::{GitPanel, GitPanelAddon, GitStatusEntry},
+    project_diff::ReviewDiff,
 };
+use agent_settings::AgentSettings;
 use anyhow::{Context as _, Result};
 use buffer_diff::DiffHunkStatus;
 use editor::{
     DiffHunkDelegate, Editor, EditorEvent, ResolvedDiffHunks, SplittableEditor,
     actions::{GoToHunk, GoToPreviousHunk},
 };
-use git::{Commit, UnstageAll, UnstageAndNext};
+use git::{Commit, UnstageAll, UnstageAndNext, repository::DiffType};
 use gpui::{
     Action, AnyElement, App, Context, Entity, EventEmitter, FocusHandle, Focusable, Render,
     SharedString, Subscription, Task, WeakEntity,
@@ -31,8 +33,10 @@ use workspace::{
     ItemNavHistory, SerializableItem, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView,
     Workspace,
     item::{Item, ItemEvent, ItemHandle, SaveOptions},
+    notifications::NotifyTaskExt,
     searchable::SearchableItemHandle,
 };
+use zed_actions::agent::ReviewChanges;
 
 pub(crate) struct StagedDiffDelegate;
 
@@ -301,6 +305,39 @@ impl StagedDiff {
             diff.stage_or_unstage_selected_hunks(false, move_to_next, window, cx)
         });
     }
+
+    fn review_diff(&mut self, _: &ReviewDiff, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repo) = self.diff.read(cx).repo(cx) else {
+            return;
+        };
+
+        let diff_receiver = repo.update(cx, |repo, cx| repo.diff(DiffType::HeadToIndex, cx));
+
+        let workspace = self.workspace.clone();
+        window
+            .spawn(cx, {
+                let workspace = workspace.clone();
+                async move |cx| {
+                    let diff_text = diff_receiver.await??;
+
+                    if let Some(workspace) = workspace.upgrade() {
+                        workspace.update_in(cx, |_workspace, window, cx| {
+                            window.dispatch_action(
+                                ReviewChanges {
+                                    diff_text: diff_text.into(),
+                                    subject: "the staged changes".into(),
+                                }
+                                .boxed_clone(),
+                                cx,
+                            );
+                        })?;
+                    }
+
+                    anyhow::Ok(())
+                }
+            })
+            .detach_and_notify_err(workspace, window, cx);
+    }
 }
 
 struct ButtonStates {
@@ -526,8 +563,11 @@ impl SerializableItem for StagedDiff {
 }
 
 impl Render for StagedDiff {
-    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
-        self.diff.clone()
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .size_full()
+            .on_action(cx.listener(Self::review_diff))
+            .child(self.diff.clone())
     }
 }
 
@@ -625,6 +665,7 @@ impl Render for StagedDiffToolbar {
         let diff = staged_diff.read(cx).diff.read(cx);
         let (additions, deletions) = diff.calculate_changed_lines(cx);
         let is_multibuffer_empty = diff.multibuffer().read(cx).is_empty();
+        let show_review_button = !is_multibuffer_empty && AgentSettings::get_global(cx).enabled(cx);
 
         h_flex()
             .my_neg_1()
@@ -723,6 +764,24 @@ impl Render for StagedDiffToolbar {
                         this.dispatch_action(&Commit, window, cx);
                     })),
             )
+            .when(show_review_button, |this| {
+                this.child(Divider::vertical()).child(
+                    Button::new("review-diff", "Review Diff")
+                        .start_icon(
+                            Icon::new(IconName::ZedAssistant)
+                                .size(IconSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .tooltip(Tooltip::for_action_title_in(
+                            "Review the staged changes in a new agent thread",
+                            &ReviewDiff,
+                            &focus_handle,
+                        ))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.dispatch_action(&ReviewDiff, window, cx);
+                        })),
+                )
+            })
     }
 }
 
@@ -40,7 +40,8 @@ use crate::{
     FontId, FontMetrics, FontRun, ForegroundExecutor, GlyphId, GpuSpecs, Hsla, ImageSource, Keymap,
     LineLayout, Pixels, PlatformGestures, PlatformInput, Point, Priority, RenderGlyphParams,
     RenderImage, RenderImageParams, RenderSvgParams, Scene, ShapedGlyph, ShapedRun, SharedString,
-    Size, SvgRenderer, SystemWindowTab, Task, Window, WindowControlArea, hash, point, px, size,
+    Size, SvgRenderer, SystemWindowTab, Task, Window, WindowControlArea, bidi_runs, hash, point,
+    px, size,
 };
 #[cfg(any(target_os = "linux", target_os = "freebsd"))]
 use anyhow::bail;
@@ -1203,6 +1204,35 @@ impl PlatformTextSystem for NoopTextSystem {
                 position += em_width
             }
         }
+
+        // Like the platform text systems, lay out right-to-left runs in visual order.
+        let bidi_runs = bidi_runs(text);
+        if !bidi_runs.is_empty() {
+            let mut x = px(0.);
+            let mut reordered = Vec::with_capacity(glyphs.len());
+            for run in &bidi_runs {
+                let mut run_glyphs = glyphs
+                    .iter()
+                    .filter(|glyph| run.range.contains(&glyph.index))
+                    .collect::<Vec<_>>();
+                if run.is_rtl() {
+                    run_glyphs.reverse();
+                }
+                for glyph in run_glyphs {
+                    reordered.push(ShapedGlyph {
+                        position: point(x, px(0.)),
+                        ..glyph.clone()
+                    });
+                    x += if glyph.is_emoji {
+                        em_width * 2.0
+                    } else {
+                        em_width
+                    };
+                }
+            }
+            glyphs = reordered;
+        }
+
         let mut runs = Vec::default();
         if !glyphs.is_empty() {
             runs.push(ShapedRun {
@@ -1220,6 +1250,7 @@ impl PlatformTextSystem for NoopTextSystem {
             descent: font_size * (metrics.descent / metrics.units_per_em as f32),
             runs,
             len: text.len(),
+            bidi_runs,
         }
     }
 
@@ -0,0 +1,82 @@
+use std::ops::Range;
+
+use unicode_bidi::{BidiClass, BidiInfo};
+
+/// A run of text that the Unicode bidi algorithm lays out in a single direction.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BidiRun {
+    /// The byte range of the run in the line's text.
+    pub range: Range<usize>,
+    /// The embedding level of the run. Runs with odd levels are laid out right to left.
+    pub level: u8,
+}
+
+impl BidiRun {
+    /// Whether this run is laid out right to left.
+    pub fn is_rtl(&self) -> bool {
+        self.level % 2 == 1
+    }
+}
+
+/// Splits a line of text into the runs the Unicode bidi algorithm lays out in a
+/// single direction, in visual order from left to right.
+///
+/// The direction of each paragraph is taken from its first strong character,
+/// which matches how the platform text systems shape it. Text that is laid out
+/// entirely left to right, like most source code, has no runs.
+pub fn bidi_runs(text: &str) -> Vec<BidiRun> {
+    if text.is_ascii() || !text.chars().any(is_rtl_char) {
+        return Vec::new();
+    }
+
+    let bidi_info = BidiInfo::new(text, None);
+    let mut runs = Vec::new();
+    for paragraph in &bidi_info.paragraphs {
+        let (levels, level_runs) = bidi_info.visual_runs(paragraph, paragraph.range.clone());
+        runs.extend(level_runs.into_iter().map(|range| BidiRun {
+            level: levels[range.start].number(),
+            range,
+        }));
+    }
+    if runs.iter().all(|run| !run.is_rtl()) {
+        runs.clear();
+    }
+    runs
+}
+
+fn is_rtl_char(character: char) -> bool {
+    matches!(
+        unicode_bidi::bidi_class(character),
+        BidiClass::R | BidiClass::AL | BidiClass::RLE | BidiClass::RLO | BidiClass::RLI
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runs(text: &str) -> Vec<(Range<usize>, bool)> {
+        bidi_runs(text)
+            .into_iter()
+            .map(|run| (run.range.clone(), run.is_rtl()))
+            .collect()
+    }
+
+    #[test]
+    fn test_left_to_right_text_has_no_runs() {
+        assert_eq!(runs("fn main() {}"), []);
+        assert_eq!(runs("naïve café"), []);
+    }
+
+    #[test]
+    fn test_mixed_direction_runs() {
+        // "ab " and " cd" stay left to right around the Hebrew word.
+        assert_eq!(
+            runs("ab אבג cd"),
+            [(0..3, false), (3..9, true), (9..12, false)]
+        );
+        // A line starting with Arabic is a right-to-left paragraph, so its
+        // trailing left-to-right text comes first visually.
+        assert_eq!(runs("سلام ok"), [(9..11, false), (0..9, true)]);
+    }
+}
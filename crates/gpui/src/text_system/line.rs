@@ -75,6 +75,7 @@ impl ShapedLine {
             descent: layout.descent,
             runs: layout.runs.clone(),
             len,
+            bidi_runs: layout.bidi_runs.clone(),
         });
         self
     }
@@ -362,8 +363,10 @@ fn paint_line(
     window.paint_layer(line_bounds, |window| {
         let padding_top = (line_height - layout.ascent - layout.descent) / 2.;
         let baseline_offset = point(px(0.), padding_top + layout.ascent);
+        let line_decoration_runs = decoration_runs;
         let mut decoration_runs = decoration_runs.iter();
         let mut wraps = wrap_boundaries.iter().peekable();
+        let mut run_start = 0;
         let mut run_end = 0;
         let mut color = black();
         let mut current_underline: Option<(Point<Pixels>, UnderlineStyle)> = None;
@@ -443,6 +446,13 @@ fn paint_line(
 
                 let mut finished_underline: Option<(Point<Pixels>, UnderlineStyle)> = None;
                 let mut finished_strikethrough: Option<(Point<Pixels>, StrikethroughStyle)> = None;
+                // Glyphs of right-to-left text come in decreasing index order,
+                // so their style is looked up from the start of the line again.
+                if glyph.index < run_start {
+                    decoration_runs = line_decoration_runs.iter();
+                    run_start = 0;
+                    run_end = 0;
+                }
                 if glyph.index >= run_end {
                     let mut style_run = decoration_runs.next();
 
@@ -493,6 +503,7 @@ fn paint_line(
                             ));
                         }
 
+                        run_start = run_end;
                         run_end += style_run.len as usize;
                         color = style_run.color;
                     } else {
@@ -606,8 +617,10 @@ fn paint_line_background(
         ),
     );
     window.paint_layer(line_bounds, |window| {
+        let line_decoration_runs = decoration_runs;
         let mut decoration_runs = decoration_runs.iter();
         let mut wraps = wrap_boundaries.iter().peekable();
+        let mut run_start = 0;
         let mut run_end = 0;
         let mut current_background: Option<(Point<Pixels>, Hsla)> = None;
         let text_system = cx.text_system().clone();
@@ -665,6 +678,13 @@ fn paint_line_background(
                 prev_glyph_position = glyph.position;
 
                 let mut finished_background: Option<(Point<Pixels>, Hsla)> = None;
+                // Glyphs of right-to-left text come in decreasing index order,
+                // so their style is looked up from the start of the line again.
+                if glyph.index < run_start {
+                    decoration_runs = line_decoration_runs.iter();
+                    run_start = 0;
+                    run_end = 0;
+                }
                 if glyph.index >= run_end {
                     let mut style_run = decoration_runs.next();
 
@@ -689,6 +709,7 @@ fn paint_line_background(
                                 run_background,
                             ));
                         }
+                        run_start = run_end;
                         run_end += style_run.len as usize;
                     } else {
                         run_end = layout.len;
@@ -793,6 +814,7 @@ mod tests {
                     glyphs: shaped_glyphs,
                 }],
                 len: text.len(),
+                bidi_runs: Vec::new(),
             }),
             text: SharedString::new(text),
             decoration_runs: SmallVec::from(decorations.to_vec()),
@@ -911,6 +933,7 @@ mod tests {
                     },
                 ],
                 len: 6,
+                bidi_runs: Vec::new(),
             }),
             text: "abcdef".into(),
             decoration_runs: SmallVec::new(),
@@ -1,7 +1,10 @@
-use crate::{FontId, GlyphId, Pixels, PlatformTextSystem, Point, SharedString, Size, point, px};
+use crate::{
+    BidiRun, FontId, GlyphId, Pixels, PlatformTextSystem, Point, SharedString, Size, bidi_runs,
+    point, px,
+};
 use collections::FxHashMap;
 use parking_lot::{Mutex, RwLock, RwLockUpgradableReadGuard};
-use smallvec::SmallVec;
+use smallvec::{SmallVec, smallvec};
 use std::{
     borrow::Borrow,
     hash::{Hash, Hasher},
@@ -26,6 +29,9 @@ pub struct LineLayout {
     pub runs: Vec<ShapedRun>,
     /// The length of the line in utf-8 bytes
     pub len: usize,
+    /// The runs of text laid out in a single direction, in visual order.
+    /// Empty when the whole line is laid out left to right.
+    pub bidi_runs: Vec<BidiRun>,
 }
 
 /// A run of text that has been shaped .
@@ -73,6 +79,14 @@ impl LineLayout {
     /// closest_index_for_x returns the character boundary closest to the given x coordinate
     /// (e.g. to handle aligning up/down arrow keys)
     pub fn closest_index_for_x(&self, x: Pixels) -> usize {
+        if !self.bidi_runs.is_empty() {
+            return self
+                .caret_positions()
+                .into_iter()
+                .min_by_key(|(_, caret_x)| (*caret_x - x).abs())
+                .map_or(0, |(index, _)| index);
+        }
+
         let mut prev_index = 0;
         let mut prev_x = px(0.);
 
@@ -103,6 +117,10 @@ impl LineLayout {
 
     /// The x position of the character at the given index
     pub fn x_for_index(&self, index: usize) -> Pixels {
+        if !self.bidi_runs.is_empty() {
+            return self.bidi_x_for_index(&self.visual_runs(), index);
+        }
+
         for run in &self.runs {
             for glyph in &run.glyphs {
                 if glyph.index >= index {
@@ -113,6 +131,142 @@ impl LineLayout {
         self.width
     }
 
+    /// The x position of the caret at each glyph's index and at the end of the line,
+    /// sorted by index.
+    pub fn caret_positions(&self) -> Vec<(usize, Pixels)> {
+        if self.bidi_runs.is_empty() {
+            let mut carets = self
+                .runs
+                .iter()
+                .flat_map(|run| &run.glyphs)
+                .map(|glyph| (glyph.index, glyph.position.x))
+                .chain([(self.len, self.width)])
+                .collect::<Vec<_>>();
+            carets.dedup_by_key(|(index, _)| *index);
+            return carets;
+        }
+
+        let visual_runs = self.visual_runs();
+        let mut indices = visual_runs
+            .iter()
+            .flat_map(|visual_run| visual_run.carets.iter().map(|(index, _)| *index))
+            .collect::<Vec<_>>();
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+            .into_iter()
+            .map(|index| (index, self.bidi_x_for_index(&visual_runs, index)))
+            .collect()
+    }
+
+    /// The x ranges covered by the characters in the given byte range, from left to right.
+    ///
+    /// Text that mixes directions can cover several disjoint ranges.
+    pub fn x_ranges_for_range(&self, range: Range<usize>) -> SmallVec<[Range<Pixels>; 1]> {
+        if self.bidi_runs.is_empty() {
+            return smallvec![self.x_for_index(range.start)..self.x_for_index(range.end)];
+        }
+
+        let mut x_ranges: SmallVec<[Range<Pixels>; 1]> = SmallVec::new();
+        for visual_run in self.visual_runs() {
+            let start = range.start.max(visual_run.run.range.start);
+            let end = range.end.min(visual_run.run.range.end);
+            if start >= end {
+                continue;
+            }
+            let start_x = visual_run.x_for_index(start);
+            let end_x = visual_run.x_for_index(end);
+            let x_range = start_x.min(end_x)..start_x.max(end_x);
+            match x_ranges.last_mut() {
+                Some(last) if last.end == x_range.start => last.end = x_range.end,
+                _ => x_ranges.push(x_range),
+            }
+        }
+        x_ranges
+    }
+
+    /// Pairs each bidi run with the x positions of the carets inside it.
+    fn visual_runs(&self) -> Vec<VisualRun<'_>> {
+        let mut glyphs_by_run = vec![Vec::new(); self.bidi_runs.len()];
+        for glyph in self.runs.iter().flat_map(|run| &run.glyphs) {
+            if let Some(run_ix) = self
+                .bidi_runs
+                .iter()
+                .position(|run| run.range.contains(&glyph.index))
+            {
+                glyphs_by_run[run_ix].push((glyph.index, glyph.position.x));
+            }
+        }
+
+        // Runs are contiguous, so each one ends where the next one starts.
+        let mut visual_runs = Vec::with_capacity(self.bidi_runs.len());
+        let mut end_x = self.width;
+        for (run, mut glyphs) in self.bidi_runs.iter().zip(glyphs_by_run).rev() {
+            glyphs.sort_unstable();
+            glyphs.dedup_by_key(|(index, _)| *index);
+            let start_x = glyphs
+                .iter()
+                .map(|(_, x)| *x)
+                .min()
+                .map_or(end_x, |x| x.min(end_x));
+
+            let mut carets = Vec::with_capacity(glyphs.len() + 2);
+            if run.is_rtl() {
+                // A character's right edge is the left edge of the one logically before it.
+                carets.push((run.range.start, end_x));
+                carets.extend(glyphs.windows(2).map(|pair| (pair[1].0, pair[0].1)));
+                carets.push((run.range.end, glyphs.last().map_or(start_x, |(_, x)| *x)));
+            } else {
+                carets.push((run.range.start, start_x));
+                carets.extend(
+                    glyphs
+                        .iter()
+                        .copied()
+                        .filter(|(index, _)| *index > run.range.start),
+                );
+                carets.push((run.range.end, end_x));
+            }
+            visual_runs.push(VisualRun { run, carets });
+            end_x = start_x;
+        }
+        visual_runs.reverse();
+        visual_runs
+    }
+
+    /// The x position of a caret in a line with right-to-left text.
+    ///
+    /// A caret between two runs belongs to the one with the lower embedding level,
+    /// and the start and end of the line to the paragraph itself. This places
+    /// every caret in its own position, even where the runs change direction.
+    fn bidi_x_for_index(&self, visual_runs: &[VisualRun], index: usize) -> Pixels {
+        let paragraph_level = self.bidi_runs.iter().map(|run| run.level).min();
+        let paragraph_level = paragraph_level.unwrap_or(0);
+        let paragraph_is_rtl = paragraph_level % 2 == 1;
+        let before = visual_runs.iter().find(|visual_run| {
+            visual_run.run.range.start < index && index <= visual_run.run.range.end
+        });
+        let after = visual_runs
+            .iter()
+            .find(|visual_run| visual_run.run.range.contains(&index));
+        let level = |visual_run: Option<&VisualRun>| {
+            visual_run.map_or(paragraph_level, |visual_run| visual_run.run.level)
+        };
+
+        if level(after) <= level(before) {
+            match after {
+                Some(after) => after.x_for_index(index),
+                None if paragraph_is_rtl => Pixels::ZERO,
+                None => self.width,
+            }
+        } else {
+            match before {
+                Some(before) => before.x_for_index(index),
+                None if paragraph_is_rtl => self.width,
+                None => Pixels::ZERO,
+            }
+        }
+    }
+
     /// The corresponding Font at the given index
     pub fn font_id_for_index(&self, index: usize) -> Option<FontId> {
         for run in &self.runs {
@@ -166,6 +320,23 @@ impl LineLayout {
             }
         }
 
+        let mut left_bidi_runs = Vec::new();
+        let mut right_bidi_runs = Vec::new();
+        for run in &self.bidi_runs {
+            if run.range.start < byte_index {
+                left_bidi_runs.push(BidiRun {
+                    range: run.range.start..run.range.end.min(byte_index),
+                    level: run.level,
+                });
+            }
+            if run.range.end > byte_index {
+                right_bidi_runs.push(BidiRun {
+                    range: run.range.start.saturating_sub(byte_index)..run.range.end - byte_index,
+                    level: run.level,
+                });
+            }
+        }
+
         let left = LineLayout {
             font_size: self.font_size,
             width: x_offset,
@@ -173,6 +344,7 @@ impl LineLayout {
             descent: self.descent,
             runs: left_runs,
             len: byte_index,
+            bidi_runs: left_bidi_runs,
         };
 
         let right = LineLayout {
@@ -182,6 +354,7 @@ impl LineLayout {
             descent: self.descent,
             runs: right_runs,
             len: self.len - byte_index,
+            bidi_runs: right_bidi_runs,
         };
 
         (left, right)
@@ -269,6 +442,25 @@ impl LineLayout {
     }
 }
 
+/// A [`BidiRun`] along with the x position of each caret inside it.
+struct VisualRun<'a> {
+    run: &'a BidiRun,
+    /// Sorted by index, and including both ends of the run.
+    carets: Vec<(usize, Pixels)>,
+}
+
+impl VisualRun<'_> {
+    fn x_for_index(&self, index: usize) -> Pixels {
+        let caret_ix = self
+            .carets
+            .partition_point(|(caret_index, _)| *caret_index < index);
+        self.carets
+            .get(caret_ix)
+            .or(self.carets.last())
+            .map_or(Pixels::ZERO, |(_, x)| *x)
+    }
+}
+
 /// A line of text that has been wrapped to fit a given width
 #[derive(Default, Debug)]
 pub struct WrappedLineLayout {
@@ -670,6 +862,7 @@ impl LineLayoutCache {
             let mut layout = self
                 .platform_text_system
                 .layout_line(&text, font_size, runs);
+            layout.bidi_runs = bidi_runs(&text);
 
             if let Some(force_width) = force_width {
                 apply_force_width_to_layout(&mut layout, force_width);
@@ -819,6 +1012,7 @@ impl LineLayoutCache {
         let mut layout = self
             .platform_text_system
             .layout_line(&text, font_size, runs);
+        layout.bidi_runs = bidi_runs(&text);
 
         if let Some(force_width) = force_width {
             apply_force_width_to_layout(&mut layout, force_width);
@@ -1044,9 +1238,16 @@ mod tests {
                 glyphs,
             }],
             len: 0,
+            bidi_runs: Vec::new(),
         }
     }
 
+    /// Lays out text with glyphs 6px wide, reordering right-to-left runs like
+    /// the platform text systems do.
+    fn layout_text(text: &str) -> LineLayout {
+        crate::NoopTextSystem.layout_line(text, px(10.), &[])
+    }
+
     fn glyph_x_positions(layout: &LineLayout) -> Vec<f32> {
         layout.runs[0]
             .glyphs
@@ -1137,4 +1338,64 @@ mod tests {
         let positions = glyph_x_positions(&layout);
         assert_eq!(positions, vec![0.5, 0.5]);
     }
+
+    #[test]
+    fn test_bidi_carets_in_left_to_right_paragraph() {
+        // Visually "ab גבא cd", with the Hebrew word covering 18px..36px.
+        let layout = layout_text("ab אבג cd");
+        let carets = layout
+            .caret_positions()
+            .into_iter()
+            .map(|(index, x)| (index, f32::from(x)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            carets,
+            [
+                (0, 0.),
+                (1, 6.),
+                (2, 12.),
+                (3, 18.),
+                (5, 30.),
+                (7, 24.),
+                (9, 36.),
+                (10, 42.),
+                (11, 48.),
+                (12, 54.),
+            ]
+        );
+        assert_eq!(layout.x_for_index(5), px(30.));
+        assert_eq!(layout.index_for_x(px(25.)), Some(5));
+        assert_eq!(layout.closest_index_for_x(px(25.)), 7);
+        assert_eq!(layout.closest_index_for_x(px(35.)), 9);
+    }
+
+    #[test]
+    fn test_bidi_carets_in_right_to_left_paragraph() {
+        // Visually "ok مالس", starting from the right edge.
+        let layout = layout_text("سلام ok");
+        assert_eq!(layout.x_for_index(0), px(42.));
+        assert_eq!(layout.x_for_index(2), px(36.));
+        assert_eq!(layout.x_for_index(9), px(12.));
+        assert_eq!(layout.x_for_index(10), px(6.));
+        assert_eq!(layout.x_for_index(11), px(0.));
+        assert_eq!(layout.closest_index_for_x(px(40.)), 0);
+    }
+
+    #[test]
+    fn test_bidi_x_ranges_for_range() {
+        let layout = layout_text("ab אבג cd");
+        // The space and the first Hebrew letter, which is drawn at the right end of the word.
+        assert_eq!(
+            layout.x_ranges_for_range(2..5).to_vec(),
+            [px(12.)..px(18.), px(30.)..px(36.)]
+        );
+        assert_eq!(
+            layout.x_ranges_for_range(5..10).to_vec(),
+            [px(18.)..px(30.), px(36.)..px(42.)]
+        );
+        assert_eq!(layout.x_ranges_for_range(0..12).to_vec(), [px(0.)..px(54.)]);
+
+        let layout = layout_text("abc");
+        assert_eq!(layout.x_ranges_for_range(1..2).to_vec(), [px(6.)..px(12.)]);
+    }
 }
@@ -652,6 +652,7 @@ impl CosmicTextSystemState {
                 descent: Pixels::ZERO,
                 runs: Vec::new(),
                 len: text.len(),
+                bidi_runs: Vec::new(),
             };
         };
 
@@ -708,6 +709,7 @@ impl CosmicTextSystemState {
             descent: layout.max_descent.into(),
             runs,
             len: text.len(),
+            bidi_runs: Vec::new(),
         }
     }
 }
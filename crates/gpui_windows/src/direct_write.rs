@@ -630,6 +630,7 @@ impl DirectWriteState {
                 descent,
                 runs,
                 len: text.len(),
+                bidi_runs: Vec::new(),
             })
         }
     }
@@ -0,0 +1,1348 @@
+mod hex_file;
+mod values;
+
+use std::{ops::Range, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use editor::{
+    Editor, EditorSettings, RevealInFileManager,
+    actions::{
+        Copy, MoveDown, MoveLeft, MovePageDown, MovePageUp, MoveRight, MoveToBeginning,
+        MoveToBeginningOfLine, MoveToEnd, MoveToEndOfLine, MoveUp, Paste, Redo, SelectAll,
+        SelectDown, SelectLeft, SelectRight, SelectToBeginning, SelectToEnd, SelectUp, Undo,
+    },
+    items::entry_git_aware_label_color,
+};
+use file_icons::FileIcons;
+use fs::Fs;
+use gpui::{
+    AnyElement, App, ClipboardItem, Context, Div, Entity, EventEmitter, FocusHandle, Focusable,
+    Font, Hsla, KeyDownEvent, MouseButton, MouseDownEvent, MouseMoveEvent, Pixels, Render,
+    ScrollWheelEvent, Subscription, Task, Window, actions, canvas, div, px, relative,
+};
+use project::{Project, ProjectEntryId, ProjectPath};
+use settings::Settings;
+use theme_settings::ThemeSettings;
+use ui::{Tooltip, prelude::*};
+use util::{ResultExt as _, paths::PathExt, size::format_file_size};
+use workspace::{
+    ItemSettings, ToolbarItemLocation, Workspace, WorkspaceId,
+    invalid_item_view::InvalidItemView,
+    item::{HighlightedText, Item, ItemEvent, SaveOptions, TabContentParams},
+    notifications::DetachAndPromptErr as _,
+};
+use zed_actions::hex_editor::OpenInHexEditor;
+
+pub use crate::hex_file::*;
+pub use crate::values::*;
+
+actions!(
+    hex_editor,
+    [
+        /// Focuses the byte pattern search input.
+        FocusSearch,
+        /// Selects the next match of the byte pattern.
+        SelectNextMatch,
+        /// Selects the previous match of the byte pattern.
+        SelectPreviousMatch,
+        /// Focuses the go-to-offset input.
+        GoToOffset,
+        /// Moves the cursor between the hex and the ASCII columns.
+        SwitchColumn,
+        /// Toggles the byte order used to decode the values at the cursor.
+        ToggleEndianness
+    ]
+);
+
+const BYTES_PER_ROW: u64 = 16;
+const MAX_COPY_LEN: u64 = 16 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HexFileEvent {
+    Edited,
+    Saved,
+    Reloaded,
+}
+
+/// A local file that is shown and edited byte by byte in a [`HexEditorView`].
+///
+/// Files are only opened this way on request, through [`OpenInHexEditor`],
+/// so that opening a project file never reads it on the main thread to guess
+/// whether it is binary.
+pub struct HexFileItem {
+    file: Arc<HexFile>,
+    fs: Arc<dyn Fs>,
+    project_path: ProjectPath,
+    entry_id: ProjectEntryId,
+}
+
+impl EventEmitter<HexFileEvent> for HexFileItem {}
+
+impl HexFileItem {
+    pub fn file(&self) -> &Arc<HexFile> {
+        &self.file
+    }
+
+    /// Opens any local file, whether or not it contains text.
+    fn open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Result<Task<Result<Entity<Self>>>> {
+        let project = project.read(cx);
+        anyhow::ensure!(
+            project.is_local(),
+            "The hex editor can only open local files"
+        );
+        let entry = project
+            .entry_for_path(path, cx)
+            .filter(|entry| entry.is_file())
+            .context("The hex editor can only open files")?;
+        let abs_path = project
+            .absolute_path(path, cx)
+            .context("The file has no absolute path")?;
+        let fs = project.fs().clone();
+        let project_path = path.clone();
+        let entry_id = entry.id;
+
+        Ok(cx.spawn(async move |cx| {
+            let file = cx
+                .background_spawn(async move { HexFile::open(&abs_path) })
+                .await?;
+            Ok(cx.new(|_| Self {
+                file: Arc::new(file),
+                fs,
+                project_path,
+                entry_id,
+            }))
+        }))
+    }
+
+    fn write(&mut self, offset: u64, bytes: &[u8], cx: &mut Context<Self>) -> Result<Range<u64>> {
+        let range = self.file.write(offset, bytes)?;
+        cx.emit(HexFileEvent::Edited);
+        cx.notify();
+        Ok(range)
+    }
+
+    fn undo(&mut self, cx: &mut Context<Self>) -> Option<Range<u64>> {
+        let range = self.file.undo()?;
+        cx.emit(HexFileEvent::Edited);
+        cx.notify();
+        Some(range)
+    }
+
+    fn redo(&mut self, cx: &mut Context<Self>) -> Option<Range<u64>> {
+        let range = self.file.redo()?;
+        cx.emit(HexFileEvent::Edited);
+        cx.notify();
+        Some(range)
+    }
+
+    fn save(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let file = self.file.clone();
+        let fs = self.fs.clone();
+        cx.spawn(async move |this, cx| {
+            cx.background_spawn(async move { file.save(fs.as_ref()).await })
+                .await?;
+            this.update(cx, |_, cx| {
+                cx.emit(HexFileEvent::Saved);
+                cx.notify();
+            })
+        })
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let file = self.file.clone();
+        cx.spawn(async move |this, cx| {
+            cx.background_spawn(async move { file.reload() }).await?;
+            this.update(cx, |_, cx| {
+                cx.emit(HexFileEvent::Reloaded);
+                cx.notify();
+            })
+        })
+    }
+}
+
+impl project::ProjectItem for HexFileItem {
+    fn try_open(
+        _: &Entity<Project>,
+        _: &ProjectPath,
+        _: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        None
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        Some(self.entry_id)
+    }
+
+    fn project_path(&self, _: &App) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.file.is_dirty()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Column {
+    Hex,
+    Ascii,
+}
+
+/// An offset / hex / ASCII view of a [`HexFileItem`] that reads only the rows
+/// it displays, and overwrites bytes in place.
+pub struct HexEditorView {
+    item: Entity<HexFileItem>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    search_editor: Entity<Editor>,
+    go_to_offset_editor: Entity<Editor>,
+    top_row: u64,
+    visible_row_count: u64,
+    scroll_remainder: Pixels,
+    cursor: u64,
+    /// The offset the selection started at, when bytes are selected.
+    anchor: Option<u64>,
+    column: Column,
+    /// The first digit typed into the hex column, which is written once the
+    /// second one is typed.
+    pending_nibble: Option<u8>,
+    endianness: Endianness,
+    status: Option<SharedString>,
+    search_task: Option<Task<()>>,
+    _item_subscription: Subscription,
+}
+
+impl HexEditorView {
+    pub fn new(
+        item: Entity<HexFileItem>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let search_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("DE AD ?? EF or \"text\"", window, cx);
+            editor
+        });
+        let go_to_offset_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Offset", window, cx);
+            editor
+        });
+        let item_subscription = cx.subscribe(&item, |this, _, event: &HexFileEvent, cx| {
+            if *event == HexFileEvent::Reloaded {
+                this.move_cursor_to(this.cursor, false, cx);
+            }
+            cx.emit(*event);
+            cx.notify();
+        });
+
+        Self {
+            item,
+            project,
+            focus_handle: cx.focus_handle(),
+            search_editor,
+            go_to_offset_editor,
+            top_row: 0,
+            visible_row_count: 32,
+            scroll_remainder: px(0.),
+            cursor: 0,
+            anchor: None,
+            column: Column::Hex,
+            pending_nibble: None,
+            endianness: Endianness::default(),
+            status: None,
+            search_task: None,
+            _item_subscription: item_subscription,
+        }
+    }
+
+    fn file(&self, cx: &App) -> Arc<HexFile> {
+        self.item.read(cx).file.clone()
+    }
+
+    fn selection(&self) -> Option<Range<u64>> {
+        self.anchor
+            .map(|anchor| anchor.min(self.cursor)..anchor.max(self.cursor) + 1)
+    }
+
+    /// The selected bytes, or the byte at the cursor.
+    fn selection_or_cursor(&self) -> Range<u64> {
+        self.selection().unwrap_or(self.cursor..self.cursor + 1)
+    }
+
+    fn max_top_row(&self, cx: &App) -> u64 {
+        self.file(cx)
+            .len()
+            .div_ceil(BYTES_PER_ROW)
+            .saturating_sub(self.visible_row_count)
+    }
+
+    fn set_top_row(&mut self, row: u64, cx: &mut Context<Self>) {
+        self.top_row = row.min(self.max_top_row(cx));
+        cx.notify();
+    }
+
+    /// Scrolls as little as possible to show the row of `offset`.
+    fn autoscroll(&mut self, offset: u64, cx: &mut Context<Self>) {
+        let row = offset / BYTES_PER_ROW;
+        if row < self.top_row {
+            self.set_top_row(row, cx);
+        } else if row >= self.top_row + self.visible_row_count {
+            self.set_top_row(row + 1 - self.visible_row_count, cx);
+        }
+    }
+
+    fn move_cursor_to(&mut self, offset: u64, extend: bool, cx: &mut Context<Self>) {
+        let offset = offset.min(self.file(cx).len().saturating_sub(1));
+        if extend {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = offset;
+        self.pending_nibble = None;
+        self.autoscroll(offset, cx);
+        cx.notify();
+    }
+
+    fn move_cursor_by(&mut self, delta: i64, extend: bool, cx: &mut Context<Self>) {
+        // Moving past either end of the file leaves the cursor where it is.
+        let len = self.file(cx).len();
+        let Some(offset) = self
+            .cursor
+            .checked_add_signed(delta)
+            .filter(|offset| *offset < len)
+        else {
+            return;
+        };
+        self.move_cursor_to(offset, extend, cx);
+    }
+
+    /// Selects `range` and scrolls its start to the middle of the view.
+    fn reveal_range(&mut self, range: Range<u64>, cx: &mut Context<Self>) {
+        self.anchor = Some(range.start);
+        self.cursor = range.end.saturating_sub(1).max(range.start);
+        self.pending_nibble = None;
+        let row = range.start / BYTES_PER_ROW;
+        self.set_top_row(row.saturating_sub(self.visible_row_count / 2), cx);
+    }
+
+    fn move_left(&mut self, _: &MoveLeft, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(-1, false, cx);
+    }
+
+    fn move_right(&mut self, _: &MoveRight, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(1, false, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(-(BYTES_PER_ROW as i64), false, cx);
+    }
+
+    fn move_down(&mut self, _: &MoveDown, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(BYTES_PER_ROW as i64, false, cx);
+    }
+
+    fn select_left(&mut self, _: &SelectLeft, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(-1, true, cx);
+    }
+
+    fn select_right(&mut self, _: &SelectRight, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(1, true, cx);
+    }
+
+    fn select_up(&mut self, _: &SelectUp, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(-(BYTES_PER_ROW as i64), true, cx);
+    }
+
+    fn select_down(&mut self, _: &SelectDown, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(BYTES_PER_ROW as i64, true, cx);
+    }
+
+    fn move_page_up(&mut self, _: &MovePageUp, _window: &mut Window, cx: &mut Context<Self>) {
+        let page_len = self.visible_row_count * BYTES_PER_ROW;
+        self.move_cursor_to(self.cursor.saturating_sub(page_len), false, cx);
+    }
+
+    fn move_page_down(&mut self, _: &MovePageDown, _window: &mut Window, cx: &mut Context<Self>) {
+        let page_len = self.visible_row_count * BYTES_PER_ROW;
+        self.move_cursor_to(self.cursor.saturating_add(page_len), false, cx);
+    }
+
+    fn move_to_beginning_of_line(
+        &mut self,
+        _: &MoveToBeginningOfLine,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_cursor_to(self.cursor - self.cursor % BYTES_PER_ROW, false, cx);
+    }
+
+    fn move_to_end_of_line(
+        &mut self,
+        _: &MoveToEndOfLine,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let row_start = self.cursor - self.cursor % BYTES_PER_ROW;
+        self.move_cursor_to(row_start + BYTES_PER_ROW - 1, false, cx);
+    }
+
+    fn move_to_beginning(
+        &mut self,
+        _: &MoveToBeginning,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_cursor_to(0, false, cx);
+    }
+
+    fn move_to_end(&mut self, _: &MoveToEnd, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_to(u64::MAX, false, cx);
+    }
+
+    fn select_to_beginning(
+        &mut self,
+        _: &SelectToBeginning,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_cursor_to(0, true, cx);
+    }
+
+    fn select_to_end(&mut self, _: &SelectToEnd, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_to(u64::MAX, true, cx);
+    }
+
+    fn select_all(&mut self, _: &SelectAll, _window: &mut Window, cx: &mut Context<Self>) {
+        self.anchor = Some(0);
+        self.move_cursor_to(u64::MAX, true, cx);
+    }
+
+    fn switch_column(&mut self, _: &SwitchColumn, _window: &mut Window, cx: &mut Context<Self>) {
+        self.column = match self.column {
+            Column::Hex => Column::Ascii,
+            Column::Ascii => Column::Hex,
+        };
+        self.pending_nibble = None;
+        cx.notify();
+    }
+
+    fn toggle_endianness(
+        &mut self,
+        _: &ToggleEndianness,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.endianness = self.endianness.toggled();
+        cx.notify();
+    }
+
+    fn handle_scroll_wheel(
+        &mut self,
+        event: &ScrollWheelEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let line_height = line_height(cx);
+        self.scroll_remainder -= event.delta.pixel_delta(line_height).y;
+        let rows = (self.scroll_remainder / line_height).trunc();
+        if rows == 0. {
+            return;
+        }
+        self.scroll_remainder -= line_height * rows;
+        let top_row = self.top_row.saturating_add_signed(rows as i64);
+        self.set_top_row(top_row, cx);
+    }
+
+    fn handle_byte_mouse_down(
+        &mut self,
+        offset: u64,
+        column: Column,
+        event: &MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.focus_handle.focus(window, cx);
+        self.column = column;
+        self.move_cursor_to(offset, event.modifiers.shift, cx);
+    }
+
+    fn handle_byte_mouse_move(
+        &mut self,
+        offset: u64,
+        event: &MouseMoveEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if event.dragging() && self.focus_handle.is_focused(window) && offset != self.cursor {
+            self.move_cursor_to(offset, true, cx);
+        }
+    }
+
+    fn handle_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let modifiers = event.keystroke.modifiers;
+        if !self.focus_handle.is_focused(window)
+            || modifiers.control
+            || modifiers.platform
+            || modifiers.function
+        {
+            return;
+        }
+        let Some(text) = event.keystroke.key_char.as_deref() else {
+            return;
+        };
+        match self.column {
+            Column::Hex => {
+                let Some(digit) = text
+                    .chars()
+                    .next()
+                    .and_then(|c| c.to_digit(16))
+                    .filter(|_| text.chars().count() == 1)
+                else {
+                    return;
+                };
+                self.type_nibble(digit as u8, cx);
+            }
+            Column::Ascii => {
+                let offset = self.selection_or_cursor().start;
+                self.write(offset, text.as_bytes(), cx);
+            }
+        }
+        cx.stop_propagation();
+    }
+
+    fn type_nibble(&mut self, digit: u8, cx: &mut Context<Self>) {
+        let offset = self.selection_or_cursor().start;
+        if self.anchor.is_some() {
+            self.move_cursor_to(offset, false, cx);
+        }
+        match self.pending_nibble.take() {
+            Some(high) => self.write(offset, &[(high << 4) | digit], cx),
+            None => {
+                if offset < self.file(cx).len() {
+                    self.pending_nibble = Some(digit);
+                }
+                cx.notify();
+            }
+        }
+    }
+
+    /// Overwrites the bytes at `offset` and moves the cursor past them.
+    fn write(&mut self, offset: u64, bytes: &[u8], cx: &mut Context<Self>) {
+        match self
+            .item
+            .update(cx, |item, cx| item.write(offset, bytes, cx))
+        {
+            Ok(range) => {
+                self.status = None;
+                let len = self.file(cx).len();
+                // Stay on the last byte instead of moving past the end.
+                self.move_cursor_to(range.end.min(len.saturating_sub(1)), false, cx);
+            }
+            Err(error) => {
+                self.status = Some(error.to_string().into());
+                cx.notify();
+            }
+        }
+    }
+
+    fn undo(&mut self, _: &Undo, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(range) = self.item.update(cx, |item, cx| item.undo(cx)) {
+            self.move_cursor_to(range.start, false, cx);
+        }
+    }
+
+    fn redo(&mut self, _: &Redo, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(range) = self.item.update(cx, |item, cx| item.redo(cx)) {
+            self.move_cursor_to(range.start, false, cx);
+        }
+    }
+
+    /// Copies the selected bytes as hex from the hex column, or as text from
+    /// the ASCII column.
+    fn copy(&mut self, _: &Copy, _window: &mut Window, cx: &mut Context<Self>) {
+        let range = self.selection_or_cursor();
+        if range.end - range.start > MAX_COPY_LEN {
+            self.status = Some(
+                format!(
+                    "The selection is larger than {} and was not copied",
+                    format_file_size(MAX_COPY_LEN, false)
+                )
+                .into(),
+            );
+            cx.notify();
+            return;
+        }
+        let file = self.file(cx);
+        let column = self.column;
+        cx.spawn(async move |this, cx| {
+            let bytes = cx.background_spawn(async move { file.read(range) }).await;
+            this.update(cx, |this, cx| {
+                match bytes {
+                    Ok(bytes) => {
+                        let text = match column {
+                            Column::Hex => bytes
+                                .iter()
+                                .map(|byte| format!("{byte:02X}"))
+                                .collect::<Vec<_>>()
+                                .join(" "),
+                            Column::Ascii => String::from_utf8_lossy(&bytes).into_owned(),
+                        };
+                        cx.write_to_clipboard(ClipboardItem::new_string(text));
+                    }
+                    Err(error) => this.status = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Overwrites the bytes at the cursor with the pasted hex bytes in the hex
+    /// column, or with the bytes of the pasted text in the ASCII column.
+    fn paste(&mut self, _: &Paste, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            return;
+        };
+        let bytes = match self.column {
+            Column::Hex => match parse_hex_bytes(&text).and_then(|bytes| {
+                bytes
+                    .into_iter()
+                    .collect::<Option<Vec<_>>>()
+                    .context("wildcards can't be pasted")
+            }) {
+                Ok(bytes) => bytes,
+                Err(error) => {
+                    self.status = Some(format!("Couldn't paste hex bytes: {error}").into());
+                    cx.notify();
+                    return;
+                }
+            },
+            Column::Ascii => text.into_bytes(),
+        };
+        let offset = self.selection_or_cursor().start;
+        self.write(offset, &bytes, cx);
+    }
+
+    fn reveal_in_file_manager(
+        &mut self,
+        _: &RevealInFileManager,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let path = self.file(cx).path().to_path_buf();
+        self.project
+            .update(cx, |project, cx| project.reveal_path(&path, cx));
+    }
+
+    fn focus_search(&mut self, _: &FocusSearch, window: &mut Window, cx: &mut Context<Self>) {
+        self.search_editor.update(cx, |editor, cx| {
+            editor.select_all(&SelectAll, window, cx);
+        });
+        self.search_editor.focus_handle(cx).focus(window, cx);
+    }
+
+    fn select_next_match(&mut self, _: &SelectNextMatch, _: &mut Window, cx: &mut Context<Self>) {
+        self.search(true, cx);
+    }
+
+    fn select_previous_match(
+        &mut self,
+        _: &SelectPreviousMatch,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.search(false, cx);
+    }
+
+    fn search(&mut self, forward: bool, cx: &mut Context<Self>) {
+        let query = self.search_editor.read(cx).text(cx);
+        if query.trim().is_empty() {
+            return;
+        }
+        let pattern = match BytePattern::parse(&query) {
+            Ok(pattern) => pattern,
+            Err(error) => {
+                self.status = Some(error.to_string().into());
+                cx.notify();
+                return;
+            }
+        };
+        // Searching forward skips the match that is already selected.
+        let start = match self.selection() {
+            Some(selection) if forward => selection.start + 1,
+            Some(selection) => selection.start,
+            None => self.cursor,
+        };
+        let file = self.file(cx);
+        self.status = Some("Searching…".into());
+        self.search_task = Some(cx.spawn(async move |this, cx| {
+            let (pattern, result) = cx
+                .background_spawn(async move {
+                    let result = file.find(&pattern, start, forward).await;
+                    (pattern, result)
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                this.status = match result {
+                    Ok(Some(offset)) => {
+                        this.reveal_range(offset..offset + pattern.len() as u64, cx);
+                        None
+                    }
+                    Ok(None) if forward => Some("No more matches below".into()),
+                    Ok(None) => Some("No more matches above".into()),
+                    Err(error) => Some(error.to_string().into()),
+                };
+                this.search_task = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn go_to_offset(&mut self, _: &GoToOffset, window: &mut Window, cx: &mut Context<Self>) {
+        self.go_to_offset_editor.update(cx, |editor, cx| {
+            editor.select_all(&SelectAll, window, cx);
+        });
+        self.go_to_offset_editor.focus_handle(cx).focus(window, cx);
+    }
+
+    fn confirm_go_to_offset(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self.go_to_offset_editor.read(cx).text(cx);
+        let Some(offset) = parse_offset(&text) else {
+            self.status = Some("Offsets are decimal, or hex when prefixed with 0x".into());
+            cx.notify();
+            return;
+        };
+        let len = self.file(cx).len();
+        self.status = if offset < len {
+            self.reveal_range(offset..offset + 1, cx);
+            self.anchor = None;
+            self.focus_handle.focus(window, cx);
+            None
+        } else {
+            Some(format!("The file has {len} bytes").into())
+        };
+        cx.notify();
+    }
+
+    fn set_visible_row_count(&mut self, visible_row_count: u64, cx: &mut Context<Self>) {
+        self.visible_row_count = visible_row_count;
+        cx.notify();
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let file = self.file(cx);
+        let mut summary = format_file_size(file.len(), false);
+        if let Some(selection) = self.selection() {
+            let selected_len = selection.end - selection.start;
+            summary.push_str(&format!(" · {selected_len} bytes selected"));
+        }
+        if file.is_dirty() {
+            summary.push_str(" · Modified");
+        }
+        let focus_handle = self.focus_handle.clone();
+
+        h_flex()
+            .flex_none()
+            .gap_2()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new(summary)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .when_some(self.status.clone(), |this, status| {
+                this.child(Label::new(status).size(LabelSize::Small))
+            })
+            .child(div().flex_1())
+            .child(
+                h_flex()
+                    .w_48()
+                    .px_1()
+                    .rounded_sm()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(self.search_editor.clone())
+                    .on_action(cx.listener(|this, _: &menu::Confirm, _window, cx| {
+                        this.search(true, cx);
+                    })),
+            )
+            .child(
+                IconButton::new("previous-match", IconName::ChevronUp)
+                    .icon_size(IconSize::Small)
+                    .disabled(self.search_task.is_some())
+                    .tooltip({
+                        let focus_handle = focus_handle.clone();
+                        move |_window, cx| {
+                            Tooltip::for_action_in(
+                                "Previous Match",
+                                &SelectPreviousMatch,
+                                &focus_handle,
+                                cx,
+                            )
+                        }
+                    })
+                    .on_click(cx.listener(|this, _, _window, cx| this.search(false, cx))),
+            )
+            .child(
+                IconButton::new("next-match", IconName::ChevronDown)
+                    .icon_size(IconSize::Small)
+                    .disabled(self.search_task.is_some())
+                    .tooltip(move |_window, cx| {
+                        Tooltip::for_action_in("Next Match", &SelectNextMatch, &focus_handle, cx)
+                    })
+                    .on_click(cx.listener(|this, _, _window, cx| this.search(true, cx))),
+            )
+            .child(
+                h_flex()
+                    .w_24()
+                    .px_1()
+                    .rounded_sm()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(self.go_to_offset_editor.clone())
+                    .on_action(cx.listener(|this, _: &menu::Confirm, window, cx| {
+                        this.confirm_go_to_offset(window, cx);
+                    })),
+            )
+    }
+
+    fn render_rows(&mut self, window: &Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let file = self.file(cx);
+        let len = file.len();
+        self.top_row = self.top_row.min(self.max_top_row(cx));
+        let start = self.top_row * BYTES_PER_ROW;
+        let end = start + self.visible_row_count * BYTES_PER_ROW;
+        let bytes = file.read(start..end).log_err().unwrap_or_default();
+
+        let settings = ThemeSettings::get_global(cx);
+        let font_size = settings.buffer_font_size(cx);
+        let buffer_font = settings.buffer_font.clone();
+        let line_height = line_height(cx);
+        let theme = cx.theme();
+        let style = ByteStyle {
+            char_width: font_size * 0.6,
+            text: theme.colors().editor_foreground,
+            zero: theme.colors().text_muted,
+            edited: theme.status().modified,
+            selection: theme.players().local().selection,
+            cursor: theme.players().local().cursor,
+        };
+        let offset_width = format!("{:X}", len.saturating_sub(1)).len().max(8);
+        let line_number_color = theme.colors().editor_line_number;
+        let is_focused = self.focus_handle.is_focused(window);
+        let visible_row_count = self.visible_row_count;
+        let this = cx.weak_entity();
+
+        let mut rows = Vec::new();
+        for (row_ix, row) in bytes.chunks(BYTES_PER_ROW as usize).enumerate() {
+            let row_start = start + row_ix as u64 * BYTES_PER_ROW;
+            let mut hex = Vec::new();
+            let mut ascii = Vec::new();
+            for (ix, byte) in row.iter().enumerate() {
+                let offset = row_start + ix as u64;
+                let edited = file.is_edited(offset);
+                hex.push(
+                    self.render_byte(offset, *byte, edited, Column::Hex, is_focused, &style, cx)
+                        .when(ix == BYTES_PER_ROW as usize / 2, |this| {
+                            this.ml(style.char_width)
+                        }),
+                );
+                ascii.push(self.render_byte(
+                    offset,
+                    *byte,
+                    edited,
+                    Column::Ascii,
+                    is_focused,
+                    &style,
+                    cx,
+                ));
+            }
+            rows.push(
+                h_flex()
+                    .h(line_height)
+                    .gap(style.char_width * 2.)
+                    .child(
+                        div()
+                            .flex_none()
+                            .w(style.char_width * offset_width as f32)
+                            .text_color(line_number_color)
+                            .child(format!("{row_start:0offset_width$X}")),
+                    )
+                    .child(
+                        h_flex()
+                            .flex_none()
+                            .w(style.char_width * (BYTES_PER_ROW * 3 + 1) as f32)
+                            .children(hex),
+                    )
+                    .child(h_flex().flex_none().children(ascii)),
+            );
+        }
+
+        div()
+            .id("rows")
+            .flex_1()
+            .relative()
+            .overflow_hidden()
+            .px_2()
+            .font(buffer_font)
+            .text_size(font_size)
+            .line_height(line_height)
+            .on_scroll_wheel(cx.listener(Self::handle_scroll_wheel))
+            .children(rows)
+            .child(self.render_scrollbar(len, cx))
+            .child(
+                canvas(
+                    move |bounds, window, cx| {
+                        let new_visible_row_count =
+                            ((bounds.size.height / line_height).floor() as u64).max(1);
+                        if new_visible_row_count == visible_row_count {
+                            return;
+                        }
+                        window.defer(cx, move |_window, cx| {
+                            this.update(cx, |this, cx| {
+                                this.set_visible_row_count(new_visible_row_count, cx);
+                            })
+                            .ok();
+                        });
+                    },
+                    |_, _, _, _| {},
+                )
+                .absolute()
+                .size_full(),
+            )
+    }
+
+    fn render_byte(
+        &self,
+        offset: u64,
+        byte: u8,
+        edited: bool,
+        column: Column,
+        is_focused: bool,
+        style: &ByteStyle,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let is_cursor = offset == self.cursor;
+        let (text, width) = match column {
+            Column::Hex => match self.pending_nibble.filter(|_| is_cursor) {
+                Some(high) => (format!("{high:X}_"), style.char_width * 3.),
+                None => (format!("{byte:02X}"), style.char_width * 3.),
+            },
+            Column::Ascii => {
+                let c = if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                };
+                (c.to_string(), style.char_width)
+            }
+        };
+        let is_selected = self
+            .selection()
+            .is_some_and(|selection| selection.contains(&offset));
+        let text_color = if edited {
+            style.edited
+        } else if byte == 0 {
+            style.zero
+        } else {
+            style.text
+        };
+
+        div()
+            .flex_none()
+            .w(width)
+            .text_color(text_color)
+            .when(is_selected, |this| this.bg(style.selection))
+            .when(is_cursor, |this| {
+                if is_focused && column == self.column {
+                    this.border_1().border_color(style.cursor)
+                } else {
+                    this.border_b_1().border_color(style.cursor.opacity(0.5))
+                }
+            })
+            .child(text)
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, event, window, cx| {
+                    this.handle_byte_mouse_down(offset, column, event, window, cx);
+                }),
+            )
+            .on_mouse_move(cx.listener(move |this, event, window, cx| {
+                this.handle_byte_mouse_move(offset, event, window, cx);
+            }))
+    }
+
+    /// Shows the values that the bytes at the cursor decode to.
+    fn render_inspector(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let bytes = self
+            .file(cx)
+            .read(self.cursor..self.cursor + MAX_DECODED_LEN as u64)
+            .log_err()
+            .unwrap_or_default();
+        let values = decode_values(&bytes, self.endianness);
+        let focus_handle = self.focus_handle.clone();
+
+        v_flex()
+            .flex_none()
+            .w_64()
+            .p_2()
+            .gap_1()
+            .border_l_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        Label::new(format!("Offset {0} (0x{0:X})", self.cursor))
+                            .size(LabelSize::Small),
+                    )
+                    .child(
+                        Button::new("endianness", self.endianness.label())
+                            .label_size(LabelSize::Small)
+                            .tooltip(move |_window, cx| {
+                                Tooltip::for_action_in(
+                                    "Toggle Byte Order",
+                                    &ToggleEndianness,
+                                    &focus_handle,
+                                    cx,
+                                )
+                            })
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.toggle_endianness(&ToggleEndianness, window, cx);
+                            })),
+                    ),
+            )
+            .children(values.into_iter().map(|value| {
+                h_flex()
+                    .gap_2()
+                    .child(
+                        div().w_12().flex_none().child(
+                            Label::new(value.name)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                    )
+                    .child(
+                        Label::new(value.value.unwrap_or_else(|| "—".into()))
+                            .size(LabelSize::Small)
+                            .buffer_font(cx),
+                    )
+            }))
+    }
+
+    /// Shows where the visible rows are in the file.
+    fn render_scrollbar(&self, len: u64, cx: &App) -> impl IntoElement {
+        let row_count = len.div_ceil(BYTES_PER_ROW).max(1) as f32;
+        let thumb_top = self.top_row as f32 / row_count;
+        let thumb_height = (self.visible_row_count as f32 / row_count).min(1.);
+
+        div().absolute().top_0().right_0().h_full().w_2().child(
+            div()
+                .absolute()
+                .top(relative(thumb_top))
+                .h(relative(thumb_height))
+                .min_h_2()
+                .w_full()
+                .rounded_sm()
+                .bg(cx.theme().colors().scrollbar_thumb_background),
+        )
+    }
+}
+
+struct ByteStyle {
+    char_width: Pixels,
+    text: Hsla,
+    zero: Hsla,
+    edited: Hsla,
+    selection: Hsla,
+    cursor: Hsla,
+}
+
+fn line_height(cx: &App) -> Pixels {
+    let settings = ThemeSettings::get_global(cx);
+    settings.buffer_font_size(cx) * settings.line_height()
+}
+
+impl EventEmitter<HexFileEvent> for HexEditorView {}
+
+impl Item for HexEditorView {
+    type Event = HexFileEvent;
+
+    fn to_item_events(event: &Self::Event, f: &mut dyn FnMut(ItemEvent)) {
+        match event {
+            HexFileEvent::Edited => {
+                f(ItemEvent::UpdateTab);
+                f(ItemEvent::Edit);
+            }
+            HexFileEvent::Saved | HexFileEvent::Reloaded => f(ItemEvent::UpdateTab),
+        }
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.item.entity_id(), self.item.read(cx))
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let path = self.item.read(cx).file.path().compact();
+        Some(path.to_string_lossy().into_owned().into())
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        let project_path = &self.item.read(cx).project_path;
+
+        let label_color = if ItemSettings::get_global(cx).git_status {
+            let git_status = self
+                .project
+                .read(cx)
+                .git_store()
+                .read(cx)
+                .display_status_for_project_path(project_path, cx)
+                .map(|status| status.summary())
+                .unwrap_or_default();
+
+            self.project
+                .read(cx)
+                .entry_for_path(project_path, cx)
+                .map(|entry| {
+                    entry_git_aware_label_color(git_status, entry.is_ignored, params.selected)
+                })
+                .unwrap_or_else(|| params.text_color())
+        } else {
+            params.text_color()
+        };
+
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .single_line()
+            .color(label_color)
+            .when(params.preview, |this| this.italic())
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _: usize, cx: &App) -> SharedString {
+        self.item
+            .read(cx)
+            .file
+            .path()
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned().into())
+            .unwrap_or_default()
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let path = self.item.read(cx).file.path();
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn breadcrumb_location(&self, cx: &App) -> ToolbarItemLocation {
+        let show_breadcrumb = EditorSettings::get_global(cx).toolbar.breadcrumbs;
+        if show_breadcrumb {
+            ToolbarItemLocation::PrimaryLeft
+        } else {
+            ToolbarItemLocation::Hidden
+        }
+    }
+
+    fn breadcrumbs(&self, cx: &App) -> Option<(Vec<HighlightedText>, Option<Font>)> {
+        let project = self.project.read(cx);
+        let project_path = &self.item.read(cx).project_path;
+        let mut path = project_path.path.to_rel_path_buf();
+        if project.visible_worktrees(cx).count() > 1
+            && let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx)
+        {
+            path = worktree.read(cx).root_name().join(&path);
+        }
+        let font = ThemeSettings::get_global(cx).buffer_font.clone();
+
+        Some((
+            vec![HighlightedText {
+                text: path.display(project.path_style(cx)).to_string().into(),
+                highlights: vec![],
+            }],
+            Some(font),
+        ))
+    }
+
+    fn can_split(&self) -> bool {
+        true
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Entity<Self>>>
+    where
+        Self: Sized,
+    {
+        let item = self.item.clone();
+        let project = self.project.clone();
+        let top_row = self.top_row;
+        let cursor = self.cursor;
+        Task::ready(Some(cx.new(|cx| {
+            let mut view = Self::new(item, project, window, cx);
+            view.top_row = top_row;
+            view.cursor = cursor;
+            view
+        })))
+    }
+
+    fn buffer_kind(&self, _: &App) -> workspace::item::ItemBufferKind {
+        workspace::item::ItemBufferKind::Singleton
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.item.read(cx).file.is_dirty()
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _options: SaveOptions,
+        _project: Entity<Project>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.item.update(cx, |item, cx| item.save(cx))
+    }
+
+    fn reload(
+        &mut self,
+        _project: Entity<Project>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.item.update(cx, |item, cx| item.reload(cx))
+    }
+}
+
+impl Focusable for HexEditorView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for HexEditorView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .track_focus(&self.focus_handle(cx))
+            .key_context("HexEditor")
+            .on_action(cx.listener(Self::move_left))
+            .on_action(cx.listener(Self::move_right))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::select_left))
+            .on_action(cx.listener(Self::select_right))
+            .on_action(cx.listener(Self::select_up))
+            .on_action(cx.listener(Self::select_down))
+            .on_action(cx.listener(Self::move_page_up))
+            .on_action(cx.listener(Self::move_page_down))
+            .on_action(cx.listener(Self::move_to_beginning_of_line))
+            .on_action(cx.listener(Self::move_to_end_of_line))
+            .on_action(cx.listener(Self::move_to_beginning))
+            .on_action(cx.listener(Self::move_to_end))
+            .on_action(cx.listener(Self::select_to_beginning))
+            .on_action(cx.listener(Self::select_to_end))
+            .on_action(cx.listener(Self::select_all))
+            .on_action(cx.listener(Self::switch_column))
+            .on_action(cx.listener(Self::toggle_endianness))
+            .on_action(cx.listener(Self::undo))
+            .on_action(cx.listener(Self::redo))
+            .on_action(cx.listener(Self::copy))
+            .on_action(cx.listener(Self::paste))
+            .on_action(cx.listener(Self::reveal_in_file_manager))
+            .on_action(cx.listener(Self::focus_search))
+            .on_action(cx.listener(Self::select_next_match))
+            .on_action(cx.listener(Self::select_previous_match))
+            .on_action(cx.listener(Self::go_to_offset))
+            .on_key_down(cx.listener(Self::handle_key_down))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_header(cx))
+            .child(
+                h_flex()
+                    .flex_1()
+                    .items_start()
+                    .overflow_hidden()
+                    .child(self.render_rows(window, cx))
+                    .child(self.render_inspector(cx)),
+            )
+    }
+}
+
+/// Opens the file of the active item in a hex editor, reusing one that is
+/// already open. Files that failed to open are found through their path.
+fn open_in_hex_editor(
+    workspace: &mut Workspace,
+    _: &OpenInHexEditor,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let Some(active_item) = workspace.active_item(cx) else {
+        return;
+    };
+    let project_path = active_item.project_path(cx).or_else(|| {
+        let invalid_item = active_item.downcast::<InvalidItemView>()?;
+        project
+            .read(cx)
+            .find_project_path(&invalid_item.read(cx).abs_path, cx)
+    });
+    let Some(project_path) = project_path else {
+        return;
+    };
+
+    if let Some(view) = workspace
+        .items_of_type::<HexEditorView>(cx)
+        .find(|view| view.read(cx).item.read(cx).project_path == project_path)
+    {
+        workspace.activate_item(&view, true, true, window, cx);
+        return;
+    }
+
+    let open_task = HexFileItem::open(&project, &project_path, cx);
+    cx.spawn_in(window, async move |workspace, cx| {
+        let item = open_task?.await?;
+        workspace.update_in(cx, |workspace, window, cx| {
+            let view = cx.new(|cx| HexEditorView::new(item, project, window, cx));
+            workspace.add_item_to_active_pane(Box::new(view), None, true, window, cx);
+        })
+    })
+    .detach_and_prompt_err("Failed to open the hex editor", window, cx, |_, _, _| None);
+}
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(open_in_hex_editor);
+    })
+    .detach();
+}
@@ -0,0 +1,486 @@
+use anyhow::Result;
+use fs::Fs;
+use large_file_viewer::PagedFile;
+use parking_lot::Mutex;
+use regex::bytes::Regex;
+use std::{collections::BTreeMap, ops::Range, path::Path};
+
+const SEARCH_CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+/// Longer patterns are rejected, so that chunks only need to overlap by this
+/// much for matches spanning two chunks to be found.
+pub const MAX_PATTERN_LEN: usize = 1024;
+
+/// A file on disk that is read in pages on demand and edited in place.
+///
+/// Edits only ever overwrite bytes, so the length of the file never changes.
+/// They are kept in memory until [`HexFile::save`] writes them back.
+pub struct HexFile {
+    /// The cached pages hold the bytes on disk, without the edits.
+    file: PagedFile,
+    state: Mutex<State>,
+}
+
+struct State {
+    len: u64,
+    /// The bytes that differ from the ones on disk.
+    edits: BTreeMap<u64, u8>,
+    undo_stack: Vec<Vec<Change>>,
+    redo_stack: Vec<Vec<Change>>,
+}
+
+/// The edit of a byte, where `None` stands for the byte on disk.
+#[derive(Clone, Copy, Debug)]
+struct Change {
+    offset: u64,
+    before: Option<u8>,
+    after: Option<u8>,
+}
+
+impl HexFile {
+    pub fn open(path: &Path) -> Result<Self> {
+        let (file, len) = PagedFile::open(path)?;
+        Ok(Self {
+            file,
+            state: Mutex::new(State {
+                len,
+                edits: BTreeMap::default(),
+                undo_stack: Vec::new(),
+                redo_stack: Vec::new(),
+            }),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    pub fn len(&self) -> u64 {
+        self.state.lock().len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether there are edits that haven't been saved.
+    pub fn is_dirty(&self) -> bool {
+        !self.state.lock().edits.is_empty()
+    }
+
+    pub fn is_edited(&self, offset: u64) -> bool {
+        self.state.lock().edits.contains_key(&offset)
+    }
+
+    /// Returns the bytes in `range`, including the unsaved edits. The range is
+    /// clamped to the end of the file.
+    pub fn read(&self, range: Range<u64>) -> Result<Vec<u8>> {
+        let end = range.end.min(self.len());
+        let mut bytes = Vec::with_capacity(end.saturating_sub(range.start) as usize);
+        let mut offset = range.start;
+        while offset < end {
+            let page_bytes = self.file.bytes_at(offset)?;
+            if page_bytes.is_empty() {
+                // The file was truncated since it was opened.
+                break;
+            }
+            let len = page_bytes.len().min((end - offset) as usize);
+            bytes.extend_from_slice(&page_bytes[..len]);
+            offset += len as u64;
+        }
+
+        let state = self.state.lock();
+        for (offset, byte) in state.edits.range(range.start..end) {
+            if let Some(slot) = bytes.get_mut((offset - range.start) as usize) {
+                *slot = *byte;
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// Overwrites the bytes at `offset` with `bytes`, dropping the ones that
+    /// would extend past the end of the file, as a single undoable edit.
+    /// Returns the range of bytes that were written.
+    pub fn write(&self, offset: u64, bytes: &[u8]) -> Result<Range<u64>> {
+        let end = offset
+            .saturating_add(bytes.len() as u64)
+            .min(self.len())
+            .max(offset);
+        let on_disk = self.read_from_disk(offset..end)?;
+
+        let mut state = self.state.lock();
+        let mut changes = Vec::new();
+        for (i, (byte, disk_byte)) in bytes.iter().zip(on_disk).enumerate() {
+            let offset = offset + i as u64;
+            let before = state.edits.get(&offset).copied();
+            let after = (*byte != disk_byte).then_some(*byte);
+            if before != after {
+                changes.push(Change {
+                    offset,
+                    before,
+                    after,
+                });
+            }
+        }
+        if !changes.is_empty() {
+            for change in &changes {
+                state.apply(change.offset, change.after);
+            }
+            state.undo_stack.push(changes);
+            state.redo_stack.clear();
+        }
+        Ok(offset..end)
+    }
+
+    /// Reverts the last edit, returning the range of bytes it touched.
+    pub fn undo(&self) -> Option<Range<u64>> {
+        let mut state = self.state.lock();
+        let changes = state.undo_stack.pop()?;
+        for change in changes.iter().rev() {
+            state.apply(change.offset, change.before);
+        }
+        let range = changed_range(&changes);
+        state.redo_stack.push(changes);
+        Some(range)
+    }
+
+    /// Reapplies the last undone edit, returning the range of bytes it touched.
+    pub fn redo(&self) -> Option<Range<u64>> {
+        let mut state = self.state.lock();
+        let changes = state.redo_stack.pop()?;
+        for change in &changes {
+            state.apply(change.offset, change.after);
+        }
+        let range = changed_range(&changes);
+        state.undo_stack.push(changes);
+        Some(range)
+    }
+
+    /// Writes the edited bytes to disk through `fs`. The undo history is
+    /// cleared, as it refers to the bytes that were on disk before. Edits made
+    /// while saving are kept.
+    pub async fn save(&self, fs: &dyn Fs) -> Result<()> {
+        let edits = self.state.lock().edits.clone();
+        if edits.is_empty() {
+            return Ok(());
+        }
+
+        let mut bytes = fs.load_bytes(self.path()).await?;
+        for (offset, byte) in &edits {
+            if let Some(slot) = bytes.get_mut(*offset as usize) {
+                *slot = *byte;
+            }
+        }
+        fs.write(self.path(), &bytes).await?;
+        let len = self.file.reopen()?;
+
+        let mut state = self.state.lock();
+        state.len = len;
+        state
+            .edits
+            .retain(|offset, byte| edits.get(offset) != Some(byte));
+        state.undo_stack.clear();
+        state.redo_stack.clear();
+        Ok(())
+    }
+
+    /// Discards the edits and re-reads the file from disk.
+    pub fn reload(&self) -> Result<()> {
+        let len = self.file.reopen()?;
+
+        let mut state = self.state.lock();
+        state.len = len;
+        state.edits.clear();
+        state.undo_stack.clear();
+        state.redo_stack.clear();
+        Ok(())
+    }
+
+    /// Searches for `pattern` from `start` on when searching forward, or
+    /// before `start` when searching backward, and returns the offset of the
+    /// closest match.
+    ///
+    /// This reads the file in chunks and yields between them, so that
+    /// dropping the returned future cancels the search.
+    pub async fn find(
+        &self,
+        pattern: &BytePattern,
+        start: u64,
+        forward: bool,
+    ) -> Result<Option<u64>> {
+        let len = self.len();
+        let overlap = pattern.len as u64 - 1;
+        let mut offset = start.min(len);
+        if forward {
+            while offset < len {
+                let chunk_end = len.min(offset + SEARCH_CHUNK_SIZE);
+                let chunk = self.read(offset..chunk_end + overlap)?;
+                let searched_len = (chunk_end - offset) as usize;
+                if let Some(found) = pattern
+                    .regex
+                    .find_iter(&chunk)
+                    .find(|found| found.start() < searched_len)
+                {
+                    return Ok(Some(offset + found.start() as u64));
+                }
+                offset = chunk_end;
+                smol::future::yield_now().await;
+            }
+        } else {
+            while offset > 0 {
+                let chunk_start = offset.saturating_sub(SEARCH_CHUNK_SIZE);
+                // Matches must start before `offset`, but may extend past it.
+                let chunk = self.read(chunk_start..offset + overlap)?;
+                let searched_len = (offset - chunk_start) as usize;
+                if let Some(found) = last_match(&pattern.regex, &chunk, searched_len) {
+                    return Ok(Some(chunk_start + found as u64));
+                }
+                offset = chunk_start;
+                smol::future::yield_now().await;
+            }
+        }
+        Ok(None)
+    }
+
+    fn read_from_disk(&self, range: Range<u64>) -> Result<Vec<u8>> {
+        let mut bytes = vec![0; (range.end - range.start) as usize];
+        let read_len = self.file.read_at(range.start, &mut bytes)?;
+        bytes.truncate(read_len);
+        Ok(bytes)
+    }
+}
+
+impl State {
+    fn apply(&mut self, offset: u64, byte: Option<u8>) {
+        match byte {
+            Some(byte) => self.edits.insert(offset, byte),
+            None => self.edits.remove(&offset),
+        };
+    }
+}
+
+fn changed_range(changes: &[Change]) -> Range<u64> {
+    let start = changes.iter().map(|change| change.offset).min();
+    let end = changes.iter().map(|change| change.offset + 1).max();
+    start.unwrap_or_default()..end.unwrap_or_default()
+}
+
+/// Returns the start of the last match that starts before `searched_len`.
+/// Matches found by `find_iter` don't overlap, so every start is tried.
+fn last_match(regex: &Regex, haystack: &[u8], searched_len: usize) -> Option<usize> {
+    let mut last = None;
+    let mut start = 0;
+    while start < searched_len {
+        let Some(found) = regex.find_at(haystack, start) else {
+            break;
+        };
+        if found.start() >= searched_len {
+            break;
+        }
+        last = Some(found.start());
+        start = found.start() + 1;
+    }
+    last
+}
+
+/// A sequence of bytes to search for, in which some bytes may match any byte.
+///
+/// Patterns are written as hex bytes, like `DE AD ?? EF`, where `??` matches
+/// any byte, or as quoted text, like `"IHDR"`, which matches its UTF-8 bytes.
+#[derive(Debug)]
+pub struct BytePattern {
+    regex: Regex,
+    len: usize,
+}
+
+impl BytePattern {
+    pub fn parse(query: &str) -> Result<Self> {
+        let query = query.trim();
+        let bytes = if let Some(text) = query
+            .strip_prefix('"')
+            .and_then(|query| query.strip_suffix('"'))
+        {
+            text.bytes().map(Some).collect::<Vec<_>>()
+        } else {
+            parse_hex_bytes(query)?
+        };
+        anyhow::ensure!(!bytes.is_empty(), "the search pattern is empty");
+        anyhow::ensure!(
+            bytes.len() <= MAX_PATTERN_LEN,
+            "search patterns are limited to {MAX_PATTERN_LEN} bytes"
+        );
+
+        let mut regex = String::from("(?s-u)");
+        for byte in &bytes {
+            match byte {
+                Some(byte) => regex.push_str(&format!("\\x{byte:02X}")),
+                None => regex.push('.'),
+            }
+        }
+        Ok(Self {
+            regex: Regex::new(&regex)?,
+            len: bytes.len(),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Parses hex bytes like `DE AD ?? EF`, where `??` stands for any byte.
+/// Whitespace between the digits is ignored.
+pub fn parse_hex_bytes(text: &str) -> Result<Vec<Option<u8>>> {
+    let digits = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+    anyhow::ensure!(digits.len() % 2 == 0, "hex bytes need two digits per byte");
+    digits
+        .chunks(2)
+        .map(|pair| match pair {
+            ['?', '?'] => Ok(None),
+            [high, low] => match (high.to_digit(16), low.to_digit(16)) {
+                (Some(high), Some(low)) => Ok(Some((high * 16 + low) as u8)),
+                _ => anyhow::bail!("\"{high}{low}\" is not a hex byte"),
+            },
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::RealFs;
+    use gpui::TestAppContext;
+    use large_file_viewer::PAGE_SIZE;
+
+    fn hex_file(bytes: &[u8]) -> (tempfile::TempDir, HexFile) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("firmware.bin");
+        std::fs::write(&path, bytes).unwrap();
+        let file = HexFile::open(&path).unwrap();
+        (dir, file)
+    }
+
+    #[test]
+    fn test_read_across_pages() {
+        let bytes = (0..PAGE_SIZE as usize * 3)
+            .map(|i| i as u8)
+            .collect::<Vec<_>>();
+        let (_dir, file) = hex_file(&bytes);
+        let start = PAGE_SIZE - 2;
+        assert_eq!(
+            file.read(start..start + 4).unwrap(),
+            &bytes[start as usize..start as usize + 4]
+        );
+        assert_eq!(
+            file.read(file.len() - 1..u64::MAX).unwrap(),
+            [bytes[bytes.len() - 1]]
+        );
+        assert_eq!(file.read(file.len() + 5..file.len() + 10).unwrap(), []);
+    }
+
+    #[gpui::test]
+    async fn test_edit_undo_and_save(cx: &mut TestAppContext) {
+        let (_dir, file) = hex_file(&[0, 1, 2, 3, 4, 5]);
+        assert_eq!(file.write(4, &[0xAA, 0xBB, 0xCC]).unwrap(), 4..6);
+        assert_eq!(file.read(0..6).unwrap(), [0, 1, 2, 3, 0xAA, 0xBB]);
+        assert!(file.is_dirty());
+        assert!(file.is_edited(5));
+
+        // Writing the bytes on disk back is not an edit.
+        file.write(0, &[0xFF]).unwrap();
+        file.write(0, &[0]).unwrap();
+        assert!(!file.is_edited(0));
+
+        assert_eq!(file.undo(), Some(0..1));
+        assert_eq!(file.read(0..1).unwrap(), [0xFF]);
+        assert_eq!(file.undo(), Some(0..1));
+        assert_eq!(file.undo(), Some(4..6));
+        assert_eq!(file.undo(), None);
+        assert!(!file.is_dirty());
+        assert_eq!(file.redo(), Some(4..6));
+        assert_eq!(file.read(0..6).unwrap(), [0, 1, 2, 3, 0xAA, 0xBB]);
+
+        file.write(1, &[0x11]).unwrap();
+        assert_eq!(file.redo(), None);
+        let fs = RealFs::new(None, cx.executor());
+        file.save(&fs).await.unwrap();
+        assert!(!file.is_dirty());
+        assert_eq!(file.undo(), None);
+        assert_eq!(
+            std::fs::read(file.path()).unwrap(),
+            [0, 0x11, 2, 3, 0xAA, 0xBB]
+        );
+    }
+
+    #[test]
+    fn test_reload_discards_edits() {
+        let (_dir, file) = hex_file(b"abcd");
+        file.write(0, b"x").unwrap();
+        std::fs::write(file.path(), b"abcdef").unwrap();
+        file.reload().unwrap();
+        assert!(!file.is_dirty());
+        assert_eq!(file.read(0..10).unwrap(), b"abcdef");
+    }
+
+    #[test]
+    fn test_parse_pattern() {
+        assert_eq!(BytePattern::parse("DE AD ?? ef").unwrap().len(), 4);
+        assert_eq!(BytePattern::parse("deadbeef").unwrap().len(), 4);
+        assert_eq!(BytePattern::parse("\"IHDR\"").unwrap().len(), 4);
+        assert!(BytePattern::parse("DEA").is_err());
+        assert!(BytePattern::parse("XY").is_err());
+        assert!(BytePattern::parse("?A").is_err());
+        assert!(BytePattern::parse("  ").is_err());
+        assert_eq!(
+            parse_hex_bytes("0a Ff\n??").unwrap(),
+            [Some(0x0A), Some(0xFF), None]
+        );
+    }
+
+    #[test]
+    fn test_find() {
+        let mut bytes = vec![0u8; SEARCH_CHUNK_SIZE as usize * 2];
+        let boundary = SEARCH_CHUNK_SIZE as usize - 1;
+        bytes[boundary..boundary + 3].copy_from_slice(&[0xDE, 0xAD, 0xEF]);
+        bytes[10..13].copy_from_slice(&[0xDE, 0x00, 0xEF]);
+        let (_dir, file) = hex_file(&bytes);
+        let pattern = BytePattern::parse("DE ?? EF").unwrap();
+        let boundary = boundary as u64;
+
+        smol::block_on(async {
+            assert_eq!(file.find(&pattern, 0, true).await.unwrap(), Some(10));
+            assert_eq!(file.find(&pattern, 11, true).await.unwrap(), Some(boundary));
+            assert_eq!(file.find(&pattern, boundary + 1, true).await.unwrap(), None);
+            assert_eq!(
+                file.find(&pattern, file.len(), false).await.unwrap(),
+                Some(boundary)
+            );
+            assert_eq!(
+                file.find(&pattern, boundary, false).await.unwrap(),
+                Some(10)
+            );
+            assert_eq!(file.find(&pattern, 10, false).await.unwrap(), None);
+
+            // Searches see the unsaved edits.
+            file.write(10, &[0]).unwrap();
+            assert_eq!(file.find(&pattern, 0, true).await.unwrap(), Some(boundary));
+        });
+    }
+
+    #[test]
+    fn test_find_overlapping_matches_backward() {
+        let (_dir, file) = hex_file(&[0xAA; 8]);
+        let pattern = BytePattern::parse("AA AA").unwrap();
+        smol::block_on(async {
+            assert_eq!(file.find(&pattern, 8, false).await.unwrap(), Some(6));
+            assert_eq!(file.find(&pattern, 6, false).await.unwrap(), Some(5));
+        });
+    }
+}
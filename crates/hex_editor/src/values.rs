@@ -0,0 +1,134 @@
+/// The byte order used to decode the values at the cursor.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Endianness {
+    #[default]
+    Little,
+    Big,
+}
+
+impl Endianness {
+    pub fn label(self) -> &'static str {
+        match self {
+            Endianness::Little => "Little-endian",
+            Endianness::Big => "Big-endian",
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            Endianness::Little => Endianness::Big,
+            Endianness::Big => Endianness::Little,
+        }
+    }
+}
+
+/// A value decoded from the bytes at the cursor, which is `None` when there
+/// aren't enough bytes left in the file.
+#[derive(Debug, PartialEq)]
+pub struct DecodedValue {
+    pub name: &'static str,
+    pub value: Option<String>,
+}
+
+/// The number of bytes needed to decode all of the values.
+pub const MAX_DECODED_LEN: usize = 8;
+
+/// Decodes `bytes`, which start at the cursor, as integers and floats of
+/// every width.
+pub fn decode_values(bytes: &[u8], endianness: Endianness) -> Vec<DecodedValue> {
+    fn read<const N: usize>(bytes: &[u8], endianness: Endianness) -> Option<[u8; N]> {
+        let mut array: [u8; N] = bytes.get(..N)?.try_into().ok()?;
+        if endianness == Endianness::Big {
+            array.reverse();
+        }
+        Some(array)
+    }
+
+    macro_rules! value {
+        ($name:literal, $ty:ty, $format:expr) => {
+            DecodedValue {
+                name: $name,
+                value: read::<{ size_of::<$ty>() }>(bytes, endianness)
+                    .map(<$ty>::from_le_bytes)
+                    .map($format),
+            }
+        };
+    }
+
+    vec![
+        DecodedValue {
+            name: "binary",
+            value: bytes.first().map(|byte| format!("{byte:08b}")),
+        },
+        value!("u8", u8, |value| value.to_string()),
+        value!("i8", i8, |value| value.to_string()),
+        value!("u16", u16, |value| value.to_string()),
+        value!("i16", i16, |value| value.to_string()),
+        value!("u32", u32, |value| value.to_string()),
+        value!("i32", i32, |value| value.to_string()),
+        value!("u64", u64, |value| value.to_string()),
+        value!("i64", i64, |value| value.to_string()),
+        value!("f32", f32, |value| format!("{value:e}")),
+        value!("f64", f64, |value| format!("{value:e}")),
+    ]
+}
+
+/// Parses a go-to-offset query, which is decimal unless it starts with `0x`.
+pub fn parse_offset(query: &str) -> Option<u64> {
+    let query = query.trim().replace('_', "");
+    match query
+        .strip_prefix("0x")
+        .or_else(|| query.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => query.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value<'a>(values: &'a [DecodedValue], name: &str) -> Option<&'a str> {
+        values
+            .iter()
+            .find(|value| value.name == name)
+            .unwrap()
+            .value
+            .as_deref()
+    }
+
+    #[test]
+    fn test_decode_values() {
+        let bytes = [0xFE, 0xFF, 0x00, 0x00];
+        let little = decode_values(&bytes, Endianness::Little);
+        assert_eq!(value(&little, "binary"), Some("11111110"));
+        assert_eq!(value(&little, "u8"), Some("254"));
+        assert_eq!(value(&little, "i8"), Some("-2"));
+        assert_eq!(value(&little, "u16"), Some("65534"));
+        assert_eq!(value(&little, "i16"), Some("-2"));
+        assert_eq!(value(&little, "u32"), Some("65534"));
+        assert_eq!(value(&little, "u64"), None);
+        assert_eq!(value(&little, "f64"), None);
+
+        let big = decode_values(&bytes, Endianness::Big);
+        assert_eq!(value(&big, "u16"), Some("65279"));
+        assert_eq!(value(&big, "u32"), Some("4278124544"));
+
+        let one = 1.0f32.to_be_bytes();
+        assert_eq!(
+            value(&decode_values(&one, Endianness::Big), "f32"),
+            Some("1e0")
+        );
+        assert_eq!(decode_values(&[], Endianness::Little)[0].value, None);
+    }
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(parse_offset("1024"), Some(1024));
+        assert_eq!(parse_offset(" 0x1F "), Some(31));
+        assert_eq!(parse_offset("0XdeAD_beef"), Some(0xDEAD_BEEF));
+        assert_eq!(parse_offset("0x"), None);
+        assert_eq!(parse_offset("-1"), None);
+    }
+}
@@ -2374,6 +2374,101 @@ impl Buffer {
         }
     }
 
+    /// Spawns a background task that returns a `Diff` re-indenting lines according to the
+    /// language's `indents.scm` query, trimming trailing whitespace, and collapsing runs of
+    /// blank lines into a single blank line.
+    ///
+    /// Lines for which the syntax tree has no indentation suggestion keep their current
+    /// indentation, and so do lines in languages with significant indentation, like Python.
+    /// Lines that start inside a string or comment are left as they are, and trailing
+    /// whitespace is kept where a line ends inside a string. When `modified_rows` is `Some`,
+    /// only lines whose row falls within one of the given ranges are touched; when it is
+    /// `None`, the whole buffer is formatted.
+    pub fn reindent(&self, modified_rows: Option<&[Range<u32>]>, cx: &App) -> Task<Diff> {
+        let snapshot = self.snapshot();
+        let single_indent_size = snapshot.language_indent_size_at(0, cx);
+        let max_row = snapshot.max_point().row;
+        let rows = match modified_rows {
+            Some(ranges) => ranges
+                .iter()
+                .flat_map(|range| range.start..range.end.min(max_row + 1))
+                .collect::<BTreeSet<_>>(),
+            None => (0..=max_row).collect(),
+        };
+        cx.background_spawn(async move {
+            let indents = snapshot.suggested_indents(
+                rows.iter()
+                    .copied()
+                    .filter(|row| !snapshot.is_line_blank(*row)),
+                single_indent_size,
+            );
+            let literals = match (rows.first(), rows.last()) {
+                (Some(first), Some(last)) => snapshot.string_and_comment_ranges(
+                    Point::new(*first, 0).to_offset(&snapshot)
+                        ..Point::new(*last, snapshot.line_len(*last)).to_offset(&snapshot),
+                ),
+                _ => Vec::new(),
+            };
+            let inside_literal = |offset: usize, strings_only: bool| {
+                literals.iter().any(|(range, is_string)| {
+                    (*is_string || !strings_only) && range.start < offset && offset < range.end
+                })
+            };
+
+            let empty = Arc::<str>::from("");
+            let mut edits = Vec::new();
+            let mut prev_blank_row = None;
+            for row in rows {
+                let line_start = Point::new(row, 0).to_offset(&snapshot);
+                let line_end = Point::new(row, snapshot.line_len(row)).to_offset(&snapshot);
+                if inside_literal(line_start, false) {
+                    prev_blank_row = None;
+                    continue;
+                }
+                if snapshot.is_line_blank(row) {
+                    // Fold this blank line into the preceding one by deleting the line break
+                    // between them, so a run of blank lines leaves a single empty line behind.
+                    let start = if row > 0 && prev_blank_row == Some(row - 1) {
+                        Point::new(row - 1, snapshot.line_len(row - 1)).to_offset(&snapshot)
+                    } else {
+                        line_start
+                    };
+                    if start < line_end {
+                        edits.push((start..line_end, empty.clone()));
+                    }
+                    prev_blank_row = Some(row);
+                    continue;
+                }
+
+                let line = snapshot
+                    .text_for_range(line_start..line_end)
+                    .collect::<String>();
+                let indent_len = line.len() - line.trim_start_matches([' ', '\t']).len();
+                let significant_indentation = snapshot
+                    .language_at(line_start)
+                    .is_some_and(|language| language.config().significant_indentation);
+                if let Some(indent) = indents.get(&row)
+                    && !significant_indentation
+                {
+                    let new_indent = indent.chars().collect::<String>();
+                    if line[..indent_len] != new_indent {
+                        edits.push((line_start..line_start + indent_len, new_indent.into()));
+                    }
+                }
+                let trimmed_len = line.trim_end_matches([' ', '\t']).len();
+                if trimmed_len < line.len() && !inside_literal(line_end, true) {
+                    edits.push((line_start + trimmed_len..line_end, empty.clone()));
+                }
+            }
+
+            Diff {
+                base_version: snapshot.version().clone(),
+                line_ending: snapshot.line_ending(),
+                edits,
+            }
+        })
+    }
+
     /// Applies a diff to the buffer. If the buffer has changed since the given diff was
     /// calculated, then adjust the diff to account for those changes, and discard any
     /// parts of the diff that conflict with those changes.
@@ -4481,6 +4576,37 @@ impl BufferSnapshot {
         items
     }
 
+    /// Returns the ranges of the strings and comments captured by the languages'
+    /// `overrides.scm` queries that intersect `range`, and whether each is a string.
+    fn string_and_comment_ranges(&self, range: Range<usize>) -> Vec<(Range<usize>, bool)> {
+        let mut matches = self.syntax.matches(range, &self.text, |grammar| {
+            grammar.override_config.as_ref().map(|config| &config.query)
+        });
+        let configs = matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.override_config.as_ref().unwrap())
+            .collect::<Vec<_>>();
+
+        let mut ranges = Vec::new();
+        while let Some(mat) = matches.peek() {
+            let config = configs[mat.grammar_index];
+            for capture in mat.captures {
+                match config
+                    .values
+                    .get(&capture.index)
+                    .map(|entry| entry.name.as_str())
+                {
+                    Some("string") => ranges.push((capture.node.byte_range(), true)),
+                    Some("comment") => ranges.push((capture.node.byte_range(), false)),
+                    _ => {}
+                }
+            }
+            matches.advance();
+        }
+        ranges
+    }
+
     pub fn outline_ranges_containing<T: ToOffset>(
         &self,
         range: Range<T>,
@@ -4580,6 +4580,144 @@ async fn test_trailing_whitespace_in_ranges_crlf(cx: &mut gpui::TestAppContext)
     });
 }
 
+#[gpui::test]
+async fn test_reindent(cx: &mut gpui::TestAppContext) {
+    cx.update(|cx| init_settings(cx, |_| {}));
+
+    let text = [
+        "fn a() {",
+        "b();  ",
+        "      if c {",
+        "d();",
+        "    }",
+        "  ",
+        "",
+        "   ",
+        "e();",
+        "}",
+    ]
+    .join("\n");
+
+    // Formatting the whole buffer fixes every line.
+    let buffer = cx.new(|cx| Buffer::local(text.clone(), cx).with_language(rust_lang(), cx));
+    buffer
+        .read_with(cx, |buffer, _| buffer.parsing_idle())
+        .await;
+    let diff = buffer
+        .update(cx, |buffer, cx| buffer.reindent(None, cx))
+        .await;
+    buffer.update(cx, |buffer, cx| {
+        buffer.apply_diff(diff, cx);
+        assert_eq!(
+            buffer.text(),
+            [
+                "fn a() {",
+                "    b();",
+                "    if c {",
+                "        d();",
+                "    }",
+                "",
+                "    e();",
+                "}",
+            ]
+            .join("\n")
+        );
+    });
+
+    // Formatting a selection leaves the other rows untouched.
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(rust_lang(), cx));
+    buffer
+        .read_with(cx, |buffer, _| buffer.parsing_idle())
+        .await;
+    let diff = buffer
+        .update(cx, |buffer, cx| buffer.reindent(Some(&[1..3, 6..8]), cx))
+        .await;
+    buffer.update(cx, |buffer, cx| {
+        buffer.apply_diff(diff, cx);
+        assert_eq!(
+            buffer.text(),
+            [
+                "fn a() {",
+                "    b();",
+                "    if c {",
+                "d();",
+                "    }",
+                "  ",
+                "",
+                "e();",
+                "}",
+            ]
+            .join("\n")
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_reindent_skips_strings_and_comments(cx: &mut gpui::TestAppContext) {
+    cx.update(|cx| init_settings(cx, |_| {}));
+
+    let text = [
+        "fn a() {",
+        "let s = \"one  ",
+        "  two",
+        "",
+        "",
+        "three\";",
+        "/* note",
+        "   keep */",
+        "b();",
+        "}",
+    ]
+    .join("\n");
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(rust_lang(), cx));
+    buffer
+        .read_with(cx, |buffer, _| buffer.parsing_idle())
+        .await;
+    let diff = buffer
+        .update(cx, |buffer, cx| buffer.reindent(None, cx))
+        .await;
+    buffer.update(cx, |buffer, cx| {
+        buffer.apply_diff(diff, cx);
+        assert_eq!(
+            buffer.text(),
+            [
+                "fn a() {",
+                "    let s = \"one  ",
+                "  two",
+                "",
+                "",
+                "three\";",
+                "    /* note",
+                "   keep */",
+                "    b();",
+                "}",
+            ]
+            .join("\n")
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_reindent_keeps_significant_indentation(cx: &mut gpui::TestAppContext) {
+    cx.update(|cx| init_settings(cx, |_| {}));
+
+    let text = ["def a():", "  if b:", "      c()  ", "", "", "  d()"].join("\n");
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(python_lang(), cx));
+    buffer
+        .read_with(cx, |buffer, _| buffer.parsing_idle())
+        .await;
+    let diff = buffer
+        .update(cx, |buffer, cx| buffer.reindent(None, cx))
+        .await;
+    buffer.update(cx, |buffer, cx| {
+        buffer.apply_diff(diff, cx);
+        assert_eq!(
+            buffer.text(),
+            ["def a():", "  if b:", "      c()", "", "  d()"].join("\n")
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_final_newline_in_range_crlf(cx: &mut gpui::TestAppContext) {
     let text = "line0\r\nline1\r\nline2";
@@ -1631,6 +1631,12 @@ pub fn rust_lang() -> Arc<Language> {
     test_language("rust", tree_sitter_rust::LANGUAGE.into())
 }
 
+#[doc(hidden)]
+#[cfg(any(test, feature = "test-support"))]
+pub fn python_lang() -> Arc<Language> {
+    test_language("python", tree_sitter_python::LANGUAGE.into())
+}
+
 #[doc(hidden)]
 #[cfg(any(test, feature = "test-support"))]
 pub fn json_lang() -> Arc<Language> {
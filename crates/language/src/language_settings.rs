@@ -150,6 +150,8 @@ pub struct LanguageSettings {
     pub code_actions_on_format: HashMap<String, bool>,
     /// Whether to perform linked edits
     pub linked_edits: bool,
+    /// Whether to expand Emmet abbreviations in HTML, JSX and CSS.
+    pub emmet: bool,
     /// Task configuration for this language.
     pub tasks: LanguageTaskSettings,
     /// Whether to pop the completions menu while typing in an editor without
@@ -853,6 +855,7 @@ impl settings::Settings for AllLanguageSettings {
                     .unwrap(),
                 code_actions_on_format: settings.code_actions_on_format.unwrap(),
                 linked_edits: settings.linked_edits.unwrap(),
+                emmet: settings.emmet.unwrap(),
                 tasks: LanguageTaskSettings {
                     variables: tasks.variables.unwrap_or_default(),
                     enabled: tasks.enabled.unwrap(),
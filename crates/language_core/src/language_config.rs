@@ -50,6 +50,10 @@ pub struct LanguageConfig {
     // Whether indentation of pasted content should be adjusted based on the context.
     #[serde(default)]
     pub auto_indent_on_paste: Option<bool>,
+    /// Whether indentation is part of the syntax of this language, as in Python,
+    /// so that formatting with the indentation queries must not re-indent lines.
+    #[serde(default)]
+    pub significant_indentation: bool,
     /// A regex that is used to determine whether the indentation level should be
     /// increased in the following line.
     #[serde(default, deserialize_with = "deserialize_regex")]
@@ -167,6 +171,7 @@ impl Default for LanguageConfig {
             brackets: Default::default(),
             auto_indent_using_last_non_empty_line: default_true(),
             auto_indent_on_paste: None,
+            significant_indentation: false,
             increase_indent_pattern: Default::default(),
             decrease_indent_pattern: Default::default(),
             decrease_indent_patterns: Default::default(),
@@ -0,0 +1,453 @@
+use anyhow::Result;
+use parking_lot::Mutex;
+use regex::bytes::{Regex, RegexBuilder};
+use std::{ops::Range, path::Path};
+
+use crate::paged_file::{PAGE_SIZE, PagedFile};
+
+/// The index records the offset of every `LINES_PER_CHECKPOINT`th line, so
+/// that it stays small for files with billions of lines.
+const LINES_PER_CHECKPOINT: u64 = 1024;
+const SEARCH_CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+/// Longer queries are rejected, so that chunks only need to overlap by this
+/// much for matches spanning two chunks to be found.
+pub const MAX_QUERY_LEN: usize = 1024;
+/// Lines are cut off after this many bytes when displayed.
+pub const MAX_DISPLAYED_LINE_LEN: usize = 4096;
+
+/// A read-only view of a file on disk that is read in pages on demand, so
+/// that files much larger than the available memory can be displayed.
+///
+/// Lines are numbered from 0. They are found through a sparse line index that
+/// is built incrementally by [`LargeFile::index_step`], and only the lines
+/// before [`LargeFile::indexed_len`] can be addressed by number.
+pub struct LargeFile {
+    file: PagedFile,
+    state: Mutex<State>,
+}
+
+struct State {
+    len: u64,
+    index: LineIndex,
+}
+
+#[derive(Default)]
+struct LineIndex {
+    /// `checkpoints[i]` is the offset of line `i * LINES_PER_CHECKPOINT`.
+    checkpoints: Vec<u64>,
+    indexed_len: u64,
+    newline_count: u64,
+}
+
+impl LineIndex {
+    fn new() -> Self {
+        Self {
+            checkpoints: vec![0],
+            ..Default::default()
+        }
+    }
+}
+
+impl LargeFile {
+    pub fn open(path: &Path) -> Result<Self> {
+        let (file, len) = PagedFile::open(path)?;
+        Ok(Self {
+            file,
+            state: Mutex::new(State {
+                len,
+                index: LineIndex::new(),
+            }),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    pub fn len(&self) -> u64 {
+        self.state.lock().len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of bytes from the start of the file that have been indexed.
+    pub fn indexed_len(&self) -> u64 {
+        self.state.lock().index.indexed_len
+    }
+
+    pub fn is_indexed(&self) -> bool {
+        let state = self.state.lock();
+        state.index.indexed_len == state.len
+    }
+
+    /// The number of lines known so far. This is the number of lines in the
+    /// file once it [`is_indexed`](Self::is_indexed).
+    pub fn line_count(&self) -> u64 {
+        self.state.lock().index.newline_count + 1
+    }
+
+    /// Indexes the lines in up to `max_bytes` more bytes of the file, and
+    /// returns whether the whole file is indexed.
+    pub fn index_step(&self, max_bytes: u64) -> Result<bool> {
+        let (start, len) = {
+            let state = self.state.lock();
+            (state.index.indexed_len, state.len)
+        };
+        let end = len.min(start.saturating_add(max_bytes));
+        let mut offset = start;
+        let mut chunk = vec![0; PAGE_SIZE as usize * 16];
+        while offset < end {
+            let chunk_len = (end - offset).min(chunk.len() as u64) as usize;
+            let read_len = self.file.read_at(offset, &mut chunk[..chunk_len])?;
+            if read_len == 0 {
+                break;
+            }
+
+            let mut state = self.state.lock();
+            // The file was truncated or reset while reading this chunk.
+            if state.index.indexed_len != offset {
+                return Ok(false);
+            }
+            let index = &mut state.index;
+            for (i, byte) in chunk[..read_len].iter().enumerate() {
+                if *byte == b'\n' {
+                    index.newline_count += 1;
+                    if index.newline_count.is_multiple_of(LINES_PER_CHECKPOINT) {
+                        index.checkpoints.push(offset + i as u64 + 1);
+                    }
+                }
+            }
+            index.indexed_len += read_len as u64;
+            offset += read_len as u64;
+        }
+        Ok(self.is_indexed())
+    }
+
+    /// Re-reads the length of the file from disk, to follow a file that is
+    /// being appended to. Returns whether the length changed. The index is
+    /// reset if the file got shorter.
+    pub fn refresh_len(&self) -> Result<bool> {
+        let new_len = std::fs::metadata(self.path())?.len();
+        let mut state = self.state.lock();
+        if new_len == state.len {
+            return Ok(false);
+        }
+        if new_len < state.len {
+            state.index = LineIndex::new();
+            self.file.clear_pages();
+        } else {
+            // The last page may have been cached before it was complete.
+            self.file.forget_page(state.len / PAGE_SIZE);
+        }
+        state.len = new_len;
+        Ok(true)
+    }
+
+    /// Returns the offset at which `line` starts, if it is indexed.
+    pub fn offset_for_line(&self, line: u64) -> Result<Option<u64>> {
+        let checkpoint = {
+            let state = self.state.lock();
+            if line > state.index.newline_count {
+                return Ok(None);
+            }
+            state.index.checkpoints[(line / LINES_PER_CHECKPOINT) as usize]
+        };
+        let mut remaining = line % LINES_PER_CHECKPOINT;
+        let mut offset = checkpoint;
+        while remaining > 0 {
+            let bytes = self.file.bytes_at(offset)?;
+            if bytes.is_empty() {
+                // The file was truncated since it was indexed.
+                return Ok(None);
+            }
+            for (i, byte) in bytes.iter().enumerate() {
+                if *byte == b'\n' {
+                    remaining -= 1;
+                    if remaining == 0 {
+                        return Ok(Some(offset + i as u64 + 1));
+                    }
+                }
+            }
+            offset += bytes.len() as u64;
+        }
+        Ok(Some(offset))
+    }
+
+    /// Returns the byte range of the given line, without its line ending.
+    pub fn line_range(&self, line: u64) -> Result<Option<Range<u64>>> {
+        let Some(start) = self.offset_for_line(line)? else {
+            return Ok(None);
+        };
+        let end = self.scan_line(start, |_| {})?;
+        Ok(Some(start..end))
+    }
+
+    /// Returns the text of the lines in `range`, cutting off each line after
+    /// [`MAX_DISPLAYED_LINE_LEN`] bytes. Lines that are not indexed yet are
+    /// omitted.
+    pub fn read_lines(&self, range: Range<u64>) -> Result<Vec<String>> {
+        let Some(mut offset) = self.offset_for_line(range.start)? else {
+            return Ok(Vec::new());
+        };
+        let len = self.len();
+        let last_line = range.end.min(self.line_count());
+        let mut lines = Vec::new();
+        let mut bytes = Vec::new();
+        for _ in range.start..last_line {
+            bytes.clear();
+            let end = self.scan_line(offset, |chunk| {
+                let room = MAX_DISPLAYED_LINE_LEN.saturating_sub(bytes.len());
+                bytes.extend_from_slice(&chunk[..chunk.len().min(room)]);
+            })?;
+            if bytes.last() == Some(&b'\r') {
+                bytes.pop();
+            }
+            lines.push(String::from_utf8_lossy(&bytes).replace('\t', "    "));
+            offset = end + 1;
+            if offset > len {
+                break;
+            }
+        }
+        Ok(lines)
+    }
+
+    /// Returns the text from the start of line `range.start` to the end of
+    /// line `range.end - 1`, or `None` if it is longer than `max_len` bytes.
+    pub fn read_text(&self, range: Range<u64>, max_len: u64) -> Result<Option<String>> {
+        let (Some(start), Some(last_line)) = (
+            self.offset_for_line(range.start)?,
+            self.line_range(range.end.saturating_sub(1).max(range.start))?,
+        ) else {
+            return Ok(None);
+        };
+        let len = last_line.end.saturating_sub(start);
+        if len > max_len {
+            return Ok(None);
+        }
+        let mut bytes = vec![0; len as usize];
+        let read_len = self.file.read_at(start, &mut bytes)?;
+        bytes.truncate(read_len);
+        Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+    }
+
+    /// Searches for `query`, ignoring case, in the lines from `start_line`
+    /// on when searching forward, or in the lines before it when searching
+    /// backward. Returns the line of the closest match.
+    ///
+    /// This reads the file in chunks and yields between them, so that
+    /// dropping the returned future cancels the search.
+    pub async fn find(&self, query: &str, start_line: u64, forward: bool) -> Result<Option<u64>> {
+        let regex = search_regex(query)?;
+        let Some(offset) = self.offset_for_line(start_line)? else {
+            return Ok(None);
+        };
+        if forward {
+            self.find_forward(&regex, offset, start_line).await
+        } else {
+            self.find_backward(&regex, offset, start_line).await
+        }
+    }
+
+    async fn find_forward(
+        &self,
+        regex: &Regex,
+        start: u64,
+        start_line: u64,
+    ) -> Result<Option<u64>> {
+        let len = self.len();
+        let mut line = start_line;
+        let mut offset = start;
+        let mut chunk = Vec::new();
+        while offset < len {
+            let chunk_end = len.min(offset + SEARCH_CHUNK_SIZE);
+            let read_end = len.min(chunk_end + MAX_QUERY_LEN as u64);
+            chunk.resize((read_end - offset) as usize, 0);
+            let read_len = self.file.read_at(offset, &mut chunk)?;
+            chunk.truncate(read_len);
+
+            let searched_len = ((chunk_end - offset) as usize).min(chunk.len());
+            if let Some(found) = regex
+                .find_iter(&chunk)
+                .find(|found| found.start() < searched_len)
+            {
+                return Ok(Some(line + count_newlines(&chunk[..found.start()])));
+            }
+            line += count_newlines(&chunk[..searched_len]);
+            offset = chunk_end;
+            smol::future::yield_now().await;
+        }
+        Ok(None)
+    }
+
+    async fn find_backward(&self, regex: &Regex, end: u64, end_line: u64) -> Result<Option<u64>> {
+        let mut line = end_line;
+        let mut offset = end;
+        let mut chunk = Vec::new();
+        while offset > 0 {
+            let chunk_start = offset.saturating_sub(SEARCH_CHUNK_SIZE);
+            // Matches must start before `offset`, but may extend past it.
+            let read_end = end.min(offset + MAX_QUERY_LEN as u64);
+            chunk.resize((read_end - chunk_start) as usize, 0);
+            let read_len = self.file.read_at(chunk_start, &mut chunk)?;
+            chunk.truncate(read_len);
+
+            let searched_len = ((offset - chunk_start) as usize).min(chunk.len());
+            if let Some(found) = regex
+                .find_iter(&chunk)
+                .filter(|found| found.start() < searched_len)
+                .last()
+            {
+                return Ok(Some(
+                    line - count_newlines(&chunk[found.start()..searched_len]),
+                ));
+            }
+            line -= count_newlines(&chunk[..searched_len]);
+            offset = chunk_start;
+            smol::future::yield_now().await;
+        }
+        Ok(None)
+    }
+
+    /// Calls `f` with the bytes of the line starting at `start`, in chunks,
+    /// and returns the offset of its end.
+    fn scan_line(&self, start: u64, mut f: impl FnMut(&[u8])) -> Result<u64> {
+        let len = self.len();
+        let mut offset = start;
+        while offset < len {
+            let bytes = self.file.bytes_at(offset)?;
+            if bytes.is_empty() {
+                break;
+            }
+            if let Some(newline) = bytes.iter().position(|byte| *byte == b'\n') {
+                f(&bytes[..newline]);
+                return Ok(offset + newline as u64);
+            }
+            f(&bytes);
+            offset += bytes.len() as u64;
+        }
+        Ok(offset)
+    }
+}
+
+fn search_regex(query: &str) -> Result<Regex> {
+    anyhow::ensure!(
+        query.len() <= MAX_QUERY_LEN,
+        "search queries are limited to {MAX_QUERY_LEN} bytes"
+    );
+    Ok(RegexBuilder::new(&regex::escape(query))
+        .case_insensitive(true)
+        .build()?)
+}
+
+fn count_newlines(bytes: &[u8]) -> u64 {
+    bytes.iter().filter(|byte| **byte == b'\n').count() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write as _;
+
+    fn write_lines(file: &mut File, range: Range<u64>) {
+        for line in range {
+            writeln!(file, "line {line}").unwrap();
+        }
+    }
+
+    #[test]
+    fn test_line_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.txt");
+        let mut file = File::create(&path).unwrap();
+        write_lines(&mut file, 0..5000);
+
+        let large_file = LargeFile::open(&path).unwrap();
+        assert_eq!(large_file.line_count(), 1);
+        assert_eq!(large_file.offset_for_line(1).unwrap(), None);
+
+        while !large_file.index_step(1000).unwrap() {}
+        assert_eq!(large_file.line_count(), 5001);
+        assert_eq!(
+            large_file.read_lines(2047..2050).unwrap(),
+            ["line 2047", "line 2048", "line 2049"]
+        );
+        assert_eq!(
+            large_file.read_lines(4998..5010).unwrap(),
+            ["line 4998", "line 4999", ""]
+        );
+        assert_eq!(
+            large_file.read_text(1..3, 1024).unwrap().as_deref(),
+            Some("line 1\nline 2")
+        );
+        assert_eq!(large_file.read_text(0..5000, 1024).unwrap(), None);
+    }
+
+    #[test]
+    fn test_long_lines_are_cut_off() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dump.sql");
+        let mut file = File::create(&path).unwrap();
+        let long_line = "x".repeat(PAGE_SIZE as usize * 3);
+        writeln!(file, "{long_line}\r\nshort\tline").unwrap();
+
+        let large_file = LargeFile::open(&path).unwrap();
+        while !large_file.index_step(u64::MAX).unwrap() {}
+        let lines = large_file.read_lines(0..2).unwrap();
+        assert_eq!(lines[0].len(), MAX_DISPLAYED_LINE_LEN);
+        assert_eq!(lines[1], "short    line");
+    }
+
+    #[test]
+    fn test_find() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.txt");
+        let mut file = File::create(&path).unwrap();
+        write_lines(&mut file, 0..3000);
+
+        let large_file = LargeFile::open(&path).unwrap();
+        while !large_file.index_step(u64::MAX).unwrap() {}
+        smol::block_on(async {
+            assert_eq!(
+                large_file.find("LINE 2500", 0, true).await.unwrap(),
+                Some(2500)
+            );
+            assert_eq!(
+                large_file.find("line 25", 26, true).await.unwrap(),
+                Some(250)
+            );
+            assert_eq!(
+                large_file.find("line 25", 250, false).await.unwrap(),
+                Some(25)
+            );
+            assert_eq!(large_file.find("line 25", 25, false).await.unwrap(), None);
+            assert_eq!(large_file.find("missing", 0, true).await.unwrap(), None);
+        });
+    }
+
+    #[test]
+    fn test_follow_growing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.txt");
+        let mut file = File::create(&path).unwrap();
+        write_lines(&mut file, 0..10);
+
+        let large_file = LargeFile::open(&path).unwrap();
+        while !large_file.index_step(u64::MAX).unwrap() {}
+        assert_eq!(large_file.line_count(), 11);
+        assert!(!large_file.refresh_len().unwrap());
+
+        write_lines(&mut file, 10..20);
+        assert!(large_file.refresh_len().unwrap());
+        assert!(!large_file.is_indexed());
+        while !large_file.index_step(u64::MAX).unwrap() {}
+        assert_eq!(large_file.line_count(), 21);
+        assert_eq!(large_file.read_lines(19..20).unwrap(), ["line 19"]);
+
+        file.set_len(0).unwrap();
+        assert!(large_file.refresh_len().unwrap());
+        assert_eq!(large_file.line_count(), 1);
+        assert!(large_file.is_indexed());
+    }
+}
@@ -0,0 +1,913 @@
+mod large_file;
+mod large_file_viewer_settings;
+mod paged_file;
+
+use std::{ops::Range, path::Path, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use editor::{
+    Editor, EditorSettings, RevealInFileManager,
+    actions::{Copy, MoveDown, MovePageDown, MovePageUp, MoveToBeginning, MoveToEnd, MoveUp},
+    items::entry_git_aware_label_color,
+};
+use file_icons::FileIcons;
+use gpui::{
+    AnyElement, App, ClipboardItem, Context, Entity, EventEmitter, FocusHandle, Focusable, Font,
+    MouseButton, MouseDownEvent, Pixels, Render, ScrollWheelEvent, Task, Window, actions, canvas,
+    div, px, relative,
+};
+use project::{Project, ProjectEntryId, ProjectPath};
+use settings::Settings;
+use theme_settings::ThemeSettings;
+use ui::{Tooltip, prelude::*};
+use util::{ResultExt as _, paths::PathExt, size::format_file_size};
+use workspace::{
+    ItemSettings, Pane, ToolbarItemLocation, WorkspaceId,
+    invalid_item_view::InvalidItemView,
+    item::{HighlightedText, Item, ProjectItem, TabContentParams},
+};
+
+pub use crate::large_file::*;
+pub use crate::large_file_viewer_settings::*;
+pub use crate::paged_file::*;
+
+actions!(
+    large_file_viewer,
+    [
+        /// Focuses the search input.
+        FocusSearch,
+        /// Selects the next line that matches the search query.
+        SelectNextMatch,
+        /// Selects the previous line that matches the search query.
+        SelectPreviousMatch,
+        /// Focuses the go-to-line input.
+        GoToLine,
+        /// Toggles following the end of a file that is being appended to.
+        ToggleFollow
+    ]
+);
+
+/// How many bytes are indexed between two refreshes of the views.
+const INDEX_STEP_LEN: u64 = 64 * 1024 * 1024;
+const FOLLOW_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_COPY_LEN: u64 = 16 * 1024 * 1024;
+
+/// A file that is too large to be opened in the editor, read from disk on
+/// demand by a [`LargeFileView`].
+pub struct LargeFileItem {
+    file: Arc<LargeFile>,
+    project_path: ProjectPath,
+    entry_id: ProjectEntryId,
+    index_task: Option<Task<()>>,
+    index_error: Option<SharedString>,
+}
+
+impl LargeFileItem {
+    pub fn file(&self) -> &Arc<LargeFile> {
+        &self.file
+    }
+
+    /// Indexes the lines of the file in the background, if it isn't already
+    /// being indexed.
+    fn index(&mut self, cx: &mut Context<Self>) {
+        if self.index_task.is_some() {
+            return;
+        }
+        let file = self.file.clone();
+        self.index_task = Some(cx.spawn(async move |this, cx| {
+            loop {
+                let result = cx
+                    .background_spawn({
+                        let file = file.clone();
+                        async move { file.index_step(INDEX_STEP_LEN) }
+                    })
+                    .await;
+                let done = this.update(cx, |this, cx| {
+                    cx.notify();
+                    match result {
+                        Ok(done) => done,
+                        Err(error) => {
+                            log::error!("failed to index {}: {error:#}", file.path().display());
+                            this.index_error = Some(error.to_string().into());
+                            true
+                        }
+                    }
+                });
+                if done.unwrap_or(true) {
+                    break;
+                }
+            }
+            this.update(cx, |this, _| this.index_task = None).ok();
+        }));
+    }
+
+    /// Picks up the bytes appended to the file since it was last indexed.
+    fn refresh(&mut self, cx: &mut Context<Self>) -> Task<()> {
+        let file = self.file.clone();
+        cx.spawn(async move |this, cx| {
+            let changed = cx
+                .background_spawn(async move { file.refresh_len() })
+                .await
+                .log_err()
+                .unwrap_or(false);
+            if changed {
+                this.update(cx, |this, cx| {
+                    this.index(cx);
+                    cx.notify();
+                })
+                .ok();
+            }
+        })
+    }
+}
+
+impl project::ProjectItem for LargeFileItem {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        let project = project.read(cx);
+        // Remote files are still loaded into a buffer on the host.
+        if !project.is_local() {
+            return None;
+        }
+        let entry = project.entry_for_path(path, cx)?;
+        if !entry.is_file() || !LargeFileViewerSettings::get_global(cx).should_open(entry.size) {
+            return None;
+        }
+        let abs_path = project.absolute_path(path, cx)?;
+        let project_path = path.clone();
+        let entry_id = entry.id;
+
+        Some(cx.spawn(async move |cx| {
+            let file = cx
+                .background_spawn(async move { LargeFile::open(&abs_path) })
+                .await?;
+            Ok(cx.new(|cx| {
+                let mut item = Self {
+                    file: Arc::new(file),
+                    project_path,
+                    entry_id,
+                    index_task: None,
+                    index_error: None,
+                };
+                item.index(cx);
+                item
+            }))
+        }))
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        Some(self.entry_id)
+    }
+
+    fn project_path(&self, _: &App) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+
+    fn is_dirty(&self) -> bool {
+        false
+    }
+}
+
+/// A read-only view of a [`LargeFileItem`], without syntax highlighting or
+/// language servers, that only reads the lines it displays.
+pub struct LargeFileView {
+    item: Entity<LargeFileItem>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    search_editor: Entity<Editor>,
+    go_to_line_editor: Entity<Editor>,
+    top_line: u64,
+    visible_line_count: u64,
+    scroll_remainder: Pixels,
+    /// The line the selection started at and the line it ends at.
+    selection: Option<(u64, u64)>,
+    status: Option<SharedString>,
+    search_task: Option<Task<()>>,
+    follow_task: Option<Task<()>>,
+    _item_subscription: gpui::Subscription,
+}
+
+impl LargeFileView {
+    pub fn new(
+        item: Entity<LargeFileItem>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let search_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Search…", window, cx);
+            editor
+        });
+        let go_to_line_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Line", window, cx);
+            editor
+        });
+        let item_subscription = cx.observe(&item, |this, _, cx| {
+            if this.follow_task.is_some() {
+                this.scroll_to_end(cx);
+            }
+            cx.notify();
+        });
+
+        Self {
+            item,
+            project,
+            focus_handle: cx.focus_handle(),
+            search_editor,
+            go_to_line_editor,
+            top_line: 0,
+            visible_line_count: 50,
+            scroll_remainder: px(0.),
+            selection: None,
+            status: None,
+            search_task: None,
+            follow_task: None,
+            _item_subscription: item_subscription,
+        }
+    }
+
+    fn file(&self, cx: &App) -> Arc<LargeFile> {
+        self.item.read(cx).file.clone()
+    }
+
+    fn selected_lines(&self) -> Option<Range<u64>> {
+        self.selection
+            .map(|(start, end)| start.min(end)..start.max(end) + 1)
+    }
+
+    fn max_top_line(&self, cx: &App) -> u64 {
+        self.file(cx)
+            .line_count()
+            .saturating_sub(self.visible_line_count)
+    }
+
+    fn set_top_line(&mut self, line: u64, cx: &mut Context<Self>) {
+        self.top_line = line.min(self.max_top_line(cx));
+        cx.notify();
+    }
+
+    fn scroll_to_end(&mut self, cx: &App) {
+        self.top_line = self.max_top_line(cx);
+    }
+
+    /// Scrolls as little as possible to show `line`.
+    fn autoscroll(&mut self, line: u64, cx: &mut Context<Self>) {
+        if line < self.top_line {
+            self.set_top_line(line, cx);
+        } else if line >= self.top_line + self.visible_line_count {
+            self.set_top_line(line + 1 - self.visible_line_count, cx);
+        }
+    }
+
+    fn select_line(&mut self, line: u64, extend: bool, cx: &mut Context<Self>) {
+        let line = line.min(self.file(cx).line_count() - 1);
+        self.selection = match self.selection {
+            Some((start, _)) if extend => Some((start, line)),
+            _ => Some((line, line)),
+        };
+        self.autoscroll(line, cx);
+        cx.notify();
+    }
+
+    /// Selects `line` and scrolls it to the middle of the view.
+    fn reveal_line(&mut self, line: u64, cx: &mut Context<Self>) {
+        self.selection = Some((line, line));
+        self.set_top_line(line.saturating_sub(self.visible_line_count / 2), cx);
+    }
+
+    fn move_selection(&mut self, delta: i64, cx: &mut Context<Self>) {
+        self.stop_following();
+        let line = match self.selection {
+            Some((_, end)) => end.saturating_add_signed(delta),
+            None => self.top_line,
+        };
+        self.select_line(line, false, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_selection(-1, cx);
+    }
+
+    fn move_down(&mut self, _: &MoveDown, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_selection(1, cx);
+    }
+
+    fn move_page_up(&mut self, _: &MovePageUp, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_selection(-(self.visible_line_count as i64), cx);
+    }
+
+    fn move_page_down(&mut self, _: &MovePageDown, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_selection(self.visible_line_count as i64, cx);
+    }
+
+    fn move_to_beginning(
+        &mut self,
+        _: &MoveToBeginning,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.stop_following();
+        self.select_line(0, false, cx);
+    }
+
+    fn move_to_end(&mut self, _: &MoveToEnd, _window: &mut Window, cx: &mut Context<Self>) {
+        self.select_line(u64::MAX, false, cx);
+    }
+
+    fn handle_scroll_wheel(
+        &mut self,
+        event: &ScrollWheelEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let line_height = line_height(cx);
+        self.scroll_remainder -= event.delta.pixel_delta(line_height).y;
+        let lines = (self.scroll_remainder / line_height).trunc();
+        if lines == 0. {
+            return;
+        }
+        self.scroll_remainder -= line_height * lines;
+        if lines < 0. {
+            self.stop_following();
+        }
+        let top_line = self.top_line.saturating_add_signed(lines as i64);
+        self.set_top_line(top_line, cx);
+    }
+
+    fn handle_line_mouse_down(
+        &mut self,
+        line: u64,
+        event: &MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.focus_handle.focus(window, cx);
+        self.select_line(line, event.modifiers.shift, cx);
+    }
+
+    fn set_visible_line_count(&mut self, visible_line_count: u64, cx: &mut Context<Self>) {
+        self.visible_line_count = visible_line_count;
+        if self.follow_task.is_some() {
+            self.scroll_to_end(cx);
+        }
+        cx.notify();
+    }
+
+    fn copy(&mut self, _: &Copy, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(lines) = self.selected_lines() else {
+            return;
+        };
+        let file = self.file(cx);
+        cx.spawn(async move |this, cx| {
+            let text = cx
+                .background_spawn(async move { file.read_text(lines, MAX_COPY_LEN) })
+                .await;
+            this.update(cx, |this, cx| {
+                match text {
+                    Ok(Some(text)) => cx.write_to_clipboard(ClipboardItem::new_string(text)),
+                    Ok(None) => {
+                        this.status = Some(
+                            format!(
+                                "The selection is larger than {} and was not copied",
+                                format_file_size(MAX_COPY_LEN, false)
+                            )
+                            .into(),
+                        );
+                    }
+                    Err(error) => this.status = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn reveal_in_file_manager(
+        &mut self,
+        _: &RevealInFileManager,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let path = self.file(cx).path().to_path_buf();
+        self.project
+            .update(cx, |project, cx| project.reveal_path(&path, cx));
+    }
+
+    fn focus_search(&mut self, _: &FocusSearch, window: &mut Window, cx: &mut Context<Self>) {
+        self.search_editor.update(cx, |editor, cx| {
+            editor.select_all(&editor::actions::SelectAll, window, cx);
+        });
+        self.search_editor.focus_handle(cx).focus(window, cx);
+    }
+
+    fn select_next_match(&mut self, _: &SelectNextMatch, _: &mut Window, cx: &mut Context<Self>) {
+        self.search(true, cx);
+    }
+
+    fn select_previous_match(
+        &mut self,
+        _: &SelectPreviousMatch,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.search(false, cx);
+    }
+
+    fn search(&mut self, forward: bool, cx: &mut Context<Self>) {
+        let query = self.search_editor.read(cx).text(cx);
+        if query.is_empty() {
+            return;
+        }
+        self.stop_following();
+        let start_line = match self.selection {
+            Some((_, end)) if forward => end + 1,
+            Some((_, end)) => end,
+            None => self.top_line,
+        };
+        let file = self.file(cx);
+        self.status = Some("Searching…".into());
+        self.search_task = Some(cx.spawn(async move |this, cx| {
+            let result = cx
+                .background_spawn(async move { file.find(&query, start_line, forward).await })
+                .await;
+            this.update(cx, |this, cx| {
+                this.status = match result {
+                    Ok(Some(line)) => {
+                        this.reveal_line(line, cx);
+                        None
+                    }
+                    Ok(None) if forward => Some("No more matches below".into()),
+                    Ok(None) => Some("No more matches above".into()),
+                    Err(error) => Some(error.to_string().into()),
+                };
+                this.search_task = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn go_to_line(&mut self, _: &GoToLine, window: &mut Window, cx: &mut Context<Self>) {
+        self.go_to_line_editor.update(cx, |editor, cx| {
+            editor.select_all(&editor::actions::SelectAll, window, cx);
+        });
+        self.go_to_line_editor.focus_handle(cx).focus(window, cx);
+    }
+
+    fn confirm_go_to_line(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self.go_to_line_editor.read(cx).text(cx);
+        let Some(line) = text.trim().parse::<u64>().ok().filter(|line| *line > 0) else {
+            return;
+        };
+        let file = self.file(cx);
+        self.status = if line <= file.line_count() {
+            self.stop_following();
+            self.reveal_line(line - 1, cx);
+            self.focus_handle.focus(window, cx);
+            None
+        } else if file.is_indexed() {
+            Some(format!("The file has {} lines", file.line_count()).into())
+        } else {
+            Some(format!("Line {line} has not been indexed yet").into())
+        };
+        cx.notify();
+    }
+
+    fn toggle_follow(&mut self, _: &ToggleFollow, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.follow_task.take().is_some() {
+            cx.notify();
+            return;
+        }
+        self.selection = None;
+        self.scroll_to_end(cx);
+        let item = self.item.clone();
+        self.follow_task = Some(cx.spawn(async move |_, cx| {
+            loop {
+                item.update(cx, |item, cx| item.refresh(cx)).await;
+                cx.background_executor().timer(FOLLOW_INTERVAL).await;
+            }
+        }));
+        cx.notify();
+    }
+
+    fn stop_following(&mut self) {
+        self.follow_task = None;
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let item = self.item.read(cx);
+        let file = &item.file;
+        let len = file.len();
+        let progress = if let Some(error) = item.index_error.clone() {
+            error
+        } else if file.is_indexed() {
+            format!("{} lines", file.line_count()).into()
+        } else {
+            let percent = file.indexed_len() as f64 / len.max(1) as f64 * 100.;
+            format!("Indexing lines… {percent:.0}%").into()
+        };
+        let focus_handle = self.focus_handle.clone();
+
+        h_flex()
+            .flex_none()
+            .gap_2()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new(format!(
+                    "{} · {progress} · Read-only",
+                    format_file_size(len, false)
+                ))
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+            )
+            .when_some(self.status.clone(), |this, status| {
+                this.child(Label::new(status).size(LabelSize::Small))
+            })
+            .child(div().flex_1())
+            .child(
+                h_flex()
+                    .w_48()
+                    .px_1()
+                    .rounded_sm()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(self.search_editor.clone())
+                    .on_action(cx.listener(|this, _: &menu::Confirm, _window, cx| {
+                        this.search(true, cx);
+                    })),
+            )
+            .child(
+                IconButton::new("previous-match", IconName::ChevronUp)
+                    .icon_size(IconSize::Small)
+                    .disabled(self.search_task.is_some())
+                    .tooltip({
+                        let focus_handle = focus_handle.clone();
+                        move |_window, cx| {
+                            Tooltip::for_action_in(
+                                "Previous Match",
+                                &SelectPreviousMatch,
+                                &focus_handle,
+                                cx,
+                            )
+                        }
+                    })
+                    .on_click(cx.listener(|this, _, _window, cx| this.search(false, cx))),
+            )
+            .child(
+                IconButton::new("next-match", IconName::ChevronDown)
+                    .icon_size(IconSize::Small)
+                    .disabled(self.search_task.is_some())
+                    .tooltip({
+                        let focus_handle = focus_handle.clone();
+                        move |_window, cx| {
+                            Tooltip::for_action_in(
+                                "Next Match",
+                                &SelectNextMatch,
+                                &focus_handle,
+                                cx,
+                            )
+                        }
+                    })
+                    .on_click(cx.listener(|this, _, _window, cx| this.search(true, cx))),
+            )
+            .child(
+                h_flex()
+                    .w_20()
+                    .px_1()
+                    .rounded_sm()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(self.go_to_line_editor.clone())
+                    .on_action(cx.listener(|this, _: &menu::Confirm, window, cx| {
+                        this.confirm_go_to_line(window, cx);
+                    })),
+            )
+            .child(
+                IconButton::new("follow", IconName::ArrowDown)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(self.follow_task.is_some())
+                    .tooltip(move |_window, cx| {
+                        Tooltip::for_action_in(
+                            "Follow the End of the File",
+                            &ToggleFollow,
+                            &focus_handle,
+                            cx,
+                        )
+                    })
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.toggle_follow(&ToggleFollow, window, cx);
+                    })),
+            )
+    }
+
+    fn render_lines(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        let file = self.file(cx);
+        let line_count = file.line_count();
+        self.top_line = self.top_line.min(self.max_top_line(cx));
+        let visible_lines =
+            self.top_line..(self.top_line + self.visible_line_count).min(line_count);
+        let lines = file
+            .read_lines(visible_lines.clone())
+            .log_err()
+            .unwrap_or_default();
+
+        let settings = ThemeSettings::get_global(cx);
+        let font_size = settings.buffer_font_size(cx);
+        let line_height = line_height(cx);
+        let colors = cx.theme().colors();
+        let gutter_width = font_size * (line_count.to_string().len() as f32 + 2.) * 0.6;
+        let selected_lines = self.selected_lines().unwrap_or_default();
+        let visible_line_count = self.visible_line_count;
+        let this = cx.weak_entity();
+
+        div()
+            .id("lines")
+            .flex_1()
+            .relative()
+            .overflow_hidden()
+            .font(settings.buffer_font.clone())
+            .text_size(font_size)
+            .line_height(line_height)
+            .on_scroll_wheel(cx.listener(Self::handle_scroll_wheel))
+            .children(lines.into_iter().zip(visible_lines).map(|(text, line)| {
+                h_flex()
+                    .id(("line", line as usize))
+                    .h(line_height)
+                    .w_full()
+                    .when(selected_lines.contains(&line), |this| {
+                        this.bg(colors.editor_active_line_background)
+                    })
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, event, window, cx| {
+                            this.handle_line_mouse_down(line, event, window, cx);
+                        }),
+                    )
+                    .child(
+                        div()
+                            .flex_none()
+                            .w(gutter_width)
+                            .pr_2()
+                            .text_right()
+                            .text_color(if selected_lines.contains(&line) {
+                                colors.editor_active_line_number
+                            } else {
+                                colors.editor_line_number
+                            })
+                            .child((line + 1).to_string()),
+                    )
+                    .child(
+                        div()
+                            .whitespace_nowrap()
+                            .text_color(colors.editor_foreground)
+                            .child(text),
+                    )
+            }))
+            .child(self.render_scrollbar(line_count, cx))
+            .child(
+                canvas(
+                    move |bounds, window, cx| {
+                        let new_visible_line_count =
+                            ((bounds.size.height / line_height).floor() as u64).max(1);
+                        if new_visible_line_count == visible_line_count {
+                            return;
+                        }
+                        window.defer(cx, move |_window, cx| {
+                            this.update(cx, |this, cx| {
+                                this.set_visible_line_count(new_visible_line_count, cx);
+                            })
+                            .ok();
+                        });
+                    },
+                    |_, _, _, _| {},
+                )
+                .absolute()
+                .size_full(),
+            )
+    }
+
+    /// Shows where the visible lines are in the file. Dragging isn't
+    /// supported, as it would need to map pixels to lines of a file that is
+    /// still being indexed.
+    fn render_scrollbar(&self, line_count: u64, cx: &App) -> impl IntoElement {
+        let line_count = line_count.max(1) as f32;
+        let thumb_top = self.top_line as f32 / line_count;
+        let thumb_height = (self.visible_line_count as f32 / line_count).min(1.);
+
+        div().absolute().top_0().right_0().h_full().w_2().child(
+            div()
+                .absolute()
+                .top(relative(thumb_top))
+                .h(relative(thumb_height))
+                .min_h_2()
+                .w_full()
+                .rounded_sm()
+                .bg(cx.theme().colors().scrollbar_thumb_background),
+        )
+    }
+}
+
+fn line_height(cx: &App) -> Pixels {
+    let settings = ThemeSettings::get_global(cx);
+    settings.buffer_font_size(cx) * settings.line_height()
+}
+
+impl EventEmitter<()> for LargeFileView {}
+
+impl Item for LargeFileView {
+    type Event = ();
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.item.entity_id(), self.item.read(cx))
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let path = self.item.read(cx).file.path().compact();
+        Some(path.to_string_lossy().into_owned().into())
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        let project_path = &self.item.read(cx).project_path;
+
+        let label_color = if ItemSettings::get_global(cx).git_status {
+            let git_status = self
+                .project
+                .read(cx)
+                .git_store()
+                .read(cx)
+                .display_status_for_project_path(project_path, cx)
+                .map(|status| status.summary())
+                .unwrap_or_default();
+
+            self.project
+                .read(cx)
+                .entry_for_path(project_path, cx)
+                .map(|entry| {
+                    entry_git_aware_label_color(git_status, entry.is_ignored, params.selected)
+                })
+                .unwrap_or_else(|| params.text_color())
+        } else {
+            params.text_color()
+        };
+
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .single_line()
+            .color(label_color)
+            .when(params.preview, |this| this.italic())
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _: usize, cx: &App) -> SharedString {
+        self.item
+            .read(cx)
+            .file
+            .path()
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned().into())
+            .unwrap_or_default()
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let path = self.item.read(cx).file.path();
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn breadcrumb_location(&self, cx: &App) -> ToolbarItemLocation {
+        let show_breadcrumb = EditorSettings::get_global(cx).toolbar.breadcrumbs;
+        if show_breadcrumb {
+            ToolbarItemLocation::PrimaryLeft
+        } else {
+            ToolbarItemLocation::Hidden
+        }
+    }
+
+    fn breadcrumbs(&self, cx: &App) -> Option<(Vec<HighlightedText>, Option<Font>)> {
+        let project = self.project.read(cx);
+        let project_path = &self.item.read(cx).project_path;
+        let mut path = project_path.path.to_rel_path_buf();
+        if project.visible_worktrees(cx).count() > 1
+            && let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx)
+        {
+            path = worktree.read(cx).root_name().join(&path);
+        }
+        let font = ThemeSettings::get_global(cx).buffer_font.clone();
+
+        Some((
+            vec![HighlightedText {
+                text: path.display(project.path_style(cx)).to_string().into(),
+                highlights: vec![],
+            }],
+            Some(font),
+        ))
+    }
+
+    fn can_split(&self) -> bool {
+        true
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Entity<Self>>>
+    where
+        Self: Sized,
+    {
+        let item = self.item.clone();
+        let project = self.project.clone();
+        let top_line = self.top_line;
+        Task::ready(Some(cx.new(|cx| {
+            let mut view = Self::new(item, project, window, cx);
+            view.top_line = top_line;
+            view
+        })))
+    }
+
+    fn buffer_kind(&self, _: &App) -> workspace::item::ItemBufferKind {
+        workspace::item::ItemBufferKind::Singleton
+    }
+}
+
+impl Focusable for LargeFileView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for LargeFileView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .track_focus(&self.focus_handle(cx))
+            .key_context("LargeFileViewer")
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::move_page_up))
+            .on_action(cx.listener(Self::move_page_down))
+            .on_action(cx.listener(Self::move_to_beginning))
+            .on_action(cx.listener(Self::move_to_end))
+            .on_action(cx.listener(Self::copy))
+            .on_action(cx.listener(Self::reveal_in_file_manager))
+            .on_action(cx.listener(Self::focus_search))
+            .on_action(cx.listener(Self::select_next_match))
+            .on_action(cx.listener(Self::select_previous_match))
+            .on_action(cx.listener(Self::go_to_line))
+            .on_action(cx.listener(Self::toggle_follow))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_header(cx))
+            .child(self.render_lines(cx))
+    }
+}
+
+impl ProjectItem for LargeFileView {
+    type Item = LargeFileItem;
+
+    fn for_project_item(
+        project: Entity<Project>,
+        _: Option<&Pane>,
+        item: Entity<Self::Item>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, project, window, cx)
+    }
+
+    fn for_broken_project_item(
+        abs_path: &Path,
+        is_local: bool,
+        e: &anyhow::Error,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<InvalidItemView>
+    where
+        Self: Sized,
+    {
+        Some(InvalidItemView::new(abs_path, is_local, e, window, cx))
+    }
+}
+
+pub fn init(cx: &mut App) {
+    workspace::register_project_item::<LargeFileView>(cx);
+}
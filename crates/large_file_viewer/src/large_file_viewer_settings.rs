@@ -0,0 +1,32 @@
+use settings::{RegisterSetting, Settings};
+
+/// The settings for the large file viewer.
+#[derive(Clone, Debug, Default, RegisterSetting)]
+pub struct LargeFileViewerSettings {
+    /// The file size, in megabytes, from which files are opened in the large
+    /// file viewer. 0 disables the large file viewer.
+    ///
+    /// Default: 100
+    pub threshold_mb: u64,
+}
+
+impl LargeFileViewerSettings {
+    /// Returns whether a file of `size` bytes should be opened in the large
+    /// file viewer.
+    pub fn should_open(&self, size: u64) -> bool {
+        self.threshold_mb > 0 && size >= self.threshold_mb.saturating_mul(1024 * 1024)
+    }
+}
+
+impl Settings for LargeFileViewerSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        Self {
+            threshold_mb: content
+                .large_file_viewer
+                .clone()
+                .unwrap()
+                .threshold_mb
+                .unwrap(),
+        }
+    }
+}
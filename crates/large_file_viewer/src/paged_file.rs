@@ -0,0 +1,123 @@
+use anyhow::{Context as _, Result};
+use parking_lot::Mutex;
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{Read as _, Seek as _, SeekFrom},
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+pub const PAGE_SIZE: u64 = 64 * 1024;
+const MAX_CACHED_PAGES: usize = 64;
+
+/// A file on disk that is read in pages on demand, keeping the most recently
+/// used pages in memory. Shared by the views that display files too large to
+/// load at once.
+pub struct PagedFile {
+    path: PathBuf,
+    file: Mutex<File>,
+    pages: Mutex<VecDeque<(u64, Arc<[u8]>)>>,
+}
+
+/// The bytes of a cached page from some offset to the end of the page.
+pub struct PageBytes {
+    page: Arc<[u8]>,
+    start: usize,
+}
+
+impl Deref for PageBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.page[self.start.min(self.page.len())..]
+    }
+}
+
+impl PagedFile {
+    /// Opens the file at `path`, returning it along with its length.
+    pub fn open(path: &Path) -> Result<(Self, u64)> {
+        let file = File::open(path).with_context(|| format!("opening file {}", path.display()))?;
+        let len = file.metadata()?.len();
+        Ok((
+            Self {
+                path: path.to_path_buf(),
+                file: Mutex::new(file),
+                pages: Mutex::new(VecDeque::new()),
+            },
+            len,
+        ))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Opens the file again, in case it was replaced on disk, and drops the
+    /// cached pages. Returns the new length of the file.
+    pub fn reopen(&self) -> Result<u64> {
+        let file = File::open(&self.path)
+            .with_context(|| format!("opening file {}", self.path.display()))?;
+        let len = file.metadata()?.len();
+        *self.file.lock() = file;
+        self.clear_pages();
+        Ok(len)
+    }
+
+    pub fn clear_pages(&self) {
+        self.pages.lock().clear();
+    }
+
+    /// Drops the cached page, for example because it was cached before the
+    /// file grew and so may be incomplete.
+    pub fn forget_page(&self, page: u64) {
+        self.pages.lock().retain(|(ix, _)| *ix != page);
+    }
+
+    /// Returns the bytes from `offset` to the end of its page. They are empty
+    /// if `offset` is past the end of the file.
+    pub fn bytes_at(&self, offset: u64) -> Result<PageBytes> {
+        Ok(PageBytes {
+            page: self.page(offset / PAGE_SIZE)?,
+            start: (offset % PAGE_SIZE) as usize,
+        })
+    }
+
+    fn page(&self, page: u64) -> Result<Arc<[u8]>> {
+        {
+            let mut pages = self.pages.lock();
+            if let Some(ix) = pages.iter().position(|(ix, _)| *ix == page) {
+                let entry = pages.remove(ix).unwrap();
+                let bytes = entry.1.clone();
+                pages.push_front(entry);
+                return Ok(bytes);
+            }
+        }
+
+        let mut bytes = vec![0; PAGE_SIZE as usize];
+        let read_len = self.read_at(page * PAGE_SIZE, &mut bytes)?;
+        bytes.truncate(read_len);
+        let bytes = Arc::<[u8]>::from(bytes);
+
+        let mut pages = self.pages.lock();
+        pages.push_front((page, bytes.clone()));
+        pages.truncate(MAX_CACHED_PAGES);
+        Ok(bytes)
+    }
+
+    /// Reads the bytes at `offset` into `buf`, bypassing the cached pages, and
+    /// returns how many were read. Fewer bytes are read at the end of the file.
+    pub fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<usize> {
+        let mut file = self.file.lock();
+        file.seek(SeekFrom::Start(offset))?;
+        let mut read_len = 0;
+        while read_len < buf.len() {
+            match file.read(&mut buf[read_len..])? {
+                0 => break,
+                n => read_len += n,
+            }
+        }
+        Ok(read_len)
+    }
+}
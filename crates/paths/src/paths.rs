@@ -380,6 +380,14 @@ pub fn snippets_dir() -> &'static PathBuf {
     SNIPPETS_DIR.get_or_init(|| config_dir().join("snippets"))
 }
 
+/// Returns the path to the dictionaries directory.
+///
+/// This is where Hunspell dictionaries used for spell checking can be installed.
+pub fn dictionaries_dir() -> &'static PathBuf {
+    static DICTIONARIES_DIR: OnceLock<PathBuf> = OnceLock::new();
+    DICTIONARIES_DIR.get_or_init(|| config_dir().join("dictionaries"))
+}
+
 /// Returns the path to the contexts directory.
 ///
 /// This is where the prompts for use with the Assistant are stored.
@@ -507,6 +515,14 @@ pub fn local_tasks_file_relative_path() -> &'static RelPath {
     *CACHED
 }
 
+/// Returns the relative path to a `dictionary.txt` file within a project, which
+/// lists the words the spell checker should accept in that project.
+pub fn local_dictionary_file_relative_path() -> &'static RelPath {
+    static CACHED: LazyLock<&'static RelPath> =
+        LazyLock::new(|| RelPath::from_unix_str(".zed/dictionary.txt").unwrap());
+    *CACHED
+}
+
 /// Returns the relative path to a `.vscode/tasks.json` file within a project.
 pub fn local_vscode_tasks_file_relative_path() -> &'static RelPath {
     static CACHED: LazyLock<&'static RelPath> =
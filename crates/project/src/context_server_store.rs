@@ -5,10 +5,13 @@ use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::{Context as _, Result};
+use anyhow::{Context as _, Result, anyhow};
 use collections::{HashMap, HashSet};
+use context_server::client::NotificationSubscription;
 use context_server::oauth::{self, McpOAuthTokenProvider, OAuthDiscovery, OAuthSession};
+use context_server::protocol::{InitializedContextServerProtocol, ServerCapability};
 use context_server::transport::HttpTransport;
+use context_server::types::{self, requests};
 use context_server::{ContextServer, ContextServerCommand, ContextServerId};
 use credentials_provider::CredentialsProvider;
 use futures::future::Either;
@@ -23,6 +26,7 @@ use registry::ContextServerDescriptorRegistry;
 use remote::{Interactive, RemoteClient};
 use rpc::{AnyProtoClient, TypedEnvelope, proto};
 use settings::{Settings as _, SettingsLocation, SettingsStore, WorktreeId};
+use url::Url;
 use util::{ResultExt as _, rel_path::RelPath};
 
 use crate::{
@@ -303,6 +307,8 @@ pub struct ContextServerStore {
     /// `maintain_servers` restart a server when its working directory changes,
     /// since the working directory is not part of `ContextServerConfiguration`.
     server_working_directories: HashMap<ContextServerId, Option<Arc<Path>>>,
+    /// Resources listed by each running server that supports them.
+    server_resources: HashMap<ContextServerId, ServerResources>,
     needs_server_update: bool,
     ai_disabled: bool,
     _subscriptions: Vec<Subscription>,
@@ -315,6 +321,31 @@ pub struct ServerStatusChangedEvent {
 
 impl EventEmitter<ServerStatusChangedEvent> for ContextServerStore {}
 
+/// Emitted when a running server sends `notifications/resources/updated` for a
+/// resource that was subscribed to with [`ContextServerStore::subscribe_to_resource`].
+pub struct ContextServerResourceUpdatedEvent {
+    pub server_id: ContextServerId,
+    pub uri: String,
+}
+
+impl EventEmitter<ContextServerResourceUpdatedEvent> for ContextServerStore {}
+
+/// A resource listed by a running context server.
+#[derive(Debug, Clone)]
+pub struct ContextServerResource {
+    pub server_id: ContextServerId,
+    pub resource: types::Resource,
+}
+
+struct ServerResources {
+    resources: Vec<types::Resource>,
+    /// How many callers are subscribed to each resource. The server is only
+    /// told to unsubscribe once the last of them goes away.
+    subscribed_uris: HashMap<Url, usize>,
+    load_resources: Task<()>,
+    _notification_subscriptions: Vec<NotificationSubscription>,
+}
+
 impl ContextServerStore {
     pub fn local(
         worktree_store: Entity<WorktreeStore>,
@@ -521,6 +552,7 @@ impl ContextServerStore {
             update_servers_task: None,
             context_server_factory,
             server_working_directories: HashMap::default(),
+            server_resources: HashMap::default(),
         };
         if maintain_server_loop && !DisableAiSettings::get_global(cx).disable_ai {
             this.available_context_servers_changed(cx);
@@ -650,6 +682,205 @@ impl ContextServerStore {
             .collect()
     }
 
+    /// Returns the resources listed by every running server.
+    pub fn resources(&self) -> Vec<ContextServerResource> {
+        self.server_resources
+            .iter()
+            .flat_map(|(server_id, server_resources)| {
+                server_resources
+                    .resources
+                    .iter()
+                    .map(|resource| ContextServerResource {
+                        server_id: server_id.clone(),
+                        resource: resource.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    pub fn read_resource(
+        &self,
+        server_id: &ContextServerId,
+        uri: Url,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<types::ResourcesReadResponse>> {
+        let Some(client) = self
+            .get_running_server(server_id)
+            .and_then(|server| server.client())
+        else {
+            return Task::ready(Err(anyhow!("context server {server_id} is not running")));
+        };
+        cx.spawn(async move |_, _| {
+            client
+                .request::<requests::ResourcesRead>(types::ResourcesReadParams { uri, meta: None })
+                .await
+        })
+    }
+
+    /// Asks a running server to notify us when the resource at `uri` changes,
+    /// which surfaces as a [`ContextServerResourceUpdatedEvent`]. Does nothing
+    /// if the server doesn't support subscriptions. Every call must be paired
+    /// with a call to [`Self::unsubscribe_from_resource`].
+    pub fn subscribe_to_resource(
+        &mut self,
+        server_id: &ContextServerId,
+        uri: Url,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(client) = self.subscribable_client(server_id) else {
+            return;
+        };
+        let Some(server_resources) = self.server_resources.get_mut(server_id) else {
+            return;
+        };
+        let count = server_resources
+            .subscribed_uris
+            .entry(uri.clone())
+            .or_default();
+        *count += 1;
+        if *count > 1 {
+            return;
+        }
+        cx.spawn(async move |_, _| {
+            client
+                .request::<requests::ResourcesSubscribe>(types::ResourcesSubscribeParams {
+                    uri,
+                    meta: None,
+                })
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Releases a subscription taken with [`Self::subscribe_to_resource`],
+    /// sending `resources/unsubscribe` once nobody is subscribed anymore.
+    pub fn unsubscribe_from_resource(
+        &mut self,
+        server_id: &ContextServerId,
+        uri: Url,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(server_resources) = self.server_resources.get_mut(server_id) else {
+            return;
+        };
+        let Some(count) = server_resources.subscribed_uris.get_mut(&uri) else {
+            return;
+        };
+        *count -= 1;
+        if *count > 0 {
+            return;
+        }
+        server_resources.subscribed_uris.remove(&uri);
+        let Some(client) = self.subscribable_client(server_id) else {
+            return;
+        };
+        cx.spawn(async move |_, _| {
+            client
+                .request::<requests::ResourcesUnsubscribe>(types::ResourcesUnsubscribeParams {
+                    uri,
+                    meta: None,
+                })
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn subscribable_client(
+        &self,
+        server_id: &ContextServerId,
+    ) -> Option<Arc<InitializedContextServerProtocol>> {
+        let client = self.get_running_server(server_id)?.client()?;
+        let supports_subscribe = client
+            .initialize
+            .capabilities
+            .resources
+            .as_ref()
+            .and_then(|resources| resources.subscribe)
+            .unwrap_or(false);
+        supports_subscribe.then_some(client)
+    }
+
+    fn reload_resources_for_server(&mut self, server_id: ContextServerId, cx: &mut Context<Self>) {
+        let Some(client) = self
+            .get_running_server(&server_id)
+            .and_then(|server| server.client())
+        else {
+            return;
+        };
+        if !client.capable(ServerCapability::Resources) {
+            return;
+        }
+
+        let server_resources = self
+            .server_resources
+            .entry(server_id.clone())
+            .or_insert_with(|| ServerResources {
+                resources: Vec::new(),
+                subscribed_uris: HashMap::default(),
+                load_resources: Task::ready(()),
+                _notification_subscriptions: Self::subscribe_to_resource_notifications(
+                    &server_id, &client, cx,
+                ),
+            });
+        server_resources.load_resources = cx.spawn(async move |this, cx| {
+            let response = client.request::<requests::ResourcesList>(()).await;
+            this.update(cx, |this, cx| {
+                let Some(server_resources) = this.server_resources.get_mut(&server_id) else {
+                    return;
+                };
+                if let Some(response) = response.log_err() {
+                    server_resources.resources = response.resources;
+                    cx.notify();
+                }
+            })
+            .ok();
+        });
+    }
+
+    fn subscribe_to_resource_notifications(
+        server_id: &ContextServerId,
+        client: &InitializedContextServerProtocol,
+        cx: &mut Context<Self>,
+    ) -> Vec<NotificationSubscription> {
+        let this = cx.entity().downgrade();
+        let list_changed = client.on_notification("notifications/resources/list_changed", {
+            let server_id = server_id.clone();
+            let this = this.clone();
+            Box::new(move |_params: serde_json::Value, cx: AsyncApp| {
+                let server_id = server_id.clone();
+                let this = this.clone();
+                cx.spawn(async move |cx| {
+                    this.update(cx, |this, cx| {
+                        this.reload_resources_for_server(server_id, cx);
+                    })
+                })
+                .detach();
+            })
+        });
+        let updated = client.on_notification("notifications/resources/updated", {
+            let server_id = server_id.clone();
+            Box::new(move |params: serde_json::Value, cx: AsyncApp| {
+                let Some(params) =
+                    serde_json::from_value::<types::ResourcesUpdatedParams>(params).log_err()
+                else {
+                    return;
+                };
+                let server_id = server_id.clone();
+                let this = this.clone();
+                cx.spawn(async move |cx| {
+                    this.update(cx, |_, cx| {
+                        cx.emit(ContextServerResourceUpdatedEvent {
+                            server_id,
+                            uri: params.uri,
+                        });
+                    })
+                })
+                .detach();
+            })
+        });
+        vec![list_changed, updated]
+    }
+
     pub fn start_server(&mut self, server: Arc<ContextServer>, cx: &mut Context<Self>) {
         cx.spawn(async move |this, cx| {
             let this = this.upgrade().context("Context server store dropped")?;
@@ -1696,6 +1927,11 @@ impl ContextServerStore {
     ) {
         let status = ContextServerStatus::from_state(&state);
         self.servers.insert(id.clone(), state);
+        if status == ContextServerStatus::Running {
+            self.reload_resources_for_server(id.clone(), cx);
+        } else {
+            self.server_resources.remove(&id);
+        }
         cx.emit(ServerStatusChangedEvent {
             server_id: id,
             status,
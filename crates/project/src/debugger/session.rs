@@ -2742,6 +2742,44 @@ impl Session {
         }
     }
 
+    /// Fetches the debuggee's threads straight from the adapter. Unlike
+    /// [`Self::threads`], this bypasses the session's cache so the caller can
+    /// await the response.
+    pub fn fetch_threads(&self) -> Task<Result<Vec<dap::Thread>>> {
+        self.state.request_dap(ThreadsCommand)
+    }
+
+    /// Fetches a thread's stack trace straight from the adapter, bypassing the
+    /// session's cache.
+    pub fn fetch_stack_trace(&self, thread_id: ThreadId) -> Task<Result<Vec<dap::StackFrame>>> {
+        self.state.request_dap(StackTraceCommand {
+            thread_id: thread_id.0,
+            start_frame: None,
+            levels: None,
+        })
+    }
+
+    /// Fetches a stack frame's scopes straight from the adapter, bypassing the
+    /// session's cache.
+    pub fn fetch_scopes(&self, stack_frame_id: u64) -> Task<Result<Vec<dap::Scope>>> {
+        self.state.request_dap(ScopesCommand { stack_frame_id })
+    }
+
+    /// Fetches the children of a variables reference straight from the
+    /// adapter, bypassing the session's cache.
+    pub fn fetch_variables(
+        &self,
+        variables_reference: VariableReference,
+    ) -> Task<Result<Vec<dap::Variable>>> {
+        self.state.request_dap(VariablesCommand {
+            variables_reference,
+            filter: None,
+            start: None,
+            count: None,
+            format: None,
+        })
+    }
+
     pub fn evaluate(
         &mut self,
         expression: String,
@@ -2750,6 +2788,22 @@ impl Session {
         source: Option<Source>,
         cx: &mut Context<Self>,
     ) -> Task<()> {
+        let response = self.evaluate_with_response(expression, context, frame_id, source, cx);
+        cx.spawn(async move |_, _| {
+            response.await.ok();
+        })
+    }
+
+    /// Like [`Self::evaluate`], echoing the expression and its result to the
+    /// console, but also returns the adapter's response.
+    pub fn evaluate_with_response(
+        &mut self,
+        expression: String,
+        context: Option<EvaluateArgumentsContext>,
+        frame_id: Option<u64>,
+        source: Option<Source>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<dap::EvaluateResponse>> {
         let event = dap::OutputEvent {
             category: None,
             output: format!("> {expression}"),
@@ -2775,7 +2829,7 @@ impl Session {
                 this.invalidate_command_type::<ReadMemory>();
                 this.invalidate_command_type::<VariablesCommand>();
                 cx.emit(SessionEvent::Variables);
-                match response {
+                match &response {
                     Ok(response) => {
                         let event = dap::OutputEvent {
                             category: None,
@@ -2808,6 +2862,7 @@ impl Session {
                 cx.notify();
             })
             .ok();
+            response
         })
     }
 
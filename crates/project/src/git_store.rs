@@ -212,6 +212,16 @@ fn decode_git_text(bytes: Vec<u8>) -> Result<String> {
     Ok(decode_text(bytes)?.text)
 }
 
+/// The texts of the three index stages of a conflicted path.
+///
+/// A stage is `None` when the path doesn't exist on that side of the merge.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConflictStages {
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
 #[derive(Debug)]
 pub struct CommitDiff {
     pub files: Vec<CommitFile>,
@@ -976,6 +986,7 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_get_permalink_to_line);
         client.add_entity_request_handler(Self::handle_blame_buffer);
         client.add_entity_request_handler(Self::handle_blame_buffer_at_revision);
+        client.add_entity_request_handler(Self::handle_load_conflict_stages);
         client.add_entity_message_handler(Self::handle_update_repository);
         client.add_entity_message_handler(Self::handle_remove_repository);
         client.add_entity_request_handler(Self::handle_git_clone);
@@ -4809,6 +4820,27 @@ impl GitStore {
         })
     }
 
+    async fn handle_load_conflict_stages(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::LoadConflictStages>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::LoadConflictStagesResponse> {
+        let repository_id = RepositoryId(envelope.payload.repository_id);
+        let path = RepoPath::from_proto(&envelope.payload.path)?;
+        let rx = this
+            .update(&mut cx, |this, cx| {
+                let repository = this.repositories().get(&repository_id)?;
+                Some(repository.update(cx, |repository, _| repository.load_conflict_stages(path)))
+            })
+            .context("missing repository")?;
+        let stages = rx.await??;
+        Ok(proto::LoadConflictStagesResponse {
+            base: stages.base,
+            ours: stages.ours,
+            theirs: stages.theirs,
+        })
+    }
+
     async fn handle_get_permalink_to_line(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GetPermalinkToLine>,
@@ -10144,6 +10176,40 @@ impl Repository {
         })
     }
 
+    /// Loads the base, ours and theirs versions of a conflicted path from index stages 1–3.
+    pub fn load_conflict_stages(
+        &mut self,
+        path: RepoPath,
+    ) -> oneshot::Receiver<Result<ConflictStages>> {
+        let repository_id = self.snapshot.id;
+        self.send_job("load_conflict_stages", None, move |state, _| async move {
+            match state {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    let [base, ours, theirs] = backend.load_conflict_stages(path).await?;
+                    Ok(ConflictStages {
+                        base: base.map(decode_git_text).transpose()?,
+                        ours: ours.map(decode_git_text).transpose()?,
+                        theirs: theirs.map(decode_git_text).transpose()?,
+                    })
+                }
+                RepositoryState::Remote(RemoteRepositoryState { client, project_id }) => {
+                    let response = client
+                        .request(proto::LoadConflictStages {
+                            project_id: project_id.to_proto(),
+                            repository_id: repository_id.0,
+                            path: path.as_unix_str().to_owned(),
+                        })
+                        .await?;
+                    Ok(ConflictStages {
+                        base: response.base,
+                        ours: response.ours,
+                        theirs: response.theirs,
+                    })
+                }
+            }
+        })
+    }
+
     fn load_blob_content(&mut self, oid: Oid, cx: &App) -> Task<Result<String>> {
         let repository_id = self.snapshot.id;
         let rx = self.send_job("load_blob_content", None, move |state, _| async move {
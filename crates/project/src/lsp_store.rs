@@ -1630,26 +1630,7 @@ impl LocalLspStore {
             .read_with(cx, |buffer, _| buffer.max_point().row > 0);
 
         // When formatting a selection, only the rows it spans may be touched.
-        let selection_row_ranges = buffer.ranges.as_ref().map(|ranges| {
-            buffer.handle.read_with(cx, |buffer, _cx| {
-                let snapshot = buffer.snapshot();
-                ranges
-                    .iter()
-                    .map(|range| {
-                        let start = range.start.to_point(&snapshot);
-                        let end = range.end.to_point(&snapshot);
-                        // A selection ending at column 0 of a row only includes that row's
-                        // preceding line break, not its content, so it shouldn't be trimmed.
-                        let end_row = if end.column == 0 && end.row > start.row {
-                            end.row
-                        } else {
-                            end.row + 1
-                        };
-                        start.row..end_row
-                    })
-                    .collect::<Vec<_>>()
-            })
-        });
+        let selection_row_ranges = Self::selection_row_ranges(buffer, cx);
 
         // handle whitespace formatting
         if settings.remove_trailing_whitespace_on_save {
@@ -1785,6 +1766,29 @@ impl LocalLspStore {
         }
     }
 
+    fn selection_row_ranges(buffer: &FormattableBuffer, cx: &AsyncApp) -> Option<Vec<Range<u32>>> {
+        buffer.ranges.as_ref().map(|ranges| {
+            buffer.handle.read_with(cx, |buffer, _cx| {
+                let snapshot = buffer.snapshot();
+                ranges
+                    .iter()
+                    .map(|range| {
+                        let start = range.start.to_point(&snapshot);
+                        let end = range.end.to_point(&snapshot);
+                        // A selection ending at column 0 of a row only includes that row's
+                        // preceding line break, not its content, so it shouldn't be trimmed.
+                        let end_row = if end.column == 0 && end.row > start.row {
+                            end.row
+                        } else {
+                            end.row + 1
+                        };
+                        start.row..end_row
+                    })
+                    .collect::<Vec<_>>()
+            })
+        })
+    }
+
     async fn apply_formatter(
         formatter: &Formatter,
         lsp_store: &WeakEntity<LspStore>,
@@ -1909,6 +1913,32 @@ impl LocalLspStore {
                     },
                 )?;
             }
+            Formatter::TreeSitter => {
+                let logger = zlog::scoped!(logger => "tree-sitter");
+                zlog::trace!(logger => "formatting");
+                let _timer = zlog::time!(logger => "Formatting buffer via indentation queries");
+
+                let selection_row_ranges = Self::selection_row_ranges(buffer, cx);
+                let diff = buffer
+                    .handle
+                    .read_with(cx, |buffer, cx| {
+                        buffer.reindent(selection_row_ranges.as_deref(), cx)
+                    })
+                    .await;
+                if diff.edits.is_empty() {
+                    zlog::trace!(logger => "No changes");
+                    return Ok(());
+                }
+
+                extend_formatting_transaction(
+                    buffer,
+                    formatting_transaction_id,
+                    cx,
+                    |buffer, cx| {
+                        buffer.apply_diff(diff, cx);
+                    },
+                )?;
+            }
             Formatter::LanguageServer(specifier) => {
                 let logger = zlog::scoped!(logger => "language-server");
                 zlog::trace!(logger => "formatting");
@@ -5552,6 +5582,7 @@ impl LspStore {
                 }
                 Formatter::Prettier => true,
                 Formatter::External { .. } => false,
+                Formatter::TreeSitter => true,
                 Formatter::LanguageServer(settings::LanguageServerFormatterSpecifier::Current) => {
                     self.check_if_capable_for_proto_request(
                         buffer,
@@ -1,8 +1,10 @@
 use anyhow::Result;
-use context_server::test::create_fake_transport;
+use context_server::test::{create_fake_transport, create_fake_transport_with_capabilities};
+use context_server::types::{self, ResourcesCapabilities, ServerCapabilities, requests};
 use context_server::{ContextServer, ContextServerId};
 use gpui::{AppContext, AsyncApp, Entity, Subscription, Task, TestAppContext, UpdateGlobal as _};
 use http_client::{FakeHttpClient, Response};
+use parking_lot::Mutex;
 use project::context_server_store::registry::ContextServerDescriptorRegistry;
 use project::context_server_store::*;
 use project::project_settings::ContextServerSettings;
@@ -1412,6 +1414,98 @@ impl Drop for ServerEvents {
     }
 }
 
+#[gpui::test]
+async fn test_context_server_resource_subscriptions(cx: &mut TestAppContext) {
+    const SERVER_ID: &str = "mcp-resources";
+
+    let (_fs, project) = setup_context_server_test(cx, json!({"code.rs": ""}), vec![]).await;
+
+    let registry = cx.new(|_| ContextServerDescriptorRegistry::new());
+    let store = cx.new(|cx| {
+        ContextServerStore::test(
+            registry.clone(),
+            project.read(cx).worktree_store(),
+            Some(project.downgrade()),
+            cx,
+        )
+    });
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let transport = create_fake_transport_with_capabilities(
+        SERVER_ID,
+        || ServerCapabilities {
+            resources: Some(ResourcesCapabilities {
+                subscribe: Some(true),
+                list_changed: None,
+            }),
+            ..Default::default()
+        },
+        cx.executor(),
+    )
+    .on_request::<requests::ResourcesList, _>(|_| async {
+        types::ResourcesListResponse {
+            resources: Vec::new(),
+            next_cursor: None,
+            meta: None,
+        }
+    })
+    .on_request::<requests::ResourcesSubscribe, _>({
+        let received = received.clone();
+        move |params| {
+            received.lock().push(format!("subscribe {}", params.uri));
+            async {}
+        }
+    })
+    .on_request::<requests::ResourcesUnsubscribe, _>({
+        let received = received.clone();
+        move |params| {
+            received.lock().push(format!("unsubscribe {}", params.uri));
+            async {}
+        }
+    });
+
+    let server_id = ContextServerId(SERVER_ID.into());
+    let server = Arc::new(ContextServer::new(server_id.clone(), Arc::new(transport)));
+    store.update(cx, |store, cx| store.test_start_server(server, cx));
+    cx.run_until_parked();
+
+    let uri = url::Url::parse("file:///notes.md").unwrap();
+
+    // Two mentions of the same resource only subscribe once.
+    store.update(cx, |store, cx| {
+        store.subscribe_to_resource(&server_id, uri.clone(), cx);
+        store.subscribe_to_resource(&server_id, uri.clone(), cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        received.lock().drain(..).collect::<Vec<_>>(),
+        vec!["subscribe file:///notes.md".to_string()]
+    );
+
+    // The server is only told to unsubscribe once the last mention goes away.
+    store.update(cx, |store, cx| {
+        store.unsubscribe_from_resource(&server_id, uri.clone(), cx)
+    });
+    cx.run_until_parked();
+    assert!(received.lock().is_empty());
+
+    store.update(cx, |store, cx| {
+        store.unsubscribe_from_resource(&server_id, uri.clone(), cx)
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        received.lock().drain(..).collect::<Vec<_>>(),
+        vec!["unsubscribe file:///notes.md".to_string()]
+    );
+
+    // Unbalanced unsubscribes are ignored.
+    store.update(cx, |store, cx| {
+        store.unsubscribe_from_resource(&server_id, uri.clone(), cx)
+    });
+    cx.run_until_parked();
+    assert!(received.lock().is_empty());
+}
+
 #[gpui::test]
 async fn test_context_server_global_timeout(cx: &mut TestAppContext) {
     cx.update(|cx| {
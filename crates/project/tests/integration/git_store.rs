@@ -629,6 +629,53 @@ mod conflict_set_tests {
             assert_eq!(conflict_set.snapshot.conflicts.len(), 0);
         });
     }
+
+    #[gpui::test]
+    async fn test_load_conflict_stages(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            settings::init(cx);
+        });
+        let fs = FakeFs::new(executor);
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "a.txt": "conflicted",
+            }),
+        )
+        .await;
+        fs.with_git_state(path!("/project/.git").as_ref(), true, |state| {
+            state.conflict_stages.insert(
+                repo_path("a.txt"),
+                [Some(b"base\n".to_vec()), Some(b"ours\n".to_vec()), None],
+            );
+        })
+        .unwrap();
+
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        cx.run_until_parked();
+        let repository = project.read_with(cx, |project, cx| {
+            project.repositories(cx).values().next().unwrap().clone()
+        });
+
+        let stages = cx
+            .update(|cx| {
+                repository.update(cx, |repository, _| {
+                    repository.load_conflict_stages(repo_path("a.txt"))
+                })
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            stages,
+            ConflictStages {
+                base: Some("base\n".into()),
+                ours: Some("ours\n".into()),
+                theirs: None,
+            }
+        );
+    }
 }
 
 mod git_traversal {
@@ -360,6 +360,8 @@ messages!(
     (GetBlobContentResponse, Background),
     (BlameBufferAtRevision, Background),
     (BlameBufferAtRevisionResponse, Background),
+    (LoadConflictStages, Background),
+    (LoadConflictStagesResponse, Background),
     (LoadCommitTemplate, Background),
     (LoadCommitTemplateResponse, Background),
     (GitClone, Background),
@@ -606,6 +608,7 @@ request_messages!(
     (GetDefaultBranch, GetDefaultBranchResponse),
     (GetBlobContent, GetBlobContentResponse),
     (BlameBufferAtRevision, BlameBufferAtRevisionResponse),
+    (LoadConflictStages, LoadConflictStagesResponse),
     (LoadCommitTemplate, LoadCommitTemplateResponse),
     (GetTreeDiff, GetTreeDiffResponse),
     (GitClone, GitCloneResponse),
@@ -831,6 +834,7 @@ entity_messages!(
     GetTreeDiff,
     GetBlobContent,
     BlameBufferAtRevision,
+    LoadConflictStages,
     LoadCommitTemplate,
     GitClone,
     GetAgentServerCommand,
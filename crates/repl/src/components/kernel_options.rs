@@ -29,6 +29,7 @@ fn build_grouped_entries(store: &ReplStore, worktree_id: WorktreeId) -> Vec<Kern
     let mut jupyter_kernels = Vec::new();
     let mut wsl_kernels = Vec::new();
     let mut remote_kernels = Vec::new();
+    let mut running_kernels = Vec::new();
 
     for spec in store.kernel_specifications_for_worktree(worktree_id) {
         let is_recommended = store.is_recommended_kernel(worktree_id, spec);
@@ -60,6 +61,14 @@ fn build_grouped_entries(store: &ReplStore, worktree_id: WorktreeId) -> Vec<Kern
                     is_recommended,
                 });
             }
+            KernelSpecification::JupyterServer(remote_spec)
+                if remote_spec.running_kernel.is_some() =>
+            {
+                running_kernels.push(KernelPickerEntry::Kernel {
+                    spec: spec.clone(),
+                    is_recommended,
+                });
+            }
             KernelSpecification::JupyterServer(_) | KernelSpecification::SshRemote(_) => {
                 remote_kernels.push(KernelPickerEntry::Kernel {
                     spec: spec.clone(),
@@ -122,6 +131,12 @@ fn build_grouped_entries(store: &ReplStore, worktree_id: WorktreeId) -> Vec<Kern
         entries.extend(remote_kernels);
     }
 
+    // Kernels already running on a Jupyter server, which are reattached to
+    if !running_kernels.is_empty() {
+        entries.push(KernelPickerEntry::SectionHeader("Running Kernels".into()));
+        entries.extend(running_kernels);
+    }
+
     entries
 }
 
@@ -2,11 +2,31 @@ use collections::HashMap;
 
 use editor::EditorSettings;
 use gpui::App;
-use settings::{RegisterSetting, Settings};
+use settings::{JupyterServerContent, RegisterSetting, Settings};
 
 #[derive(Debug, Default, RegisterSetting)]
 pub struct JupyterSettings {
     pub kernel_selections: HashMap<String, String>,
+    pub servers: Vec<JupyterServerSettings>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JupyterServerSettings {
+    pub name: String,
+    pub url: String,
+    pub token: String,
+    pub ssh_tunnel: bool,
+}
+
+impl From<JupyterServerContent> for JupyterServerSettings {
+    fn from(content: JupyterServerContent) -> Self {
+        Self {
+            name: content.name,
+            url: content.url.trim_end_matches('/').to_string(),
+            token: content.token.unwrap_or_default(),
+            ssh_tunnel: content.ssh_tunnel.unwrap_or(false),
+        }
+    }
 }
 
 impl JupyterSettings {
@@ -23,6 +43,12 @@ impl Settings for JupyterSettings {
         let jupyter = content.editor.jupyter.clone().unwrap();
         Self {
             kernel_selections: jupyter.kernel_selections.unwrap_or_default(),
+            servers: jupyter
+                .servers
+                .unwrap_or_default()
+                .into_iter()
+                .map(JupyterServerSettings::from)
+                .collect(),
         }
     }
 }
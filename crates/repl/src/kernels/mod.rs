@@ -314,6 +314,9 @@ impl KernelSpecification {
                     .clone()
                     .unwrap_or_else(|| "Python Environment".to_string()),
             ),
+            Self::JupyterServer(spec) if spec.running_kernel.is_some() => {
+                "Running Jupyter Kernel".into()
+            }
             Self::JupyterServer(_) => "Jupyter Server".into(),
             Self::SshRemote(_) => "SSH Remote".into(),
             Self::WslRemote(_) => "WSL Remote".into(),
@@ -324,7 +327,13 @@ impl KernelSpecification {
         SharedString::from(match self {
             Self::Jupyter(spec) => spec.path.to_string_lossy().into_owned(),
             Self::PythonEnv(spec) => spec.path.to_string_lossy().into_owned(),
-            Self::JupyterServer(spec) => spec.url.to_string(),
+            Self::JupyterServer(spec) => match &spec.running_kernel {
+                Some(RemoteKernelSession {
+                    path: Some(path), ..
+                }) => format!("{}/{}", spec.url, path),
+                Some(running_kernel) => format!("{} ({})", spec.url, running_kernel.kernel_id),
+                None => spec.url.to_string(),
+            },
             Self::SshRemote(spec) => spec.path.to_string(),
             Self::WslRemote(spec) => spec.distro.clone(),
         })
@@ -356,7 +365,13 @@ impl KernelSpecification {
                 .as_ref()
                 .map(|kind| SharedString::from(kind.clone())),
             Self::Jupyter(_) => Some("Jupyter".into()),
-            Self::JupyterServer(_) => Some("Jupyter Server".into()),
+            Self::JupyterServer(spec) => Some(match &spec.running_kernel {
+                Some(running_kernel) if !running_kernel.execution_state.is_empty() => {
+                    format!("Running ({})", running_kernel.execution_state).into()
+                }
+                Some(_) => "Running".into(),
+                None => "Jupyter Server".into(),
+            }),
             Self::SshRemote(_) => Some("SSH Remote".into()),
             Self::WslRemote(_) => Some("WSL Remote".into()),
         }
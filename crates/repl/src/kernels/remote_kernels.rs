@@ -1,21 +1,32 @@
-use futures::{SinkExt as _, channel::mpsc};
-use gpui::{App, AppContext as _, Entity, Task, Window};
-use http_client::{AsyncBody, HttpClient, Request};
+use futures::{FutureExt as _, SinkExt as _, channel::mpsc};
+use gpui::{App, AppContext as _, BackgroundExecutor, Entity, Task, Window};
+use http_client::{AsyncBody, HttpClient, Request, Url};
 use jupyter_protocol::{ExecutionState, JupyterKernelspec, JupyterMessage, KernelInfoReply};
 
 use async_tungstenite::tokio::connect_async;
 use async_tungstenite::tungstenite::{client::IntoClientRequest, http::HeaderValue};
 
 use futures::StreamExt;
+use remote::RemoteClient;
+use serde::{Deserialize, de::DeserializeOwned};
 use smol::io::AsyncReadExt as _;
 
 use super::{KernelSession, RunningKernel};
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use jupyter_websocket_client::{
     JupyterWebSocket, JupyterWebSocketReader, JupyterWebSocketWriter, KernelLaunchRequest,
     KernelSpecsResponse, ProtocolMode, RemoteServer,
 };
-use std::{fmt::Debug, sync::Arc};
+use std::{
+    fmt::Debug,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// How many times a dropped kernel connection is re-established before giving up.
+const MAX_RECONNECT_ATTEMPTS: u32 = 10;
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
 
 #[derive(Debug, Clone)]
 pub struct RemoteKernelSpecification {
@@ -23,6 +34,39 @@ pub struct RemoteKernelSpecification {
     pub url: String,
     pub token: String,
     pub kernelspec: JupyterKernelspec,
+    /// Set when this entry refers to a kernel that is already running on the
+    /// server, which is reattached to instead of launching a new one.
+    pub running_kernel: Option<RemoteKernelSession>,
+    /// The tunnel the server is reached through, which kernels started from this
+    /// specification keep open for as long as they run.
+    pub tunnel: Option<Arc<JupyterServerTunnel>>,
+}
+
+/// A kernel that is running on a Jupyter server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteKernelSession {
+    pub kernel_id: String,
+    /// Path of the notebook or console the kernel belongs to, if it was started for one.
+    pub path: Option<String>,
+    pub execution_state: String,
+    pub connections: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct KernelModel {
+    id: String,
+    name: String,
+    #[serde(default)]
+    execution_state: Option<String>,
+    #[serde(default)]
+    connections: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SessionModel {
+    #[serde(default)]
+    path: Option<String>,
+    kernel: Option<KernelModel>,
 }
 
 pub async fn launch_remote_kernel(
@@ -64,7 +108,7 @@ pub async fn launch_remote_kernel(
 }
 
 pub async fn list_remote_kernelspecs(
-    remote_server: RemoteServer,
+    remote_server: &RemoteServer,
     http_client: Arc<dyn HttpClient>,
 ) -> Result<Vec<RemoteKernelSpecification>> {
     let url = remote_server.api_url("/kernelspecs");
@@ -97,6 +141,8 @@ pub async fn list_remote_kernelspecs(
             url: remote_server.base_url.clone(),
             token: remote_server.token.clone(),
             kernelspec: spec.spec,
+            running_kernel: None,
+            tunnel: None,
         })
         .collect::<Vec<RemoteKernelSpecification>>();
 
@@ -104,9 +150,175 @@ pub async fn list_remote_kernelspecs(
     Ok(remote_kernelspecs)
 }
 
+/// Lists the kernels running on the server, using `kernelspecs` to describe them.
+///
+/// Kernels that belong to a session are labeled with the session's path.
+pub async fn list_remote_kernel_sessions(
+    remote_server: &RemoteServer,
+    http_client: Arc<dyn HttpClient>,
+    kernelspecs: &[RemoteKernelSpecification],
+) -> Result<Vec<RemoteKernelSpecification>> {
+    let kernels: Vec<KernelModel> =
+        get_json(remote_server, http_client.clone(), "/kernels").await?;
+    let sessions: Vec<SessionModel> = get_json(remote_server, http_client, "/sessions").await?;
+
+    let running_kernels = kernels
+        .into_iter()
+        .filter_map(|kernel| {
+            let spec = kernelspecs.iter().find(|spec| spec.name == kernel.name)?;
+            let path = sessions
+                .iter()
+                .find(|session| {
+                    session
+                        .kernel
+                        .as_ref()
+                        .is_some_and(|session_kernel| session_kernel.id == kernel.id)
+                })
+                .and_then(|session| session.path.clone());
+
+            Some(RemoteKernelSpecification {
+                running_kernel: Some(RemoteKernelSession {
+                    kernel_id: kernel.id,
+                    path,
+                    execution_state: kernel.execution_state.unwrap_or_default(),
+                    connections: kernel.connections,
+                }),
+                ..spec.clone()
+            })
+        })
+        .collect();
+
+    Ok(running_kernels)
+}
+
+/// Lists both the kernels that can be launched on the server and the ones already running on it.
+pub async fn remote_kernel_specifications(
+    remote_server: RemoteServer,
+    http_client: Arc<dyn HttpClient>,
+) -> Result<Vec<RemoteKernelSpecification>> {
+    let mut kernelspecs = list_remote_kernelspecs(&remote_server, http_client.clone()).await?;
+
+    match list_remote_kernel_sessions(&remote_server, http_client, &kernelspecs).await {
+        Ok(running_kernels) => kernelspecs.extend(running_kernels),
+        Err(error) => log::warn!(
+            "Failed to list running kernels on {}: {error:?}",
+            remote_server.base_url
+        ),
+    }
+
+    Ok(kernelspecs)
+}
+
+async fn get_json<T: DeserializeOwned>(
+    remote_server: &RemoteServer,
+    http_client: Arc<dyn HttpClient>,
+    path: &str,
+) -> Result<T> {
+    let request = Request::builder()
+        .method("GET")
+        .uri(&remote_server.api_url(path))
+        .header("Authorization", format!("token {}", remote_server.token))
+        .body(AsyncBody::default())?;
+
+    let response = http_client.send(request).await?;
+
+    anyhow::ensure!(
+        response.status().is_success(),
+        "Failed to fetch {path}: {}",
+        response.status()
+    );
+
+    let mut body_bytes = Vec::new();
+    response.into_body().read_to_end(&mut body_bytes).await?;
+
+    Ok(serde_json::from_slice(&body_bytes)?)
+}
+
+/// The id our websocket connections to a kernel present to the server.
+///
+/// Jupyter servers buffer the messages of a kernel while its connection is down
+/// and replay them to the next connection that presents the same `session_id`.
+/// Deriving it from the kernel id means reconnects, and reattaching after Zed
+/// was restarted, both pick up the outputs that were missed in the meantime.
+fn client_session_id(kernel_id: &str) -> String {
+    uuid::Uuid::new_v5(&uuid::Uuid::NAMESPACE_OID, kernel_id.as_bytes()).to_string()
+}
+
+/// Re-establishes a dropped kernel connection, giving up after
+/// [`MAX_RECONNECT_ATTEMPTS`] failed attempts.
+async fn reconnect<T, F, Fut>(
+    executor: &BackgroundExecutor,
+    kernel_id: &str,
+    mut connect: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        log::warn!(
+            "Reconnecting to kernel {} (attempt {}/{})",
+            kernel_id,
+            attempt,
+            MAX_RECONNECT_ATTEMPTS
+        );
+        executor.timer(RECONNECT_DELAY).await;
+        match connect().await {
+            Ok(connection) => return Ok(connection),
+            Err(error) if attempt >= MAX_RECONNECT_ATTEMPTS => return Err(error),
+            Err(error) => {
+                log::debug!("Failed to reconnect to kernel: {error:?}");
+            }
+        }
+    }
+}
+
+async fn connect_to_kernel(
+    remote_server: &RemoteServer,
+    kernel_id: &str,
+    client_session_id: &str,
+) -> Result<(JupyterWebSocketWriter, JupyterWebSocketReader)> {
+    let ws_url = format!(
+        "{}/api/kernels/{}/channels?token={}&session_id={}",
+        remote_server.base_url.replace("http", "ws"),
+        kernel_id,
+        remote_server.token,
+        client_session_id
+    );
+
+    let mut req: Request<()> = ws_url.into_client_request()?;
+    let headers = req.headers_mut();
+
+    headers.insert(
+        "User-Agent",
+        HeaderValue::from_str(&format!(
+            "Zed/{} ({}; {})",
+            "repl",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        ))?,
+    );
+
+    let (ws_stream, _response) = connect_async(req).await?;
+
+    let kernel_socket = JupyterWebSocket {
+        inner: ws_stream,
+        protocol_mode: ProtocolMode::Json,
+    };
+    Ok(kernel_socket.split())
+}
+
 impl PartialEq for RemoteKernelSpecification {
     fn eq(&self, other: &Self) -> bool {
-        self.name == other.name && self.url == other.url
+        self.name == other.name
+            && self.url == other.url
+            && self.running_kernel.as_ref().map(|kernel| &kernel.kernel_id)
+                == other
+                    .running_kernel
+                    .as_ref()
+                    .map(|kernel| &kernel.kernel_id)
     }
 }
 
@@ -114,8 +326,8 @@ impl Eq for RemoteKernelSpecification {}
 
 pub struct RemoteRunningKernel {
     remote_server: RemoteServer,
-    _receiving_task: Task<Result<()>>,
-    _routing_task: Task<Result<()>>,
+    _connection_task: Task<Result<()>>,
+    _tunnel: Option<Arc<JupyterServerTunnel>>,
     http_client: Arc<dyn HttpClient>,
     pub working_directory: std::path::PathBuf,
     pub request_tx: mpsc::Sender<JupyterMessage>,
@@ -137,94 +349,114 @@ impl RemoteRunningKernel {
             base_url: kernelspec.url,
             token: kernelspec.token,
         };
+        let tunnel = kernelspec.tunnel;
 
         let http_client = cx.http_client();
 
         window.spawn(cx, async move |cx| {
-            let kernel_id = launch_remote_kernel(
-                &remote_server,
-                http_client.clone(),
-                &kernelspec.name,
-                working_directory.to_str().unwrap_or_default(),
-            )
-            .await?;
-
-            let ws_url = format!(
-                "{}/api/kernels/{}/channels?token={}",
-                remote_server.base_url.replace("http", "ws"),
-                kernel_id,
-                remote_server.token
-            );
-
-            let mut req: Request<()> = ws_url.into_client_request()?;
-            let headers = req.headers_mut();
-
-            headers.insert(
-                "User-Agent",
-                HeaderValue::from_str(&format!(
-                    "Zed/{} ({}; {})",
-                    "repl",
-                    std::env::consts::OS,
-                    std::env::consts::ARCH
-                ))?,
-            );
-
-            let response = connect_async(req).await;
-
-            let (ws_stream, _response) = response?;
-
-            let kernel_socket = JupyterWebSocket {
-                inner: ws_stream,
-                protocol_mode: ProtocolMode::Json,
+            let (kernel_id, execution_state) = match kernelspec.running_kernel {
+                Some(running_kernel) => {
+                    let kernel: KernelModel = get_json(
+                        &remote_server,
+                        http_client.clone(),
+                        &format!("/kernels/{}", running_kernel.kernel_id),
+                    )
+                    .await
+                    .context("kernel is no longer running on the server")?;
+                    let execution_state = kernel
+                        .execution_state
+                        .and_then(|state| {
+                            serde_json::from_value(serde_json::Value::String(state)).ok()
+                        })
+                        .unwrap_or(ExecutionState::Idle);
+                    (kernel.id, execution_state)
+                }
+                None => {
+                    let kernel_id = launch_remote_kernel(
+                        &remote_server,
+                        http_client.clone(),
+                        &kernelspec.name,
+                        working_directory.to_str().unwrap_or_default(),
+                    )
+                    .await?;
+                    (kernel_id, ExecutionState::Idle)
+                }
             };
-            let (mut w, mut r): (JupyterWebSocketWriter, JupyterWebSocketReader) =
-                kernel_socket.split();
+
+            let client_session_id = client_session_id(&kernel_id);
+            let (mut w, mut r) =
+                connect_to_kernel(&remote_server, &kernel_id, &client_session_id).await?;
 
             let (request_tx, mut request_rx) =
                 futures::channel::mpsc::channel::<JupyterMessage>(100);
 
-            let routing_task = cx.background_spawn({
-                async move {
-                    while let Some(message) = request_rx.next().await {
-                        w.send(message).await.ok();
-                    }
-                    Ok(())
-                }
-            });
-
-            let receiving_task = cx.spawn({
-                let session = session.clone();
+            let connection_task = cx.spawn({
+                let remote_server = RemoteServer {
+                    base_url: remote_server.base_url.clone(),
+                    token: remote_server.token.clone(),
+                };
+                let kernel_id = kernel_id.clone();
 
                 async move |cx| {
-                    while let Some(message) = r.next().await {
-                        match message {
-                            Ok(message) => {
-                                session
-                                    .update_in(cx, |session, window, cx| {
-                                        session.route(&message, window, cx);
-                                    })
-                                    .ok();
-                            }
-                            Err(e) => {
-                                log::error!("Error receiving message: {:?}", e);
+                    loop {
+                        loop {
+                            futures::select! {
+                                request = request_rx.next() => match request {
+                                    Some(request) => {
+                                        if let Err(error) = w.send(request).await {
+                                            log::error!("Error sending message: {error:?}");
+                                            break;
+                                        }
+                                    }
+                                    None => return Ok(()),
+                                },
+                                message = r.next().fuse() => match message {
+                                    Some(Ok(message)) => {
+                                        session
+                                            .update_in(cx, |session, window, cx| {
+                                                session.route(&message, window, cx);
+                                            })
+                                            .ok();
+                                    }
+                                    Some(Err(e)) => {
+                                        log::error!("Error receiving message: {:?}", e);
+                                    }
+                                    None => break,
+                                },
                             }
                         }
+
+                        let executor = cx.background_executor().clone();
+                        (w, r) = match reconnect(&executor, &kernel_id, || {
+                            connect_to_kernel(&remote_server, &kernel_id, &client_session_id)
+                        })
+                        .await
+                        {
+                            Ok(connection) => connection,
+                            Err(error) => {
+                                session.update(cx, |session, cx| {
+                                    session.kernel_errored(
+                                        format!("lost connection to kernel: {error}"),
+                                        cx,
+                                    );
+                                });
+                                return Err(error);
+                            }
+                        };
                     }
-                    Ok(())
                 }
             });
 
             let stdin_tx = request_tx.clone();
 
             anyhow::Ok(Box::new(Self {
-                _routing_task: routing_task,
-                _receiving_task: receiving_task,
+                _connection_task: connection_task,
+                _tunnel: tunnel,
                 remote_server,
                 working_directory,
                 request_tx,
                 stdin_tx,
-                // todo(kyle): pull this from the kernel API to start with
-                execution_state: ExecutionState::Idle,
+                execution_state,
                 kernel_info: None,
                 kernel_id,
                 http_client: http_client.clone(),
@@ -305,3 +537,272 @@ impl RunningKernel for RemoteRunningKernel {
         self.stdin_tx.close_channel();
     }
 }
+
+/// A local port forwarded to a Jupyter server that listens on the host of a
+/// remote connection.
+pub struct JupyterServerTunnel {
+    pub local_url: String,
+    local_port: Option<u16>,
+    process: Option<Mutex<util::command::Child>>,
+}
+
+impl Debug for JupyterServerTunnel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JupyterServerTunnel")
+            .field("local_url", &self.local_url)
+            .finish()
+    }
+}
+
+impl JupyterServerTunnel {
+    pub fn open(server_url: &str, remote_client: &RemoteClient) -> Result<Self> {
+        if remote_client.shares_network_interface() {
+            return Ok(Self {
+                local_url: server_url.to_string(),
+                local_port: None,
+                process: None,
+            });
+        }
+
+        let mut url = Url::parse(server_url).context("invalid Jupyter server URL")?;
+        let remote_host = url
+            .host_str()
+            .context("Jupyter server URL has no host")?
+            .to_string();
+        let remote_port = url
+            .port_or_known_default()
+            .context("Jupyter server URL has no port")?;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let local_port = listener.local_addr()?.port();
+        drop(listener);
+
+        let command_template = remote_client.build_forward_ports_command(vec![(
+            local_port,
+            remote_host,
+            remote_port,
+        )])?;
+        let process = util::command::new_command(&command_template.program)
+            .args(&command_template.args)
+            .envs(&command_template.env)
+            .kill_on_drop(true)
+            .spawn()
+            .context("failed to spawn ssh tunnel")?;
+
+        url.set_host(Some("127.0.0.1"))?;
+        url.set_port(Some(local_port))
+            .ok()
+            .context("failed to rewrite Jupyter server URL")?;
+
+        Ok(Self {
+            local_url: url.as_str().trim_end_matches('/').to_string(),
+            local_port: Some(local_port),
+            process: Some(Mutex::new(process)),
+        })
+    }
+
+    /// Whether the process forwarding the port is still running.
+    pub fn is_alive(&self) -> bool {
+        let Some(process) = &self.process else {
+            return true;
+        };
+        process
+            .lock()
+            .is_ok_and(|mut process| matches!(process.try_status(), Ok(None)))
+    }
+
+    pub fn wait_until_ready(
+        &self,
+        executor: BackgroundExecutor,
+    ) -> impl Future<Output = Result<()>> + use<> {
+        let local_port = self.local_port;
+        async move {
+            let Some(local_port) = local_port else {
+                return Ok(());
+            };
+
+            let max_attempts = 100;
+            for _ in 0..max_attempts {
+                if smol::net::TcpStream::connect(("127.0.0.1", local_port))
+                    .await
+                    .is_ok()
+                {
+                    return Ok(());
+                }
+                executor.timer(Duration::from_millis(100)).await;
+            }
+            anyhow::bail!("SSH tunnel failed to establish after {max_attempts} attempts")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use http_client::{FakeHttpClient, Response};
+    use serde_json::json;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn remote_server() -> RemoteServer {
+        RemoteServer {
+            base_url: "http://localhost:8888".to_string(),
+            token: "secret".to_string(),
+        }
+    }
+
+    fn remote_kernelspec(name: &str) -> RemoteKernelSpecification {
+        RemoteKernelSpecification {
+            name: name.to_string(),
+            url: "http://localhost:8888".to_string(),
+            token: "secret".to_string(),
+            kernelspec: JupyterKernelspec {
+                argv: Vec::new(),
+                display_name: name.to_string(),
+                language: "python".to_string(),
+                interrupt_mode: None,
+                metadata: None,
+                env: None,
+            },
+            running_kernel: None,
+            tunnel: None,
+        }
+    }
+
+    #[gpui::test]
+    async fn test_list_remote_kernel_sessions(_cx: &mut TestAppContext) {
+        let http_client = FakeHttpClient::create(|request| async move {
+            let body = match request.uri().path() {
+                path if path.ends_with("/api/kernels") => json!([
+                    {
+                        "id": "k1",
+                        "name": "python3",
+                        "last_activity": "2024-01-01T00:00:00Z",
+                        "execution_state": "busy",
+                        "connections": 1
+                    },
+                    { "id": "k2", "name": "python3" },
+                    { "id": "k3", "name": "julia-1.10", "execution_state": "idle" }
+                ]),
+                path if path.ends_with("/api/sessions") => json!([
+                    {
+                        "id": "s1",
+                        "path": "analysis.ipynb",
+                        "name": "analysis.ipynb",
+                        "type": "notebook",
+                        "kernel": { "id": "k1", "name": "python3" }
+                    },
+                    { "id": "s2", "path": "scratch.ipynb", "kernel": null }
+                ]),
+                path => anyhow::bail!("unexpected request to {path}"),
+            };
+            Ok(Response::builder()
+                .status(200)
+                .body(AsyncBody::from(body.to_string()))
+                .unwrap())
+        });
+
+        let running_kernels = list_remote_kernel_sessions(
+            &remote_server(),
+            http_client,
+            &[remote_kernelspec("python3")],
+        )
+        .await
+        .unwrap();
+
+        // Kernels without a matching kernelspec can't be described, so they are skipped.
+        let sessions = running_kernels
+            .into_iter()
+            .map(|spec| {
+                assert_eq!(spec.name, "python3");
+                spec.running_kernel.unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sessions,
+            [
+                RemoteKernelSession {
+                    kernel_id: "k1".to_string(),
+                    path: Some("analysis.ipynb".to_string()),
+                    execution_state: "busy".to_string(),
+                    connections: 1,
+                },
+                RemoteKernelSession {
+                    kernel_id: "k2".to_string(),
+                    path: None,
+                    execution_state: String::new(),
+                    connections: 0,
+                },
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_list_remote_kernel_sessions_fails_on_error_status(_cx: &mut TestAppContext) {
+        let http_client = FakeHttpClient::with_404_response();
+        let result = list_remote_kernel_sessions(
+            &remote_server(),
+            http_client,
+            &[remote_kernelspec("python3")],
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_client_session_id_is_stable_per_kernel() {
+        assert_eq!(client_session_id("k1"), client_session_id("k1"));
+        assert_ne!(client_session_id("k1"), client_session_id("k2"));
+    }
+
+    #[gpui::test]
+    async fn test_reconnect_retries_until_connected(cx: &mut TestAppContext) {
+        let attempts = Arc::new(AtomicU32::new(0));
+        let executor = cx.background_executor.clone();
+        let task = cx.background_executor.spawn({
+            let attempts = attempts.clone();
+            async move {
+                reconnect(&executor, "k1", || {
+                    let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+                    async move {
+                        anyhow::ensure!(attempt >= 3, "connection refused");
+                        Ok(attempt)
+                    }
+                })
+                .await
+            }
+        });
+
+        for _ in 0..MAX_RECONNECT_ATTEMPTS {
+            cx.run_until_parked();
+            cx.background_executor.advance_clock(RECONNECT_DELAY);
+        }
+
+        assert_eq!(task.await.unwrap(), 3);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[gpui::test]
+    async fn test_reconnect_gives_up_after_max_attempts(cx: &mut TestAppContext) {
+        let attempts = Arc::new(AtomicU32::new(0));
+        let executor = cx.background_executor.clone();
+        let task = cx.background_executor.spawn({
+            let attempts = attempts.clone();
+            async move {
+                reconnect(&executor, "k1", || {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    async { anyhow::Result::<()>::Err(anyhow::anyhow!("connection refused")) }
+                })
+                .await
+            }
+        });
+
+        for _ in 0..MAX_RECONNECT_ATTEMPTS + 1 {
+            cx.run_until_parked();
+            cx.background_executor.advance_clock(RECONNECT_DELAY);
+        }
+
+        assert!(task.await.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), MAX_RECONNECT_ATTEMPTS);
+    }
+}
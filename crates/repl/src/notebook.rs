@@ -1,4 +1,5 @@
 mod cell;
+pub mod convert;
 mod notebook_ui;
 pub use cell::*;
 pub use notebook_ui::*;
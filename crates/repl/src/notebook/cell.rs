@@ -794,6 +794,14 @@ impl CodeCell {
             .collect()
     }
 
+    /// The outputs as written by an export, see [`Output::to_export_nbformat`].
+    pub fn outputs_to_export_nbformat(&self, cx: &App) -> Vec<nbformat::v4::Output> {
+        self.outputs
+            .iter()
+            .filter_map(|output| output.to_export_nbformat(cx))
+            .collect()
+    }
+
     pub fn has_outputs(&self) -> bool {
         !self.outputs.is_empty()
     }
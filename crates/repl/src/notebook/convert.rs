@@ -0,0 +1,734 @@
+//! Conversions between Jupyter notebooks and plain files.
+//!
+//! Percent-format scripts use the same `# %%` cell markers the REPL recognizes
+//! for inline execution, so an exported script can be run cell by cell from a
+//! regular editor and imported back into a notebook. Markdown and HTML exports
+//! are one-way and include the cell outputs, with images embedded as data URIs.
+
+use std::fmt::Write as _;
+
+use jupyter_protocol::MediaType;
+use nbformat::v4::{Cell, CellId, CellMetadata, Notebook, Output};
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use uuid::Uuid;
+
+const CELL_MARKER: &str = "%%";
+const MARKDOWN_CELL_TAGS: [&str; 2] = ["[markdown]", "[md]"];
+const RAW_CELL_TAG: &str = "[raw]";
+
+/// The file formats a notebook can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    PercentScript,
+    Markdown,
+    Html,
+}
+
+/// Writes the notebook as a percent-format script.
+///
+/// Code cells are emitted as-is. Markdown and raw cell contents are commented
+/// out with `comment_prefix`, which should include its trailing space (`"# "`).
+pub fn to_percent_script(notebook: &Notebook, comment_prefix: &str) -> String {
+    let bare_prefix = comment_prefix.trim_end();
+    let mut script = String::new();
+
+    for cell in &notebook.cells {
+        if !script.is_empty() {
+            script.push('\n');
+        }
+
+        let (tag, source, commented) = match cell {
+            Cell::Code { source, .. } => (None, source, false),
+            Cell::Markdown { source, .. } => (Some(MARKDOWN_CELL_TAGS[0]), source, true),
+            Cell::Raw { source, .. } => (Some(RAW_CELL_TAG), source, true),
+        };
+
+        script.push_str(comment_prefix);
+        script.push_str(CELL_MARKER);
+        if let Some(tag) = tag {
+            script.push(' ');
+            script.push_str(tag);
+        }
+        script.push('\n');
+
+        for line in source.concat().lines() {
+            if !commented {
+                script.push_str(line);
+            } else if line.is_empty() {
+                script.push_str(bare_prefix);
+            } else {
+                script.push_str(comment_prefix);
+                script.push_str(line);
+            }
+            script.push('\n');
+        }
+    }
+
+    script
+}
+
+/// Parses a percent-format script into a notebook for `language`.
+///
+/// Anything before the first cell marker becomes its own code cell, so a
+/// script without markers is imported as a single cell.
+pub fn from_percent_script(script: &str, comment_prefix: &str, language: &str) -> Notebook {
+    let bare_prefix = comment_prefix.trim_end();
+    let marker = format!("{bare_prefix}{CELL_MARKER}");
+    let spaced_marker = format!("{comment_prefix}{CELL_MARKER}");
+
+    let mut cells = Vec::new();
+    let mut kind = PercentCellKind::Code;
+    let mut lines: Vec<&str> = Vec::new();
+
+    for line in script.lines() {
+        let marker_rest = line
+            .strip_prefix(&spaced_marker)
+            .or_else(|| line.strip_prefix(&marker));
+
+        if let Some(rest) = marker_rest {
+            if let Some(cell) = percent_cell(kind, &lines, comment_prefix) {
+                cells.push(cell);
+            }
+            lines.clear();
+            kind = PercentCellKind::from_marker(rest);
+        } else {
+            lines.push(line);
+        }
+    }
+    if let Some(cell) = percent_cell(kind, &lines, comment_prefix) {
+        cells.push(cell);
+    }
+
+    let metadata = serde_json::from_value(serde_json::json!({
+        "language_info": { "name": language }
+    }))
+    .unwrap_or_else(|_| serde_json::from_str("{}").unwrap());
+
+    Notebook {
+        metadata,
+        nbformat: 4,
+        nbformat_minor: 5,
+        cells,
+    }
+}
+
+#[derive(Clone, Copy)]
+enum PercentCellKind {
+    Code,
+    Markdown,
+    Raw,
+}
+
+impl PercentCellKind {
+    fn from_marker(rest: &str) -> Self {
+        let rest = rest.trim();
+        if MARKDOWN_CELL_TAGS.iter().any(|tag| rest.contains(tag)) {
+            Self::Markdown
+        } else if rest.contains(RAW_CELL_TAG) {
+            Self::Raw
+        } else {
+            Self::Code
+        }
+    }
+}
+
+fn percent_cell(kind: PercentCellKind, lines: &[&str], comment_prefix: &str) -> Option<Cell> {
+    let bare_prefix = comment_prefix.trim_end();
+    let mut lines = lines.to_vec();
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    while lines.first().is_some_and(|line| line.trim().is_empty()) {
+        lines.remove(0);
+    }
+    if lines.is_empty() {
+        return None;
+    }
+
+    let uncommented = || {
+        let lines = lines
+            .iter()
+            .map(|line| {
+                line.strip_prefix(comment_prefix)
+                    .or_else(|| line.strip_prefix(bare_prefix))
+                    .unwrap_or(line)
+            })
+            .collect::<Vec<_>>();
+        source_lines(&lines.join("\n"))
+    };
+
+    let id = CellId::from(Uuid::new_v4());
+    let metadata: CellMetadata = serde_json::from_str("{}").unwrap();
+
+    Some(match kind {
+        PercentCellKind::Code => Cell::Code {
+            id,
+            metadata,
+            execution_count: None,
+            source: source_lines(&lines.join("\n")),
+            outputs: Vec::new(),
+        },
+        PercentCellKind::Markdown => Cell::Markdown {
+            id,
+            metadata,
+            source: uncommented(),
+            attachments: None,
+        },
+        PercentCellKind::Raw => Cell::Raw {
+            id,
+            metadata,
+            source: uncommented(),
+        },
+    })
+}
+
+/// Splits text the way nbformat stores it: every line but the last keeps its newline.
+fn source_lines(text: &str) -> Vec<String> {
+    text.split_inclusive('\n')
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Writes the notebook as Markdown, with code cells fenced in `language` and
+/// their outputs following them.
+pub fn to_markdown(notebook: &Notebook, language: &str) -> String {
+    let mut markdown = String::new();
+
+    for cell in &notebook.cells {
+        if !markdown.is_empty() {
+            markdown.push('\n');
+        }
+
+        match cell {
+            Cell::Markdown { source, .. } | Cell::Raw { source, .. } => {
+                push_line(&mut markdown, source.concat().trim_end());
+            }
+            Cell::Code {
+                source, outputs, ..
+            } => {
+                push_fenced(&mut markdown, language, source.concat().trim_end());
+                for output in outputs {
+                    markdown.push('\n');
+                    push_markdown_output(&mut markdown, output);
+                }
+            }
+        }
+    }
+
+    markdown
+}
+
+fn push_markdown_output(markdown: &mut String, output: &Output) {
+    match output {
+        Output::Stream { text, .. } => push_fenced(markdown, "text", text.0.trim_end()),
+        Output::DisplayData(display_data) => push_markdown_media(markdown, &display_data.data),
+        Output::ExecuteResult(execute_result) => {
+            push_markdown_media(markdown, &execute_result.data)
+        }
+        Output::Error(error) => push_fenced(markdown, "text", &error_text(error)),
+    }
+}
+
+fn push_markdown_media(markdown: &mut String, media: &jupyter_protocol::media::Media) {
+    let richest = media.content.iter().max_by_key(|media| match media {
+        MediaType::Markdown(_) => 5,
+        MediaType::Png(_) => 4,
+        MediaType::Jpeg(_) => 3,
+        MediaType::Plain(_) => 2,
+        MediaType::Html(_) => 1,
+        _ => 0,
+    });
+
+    match richest {
+        Some(MediaType::Markdown(text)) => push_line(markdown, text.trim_end()),
+        Some(MediaType::Png(data)) => push_line(
+            markdown,
+            &format!("![output]({})", data_uri("image/png", data)),
+        ),
+        Some(MediaType::Jpeg(data)) => push_line(
+            markdown,
+            &format!("![output]({})", data_uri("image/jpeg", data)),
+        ),
+        Some(MediaType::Plain(text)) => push_fenced(markdown, "text", strip_ansi(text).trim_end()),
+        Some(MediaType::Html(html)) => push_line(markdown, html.trim_end()),
+        _ => {}
+    }
+}
+
+fn push_line(output: &mut String, text: &str) {
+    output.push_str(text);
+    output.push('\n');
+}
+
+fn push_fenced(markdown: &mut String, info: &str, text: &str) {
+    // Use a fence longer than any backtick run in the text so it can't be closed early.
+    let longest_run = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    let _ = writeln!(markdown, "{fence}{info}\n{text}\n{fence}");
+}
+
+/// Writes the notebook as a standalone HTML document. Images are embedded, so
+/// the file can be viewed without the notebook or network access.
+pub fn to_html(notebook: &Notebook, title: &str, language: &str) -> String {
+    let mut body = String::new();
+
+    for cell in &notebook.cells {
+        match cell {
+            Cell::Markdown { source, .. } => {
+                body.push_str("<section class=\"cell markdown\">\n");
+                push_markdown_as_html(&mut body, &source.concat());
+                body.push_str("</section>\n");
+            }
+            Cell::Raw { source, .. } => {
+                let _ = writeln!(
+                    body,
+                    "<section class=\"cell raw\"><pre>{}</pre></section>",
+                    escape_html(&source.concat())
+                );
+            }
+            Cell::Code {
+                source,
+                outputs,
+                execution_count,
+                ..
+            } => {
+                let prompt = execution_count
+                    .map(|count| format!("[{count}]"))
+                    .unwrap_or_else(|| "[ ]".to_string());
+                let _ = writeln!(
+                    body,
+                    "<section class=\"cell code\">\n<div class=\"prompt\">{prompt}</div>\n<pre class=\"source\"><code class=\"language-{}\">{}</code></pre>",
+                    escape_html(language),
+                    escape_html(source.concat().trim_end())
+                );
+                for output in outputs {
+                    push_html_output(&mut body, output);
+                }
+                body.push_str("</section>\n");
+            }
+        }
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(title),
+        HTML_STYLE,
+        body
+    )
+}
+
+const HTML_STYLE: &str = "\
+body { font-family: -apple-system, BlinkMacSystemFont, \"Segoe UI\", sans-serif; max-width: 960px; margin: 2rem auto; padding: 0 1rem; line-height: 1.5; }
+.cell { margin: 1rem 0; }
+.prompt { color: #888; font-family: monospace; font-size: 0.85em; }
+pre { background: #f6f8fa; padding: 0.75rem; overflow-x: auto; border-radius: 4px; }
+.output pre { background: transparent; border-left: 3px solid #ddd; }
+.output.error pre { border-left-color: #d73a49; color: #b31d28; }
+img { max-width: 100%; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ddd; padding: 0.25rem 0.5rem; }
+";
+
+fn push_html_output(body: &mut String, output: &Output) {
+    match output {
+        Output::Stream { text, .. } => {
+            let _ = writeln!(
+                body,
+                "<div class=\"output stream\"><pre>{}</pre></div>",
+                escape_html(strip_ansi(&text.0).trim_end())
+            );
+        }
+        Output::DisplayData(display_data) => push_html_media(body, &display_data.data),
+        Output::ExecuteResult(execute_result) => push_html_media(body, &execute_result.data),
+        Output::Error(error) => {
+            let _ = writeln!(
+                body,
+                "<div class=\"output error\"><pre>{}</pre></div>",
+                escape_html(&error_text(error))
+            );
+        }
+    }
+}
+
+fn push_html_media(body: &mut String, media: &jupyter_protocol::media::Media) {
+    let richest = media.content.iter().max_by_key(|media| match media {
+        MediaType::Html(_) => 6,
+        MediaType::Svg(_) => 5,
+        MediaType::Png(_) => 4,
+        MediaType::Jpeg(_) => 3,
+        MediaType::Markdown(_) => 2,
+        MediaType::Plain(_) => 1,
+        _ => 0,
+    });
+
+    body.push_str("<div class=\"output\">");
+    match richest {
+        Some(MediaType::Html(html)) => body.push_str(html),
+        Some(MediaType::Svg(svg)) => body.push_str(svg),
+        Some(MediaType::Png(data)) => {
+            let _ = write!(body, "<img src=\"{}\">", data_uri("image/png", data));
+        }
+        Some(MediaType::Jpeg(data)) => {
+            let _ = write!(body, "<img src=\"{}\">", data_uri("image/jpeg", data));
+        }
+        Some(MediaType::Markdown(markdown)) => push_markdown_as_html(body, markdown),
+        Some(MediaType::Plain(text)) => {
+            let _ = write!(
+                body,
+                "<pre>{}</pre>",
+                escape_html(strip_ansi(text).trim_end())
+            );
+        }
+        _ => {}
+    }
+    body.push_str("</div>\n");
+}
+
+/// Renders CommonMark to HTML. Raw HTML in the source is passed through, as
+/// Jupyter does when rendering markdown cells.
+fn push_markdown_as_html(html: &mut String, markdown: &str) {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+
+    let mut in_table_head = false;
+    let mut image_depth = 0;
+    for event in Parser::new_ext(markdown, options) {
+        // An image's description becomes its `alt` attribute, which can only
+        // hold plain text, so any markup nested inside it is flattened.
+        if image_depth > 0 {
+            match event {
+                Event::Start(Tag::Image { .. }) => image_depth += 1,
+                Event::End(TagEnd::Image) => {
+                    image_depth -= 1;
+                    if image_depth == 0 {
+                        html.push_str("\">");
+                    }
+                }
+                Event::Text(text)
+                | Event::Code(text)
+                | Event::InlineMath(text)
+                | Event::Html(text)
+                | Event::InlineHtml(text) => html.push_str(&escape_html(&text)),
+                Event::SoftBreak | Event::HardBreak => html.push(' '),
+                _ => {}
+            }
+            continue;
+        }
+
+        match event {
+            Event::Start(tag) => match tag {
+                Tag::Paragraph => html.push_str("<p>"),
+                Tag::Heading { level, .. } => {
+                    let _ = write!(html, "<{level}>");
+                }
+                Tag::BlockQuote(_) => html.push_str("<blockquote>\n"),
+                Tag::CodeBlock(CodeBlockKind::Fenced(info)) if !info.is_empty() => {
+                    let language = info.split_whitespace().next().unwrap_or_default();
+                    let _ = write!(
+                        html,
+                        "<pre><code class=\"language-{}\">",
+                        escape_html(language)
+                    );
+                }
+                Tag::CodeBlock(_) => html.push_str("<pre><code>"),
+                Tag::List(Some(1)) => html.push_str("<ol>\n"),
+                Tag::List(Some(start)) => {
+                    let _ = writeln!(html, "<ol start=\"{start}\">");
+                }
+                Tag::List(None) => html.push_str("<ul>\n"),
+                Tag::Item => html.push_str("<li>"),
+                Tag::Table(_) => html.push_str("<table>\n"),
+                Tag::TableHead => {
+                    in_table_head = true;
+                    html.push_str("<thead><tr>");
+                }
+                Tag::TableRow => html.push_str("<tr>"),
+                Tag::TableCell if in_table_head => html.push_str("<th>"),
+                Tag::TableCell => html.push_str("<td>"),
+                Tag::Emphasis => html.push_str("<em>"),
+                Tag::Strong => html.push_str("<strong>"),
+                Tag::Strikethrough => html.push_str("<del>"),
+                Tag::Link {
+                    dest_url, title, ..
+                } => {
+                    let _ = write!(html, "<a href=\"{}\"", escape_html(&dest_url));
+                    if !title.is_empty() {
+                        let _ = write!(html, " title=\"{}\"", escape_html(&title));
+                    }
+                    html.push('>');
+                }
+                Tag::Image { dest_url, .. } => {
+                    image_depth = 1;
+                    let _ = write!(html, "<img src=\"{}\" alt=\"", escape_html(&dest_url));
+                }
+                _ => {}
+            },
+            Event::End(tag) => match tag {
+                TagEnd::Paragraph => html.push_str("</p>\n"),
+                TagEnd::Heading(level) => {
+                    let _ = writeln!(html, "</{level}>");
+                }
+                TagEnd::BlockQuote(_) => html.push_str("</blockquote>\n"),
+                TagEnd::CodeBlock => html.push_str("</code></pre>\n"),
+                TagEnd::List(true) => html.push_str("</ol>\n"),
+                TagEnd::List(false) => html.push_str("</ul>\n"),
+                TagEnd::Item => html.push_str("</li>\n"),
+                TagEnd::Table => html.push_str("</tbody></table>\n"),
+                TagEnd::TableHead => {
+                    in_table_head = false;
+                    html.push_str("</tr></thead><tbody>\n");
+                }
+                TagEnd::TableRow => html.push_str("</tr>\n"),
+                TagEnd::TableCell if in_table_head => html.push_str("</th>"),
+                TagEnd::TableCell => html.push_str("</td>"),
+                TagEnd::Emphasis => html.push_str("</em>"),
+                TagEnd::Strong => html.push_str("</strong>"),
+                TagEnd::Strikethrough => html.push_str("</del>"),
+                TagEnd::Link => html.push_str("</a>"),
+                _ => {}
+            },
+            Event::Text(text) => html.push_str(&escape_html(&text)),
+            Event::Code(code) => {
+                let _ = write!(html, "<code>{}</code>", escape_html(&code));
+            }
+            Event::InlineMath(math) => {
+                let _ = write!(html, "<code>${}$</code>", escape_html(&math));
+            }
+            Event::DisplayMath(math) => {
+                let _ = write!(html, "<pre>$${}$$</pre>", escape_html(&math));
+            }
+            Event::Html(raw) | Event::InlineHtml(raw) => html.push_str(&raw),
+            Event::SoftBreak => html.push('\n'),
+            Event::HardBreak => html.push_str("<br>\n"),
+            Event::Rule => html.push_str("<hr>\n"),
+            Event::TaskListMarker(checked) => {
+                let checked = if checked { " checked" } else { "" };
+                let _ = write!(html, "<input type=\"checkbox\" disabled{checked}> ");
+            }
+            Event::FootnoteReference(_) => {}
+        }
+    }
+}
+
+fn error_text(error: &nbformat::v4::ErrorOutput) -> String {
+    if error.traceback.is_empty() {
+        format!("{}: {}", error.ename, error.evalue)
+    } else {
+        strip_ansi(&error.traceback.join("\n"))
+    }
+}
+
+fn data_uri(mime_type: &str, base64_data: &str) -> String {
+    let data: String = base64_data.chars().filter(|c| !c.is_whitespace()).collect();
+    format!("data:{mime_type};base64,{data}")
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Removes ANSI escape sequences, which kernels use to color tracebacks.
+fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+            continue;
+        }
+        if chars.next_if_eq(&'[').is_some() {
+            // CSI sequences end with a byte in the range `@`..=`~`.
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn notebook(json: serde_json::Value) -> Notebook {
+        match nbformat::parse_notebook(&json.to_string()).unwrap() {
+            nbformat::Notebook::V4(notebook) => notebook,
+            _ => panic!("expected a v4 notebook"),
+        }
+    }
+
+    fn sample_notebook() -> Notebook {
+        notebook(serde_json::json!({
+            "metadata": {},
+            "nbformat": 4,
+            "nbformat_minor": 5,
+            "cells": [
+                {
+                    "cell_type": "markdown",
+                    "id": "intro",
+                    "metadata": {},
+                    "source": ["# Title\n", "\n", "Some *text*."]
+                },
+                {
+                    "cell_type": "code",
+                    "id": "code",
+                    "metadata": {},
+                    "execution_count": 1,
+                    "source": ["x = 1\n", "print(x < 2)"],
+                    "outputs": [
+                        { "output_type": "stream", "name": "stdout", "text": ["True\n"] },
+                        {
+                            "output_type": "display_data",
+                            "metadata": {},
+                            "data": { "image/png": "iVBORw0KGgo=\n", "text/plain": "<Figure>" }
+                        }
+                    ]
+                }
+            ]
+        }))
+    }
+
+    #[test]
+    fn test_percent_script_round_trip() {
+        let script = to_percent_script(&sample_notebook(), "# ");
+        assert_eq!(
+            script,
+            indoc! {"
+                # %% [markdown]
+                # # Title
+                #
+                # Some *text*.
+
+                # %%
+                x = 1
+                print(x < 2)
+            "}
+        );
+
+        let imported = from_percent_script(&script, "# ", "python");
+        assert_eq!(imported.cells.len(), 2);
+        match &imported.cells[0] {
+            Cell::Markdown { source, .. } => {
+                assert_eq!(source.concat(), "# Title\n\nSome *text*.")
+            }
+            cell => panic!("expected markdown cell, got {cell:?}"),
+        }
+        match &imported.cells[1] {
+            Cell::Code { source, .. } => assert_eq!(source.concat(), "x = 1\nprint(x < 2)"),
+            cell => panic!("expected code cell, got {cell:?}"),
+        }
+        assert_eq!(
+            imported
+                .metadata
+                .language_info
+                .as_ref()
+                .map(|info| info.name.as_str()),
+            Some("python")
+        );
+    }
+
+    #[test]
+    fn test_import_script_without_markers() {
+        let imported = from_percent_script("import os\n\nprint(os.getcwd())\n", "# ", "python");
+        assert_eq!(imported.cells.len(), 1);
+
+        let imported = from_percent_script(
+            indoc! {"
+                import os
+                #%%
+                print(1)
+                # %% [raw]
+                # raw text
+            "},
+            "# ",
+            "python",
+        );
+        assert_eq!(imported.cells.len(), 3);
+        assert!(
+            matches!(&imported.cells[2], Cell::Raw { source, .. } if source.concat() == "raw text")
+        );
+    }
+
+    #[test]
+    fn test_markdown_export_includes_outputs() {
+        let markdown = to_markdown(&sample_notebook(), "python");
+        assert_eq!(
+            markdown,
+            indoc! {"
+                # Title
+
+                Some *text*.
+
+                ```python
+                x = 1
+                print(x < 2)
+                ```
+
+                ```text
+                True
+                ```
+
+                ![output](data:image/png;base64,iVBORw0KGgo=)
+            "}
+        );
+    }
+
+    #[test]
+    fn test_html_export_escapes_source_and_embeds_images() {
+        let html = to_html(&sample_notebook(), "Sample <notebook>", "python");
+        assert!(html.contains("<title>Sample &lt;notebook&gt;</title>"));
+        assert!(html.contains("<h1>Title</h1>"));
+        assert!(html.contains("<em>text</em>"));
+        assert!(html.contains("print(x &lt; 2)"));
+        assert!(html.contains("<img src=\"data:image/png;base64,iVBORw0KGgo=\">"));
+        assert!(html.contains("<div class=\"prompt\">[1]</div>"));
+    }
+
+    #[test]
+    fn test_markdown_table_header_cells() {
+        let mut html = String::new();
+        push_markdown_as_html(&mut html, "| a | b |\n|---|---|\n| 1 | 2 |\n");
+        assert_eq!(
+            html,
+            "<table>\n<thead><tr><th>a</th><th>b</th></tr></thead><tbody>\n\
+             <tr><td>1</td><td>2</td></tr>\n</tbody></table>\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_image_alt_is_plain_text() {
+        let mut html = String::new();
+        push_markdown_as_html(
+            &mut html,
+            "![a *plot* of `x` and ![inner](b.png) \"quoted\"](a.png)",
+        );
+        assert_eq!(
+            html,
+            "<p><img src=\"a.png\" alt=\"a plot of x and inner &quot;quoted&quot;\"></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(
+            strip_ansi("\x1b[0;31mValueError\x1b[0m: bad"),
+            "ValueError: bad"
+        );
+    }
+}
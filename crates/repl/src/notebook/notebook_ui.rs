@@ -1,17 +1,19 @@
 #![allow(unused, dead_code)]
+use std::any::TypeId;
 use std::future::Future;
 use std::{path::PathBuf, sync::Arc};
 
-use anyhow::{Context as _, Result};
+use anyhow::{Context as _, Result, anyhow};
 use client::proto::ViewId;
 use collections::HashMap;
-use editor::DisplayPoint;
+use command_palette_hooks::CommandPaletteFilter;
+use editor::{DisplayPoint, Editor};
 use feature_flags::{FeatureFlagAppExt as _, NotebookFeatureFlag};
 use futures::FutureExt;
 use futures::future::Shared;
 use gpui::{
-    AnyElement, App, Entity, EventEmitter, FocusHandle, Focusable, KeyContext, ListScrollEvent,
-    ListState, Point, Task, TaskExt, actions, list, prelude::*,
+    AnyElement, App, AsyncWindowContext, Entity, EventEmitter, FocusHandle, Focusable, KeyContext,
+    ListScrollEvent, ListState, Point, Task, TaskExt, WeakEntity, actions, list, prelude::*,
 };
 use jupyter_protocol::JupyterKernelspec;
 use language::{Language, LanguageRegistry};
@@ -20,9 +22,11 @@ use project::{Project, ProjectEntryId, ProjectPath};
 use settings::Settings as _;
 use ui::{CommonAnimationExt, KeyBinding, Tooltip, prelude::*};
 use workspace::item::{ItemEvent, SaveOptions, TabContentParams};
+use workspace::notifications::DetachAndPromptErr as _;
 use workspace::searchable::SearchableItemHandle;
-use workspace::{Item, ItemHandle, Pane, ProjectItem, ToolbarItemLocation};
+use workspace::{Item, ItemHandle, Pane, ProjectItem, ToolbarItemLocation, Workspace};
 
+use super::convert::{self, ExportFormat};
 use super::{Cell, CellEvent, CellPosition, MarkdownCellEvent, RenderableCell};
 
 use nbformat::v4::CellId;
@@ -44,8 +48,9 @@ use ui::PopoverMenuHandle;
 use zed_actions::editor::{MoveDown, MoveUp};
 use zed_actions::notebook::{
     AddCodeBlock, AddMarkdownBlock, ClearOutputs, DeleteCell, EnterCommandMode, EnterEditMode,
-    InterruptKernel, MoveCellDown, MoveCellUp, NotebookMoveDown, NotebookMoveUp, OpenNotebook,
-    RestartKernel, Run, RunAll, RunAndAdvance,
+    ExportAsHtml, ExportAsMarkdown, ExportAsScript, ImportScript, InterruptKernel, MoveCellDown,
+    MoveCellUp, NotebookMoveDown, NotebookMoveUp, OpenNotebook, RestartKernel, Run, RunAll,
+    RunAndAdvance,
 };
 
 /// Whether the notebook is in command mode (navigating cells) or edit mode (editing a cell).
@@ -70,16 +75,35 @@ pub(crate) const CODE_BLOCK_INSET: f32 = MEDIUM_SPACING_SIZE;
 pub(crate) const CONTROL_SIZE: f32 = 20.0;
 
 const NOTEBOOK_EXTENSION: &str = "ipynb";
+const DEFAULT_COMMENT_PREFIX: &str = "# ";
 
 pub fn init(cx: &mut App) {
-    if cx.has_flag::<NotebookFeatureFlag>() || std::env::var("LOCAL_NOTEBOOK_DEV").is_ok() {
+    cx.observe_new(
+        |workspace: &mut Workspace, _window, _cx: &mut Context<Workspace>| {
+            workspace.register_action(|workspace, action: &ImportScript, window, cx| {
+                if notebooks_enabled(cx) {
+                    import_script(workspace, action, window, cx);
+                }
+            });
+        },
+    )
+    .detach();
+
+    if notebooks_enabled(cx) {
         workspace::register_project_item::<NotebookEditor>(cx);
+    } else {
+        CommandPaletteFilter::update_global(cx, |filter, _cx| {
+            filter.hide_action_types(&[TypeId::of::<ImportScript>()]);
+        });
     }
 
     cx.observe_flag::<NotebookFeatureFlag, _>({
         move |flag, cx| {
             if *flag {
                 workspace::register_project_item::<NotebookEditor>(cx);
+                CommandPaletteFilter::update_global(cx, |filter, _cx| {
+                    filter.show_action_types(&[TypeId::of::<ImportScript>()]);
+                });
             } else {
                 // todo: there is no way to unregister a project item, so if the feature flag
                 // gets turned off they need to restart Zed.
@@ -89,6 +113,10 @@ pub fn init(cx: &mut App) {
     .detach();
 }
 
+fn notebooks_enabled(cx: &App) -> bool {
+    cx.has_flag::<NotebookFeatureFlag>() || std::env::var("LOCAL_NOTEBOOK_DEV").is_ok()
+}
+
 pub struct NotebookEditor {
     languages: Arc<LanguageRegistry>,
     project: Entity<Project>,
@@ -301,6 +329,20 @@ impl NotebookEditor {
         }
     }
 
+    /// The notebook as written by an export, which unlike a save also keeps
+    /// image and Markdown outputs.
+    fn to_export_notebook(&self, cx: &App) -> nbformat::v4::Notebook {
+        let mut notebook = self.to_notebook(cx);
+        for cell in &mut notebook.cells {
+            if let nbformat::v4::Cell::Code { id, outputs, .. } = cell
+                && let Some(Cell::Code(code_cell)) = self.cell_map.get(id)
+            {
+                *outputs = code_cell.read(cx).outputs_to_export_nbformat(cx);
+            }
+        }
+        notebook
+    }
+
     pub fn mark_as_saved(&mut self, cx: &mut Context<Self>) {
         self.original_cell_order = self.cell_order.clone();
 
@@ -392,6 +434,71 @@ impl NotebookEditor {
         })
     }
 
+    fn export(&mut self, format: ExportFormat, window: &mut Window, cx: &mut Context<Self>) {
+        let notebook = self.to_export_notebook(cx);
+        let project = self.project.clone();
+        let project_path = self.notebook_item.read(cx).project_path.clone();
+        let notebook_language = self.notebook_language.clone();
+        let workspace = Workspace::for_window(window, cx).map(|workspace| workspace.downgrade());
+
+        cx.spawn_in(window, async move |_, cx| {
+            let language = notebook_language.await;
+            let language_info = notebook.metadata.language_info.as_ref();
+            let language_name = language_info
+                .map(|info| info.name.clone())
+                .or_else(|| {
+                    language
+                        .as_ref()
+                        .map(|language| language.code_fence_block_name().to_string())
+                })
+                .unwrap_or_default();
+            let stem = project_path
+                .path
+                .file_stem()
+                .context("Cannot export a notebook without a file name")?;
+
+            let (extension, contents) = match format {
+                ExportFormat::PercentScript => {
+                    let comment_prefix = language.as_ref().and_then(|language| {
+                        language
+                            .default_scope()
+                            .line_comment_prefixes()
+                            .first()
+                            .cloned()
+                    });
+                    // Kernels report the extension with a leading dot (".py").
+                    let extension = language_info
+                        .and_then(|info| info.additional.get("file_extension"))
+                        .and_then(|extension| extension.as_str())
+                        .map(|extension| extension.trim_start_matches('.').to_string())
+                        .or_else(|| {
+                            language
+                                .as_ref()
+                                .and_then(|language| language.path_suffixes().first().cloned())
+                        })
+                        .unwrap_or_else(|| "txt".to_string());
+                    let script = convert::to_percent_script(
+                        &notebook,
+                        comment_prefix.as_deref().unwrap_or(DEFAULT_COMMENT_PREFIX),
+                    );
+                    (extension, script)
+                }
+                ExportFormat::Markdown => (
+                    "md".to_string(),
+                    convert::to_markdown(&notebook, &language_name),
+                ),
+                ExportFormat::Html => (
+                    "html".to_string(),
+                    convert::to_html(&notebook, stem, &language_name),
+                ),
+            };
+
+            let path = unused_sibling_path(&project, &project_path, stem, &extension, cx)?;
+            write_file_and_open(project, path, contents, workspace, cx).await
+        })
+        .detach_and_prompt_err("Failed to export notebook", window, cx, |_, _, _| None);
+    }
+
     fn launch_kernel(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let spec = self.kernel_specification.clone().or_else(|| {
             ReplStore::global(cx)
@@ -1454,6 +1561,118 @@ impl NotebookEditor {
     }
 }
 
+/// Converts the `# %%` script in the active editor into a notebook saved next to it.
+fn import_script(
+    workspace: &mut Workspace,
+    _: &ImportScript,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let buffer = buffer.read(cx);
+    let Some(file) = buffer.file() else {
+        return;
+    };
+
+    let project_path = ProjectPath::from_file(file.as_ref(), cx);
+    let language = buffer.language();
+    let comment_prefix = language
+        .and_then(|language| {
+            language
+                .default_scope()
+                .line_comment_prefixes()
+                .first()
+                .cloned()
+        })
+        .unwrap_or_else(|| DEFAULT_COMMENT_PREFIX.into());
+    let language_name = language
+        .map(|language| language.code_fence_block_name().to_string())
+        .unwrap_or_default();
+    let notebook = convert::from_percent_script(&buffer.text(), &comment_prefix, &language_name);
+
+    let project = workspace.project().clone();
+    let workspace = workspace.weak_handle();
+    cx.spawn_in(window, async move |_, cx| {
+        let stem = project_path
+            .path
+            .file_stem()
+            .context("Cannot import a script without a file name")?;
+        let path = unused_sibling_path(&project, &project_path, stem, NOTEBOOK_EXTENSION, cx)?;
+        let json =
+            serde_json::to_string_pretty(&notebook).context("Failed to serialize notebook")?;
+        write_file_and_open(project, path, json, Some(workspace), cx).await
+    })
+    .detach_and_prompt_err("Failed to import script", window, cx, |_, _, _| None);
+}
+
+fn sibling_path(project_path: &ProjectPath, file_name: &str) -> Result<ProjectPath> {
+    let mut path = project_path.path.to_rel_path_buf();
+    path.pop();
+    path.push_component(file_name)?;
+    Ok(ProjectPath {
+        worktree_id: project_path.worktree_id,
+        path: path.as_rel_path().into_arc(),
+    })
+}
+
+/// Picks `{stem}.{extension}`, or `{stem}-1.{extension}` and so on if that is
+/// taken, so an import or export never overwrites an existing file.
+fn unused_sibling_path(
+    project: &Entity<Project>,
+    project_path: &ProjectPath,
+    stem: &str,
+    extension: &str,
+    cx: &mut AsyncWindowContext,
+) -> Result<ProjectPath> {
+    project.read_with(cx, |project, cx| {
+        for suffix in 0.. {
+            let file_name = if suffix == 0 {
+                format!("{stem}.{extension}")
+            } else {
+                format!("{stem}-{suffix}.{extension}")
+            };
+            let path = sibling_path(project_path, &file_name)?;
+            if project.entry_for_path(&path, cx).is_none() {
+                return Ok(path);
+            }
+        }
+        Err(anyhow!("No free file name for {stem}.{extension}"))
+    })
+}
+
+/// Writes `contents` to a new file at `path` and opens it.
+async fn write_file_and_open(
+    project: Entity<Project>,
+    path: ProjectPath,
+    contents: String,
+    workspace: Option<WeakEntity<Workspace>>,
+    cx: &mut AsyncWindowContext,
+) -> Result<()> {
+    let buffer = project
+        .update(cx, |project, cx| project.create_buffer(None, true, cx))
+        .await?;
+    buffer.update(cx, |buffer, cx| buffer.set_text(contents, cx));
+    project
+        .update(cx, |project, cx| {
+            project.save_buffer_as(buffer, path.clone(), cx)
+        })
+        .await?;
+
+    if let Some(workspace) = workspace {
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path(path, None, true, window, cx)
+            })?
+            .await?;
+    }
+    Ok(())
+}
+
 impl Render for NotebookEditor {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let mut key_context = KeyContext::new_with_defaults();
@@ -1496,6 +1715,15 @@ impl Render for NotebookEditor {
                 cx.listener(|this, _: &AddCodeBlock, window, cx| this.add_code_block(window, cx)),
             )
             .on_action(cx.listener(|this, _: &DeleteCell, window, cx| this.delete_cell(window, cx)))
+            .on_action(cx.listener(|this, _: &ExportAsScript, window, cx| {
+                this.export(ExportFormat::PercentScript, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &ExportAsMarkdown, window, cx| {
+                this.export(ExportFormat::Markdown, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &ExportAsHtml, window, cx| {
+                this.export(ExportFormat::Html, window, cx)
+            }))
             .on_action(
                 cx.listener(|this, action, window, cx| this.enter_edit_mode(action, window, cx)),
             )
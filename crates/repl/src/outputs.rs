@@ -17,6 +17,7 @@
 //! - Images (PNG and JPEG)
 //! - Tables
 //! - Error messages
+//! - Jupyter widgets (`ipywidgets`)
 //!
 //! ## Clipboard Support
 //!
@@ -54,6 +55,9 @@ use json::JsonView;
 
 mod html;
 
+mod widget;
+use widget::WidgetView;
+
 pub mod plain;
 use plain::TerminalOutput;
 
@@ -62,6 +66,7 @@ use user_error::ErrorView;
 use workspace::Workspace;
 
 use crate::repl_settings::ReplSettings;
+use crate::session::comm::CommManager;
 use settings::Settings;
 
 /// When deciding what to render from a collection of mediatypes, we need to rank them in order of importance
@@ -136,6 +141,10 @@ pub enum Output {
         content: Entity<JsonView>,
         display_id: Option<String>,
     },
+    Widget {
+        content: Entity<WidgetView>,
+        display_id: Option<String>,
+    },
     ClearOutputWaitMarker,
 }
 
@@ -173,11 +182,32 @@ impl Output {
             Output::Image { .. }
             | Output::Markdown { .. }
             | Output::Table { .. }
-            | Output::Json { .. } => None,
+            | Output::Json { .. }
+            | Output::Widget { .. } => None,
             Output::Message(_) => None,
             Output::ClearOutputWaitMarker => None,
         }
     }
+
+    /// Like [`Output::to_nbformat`], but also keeps rendered images and
+    /// Markdown, which exports include even though saving drops them.
+    pub fn to_export_nbformat(&self, cx: &App) -> Option<nbformat::v4::Output> {
+        let content = match self {
+            Output::Image { content, .. } => content.read(cx).to_media()?,
+            Output::Markdown { content, .. } => {
+                jupyter_protocol::MediaType::Markdown(content.read(cx).source(cx))
+            }
+            _ => return self.to_nbformat(cx),
+        };
+        let mut data = jupyter_protocol::media::Media::default();
+        data.content.push(content);
+        Some(nbformat::v4::Output::DisplayData(
+            nbformat::v4::DisplayData {
+                data,
+                metadata: serde_json::Map::new(),
+            },
+        ))
+    }
 }
 
 impl Output {
@@ -262,6 +292,7 @@ impl Output {
             Self::Message(message) => Some(div().child(message.clone()).into_any_element()),
             Self::Table { content, .. } => Some(content.clone().into_any_element()),
             Self::Json { content, .. } => Some(content.clone().into_any_element()),
+            Self::Widget { content, .. } => Some(content.clone().into_any_element()),
             Self::ErrorOutput(error_view) => error_view.render(window, cx),
             Self::ClearOutputWaitMarker => None,
         }
@@ -373,6 +404,7 @@ impl Output {
                 Self::Table { content, .. } => {
                     Self::render_output_controls(content.clone(), workspace, window, cx)
                 }
+                Self::Widget { .. } => None,
                 Self::ClearOutputWaitMarker => None,
             })
     }
@@ -387,6 +419,7 @@ impl Output {
             Output::Table { display_id, .. } => display_id.clone(),
             Output::Markdown { display_id, .. } => display_id.clone(),
             Output::Json { display_id, .. } => display_id.clone(),
+            Output::Widget { display_id, .. } => display_id.clone(),
             Output::ClearOutputWaitMarker => None,
         }
     }
@@ -444,6 +477,34 @@ impl Output {
             _ => Output::Message("Unsupported media type".to_string()),
         }
     }
+
+    /// Like [`Output::new`], but renders widget views natively when the
+    /// referenced model is known to `comms`. Otherwise falls back to the
+    /// richest regular media type, which for widgets is their text repr.
+    pub fn new_with_comms(
+        data: &MimeBundle,
+        display_id: Option<String>,
+        comms: Option<&Entity<CommManager>>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Self {
+        let widget_model_id = data.content.iter().find_map(|media| match media {
+            MimeType::WidgetView(view) => WidgetView::model_id_from_view(view),
+            _ => None,
+        });
+
+        if let Some((model_id, comms)) = widget_model_id.zip(comms)
+            && comms.read(cx).model(&model_id).is_some()
+        {
+            let comms = comms.clone();
+            return Output::Widget {
+                content: cx.new(|cx| WidgetView::new(model_id, comms, cx)),
+                display_id,
+            };
+        }
+
+        Self::new(data, display_id, window, cx)
+    }
 }
 
 #[derive(Default, Clone, Debug)]
@@ -484,6 +545,7 @@ pub struct ExecutionView {
     pub outputs: Vec<Output>,
     pub status: ExecutionStatus,
     pending_input: Option<PendingInput>,
+    comms: Option<Entity<CommManager>>,
 }
 
 impl EventEmitter<ExecutionViewFinishedEmpty> for ExecutionView {}
@@ -501,9 +563,15 @@ impl ExecutionView {
             outputs: Default::default(),
             status,
             pending_input: None,
+            comms: None,
         }
     }
 
+    /// Lets this view render widget outputs backed by the session's comms.
+    pub fn set_comm_manager(&mut self, comms: Entity<CommManager>) {
+        self.comms = Some(comms);
+    }
+
     fn submit_input(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
         if let Some(pending_input) = self.pending_input.take() {
             let value = pending_input.editor.read(cx).text(cx);
@@ -561,15 +629,17 @@ impl ExecutionView {
         cx: &mut Context<Self>,
     ) {
         let output: Output = match message {
-            JupyterMessageContent::ExecuteResult(result) => Output::new(
+            JupyterMessageContent::ExecuteResult(result) => Output::new_with_comms(
                 &result.data,
                 result.transient.as_ref().and_then(|t| t.display_id.clone()),
+                self.comms.as_ref(),
                 window,
                 cx,
             ),
-            JupyterMessageContent::DisplayData(result) => Output::new(
+            JupyterMessageContent::DisplayData(result) => Output::new_with_comms(
                 &result.data,
                 result.transient.as_ref().and_then(|t| t.display_id.clone()),
+                self.comms.as_ref(),
                 window,
                 cx,
             ),
@@ -667,12 +737,19 @@ impl ExecutionView {
         cx: &mut Context<Self>,
     ) {
         let mut any = false;
+        let comms = self.comms.clone();
 
         self.outputs.iter_mut().for_each(|output| {
             if let Some(other_display_id) = output.display_id().as_ref()
                 && other_display_id == display_id
             {
-                *output = Output::new(data, Some(display_id.to_owned()), window, cx);
+                *output = Output::new_with_comms(
+                    data,
+                    Some(display_id.to_owned()),
+                    comms.as_ref(),
+                    window,
+                    cx,
+                );
                 any = true;
             }
         });
@@ -106,6 +106,17 @@ impl ImageView {
 
         (height, width)
     }
+
+    /// Re-encodes the image as Jupyter media so it can be written back to a notebook.
+    pub fn to_media(&self) -> Option<jupyter_protocol::MediaType> {
+        let encoded =
+            base64::engine::general_purpose::STANDARD.encode(self.clipboard_image.bytes());
+        match self.clipboard_image.format() {
+            ImageFormat::Png => Some(jupyter_protocol::MediaType::Png(encoded)),
+            ImageFormat::Jpeg => Some(jupyter_protocol::MediaType::Jpeg(encoded)),
+            _ => None,
+        }
+    }
 }
 
 impl Render for ImageView {
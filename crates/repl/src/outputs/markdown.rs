@@ -14,6 +14,10 @@ impl MarkdownView {
 
         Self { markdown }
     }
+
+    pub fn source(&self, cx: &App) -> String {
+        self.markdown.read(cx).source().to_string()
+    }
 }
 
 impl OutputContent for MarkdownView {
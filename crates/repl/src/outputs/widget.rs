@@ -0,0 +1,502 @@
+//! # Widget Output for REPL
+//!
+//! Renders `application/vnd.jupyter.widget-view+json` outputs natively. The
+//! view only knows the id of the model it displays; the model state lives in the
+//! session's [`CommManager`] and is kept in sync with the kernel there.
+//!
+//! The core `ipywidgets` controls (sliders, progress bars, checkboxes,
+//! dropdowns, text inputs, buttons, labels and boxes) are supported. Anything
+//! else falls back to a short notice naming the unsupported model.
+
+use collections::HashMap;
+use editor::{Editor, EditorEvent};
+use gpui::{AnyElement, App, ClipboardItem, Context, Entity, Subscription, Window, prelude::*};
+use serde_json::{Value, json};
+use ui::{Checkbox, ContextMenu, DropdownMenu, ProgressBar, ToggleState, Tooltip, prelude::*};
+
+use crate::outputs::{OutputContent, html};
+use crate::session::comm::{CommManager, WidgetModel};
+
+/// Boxes can nest arbitrarily deep; stop before a reference cycle overflows the stack.
+const MAX_WIDGET_DEPTH: usize = 16;
+
+pub struct WidgetView {
+    model_id: String,
+    comms: Entity<CommManager>,
+    text_editors: HashMap<String, Entity<Editor>>,
+    model_observations: HashMap<String, Subscription>,
+    _comms_observation: Subscription,
+}
+
+impl WidgetView {
+    pub fn new(model_id: String, comms: Entity<CommManager>, cx: &mut Context<Self>) -> Self {
+        let comms_observation = cx.observe(&comms, |_, _, cx| cx.notify());
+        Self {
+            model_id,
+            comms,
+            text_editors: HashMap::default(),
+            model_observations: HashMap::default(),
+            _comms_observation: comms_observation,
+        }
+    }
+
+    /// Extracts the model id from a widget view mime bundle entry.
+    pub fn model_id_from_view(view: &serde_json::Map<String, Value>) -> Option<String> {
+        view.get("model_id")
+            .and_then(Value::as_str)
+            .map(ToString::to_string)
+    }
+
+    fn observe_model(
+        &mut self,
+        model: &Entity<WidgetModel>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let model_id = model.read(cx).model_id().to_string();
+        if self.model_observations.contains_key(&model_id) {
+            return;
+        }
+
+        let subscription = cx.observe_in(model, window, |this, model, window, cx| {
+            this.sync_text_editor(&model, window, cx);
+            cx.notify();
+        });
+        self.model_observations.insert(model_id, subscription);
+    }
+
+    /// Keeps an editor-backed widget in sync with values pushed by the kernel.
+    fn sync_text_editor(
+        &mut self,
+        model: &Entity<WidgetModel>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let model = model.read(cx);
+        let Some(editor) = self.text_editors.get(model.model_id()) else {
+            return;
+        };
+        let value = model.get_str("value").unwrap_or_default().to_string();
+        editor.update(cx, |editor, cx| {
+            if editor.text(cx) != value {
+                editor.set_text(value, window, cx);
+            }
+        });
+    }
+
+    fn text_editor(
+        &mut self,
+        model: &Entity<WidgetModel>,
+        multi_line: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<Editor> {
+        let model_id = model.read(cx).model_id().to_string();
+        if let Some(editor) = self.text_editors.get(&model_id) {
+            return editor.clone();
+        }
+
+        let (value, placeholder) = {
+            let model = model.read(cx);
+            (
+                model.get_str("value").unwrap_or_default().to_string(),
+                model.get_str("placeholder").unwrap_or_default().to_string(),
+            )
+        };
+
+        let editor = cx.new(|cx| {
+            let mut editor = if multi_line {
+                Editor::auto_height(1, 8, window, cx)
+            } else {
+                Editor::single_line(window, cx)
+            };
+            editor.set_text(value, window, cx);
+            editor.set_placeholder_text(&placeholder, window, cx);
+            editor
+        });
+
+        let weak_model = model.downgrade();
+        cx.subscribe(&editor, move |_, editor, event: &EditorEvent, cx| {
+            if let EditorEvent::BufferEdited = event {
+                let text = editor.read(cx).text(cx);
+                weak_model
+                    .update(cx, |model, cx| model.set("value", json!(text), cx))
+                    .ok();
+            }
+        })
+        .detach();
+
+        self.text_editors.insert(model_id, editor.clone());
+        editor
+    }
+
+    fn render_model(
+        &mut self,
+        model: Entity<WidgetModel>,
+        depth: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        self.observe_model(&model, window, cx);
+
+        let model_name = model.read(cx).model_name().to_string();
+        let description = model
+            .read(cx)
+            .get_str("description")
+            .filter(|description| !description.is_empty())
+            .map(|description| SharedString::from(description.to_string()));
+
+        let control = match model_name.as_str() {
+            "IntSliderModel" | "FloatSliderModel" => self.render_slider(&model, cx),
+            "IntProgressModel" | "FloatProgressModel" => self.render_progress(&model, cx),
+            "CheckboxModel" => self.render_checkbox(&model, cx),
+            "ToggleButtonModel" => self.render_toggle_button(&model, cx),
+            "DropdownModel" | "SelectModel" | "RadioButtonsModel" | "ToggleButtonsModel" => {
+                self.render_dropdown(&model, window, cx)
+            }
+            "TextModel" | "PasswordModel" => self.render_text(&model, false, window, cx),
+            "TextareaModel" => self.render_text(&model, true, window, cx),
+            "ButtonModel" => {
+                // Buttons show their description on the button itself.
+                return self.render_button(&model, cx);
+            }
+            "LabelModel" | "HTMLModel" | "HTMLMathModel" => self.render_text_output(&model, cx),
+            "BoxModel" | "HBoxModel" | "VBoxModel" | "GridBoxModel" => {
+                if depth >= MAX_WIDGET_DEPTH {
+                    return Label::new("Widget nesting is too deep")
+                        .color(Color::Muted)
+                        .into_any_element();
+                }
+                return self.render_box(&model, model_name == "VBoxModel", depth, window, cx);
+            }
+            _ => Label::new(format!("{model_name} is not supported"))
+                .color(Color::Muted)
+                .into_any_element(),
+        };
+
+        h_flex()
+            .gap_2()
+            .min_w_0()
+            .children(description.map(|description| {
+                Label::new(description)
+                    .color(Color::Muted)
+                    .into_any_element()
+            }))
+            .child(control)
+            .into_any_element()
+    }
+
+    fn render_slider(&self, model: &Entity<WidgetModel>, cx: &mut Context<Self>) -> AnyElement {
+        let state = model.read(cx);
+        let model_id = state.model_id().to_string();
+        let is_int = state.model_name() == "IntSliderModel";
+        let min = state.get_f64("min").unwrap_or(0.);
+        let max = state.get_f64("max").unwrap_or(100.);
+        let step = state.get_f64("step").unwrap_or(1.);
+        let value = state.get_f64("value").unwrap_or(min);
+        let disabled = state.get_bool("disabled").unwrap_or(false);
+        let readout = state.get_bool("readout").unwrap_or(true);
+
+        let step_button = |id: &str, icon: IconName, delta: f64, tooltip: &'static str| {
+            let model = model.downgrade();
+            IconButton::new(SharedString::from(format!("{id}-{model_id}")), icon)
+                .icon_size(IconSize::Small)
+                .disabled(disabled)
+                .tooltip(Tooltip::text(tooltip))
+                .on_click(move |_, _, cx| {
+                    let new_value = (value + delta).clamp(min, max);
+                    let new_value = if is_int {
+                        json!(new_value.round() as i64)
+                    } else {
+                        json!(new_value)
+                    };
+                    model
+                        .update(cx, |model, cx| model.set("value", new_value, cx))
+                        .ok();
+                })
+        };
+
+        h_flex()
+            .gap_1()
+            .min_w_48()
+            .child(step_button("decrement", IconName::Dash, -step, "Decrease"))
+            .child(div().flex_1().child(ProgressBar::new(
+                SharedString::from(format!("slider-{model_id}")),
+                (value - min) as f32,
+                (max - min).max(f64::EPSILON) as f32,
+                cx,
+            )))
+            .child(step_button("increment", IconName::Plus, step, "Increase"))
+            .when(readout, |this| {
+                this.child(Label::new(format_number(value, is_int)).buffer_font(cx))
+            })
+            .into_any_element()
+    }
+
+    fn render_progress(&self, model: &Entity<WidgetModel>, cx: &mut Context<Self>) -> AnyElement {
+        let state = model.read(cx);
+        let min = state.get_f64("min").unwrap_or(0.);
+        let max = state.get_f64("max").unwrap_or(100.);
+        let value = state.get_f64("value").unwrap_or(min);
+
+        let mut progress_bar = ProgressBar::new(
+            SharedString::from(format!("progress-{}", state.model_id())),
+            (value - min) as f32,
+            (max - min).max(f64::EPSILON) as f32,
+            cx,
+        );
+
+        let status = cx.theme().status();
+        let bar_color = match state.get_str("bar_style") {
+            Some("success") => Some(status.success),
+            Some("danger") => Some(status.error),
+            Some("warning") => Some(status.warning),
+            _ => None,
+        };
+        if let Some(bar_color) = bar_color {
+            progress_bar = progress_bar.fg_color(bar_color);
+        }
+
+        div().min_w_48().child(progress_bar).into_any_element()
+    }
+
+    fn render_checkbox(&self, model: &Entity<WidgetModel>, cx: &mut Context<Self>) -> AnyElement {
+        let state = model.read(cx);
+        let checked = state.get_bool("value").unwrap_or(false);
+        let disabled = state.get_bool("disabled").unwrap_or(false);
+        let weak_model = model.downgrade();
+
+        Checkbox::new(
+            SharedString::from(format!("checkbox-{}", state.model_id())),
+            ToggleState::from(checked),
+        )
+        .disabled(disabled)
+        .on_click(move |toggle_state, _, cx| {
+            let checked = toggle_state.selected();
+            weak_model
+                .update(cx, |model, cx| model.set("value", json!(checked), cx))
+                .ok();
+        })
+        .into_any_element()
+    }
+
+    fn render_toggle_button(
+        &self,
+        model: &Entity<WidgetModel>,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let state = model.read(cx);
+        let toggled = state.get_bool("value").unwrap_or(false);
+        let disabled = state.get_bool("disabled").unwrap_or(false);
+        let label = state.get_str("tooltip").unwrap_or_default().to_string();
+        let weak_model = model.downgrade();
+
+        Button::new(
+            SharedString::from(format!("toggle-{}", state.model_id())),
+            if label.is_empty() {
+                if toggled { "On" } else { "Off" }.to_string()
+            } else {
+                label
+            },
+        )
+        .style(ButtonStyle::Outlined)
+        .toggle_state(toggled)
+        .disabled(disabled)
+        .on_click(move |_, _, cx| {
+            weak_model
+                .update(cx, |model, cx| model.set("value", json!(!toggled), cx))
+                .ok();
+        })
+        .into_any_element()
+    }
+
+    fn render_dropdown(
+        &self,
+        model: &Entity<WidgetModel>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let state = model.read(cx);
+        let labels: Vec<String> = state
+            .get("_options_labels")
+            .and_then(Value::as_array)
+            .map(|labels| {
+                labels
+                    .iter()
+                    .filter_map(|label| label.as_str().map(ToString::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let selected_index = state
+            .get("index")
+            .and_then(Value::as_u64)
+            .map(|index| index as usize);
+        let disabled = state.get_bool("disabled").unwrap_or(false);
+        let current_label = selected_index
+            .and_then(|index| labels.get(index).cloned())
+            .unwrap_or_else(|| "Select…".to_string());
+        let id = SharedString::from(format!("dropdown-{}", state.model_id()));
+
+        let weak_model = model.downgrade();
+        let menu = ContextMenu::build(window, cx, move |mut menu, _, _| {
+            for (index, label) in labels.into_iter().enumerate() {
+                let weak_model = weak_model.clone();
+                menu = menu.toggleable_entry(
+                    label,
+                    selected_index == Some(index),
+                    IconPosition::End,
+                    None,
+                    move |_, cx| {
+                        weak_model
+                            .update(cx, |model, cx| model.set("index", json!(index), cx))
+                            .ok();
+                    },
+                );
+            }
+            menu
+        });
+
+        DropdownMenu::new(id, current_label, menu)
+            .trigger_size(ButtonSize::Compact)
+            .disabled(disabled)
+            .into_any_element()
+    }
+
+    fn render_text(
+        &mut self,
+        model: &Entity<WidgetModel>,
+        multi_line: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let editor = self.text_editor(model, multi_line, window, cx);
+        let is_password = model.read(cx).model_name() == "PasswordModel";
+        editor.update(cx, |editor, cx| editor.set_masked(is_password, cx));
+
+        div()
+            .min_w_48()
+            .flex_1()
+            .px_2()
+            .py_1()
+            .border_1()
+            .border_color(cx.theme().colors().border)
+            .rounded_md()
+            .child(editor)
+            .into_any_element()
+    }
+
+    fn render_button(&self, model: &Entity<WidgetModel>, cx: &mut Context<Self>) -> AnyElement {
+        let state = model.read(cx);
+        let label = state.get_str("description").unwrap_or_default().to_string();
+        let disabled = state.get_bool("disabled").unwrap_or(false);
+        let tooltip = state
+            .get_str("tooltip")
+            .filter(|tooltip| !tooltip.is_empty())
+            .map(|tooltip| SharedString::from(tooltip.to_string()));
+        let weak_model = model.downgrade();
+
+        Button::new(
+            SharedString::from(format!("button-{}", state.model_id())),
+            label,
+        )
+        .style(ButtonStyle::Outlined)
+        .disabled(disabled)
+        .when_some(tooltip, |button, tooltip| {
+            button.tooltip(Tooltip::text(tooltip))
+        })
+        .on_click(move |_, _, cx| {
+            weak_model
+                .update(cx, |model, cx| {
+                    model.send_custom(json!({ "event": "click" }), cx)
+                })
+                .ok();
+        })
+        .into_any_element()
+    }
+
+    fn render_text_output(
+        &self,
+        model: &Entity<WidgetModel>,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let state = model.read(cx);
+        let value = state.get_str("value").unwrap_or_default();
+        let text = if state.model_name() == "LabelModel" {
+            value.to_string()
+        } else {
+            html::html_to_markdown(value).unwrap_or_else(|_| value.to_string())
+        };
+
+        Label::new(text.trim().to_string())
+            .buffer_font(cx)
+            .into_any_element()
+    }
+
+    fn render_box(
+        &mut self,
+        model: &Entity<WidgetModel>,
+        vertical: bool,
+        depth: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let children: Vec<Entity<WidgetModel>> = {
+            let comms = self.comms.read(cx);
+            model
+                .read(cx)
+                .get("children")
+                .and_then(Value::as_array)
+                .map(|children| {
+                    children
+                        .iter()
+                        .filter_map(|child| comms.resolve_reference(child))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let children = children
+            .into_iter()
+            .map(|child| self.render_model(child, depth + 1, window, cx))
+            .collect::<Vec<_>>();
+
+        if vertical {
+            v_flex().gap_1().children(children).into_any_element()
+        } else {
+            h_flex()
+                .gap_2()
+                .items_center()
+                .children(children)
+                .into_any_element()
+        }
+    }
+}
+
+fn format_number(value: f64, is_int: bool) -> String {
+    if is_int {
+        format!("{}", value.round() as i64)
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+impl OutputContent for WidgetView {
+    fn clipboard_content(&self, _window: &Window, _cx: &App) -> Option<ClipboardItem> {
+        None
+    }
+}
+
+impl Render for WidgetView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(model) = self.comms.read(cx).model(&self.model_id) else {
+            return Label::new("Widget is no longer available")
+                .color(Color::Muted)
+                .into_any_element();
+        };
+
+        div()
+            .w_full()
+            .child(self.render_model(model, 0, window, cx))
+            .into_any_element()
+    }
+}
@@ -16,7 +16,7 @@ use gpui::{App, PlatformDispatcher, Priority, RunnableMeta};
 use project::Fs;
 pub use runtimelib::ExecutionState;
 
-pub use crate::jupyter_settings::JupyterSettings;
+pub use crate::jupyter_settings::{JupyterServerSettings, JupyterSettings};
 pub use crate::kernels::{Kernel, KernelSpecification, KernelStatus, PythonEnvKernelSpecification};
 pub use crate::repl_editor::*;
 pub use crate::repl_sessions_ui::{
@@ -86,10 +86,24 @@ pub fn init(cx: &mut App) {
                 }
             });
 
-            workspace.register_action(|_workspace, _: &RefreshKernelspecs, _, cx| {
+            workspace.register_action(|workspace, _: &RefreshKernelspecs, _, cx| {
+                let project = workspace.project().clone();
+                let worktree_ids = project
+                    .read(cx)
+                    .worktrees(cx)
+                    .map(|worktree| worktree.read(cx).id())
+                    .collect::<Vec<_>>();
                 let store = ReplStore::global(cx);
                 store.update(cx, |store, cx| {
                     store.refresh_kernelspecs(cx).detach();
+                    // Worktree kernelspecs include the running kernels of tunneled Jupyter servers.
+                    for worktree_id in worktree_ids {
+                        if store.has_python_kernelspecs(worktree_id) {
+                            store
+                                .refresh_python_kernelspecs(worktree_id, &project, cx)
+                                .detach();
+                        }
+                    }
                 });
             });
         },
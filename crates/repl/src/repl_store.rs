@@ -7,18 +7,21 @@ use command_palette_hooks::CommandPaletteFilter;
 use gpui::{
     App, Context, Entity, EntityId, Global, SharedString, Subscription, Task, TaskExt, prelude::*,
 };
+use http_client::HttpClient;
 use jupyter_websocket_client::RemoteServer;
 use language::{Language, LanguageName};
 use project::{Fs, Project, ProjectPath, WorktreeId};
 use remote::RemoteConnectionOptions;
 use settings::{Settings, SettingsStore};
+use util::ResultExt as _;
 use util::rel_path::RelPath;
 
 use crate::kernels::{
-    Kernel, PythonEnvKernelSpecification, list_remote_kernelspecs, local_kernel_specifications,
-    python_env_kernel_specifications, wsl_kernel_specifications,
+    JupyterServerTunnel, Kernel, PythonEnvKernelSpecification, RemoteKernelSpecification,
+    local_kernel_specifications, python_env_kernel_specifications, remote_kernel_specifications,
+    wsl_kernel_specifications,
 };
-use crate::{JupyterSettings, KernelSpecification, Session};
+use crate::{JupyterServerSettings, JupyterSettings, KernelSpecification, Session};
 
 struct GlobalReplStore(Entity<ReplStore>);
 
@@ -35,6 +38,11 @@ pub struct ReplStore {
     active_python_toolchain_for_worktree: HashMap<WorktreeId, SharedString>,
     remote_worktrees: HashSet<WorktreeId>,
     fetching_python_kernelspecs: HashSet<WorktreeId>,
+    jupyter_servers: Vec<JupyterServerSettings>,
+    /// Tunnels to `ssh_tunnel` servers, keyed by remote connection and server URL so
+    /// that refreshing the kernelspecs reuses them. Kernels hold on to the tunnel
+    /// they were started through, so dropping one here never disconnects a kernel.
+    jupyter_server_tunnels: HashMap<(RemoteConnectionOptions, String), Arc<JupyterServerTunnel>>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -54,6 +62,14 @@ impl ReplStore {
         let subscriptions = vec![
             cx.observe_global::<SettingsStore>(move |this, cx| {
                 this.set_enabled(JupyterSettings::enabled(cx), cx);
+
+                let jupyter_servers = &JupyterSettings::get_global(cx).servers;
+                if &this.jupyter_servers != jupyter_servers {
+                    this.jupyter_servers = jupyter_servers.clone();
+                    if this.kernelspecs_initialized {
+                        this.refresh_kernelspecs(cx).detach_and_log_err(cx);
+                    }
+                }
             }),
             cx.on_app_quit(Self::shutdown_all_sessions),
         ];
@@ -70,6 +86,8 @@ impl ReplStore {
             active_python_toolchain_for_worktree: HashMap::default(),
             remote_worktrees: HashSet::default(),
             fetching_python_kernelspecs: HashSet::default(),
+            jupyter_servers: JupyterSettings::get_global(cx).servers.clone(),
+            jupyter_server_tunnels: HashMap::default(),
         };
         this.on_enabled_changed(cx);
         this
@@ -175,6 +193,7 @@ impl ReplStore {
                 matches!(opts, RemoteConnectionOptions::Wsl(_))
             });
         let kernel_specifications_task = python_env_kernel_specifications(project, worktree_id, cx);
+        let tunneled_kernel_specifications_task = self.tunneled_kernel_specifications(project, cx);
         let active_toolchain = project.read(cx).active_toolchain(
             ProjectPath {
                 worktree_id,
@@ -192,8 +211,9 @@ impl ReplStore {
             })
             .ok();
 
-            let kernel_specifications =
+            let mut kernel_specifications =
                 kernel_specifications_res.context("getting python kernelspecs")?;
+            kernel_specifications.extend(tunneled_kernel_specifications_task.await);
 
             let active_toolchain_path = active_toolchain.await.map(|toolchain| toolchain.path);
 
@@ -217,30 +237,132 @@ impl ReplStore {
     fn get_remote_kernel_specifications(
         &self,
         cx: &mut Context<Self>,
-    ) -> Option<Task<Result<Vec<KernelSpecification>>>> {
-        match (
+    ) -> Task<Vec<KernelSpecification>> {
+        let mut remote_servers = Vec::new();
+        if let (Ok(server), Ok(token)) = (
             std::env::var("JUPYTER_SERVER"),
             std::env::var("JUPYTER_TOKEN"),
         ) {
-            (Ok(server), Ok(token)) => {
-                let remote_server = RemoteServer {
-                    base_url: server,
-                    token,
-                };
-                let http_client = cx.http_client();
-                Some(cx.spawn(async move |_, _| {
-                    list_remote_kernelspecs(remote_server, http_client)
-                        .await
-                        .map(|specs| {
-                            specs
-                                .into_iter()
-                                .map(KernelSpecification::JupyterServer)
-                                .collect()
+            remote_servers.push(RemoteServer {
+                base_url: server,
+                token,
+            });
+        }
+        remote_servers.extend(
+            self.jupyter_servers
+                .iter()
+                .filter(|server| !server.ssh_tunnel)
+                .map(|server| RemoteServer {
+                    base_url: server.url.clone(),
+                    token: server.token.clone(),
+                }),
+        );
+        let remote_servers = remote_servers
+            .into_iter()
+            .map(|remote_server| (remote_server, None))
+            .collect();
+
+        let http_client = cx.http_client();
+        cx.background_spawn(Self::list_jupyter_servers(remote_servers, http_client))
+    }
+
+    /// Lists the kernels of the servers that are reached through the project's remote connection.
+    ///
+    /// Tunnels that are still running are reused, so refreshing doesn't move the
+    /// servers to a new local port.
+    fn tunneled_kernel_specifications(
+        &mut self,
+        project: &Entity<Project>,
+        cx: &mut Context<Self>,
+    ) -> Task<Vec<KernelSpecification>> {
+        let jupyter_servers = &self.jupyter_servers;
+        self.jupyter_server_tunnels.retain(|(_, url), tunnel| {
+            tunnel.is_alive()
+                && jupyter_servers
+                    .iter()
+                    .any(|server| server.ssh_tunnel && &server.url == url)
+        });
+
+        let tunneled_servers = self
+            .jupyter_servers
+            .iter()
+            .filter(|server| server.ssh_tunnel)
+            .collect::<Vec<_>>();
+        let Some(remote_client) = project.read(cx).remote_client() else {
+            if !tunneled_servers.is_empty() {
+                log::warn!("Jupyter servers with `ssh_tunnel` require a remote project");
+            }
+            return Task::ready(Vec::new());
+        };
+        let remote_client = remote_client.read(cx);
+        let connection_options = remote_client.connection_options();
+
+        let mut remote_servers = Vec::new();
+        for server in tunneled_servers {
+            let key = (connection_options.clone(), server.url.clone());
+            let tunnel = match self.jupyter_server_tunnels.get(&key) {
+                Some(tunnel) => tunnel.clone(),
+                None => {
+                    let Some(tunnel) = JupyterServerTunnel::open(&server.url, remote_client)
+                        .with_context(|| {
+                            format!("opening tunnel to Jupyter server {}", server.name)
+                        })
+                        .log_err()
+                    else {
+                        continue;
+                    };
+                    let tunnel = Arc::new(tunnel);
+                    self.jupyter_server_tunnels.insert(key, tunnel.clone());
+                    tunnel
+                }
+            };
+            remote_servers.push((
+                RemoteServer {
+                    base_url: tunnel.local_url.clone(),
+                    token: server.token.clone(),
+                },
+                Some(tunnel),
+            ));
+        }
+
+        let executor = cx.background_executor().clone();
+        let http_client = cx.http_client();
+        cx.background_spawn(async move {
+            let ready = futures::future::join_all(
+                remote_servers
+                    .iter()
+                    .filter_map(|(_, tunnel)| tunnel.as_ref())
+                    .map(|tunnel| tunnel.wait_until_ready(executor.clone())),
+            );
+            for result in ready.await {
+                result.log_err();
+            }
+            Self::list_jupyter_servers(remote_servers, http_client).await
+        })
+    }
+
+    async fn list_jupyter_servers(
+        remote_servers: Vec<(RemoteServer, Option<Arc<JupyterServerTunnel>>)>,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Vec<KernelSpecification> {
+        let mut specs = Vec::new();
+        for (remote_server, tunnel) in remote_servers {
+            let base_url = remote_server.base_url.clone();
+            match remote_kernel_specifications(remote_server, http_client.clone()).await {
+                Ok(remote_specs) => {
+                    specs.extend(remote_specs.into_iter().map(|spec| {
+                        KernelSpecification::JupyterServer(RemoteKernelSpecification {
+                            tunnel: tunnel.clone(),
+                            ..spec
                         })
-                }))
+                    }));
+                }
+                Err(error) => {
+                    log::warn!("Failed to list kernels on Jupyter server {base_url}: {error:?}");
+                }
             }
-            _ => None,
         }
+        specs
     }
 
     pub fn ensure_kernelspecs(&mut self, cx: &mut Context<Self>) {
@@ -267,11 +389,7 @@ impl ReplStore {
                 all_specs.extend(wsl_specs);
             }
 
-            if let Some(remote_task) = remote_kernel_specifications
-                && let Ok(remote_specs) = remote_task.await
-            {
-                all_specs.extend(remote_specs);
-            }
+            all_specs.extend(remote_kernel_specifications.await);
 
             anyhow::Ok(all_specs)
         });
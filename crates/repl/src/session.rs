@@ -1,3 +1,5 @@
+pub(crate) mod comm;
+
 use crate::components::KernelListItem;
 use crate::setup_editor_session_actions;
 use crate::{
@@ -14,6 +16,7 @@ use crate::{
 };
 use anyhow::Context as _;
 use collections::{HashMap, HashSet};
+use comm::{CommManager, OutgoingCommMessage};
 use editor::SelectionEffects;
 use editor::{
     Anchor, AnchorRangeExt as _, Editor, Inlay, MultiBuffer, ToOffset, ToPoint,
@@ -53,6 +56,7 @@ pub struct Session {
     blocks: HashMap<String, EditorBlock>,
     result_inlays: HashMap<String, (InlayId, Range<Anchor>, usize)>,
     next_inlay_id: usize,
+    comms: Entity<CommManager>,
 
     _subscriptions: Vec<Subscription>,
 }
@@ -72,13 +76,18 @@ impl EditorBlock {
         editor: WeakEntity<Editor>,
         code_range: Range<Anchor>,
         status: ExecutionStatus,
+        comms: Entity<CommManager>,
         on_close: CloseBlockFn,
         cx: &mut Context<Session>,
     ) -> anyhow::Result<Self> {
         let editor = editor.upgrade().context("editor is not open")?;
         let workspace = editor.read(cx).workspace().context("workspace dropped")?;
 
-        let execution_view = cx.new(|cx| ExecutionView::new(status, workspace.downgrade(), cx));
+        let execution_view = cx.new(|cx| {
+            let mut execution_view = ExecutionView::new(status, workspace.downgrade(), cx);
+            execution_view.set_comm_manager(comms);
+            execution_view
+        });
 
         let (block_id, invalidation_anchor) = editor.update(cx, |editor, cx| {
             let buffer = editor.buffer().clone();
@@ -250,6 +259,12 @@ impl Session {
             })
             .ok();
 
+        let comms = cx.new(|_| CommManager::default());
+        let comms_subscription =
+            cx.subscribe(&comms, |session, _, event: &OutgoingCommMessage, cx| {
+                session.send(event.0.clone().into(), cx).log_err();
+            });
+
         let mut session = Self {
             fs,
             editor,
@@ -257,8 +272,9 @@ impl Session {
             blocks: HashMap::default(),
             result_inlays: HashMap::default(),
             next_inlay_id: 0,
+            comms,
             kernel_specification,
-            _subscriptions: vec![subscription],
+            _subscriptions: vec![subscription, comms_subscription],
         };
 
         session.start_kernel(window, cx);
@@ -721,6 +737,7 @@ impl Session {
             self.editor.clone(),
             anchor_range.clone(),
             status,
+            self.comms.clone(),
             on_close,
             cx,
         ) else {
@@ -823,6 +840,12 @@ impl Session {
             cx.emit(SessionEvent::Shutdown(self.editor.clone()));
         }
 
+        // Widget models belong to the kernel that opened their comms, so they
+        // are dropped once that kernel shuts down or a new one is starting.
+        if let Kernel::Shutdown | Kernel::StartingKernel(_) = kernel {
+            self.comms.update(cx, |comms, cx| comms.clear(cx));
+        }
+
         let kernel_status = KernelStatus::from(&kernel).to_string();
         let kernel_language = self.kernel_specification.language();
 
@@ -977,6 +1000,15 @@ impl Render for Session {
 
 impl KernelSession for Session {
     fn route(&mut self, message: &JupyterMessage, window: &mut Window, cx: &mut Context<Self>) {
+        // Comms outlive the execution that opened them, so they are handled
+        // by the session rather than by the block of the parent request.
+        if self
+            .comms
+            .update(cx, |comms, cx| comms.handle_message(&message.content, cx))
+        {
+            return;
+        }
+
         let parent_message_id = match message.parent_header.as_ref() {
             Some(header) => &header.msg_id,
             None => return,
@@ -1025,3 +1057,85 @@ impl KernelSession for Session {
         self.kernel_errored(error_message, cx);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernels::SshRemoteKernelSpecification;
+    use gpui::{TestAppContext, VisualTestContext};
+    use jupyter_protocol::JupyterKernelspec;
+    use runtimelib::{CommId, CommMsg, CommOpen};
+    use serde_json::{Map, json};
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_widgets_outlive_cleared_outputs(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme_settings::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+        });
+
+        let fs = project::FakeFs::new(cx.executor());
+        let window = cx.add_window(|window, cx| Editor::single_line(window, cx));
+        let editor = window.root(cx).unwrap();
+        let cx = &mut VisualTestContext::from_window(window.into(), cx);
+
+        // The editor has no project, so the SSH kernel fails to launch and the
+        // session is left without a running kernel.
+        let kernel_specification = KernelSpecification::SshRemote(SshRemoteKernelSpecification {
+            name: "python3".to_string(),
+            path: "python3".into(),
+            kernelspec: JupyterKernelspec {
+                argv: Vec::new(),
+                display_name: "Python 3".to_string(),
+                language: "python".to_string(),
+                interrupt_mode: None,
+                metadata: None,
+                env: None,
+            },
+        });
+        let session = cx.new_window_entity(|window, cx| {
+            Session::new(editor.downgrade(), fs, kernel_specification, window, cx)
+        });
+        cx.run_until_parked();
+
+        let mut data = Map::new();
+        data.insert(
+            "state".into(),
+            json!({ "_model_name": "IntSliderModel", "value": 1 }),
+        );
+        let open = JupyterMessageContent::CommOpen(CommOpen {
+            comm_id: CommId("slider".into()),
+            target_name: comm::WIDGET_TARGET_NAME.to_string(),
+            data,
+        });
+        session.update_in(cx, |session, window, cx| {
+            session.route(&open.into(), window, cx);
+            session.clear_outputs(cx);
+        });
+
+        let mut data = Map::new();
+        data.insert("method".into(), json!("update"));
+        data.insert("state".into(), json!({ "value": 5 }));
+        let update = JupyterMessageContent::CommMsg(CommMsg {
+            comm_id: CommId("slider".into()),
+            data,
+        });
+        session.update_in(cx, |session, window, cx| {
+            session.route(&update.into(), window, cx);
+        });
+
+        let model = session
+            .read_with(cx, |session, cx| session.comms.read(cx).model("slider"))
+            .expect("widget should survive clearing outputs");
+        model.read_with(cx, |model, _| assert_eq!(model.get_f64("value"), Some(5.0)));
+
+        // Restarting replaces the kernel, which takes its widgets with it.
+        session.update_in(cx, |session, window, cx| session.restart(window, cx));
+        session.read_with(cx, |session, cx| {
+            assert!(session.comms.read(cx).model("slider").is_none());
+        });
+    }
+}
@@ -0,0 +1,387 @@
+//! # Jupyter Comms
+//!
+//! Kernels open "comms" to talk to a frontend counterpart. The only comm target
+//! we understand is `jupyter.widget`, which backs `ipywidgets` (and everything
+//! built on top of it, such as `tqdm.notebook` progress bars).
+//!
+//! Every open widget comm becomes a [`WidgetModel`] holding the model's state.
+//! Updates from the kernel are merged into that state, and changes made through
+//! the rendered widget are sent back as `comm_msg` updates so the kernel side
+//! stays in sync.
+
+use collections::HashMap;
+use gpui::{Context, Entity, EventEmitter, Subscription};
+use runtimelib::{CommClose, CommId, CommMsg, CommOpen, JupyterMessageContent};
+use serde_json::{Map, Value, json};
+
+/// The comm target registered by `ipywidgets` for widget models.
+pub const WIDGET_TARGET_NAME: &str = "jupyter.widget";
+
+/// Prefix `ipywidgets` uses when one model references another in its state.
+const MODEL_REFERENCE_PREFIX: &str = "IPY_MODEL_";
+
+/// Emitted by a [`WidgetModel`] when the user changed its state from the UI.
+pub struct WidgetStateChanged(pub Map<String, Value>);
+
+/// Emitted by a [`WidgetModel`] when the UI sends a custom message, e.g. a button click.
+pub struct WidgetCustomMessage(pub Value);
+
+/// Emitted by a [`WidgetModel`] whenever its state changes, regardless of origin.
+pub struct WidgetModelUpdated;
+
+/// The frontend half of an `ipywidgets` model.
+pub struct WidgetModel {
+    comm_id: CommId,
+    state: Map<String, Value>,
+}
+
+impl EventEmitter<WidgetStateChanged> for WidgetModel {}
+impl EventEmitter<WidgetCustomMessage> for WidgetModel {}
+impl EventEmitter<WidgetModelUpdated> for WidgetModel {}
+
+impl WidgetModel {
+    fn new(comm_id: CommId, state: Map<String, Value>) -> Self {
+        Self { comm_id, state }
+    }
+
+    pub fn model_id(&self) -> &str {
+        &self.comm_id.0
+    }
+
+    /// The `_model_name` of the widget, e.g. `IntSliderModel`.
+    pub fn model_name(&self) -> &str {
+        self.get_str("_model_name").unwrap_or_default()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.state.get(key)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.state.get(key).and_then(Value::as_str)
+    }
+
+    pub fn get_f64(&self, key: &str) -> Option<f64> {
+        self.state.get(key).and_then(Value::as_f64)
+    }
+
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.state.get(key).and_then(Value::as_bool)
+    }
+
+    /// Merges state received from the kernel.
+    fn apply_state(&mut self, state: Map<String, Value>, cx: &mut Context<Self>) {
+        self.state.extend(state);
+        cx.emit(WidgetModelUpdated);
+        cx.notify();
+    }
+
+    /// Updates a single key from the UI and syncs the change back to the kernel.
+    pub fn set(&mut self, key: &str, value: Value, cx: &mut Context<Self>) {
+        if self.state.get(key) == Some(&value) {
+            return;
+        }
+
+        self.state.insert(key.to_string(), value.clone());
+
+        let mut changed = Map::new();
+        changed.insert(key.to_string(), value);
+        cx.emit(WidgetStateChanged(changed));
+        cx.emit(WidgetModelUpdated);
+        cx.notify();
+    }
+
+    /// Sends a custom message to the kernel side of the widget.
+    pub fn send_custom(&mut self, content: Value, cx: &mut Context<Self>) {
+        cx.emit(WidgetCustomMessage(content));
+    }
+}
+
+/// A message the [`CommManager`] wants delivered to the kernel.
+pub struct OutgoingCommMessage(pub JupyterMessageContent);
+
+/// Tracks the comms opened by a kernel for a single session.
+#[derive(Default)]
+pub struct CommManager {
+    models: HashMap<String, Entity<WidgetModel>>,
+    model_subscriptions: HashMap<String, [Subscription; 2]>,
+}
+
+impl EventEmitter<OutgoingCommMessage> for CommManager {}
+
+impl CommManager {
+    pub fn model(&self, model_id: &str) -> Option<Entity<WidgetModel>> {
+        self.models.get(model_id).cloned()
+    }
+
+    /// Resolves an `IPY_MODEL_<id>` reference found in another model's state.
+    pub fn resolve_reference(&self, value: &Value) -> Option<Entity<WidgetModel>> {
+        let model_id = value.as_str()?.strip_prefix(MODEL_REFERENCE_PREFIX)?;
+        self.model(model_id)
+    }
+
+    /// Handles a comm message from the kernel. Returns `false` for any
+    /// message that isn't comm-related so the caller can keep routing it.
+    pub fn handle_message(
+        &mut self,
+        content: &JupyterMessageContent,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        match content {
+            JupyterMessageContent::CommOpen(open) => self.open(open, cx),
+            JupyterMessageContent::CommMsg(msg) => self.receive(msg, cx),
+            JupyterMessageContent::CommClose(close) => self.close(&close.comm_id, cx),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Drops every model, e.g. after the kernel restarted.
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        self.models.clear();
+        self.model_subscriptions.clear();
+        cx.notify();
+    }
+
+    fn open(&mut self, open: &CommOpen, cx: &mut Context<Self>) {
+        if open.target_name != WIDGET_TARGET_NAME {
+            // The protocol asks us to close comms for targets we don't know about,
+            // so the kernel doesn't keep waiting on a counterpart that never shows up.
+            cx.emit(OutgoingCommMessage(JupyterMessageContent::CommClose(
+                CommClose {
+                    comm_id: open.comm_id.clone(),
+                    data: Map::new(),
+                },
+            )));
+            return;
+        }
+
+        let state = open
+            .data
+            .get("state")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+
+        let model_id = open.comm_id.0.clone();
+        let model = cx.new(|_| WidgetModel::new(open.comm_id.clone(), state));
+
+        let comm_id = open.comm_id.clone();
+        let state_subscription =
+            cx.subscribe(&model, move |_, _, event: &WidgetStateChanged, cx| {
+                cx.emit(OutgoingCommMessage(JupyterMessageContent::CommMsg(
+                    CommMsg {
+                        comm_id: comm_id.clone(),
+                        data: update_data(event.0.clone()),
+                    },
+                )));
+            });
+
+        let comm_id = open.comm_id.clone();
+        let custom_subscription =
+            cx.subscribe(&model, move |_, _, event: &WidgetCustomMessage, cx| {
+                let mut data = Map::new();
+                data.insert("method".into(), json!("custom"));
+                data.insert("content".into(), event.0.clone());
+                cx.emit(OutgoingCommMessage(JupyterMessageContent::CommMsg(
+                    CommMsg {
+                        comm_id: comm_id.clone(),
+                        data,
+                    },
+                )));
+            });
+
+        self.models.insert(model_id.clone(), model);
+        self.model_subscriptions
+            .insert(model_id, [state_subscription, custom_subscription]);
+        cx.notify();
+    }
+
+    fn receive(&mut self, msg: &CommMsg, cx: &mut Context<Self>) {
+        let Some(model) = self.models.get(&msg.comm_id.0) else {
+            return;
+        };
+
+        match msg.data.get("method").and_then(Value::as_str) {
+            // `echo_update` is sent by ipywidgets 8 to acknowledge our own updates,
+            // applying it again is harmless and keeps us consistent with the kernel.
+            Some("update") | Some("echo_update") => {
+                let Some(state) = msg.data.get("state").and_then(Value::as_object) else {
+                    return;
+                };
+                let state = state.clone();
+                model.update(cx, |model, cx| model.apply_state(state, cx));
+            }
+            Some(method) => {
+                log::debug!("ignoring unsupported widget comm method {method:?}");
+            }
+            None => {}
+        }
+    }
+
+    fn close(&mut self, comm_id: &CommId, cx: &mut Context<Self>) {
+        self.models.remove(&comm_id.0);
+        self.model_subscriptions.remove(&comm_id.0);
+        cx.notify();
+    }
+}
+
+fn update_data(state: Map<String, Value>) -> Map<String, Value> {
+    let mut data = Map::new();
+    data.insert("method".into(), json!("update"));
+    data.insert("state".into(), Value::Object(state));
+    data.insert("buffer_paths".into(), json!([]));
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use std::sync::{Arc, Mutex};
+
+    fn widget_open(comm_id: &str, state: Value) -> JupyterMessageContent {
+        let mut data = Map::new();
+        data.insert("state".into(), state);
+        data.insert("buffer_paths".into(), json!([]));
+        JupyterMessageContent::CommOpen(CommOpen {
+            comm_id: CommId(comm_id.to_string()),
+            target_name: WIDGET_TARGET_NAME.to_string(),
+            data,
+        })
+    }
+
+    fn collect_outgoing(
+        manager: &Entity<CommManager>,
+        cx: &mut TestAppContext,
+    ) -> Arc<Mutex<Vec<JupyterMessageContent>>> {
+        let outgoing = Arc::new(Mutex::new(Vec::new()));
+        cx.update(|cx| {
+            let outgoing = outgoing.clone();
+            cx.subscribe(manager, move |_, event: &OutgoingCommMessage, _| {
+                outgoing.lock().unwrap().push(event.0.clone());
+            })
+            .detach();
+        });
+        outgoing
+    }
+
+    #[gpui::test]
+    fn test_widget_comm_lifecycle(cx: &mut TestAppContext) {
+        let manager = cx.new(|_| CommManager::default());
+
+        manager.update(cx, |manager, cx| {
+            assert!(manager.handle_message(
+                &widget_open(
+                    "slider",
+                    json!({ "_model_name": "IntSliderModel", "value": 3, "max": 10 })
+                ),
+                cx,
+            ));
+        });
+
+        let model = manager.read_with(cx, |manager, _| manager.model("slider").unwrap());
+        model.read_with(cx, |model, _| {
+            assert_eq!(model.model_name(), "IntSliderModel");
+            assert_eq!(model.get_f64("value"), Some(3.0));
+        });
+
+        manager.update(cx, |manager, cx| {
+            let mut data = Map::new();
+            data.insert("method".into(), json!("update"));
+            data.insert("state".into(), json!({ "value": 7 }));
+            manager.handle_message(
+                &JupyterMessageContent::CommMsg(CommMsg {
+                    comm_id: CommId("slider".into()),
+                    data,
+                }),
+                cx,
+            );
+        });
+        model.read_with(cx, |model, _| {
+            assert_eq!(model.get_f64("value"), Some(7.0));
+            assert_eq!(model.get_f64("max"), Some(10.0));
+        });
+
+        manager.update(cx, |manager, cx| {
+            manager.handle_message(
+                &JupyterMessageContent::CommClose(CommClose {
+                    comm_id: CommId("slider".into()),
+                    data: Map::new(),
+                }),
+                cx,
+            );
+            assert!(manager.model("slider").is_none());
+        });
+    }
+
+    #[gpui::test]
+    fn test_widget_state_is_synced_to_kernel(cx: &mut TestAppContext) {
+        let manager = cx.new(|_| CommManager::default());
+        let outgoing = collect_outgoing(&manager, cx);
+
+        manager.update(cx, |manager, cx| {
+            manager.handle_message(
+                &widget_open(
+                    "checkbox",
+                    json!({ "_model_name": "CheckboxModel", "value": false }),
+                ),
+                cx,
+            );
+        });
+        let model = manager.read_with(cx, |manager, _| manager.model("checkbox").unwrap());
+
+        model.update(cx, |model, cx| model.set("value", json!(true), cx));
+        // Setting the same value again must not produce another message.
+        model.update(cx, |model, cx| model.set("value", json!(true), cx));
+
+        let outgoing = outgoing.lock().unwrap();
+        assert_eq!(outgoing.len(), 1);
+        match &outgoing[0] {
+            JupyterMessageContent::CommMsg(msg) => {
+                assert_eq!(msg.comm_id.0, "checkbox");
+                assert_eq!(msg.data.get("method"), Some(&json!("update")));
+                assert_eq!(msg.data.get("state"), Some(&json!({ "value": true })));
+            }
+            other => panic!("expected comm_msg, got {}", other.message_type()),
+        }
+    }
+
+    #[gpui::test]
+    fn test_unknown_comm_target_is_closed(cx: &mut TestAppContext) {
+        let manager = cx.new(|_| CommManager::default());
+        let outgoing = collect_outgoing(&manager, cx);
+
+        manager.update(cx, |manager, cx| {
+            manager.handle_message(
+                &JupyterMessageContent::CommOpen(CommOpen {
+                    comm_id: CommId("other".into()),
+                    target_name: "some.other.target".into(),
+                    data: Map::new(),
+                }),
+                cx,
+            );
+            assert!(manager.model("other").is_none());
+        });
+
+        let outgoing = outgoing.lock().unwrap();
+        assert!(matches!(
+            outgoing.as_slice(),
+            [JupyterMessageContent::CommClose(close)] if close.comm_id.0 == "other"
+        ));
+    }
+
+    #[gpui::test]
+    fn test_resolve_model_reference(cx: &mut TestAppContext) {
+        let manager = cx.new(|_| CommManager::default());
+        manager.update(cx, |manager, cx| {
+            manager.handle_message(
+                &widget_open("bar", json!({ "_model_name": "FloatProgressModel" })),
+                cx,
+            );
+            assert!(manager.resolve_reference(&json!("IPY_MODEL_bar")).is_some());
+            assert!(manager.resolve_reference(&json!("bar")).is_none());
+            assert!(manager.resolve_reference(&json!(1)).is_none());
+        });
+    }
+}
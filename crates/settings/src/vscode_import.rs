@@ -197,6 +197,7 @@ impl VsCodeSettings {
             markdown_preview: None,
             journal: None,
             language_models: None,
+            large_file_viewer: None,
             line_indicator_format: None,
             log: None,
             node: self.node_binary_settings(),
@@ -215,6 +216,7 @@ impl VsCodeSettings {
             repl: None,
             server_url: None,
             session: None,
+            spell_check: None,
             status_bar: self.status_bar_settings_content(),
             tab_bar: self.tab_bar_settings_content(),
             tabs: self.item_settings_content(),
@@ -325,6 +327,8 @@ impl VsCodeSettings {
             completion_menu_item_kind: None,
             diff_view_style: None,
             minimum_split_diff_width: None,
+            keyboard_macros: None,
+            virtual_space: None,
         }
     }
 
@@ -550,6 +554,7 @@ impl VsCodeSettings {
             }),
             debuggers: None,
             edit_predictions_disabled_in: None,
+            emmet: None,
             enable_language_server: None,
             ensure_final_newline_on_save: self.read_bool("files.insertFinalNewline"),
             line_ending: self.read_enum("files.eol", |s| match s {
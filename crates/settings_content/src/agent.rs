@@ -166,6 +166,56 @@ impl JsonSchema for AutoCompactThreshold {
     }
 }
 
+#[with_fallible_options]
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, Default)]
+pub struct AgentBudgetContent {
+    /// The maximum number of tokens the thread may use across all of its
+    /// requests, counting input, output, and cached tokens.
+    ///
+    /// Default: no limit
+    pub max_tokens: Option<u64>,
+    /// The maximum number of tool calls the agent may make in the thread.
+    ///
+    /// Default: no limit
+    pub max_tool_calls: Option<u32>,
+    /// The maximum number of minutes the agent may spend working in the
+    /// thread, summed across turns.
+    ///
+    /// Default: no limit
+    pub max_minutes: Option<u64>,
+    /// The maximum estimated cost of the thread in US dollars, based on
+    /// `model_prices`. Requests to models without a price don't count.
+    ///
+    /// Default: no limit
+    pub max_cost: Option<f64>,
+    /// The percentage of any limit at which the agent panel warns that the
+    /// thread is running out of budget.
+    ///
+    /// Default: 80
+    pub warn_at_percent: Option<u8>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct LanguageModelPrice {
+    pub provider: Option<LanguageModelProviderSetting>,
+    pub model: Option<String>,
+    /// The price of a million input tokens, in US dollars.
+    pub input: Option<f64>,
+    /// The price of a million output tokens, in US dollars.
+    pub output: Option<f64>,
+    /// The price of writing a million input tokens to the prompt cache, in US
+    /// dollars.
+    ///
+    /// Default: the input price
+    pub cache_write: Option<f64>,
+    /// The price of reading a million input tokens from the prompt cache, in
+    /// US dollars.
+    ///
+    /// Default: the input price
+    pub cache_read: Option<f64>,
+}
+
 #[with_fallible_options]
 #[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, Default)]
 pub struct AutoCompactSettingsContent {
@@ -296,6 +346,18 @@ pub struct AgentSettingsContent {
     /// earlier messages to free up room in the model's context window once the
     /// context grows too large.
     pub auto_compact: Option<AutoCompactSettingsContent>,
+    /// Limits on what a single thread, including a subagent's thread, may
+    /// spend. The agent stops once any limit is reached. Profiles can
+    /// override these limits with their own `budget`.
+    pub budget: Option<AgentBudgetContent>,
+    /// Prices of language models, used to estimate what a thread costs for
+    /// `budget.max_cost`. When estimating the cost of a request, prices are
+    /// taken from the last entry in this list that matches the model's
+    /// provider and name. In each entry, both provider and model are optional.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub model_prices: Vec<LanguageModelPrice>,
     /// Whether to show thumb buttons for feedback in the agent panel.
     ///
     /// Default: true
@@ -357,6 +419,11 @@ pub struct AgentSettingsContent {
     /// These are populated when choosing "Allow always" from a sandbox
     /// escalation prompt.
     pub sandbox_permissions: Option<SandboxPermissionsContent>,
+
+    /// Shell commands to run at points in the agent's lifecycle: before and
+    /// after each tool call, and when the agent stops. Each hook receives a
+    /// JSON description of the event on stdin.
+    pub hooks: Option<AgentHooksContent>,
 }
 
 impl AgentSettingsContent {
@@ -531,6 +598,9 @@ pub struct AgentProfileContent {
     pub context_servers: IndexMap<Arc<str>, ContextServerPresetContent>,
     /// The default language model selected when using this profile.
     pub default_model: Option<LanguageModelSelection>,
+    /// Limits on what a thread using this profile may spend. Limits set here
+    /// replace the ones in `agent.budget`.
+    pub budget: Option<AgentBudgetContent>,
 }
 
 #[with_fallible_options]
@@ -957,6 +1027,44 @@ pub struct SandboxPermissionsContent {
     pub warn_ntfs_grants: Option<bool>,
 }
 
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentHooksContent {
+    /// Hooks to run before a tool call. A hook can veto the call by exiting
+    /// with a non-zero status, in which case its output is reported to the
+    /// model, or rewrite the tool's input by printing
+    /// `{"tool_input": {...}}` to stdout.
+    /// Default: []
+    pub pre_tool_call: Option<Vec<AgentHookContent>>,
+
+    /// Hooks to run after a tool call. Their output, if any, is appended to
+    /// the tool's result.
+    /// Default: []
+    pub post_tool_call: Option<Vec<AgentHookContent>>,
+
+    /// Hooks to run when the agent stops after a turn.
+    /// Default: []
+    pub stop: Option<Vec<AgentHookContent>>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentHookContent {
+    /// The shell command to run, in the project's first worktree.
+    #[serde(default)]
+    pub command: String,
+
+    /// A regex matched against the tool name (e.g. `edit_file|terminal`).
+    /// Ignored by `stop` hooks.
+    /// Default: matches all tools
+    pub tools: Option<String>,
+
+    /// How many seconds to let the command run before it is killed. A
+    /// `pre_tool_call` hook that times out vetoes the call.
+    /// Default: 60
+    pub timeout: Option<u64>,
+}
+
 #[with_fallible_options]
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
 pub struct ToolPermissionsContent {
@@ -1,5 +1,6 @@
 use std::fmt::Display;
 use std::num;
+use std::ops::Range;
 
 use collections::HashMap;
 use schemars::JsonSchema;
@@ -7,7 +8,8 @@ use serde::{Deserialize, Serialize};
 use settings_macros::{MergeFrom, with_fallible_options};
 
 use crate::{
-    DelayMs, DiagnosticSeverityContent, ShowScrollbar, serialize_f32_with_two_decimal_places,
+    ActionName, DelayMs, DiagnosticSeverityContent, ShowScrollbar,
+    serialize_f32_with_two_decimal_places,
 };
 
 #[with_fallible_options]
@@ -38,6 +40,11 @@ pub struct EditorSettingsContent {
     ///
     /// Default: true
     pub rounded_selection: Option<bool>,
+    /// Whether cursors and column selections can extend past the end of shorter
+    /// lines, padding those lines with spaces when typing or pasting.
+    ///
+    /// Default: false
+    pub virtual_space: Option<bool>,
     /// The debounce delay before querying highlights from the language
     /// server based on the current cursor location.
     ///
@@ -279,6 +286,42 @@ pub struct EditorSettingsContent {
     ///
     /// Default: 100
     pub minimum_split_diff_width: Option<f32>,
+
+    /// Keyboard macros that can be replayed with `editor::ReplayMacro`, by
+    /// name. Each step is an action name, an action with its arguments, or
+    /// text to type, for example:
+    ///
+    /// ```json
+    /// "keyboard_macros": {
+    ///   "append-todo": [
+    ///     ["editor::MoveToEndOfLine", { "stop_at_soft_wraps": false }],
+    ///     { "text": " // TODO" }
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Default: {}
+    pub keyboard_macros: Option<HashMap<String, Vec<KeyboardMacroStep>>>,
+}
+
+/// A step of a keyboard macro saved in the `keyboard_macros` setting.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+#[serde(untagged)]
+pub enum KeyboardMacroStep {
+    /// Text typed into the editor.
+    Text {
+        text: String,
+        /// The range of text to replace with the typed text, in UTF-16 code
+        /// units relative to each cursor.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        replace_utf16_range: Option<Range<isize>>,
+    },
+    /// The name of an action that takes no arguments, for example,
+    /// `"editor::MoveDown"`.
+    Action(ActionName),
+    /// An action and its arguments, for example,
+    /// `["editor::MoveToBeginningOfLine", { "stop_at_indent": true }]`.
+    ActionWithArguments(ActionName, serde_json::Value),
 }
 
 #[derive(
@@ -1016,6 +1059,28 @@ pub struct JupyterContent {
     ///
     /// Default: `{}`
     pub kernel_selections: Option<HashMap<String, String>>,
+
+    /// Jupyter servers whose kernels can be launched or reattached to.
+    ///
+    /// Default: `[]`
+    pub servers: Option<Vec<JupyterServerContent>>,
+}
+
+/// A Jupyter server that kernels can be launched on or reattached to.
+#[with_fallible_options]
+#[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom)]
+pub struct JupyterServerContent {
+    /// Name to use for this server in the UI.
+    pub name: String,
+    /// Base URL of the server, e.g. `http://localhost:8888`.
+    pub url: String,
+    /// Token used to authenticate with the server.
+    pub token: Option<String>,
+    /// Whether to reach the server through an SSH tunnel of the project's
+    /// remote connection. The URL is then resolved on the remote host.
+    ///
+    /// Default: false
+    pub ssh_tunnel: Option<bool>,
 }
 
 /// Whether to allow drag and drop text selection in buffer.
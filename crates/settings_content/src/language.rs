@@ -690,6 +690,12 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub linked_edits: Option<bool>,
+    /// Whether to expand Emmet abbreviations, such as `ul>li*3`, in HTML, JSX
+    /// and CSS, both from the completions menu and with
+    /// `editor::ExpandEmmetAbbreviation`.
+    ///
+    /// Default: true
+    pub emmet: Option<bool>,
     /// Controls automatic indentation behavior when typing.
     ///
     /// - "syntax_aware": Adjusts indentation based on syntax context (default)
@@ -1107,6 +1113,10 @@ pub enum Formatter {
     },
     /// Files should be formatted using a code action executed by language servers.
     CodeAction(String),
+    /// Re-indent code using the language's tree-sitter indentation queries, trimming
+    /// trailing whitespace and collapsing runs of blank lines. Useful for languages
+    /// without a language server or external formatter.
+    TreeSitter,
     /// Format code using a language server.
     #[serde(untagged)]
     LanguageServer(LanguageServerFormatterSpecifier),
@@ -1309,6 +1319,12 @@ mod test {
             settings.formatter,
             Some(FormatterList::Single(Formatter::None))
         );
+        let raw = "{\"formatter\": \"tree_sitter\"}";
+        let settings: LanguageSettingsContent = serde_json::from_str(raw).unwrap();
+        assert_eq!(
+            settings.formatter,
+            Some(FormatterList::Single(Formatter::TreeSitter))
+        );
         let raw = "{\"formatter\": \"language_server\"}";
         let settings: LanguageSettingsContent = serde_json::from_str(raw).unwrap();
         assert_eq!(
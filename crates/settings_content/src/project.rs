@@ -916,7 +916,8 @@ pub enum DiagnosticSeverityContent {
 pub struct GitHostingProviderConfig {
     /// The type of the provider.
     ///
-    /// Must be one of `github`, `gitlab`, `bitbucket`, `gitea`, `forgejo`, `sourcehut`, or `tangled`.
+    /// Must be one of `github`, `gitlab`, `bitbucket`, `gitea`, `forgejo`, `sourcehut`, `tangled`,
+    /// or `url_template`.
     pub provider: GitHostingProviderKind,
 
     /// The base URL for the provider (e.g., "https://code.corp.big.com").
@@ -924,6 +925,9 @@ pub struct GitHostingProviderConfig {
 
     /// The display name for the provider (e.g., "BigCorp GitHub").
     pub name: String,
+
+    /// The URLs of a `url_template` provider.
+    pub url_templates: Option<GitHostingProviderUrlTemplates>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
@@ -936,6 +940,53 @@ pub enum GitHostingProviderKind {
     Forgejo,
     SourceHut,
     Tangled,
+    UrlTemplate,
+}
+
+/// URL templates for a Git hosting provider without built-in support.
+///
+/// Every template may reference `{base_url}`, `{owner}`, `{repo}`, and `{project}`, which is
+/// the owner and repository joined by `/`. The owner is everything in the remote's path before
+/// the repository name, and may itself contain `/`.
+#[with_fallible_options]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct GitHostingProviderUrlTemplates {
+    /// The URL of a commit, which may reference `{sha}`.
+    ///
+    /// Example: "{base_url}/{project}/commit/{sha}"
+    pub commit: String,
+
+    /// The URL of a file at a commit, which may reference `{sha}` and `{path}`.
+    ///
+    /// Example: "{base_url}/{project}/blob/{sha}/{path}"
+    pub file: String,
+
+    /// The fragment appended to a file URL to select a line, which may reference `{line}`.
+    ///
+    /// Default: "L{line}"
+    pub line: Option<String>,
+
+    /// The fragment appended to a file URL to select a range of lines, which may reference
+    /// `{start_line}` and `{end_line}`.
+    ///
+    /// Default: "L{start_line}-L{end_line}"
+    pub line_range: Option<String>,
+
+    /// The URL of a pull request, which may reference `{number}`.
+    ///
+    /// Example: "{base_url}/{project}/pull/{number}"
+    pub pull_request: Option<String>,
+
+    /// A regex that finds the pull request number in a line of a commit message, as its first
+    /// capture group.
+    ///
+    /// Default: "\\(#(\\d+)\\)$"
+    pub pull_request_pattern: Option<String>,
+
+    /// The URL to create a pull request from a branch, which may reference `{branch}`.
+    ///
+    /// Example: "{base_url}/{project}/compare/{branch}"
+    pub create_pull_request: Option<String>,
 }
 
 #[cfg(test)]
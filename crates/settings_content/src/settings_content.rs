@@ -236,6 +236,9 @@ pub struct SettingsContent {
     /// The settings for the image viewer.
     pub image_viewer: Option<ImageViewerSettingsContent>,
 
+    /// The settings for the large file viewer.
+    pub large_file_viewer: Option<LargeFileViewerSettingsContent>,
+
     /// The settings for the markdown preview.
     pub markdown_preview: Option<MarkdownPreviewSettingsContent>,
 
@@ -292,6 +295,8 @@ pub struct SettingsContent {
 
     /// Configuration for session-related features
     pub session: Option<SessionSettingsContent>,
+    /// The settings for spell checking.
+    pub spell_check: Option<SpellCheckSettingsContent>,
     /// Control what info is collected by Zed.
     pub telemetry: Option<TelemetrySettingsContent>,
 
@@ -1250,6 +1255,41 @@ pub struct ImageViewerSettingsContent {
     pub unit: Option<ImageFileSizeUnit>,
 }
 
+/// The settings for the large file viewer.
+#[with_fallible_options]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, Default, PartialEq)]
+pub struct LargeFileViewerSettingsContent {
+    /// The file size, in megabytes, from which files are opened in the
+    /// read-only large file viewer instead of the editor. Set to 0 to always
+    /// open files in the editor.
+    ///
+    /// Default: 100
+    pub threshold_mb: Option<u64>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, Default, PartialEq)]
+pub struct SpellCheckSettingsContent {
+    /// Whether to spell check comments and strings in code, and all of the
+    /// text in prose languages.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The names of the Hunspell dictionaries to check words against, like
+    /// `en_US`. Dictionaries are looked up in Zed's `dictionaries` config
+    /// directory, then among the ones bundled with Zed, then in the system's
+    /// Hunspell directories.
+    ///
+    /// Default: ["en_US", "code"]
+    pub dictionaries: Option<Vec<String>>,
+    /// The languages whose buffers are checked in full, rather than only in
+    /// their comments and strings. Buffers without a language are always
+    /// checked in full.
+    ///
+    /// Default: ["Markdown", "Plain Text", "Git Commit"]
+    pub prose_languages: Option<Vec<String>>,
+}
+
 #[with_fallible_options]
 #[derive(
     Clone,
@@ -1301,7 +1301,7 @@ fn appearance_page() -> SettingsPage {
         ]
     }
 
-    fn cursor_section() -> [SettingsPageItem; 6] {
+    fn cursor_section() -> [SettingsPageItem; 7] {
         [
             SettingsPageItem::SectionHeader("Cursor"),
             SettingsPageItem::SettingItem(SettingItem {
@@ -1318,6 +1318,20 @@ fn appearance_page() -> SettingsPage {
                 metadata: None,
                 files: USER,
             }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Virtual Space",
+                description: "Whether cursors and column selections can extend past the end of shorter lines.",
+                field: Box::new(SettingField {
+                    organization_override: None,
+                    json_path: Some("virtual_space"),
+                    pick: |settings_content| settings_content.editor.virtual_space.as_ref(),
+                    write: |settings_content, value, _| {
+                        settings_content.editor.virtual_space = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
             SettingsPageItem::SettingItem(SettingItem {
                 title: "Cursor Blink",
                 description: "Whether the cursor blinks in the editor.",
@@ -10114,7 +10128,7 @@ fn language_settings_data() -> Box<[SettingsPageItem]> {
         ]
     }
 
-    fn global_only_miscellaneous_sub_section() -> [SettingsPageItem; 3] {
+    fn global_only_miscellaneous_sub_section() -> [SettingsPageItem; 5] {
         [
             SettingsPageItem::SettingItem(SettingItem {
                 title: "Image Viewer",
@@ -10135,6 +10149,47 @@ fn language_settings_data() -> Box<[SettingsPageItem]> {
                 metadata: None,
                 files: USER,
             }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Large File Viewer Threshold",
+                description: "Files of this size in megabytes or larger open in the read-only large file viewer (0 to disable).",
+                field: Box::new(SettingField {
+                    organization_override: None,
+                    json_path: Some("large_file_viewer.threshold_mb"),
+                    pick: |settings_content| {
+                        settings_content
+                            .large_file_viewer
+                            .as_ref()
+                            .and_then(|large_file_viewer| large_file_viewer.threshold_mb.as_ref())
+                    },
+                    write: |settings_content, value, _| {
+                        settings_content
+                            .large_file_viewer
+                            .get_or_insert_default()
+                            .threshold_mb = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Spell Check",
+                description: "Whether to underline misspelled words in comments, strings and prose.",
+                field: Box::new(SettingField {
+                    organization_override: None,
+                    json_path: Some("spell_check.enabled"),
+                    pick: |settings_content| {
+                        settings_content
+                            .spell_check
+                            .as_ref()
+                            .and_then(|spell_check| spell_check.enabled.as_ref())
+                    },
+                    write: |settings_content, value, _| {
+                        settings_content.spell_check.get_or_insert_default().enabled = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
             SettingsPageItem::DynamicItem(DynamicItem {
                 discriminant: SettingItem {
                     files: USER,
@@ -26,6 +26,30 @@ const TOOLS: &[ToolInfo] = &[
         description: "Commands executed in the terminal",
         regex_explanation: "Patterns are matched against each command in the input. Commands chained with &&, ||, ;, or pipes are split and checked individually.",
     },
+    ToolInfo {
+        id: "run_task",
+        name: "Run Task",
+        description: "Project tasks run in the terminal panel",
+        regex_explanation: "Patterns are matched against the task's full command line.",
+    },
+    ToolInfo {
+        id: "debug_start",
+        name: "Start Debugging",
+        description: "Debug scenarios started in the debug panel",
+        regex_explanation: "Patterns are matched against the debug scenario's label, its build command, and the program it launches. The terminal's deny and confirm patterns also apply.",
+    },
+    ToolInfo {
+        id: "debug_breakpoint",
+        name: "Debug Breakpoint",
+        description: "Breakpoints set or cleared by the agent",
+        regex_explanation: "Patterns are matched against the breakpoint's location (path:line) and any condition or log message.",
+    },
+    ToolInfo {
+        id: "debug_inspect",
+        name: "Debug Inspect",
+        description: "Expressions evaluated in a paused debuggee",
+        regex_explanation: "Patterns are matched against the expression being evaluated.",
+    },
     ToolInfo {
         id: "edit_file",
         name: "Edit File",
@@ -303,6 +327,10 @@ fn get_tool_render_fn(
 ) -> fn(&SettingsWindow, &ScrollHandle, &mut Window, &mut Context<SettingsWindow>) -> AnyElement {
     match tool_id {
         "terminal" => render_terminal_tool_config,
+        "run_task" => render_run_task_tool_config,
+        "debug_start" => render_debug_start_tool_config,
+        "debug_breakpoint" => render_debug_breakpoint_tool_config,
+        "debug_inspect" => render_debug_inspect_tool_config,
         "edit_file" => render_edit_file_tool_config,
         "write_file" => render_write_file_tool_config,
         "delete_path" => render_delete_path_tool_config,
@@ -1381,6 +1409,10 @@ macro_rules! tool_config_page_fn {
 }
 
 tool_config_page_fn!(render_terminal_tool_config, "terminal");
+tool_config_page_fn!(render_run_task_tool_config, "run_task");
+tool_config_page_fn!(render_debug_start_tool_config, "debug_start");
+tool_config_page_fn!(render_debug_breakpoint_tool_config, "debug_breakpoint");
+tool_config_page_fn!(render_debug_inspect_tool_config, "debug_inspect");
 tool_config_page_fn!(render_edit_file_tool_config, "edit_file");
 tool_config_page_fn!(render_write_file_tool_config, "write_file");
 tool_config_page_fn!(render_delete_path_tool_config, "delete_path");
@@ -1403,6 +1435,7 @@ mod tests {
         //   2. Add it to this list with a comment explaining why it's excluded.
         const EXCLUDED_TOOLS: &[&str] = &[
             // Read-only / low-risk tools that don't call decide_permission_from_settings
+            "add_review_comment",
             "apply_code_action",
             // Interactive prompt: always asks the user via prompt_for_decision,
             // so it has no settings-driven permission rules.
@@ -1426,6 +1459,9 @@ mod tests {
             // tool calls inside the spawned thread, not the spawning itself.
             "create_thread",
             "spawn_agent",
+            // Only steps, resumes, or stops sessions this thread started with
+            // debug_start, which asks for permission itself.
+            "debug_control",
         ];
 
         let tool_info_ids: Vec<&str> = TOOLS.iter().map(|t| t.id).collect();
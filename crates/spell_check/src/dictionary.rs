@@ -0,0 +1,560 @@
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashMap;
+
+type Flag = u32;
+
+/// A Hunspell dictionary, made of a `.aff` file with the rules words can be
+/// derived with, and a `.dic` file with the stems those rules apply to.
+///
+/// This supports prefixes, suffixes and their cross products, replacement
+/// tables and flag aliases, but not compounding or morphology, which the
+/// dictionaries of most Latin-script languages don't need to check words.
+#[derive(Default)]
+pub struct Dictionary {
+    words: HashMap<String, Vec<Flag>>,
+    prefixes: Vec<Affix>,
+    /// Suffixes by the text they add to stems.
+    suffixes: HashMap<String, Vec<Affix>>,
+    try_chars: Vec<char>,
+    replacements: Vec<(String, String)>,
+    forbidden_flag: Option<Flag>,
+    no_suggest_flag: Option<Flag>,
+    need_affix_flag: Option<Flag>,
+    only_in_compound_flag: Option<Flag>,
+    keep_case_flag: Option<Flag>,
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+enum FlagMode {
+    #[default]
+    Char,
+    Long,
+    Num,
+}
+
+struct Affix {
+    flag: Flag,
+    strip: String,
+    add: String,
+    condition: Vec<ConditionPart>,
+    cross_product: bool,
+}
+
+enum ConditionPart {
+    Any,
+    Char(char),
+    Set { chars: Vec<char>, negated: bool },
+}
+
+impl ConditionPart {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            ConditionPart::Any => true,
+            ConditionPart::Char(expected) => c == *expected,
+            ConditionPart::Set { chars, negated } => chars.contains(&c) != *negated,
+        }
+    }
+}
+
+#[derive(PartialEq)]
+enum Case {
+    Lower,
+    Capitalized,
+    Upper,
+    Mixed,
+}
+
+fn case(word: &str) -> Case {
+    let mut chars = word.chars().filter(|c| c.is_alphabetic());
+    let Some(first) = chars.next() else {
+        return Case::Lower;
+    };
+    let (mut has_upper, mut has_lower) = (false, false);
+    for c in chars {
+        has_upper |= c.is_uppercase();
+        has_lower |= c.is_lowercase();
+    }
+    match (first.is_uppercase(), has_upper, has_lower) {
+        (false, false, _) => Case::Lower,
+        (true, false, _) => Case::Capitalized,
+        (true, true, false) => Case::Upper,
+        _ => Case::Mixed,
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+impl Dictionary {
+    pub fn parse(aff: &[u8], dic: &[u8]) -> Result<Self> {
+        let encoding = aff
+            .split(|byte| *byte == b'\n')
+            .find_map(|line| {
+                let line = std::str::from_utf8(line).ok()?.trim();
+                line.strip_prefix("SET ")
+                    .map(|encoding| encoding.trim().to_string())
+            })
+            .unwrap_or_else(|| "ISO8859-1".to_string());
+        let aff = decode(aff, &encoding)?;
+        let dic = decode(dic, &encoding)?;
+
+        let mut dictionary = Self::default();
+        let mut flag_mode = FlagMode::default();
+        let mut aliases = Vec::new();
+        let mut alias_count_seen = false;
+        let mut replacement_count_seen = false;
+        // The remaining number of rules and whether they can be combined,
+        // by affix flag.
+        let mut affix_headers = HashMap::<(bool, Flag), (usize, bool)>::default();
+
+        for line in aff.lines() {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let Some(keyword) = fields.first() else {
+                continue;
+            };
+            let value = fields.get(1).copied().unwrap_or_default();
+            match *keyword {
+                "FLAG" => {
+                    flag_mode = match value {
+                        "long" => FlagMode::Long,
+                        "num" => FlagMode::Num,
+                        _ => FlagMode::Char,
+                    }
+                }
+                "TRY" => dictionary.try_chars = value.chars().collect(),
+                "REP" if !replacement_count_seen => replacement_count_seen = true,
+                "REP" => {
+                    if let Some(to) = fields.get(2) {
+                        dictionary
+                            .replacements
+                            .push((value.replace('_', " "), to.replace('_', " ")));
+                    }
+                }
+                "AF" if !alias_count_seen => alias_count_seen = true,
+                "AF" => aliases.push(parse_flags(value, flag_mode)),
+                "FORBIDDENWORD" => dictionary.forbidden_flag = first_flag(value, flag_mode),
+                "NOSUGGEST" => dictionary.no_suggest_flag = first_flag(value, flag_mode),
+                "NEEDAFFIX" | "PSEUDOROOT" => {
+                    dictionary.need_affix_flag = first_flag(value, flag_mode)
+                }
+                "ONLYINCOMPOUND" => dictionary.only_in_compound_flag = first_flag(value, flag_mode),
+                "KEEPCASE" => dictionary.keep_case_flag = first_flag(value, flag_mode),
+                "PFX" | "SFX" => {
+                    let is_prefix = *keyword == "PFX";
+                    let Some(flag) = first_flag(value, flag_mode) else {
+                        continue;
+                    };
+                    let header = affix_headers.entry((is_prefix, flag)).or_default();
+                    if header.0 == 0 {
+                        header.0 = fields
+                            .get(3)
+                            .and_then(|count| count.parse().ok())
+                            .unwrap_or(0);
+                        header.1 = fields.get(2) == Some(&"Y");
+                        continue;
+                    }
+                    header.0 -= 1;
+
+                    let (Some(strip), Some(add)) = (fields.get(2), fields.get(3)) else {
+                        continue;
+                    };
+                    let add = add.split('/').next().unwrap_or_default();
+                    let affix = Affix {
+                        flag,
+                        strip: if *strip == "0" { "" } else { strip }.to_string(),
+                        add: if add == "0" { "" } else { add }.to_string(),
+                        condition: parse_condition(fields.get(4).copied().unwrap_or(".")),
+                        cross_product: header.1,
+                    };
+                    if is_prefix {
+                        dictionary.prefixes.push(affix);
+                    } else {
+                        dictionary
+                            .suffixes
+                            .entry(affix.add.clone())
+                            .or_default()
+                            .push(affix);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut lines = dic.lines();
+        lines.next().context("empty dictionary")?;
+        for line in lines {
+            // Lines may continue with morphological fields, after whitespace.
+            let Some(entry) = line.split_whitespace().next() else {
+                continue;
+            };
+            let (word, flags) = split_entry(entry);
+            let flags = match flags {
+                Some(flags) if !aliases.is_empty() => flags
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|ix| aliases.get(ix.checked_sub(1)?))
+                    .cloned()
+                    .unwrap_or_default(),
+                Some(flags) => parse_flags(flags, flag_mode),
+                None => Vec::new(),
+            };
+            dictionary.words.entry(word).or_default().extend(flags);
+        }
+
+        Ok(dictionary)
+    }
+
+    /// Returns whether `word` is spelled correctly.
+    pub fn check(&self, word: &str) -> bool {
+        if self.check_form(word, true) {
+            return true;
+        }
+        match case(word) {
+            Case::Capitalized => self.check_form(&word.to_lowercase(), false),
+            Case::Upper => {
+                let lowercase = word.to_lowercase();
+                self.check_form(&lowercase, false)
+                    || self.check_form(&capitalize(&lowercase), false)
+            }
+            Case::Lower | Case::Mixed => false,
+        }
+    }
+
+    /// Returns up to `limit` correctly spelled words that `word` may be a
+    /// misspelling of, most likely first.
+    pub fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        let word_case = case(word);
+        let word = match word_case {
+            Case::Capitalized | Case::Upper => word.to_lowercase(),
+            Case::Lower | Case::Mixed => word.to_string(),
+        };
+
+        let mut candidates = Vec::new();
+        for (from, to) in &self.replacements {
+            for (ix, _) in word.match_indices(from.as_str()) {
+                candidates.push(format!("{}{to}{}", &word[..ix], &word[ix + from.len()..]));
+            }
+        }
+        if word_case == Case::Lower {
+            candidates.push(capitalize(&word));
+        }
+
+        let chars = word.chars().collect::<Vec<_>>();
+        let try_chars = if self.try_chars.is_empty() {
+            ('a'..='z').collect()
+        } else {
+            self.try_chars.clone()
+        };
+        for ix in 0..chars.len().saturating_sub(1) {
+            let mut swapped = chars.clone();
+            swapped.swap(ix, ix + 1);
+            candidates.push(swapped.into_iter().collect());
+        }
+        for ix in 0..chars.len() {
+            let mut removed = chars.clone();
+            removed.remove(ix);
+            candidates.push(removed.into_iter().collect());
+        }
+        for ix in 0..chars.len() {
+            for c in &try_chars {
+                if *c != chars[ix] {
+                    let mut replaced = chars.clone();
+                    replaced[ix] = *c;
+                    candidates.push(replaced.into_iter().collect());
+                }
+            }
+        }
+        for ix in 0..=chars.len() {
+            for c in &try_chars {
+                let mut inserted = chars.clone();
+                inserted.insert(ix, *c);
+                candidates.push(inserted.into_iter().collect());
+            }
+        }
+        for ix in 1..chars.len() {
+            let (left, right) = chars.split_at(ix);
+            let (left, right) = (
+                left.iter().collect::<String>(),
+                right.iter().collect::<String>(),
+            );
+            if self.check(&left) && self.check(&right) {
+                candidates.push(format!("{left} {right}"));
+            }
+        }
+
+        let mut suggestions = Vec::new();
+        for candidate in candidates {
+            if suggestions.len() == limit {
+                break;
+            }
+            if candidate.is_empty()
+                || suggestions.contains(&candidate)
+                || (!candidate.contains(' ') && !self.check_suggestable(&candidate))
+            {
+                continue;
+            }
+            suggestions.push(candidate);
+        }
+
+        match word_case {
+            Case::Capitalized => suggestions.iter().map(|word| capitalize(word)).collect(),
+            Case::Upper => suggestions.iter().map(|word| word.to_uppercase()).collect(),
+            Case::Lower | Case::Mixed => suggestions,
+        }
+    }
+
+    fn check_suggestable(&self, word: &str) -> bool {
+        let no_suggest = self.no_suggest_flag.is_some_and(|no_suggest| {
+            self.words
+                .get(word)
+                .is_some_and(|flags| flags.contains(&no_suggest))
+        });
+        !no_suggest && self.check(word)
+    }
+
+    fn check_form(&self, word: &str, exact_case: bool) -> bool {
+        if let Some(flags) = self.words.get(word) {
+            let has_flag = |flag: Option<Flag>| flag.is_some_and(|flag| flags.contains(&flag));
+            if has_flag(self.forbidden_flag) || (!exact_case && has_flag(self.keep_case_flag)) {
+                return false;
+            }
+            if !has_flag(self.need_affix_flag) && !has_flag(self.only_in_compound_flag) {
+                return true;
+            }
+        }
+
+        for (ix, _) in word.char_indices().skip(1).chain([(word.len(), ' ')]) {
+            let Some(suffixes) = self.suffixes.get(&word[ix..]) else {
+                continue;
+            };
+            for suffix in suffixes {
+                let stem = format!("{}{}", &word[..ix], suffix.strip);
+                if !condition_matches_end(&suffix.condition, &stem) {
+                    continue;
+                }
+                if self.stem_has_flags(&stem, &[suffix.flag], exact_case) {
+                    return true;
+                }
+                if suffix.cross_product {
+                    for prefix in self.prefixes.iter().filter(|prefix| prefix.cross_product) {
+                        if let Some(stem) = self.strip_prefix(prefix, &stem)
+                            && self.stem_has_flags(&stem, &[suffix.flag, prefix.flag], exact_case)
+                        {
+                            return true;
+                        }
+                    }
+                }
+            }
+        }
+
+        self.prefixes.iter().any(|prefix| {
+            self.strip_prefix(prefix, word)
+                .is_some_and(|stem| self.stem_has_flags(&stem, &[prefix.flag], exact_case))
+        })
+    }
+
+    fn strip_prefix(&self, prefix: &Affix, word: &str) -> Option<String> {
+        let rest = word.strip_prefix(prefix.add.as_str())?;
+        if rest.is_empty() {
+            return None;
+        }
+        let stem = format!("{}{rest}", prefix.strip);
+        condition_matches_start(&prefix.condition, &stem).then_some(stem)
+    }
+
+    fn stem_has_flags(&self, stem: &str, required: &[Flag], exact_case: bool) -> bool {
+        self.words.get(stem).is_some_and(|flags| {
+            required.iter().all(|flag| flags.contains(flag))
+                && !self
+                    .forbidden_flag
+                    .is_some_and(|forbidden| flags.contains(&forbidden))
+                && (exact_case
+                    || !self
+                        .keep_case_flag
+                        .is_some_and(|keep_case| flags.contains(&keep_case)))
+        })
+    }
+}
+
+fn decode(bytes: &[u8], label: &str) -> Result<String> {
+    let label = match label.to_ascii_uppercase().as_str() {
+        // Hunspell spells ISO-8859 encodings without the first dash.
+        label if label.starts_with("ISO8859") => label.replacen("ISO8859", "ISO-8859", 1),
+        label => label.to_string(),
+    };
+    let encoding = encoding_rs::Encoding::for_label(label.as_bytes())
+        .ok_or_else(|| anyhow!("unsupported dictionary encoding {label}"))?;
+    Ok(encoding.decode_with_bom_removal(bytes).0.into_owned())
+}
+
+fn parse_flags(flags: &str, mode: FlagMode) -> Vec<Flag> {
+    match mode {
+        FlagMode::Char => flags.chars().map(|c| c as Flag).collect(),
+        FlagMode::Long => flags
+            .chars()
+            .collect::<Vec<_>>()
+            .chunks(2)
+            .map(|pair| pair.iter().fold(0, |flag, c| (flag << 16) | *c as Flag))
+            .collect(),
+        FlagMode::Num => flags
+            .split(',')
+            .filter_map(|flag| flag.trim().parse().ok())
+            .collect(),
+    }
+}
+
+fn first_flag(flags: &str, mode: FlagMode) -> Option<Flag> {
+    parse_flags(flags, mode).first().copied()
+}
+
+/// Splits a `.dic` entry into its word and its flags, at the first slash
+/// that isn't escaped with a backslash.
+fn split_entry(entry: &str) -> (String, Option<&str>) {
+    let mut word = String::new();
+    let mut chars = entry.char_indices();
+    while let Some((ix, c)) = chars.next() {
+        match c {
+            '\\' => word.extend(chars.next().map(|(_, c)| c)),
+            '/' if ix > 0 => return (word, Some(&entry[ix + 1..])),
+            c => word.push(c),
+        }
+    }
+    (word, None)
+}
+
+fn parse_condition(condition: &str) -> Vec<ConditionPart> {
+    let mut parts = Vec::new();
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        parts.push(match c {
+            '.' => ConditionPart::Any,
+            '[' => {
+                let mut set = chars.by_ref().take_while(|c| *c != ']').collect::<Vec<_>>();
+                let negated = set.first() == Some(&'^');
+                if negated {
+                    set.remove(0);
+                }
+                ConditionPart::Set {
+                    chars: set,
+                    negated,
+                }
+            }
+            c => ConditionPart::Char(c),
+        });
+    }
+    if let [ConditionPart::Any] = parts.as_slice() {
+        parts.clear();
+    }
+    parts
+}
+
+fn condition_matches_start(condition: &[ConditionPart], stem: &str) -> bool {
+    let mut chars = stem.chars();
+    condition
+        .iter()
+        .all(|part| chars.next().is_some_and(|c| part.matches(c)))
+}
+
+fn condition_matches_end(condition: &[ConditionPart], stem: &str) -> bool {
+    let mut chars = stem.chars().rev();
+    condition
+        .iter()
+        .rev()
+        .all(|part| chars.next().is_some_and(|c| part.matches(c)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "
+SET UTF-8
+TRY esianrtolcdugmphbyfvkwzESIANRTOLCDUGMPHBYFVKWZ'
+NOSUGGEST !
+FORBIDDENWORD ?
+
+REP 2
+REP f ph
+REP ph f
+
+PFX U Y 1
+PFX U   0     un         .
+
+SFX S Y 4
+SFX S   y     ies        [^aeiou]y
+SFX S   0     s          [aeiou]y
+SFX S   0     es         [sxzh]
+SFX S   0     s          [^sxzhy]
+
+SFX D Y 2
+SFX D   0     d          e
+SFX D   0     ed         [^e]
+";
+
+    const DIC: &str = "7
+happy/U
+city/S
+box/S
+lock/USD
+phone/S
+damn/!
+irregardless/?
+";
+
+    fn dictionary() -> Dictionary {
+        Dictionary::parse(AFF.as_bytes(), DIC.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_check() {
+        let dictionary = dictionary();
+        for word in [
+            "happy", "unhappy", "cities", "boxes", "locks", "unlocked", "Cities", "BOXES",
+            "phones", "damn",
+        ] {
+            assert!(dictionary.check(word), "{word} should be correct");
+        }
+        for word in [
+            "citys",
+            "boxs",
+            "unbox",
+            "happys",
+            "lockes",
+            "irregardless",
+            "cItY",
+            "fone",
+        ] {
+            assert!(!dictionary.check(word), "{word} should be misspelled");
+        }
+    }
+
+    #[test]
+    fn test_suggest() {
+        let dictionary = dictionary();
+        assert_eq!(dictionary.suggest("citeis", 3), ["cities"]);
+        assert_eq!(dictionary.suggest("Hapy", 3), ["Happy"]);
+        assert_eq!(dictionary.suggest("fones", 3), ["phones"]);
+        assert_eq!(
+            dictionary.suggest("unlockd", 3),
+            ["unlock", "unlocks", "unlocked"]
+        );
+        // Words marked as NOSUGGEST are accepted but never suggested.
+        assert!(dictionary.suggest("damm", 3).is_empty());
+    }
+
+    #[test]
+    fn test_flag_modes_and_aliases() {
+        let aff = "SET ISO8859-1\nFLAG long\nAF 1\nAF SsDd\nSFX Ss Y 1\nSFX Ss 0 s .\nSFX Dd Y 1\nSFX Dd 0 ed .\n";
+        let dic = b"1\nwalk/1\ncaf\xe9\n";
+        let dictionary = Dictionary::parse(aff.as_bytes(), dic).unwrap();
+        assert!(dictionary.check("walks"));
+        assert!(dictionary.check("walked"));
+        assert!(dictionary.check("café"));
+        assert!(!dictionary.check("walking"));
+    }
+}
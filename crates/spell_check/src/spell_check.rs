@@ -0,0 +1,1045 @@
+mod dictionary;
+mod spell_check_settings;
+mod words;
+
+use std::{
+    borrow::Cow,
+    ops::Range,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Result, anyhow};
+use collections::{HashMap, HashSet};
+use editor::{CodeActionProvider, Editor, EditorEvent, EditorMode, HighlightKey};
+use fs::Fs;
+use futures::future;
+use gpui::{
+    App, AppContext as _, Context, Entity, EntityId, EventEmitter, Focusable as _, Global,
+    HighlightStyle, SharedString, Subscription, Task, UnderlineStyle, WeakEntity, Window, px,
+};
+use language::{Anchor, Buffer, BufferId, BufferSnapshot, ToOffset as _};
+use lsp::LanguageServerId;
+use project::{CodeAction, LspAction, Project, ProjectTransaction, WorktreeId};
+use serde::{Deserialize, Serialize};
+use settings::{Settings as _, SettingsStore};
+use theme::ActiveTheme as _;
+use workspace::notifications::{MessageNotification, NotificationId, show_app_notification};
+
+pub use dictionary::Dictionary;
+pub use spell_check_settings::SpellCheckSettings;
+
+const CHECK_DEBOUNCE: Duration = Duration::from_millis(300);
+/// Buffers larger than this aren't spell checked.
+const MAX_BUFFER_LEN: usize = 4 * 1024 * 1024;
+const MAX_SUGGESTIONS: usize = 5;
+const SYSTEM_DICTIONARY_DIRS: &[&str] = &[
+    "/usr/share/hunspell",
+    "/usr/share/myspell",
+    "/usr/share/myspell/dicts",
+    "/usr/local/share/hunspell",
+    "/Library/Spelling",
+];
+
+pub fn init(cx: &mut App) {
+    let spell_checker = cx.new(SpellChecker::new);
+    cx.set_global(GlobalSpellChecker(spell_checker));
+    cx.observe_new(register_editor).detach();
+}
+
+struct GlobalSpellChecker(Entity<SpellChecker>);
+
+impl Global for GlobalSpellChecker {}
+
+/// A word the spell checker found no match for in a buffer.
+#[derive(Clone, Debug)]
+pub struct Misspelling {
+    pub range: Range<Anchor>,
+    pub word: Arc<str>,
+}
+
+pub enum SpellCheckerEvent {
+    BufferChecked(BufferId),
+    /// The misspellings of all buffers were discarded, as spell checking was
+    /// disabled or its dictionaries changed.
+    Reset,
+}
+
+/// Checks the spelling of buffers against the configured dictionaries, the
+/// words of project dictionaries and the words ignored during this session.
+pub struct SpellChecker {
+    settings: SpellCheckSettings,
+    fs: Arc<dyn Fs>,
+    /// The loaded dictionaries, or `None` if they're being loaded or none of
+    /// them could be loaded, in which case nothing is checked.
+    dictionaries: Option<Arc<[Dictionary]>>,
+    ignored_words: HashSet<String>,
+    project_dictionaries: HashMap<WorktreeId, ProjectDictionary>,
+    projects: HashMap<EntityId, [Subscription; 2]>,
+    buffers: HashMap<BufferId, BufferMisspellings>,
+    _load_dictionaries_task: Task<()>,
+    _settings_subscription: Subscription,
+}
+
+struct ProjectDictionary {
+    path: PathBuf,
+    words: HashSet<String>,
+}
+
+struct BufferMisspellings {
+    buffer: WeakEntity<Buffer>,
+    misspellings: Arc<[Misspelling]>,
+    _check: Task<()>,
+    _release_subscription: Subscription,
+}
+
+impl EventEmitter<SpellCheckerEvent> for SpellChecker {}
+
+impl SpellChecker {
+    pub fn global(cx: &App) -> Option<Entity<Self>> {
+        cx.try_global::<GlobalSpellChecker>()
+            .map(|spell_checker| spell_checker.0.clone())
+    }
+
+    fn new(cx: &mut Context<Self>) -> Self {
+        let mut this = Self {
+            settings: SpellCheckSettings::default(),
+            fs: <dyn Fs>::global(cx),
+            dictionaries: None,
+            ignored_words: HashSet::default(),
+            project_dictionaries: HashMap::default(),
+            projects: HashMap::default(),
+            buffers: HashMap::default(),
+            _load_dictionaries_task: Task::ready(()),
+            _settings_subscription: cx.observe_global::<SettingsStore>(Self::settings_changed),
+        };
+        this.settings_changed(cx);
+        this
+    }
+
+    fn settings_changed(&mut self, cx: &mut Context<Self>) {
+        let settings = SpellCheckSettings::get_global(cx).clone();
+        if settings == self.settings {
+            return;
+        }
+
+        let old_settings = std::mem::replace(&mut self.settings, settings);
+        if !self.settings.enabled {
+            self.dictionaries = None;
+            self._load_dictionaries_task = Task::ready(());
+            self.reset(cx);
+        } else if !old_settings.enabled || old_settings.dictionaries != self.settings.dictionaries {
+            self.load_dictionaries(cx);
+        } else {
+            self.recheck_buffers(cx);
+        }
+    }
+
+    fn load_dictionaries(&mut self, cx: &mut Context<Self>) {
+        let fs = self.fs.clone();
+        let dictionaries = self
+            .settings
+            .dictionaries
+            .iter()
+            .map(|name| (name.clone(), bundled_dictionary(name, cx)))
+            .collect::<Vec<_>>();
+        self._load_dictionaries_task = cx.spawn(async move |this, cx| {
+            let (dictionaries, errors) = cx
+                .background_spawn(async move {
+                    let mut loaded = Vec::new();
+                    let mut errors = Vec::new();
+                    for (name, bundled) in dictionaries {
+                        match load_dictionary(&name, bundled, fs.as_ref()).await {
+                            Ok(dictionary) => loaded.push(dictionary),
+                            Err(error) => errors.push(
+                                error.context(format!("skipping spell check dictionary {name:?}")),
+                            ),
+                        }
+                    }
+                    (loaded, errors)
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                if !errors.is_empty() {
+                    notify_skipped_dictionaries(&errors, cx);
+                }
+                this.dictionaries = (!dictionaries.is_empty()).then(|| Arc::from(dictionaries));
+                this.reset(cx);
+            })
+            .ok();
+        });
+    }
+
+    /// Discards the misspellings of all buffers, letting editors check their
+    /// buffers again.
+    fn reset(&mut self, cx: &mut Context<Self>) {
+        self.buffers.clear();
+        cx.emit(SpellCheckerEvent::Reset);
+    }
+
+    fn recheck_buffers(&mut self, cx: &mut Context<Self>) {
+        let buffers = self
+            .buffers
+            .values()
+            .filter_map(|buffer| buffer.buffer.upgrade())
+            .collect::<Vec<_>>();
+        for buffer in buffers {
+            self.check_buffer(&buffer, cx);
+        }
+    }
+
+    /// Returns the misspellings found the last time `buffer_id` was checked,
+    /// or `None` if it hasn't been checked.
+    pub fn misspellings(&self, buffer_id: BufferId) -> Option<&[Misspelling]> {
+        self.buffers
+            .get(&buffer_id)
+            .map(|buffer| buffer.misspellings.as_ref())
+    }
+
+    /// Checks the spelling of `buffer` in the background, after a delay if it
+    /// was checked before, so that edits in quick succession are checked once.
+    pub fn check_buffer(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        if self.dictionaries.is_none() {
+            return;
+        }
+
+        let buffer_id = buffer.read(cx).remote_id();
+        let debounce = self.buffers.contains_key(&buffer_id);
+        let weak_buffer = buffer.downgrade();
+        let check = cx.spawn(async move |this, cx| {
+            if debounce {
+                cx.background_executor().timer(CHECK_DEBOUNCE).await;
+            }
+
+            let Some((snapshot, check_all_text, vocabulary)) = this
+                .update(cx, |this, cx| {
+                    let buffer = weak_buffer.upgrade()?;
+                    let buffer = buffer.read(cx);
+                    let snapshot = buffer.snapshot();
+                    let check_all_text = snapshot.language().is_none_or(|language| {
+                        let name = language.name();
+                        this.settings
+                            .prose_languages
+                            .iter()
+                            .any(|prose_language| name == prose_language.as_str())
+                    });
+                    Some((snapshot, check_all_text, this.vocabulary(buffer, cx)?))
+                })
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+
+            let misspellings = cx
+                .background_spawn(async move {
+                    find_misspellings(&snapshot, check_all_text, &vocabulary)
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                if let Some(buffer) = this.buffers.get_mut(&buffer_id) {
+                    buffer.misspellings = misspellings.into();
+                    cx.emit(SpellCheckerEvent::BufferChecked(buffer_id));
+                }
+            })
+            .ok();
+        });
+
+        if let Some(buffer) = self.buffers.get_mut(&buffer_id) {
+            buffer._check = check;
+        } else {
+            let release_subscription = cx.observe_release(buffer, move |this, _, _| {
+                this.buffers.remove(&buffer_id);
+            });
+            self.buffers.insert(
+                buffer_id,
+                BufferMisspellings {
+                    buffer: buffer.downgrade(),
+                    misspellings: Arc::default(),
+                    _check: check,
+                    _release_subscription: release_subscription,
+                },
+            );
+        }
+    }
+
+    fn vocabulary(&self, buffer: &Buffer, cx: &App) -> Option<Vocabulary> {
+        let mut words = self.ignored_words.clone();
+        if let Some(project_dictionary) = buffer
+            .file()
+            .and_then(|file| self.project_dictionaries.get(&file.worktree_id(cx)))
+        {
+            words.extend(project_dictionary.words.iter().cloned());
+        }
+        Some(Vocabulary {
+            dictionaries: self.dictionaries.clone()?,
+            words,
+        })
+    }
+
+    /// Returns the words `word` may be a misspelling of.
+    pub fn suggestions(&self, word: &str, cx: &App) -> Task<Vec<String>> {
+        let Some(dictionaries) = self.dictionaries.clone() else {
+            return Task::ready(Vec::new());
+        };
+        let word = normalize_apostrophes(word);
+        cx.background_spawn(async move {
+            let mut suggestions = Vec::new();
+            for dictionary in dictionaries.iter() {
+                for suggestion in dictionary.suggest(&word, MAX_SUGGESTIONS) {
+                    if !suggestions.contains(&suggestion) {
+                        suggestions.push(suggestion);
+                    }
+                }
+            }
+            suggestions.truncate(MAX_SUGGESTIONS);
+            suggestions
+        })
+    }
+
+    /// Accepts `word` in all buffers until Zed is restarted.
+    pub fn ignore_word(&mut self, word: &str, cx: &mut Context<Self>) {
+        if self.ignored_words.insert(word.to_lowercase()) {
+            self.recheck_buffers(cx);
+        }
+    }
+
+    pub fn has_project_dictionary(&self, worktree_id: WorktreeId) -> bool {
+        self.project_dictionaries.contains_key(&worktree_id)
+    }
+
+    /// Adds `word` to the dictionary file of the given worktree, creating it
+    /// if needed.
+    pub fn add_to_project_dictionary(
+        &mut self,
+        word: &str,
+        worktree_id: WorktreeId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(project_dictionary) = self.project_dictionaries.get_mut(&worktree_id) else {
+            return Task::ready(Err(anyhow!("worktree has no project dictionary")));
+        };
+        project_dictionary.words.insert(word.to_lowercase());
+        let path = project_dictionary.path.clone();
+        self.recheck_buffers(cx);
+
+        let fs = self.fs.clone();
+        let word = word.to_string();
+        cx.background_spawn(async move {
+            let mut text = fs.load(&path).await.unwrap_or_default();
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+            text.push_str(&word);
+            text.push('\n');
+            if let Some(dir) = path.parent() {
+                fs.create_dir(dir).await?;
+            }
+            fs.atomic_write(path, text).await
+        })
+    }
+
+    /// Loads the dictionaries of the project's local worktrees, and reloads
+    /// them whenever they change.
+    pub fn register_project(&mut self, project: &Entity<Project>, cx: &mut Context<Self>) {
+        let project_id = project.entity_id();
+        if self.projects.contains_key(&project_id) {
+            return;
+        }
+
+        self.projects.insert(
+            project_id,
+            [
+                cx.subscribe(project, Self::handle_project_event),
+                cx.observe_release(project, move |this, _, _| {
+                    this.projects.remove(&project_id);
+                }),
+            ],
+        );
+        let worktree_ids = project
+            .read(cx)
+            .worktrees(cx)
+            .map(|worktree| worktree.read(cx).id())
+            .collect::<Vec<_>>();
+        for worktree_id in worktree_ids {
+            self.load_project_dictionary(project, worktree_id, cx);
+        }
+    }
+
+    fn handle_project_event(
+        &mut self,
+        project: Entity<Project>,
+        event: &project::Event,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            project::Event::WorktreeAdded(worktree_id) => {
+                self.load_project_dictionary(&project, *worktree_id, cx);
+            }
+            project::Event::WorktreeRemoved(worktree_id) => {
+                self.project_dictionaries.remove(worktree_id);
+            }
+            project::Event::WorktreeUpdatedEntries(worktree_id, entries) => {
+                let dictionary_path = paths::local_dictionary_file_relative_path();
+                if entries
+                    .iter()
+                    .any(|(path, _, _)| &**path == dictionary_path)
+                {
+                    self.load_project_dictionary(&project, *worktree_id, cx);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn load_project_dictionary(
+        &mut self,
+        project: &Entity<Project>,
+        worktree_id: WorktreeId,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(worktree) = project.read(cx).worktree_for_id(worktree_id, cx) else {
+            return;
+        };
+        let worktree = worktree.read(cx);
+        if !worktree.is_local() {
+            return;
+        }
+
+        let path = worktree
+            .abs_path()
+            .join(paths::local_dictionary_file_relative_path().as_std_path());
+        let fs = self.fs.clone();
+        cx.spawn(async move |this, cx| {
+            let words = match fs.load(&path).await {
+                Ok(text) => parse_word_list(&text),
+                Err(_) => HashSet::default(),
+            };
+            this.update(cx, |this, cx| {
+                let changed = this
+                    .project_dictionaries
+                    .get(&worktree_id)
+                    .is_none_or(|dictionary| dictionary.words != words);
+                this.project_dictionaries
+                    .insert(worktree_id, ProjectDictionary { path, words });
+                if changed {
+                    this.recheck_buffers(cx);
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+/// The words a buffer's spelling is checked against.
+struct Vocabulary {
+    dictionaries: Arc<[Dictionary]>,
+    /// Additionally accepted words, in lowercase.
+    words: HashSet<String>,
+}
+
+impl Vocabulary {
+    fn check(&self, word: &str) -> bool {
+        let word = normalize_apostrophes(word);
+        self.words.contains(&word.to_lowercase())
+            || self
+                .dictionaries
+                .iter()
+                .any(|dictionary| dictionary.check(&word))
+    }
+}
+
+/// Replaces typographic apostrophes with the ASCII ones dictionaries use.
+fn normalize_apostrophes(word: &str) -> Cow<'_, str> {
+    if word.contains('’') {
+        Cow::Owned(word.replace('’', "'"))
+    } else {
+        Cow::Borrowed(word)
+    }
+}
+
+fn parse_word_list(text: &str) -> HashSet<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn bundled_dictionary(name: &str, cx: &App) -> Option<(Cow<'static, [u8]>, Cow<'static, [u8]>)> {
+    let load = |extension: &str| {
+        cx.asset_source()
+            .load(&format!("dictionaries/{name}.{extension}"))
+            .ok()
+            .flatten()
+    };
+    Some((load("aff")?, load("dic")?))
+}
+
+/// Loads the dictionary called `name` from the user's dictionaries directory,
+/// falling back to the bundled dictionary and then to the system's ones.
+async fn load_dictionary(
+    name: &str,
+    bundled: Option<(Cow<'static, [u8]>, Cow<'static, [u8]>)>,
+    fs: &dyn Fs,
+) -> Result<Dictionary> {
+    let user_dir = paths::dictionaries_dir();
+    if let Some((aff, dic)) = load_dictionary_files(user_dir, name, fs).await {
+        return Dictionary::parse(&aff, &dic);
+    }
+    if let Some((aff, dic)) = bundled {
+        return Dictionary::parse(&aff, &dic);
+    }
+
+    let system_dirs = SYSTEM_DICTIONARY_DIRS
+        .iter()
+        .map(PathBuf::from)
+        .chain([paths::home_dir().join("Library/Spelling")]);
+    for dir in system_dirs {
+        if let Some((aff, dic)) = load_dictionary_files(&dir, name, fs).await {
+            return Dictionary::parse(&aff, &dic);
+        }
+    }
+
+    Err(anyhow!(
+        "no {name}.aff and {name}.dic files found in {} or in the system's dictionary directories",
+        user_dir.display()
+    ))
+}
+
+struct SkippedDictionariesNotification;
+
+/// Tells the user about the configured dictionaries that couldn't be loaded,
+/// as words are then only checked against the other ones.
+fn notify_skipped_dictionaries(errors: &[anyhow::Error], cx: &mut App) {
+    let mut message = String::new();
+    for error in errors {
+        log::error!("{error:#}");
+        if !message.is_empty() {
+            message.push('\n');
+        }
+        message.push_str(&format!("{error:#}"));
+    }
+    let message = SharedString::from(message);
+    show_app_notification(
+        NotificationId::unique::<SkippedDictionariesNotification>(),
+        cx,
+        move |cx| cx.new(|cx| MessageNotification::new(message.clone(), cx)),
+    );
+}
+
+async fn load_dictionary_files(dir: &Path, name: &str, fs: &dyn Fs) -> Option<(Vec<u8>, Vec<u8>)> {
+    let aff = fs.load_bytes(&dir.join(format!("{name}.aff"))).await.ok()?;
+    let dic = fs.load_bytes(&dir.join(format!("{name}.dic"))).await.ok()?;
+    Some((aff, dic))
+}
+
+fn find_misspellings(
+    snapshot: &BufferSnapshot,
+    check_all_text: bool,
+    vocabulary: &Vocabulary,
+) -> Vec<Misspelling> {
+    if snapshot.len() > MAX_BUFFER_LEN {
+        return Vec::new();
+    }
+
+    let ranges = if check_all_text {
+        vec![0..snapshot.len()]
+    } else {
+        checked_syntax_ranges(snapshot)
+    };
+    let mut checked_words = HashMap::<String, bool>::default();
+    let mut misspellings = Vec::new();
+    for range in ranges {
+        let text = snapshot.text_for_range(range.clone()).collect::<String>();
+        for word_range in words::word_ranges(&text) {
+            let word = &text[word_range.clone()];
+            let is_correct = match checked_words.get(word) {
+                Some(is_correct) => *is_correct,
+                None => *checked_words
+                    .entry(word.to_string())
+                    .or_insert_with(|| vocabulary.check(word)),
+            };
+            if !is_correct {
+                misspellings.push(Misspelling {
+                    range: snapshot.anchor_after(range.start + word_range.start)
+                        ..snapshot.anchor_before(range.start + word_range.end),
+                    word: word.into(),
+                });
+            }
+        }
+    }
+    misspellings
+}
+
+/// Returns the ranges of the comments and strings in the buffer, according
+/// to the highlight captures of its languages.
+fn checked_syntax_ranges(snapshot: &BufferSnapshot) -> Vec<Range<usize>> {
+    let captures = snapshot.captures(0..snapshot.len(), |grammar| {
+        grammar
+            .highlights_config
+            .as_ref()
+            .map(|config| &config.query)
+    });
+    let checked_captures_by_grammar = captures
+        .grammars()
+        .iter()
+        .map(|grammar| {
+            grammar
+                .highlights_config
+                .as_ref()
+                .map(|config| {
+                    config
+                        .query
+                        .capture_names()
+                        .iter()
+                        .map(|name| {
+                            *name == "comment"
+                                || name.starts_with("comment.")
+                                || *name == "string"
+                                || *name == "string.doc"
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    let mut ranges = Vec::<Range<usize>>::new();
+    for capture in captures {
+        let is_checked = checked_captures_by_grammar[capture.grammar_index]
+            .get(capture.index as usize)
+            .copied()
+            .unwrap_or(false);
+        if !is_checked {
+            continue;
+        }
+        let range = capture.node.byte_range();
+        match ranges.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => ranges.push(range),
+        }
+    }
+    ranges
+}
+
+struct SpellCheckAddon {
+    _subscriptions: [Subscription; 2],
+}
+
+impl editor::Addon for SpellCheckAddon {
+    fn to_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+fn register_editor(editor: &mut Editor, window: Option<&mut Window>, cx: &mut Context<Editor>) {
+    let Some(window) = window else {
+        return;
+    };
+    if !matches!(
+        editor.mode(),
+        EditorMode::Full { .. } | EditorMode::AutoHeight { .. }
+    ) || editor.read_only(cx)
+    {
+        return;
+    }
+    let Some(spell_checker) = SpellChecker::global(cx) else {
+        return;
+    };
+
+    if let Some(project) = editor.project().cloned() {
+        spell_checker.update(cx, |spell_checker, cx| {
+            spell_checker.register_project(&project, cx)
+        });
+    }
+    editor.add_code_action_provider(
+        Rc::new(SpellCheckCodeActionProvider {
+            spell_checker: spell_checker.clone(),
+        }),
+        window,
+        cx,
+    );
+    editor.register_addon(SpellCheckAddon {
+        _subscriptions: [
+            cx.subscribe_self(handle_editor_event),
+            cx.subscribe_in(&spell_checker, window, handle_spell_checker_event),
+        ],
+    });
+    refresh_misspellings(editor, &spell_checker, window, cx);
+}
+
+fn handle_editor_event(editor: &mut Editor, event: &EditorEvent, cx: &mut Context<Editor>) {
+    let buffers = match event {
+        EditorEvent::BuffersEdited { buffer_ids } => buffer_ids
+            .iter()
+            .filter_map(|buffer_id| editor.buffer().read(cx).buffer(*buffer_id))
+            .collect::<Vec<_>>(),
+        EditorEvent::Reparsed(buffer_id) => editor
+            .buffer()
+            .read(cx)
+            .buffer(*buffer_id)
+            .into_iter()
+            .collect(),
+        EditorEvent::BufferRangesUpdated { buffer, .. } => vec![buffer.clone()],
+        _ => return,
+    };
+    if let Some(spell_checker) = SpellChecker::global(cx) {
+        spell_checker.update(cx, |spell_checker, cx| {
+            for buffer in &buffers {
+                spell_checker.check_buffer(buffer, cx);
+            }
+        });
+    }
+}
+
+fn handle_spell_checker_event(
+    editor: &mut Editor,
+    spell_checker: &Entity<SpellChecker>,
+    event: &SpellCheckerEvent,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    match event {
+        SpellCheckerEvent::BufferChecked(buffer_id) => {
+            if editor.buffer().read(cx).buffer(*buffer_id).is_some() {
+                refresh_misspellings(editor, spell_checker, window, cx);
+            }
+        }
+        SpellCheckerEvent::Reset => refresh_misspellings(editor, spell_checker, window, cx),
+    }
+}
+
+/// Underlines the misspellings found in the editor's buffers, and checks the
+/// buffers that weren't checked yet.
+fn refresh_misspellings(
+    editor: &mut Editor,
+    spell_checker: &Entity<SpellChecker>,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let buffers = editor.buffer().read(cx).all_buffers();
+    let unchecked_buffers = buffers
+        .iter()
+        .filter(|buffer| {
+            spell_checker
+                .read(cx)
+                .misspellings(buffer.read(cx).remote_id())
+                .is_none()
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    if !unchecked_buffers.is_empty() {
+        spell_checker.update(cx, |spell_checker, cx| {
+            for buffer in &unchecked_buffers {
+                spell_checker.check_buffer(buffer, cx);
+            }
+        });
+    }
+
+    let multibuffer = editor.buffer().read(cx).snapshot(cx);
+    let ranges = buffers
+        .iter()
+        .filter_map(|buffer| {
+            spell_checker
+                .read(cx)
+                .misspellings(buffer.read(cx).remote_id())
+        })
+        .flatten()
+        .filter_map(|misspelling| multibuffer.anchor_range_in_buffer(misspelling.range.clone()))
+        .collect::<Vec<_>>();
+    if ranges.is_empty() {
+        editor.clear_highlights(HighlightKey::SpellCheck, cx);
+    } else {
+        editor.highlight_text(
+            HighlightKey::SpellCheck,
+            ranges,
+            HighlightStyle {
+                underline: Some(UnderlineStyle {
+                    thickness: px(1.),
+                    color: Some(cx.theme().status().info),
+                    wavy: true,
+                }),
+                ..HighlightStyle::default()
+            },
+            cx,
+        );
+    }
+
+    if editor.focus_handle(cx).is_focused(window) {
+        editor.refresh_code_actions_for_selection(window, cx);
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum SpellCheckAction {
+    Replace { replacement: String },
+    AddToProjectDictionary { word: String },
+    Ignore { word: String },
+}
+
+struct SpellCheckCodeActionProvider {
+    spell_checker: Entity<SpellChecker>,
+}
+
+impl CodeActionProvider for SpellCheckCodeActionProvider {
+    fn id(&self) -> Arc<str> {
+        "spell_check".into()
+    }
+
+    fn code_actions(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<Anchor>,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Vec<CodeAction>>> {
+        let spell_checker = self.spell_checker.read(cx);
+        let buffer = buffer.read(cx);
+        let snapshot = buffer.snapshot();
+        let range = range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot);
+        let misspellings = spell_checker
+            .misspellings(buffer.remote_id())
+            .unwrap_or_default()
+            .iter()
+            .filter(|misspelling| {
+                misspelling.range.start.to_offset(&snapshot) <= range.end
+                    && range.start <= misspelling.range.end.to_offset(&snapshot)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if misspellings.is_empty() {
+            return Task::ready(Ok(Vec::new()));
+        }
+
+        let can_add_to_dictionary = buffer
+            .file()
+            .is_some_and(|file| spell_checker.has_project_dictionary(file.worktree_id(cx)));
+        let suggestions = misspellings
+            .iter()
+            .map(|misspelling| spell_checker.suggestions(&misspelling.word, cx))
+            .collect::<Vec<_>>();
+        cx.background_spawn(async move {
+            let mut actions = Vec::new();
+            for (misspelling, suggestions) in misspellings
+                .into_iter()
+                .zip(future::join_all(suggestions).await)
+            {
+                let word = misspelling.word.to_string();
+                for suggestion in suggestions {
+                    actions.push(code_action(
+                        &misspelling,
+                        format!("Change to \"{suggestion}\""),
+                        SpellCheckAction::Replace {
+                            replacement: suggestion,
+                        },
+                    ));
+                }
+                if can_add_to_dictionary {
+                    actions.push(code_action(
+                        &misspelling,
+                        format!("Add \"{word}\" to project dictionary"),
+                        SpellCheckAction::AddToProjectDictionary { word: word.clone() },
+                    ));
+                }
+                actions.push(code_action(
+                    &misspelling,
+                    format!("Ignore \"{word}\""),
+                    SpellCheckAction::Ignore { word },
+                ));
+            }
+            Ok(actions)
+        })
+    }
+
+    fn apply_code_action(
+        &self,
+        buffer_handle: Entity<Buffer>,
+        action: CodeAction,
+        push_to_history: bool,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<ProjectTransaction>> {
+        let spell_check_action = match &action.lsp_action {
+            LspAction::Action(lsp_action) => lsp_action
+                .data
+                .clone()
+                .and_then(|data| serde_json::from_value::<SpellCheckAction>(data).ok()),
+            _ => None,
+        };
+        let Some(spell_check_action) = spell_check_action else {
+            return Task::ready(Err(anyhow!("not a spell check code action")));
+        };
+
+        match spell_check_action {
+            SpellCheckAction::Replace { replacement } => {
+                let transaction = buffer_handle.update(cx, |buffer, cx| {
+                    buffer.finalize_last_transaction();
+                    buffer.start_transaction();
+                    buffer.edit([(action.range, replacement)], None, cx);
+                    buffer.end_transaction(cx)?;
+                    let transaction = buffer.finalize_last_transaction()?.clone();
+                    if !push_to_history {
+                        buffer.forget_transaction(transaction.id);
+                    }
+                    Some(transaction)
+                });
+                let mut project_transaction = ProjectTransaction::default();
+                if let Some(transaction) = transaction {
+                    project_transaction.0.insert(buffer_handle, transaction);
+                }
+                Task::ready(Ok(project_transaction))
+            }
+            SpellCheckAction::AddToProjectDictionary { word } => {
+                let Some(worktree_id) = buffer_handle
+                    .read(cx)
+                    .file()
+                    .map(|file| file.worktree_id(cx))
+                else {
+                    return Task::ready(Err(anyhow!("buffer isn't part of a worktree")));
+                };
+                let add_word = self.spell_checker.update(cx, |spell_checker, cx| {
+                    spell_checker.add_to_project_dictionary(&word, worktree_id, cx)
+                });
+                cx.background_spawn(async move {
+                    add_word.await?;
+                    Ok(ProjectTransaction::default())
+                })
+            }
+            SpellCheckAction::Ignore { word } => {
+                self.spell_checker
+                    .update(cx, |spell_checker, cx| spell_checker.ignore_word(&word, cx));
+                Task::ready(Ok(ProjectTransaction::default()))
+            }
+        }
+    }
+}
+
+fn code_action(misspelling: &Misspelling, title: String, action: SpellCheckAction) -> CodeAction {
+    CodeAction {
+        server_id: LanguageServerId(usize::MAX),
+        range: misspelling.range.clone(),
+        lsp_action: LspAction::Action(Box::new(lsp::CodeAction {
+            title,
+            kind: Some(lsp::CodeActionKind::QUICKFIX),
+            data: serde_json::to_value(action).ok(),
+            ..lsp::CodeAction::default()
+        })),
+        resolved: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+
+    const AFF: &str = "SET UTF-8\n";
+    const DIC: &str = "4\na\ncomment\nhello\nworld\n";
+
+    async fn init_test(dictionaries: &[&str], cx: &mut TestAppContext) -> Entity<SpellChecker> {
+        let fs = FakeFs::new(cx.executor());
+        let dir = paths::dictionaries_dir();
+        fs.insert_file(dir.join("test.aff"), AFF.as_bytes().to_vec())
+            .await;
+        fs.insert_file(dir.join("test.dic"), DIC.as_bytes().to_vec())
+            .await;
+        let dictionaries = dictionaries
+            .iter()
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>();
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            <dyn Fs>::set_global(fs, cx);
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |settings| {
+                    let spell_check = settings.spell_check.get_or_insert_default();
+                    spell_check.enabled = Some(true);
+                    spell_check.dictionaries = Some(dictionaries);
+                });
+            });
+        });
+        let spell_checker = cx.new(SpellChecker::new);
+        cx.run_until_parked();
+        spell_checker
+    }
+
+    async fn misspelled_words(
+        spell_checker: &Entity<SpellChecker>,
+        text: &str,
+        language: Option<Arc<language::Language>>,
+        cx: &mut TestAppContext,
+    ) -> Vec<String> {
+        let buffer = cx.new(|cx| {
+            let buffer = Buffer::local(text, cx);
+            match language {
+                Some(language) => buffer.with_language(language, cx),
+                None => buffer,
+            }
+        });
+        cx.run_until_parked();
+        spell_checker.update(cx, |spell_checker, cx| {
+            spell_checker.check_buffer(&buffer, cx)
+        });
+        cx.run_until_parked();
+        let buffer_id = buffer.read_with(cx, |buffer, _| buffer.remote_id());
+        spell_checker.read_with(cx, |spell_checker, _| {
+            spell_checker
+                .misspellings(buffer_id)
+                .unwrap_or_default()
+                .iter()
+                .map(|misspelling| misspelling.word.to_string())
+                .collect()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_checks_comments_and_strings_but_not_identifiers(cx: &mut TestAppContext) {
+        let spell_checker = init_test(&["test"], cx).await;
+        let text = "// a coment\nfn mispeled() {\n    let greting = \"helo world\";\n}\n";
+
+        assert_eq!(
+            misspelled_words(&spell_checker, text, Some(language::rust_lang()), cx).await,
+            ["coment", "helo"]
+        );
+
+        // Text without a language is checked in full.
+        let words = misspelled_words(&spell_checker, text, None, cx).await;
+        assert!(words.contains(&"mispeled".to_string()), "{words:?}");
+        assert!(words.contains(&"greting".to_string()), "{words:?}");
+    }
+
+    #[gpui::test]
+    async fn test_missing_dictionaries_are_skipped(cx: &mut TestAppContext) {
+        let spell_checker = init_test(&["en_US", "test"], cx).await;
+        spell_checker.read_with(cx, |spell_checker, _| {
+            assert_eq!(
+                spell_checker
+                    .dictionaries
+                    .as_ref()
+                    .map(|dictionaries| dictionaries.len()),
+                Some(1)
+            );
+        });
+        assert_eq!(
+            misspelled_words(
+                &spell_checker,
+                "// a coment",
+                Some(language::rust_lang()),
+                cx
+            )
+            .await,
+            ["coment"]
+        );
+
+        let spell_checker = init_test(&["en_US"], cx).await;
+        spell_checker.read_with(cx, |spell_checker, _| {
+            assert!(spell_checker.dictionaries.is_none());
+        });
+    }
+}
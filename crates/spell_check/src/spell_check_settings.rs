@@ -0,0 +1,31 @@
+use settings::{RegisterSetting, Settings};
+
+/// The settings for spell checking.
+#[derive(Clone, Debug, Default, PartialEq, RegisterSetting)]
+pub struct SpellCheckSettings {
+    /// Whether to spell check comments and strings in code, and all of the
+    /// text in prose languages.
+    ///
+    /// Default: false
+    pub enabled: bool,
+    /// The names of the Hunspell dictionaries to check words against.
+    ///
+    /// Default: ["en_US", "code"]
+    pub dictionaries: Vec<String>,
+    /// The languages whose buffers are checked in full, rather than only in
+    /// their comments and strings.
+    ///
+    /// Default: ["Markdown", "Plain Text", "Git Commit"]
+    pub prose_languages: Vec<String>,
+}
+
+impl Settings for SpellCheckSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let spell_check = content.spell_check.clone().unwrap();
+        Self {
+            enabled: spell_check.enabled.unwrap(),
+            dictionaries: spell_check.dictionaries.unwrap(),
+            prose_languages: spell_check.prose_languages.unwrap(),
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use std::ops::Range;
+
+/// Returns the byte ranges of the words in `text` that should be spell checked.
+///
+/// Identifiers are split into their camelCase and snake_case parts, while
+/// tokens that look like code, paths, URLs or email addresses, acronyms and
+/// single letters are skipped.
+pub fn word_ranges(text: &str) -> Vec<Range<usize>> {
+    let code_spans = code_spans(text);
+    let mut ranges = Vec::new();
+    for (token_start, token) in tokens(text) {
+        let token_range = token_start..token_start + token.len();
+        if looks_like_code(token)
+            || code_spans
+                .iter()
+                .any(|span| span.start < token_range.end && token_range.start < span.end)
+        {
+            continue;
+        }
+
+        for run in letter_runs(token) {
+            for part in split_identifier(&token[run.clone()]) {
+                let word = &token[run.start + part.start..run.start + part.end];
+                if is_checkable(word) {
+                    ranges.push(
+                        token_start + run.start + part.start..token_start + run.start + part.end,
+                    );
+                }
+            }
+        }
+    }
+    ranges
+}
+
+fn tokens(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split(char::is_whitespace)
+        .filter(|token| !token.is_empty())
+        .map(move |token| (token.as_ptr() as usize - text.as_ptr() as usize, token))
+}
+
+/// Returns the ranges of Markdown code spans in `text`, which are delimited by
+/// runs of the same number of backticks. Unterminated ones end with the line.
+fn code_spans(text: &str) -> Vec<Range<usize>> {
+    let backtick_runs = |offset: usize| {
+        text[offset..]
+            .match_indices('`')
+            .filter_map(move |(ix, _)| {
+                let start = offset + ix;
+                let len = text[start..].len() - text[start..].trim_start_matches('`').len();
+                (start == 0 || !text[..start].ends_with('`')).then_some(start..start + len)
+            })
+    };
+
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    while let Some(open) = backtick_runs(offset).next() {
+        match backtick_runs(open.end).find(|close| close.len() == open.len()) {
+            Some(close) => {
+                spans.push(open.start..close.end);
+                offset = close.end;
+            }
+            None => {
+                let line_end = text[open.end..]
+                    .find('\n')
+                    .map_or(text.len(), |ix| open.end + ix);
+                spans.push(open.start..line_end);
+                offset = open.end;
+            }
+        }
+    }
+    spans
+}
+
+fn looks_like_code(token: &str) -> bool {
+    if token.contains("://") || token.contains("::") || token.contains(['@', '/', '\\', '=']) {
+        return true;
+    }
+    // Qualified names and file names, like `self.field` or `main.rs`.
+    let token = token.trim_end_matches(|c: char| !c.is_alphanumeric());
+    token
+        .char_indices()
+        .any(|(ix, c)| c == '.' && token[ix + 1..].starts_with(char::is_alphanumeric))
+}
+
+/// Returns the runs of letters in `token`, which may contain apostrophes
+/// between letters, skipping runs that are attached to digits.
+fn letter_runs(token: &str) -> Vec<Range<usize>> {
+    let mut runs = Vec::new();
+    let mut chars = token.char_indices().peekable();
+    let mut previous = None;
+    while let Some((start, c)) = chars.next() {
+        if !c.is_alphabetic() {
+            previous = Some(c);
+            continue;
+        }
+
+        let mut end = start + c.len_utf8();
+        while let Some(&(ix, c)) = chars.peek() {
+            if c.is_alphabetic() {
+                end = ix + c.len_utf8();
+            } else if !is_apostrophe(c)
+                || !token[ix + c.len_utf8()..].starts_with(char::is_alphabetic)
+            {
+                break;
+            }
+            chars.next();
+        }
+
+        let next = token[end..].chars().next();
+        let attached_to_digit = previous.is_some_and(|c| c.is_ascii_digit())
+            || next.is_some_and(|c| c.is_ascii_digit());
+        if !attached_to_digit {
+            runs.push(start..end);
+        }
+        previous = token[..end].chars().next_back();
+    }
+    runs
+}
+
+/// Splits a run of letters on its camelCase boundaries, keeping acronyms
+/// together, so that `parseHTTPResponse` becomes `parse`, `HTTP` and `Response`.
+fn split_identifier(word: &str) -> Vec<Range<usize>> {
+    let chars = word.char_indices().collect::<Vec<_>>();
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for (ix, &(offset, c)) in chars.iter().enumerate().skip(1) {
+        if !c.is_uppercase() {
+            continue;
+        }
+        let previous = chars[ix - 1].1;
+        let next_is_lowercase = chars.get(ix + 1).is_some_and(|(_, c)| c.is_lowercase());
+        if previous.is_lowercase() || (previous.is_uppercase() && next_is_lowercase) {
+            parts.push(start..offset);
+            start = offset;
+        }
+    }
+    parts.push(start..word.len());
+    parts
+}
+
+fn is_checkable(word: &str) -> bool {
+    let mut letters = word.chars().filter(|c| c.is_alphabetic());
+    let is_acronym = letters.clone().all(|c| c.is_uppercase());
+    letters.nth(1).is_some() && !is_acronym
+}
+
+fn is_apostrophe(c: char) -> bool {
+    c == '\'' || c == '’'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<&str> {
+        word_ranges(text)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect()
+    }
+
+    #[test]
+    fn test_prose() {
+        assert_eq!(
+            words("Don't panic, it’s only a (small) test."),
+            ["Don't", "panic", "it’s", "only", "small", "test"]
+        );
+        assert_eq!(words("'quoted' words"), ["quoted", "words"]);
+    }
+
+    #[test]
+    fn test_identifiers() {
+        assert_eq!(
+            words("parseHTTPResponse snake_case_name XMLHttpRequest"),
+            [
+                "parse", "Response", "snake", "case", "name", "Http", "Request"
+            ]
+        );
+        assert_eq!(words("utf8 x86_64 sha256sum v2 fixed"), ["fixed"]);
+    }
+
+    #[test]
+    fn test_skipped_tokens() {
+        assert_eq!(
+            words("See https://zed.dev, me@example.com and src/main.rs for it"),
+            ["See", "and", "for", "it"]
+        );
+        assert_eq!(
+            words("Call `frobnicate(x)` or ``let foo = `bar` `` then self.fooo() and std::mem"),
+            ["Call", "or", "then", "and"]
+        );
+        assert_eq!(
+            words("The API uses JSON, see e.g. RFC 8259."),
+            ["The", "uses", "see"]
+        );
+    }
+
+    #[test]
+    fn test_code_spans() {
+        assert_eq!(
+            words("Some ``code with ` inside`` and `unterminated code\nnext line"),
+            ["Some", "and", "next", "line"]
+        );
+    }
+
+    #[test]
+    fn test_offsets() {
+        let text = "  héllo\twörld ";
+        assert_eq!(word_ranges(text), [2..8, 9..15]);
+    }
+}
@@ -1209,6 +1209,7 @@ mod test {
                 first_line_indent: 0,
                 file_path: None,
                 line_range: None,
+                is_block: false,
             },
             editor::ClipboardSelection {
                 len: "line two\n".len(),
@@ -1216,6 +1217,7 @@ mod test {
                 first_line_indent: 0,
                 file_path: None,
                 line_range: None,
+                is_block: false,
             },
         ];
         cx.write_to_clipboard(ClipboardItem::new_string_with_json_metadata(
@@ -1075,6 +1075,10 @@ impl Domain for WorkspaceDb {
 db::static_connection!(WorkspaceDb, []);
 
 impl WorkspaceDb {
+    pub fn from_app_db(db: &db::AppDatabase) -> Self {
+        Self(db.0.clone())
+    }
+
     /// Returns a serialized workspace for the given worktree_roots. If the passed array
     /// is empty, the most recent workspace is returned instead. If no workspace for the
     /// passed roots is stored, returns none.
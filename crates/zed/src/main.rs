@@ -83,8 +83,8 @@ use crate::zed::{CrashHandler, OpenRequestKind, eager_load_active_theme_and_icon
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
-fn build_application() -> Application {
-    let platform = gpui_platform::current_platform(false);
+fn build_application(headless: bool) -> Application {
+    let platform = gpui_platform::current_platform(headless);
     if std::env::var("ZED_EXPERIMENTAL_A11Y").as_deref() == Ok("1") {
         Application::with_platform(platform)
     } else {
@@ -120,7 +120,7 @@ fn files_not_created_on_launch(errors: HashMap<io::ErrorKind, Vec<&Path>>) {
         .collect::<Vec<_>>().join("\n\n");
 
     eprintln!("{message}: {error_details}");
-    build_application()
+    build_application(false)
         .with_quit_mode(QuitMode::Explicit)
         .run(move |cx| {
             if let Ok(window) = cx.open_window(gpui::WindowOptions::default(), |_, cx| {
@@ -345,11 +345,17 @@ fn main() {
     #[cfg(windows)]
     check_for_conpty_dll();
 
-    let app = build_application()
+    let app = build_application(args.headless)
         .with_assets(Assets)
         .with_restart_arguments(restart_arguments);
 
-    let app_db = db::AppDatabase::new();
+    // A headless Zed runs next to the user's Zed, so it keeps its own database
+    // and only reads which worktrees the user trusts from theirs.
+    let (app_db, user_db) = if args.headless {
+        (db::AppDatabase::headless(), Some(db::AppDatabase::new()))
+    } else {
+        (db::AppDatabase::new(), None)
+    };
     let system_id = app.background_executor().spawn(system_id());
     let installation_id = app
         .background_executor()
@@ -363,7 +369,11 @@ fn main() {
 
     let (open_listener, mut open_rx) = OpenListener::new();
 
-    let failed_single_instance_check = if *zed_env_vars::ZED_STATELESS
+    // A headless Zed only serves the CLI that started it, so it runs alongside
+    // the user's Zed rather than receiving its requests. It doesn't share their
+    // database, see above.
+    let failed_single_instance_check = if args.headless
+        || *zed_env_vars::ZED_STATELESS
         || *release_channel::RELEASE_CHANNEL == ReleaseChannel::Dev
     {
         false
@@ -484,13 +494,21 @@ fn main() {
 
     app.run(move |cx| {
         cx.set_global(app_db);
-        let db_trusted_paths = match workspace::WorkspaceDb::global(cx).fetch_trusted_worktrees() {
+        if args.headless {
+            zed::agent_cli::init_headless(cx);
+        }
+        let workspace_db = user_db.as_ref().map_or_else(
+            || workspace::WorkspaceDb::global(cx),
+            workspace::WorkspaceDb::from_app_db,
+        );
+        let db_trusted_paths = match workspace_db.fetch_trusted_worktrees() {
             Ok(trusted_paths) => trusted_paths,
             Err(e) => {
                 log::error!("Failed to do initial trusted worktrees fetch: {e:#}");
                 HashMap::default()
             }
         };
+        drop((workspace_db, user_db));
         trusted_worktrees::init(db_trusted_paths, cx);
         menu::init();
         zed_actions::init();
@@ -565,6 +583,7 @@ fn main() {
         let node_runtime = NodeRuntime::new(client.http_client(), Some(shell_env_loaded_rx), rx);
 
         debug_adapter_extension::init(extension_host_proxy.clone(), cx);
+        git_hosting_provider_extension::init(extension_host_proxy.clone(), cx);
         languages::init(languages.clone(), fs.clone(), node_runtime.clone(), cx);
         let user_store = cx.new(|cx| UserStore::new(client.clone(), cx));
         let workspace_store = cx.new(|cx| WorkspaceStore::new(client.clone(), cx));
@@ -660,7 +679,10 @@ fn main() {
         });
         AppState::set_global(app_state.clone(), cx);
 
-        auto_update::init(client.clone(), cx);
+        // Updates are left to the user's Zed.
+        if !args.headless {
+            auto_update::init(client.clone(), cx);
+        }
         dap_adapters::init(cx);
         auto_update_ui::init(cx);
         reliability::init(client.clone(), app_state.workspace_store.clone(), cx);
@@ -734,8 +756,11 @@ fn main() {
         load_embedded_fonts(cx);
 
         editor::init(cx);
+        hex_editor::init(cx);
         image_viewer::init(cx);
         repl::notebook::init(cx);
+        large_file_viewer::init(cx);
+        spell_check::init(cx);
         diagnostics::init(cx);
 
         audio::init(cx);
@@ -932,6 +957,20 @@ fn main() {
             .ok()
             .and_then(|request| OpenRequest::parse(request, cx).log_err())
         {
+            // A headless Zed has no windows to open anything in.
+            request
+                if args.headless
+                    && !matches!(
+                        request,
+                        Some(OpenRequest {
+                            kind: Some(OpenRequestKind::CliConnection(_)),
+                            ..
+                        })
+                    ) =>
+            {
+                log::error!("--headless is only used to serve `zed --agent`");
+                process::exit(1);
+            }
             Some(request) if request.is_focus_app_only() => cx.spawn({
                 let app_state = app_state.clone();
                 async move |cx| {
@@ -1797,6 +1836,11 @@ struct Args {
     #[cfg(target_os = "windows")]
     #[arg(long, hide = true)]
     etw_socket: Option<String>,
+
+    /// Run without windows to serve a single `zed --agent` request from the
+    /// CLI, then quit. Uses its own database rather than the user's.
+    #[arg(long, hide = true)]
+    headless: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -1,3 +1,4 @@
+pub(crate) mod agent_cli;
 mod app_menus;
 pub mod edit_prediction_registry;
 #[cfg(target_os = "macos")]
@@ -5951,6 +5952,7 @@ mod tests {
                 "lsp_tool",
                 "markdown",
                 "menu",
+                "merge_editor",
                 "multi_workspace",
                 "new_process_modal",
                 "notebook",
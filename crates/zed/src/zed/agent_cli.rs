@@ -0,0 +1,656 @@
+//! Runs the Zed Agent for `zed --agent` in a Zed started with `--headless`, and
+//! streams its progress back to the CLI.
+
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+    str::FromStr as _,
+    sync::Arc,
+};
+
+use acp_thread::{
+    AcpThread, AcpThreadEvent, AgentConnection as _, AgentThreadEntry, AssistantMessageChunk,
+    SelectedPermissionOutcome, ToolCallStatus,
+};
+use agent::{NativeAgent, NativeAgentConnection, Templates, ThreadStore};
+use agent_client_protocol::schema::v1 as acp;
+use agent_settings::{AgentProfileId, AgentSettings};
+use anyhow::{Context as _, Result, anyhow};
+use cli::{CliResponse, CliResponseSink};
+use collections::HashMap;
+use gpui::{App, AppContext as _, AsyncApp, Context, Entity, Global, Subscription, TaskExt as _};
+use language_model::{LanguageModel, LanguageModelRegistry, SelectedModel};
+use project::Project;
+use settings::Settings as _;
+use workspace::{AppState, PathList};
+
+/// Marks a Zed started with `--headless` to serve `zed --agent`, and counts the
+/// runs in progress, so that it quits once they are all done.
+#[derive(Default)]
+pub(crate) struct HeadlessAgentRuns {
+    active: usize,
+}
+
+impl Global for HeadlessAgentRuns {}
+
+pub(crate) fn init_headless(cx: &mut App) {
+    cx.set_global(HeadlessAgentRuns::default());
+}
+
+pub(crate) struct AgentRunRequest {
+    pub prompt: String,
+    pub cwd: PathBuf,
+    pub profile: Option<String>,
+    pub model: Option<String>,
+    pub allow_confirmations: bool,
+    pub env: Option<HashMap<String, String>>,
+}
+
+/// Runs the agent on the request's prompt until it stops, and returns the exit
+/// status for the CLI.
+pub(crate) async fn run(
+    request: AgentRunRequest,
+    app_state: Arc<AppState>,
+    responses: Arc<dyn CliResponseSink>,
+    cx: &mut AsyncApp,
+) -> i32 {
+    cx.update(|cx| update_active_runs(cx, |active| *active += 1));
+    let status = match run_agent(request, app_state, responses.clone(), cx).await {
+        Ok(status) => status,
+        Err(error) => {
+            responses
+                .send(CliResponse::Stderr {
+                    message: format!("error: {error:#}"),
+                })
+                .ok();
+            1
+        }
+    };
+    cx.update(|cx| update_active_runs(cx, |active| *active -= 1));
+    status
+}
+
+fn update_active_runs(cx: &mut App, update: impl FnOnce(&mut usize)) {
+    if cx.has_global::<HeadlessAgentRuns>() {
+        update(&mut cx.global_mut::<HeadlessAgentRuns>().active);
+    }
+}
+
+/// Quits Zed once its runs are done, if it was started for `zed --agent`.
+/// Zed instances the user started keep running.
+pub(crate) fn quit_if_idle(cx: &mut App) {
+    if cx
+        .try_global::<HeadlessAgentRuns>()
+        .is_some_and(|runs| runs.active == 0)
+    {
+        cx.quit();
+    }
+}
+
+async fn run_agent(
+    request: AgentRunRequest,
+    app_state: Arc<AppState>,
+    responses: Arc<dyn CliResponseSink>,
+    cx: &mut AsyncApp,
+) -> Result<i32> {
+    let AgentRunRequest {
+        prompt,
+        cwd,
+        profile,
+        model,
+        allow_confirmations,
+        env,
+    } = request;
+
+    anyhow::ensure!(
+        app_state.fs.is_dir(&cwd).await,
+        "{} is not a directory",
+        cwd.display()
+    );
+    let profile = cx.update(|cx| resolve_profile(profile, cx))?;
+    let model = resolve_model(model, cx).await?;
+    let project = project_for_directory(&cwd, &app_state, env, cx).await?;
+
+    let work_dirs = PathList::new(&[cwd]);
+    let agent = cx.update(|cx| {
+        NativeAgent::new(
+            ThreadStore::global(cx),
+            Templates::new(),
+            app_state.fs.clone(),
+            cx,
+        )
+    });
+    let connection = Rc::new(NativeAgentConnection(agent));
+    let thread = cx
+        .update(|cx| {
+            connection
+                .clone()
+                .new_session(project.clone(), work_dirs.clone(), cx)
+        })
+        .await
+        .context("creating the agent thread")?;
+
+    cx.update(|cx| {
+        let session_id = thread.read(cx).session_id().clone();
+        let native_thread = connection
+            .thread(&session_id, cx)
+            .context("agent thread not found")?;
+        native_thread.update(cx, |native_thread, cx| {
+            native_thread.set_model(model, cx);
+            if let Some(profile) = profile {
+                native_thread.set_profile(profile, cx);
+            }
+        });
+        anyhow::Ok(())
+    })?;
+
+    let run = cx.new(|cx| {
+        let mut run = AgentRun {
+            connection,
+            project,
+            work_dirs,
+            thread: thread.clone(),
+            responses: responses.clone(),
+            allow_confirmations,
+            printed_entries: 0,
+            _subagent_threads: Vec::new(),
+            _subscriptions: Vec::new(),
+        };
+        run.watch(thread.clone(), cx);
+        run
+    });
+
+    let message = vec![acp::ContentBlock::Text(acp::TextContent::new(prompt))];
+    let result = thread
+        .update(cx, |thread, cx| thread.send(message, cx))
+        .await;
+    run.update(cx, |run, cx| run.print_entries(true, cx));
+
+    let stop_reason = match result {
+        Ok(response) => response.map(|response| response.stop_reason),
+        Err(error) => return Err(error.context("the agent failed")),
+    };
+    Ok(match stop_reason {
+        None | Some(acp::StopReason::EndTurn) => 0,
+        Some(acp::StopReason::Cancelled) => 130,
+        Some(stop_reason) => {
+            responses
+                .send(CliResponse::Stderr {
+                    message: format!("The agent stopped early: {stop_reason:?}"),
+                })
+                .ok();
+            1
+        }
+    })
+}
+
+fn resolve_profile(profile: Option<String>, cx: &App) -> Result<Option<AgentProfileId>> {
+    let Some(profile) = profile else {
+        return Ok(None);
+    };
+    let profile = AgentProfileId(profile.into());
+    let profiles = &AgentSettings::get_global(cx).profiles;
+    if profiles.contains_key(&profile) {
+        Ok(Some(profile))
+    } else {
+        let available = profiles.keys().map(|id| id.as_str()).collect::<Vec<_>>();
+        Err(anyhow!(
+            "unknown profile `{profile}`. Available: {}",
+            available.join(", ")
+        ))
+    }
+}
+
+/// Finds the given `provider/model`, or the `default_model` setting, and
+/// authenticates its provider.
+async fn resolve_model(model: Option<String>, cx: &mut AsyncApp) -> Result<Arc<dyn LanguageModel>> {
+    let model = match model {
+        Some(model) => model,
+        None => cx
+            .update(|cx| {
+                AgentSettings::get_global(cx)
+                    .default_model
+                    .as_ref()
+                    .map(|selection| format!("{}/{}", selection.provider.0, selection.model))
+            })
+            .context("no model is configured; pass --model provider/model")?,
+    };
+    let selected = SelectedModel::from_str(&model).map_err(|error| anyhow!(error))?;
+
+    let authenticate = cx.update(|cx| {
+        let provider = LanguageModelRegistry::read_global(cx)
+            .provider(&selected.provider)
+            .with_context(|| format!("unknown model provider `{}`", selected.provider.0))?;
+        anyhow::Ok(provider.authenticate(cx))
+    })?;
+    authenticate
+        .await
+        .with_context(|| format!("authenticating with {}", selected.provider.0))?;
+
+    cx.update(|cx| {
+        let registry = LanguageModelRegistry::read_global(cx);
+        let mut available = Vec::new();
+        for candidate in registry.available_models(cx) {
+            if candidate.provider_id() == selected.provider && candidate.id() == selected.model {
+                return Ok(candidate);
+            }
+            available.push(format!(
+                "{}/{}",
+                candidate.provider_id().0,
+                candidate.id().0
+            ));
+        }
+        Err(anyhow!(
+            "unknown model `{model}`. Available: {}",
+            available.join(", ")
+        ))
+    })
+}
+
+/// Creates a project for `cwd` without a window, and waits for its files to be
+/// scanned.
+async fn project_for_directory(
+    cwd: &Path,
+    app_state: &Arc<AppState>,
+    env: Option<HashMap<String, String>>,
+    cx: &mut AsyncApp,
+) -> Result<Entity<Project>> {
+    let project = cx.update(|cx| {
+        Project::local(
+            app_state.client.clone(),
+            app_state.node_runtime.clone(),
+            app_state.user_store.clone(),
+            app_state.languages.clone(),
+            app_state.fs.clone(),
+            env,
+            Default::default(),
+            cx,
+        )
+    });
+    let worktree = project
+        .update(cx, |project, cx| project.create_worktree(cwd, true, cx))
+        .await
+        .with_context(|| format!("opening {}", cwd.display()))?;
+    let scan_complete = worktree.update(cx, |worktree, _| {
+        worktree
+            .as_local()
+            .context("expected a local worktree")
+            .map(|worktree| worktree.scan_complete())
+    })?;
+    scan_complete.await;
+    Ok(project)
+}
+
+/// Prints the agent's thread as it progresses, and answers the tool calls that
+/// need a confirmation on behalf of the user.
+struct AgentRun {
+    connection: Rc<NativeAgentConnection>,
+    project: Entity<Project>,
+    work_dirs: PathList,
+    thread: Entity<AcpThread>,
+    responses: Arc<dyn CliResponseSink>,
+    allow_confirmations: bool,
+    printed_entries: usize,
+    /// Subagent threads stay loaded until the run ends, so their tool calls
+    /// keep being answered.
+    _subagent_threads: Vec<Entity<AcpThread>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl AgentRun {
+    fn watch(&mut self, thread: Entity<AcpThread>, cx: &mut Context<Self>) {
+        self._subscriptions
+            .push(cx.subscribe(&thread, Self::handle_thread_event));
+        let pending = thread
+            .read(cx)
+            .entries()
+            .iter()
+            .filter_map(|entry| match entry {
+                AgentThreadEntry::ToolCall(tool_call)
+                    if matches!(
+                        tool_call.status,
+                        ToolCallStatus::WaitingForConfirmation { .. }
+                    ) =>
+                {
+                    Some(tool_call.id.clone())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        for id in pending {
+            self.authorize(&thread, id, cx);
+        }
+        if thread != self.thread {
+            self._subagent_threads.push(thread);
+        }
+    }
+
+    fn handle_thread_event(
+        &mut self,
+        thread: Entity<AcpThread>,
+        event: &AcpThreadEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            AcpThreadEvent::NewEntry if thread == self.thread => self.print_entries(false, cx),
+            AcpThreadEvent::ToolAuthorizationRequested(id) => {
+                self.authorize(&thread, id.clone(), cx)
+            }
+            AcpThreadEvent::ElicitationRequested(id) => {
+                thread.update(cx, |thread, cx| thread.cancel_elicitation(id, cx));
+                self.send(
+                    CliResponse::Stderr {
+                        message:
+                            "The agent asked for input, which isn't supported by `zed --agent`"
+                                .into(),
+                    },
+                    cx,
+                );
+            }
+            AcpThreadEvent::SubagentSpawned(session_id) => {
+                let load_session = self.connection.clone().load_session(
+                    session_id.clone(),
+                    self.project.clone(),
+                    self.work_dirs.clone(),
+                    None,
+                    cx,
+                );
+                cx.spawn(async move |this, cx| {
+                    let thread = load_session.await?;
+                    this.update(cx, |this, cx| this.watch(thread, cx))
+                })
+                .detach_and_log_err(cx);
+            }
+            _ => {}
+        }
+    }
+
+    /// Allows or denies a tool call waiting for confirmation, as `--yes`
+    /// dictates, since nobody is there to confirm it.
+    fn authorize(
+        &mut self,
+        thread: &Entity<AcpThread>,
+        id: acp::ToolCallId,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((_, tool_call)) = thread.read(cx).tool_call(&id) else {
+            return;
+        };
+        let ToolCallStatus::WaitingForConfirmation { options, .. } = &tool_call.status else {
+            return;
+        };
+        let kind = if self.allow_confirmations {
+            acp::PermissionOptionKind::AllowOnce
+        } else {
+            acp::PermissionOptionKind::RejectOnce
+        };
+        let label = tool_call.label.read(cx).source().to_string();
+        let Some(option_id) = options
+            .first_option_of_kind(kind)
+            .map(|option| option.option_id.clone())
+        else {
+            self.send(
+                CliResponse::Stderr {
+                    message: format!("`{label}` needs a choice that `zed --agent` can't make"),
+                },
+                cx,
+            );
+            self.thread
+                .update(cx, |thread, cx| thread.cancel(cx))
+                .detach();
+            return;
+        };
+
+        thread.update(cx, |thread, cx| {
+            thread.authorize_tool_call(id, SelectedPermissionOutcome::new(option_id, kind), cx)
+        });
+        if !self.allow_confirmations {
+            self.send(
+                CliResponse::Stderr {
+                    message: format!(
+                        "Denied `{label}`, which needs confirmation. Pass --yes to allow it."
+                    ),
+                },
+                cx,
+            );
+        }
+    }
+
+    /// Prints the thread's entries that haven't been printed yet. The last one
+    /// may still be streaming, so it is held back unless the turn is `finished`.
+    fn print_entries(&mut self, finished: bool, cx: &mut Context<Self>) {
+        let entries = self.thread.read(cx).entries();
+        let end = if finished {
+            entries.len()
+        } else {
+            entries.len().saturating_sub(1)
+        };
+        let mut messages = Vec::new();
+        for entry in entries.get(self.printed_entries..end).unwrap_or_default() {
+            match entry {
+                AgentThreadEntry::AssistantMessage(message) => {
+                    let text = message
+                        .chunks
+                        .iter()
+                        .filter_map(|chunk| match chunk {
+                            AssistantMessageChunk::Message { block, .. } => {
+                                Some(block.to_markdown(cx))
+                            }
+                            AssistantMessageChunk::Thought { .. } => None,
+                        })
+                        .collect::<String>();
+                    if !text.trim().is_empty() {
+                        messages.push(text.trim().to_string());
+                    }
+                }
+                AgentThreadEntry::ToolCall(tool_call) => {
+                    let label = tool_call.label.read(cx).source();
+                    let outcome = match tool_call.status {
+                        ToolCallStatus::Failed => " (failed)",
+                        ToolCallStatus::Rejected => " (denied)",
+                        ToolCallStatus::Canceled => " (canceled)",
+                        _ => "",
+                    };
+                    messages.push(format!("› {label}{outcome}"));
+                }
+                AgentThreadEntry::UserMessage(_)
+                | AgentThreadEntry::Elicitation(_)
+                | AgentThreadEntry::CompletedPlan(_)
+                | AgentThreadEntry::ContextCompaction(_) => {}
+            }
+        }
+        self.printed_entries = self.printed_entries.max(end);
+
+        for message in messages {
+            self.send(CliResponse::Stdout { message }, cx);
+        }
+    }
+
+    /// Sends a response to the CLI, and stops the agent if the CLI has gone
+    /// away.
+    fn send(&self, response: CliResponse, cx: &mut Context<Self>) {
+        if self.responses.send(response).is_err() {
+            self.thread
+                .update(cx, |thread, cx| thread.cancel(cx))
+                .detach();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zed::tests::init_test;
+    use gpui::{Task, TestAppContext};
+    use language_model::{
+        LanguageModelCompletionEvent, LanguageModelProvider as _, LanguageModelToolUse,
+        LanguageModelToolUseInput, fake_provider::FakeLanguageModel,
+    };
+    use parking_lot::Mutex;
+    use serde_json::json;
+    use util::path;
+
+    #[derive(Clone, Default)]
+    struct RecordingResponseSink(Arc<Mutex<Vec<CliResponse>>>);
+
+    impl CliResponseSink for RecordingResponseSink {
+        fn send(&self, response: CliResponse) -> Result<()> {
+            self.0.lock().push(response);
+            Ok(())
+        }
+    }
+
+    impl RecordingResponseSink {
+        fn stdout(&self) -> Vec<String> {
+            self.0
+                .lock()
+                .iter()
+                .filter_map(|response| match response {
+                    CliResponse::Stdout { message } => Some(message.clone()),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        fn stderr(&self) -> Vec<String> {
+            self.0
+                .lock()
+                .iter()
+                .filter_map(|response| match response {
+                    CliResponse::Stderr { message } => Some(message.clone()),
+                    _ => None,
+                })
+                .collect()
+        }
+    }
+
+    async fn init_agent_test(cx: &mut TestAppContext) -> (Arc<AppState>, Arc<dyn LanguageModel>) {
+        let app_state = init_test(cx);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(path!("/project"), json!({ "a.txt": "hello" }))
+            .await;
+        let model = cx.update(|cx| LanguageModelRegistry::test(cx).provided_models(cx)[0].clone());
+        (app_state, model)
+    }
+
+    fn start_run(
+        model: &str,
+        allow_confirmations: bool,
+        app_state: &Arc<AppState>,
+        cx: &mut TestAppContext,
+    ) -> (Task<i32>, RecordingResponseSink) {
+        let request = AgentRunRequest {
+            prompt: "Do the thing".into(),
+            cwd: PathBuf::from(path!("/project")),
+            profile: None,
+            model: Some(model.into()),
+            allow_confirmations,
+            env: None,
+        };
+        let responses = RecordingResponseSink::default();
+        let task = cx.spawn({
+            let app_state = app_state.clone();
+            let responses = responses.clone();
+            |mut cx| async move { run(request, app_state, Arc::new(responses), &mut cx).await }
+        });
+        cx.run_until_parked();
+        (task, responses)
+    }
+
+    fn active_runs(cx: &mut TestAppContext) -> usize {
+        cx.update(|cx| cx.global::<HeadlessAgentRuns>().active)
+    }
+
+    fn create_directory(model: &FakeLanguageModel) {
+        model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+            LanguageModelToolUse {
+                id: "create-dir".into(),
+                name: "create_directory".into(),
+                raw_input: json!({ "path": "project/new" }).to_string(),
+                input: LanguageModelToolUseInput::Json(json!({ "path": "project/new" })),
+                is_input_complete: true,
+                thought_signature: None,
+            },
+        ));
+        model.end_last_completion_stream();
+    }
+
+    #[gpui::test]
+    async fn test_agent_run_prints_the_reply(cx: &mut TestAppContext) {
+        let (app_state, model) = init_agent_test(cx).await;
+        cx.update(init_headless);
+
+        let (task, responses) = start_run("fake/fake", false, &app_state, cx);
+        assert_eq!(active_runs(cx), 1);
+
+        model
+            .as_fake()
+            .send_last_completion_stream_text_chunk("All done.");
+        model.as_fake().end_last_completion_stream();
+        cx.run_until_parked();
+
+        assert_eq!(task.await, 0);
+        assert_eq!(responses.stdout(), vec!["All done.".to_string()]);
+        assert_eq!(active_runs(cx), 0);
+    }
+
+    #[gpui::test]
+    async fn test_agent_run_denies_confirmations_without_yes(cx: &mut TestAppContext) {
+        let (app_state, model) = init_agent_test(cx).await;
+
+        let (task, responses) = start_run("fake/fake", false, &app_state, cx);
+        create_directory(model.as_fake());
+        cx.run_until_parked();
+        model
+            .as_fake()
+            .send_last_completion_stream_text_chunk("Couldn't do it.");
+        model.as_fake().end_last_completion_stream();
+        cx.run_until_parked();
+
+        assert_eq!(task.await, 0);
+        assert!(
+            responses
+                .stderr()
+                .iter()
+                .any(|message| message.starts_with("Denied")),
+            "expected a denial on stderr, got {:?}",
+            responses.stderr()
+        );
+        assert!(!app_state.fs.is_dir(path!("/project/new").as_ref()).await);
+        // Only a Zed started for `zed --agent` counts runs, and quits after them.
+        cx.update(|cx| assert!(!cx.has_global::<HeadlessAgentRuns>()));
+    }
+
+    #[gpui::test]
+    async fn test_agent_run_allows_confirmations_with_yes(cx: &mut TestAppContext) {
+        let (app_state, model) = init_agent_test(cx).await;
+
+        let (task, responses) = start_run("fake/fake", true, &app_state, cx);
+        create_directory(model.as_fake());
+        cx.run_until_parked();
+        model
+            .as_fake()
+            .send_last_completion_stream_text_chunk("Created it.");
+        model.as_fake().end_last_completion_stream();
+        cx.run_until_parked();
+
+        assert_eq!(task.await, 0);
+        assert_eq!(responses.stderr(), Vec::<String>::new());
+        assert!(app_state.fs.is_dir(path!("/project/new").as_ref()).await);
+    }
+
+    #[gpui::test]
+    async fn test_agent_run_reports_unknown_models(cx: &mut TestAppContext) {
+        let (app_state, _model) = init_agent_test(cx).await;
+
+        let (task, responses) = start_run("fake/missing", false, &app_state, cx);
+
+        assert_eq!(task.await, 1);
+        assert_eq!(
+            responses.stderr(),
+            vec!["error: unknown model `fake/missing`. Available: fake/fake".to_string()]
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use super::agent_cli::{self, AgentRunRequest};
 use crate::handle_open_request;
 use crate::restore_or_create_workspace;
 use agent_ui::ExternalSourcePrompt;
@@ -670,6 +671,27 @@ pub async fn handle_cli_connection(
                 let status = if open_workspace_result.is_err() { 1 } else { 0 };
                 responses.send(CliResponse::Exit { status }).log_err();
             }
+            CliRequest::RunAgent {
+                prompt,
+                cwd,
+                profile,
+                model,
+                allow_confirmations,
+                env,
+            } => {
+                let responses: Arc<dyn CliResponseSink> = responses.into();
+                let request = AgentRunRequest {
+                    prompt,
+                    cwd,
+                    profile,
+                    model,
+                    allow_confirmations,
+                    env,
+                };
+                let status = agent_cli::run(request, app_state, responses.clone(), cx).await;
+                responses.send(CliResponse::Exit { status }).log_err();
+                cx.update(agent_cli::quit_if_idle);
+            }
             CliRequest::SetOpenBehavior { .. } => {
                 // We handle this case in a situation-specific way in
                 // resolve_open_behavior
@@ -311,6 +311,8 @@ pub enum NewWorktreeBranchTarget {
         remote_name: String,
         branch_name: String,
     },
+    /// Create a detached worktree at a specific commit.
+    Commit { sha: String },
 }
 
 /// Creates a new git worktree and switches the workspace to it.
@@ -427,6 +429,19 @@ pub mod project_panel {
         ]
     );
 }
+
+pub mod hex_editor {
+    use gpui::actions;
+
+    actions!(
+        hex_editor,
+        [
+            /// Opens the active file in the hex editor, including files that
+            /// couldn't be opened as text.
+            OpenInHexEditor
+        ]
+    );
+}
 pub mod feedback {
     use gpui::actions;
 
@@ -625,6 +640,18 @@ pub mod agent {
         pub base_ref: SharedString,
     }
 
+    /// Opens a new agent thread that reviews the provided diff, such as the
+    /// staged changes or a commit, and leaves comments on it.
+    #[derive(Clone, PartialEq, Deserialize, JsonSchema, Action)]
+    #[action(namespace = agent)]
+    #[serde(deny_unknown_fields)]
+    pub struct ReviewChanges {
+        /// The full text of the diff to review.
+        pub diff_text: SharedString,
+        /// What the diff contains, used in the prompt (e.g. "the staged changes").
+        pub subject: SharedString,
+    }
+
     /// A single merge conflict region extracted from a file.
     #[derive(Clone, Debug, PartialEq, Deserialize, JsonSchema)]
     pub struct ConflictContent {
@@ -977,6 +1004,14 @@ pub mod notebook {
             EnterEditMode,
             /// Exits the cell editor and returns to cell command mode.
             EnterCommandMode,
+            /// Exports the notebook to a `# %%` percent-format script next to it.
+            ExportAsScript,
+            /// Exports the notebook, including outputs, to a Markdown file next to it.
+            ExportAsMarkdown,
+            /// Exports the notebook, including outputs, to a standalone HTML file next to it.
+            ExportAsHtml,
+            /// Creates a new notebook from the `# %%` percent-format script in the active editor.
+            ImportScript,
         ]
     );
 }